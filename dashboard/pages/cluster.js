@@ -20,6 +20,7 @@ import { useState, useRef } from 'react';
 import Message from '../components/Message';
 import NoData from '../components/NoData';
 import { getClusterInfoFrontend, getClusterInfoComputeNode } from "./api/cluster";
+import { getMaterializedViews } from "./api/streaming";
 
 import Box from '@mui/material/Box';
 import Table from '@mui/material/Table';
@@ -31,6 +32,10 @@ import TableRow from '@mui/material/TableRow';
 import Paper from '@mui/material/Paper';
 import StatusLamp from '../components/StatusLamp';
 
+const formatLabels = (labels) => {
+  return Object.entries(labels || {}).map(([key, value]) => `${key}=${value}`).join(", ");
+}
+
 const NodeTable = (props) => {
   return (
     <Box sx={{ width: "100%", maxWidth: 1000 }}>
@@ -42,6 +47,7 @@ const NodeTable = (props) => {
               <TableCell>Status</TableCell>
               <TableCell>Host</TableCell>
               <TableCell>Post</TableCell>
+              <TableCell>Labels</TableCell>
             </TableRow>
           </TableHead>
           <TableBody>
@@ -55,6 +61,41 @@ const NodeTable = (props) => {
                 </TableCell>
                 <TableCell>{row.host.host}</TableCell>
                 <TableCell>{row.host.port}</TableCell>
+                <TableCell>{formatLabels(row.labels)}</TableCell>
+              </TableRow>
+            ))}
+          </TableBody>
+        </Table>
+      </TableContainer>
+        : <NoData />}
+    </Box>
+  )
+}
+
+const PlacementTable = (props) => {
+  const mvNames = new Map(props.data.map(mv => [mv.id, mv.name]));
+  const mvList = props.data.filter(mv => mv.placement);
+  return (
+    <Box sx={{ width: "100%", maxWidth: 1000 }}>
+      {mvList.length !== 0 ? <TableContainer component={Paper}>
+        <Table size="small">
+          <TableHead>
+            <TableRow>
+              <TableCell>ID</TableCell>
+              <TableCell>Name</TableCell>
+              <TableCell>Labels</TableCell>
+              <TableCell>Anti-affinity</TableCell>
+            </TableRow>
+          </TableHead>
+          <TableBody>
+            {mvList.map((row, i) => (
+              <TableRow key={i}>
+                <TableCell>{row.id}</TableCell>
+                <TableCell>{row.name}</TableCell>
+                <TableCell>{formatLabels(row.placement.labels)}</TableCell>
+                <TableCell>
+                  {(row.placement.antiAffinityTableIds || []).map(id => mvNames.get(id) || id).join(", ")}
+                </TableCell>
               </TableRow>
             ))}
           </TableBody>
@@ -69,6 +110,7 @@ export default function Cluster(props) {
 
   const [frontendList, setFrontendList] = useState([]);
   const [computeNodeList, setComputeNodeList] = useState([]);
+  const [mvList, setMvList] = useState([]);
 
   const message = useRef(null);
 
@@ -76,6 +118,7 @@ export default function Cluster(props) {
     try {
       setFrontendList(await getClusterInfoFrontend());
       setComputeNodeList(await getClusterInfoComputeNode());
+      setMvList(await getMaterializedViews());
     } catch (e) {
       message.current.error(e.toString());
       console.error(e);
@@ -89,6 +132,8 @@ export default function Cluster(props) {
         <NodeTable data={frontendList} />
         <p>Compute Node</p>
         <NodeTable data={computeNodeList} />
+        <p>Materialized View Placement</p>
+        <PlacementTable data={mvList} />
       </div>
       <Message ref={message} vertical="top" horizontal="center" />
     </>
@@ -41,6 +41,8 @@ use crate::executor::join::nested_loop_join::NestedLoopJoinExecutor;
 use crate::executor::join::sort_merge_join::SortMergeJoinExecutor;
 use crate::executor::join::HashJoinExecutorBuilder;
 pub use crate::executor::stream_scan::StreamScanExecutor;
+use crate::executor::sys_row_seq_scan::SysRowSeqScanExecutor;
+use crate::executor::table_function::TableFunctionExecutor;
 use crate::executor::trace::TraceExecutor;
 use crate::executor::values::ValuesExecutor;
 use crate::task::{BatchEnvironment, TaskId};
@@ -65,6 +67,8 @@ mod projection;
 mod row_seq_scan;
 mod sort_agg;
 mod stream_scan;
+mod sys_row_seq_scan;
+mod table_function;
 #[cfg(test)]
 mod test_utils;
 mod top_n;
@@ -181,7 +185,9 @@ impl<'a> ExecutorBuilder<'a> {
             NodeBody::DropSource => DropStreamExecutor,
             NodeBody::HashAgg => HashAggExecutorBuilder,
             NodeBody::MergeSortExchange => MergeSortExchangeExecutor,
-            NodeBody::GenerateInt32Series => GenerateSeriesI32Executor
+            NodeBody::GenerateInt32Series => GenerateSeriesI32Executor,
+            NodeBody::TableFunction => TableFunctionExecutor,
+            NodeBody::SysRowSeqScan => SysRowSeqScanExecutor
         }?;
         let input_desc = real_executor.identity().to_string();
         Ok(Box::new(TraceExecutor::new(real_executor, input_desc)))
@@ -19,7 +19,7 @@ use risingwave_common::catalog::{ColumnDesc, Schema, TableId};
 use risingwave_common::error::Result;
 use risingwave_pb::plan::plan_node::NodeBody;
 use risingwave_storage::table::cell_based_table::{CellBasedTable, CellBasedTableRowIter};
-use risingwave_storage::{dispatch_state_store, Keyspace, StateStore, StateStoreImpl};
+use risingwave_storage::{dispatch_state_store, Keyspace, ReadOptions, StateStore, StateStoreImpl};
 
 use super::monitor::BatchMetrics;
 use super::{BoxedExecutor, BoxedExecutorBuilder};
@@ -122,7 +122,13 @@ impl<S: StateStore> Executor for RowSeqScanExecutor<S> {
             return Ok(());
         }
 
-        self.iter = Some(self.table.iter(self.epoch).await?);
+        // Batch scans read the whole table sequentially, so the blocks are worth reading ahead.
+        let read_options = ReadOptions { prefetch: true };
+        self.iter = Some(
+            self.table
+                .iter_with_options(self.epoch, read_options)
+                .await?,
+        );
         Ok(())
     }
 
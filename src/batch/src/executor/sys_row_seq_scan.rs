@@ -0,0 +1,136 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::Result;
+use risingwave_pb::plan::plan_node::NodeBody;
+
+use crate::executor::{BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder};
+
+/// `SysRowSeqScanExecutor` scans a system table of the frontend, whose rows are generated from the
+/// catalog and carried in the plan.
+pub(super) struct SysRowSeqScanExecutor {
+    chunk: Option<DataChunk>,
+    schema: Schema,
+    identity: String,
+}
+
+impl SysRowSeqScanExecutor {
+    pub(crate) fn new(chunk: DataChunk, schema: Schema, identity: String) -> Self {
+        Self {
+            chunk: Some(chunk),
+            schema,
+            identity,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Executor for SysRowSeqScanExecutor {
+    async fn open(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn next(&mut self) -> Result<Option<DataChunk>> {
+        Ok(self.chunk.take().filter(|chunk| chunk.cardinality() > 0))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+}
+
+impl BoxedExecutorBuilder for SysRowSeqScanExecutor {
+    fn new_boxed_executor(source: &ExecutorBuilder) -> Result<BoxedExecutor> {
+        let scan_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::SysRowSeqScan
+        )?;
+
+        let fields = scan_node
+            .get_fields()
+            .iter()
+            .map(Field::from)
+            .collect::<Vec<Field>>();
+        let chunk = DataChunk::from_protobuf(scan_node.get_chunk()?)?;
+
+        Ok(Box::new(
+            Self::new(
+                chunk,
+                Schema { fields },
+                source.plan_node().get_identity().clone(),
+            )
+            .fuse(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{Array, ArrayImpl, Row};
+    use risingwave_common::try_match_expand;
+    use risingwave_common::types::{DataType, ScalarImpl};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sys_row_seq_scan_executor() {
+        let rows = vec![
+            Row(vec![Some(ScalarImpl::Int32(1)), None]),
+            Row(vec![
+                Some(ScalarImpl::Int32(2)),
+                Some(ScalarImpl::Utf8("pg_class".to_string())),
+            ]),
+        ];
+        let data_types = vec![DataType::Int32, DataType::Varchar];
+        let chunk = DataChunk::from_rows(&rows, &data_types).unwrap();
+        // The chunk is transferred in the plan.
+        let chunk = DataChunk::from_protobuf(&chunk.to_protobuf()).unwrap();
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int32, "oid"),
+            Field::with_name(DataType::Varchar, "relname"),
+        ]);
+        let mut executor =
+            SysRowSeqScanExecutor::new(chunk, schema, "SysRowSeqScanExecutor".to_string());
+
+        let chunk = executor.next().await.unwrap().unwrap();
+        assert_eq!(chunk.cardinality(), 2);
+        let oids = try_match_expand!(chunk.column_at(0).array_ref(), ArrayImpl::Int32).unwrap();
+        assert_eq!(oids.iter().collect::<Vec<_>>(), vec![Some(1), Some(2)]);
+        let names = try_match_expand!(chunk.column_at(1).array_ref(), ArrayImpl::Utf8).unwrap();
+        assert_eq!(
+            names.iter().collect::<Vec<_>>(),
+            vec![None, Some("pg_class")]
+        );
+        assert!(executor.next().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sys_row_seq_scan_executor_empty() {
+        let chunk = DataChunk::from_rows(&[], &[DataType::Int32]).unwrap();
+        let schema = Schema::new(vec![Field::with_name(DataType::Int32, "oid")]);
+        let mut executor =
+            SysRowSeqScanExecutor::new(chunk, schema, "SysRowSeqScanExecutor".to_string());
+        assert!(executor.next().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::array::column::Column;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::Result;
+use risingwave_expr::table_function::{build_from_prost, BoxedTableFunction};
+use risingwave_pb::plan::plan_node::NodeBody;
+
+use crate::executor::{BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder};
+
+/// `TableFunctionExecutor` evaluates a table function with constant arguments, and outputs the
+/// rows it produces.
+pub(super) struct TableFunctionExecutor {
+    schema: Schema,
+    identity: String,
+    table_function: BoxedTableFunction,
+    done: bool,
+}
+
+impl TableFunctionExecutor {
+    pub(crate) fn new(
+        schema: Schema,
+        identity: String,
+        table_function: BoxedTableFunction,
+    ) -> Self {
+        Self {
+            schema,
+            identity,
+            table_function,
+            done: false,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Executor for TableFunctionExecutor {
+    async fn open(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn next(&mut self) -> Result<Option<DataChunk>> {
+        if self.done {
+            return Ok(None);
+        }
+        self.done = true;
+
+        // The arguments are constant, so evaluate them on a one row chunk.
+        let output = self.table_function.eval(&DataChunk::new_dummy(1))?;
+        match output.into_iter().next() {
+            Some(array) if !array.is_empty() => Ok(Some(
+                DataChunk::builder()
+                    .columns(vec![Column::new(array)])
+                    .build(),
+            )),
+            _ => Ok(None),
+        }
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+}
+
+impl BoxedExecutorBuilder for TableFunctionExecutor {
+    fn new_boxed_executor(source: &ExecutorBuilder) -> Result<BoxedExecutor> {
+        let node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::TableFunction
+        )?;
+
+        let table_function = build_from_prost(node.get_table_function()?)?;
+        let schema = Schema::new(vec![Field::unnamed(table_function.return_type())]);
+
+        Ok(Box::new(
+            Self::new(
+                schema,
+                source.plan_node().get_identity().clone(),
+                table_function,
+            )
+            .fuse(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{Array, ArrayImpl, ListValue};
+    use risingwave_common::try_match_expand;
+    use risingwave_common::types::DataType;
+    use risingwave_expr::expr::LiteralExpression;
+    use risingwave_expr::table_function::{TableFunction, Unnest};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unnest_executor() {
+        let list = ListValue::new(vec![Some(1.into()), Some(2.into()), None]);
+        let unnest = Unnest::new(
+            DataType::Int32,
+            Box::new(LiteralExpression::new(
+                DataType::List {
+                    datatype: Box::new(DataType::Int32),
+                },
+                Some(list.into()),
+            )),
+        )
+        .boxed();
+        let mut executor = TableFunctionExecutor::new(
+            Schema::new(vec![Field::unnamed(DataType::Int32)]),
+            "TableFunctionExecutor".to_string(),
+            unnest,
+        );
+
+        let chunk = executor.next().await.unwrap().unwrap();
+        let arr = try_match_expand!(chunk.column_at(0).array_ref(), ArrayImpl::Int32).unwrap();
+        assert_eq!(arr.iter().collect::<Vec<_>>(), vec![Some(1), Some(2), None]);
+        assert!(executor.next().await.unwrap().is_none());
+    }
+}
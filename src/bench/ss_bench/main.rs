@@ -24,7 +24,7 @@ use risingwave_meta::hummock::test_utils::setup_compute_env;
 use risingwave_meta::hummock::MockHummockMetaClient;
 use risingwave_storage::hummock::compactor::CompactorContext;
 use risingwave_storage::monitor::StateStoreMetrics;
-use risingwave_storage::{dispatch_state_store, StateStoreImpl};
+use risingwave_storage::{dispatch_state_store, StateStore, StateStoreImpl};
 
 use crate::utils::display_stats::print_statistics;
 
@@ -44,6 +44,9 @@ pub(crate) struct Opts {
     #[clap(long, default_value_t = 0.1)]
     bloom_false_positive: f64,
 
+    #[clap(long)]
+    prefix_bloom: bool,
+
     // ----- benchmarks -----
     #[clap(long)]
     benchmarks: String,
@@ -126,6 +129,7 @@ async fn main() {
 
     let config = Arc::new(StorageConfig {
         shared_buffer_threshold_size: 268435456, // 256 MB
+        shared_buffer_spill_dir: "".to_string(),
         bloom_false_positive: opts.bloom_false_positive,
         sstable_size: opts.table_size_mb * (1 << 20),
         block_size: opts.block_size_kb * (1 << 10),
@@ -135,6 +139,10 @@ async fn main() {
         write_conflict_detection_enabled: false,
         block_cache_capacity: 256 << 20,
         meta_cache_capacity: 64 << 20,
+        max_sub_compaction: 4,
+        file_cache_dir: "".to_string(),
+        file_cache_capacity: 1 << 30,
+        block_prefetch_count: 0,
     });
 
     let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
@@ -160,13 +168,24 @@ async fn main() {
                 sstable_store: hummock.inner().sstable_store(),
                 stats: state_store_stats.clone(),
                 is_share_buffer_compact: false,
+                prefix_extractors: None,
             }),
             hummock.inner().local_version_manager().clone(),
         ));
     }
 
     dispatch_state_store!(state_store, store, {
-        Operations::run(store, mock_hummock_meta_client, context, &opts).await
+        if opts.prefix_bloom {
+            store.set_prefix_extractor(&[], opts.key_prefix_size as usize);
+        }
+        Operations::run(
+            store,
+            mock_hummock_meta_client,
+            context,
+            state_store_stats.clone(),
+            &opts,
+        )
+        .await
     });
 
     if opts.statistics {
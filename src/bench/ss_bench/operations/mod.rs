@@ -21,6 +21,7 @@ use rand::SeedableRng;
 use risingwave_meta::hummock::MockHummockMetaClient;
 use risingwave_storage::hummock::compactor::CompactorContext;
 use risingwave_storage::hummock::local_version_manager::LocalVersionManager;
+use risingwave_storage::monitor::StateStoreMetrics;
 use risingwave_storage::StateStore;
 
 use crate::utils::display_stats::*;
@@ -34,6 +35,7 @@ pub(crate) struct Operations {
     pub(crate) keys: Vec<Bytes>,
     pub(crate) prefixes: Vec<Bytes>,
     pub meta_client: Arc<MockHummockMetaClient>,
+    pub stats: Arc<StateStoreMetrics>,
 
     // TODO(Sun Ting): exploit specified (no need to support encryption) rng to speed up
     rng: StdRng,
@@ -53,6 +55,7 @@ impl Operations {
         store: impl StateStore,
         meta_service: Arc<MockHummockMetaClient>,
         context: Option<(Arc<CompactorContext>, Arc<LocalVersionManager>)>,
+        stats: Arc<StateStoreMetrics>,
         opts: &Opts,
     ) {
         let mut stat_display = DisplayStats::default();
@@ -62,6 +65,7 @@ impl Operations {
             prefixes: vec![],
             rng: StdRng::seed_from_u64(opts.seed),
             meta_client: meta_service,
+            stats,
         };
 
         for operation in opts.benchmarks.split(',') {
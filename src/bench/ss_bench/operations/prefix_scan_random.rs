@@ -79,6 +79,9 @@ impl Operations {
             })
             .collect_vec();
 
+        let prefix_bloom_true_negatives = self.stats.prefix_bloom_filter_true_negative_counts.get();
+        let prefix_bloom_might_positives =
+            self.stats.prefix_bloom_filter_might_positive_counts.get();
         let total_start = Instant::now();
 
         let handles = futures.into_iter().map(tokio::spawn).collect_vec();
@@ -100,13 +103,30 @@ impl Operations {
         let stat = LatencyStat::new(total_latencies);
         let qps = opts.scans as u128 * 1_000_000_000 / total_time_nano as u128;
         let bytes_pre_sec = total_sizes as u128 * 1_000_000_000 / total_time_nano as u128;
+        // Tables skipped by prefix bloom filters among all the tables checked.
+        let prefix_bloom_true_negatives =
+            self.stats.prefix_bloom_filter_true_negative_counts.get() - prefix_bloom_true_negatives;
+        let prefix_bloom_checks = prefix_bloom_true_negatives
+            + self.stats.prefix_bloom_filter_might_positive_counts.get()
+            - prefix_bloom_might_positives;
+        let prefix_bloom_hit_rate = if prefix_bloom_checks == 0 {
+            0.0
+        } else {
+            prefix_bloom_true_negatives as f64 / prefix_bloom_checks as f64
+        };
 
         println!(
             "
     prefixscanrandom
       {}
-      QPS: {}  {} bytes/sec",
-            stat, qps, bytes_pre_sec
+      QPS: {}  {} bytes/sec
+      prefix bloom filter: {} of {} tables skipped, hit rate {:.4}",
+            stat,
+            qps,
+            bytes_pre_sec,
+            prefix_bloom_true_negatives,
+            prefix_bloom_checks,
+            prefix_bloom_hit_rate
         );
     }
 }
@@ -54,6 +54,23 @@ async fn main() {
         );
     }
 
+    // compactor configuration
+    fns.insert(
+        "compactor",
+        Box::new(|args: Vec<String>| {
+            Box::new(async move {
+                eprintln!("launching compactor");
+
+                let opts = risingwave_compactor::CompactorOpts::parse_from(args);
+
+                risingwave_logging::oneshot_common();
+                risingwave_logging::init_risingwave_logger(false, false);
+
+                risingwave_compactor::start(opts).await
+            })
+        }),
+    );
+
     // meta node configuration
     for fn_name in ["meta", "meta-node", "meta_node"] {
         fns.insert(
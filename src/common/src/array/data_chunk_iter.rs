@@ -279,7 +279,7 @@ mod tests {
             Ty::Int64,
             Ty::Float32,
             Ty::Float64,
-            Ty::Decimal,
+            Ty::UNCONSTRAINED_DECIMAL,
             Ty::Interval,
         ]);
         let row1 = de.deserialize_not_null(&bytes).unwrap();
@@ -310,7 +310,7 @@ mod tests {
             Ty::Int64,
             Ty::Float32,
             Ty::Float64,
-            Ty::Decimal,
+            Ty::UNCONSTRAINED_DECIMAL,
             Ty::Interval,
         ]);
         let row1 = de.deserialize(&bytes).unwrap();
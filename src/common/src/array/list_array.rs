@@ -17,16 +17,21 @@ use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
 use std::hash::{Hash, Hasher};
 
+use bytes::{Buf, BufMut};
 use itertools::EitherOrBoth::{Both, Left, Right};
 use itertools::Itertools;
 use risingwave_pb::data::{Array as ProstArray, ArrayType as ProstArrayType, ListArrayData};
+use serde::{Deserialize, Serialize};
 
 use super::{
     Array, ArrayBuilder, ArrayBuilderImpl, ArrayImpl, ArrayIterator, ArrayMeta, NULL_VAL_FOR_HASH,
 };
 use crate::buffer::{Bitmap, BitmapBuilder};
 use crate::error::Result;
-use crate::types::{DataType, Datum, DatumRef, Scalar, ScalarRefImpl};
+use crate::types::{
+    deserialize_datum_from, serialize_datum_ref_into, DataType, Datum, DatumRef, Scalar,
+    ScalarRefImpl,
+};
 
 /// This is a naive implementation of list array.
 /// We will eventually move to a more efficient flatten implementation.
@@ -172,7 +177,7 @@ impl Array for ListArray {
 
     fn hash_at<H: std::hash::Hasher>(&self, idx: usize, state: &mut H) {
         if !self.is_null(idx) {
-            self.value.hash_at(idx, state)
+            (self.offsets[idx]..self.offsets[idx + 1]).for_each(|i| self.value.hash_at(i, state))
         } else {
             NULL_VAL_FOR_HASH.hash(state);
         }
@@ -259,8 +264,8 @@ pub struct ListValue {
 }
 
 impl fmt::Display for ListValue {
-    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        Ok(())
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.as_scalar_ref(), f)
     }
 }
 
@@ -280,6 +285,26 @@ impl ListValue {
     pub fn new(values: Vec<Datum>) -> Self {
         Self { values }
     }
+
+    pub fn values(&self) -> &[Datum] {
+        &self.values
+    }
+
+    /// Deserialize a list from memcomparable encoding. See [`ListRef::memcmp_serialize`].
+    pub fn memcmp_deserialize(
+        datatype: &DataType,
+        deserializer: &mut memcomparable::Deserializer<impl Buf>,
+    ) -> memcomparable::Result<Self> {
+        let mut values = vec![];
+        loop {
+            match u8::deserialize(&mut *deserializer)? {
+                0 => break,
+                1 => values.push(deserialize_datum_from(datatype, deserializer)?),
+                tag => return Err(memcomparable::Error::InvalidTagEncoding(tag as _)),
+            }
+        }
+        Ok(Self::new(values))
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -301,6 +326,31 @@ impl<'a> ListRef<'a> {
                 .collect::<Vec<DatumRef<'a>>>(),
         }
     }
+
+    pub fn len(&self) -> usize {
+        match self {
+            ListRef::Indexed { arr, idx } => arr.offsets[*idx + 1] - arr.offsets[*idx],
+            ListRef::ValueRef { val } => val.values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Serialize the list into memcomparable encoding. Each element is prefixed with a `1u8`
+    /// marker and the list is terminated by a `0u8`, so that a list orders before any list it
+    /// is a prefix of.
+    pub fn memcmp_serialize(
+        &self,
+        serializer: &mut memcomparable::Serializer<impl BufMut>,
+    ) -> memcomparable::Result<()> {
+        for datum_ref in self.values_ref() {
+            1u8.serialize(&mut *serializer)?;
+            serialize_datum_ref_into(&datum_ref, serializer)?;
+        }
+        0u8.serialize(serializer)
+    }
 }
 
 impl Hash for ListRef<'_> {
@@ -360,8 +410,18 @@ impl Debug for ListRef<'_> {
 }
 
 impl Display for ListRef<'_> {
-    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{{}}}",
+            self.values_ref()
+                .iter()
+                .map(|v| match v {
+                    Some(s) => s.to_string(),
+                    None => "NULL".to_string(),
+                })
+                .join(",")
+        )
     }
 }
 
@@ -18,6 +18,7 @@ use std::fmt::{Debug, Display};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
+use bytes::{Buf, BufMut};
 use itertools::Itertools;
 use risingwave_pb::data::{Array as ProstArray, ArrayType as ProstArrayType, StructArrayData};
 
@@ -26,7 +27,10 @@ use super::{
 };
 use crate::buffer::{Bitmap, BitmapBuilder};
 use crate::error::Result;
-use crate::types::{DataType, Datum, DatumRef, Scalar, ScalarRefImpl};
+use crate::types::{
+    deserialize_datum_from, serialize_datum_ref_into, DataType, Datum, DatumRef, Scalar,
+    ScalarRefImpl,
+};
 
 /// This is a naive implementation of struct array.
 /// We will eventually move to a more efficient flatten implementation.
@@ -262,8 +266,8 @@ pub struct StructValue {
 }
 
 impl fmt::Display for StructValue {
-    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        Ok(())
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.as_scalar_ref(), f)
     }
 }
 
@@ -283,6 +287,22 @@ impl StructValue {
     pub fn new(fields: Vec<Datum>) -> Self {
         Self { fields }
     }
+
+    pub fn fields(&self) -> &[Datum] {
+        &self.fields
+    }
+
+    /// Deserialize a struct from memcomparable encoding. See [`StructRef::memcmp_serialize`].
+    pub fn memcmp_deserialize(
+        fields: &[DataType],
+        deserializer: &mut memcomparable::Deserializer<impl Buf>,
+    ) -> memcomparable::Result<Self> {
+        fields
+            .iter()
+            .map(|field| deserialize_datum_from(field, &mut *deserializer))
+            .try_collect()
+            .map(Self::new)
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -304,6 +324,18 @@ impl<'a> StructRef<'a> {
                 .collect::<Vec<DatumRef<'a>>>(),
         }
     }
+
+    /// Serialize the fields one after another. The number of fields is known from the data type,
+    /// so no length or terminator is written.
+    pub fn memcmp_serialize(
+        &self,
+        serializer: &mut memcomparable::Serializer<impl BufMut>,
+    ) -> memcomparable::Result<()> {
+        for datum_ref in self.fields_ref() {
+            serialize_datum_ref_into(&datum_ref, serializer)?;
+        }
+        Ok(())
+    }
 }
 
 impl Hash for StructRef<'_> {
@@ -365,9 +397,21 @@ impl Debug for StructRef<'_> {
     }
 }
 
+/// Formats the struct like a composite value in postgres, e.g. `(1,abc,)`, where null fields are
+/// left empty.
 impl Display for StructRef<'_> {
-    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({})",
+            self.fields_ref()
+                .iter()
+                .map(|v| match v {
+                    Some(s) => s.to_string(),
+                    None => "".to_string(),
+                })
+                .join(",")
+        )
     }
 }
 
@@ -112,7 +112,6 @@ impl ColumnDesc {
         descs
     }
 
-    #[cfg(test)]
     pub fn new_atomic(data_type: DataType, name: &str, column_id: i32) -> Self {
         Self {
             data_type,
@@ -123,7 +122,6 @@ impl ColumnDesc {
         }
     }
 
-    #[cfg(test)]
     pub fn new_struct(
         name: &str,
         column_id: i32,
@@ -268,4 +266,20 @@ pub mod tests {
         let desc: ColumnDesc = build_prost_desc().into();
         assert_eq!(desc, build_desc());
     }
+
+    #[test]
+    fn test_decimal_column_desc() {
+        let desc = ColumnDesc::new_atomic(
+            DataType::Decimal {
+                precision: Some(10),
+                scale: Some(2),
+            },
+            "price",
+            1,
+        );
+        let prost = desc.to_protobuf();
+        let column_type = prost.column_type.as_ref().unwrap();
+        assert_eq!((column_type.precision, column_type.scale), (10, 2));
+        assert_eq!(ColumnDesc::from(prost), desc);
+    }
 }
@@ -213,7 +213,7 @@ pub mod test_utils {
     }
 
     fn decimal_n<const N: usize>() -> Schema {
-        field_n::<N>(DataType::Decimal)
+        field_n::<N>(DataType::UNCONSTRAINED_DECIMAL)
     }
 
     /// Create a util schema **for test only** with three decimal fields.
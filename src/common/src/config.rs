@@ -87,10 +87,16 @@ impl Default for StreamingConfig {
 /// Currently all configurations are server before they can be specified with DDL syntaxes.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StorageConfig {
-    /// Size of state store shared buffer (MB)
+    /// Limit of the memory used by state store shared buffer in bytes. Writes are blocked when
+    /// it's exceeded, until sealed epochs are committed or spilled.
     #[serde(default = "default::shared_buffer_size")]
     pub shared_buffer_threshold_size: u32,
 
+    /// Local directory to spill sealed epochs of shared buffer to under memory pressure. Empty to
+    /// disable spilling.
+    #[serde(default = "default::shared_buffer_spill_dir")]
+    pub shared_buffer_spill_dir: String,
+
     /// Target size of the SSTable.
     #[serde(default = "default::sst_size")]
     pub sstable_size: u32,
@@ -126,6 +132,23 @@ pub struct StorageConfig {
     /// Capacity of sstable meta cache.
     #[serde(default = "default::meta_cache_capacity")]
     pub meta_cache_capacity: usize,
+
+    /// Maximum number of concurrent sub-compactions a compaction task is split into by key range.
+    #[serde(default = "default::max_sub_compaction")]
+    pub max_sub_compaction: u32,
+
+    /// Local directory of the file cache for sstable blocks and metas. Empty to disable it.
+    #[serde(default = "default::file_cache_dir")]
+    pub file_cache_dir: String,
+
+    /// Capacity of the file cache in bytes.
+    #[serde(default = "default::file_cache_capacity")]
+    pub file_cache_capacity: usize,
+
+    /// Number of blocks read ahead with one vectored read by sequential scans of sstables. 0 to
+    /// disable prefetching.
+    #[serde(default = "default::block_prefetch_count")]
+    pub block_prefetch_count: usize,
 }
 
 impl Default for StorageConfig {
@@ -170,6 +193,10 @@ mod default {
         268435456
     }
 
+    pub fn shared_buffer_spill_dir() -> String {
+        "".to_string()
+    }
+
     pub fn heartbeat_interval() -> u32 {
         1000
     }
@@ -216,6 +243,23 @@ mod default {
         // 64 MB
         67108864
     }
+
+    pub fn max_sub_compaction() -> u32 {
+        4
+    }
+
+    pub fn file_cache_dir() -> String {
+        "".to_string()
+    }
+
+    pub fn file_cache_capacity() -> usize {
+        // 1 GB
+        1073741824
+    }
+
+    pub fn block_prefetch_count() -> usize {
+        0
+    }
 }
 
 #[cfg(test)]
@@ -104,14 +104,14 @@ mod tests {
 
     fn all_data_types() -> Vec<DataType> {
         vec![
-            DataType::Boolean, // 0
-            DataType::Int16,   // 1
-            DataType::Int32,   // 2
-            DataType::Int64,   // 3
-            DataType::Float32, // 4
-            DataType::Float64, // 5
-            DataType::Decimal, // 6
-            DataType::Varchar, // 7
+            DataType::Boolean,               // 0
+            DataType::Int16,                 // 1
+            DataType::Int32,                 // 2
+            DataType::Int64,                 // 3
+            DataType::Float32,               // 4
+            DataType::Float64,               // 5
+            DataType::UNCONSTRAINED_DECIMAL, // 6
+            DataType::Varchar,               // 7
         ]
     }
 
@@ -433,6 +433,30 @@ impl Decimal {
         }
     }
 
+    /// Rounds to `scale` digits after the decimal point and checks that the result fits in
+    /// `NUMERIC(precision, scale)`. Returns `None` if there are more than `precision - scale`
+    /// digits before the decimal point. Infinities never fit while `NaN` always does, as in PG.
+    pub fn round_to_typmod(&self, precision: u32, scale: u32) -> Option<Self> {
+        match self {
+            Self::Normalized(d) => {
+                let mut rounded =
+                    d.round_dp_with_strategy(scale, RoundingStrategy::MidpointAwayFromZero);
+                let bound = RustDecimal::from_i128_with_scale(
+                    10i128.pow(precision.saturating_sub(scale)),
+                    0,
+                );
+                if rounded.abs() >= bound {
+                    return None;
+                }
+                // Pad trailing zeros so that the value is displayed with the declared scale.
+                rounded.rescale(scale);
+                Some(Self::Normalized(rounded))
+            }
+            Self::NaN => Some(Self::NaN),
+            Self::PositiveINF | Self::NegativeINF => None,
+        }
+    }
+
     /// TODO: 1. test whether the decimal in rust, any crate, has the same behavior as PG.
     /// 2. support memcomparable encoding for dynamic decimal.
     pub fn mantissa_scale_for_serialization(&self) -> (i128, u8) {
@@ -560,6 +584,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_round_to_typmod() {
+        let d = Decimal::from_str("123.455").unwrap();
+        let rounded = d.round_to_typmod(5, 2).unwrap();
+        assert_eq!(rounded, Decimal::from_str("123.46").unwrap());
+        assert_eq!(rounded.to_string(), "123.46");
+        assert_eq!(
+            Decimal::from_str("-1.5")
+                .unwrap()
+                .round_to_typmod(5, 3)
+                .unwrap()
+                .to_string(),
+            "-1.500"
+        );
+        assert_eq!(
+            Decimal::from_str("0.5").unwrap().round_to_typmod(1, 0),
+            Some(Decimal::from(1))
+        );
+        // Rounding up may carry into an extra integral digit.
+        assert_eq!(d.round_to_typmod(4, 2), None);
+        assert_eq!(
+            Decimal::from_str("99.995").unwrap().round_to_typmod(4, 2),
+            None
+        );
+        assert_eq!(Decimal::from_str("12").unwrap().round_to_typmod(2, 2), None);
+        assert_eq!(Decimal::NaN.round_to_typmod(3, 1), Some(Decimal::NaN));
+        assert_eq!(Decimal::PositiveINF.round_to_typmod(3, 1), None);
+    }
+
     #[test]
     fn basic_test() {
         assert_eq!(Decimal::from_str("nan").unwrap(), Decimal::NaN,);
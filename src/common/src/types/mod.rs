@@ -48,7 +48,7 @@ use crate::array::{
 pub type OrderedF32 = ordered_float::OrderedFloat<f32>;
 pub type OrderedF64 = ordered_float::OrderedFloat<f64>;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub enum DataType {
     Boolean,
     Int16,
@@ -56,19 +56,59 @@ pub enum DataType {
     Int64,
     Float32,
     Float64,
-    Decimal,
+    /// `NUMERIC(precision, scale)`. Both are `None` for an unconstrained `NUMERIC`, whose values
+    /// keep whatever scale they are computed with.
+    Decimal {
+        precision: Option<u32>,
+        scale: Option<u32>,
+    },
     Date,
     Varchar,
     Time,
     Timestamp,
     Timestampz,
     Interval,
-    Struct { fields: Arc<[DataType]> },
-    List { datatype: Box<DataType> },
+    Struct {
+        fields: Arc<[DataType]>,
+    },
+    List {
+        datatype: Box<DataType>,
+    },
 }
 
-const DECIMAL_DEFAULT_PRECISION: u32 = 20;
-const DECIMAL_DEFAULT_SCALE: u32 = 6;
+/// The maximum precision of `NUMERIC`, limited by the 96-bit mantissa of `rust_decimal`.
+pub const DECIMAL_MAX_PRECISION: u32 = 28;
+
+impl std::fmt::Debug for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataType::Boolean => write!(f, "Boolean"),
+            DataType::Int16 => write!(f, "Int16"),
+            DataType::Int32 => write!(f, "Int32"),
+            DataType::Int64 => write!(f, "Int64"),
+            DataType::Float32 => write!(f, "Float32"),
+            DataType::Float64 => write!(f, "Float64"),
+            // Print an unconstrained numeric as `Decimal` and a constrained one as `Decimal(p, s)`.
+            DataType::Decimal {
+                precision: Some(precision),
+                scale,
+            } => write!(f, "Decimal({}, {})", precision, scale.unwrap_or(0)),
+            DataType::Decimal { .. } => write!(f, "Decimal"),
+            DataType::Date => write!(f, "Date"),
+            DataType::Varchar => write!(f, "Varchar"),
+            DataType::Time => write!(f, "Time"),
+            DataType::Timestamp => write!(f, "Timestamp"),
+            DataType::Timestampz => write!(f, "Timestampz"),
+            DataType::Interval => write!(f, "Interval"),
+            DataType::Struct { fields } => {
+                f.debug_struct("Struct").field("fields", fields).finish()
+            }
+            DataType::List { datatype } => {
+                f.debug_struct("List").field("datatype", datatype).finish()
+            }
+        }
+    }
+}
 
 /// Number of bytes of one element in array of [`DataType`].
 pub enum DataSize {
@@ -95,20 +135,43 @@ impl From<&ProstDataType> for DataType {
             TypeName::Time => DataType::Time,
             TypeName::Timestamp => DataType::Timestamp,
             TypeName::Timestampz => DataType::Timestampz,
-            TypeName::Decimal => DataType::Decimal,
+            // Precision 0 is never valid in a declaration, so it denotes an unconstrained numeric.
+            TypeName::Decimal => match proto.precision {
+                0 => DataType::Decimal {
+                    precision: None,
+                    scale: None,
+                },
+                precision => DataType::Decimal {
+                    precision: Some(precision),
+                    scale: Some(proto.scale),
+                },
+            },
             TypeName::Interval => DataType::Interval,
             TypeName::Symbol => DataType::Varchar,
             TypeName::Struct => DataType::Struct {
-                fields: Arc::new([]),
+                fields: proto.field_type.iter().map(DataType::from).collect(),
             },
             TypeName::List => DataType::List {
-                datatype: Box::new(DataType::Int32),
+                // An unknown element type is regarded as `Int32` for compatibility.
+                datatype: Box::new(
+                    proto
+                        .field_type
+                        .first()
+                        .map(DataType::from)
+                        .unwrap_or(DataType::Int32),
+                ),
             },
         }
     }
 }
 
 impl DataType {
+    /// `NUMERIC` without declared precision and scale.
+    pub const UNCONSTRAINED_DECIMAL: DataType = DataType::Decimal {
+        precision: None,
+        scale: None,
+    };
+
     pub fn create_array_builder(&self, capacity: usize) -> Result<ArrayBuilderImpl> {
         use crate::array::*;
         Ok(match self {
@@ -118,7 +181,7 @@ impl DataType {
             DataType::Int64 => PrimitiveArrayBuilder::<i64>::new(capacity)?.into(),
             DataType::Float32 => PrimitiveArrayBuilder::<OrderedF32>::new(capacity)?.into(),
             DataType::Float64 => PrimitiveArrayBuilder::<OrderedF64>::new(capacity)?.into(),
-            DataType::Decimal => DecimalArrayBuilder::new(capacity)?.into(),
+            DataType::Decimal { .. } => DecimalArrayBuilder::new(capacity)?.into(),
             DataType::Date => NaiveDateArrayBuilder::new(capacity)?.into(),
             DataType::Varchar => Utf8ArrayBuilder::new(capacity)?.into(),
             DataType::Time => NaiveTimeArrayBuilder::new(capacity)?.into(),
@@ -151,7 +214,7 @@ impl DataType {
             DataType::Time => TypeName::Time,
             DataType::Timestamp => TypeName::Timestamp,
             DataType::Timestampz => TypeName::Timestampz,
-            DataType::Decimal => TypeName::Decimal,
+            DataType::Decimal { .. } => TypeName::Decimal,
             DataType::Interval => TypeName::Interval,
             DataType::Struct { .. } => TypeName::Struct,
             DataType::List { .. } => TypeName::List,
@@ -159,9 +222,24 @@ impl DataType {
     }
 
     pub fn to_protobuf(&self) -> ProstDataType {
+        let field_type = match self {
+            DataType::Struct { fields } => fields.iter().map(|f| f.to_protobuf()).collect(),
+            DataType::List { datatype } => vec![datatype.to_protobuf()],
+            _ => vec![],
+        };
+        let (precision, scale) = match self {
+            DataType::Decimal {
+                precision: Some(precision),
+                scale,
+            } => (*precision, scale.unwrap_or(0)),
+            _ => (0, 0),
+        };
         ProstDataType {
             type_name: self.prost_type_name() as i32,
             is_nullable: true,
+            field_type,
+            precision,
+            scale,
             ..Default::default()
         }
     }
@@ -175,7 +253,7 @@ impl DataType {
             DataType::Int64 => DataSize::Fixed(size_of::<i64>()),
             DataType::Float32 => DataSize::Fixed(size_of::<OrderedF32>()),
             DataType::Float64 => DataSize::Fixed(size_of::<OrderedF64>()),
-            DataType::Decimal => DataSize::Fixed(16),
+            DataType::Decimal { .. } => DataSize::Fixed(16),
             DataType::Varchar => DataSize::Variable,
             DataType::Date => DataSize::Fixed(size_of::<i32>()),
             DataType::Time => DataSize::Fixed(size_of::<i64>()),
@@ -195,7 +273,7 @@ impl DataType {
                 | DataType::Int64
                 | DataType::Float32
                 | DataType::Float64
-                | DataType::Decimal
+                | DataType::Decimal { .. }
         )
     }
 }
@@ -603,6 +681,8 @@ impl ScalarRefImpl<'_> {
             &Self::NaiveTime(v) => {
                 ser.serialize_naivetime(v.0.num_seconds_from_midnight(), v.0.nanosecond())?
             }
+            Self::Struct(v) => v.memcmp_serialize(ser)?,
+            Self::List(v) => v.memcmp_serialize(ser)?,
             _ => {
                 panic!("Type is unable to be serialized.")
             }
@@ -634,7 +714,7 @@ impl ScalarImpl {
             Ty::Float64 => Self::Float64(f64::deserialize(de)?.into()),
             Ty::Varchar => Self::Utf8(String::deserialize(de)?),
             Ty::Boolean => Self::Bool(bool::deserialize(de)?),
-            Ty::Decimal => Self::Decimal({
+            Ty::Decimal { .. } => Self::Decimal({
                 let (mantissa, scale) = de.deserialize_decimal()?;
                 match scale {
                     29 => Decimal::NegativeINF,
@@ -657,6 +737,8 @@ impl ScalarImpl {
                 let days = de.deserialize_naivedate()?;
                 NaiveDateWrapper::new_with_days(days)?
             }),
+            Ty::Struct { fields } => Self::Struct(StructValue::memcmp_deserialize(&fields, de)?),
+            Ty::List { datatype } => Self::List(ListValue::memcmp_deserialize(&datatype, de)?),
             _ => {
                 panic!("Type is unable to be deserialized.")
             }
@@ -744,4 +826,103 @@ mod tests {
         assert!(decoded_floats.is_sorted());
         assert_eq!(floats, decoded_floats);
     }
+
+    #[test]
+    fn test_list_memcomparable() {
+        let ty = DataType::List {
+            datatype: Box::new(DataType::Int32),
+        };
+        let lists = vec![
+            ListValue::new(vec![]),
+            ListValue::new(vec![Some(1.into())]),
+            // Nulls are encoded before any value.
+            ListValue::new(vec![Some(1.into()), None]),
+            ListValue::new(vec![Some(1.into()), Some(2.into())]),
+            ListValue::new(vec![Some(2.into())]),
+        ];
+
+        let memcomparables = lists
+            .iter()
+            .map(|l| {
+                let mut serializer = memcomparable::Serializer::new(vec![]);
+                serialize_datum_into(&Some(l.clone().into()), &mut serializer).unwrap();
+                serializer.into_inner()
+            })
+            .collect_vec();
+        assert!(memcomparables.is_sorted());
+
+        let decoded = memcomparables
+            .iter()
+            .map(|data| {
+                let mut deserializer = memcomparable::Deserializer::new(data.as_slice());
+                deserialize_datum_from(&ty, &mut deserializer)
+                    .unwrap()
+                    .unwrap()
+                    .into_list()
+            })
+            .collect_vec();
+        assert_eq!(lists, decoded);
+    }
+
+    #[test]
+    fn test_struct_memcomparable() {
+        let ty = DataType::Struct {
+            fields: vec![DataType::Int32, DataType::Varchar].into(),
+        };
+        let structs = vec![
+            StructValue::new(vec![None, None]),
+            StructValue::new(vec![Some(1.into()), Some("a".to_string().into())]),
+            StructValue::new(vec![Some(1.into()), Some("b".to_string().into())]),
+            StructValue::new(vec![Some(2.into()), None]),
+        ];
+
+        let memcomparables = structs
+            .iter()
+            .map(|s| {
+                let mut serializer = memcomparable::Serializer::new(vec![]);
+                serialize_datum_into(&Some(s.clone().into()), &mut serializer).unwrap();
+                serializer.into_inner()
+            })
+            .collect_vec();
+        assert!(memcomparables.is_sorted());
+
+        let decoded = memcomparables
+            .iter()
+            .map(|data| {
+                let mut deserializer = memcomparable::Deserializer::new(data.as_slice());
+                deserialize_datum_from(&ty, &mut deserializer)
+                    .unwrap()
+                    .unwrap()
+                    .into_struct()
+            })
+            .collect_vec();
+        assert_eq!(structs, decoded);
+    }
+
+    #[test]
+    fn test_nested_data_type_protobuf() {
+        let ty = DataType::List {
+            datatype: Box::new(DataType::List {
+                datatype: Box::new(DataType::Varchar),
+            }),
+        };
+        assert_eq!(ty, DataType::from(&ty.to_protobuf()));
+
+        let ty = DataType::Struct {
+            fields: vec![
+                DataType::Int32,
+                DataType::Struct {
+                    fields: vec![DataType::Varchar].into(),
+                },
+            ]
+            .into(),
+        };
+        assert_eq!(ty, DataType::from(&ty.to_protobuf()));
+
+        let ty = DataType::Decimal {
+            precision: Some(10),
+            scale: Some(2),
+        };
+        assert_eq!(ty, DataType::from(&ty.to_protobuf()));
+    }
 }
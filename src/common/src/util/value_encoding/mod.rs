@@ -50,7 +50,7 @@ pub fn deserialize_cell(
     ty: &DataType,
 ) -> Result<Datum> {
     match ty {
-        &DataType::Decimal => deserialize_decimal(deserializer),
+        &DataType::Decimal { .. } => deserialize_decimal(deserializer),
         _ => Ok(deserialize_datum_from(ty, deserializer.memcom_de())?),
     }
 }
@@ -61,7 +61,7 @@ pub fn deserialize_cell_not_null(
     ty: DataType,
 ) -> Result<Datum> {
     match ty {
-        DataType::Decimal => deserialize_decimal(deserializer),
+        DataType::Decimal { .. } => deserialize_decimal(deserializer),
         _ => Ok(deserialize_datum_not_null_from(
             ty,
             deserializer.memcom_de(),
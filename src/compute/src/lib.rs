@@ -70,6 +70,18 @@ pub struct ComputeNodeOpts {
     /// Enable reporting tracing information to jaeger
     #[clap(long)]
     pub enable_jaeger_tracing: bool,
+
+    /// Labels of the compute node in the form of `key=value`, e.g. `--label zone=us-east-1a`.
+    /// Used by the placement constraints of materialized views.
+    #[clap(long = "label", parse(try_from_str = parse_label), multiple_occurrences(true))]
+    pub labels: Vec<(String, String)>,
+}
+
+fn parse_label(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("invalid label `{}`, expected `key=value`", s)),
+    }
 }
 
 use crate::server::compute_node_serve;
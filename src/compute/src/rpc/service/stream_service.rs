@@ -101,6 +101,12 @@ impl StreamService for StreamServiceImpl {
     ) -> std::result::Result<Response<DropActorsResponse>, Status> {
         let req = request.into_inner();
         let actors = req.actor_ids;
+        if req.delete_states_epoch != 0 {
+            self.mgr
+                .delete_actor_states(&actors, req.delete_states_epoch)
+                .await
+                .map_err(|e| e.to_grpc_status())?;
+        }
         self.mgr
             .drop_actor(&actors)
             .map_err(|e| e.to_grpc_status())?;
@@ -154,6 +160,7 @@ impl StreamService for StreamServiceImpl {
         Ok(Response::new(InjectBarrierResponse {
             request_id: req.request_id,
             finished_create_mviews,
+            failed_actors: collect_result.failed_actors,
             status: None,
         }))
     }
@@ -204,6 +211,41 @@ impl StreamService for StreamServiceImpl {
 
         Ok(Response::new(DropSourceResponse { status: None }))
     }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_actor_stats(
+        &self,
+        _request: Request<GetActorStatsRequest>,
+    ) -> Result<Response<GetActorStatsResponse>, Status> {
+        Ok(Response::new(GetActorStatsResponse {
+            actor_row_counts: self.mgr.actor_row_counts(),
+        }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_barrier_status(
+        &self,
+        _request: Request<GetBarrierStatusRequest>,
+    ) -> Result<Response<GetBarrierStatusResponse>, Status> {
+        let status = self.mgr.barrier_collection_status();
+        Ok(Response::new(GetBarrierStatusResponse {
+            epoch: status.epoch.unwrap_or_default(),
+            collected_actors: status.collected_actors,
+            remaining_actors: status.remaining_actors,
+            actor_last_epochs: status.last_collected_epochs,
+        }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn dump_await_tree(
+        &self,
+        request: Request<DumpAwaitTreeRequest>,
+    ) -> Result<Response<DumpAwaitTreeResponse>, Status> {
+        let req = request.into_inner();
+        Ok(Response::new(DumpAwaitTreeResponse {
+            actor_traces: self.mgr.dump_await_trees(&req.actor_ids),
+        }))
+    }
 }
 
 impl StreamServiceImpl {
@@ -159,6 +159,7 @@ async fn test_table_v2_materialize() -> Result<()> {
         barrier_rx,
         1,
         1,
+        1,
         "SourceExecutor".to_string(),
         Arc::new(StreamingMetrics::unused()),
         vec![],
@@ -30,8 +30,8 @@ pub enum SourceOffset {
 }
 
 use crate::kafka::KafkaSplit;
-use crate::kinesis::split::KinesisSplit;
-use crate::pulsar::{PulsarSplit, PulsarSplitEnumerator};
+use crate::kinesis::split::{KinesisOffset, KinesisSplit};
+use crate::pulsar::{PulsarOffset, PulsarSplit, PulsarSplitEnumerator};
 use crate::utils::AnyhowProperties;
 use crate::{kafka, kinesis, pulsar};
 
@@ -87,7 +87,7 @@ pub enum SplitEnumeratorImpl {
     Kinesis(kinesis::enumerator::client::KinesisSplitEnumerator),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SplitImpl {
     Kafka(kafka::KafkaSplit),
     Pulsar(pulsar::PulsarSplit),
@@ -119,6 +119,51 @@ impl SplitImpl {
         }
     }
 
+    /// Returns the state to create a reader of this split from its start offset.
+    pub fn to_state(&self) -> ConnectorState {
+        let (start_offset, end_offset) = match self {
+            SplitImpl::Kafka(k) => (
+                k.start_offset.map(|o| o.to_string()).unwrap_or_default(),
+                k.stop_offset.map(|o| o.to_string()).unwrap_or_default(),
+            ),
+            SplitImpl::Pulsar(p) => {
+                let to_string = |offset: &PulsarOffset| match offset {
+                    PulsarOffset::MessageID(id) => id.to_string(),
+                    _ => String::new(),
+                };
+                (to_string(&p.start_offset), to_string(&p.stop_offset))
+            }
+            SplitImpl::Kinesis(k) => {
+                let to_string = |offset: &KinesisOffset| match offset {
+                    KinesisOffset::SequenceNumber(seq) => seq.clone(),
+                    _ => String::new(),
+                };
+                (to_string(&k.start_position), to_string(&k.end_position))
+            }
+        };
+        ConnectorState {
+            identifier: Bytes::from(self.id()),
+            start_offset,
+            end_offset,
+        }
+    }
+
+    /// Moves the start offset of this split past `offset`, which is the offset of the last message
+    /// read from the split, so that a new reader of the split continues from there.
+    pub fn update_offset(&mut self, offset: &str) -> Result<()> {
+        match self {
+            SplitImpl::Kafka(k) => k.start_offset = Some(offset.parse::<i64>()? + 1),
+            SplitImpl::Pulsar(p) => {
+                p.start_offset = PulsarOffset::MessageID(offset.parse::<u64>()? + 1)
+            }
+            // Kinesis readers start after the sequence number.
+            SplitImpl::Kinesis(k) => {
+                k.start_position = KinesisOffset::SequenceNumber(offset.to_string())
+            }
+        }
+        Ok(())
+    }
+
     pub fn restore_from_bytes(split_type: String, bytes: &[u8]) -> Result<Self> {
         match split_type.as_str() {
             kafka::KAFKA_SPLIT_TYPE => KafkaSplit::restore_from_bytes(bytes).map(SplitImpl::Kafka),
@@ -133,6 +178,28 @@ impl SplitImpl {
     }
 }
 
+/// Encodes `splits` into their split type and the serialized splits, as they're carried in
+/// protobuf. All the splits must be of the same type.
+pub fn encode_splits(splits: &[SplitImpl]) -> Result<(String, Vec<Vec<u8>>)> {
+    let split_type = splits
+        .first()
+        .map(|split| split.get_type())
+        .unwrap_or_default();
+    let splits = splits
+        .iter()
+        .map(|split| split.to_string().map(String::into_bytes))
+        .collect::<Result<_>>()?;
+    Ok((split_type, splits))
+}
+
+/// Decodes the splits encoded by [`encode_splits`].
+pub fn decode_splits(split_type: &str, splits: &[Vec<u8>]) -> Result<Vec<SplitImpl>> {
+    splits
+        .iter()
+        .map(|split| SplitImpl::restore_from_bytes(split_type.to_string(), split))
+        .collect()
+}
+
 impl SplitEnumeratorImpl {
     pub async fn list_splits(&mut self) -> Result<Vec<SplitImpl>> {
         match self {
@@ -178,3 +245,39 @@ pub async fn new_connector(
     };
     Ok(connector)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_splits() -> Result<()> {
+        let splits = vec![
+            SplitImpl::Kafka(KafkaSplit::new(0, None, None, "topic".to_string())),
+            SplitImpl::Kafka(KafkaSplit::new(1, Some(10), None, "topic".to_string())),
+        ];
+        let (split_type, encoded) = encode_splits(&splits)?;
+        assert_eq!(split_type, kafka::KAFKA_SPLIT_TYPE);
+        assert_eq!(decode_splits(&split_type, &encoded)?, splits);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_offset() -> Result<()> {
+        let mut split = SplitImpl::Kafka(KafkaSplit::new(0, None, None, "topic".to_string()));
+        split.update_offset("41")?;
+        assert_eq!(split.to_state().start_offset, "42");
+
+        let mut split = SplitImpl::Kinesis(KinesisSplit::new(
+            "shard-0".to_string(),
+            KinesisOffset::Earliest,
+            KinesisOffset::None,
+        ));
+        assert_eq!(split.to_state().start_offset, "");
+        split.update_offset("123")?;
+        let state = split.to_state();
+        assert_eq!(state.identifier, Bytes::from("shard-0"));
+        assert_eq!(state.start_offset, "123");
+        Ok(())
+    }
+}
@@ -20,14 +20,14 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use futures::StreamExt;
 use rdkafka::config::RDKafkaLogLevel;
-use rdkafka::consumer::{DefaultConsumerContext, StreamConsumer};
-use rdkafka::ClientConfig;
+use rdkafka::consumer::{Consumer, DefaultConsumerContext, StreamConsumer};
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
 use risingwave_common::error::ErrorCode::{InternalError, ProtocolError};
 use risingwave_common::error::RwError;
 
 use crate::base::{InnerMessage, SourceReader};
 use crate::kafka::split::KafkaSplit;
-use crate::kafka::KAFKA_CONFIG_BROKER_KEY;
+use crate::kafka::{KAFKA_CONFIG_BROKER_KEY, KAFKA_CONFIG_TOPIC_KEY};
 
 const KAFKA_MAX_FETCH_MESSAGES: usize = 1024;
 
@@ -58,7 +58,7 @@ impl SourceReader for KafkaSplitReader {
 
     async fn new(
         properties: HashMap<String, String>,
-        _state: Option<crate::ConnectorState>,
+        state: Option<crate::ConnectorState>,
     ) -> Result<Self>
     where
         Self: Sized,
@@ -70,6 +70,13 @@ impl SourceReader for KafkaSplitReader {
             )))
         })?;
 
+        let topic = properties.get(KAFKA_CONFIG_TOPIC_KEY).ok_or_else(|| {
+            RwError::from(ProtocolError(format!(
+                "could not found config {}",
+                KAFKA_CONFIG_TOPIC_KEY
+            )))
+        })?;
+
         let mut config = ClientConfig::new();
 
         // disable partition eof
@@ -96,6 +103,22 @@ impl SourceReader for KafkaSplitReader {
             .create_with_context(DefaultConsumerContext)
             .map_err(|e| RwError::from(InternalError(format!("consumer creation failed {}", e))))?;
 
+        // A reader of a split only reads its partition, starting from the offset of the split.
+        match state {
+            Some(state) => {
+                let partition = String::from_utf8(state.identifier.to_vec())?.parse::<i32>()?;
+                let offset = if state.start_offset.is_empty() {
+                    Offset::Beginning
+                } else {
+                    Offset::Offset(state.start_offset.parse::<i64>()?)
+                };
+                let mut partitions = TopicPartitionList::new();
+                partitions.add_partition_offset(topic, partition, offset)?;
+                consumer.assign(&partitions)?;
+            }
+            None => consumer.subscribe(&[topic.as_str()])?,
+        }
+
         Ok(Self {
             consumer: Arc::new(consumer),
             assigned_splits: HashMap::new(),
@@ -0,0 +1,26 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod create_backup;
+pub use create_backup::*;
+mod list_backups;
+pub use list_backups::*;
+mod delete_backup;
+pub use delete_backup::*;
+mod restore_backup;
+pub use restore_backup::*;
+
+/// Path of the backup manifest in object store.
+fn manifest_path(path: &str) -> String {
+    format!("{}/manifest", path.trim_end_matches('/'))
+}
@@ -0,0 +1,36 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use bytes::Bytes;
+use prost::Message;
+
+use super::manifest_path;
+use crate::common::HummockServiceOpts;
+
+pub async fn create_backup(path: String) -> anyhow::Result<()> {
+    let hummock_opts = HummockServiceOpts::from_env()?;
+    let meta_client = hummock_opts.meta_opts.create_meta_client().await?;
+    let object_store = hummock_opts.create_object_store().await;
+    let manifest = meta_client.create_backup(path.clone()).await?;
+    let backup = manifest.backup.clone().unwrap();
+    if let Err(e) = object_store
+        .upload(&manifest_path(&path), Bytes::from(manifest.encode_to_vec()))
+        .await
+    {
+        // The backup can't be restored without its manifest, so its SSTs are not retained.
+        meta_client.delete_backup(backup.id).await?;
+        return Err(e.into());
+    }
+    println!("{:#?}", backup);
+    Ok(())
+}
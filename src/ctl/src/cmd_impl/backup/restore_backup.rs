@@ -0,0 +1,59 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use anyhow::bail;
+use etcd_client::Client as EtcdClient;
+use prost::Message;
+use risingwave_common::error::RwError;
+use risingwave_meta::hummock::restore_meta_store;
+use risingwave_meta::storage::{EtcdMetaStore, FileMetaStore};
+use risingwave_pb::hummock::HummockBackupManifest;
+
+use super::manifest_path;
+use crate::common::HummockServiceOpts;
+
+/// Restores the backup at `path` into either the etcd at `etcd_endpoints` or the file-backed meta
+/// store in `file_store_dir`, which should then be used by a fresh meta node.
+///
+/// The file-backed meta store is opened by a single process, so no meta node may run on
+/// `file_store_dir` during the restore. The in-memory meta store can't be restored into.
+pub async fn restore_backup(
+    path: String,
+    etcd_endpoints: Option<String>,
+    file_store_dir: Option<String>,
+) -> anyhow::Result<()> {
+    let hummock_opts = HummockServiceOpts::from_env()?;
+    let object_store = hummock_opts.create_object_store().await;
+    let manifest =
+        HummockBackupManifest::decode(object_store.read(&manifest_path(&path), None).await?)?;
+    match (etcd_endpoints, file_store_dir) {
+        (Some(etcd_endpoints), None) => {
+            let endpoints = etcd_endpoints.split(',').collect::<Vec<_>>();
+            let meta_store = EtcdMetaStore::new(EtcdClient::connect(endpoints, None).await?);
+            restore_meta_store(&meta_store, &manifest).await?;
+        }
+        (None, Some(file_store_dir)) => {
+            let meta_store = FileMetaStore::open(&file_store_dir)
+                .await
+                .map_err(RwError::from)?;
+            restore_meta_store(&meta_store, &manifest).await?;
+        }
+        _ => bail!("exactly one of --etcd-endpoints and --file-store-dir should be given"),
+    }
+    println!(
+        "backup {} of Hummock version {} is restored",
+        manifest.backup.as_ref().unwrap().id,
+        manifest.backup.as_ref().unwrap().version_id
+    );
+    Ok(())
+}
@@ -16,3 +16,5 @@ mod list_version;
 pub use list_version::*;
 mod list_kv;
 pub use list_kv::*;
+mod trigger_compaction;
+pub use trigger_compaction::*;
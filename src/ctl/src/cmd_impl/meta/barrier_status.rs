@@ -0,0 +1,51 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::common::MetaServiceOpts;
+
+pub async fn barrier_status() -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let status = meta_client.get_barrier_status().await?;
+
+    println!("current epoch: {}", status.current_epoch);
+    for barrier in &status.in_flight_barriers {
+        println!(
+            "in-flight barrier: epoch {} -> {}, command {}, checkpoint {}, elapsed {}ms",
+            barrier.prev_epoch,
+            barrier.curr_epoch,
+            barrier.command,
+            barrier.checkpoint,
+            barrier.elapsed_ms
+        );
+    }
+
+    let remaining = status
+        .actors
+        .iter()
+        .filter(|actor| !actor.collected)
+        .collect::<Vec<_>>();
+    println!(
+        "{} actors, {} not collected",
+        status.actors.len(),
+        remaining.len()
+    );
+    for actor in remaining {
+        println!(
+            "actor {} on worker {}, last collected epoch {}",
+            actor.actor_id, actor.worker_id, actor.last_epoch
+        );
+        print!("{}", actor.await_tree);
+    }
+    Ok(())
+}
@@ -20,6 +20,8 @@ use risingwave_common::config::StorageConfig;
 use risingwave_storage::hummock::hummock_meta_client::MonitoredHummockMetaClient;
 use risingwave_storage::hummock::HummockStorage;
 use risingwave_storage::monitor::{HummockMetrics, MonitoredStateStore, StateStoreMetrics};
+use risingwave_storage::object::ObjectStoreImpl;
+use risingwave_storage::store_impl::new_hummock_object_store;
 use risingwave_storage::StateStoreImpl;
 
 use super::MetaServiceOpts;
@@ -76,4 +78,8 @@ impl HummockServiceOpts {
             Err(anyhow!("only Hummock state store is supported in risectl"))
         }
     }
+
+    pub async fn create_object_store(&self) -> ObjectStoreImpl {
+        new_hummock_object_store(&self.hummock_url).await
+    }
 }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::env;
 
 use anyhow::Result;
@@ -45,7 +46,11 @@ impl MetaServiceOpts {
         let mut client = MetaClient::new(&self.meta_addr).await?;
         // FIXME: don't use 127.0.0.1 for ctl
         let worker_id = client
-            .register(&"127.0.0.1:2333".parse().unwrap(), WorkerType::RiseCtl)
+            .register(
+                &"127.0.0.1:2333".parse().unwrap(),
+                WorkerType::RiseCtl,
+                HashMap::new(),
+            )
             .await?;
         tracing::info!("registered as RiseCtl worker, worker_id = {}", worker_id);
         // TODO: remove worker node
@@ -31,6 +31,15 @@ enum Commands {
     /// Commands for Hummock
     #[clap(subcommand)]
     Hummock(HummockCommands),
+    /// Commands for backup and restore
+    #[clap(subcommand)]
+    Backup(BackupCommands),
+    /// Commands for cluster scheduling
+    #[clap(subcommand)]
+    Cluster(ClusterCommands),
+    /// Commands for meta node
+    #[clap(subcommand)]
+    Meta(MetaCommands),
 }
 
 #[derive(Subcommand)]
@@ -39,6 +48,60 @@ enum HummockCommands {
     ListVersion,
     /// list all Hummock key-value pairs
     ListKv,
+    /// trigger a compaction of the given level into the level below it
+    TriggerCompaction {
+        #[clap(short, long)]
+        level: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackupCommands {
+    /// back up the current Hummock version and meta store to `path` in Hummock object store
+    Create {
+        #[clap(short, long)]
+        path: String,
+    },
+    /// list backups whose SSTs are retained by meta node
+    List,
+    /// delete a backup, after which its SSTs can be vacuumed
+    Delete {
+        #[clap(short, long)]
+        id: u64,
+    },
+    /// restore the backup at `path` into a fresh etcd or file-backed meta store for bootstrapping
+    /// a new meta node, which must not be running during the restore
+    Restore {
+        #[clap(short, long)]
+        path: String,
+        /// comma-separated endpoints of the etcd to restore into
+        #[clap(long)]
+        etcd_endpoints: Option<String>,
+        /// directory of the file-backed meta store to restore into
+        #[clap(long)]
+        file_store_dir: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ClusterCommands {
+    /// reschedule the actors of a materialized view online
+    Reschedule {
+        /// id of the materialized view
+        table_id: u32,
+        /// parallelism of the hash distributed fragments, keep the current one if not specified
+        #[clap(short, long)]
+        parallelism: Option<u32>,
+        /// comma-separated ids of the workers to schedule actors to, use all if not specified
+        #[clap(short, long, use_value_delimiter = true)]
+        workers: Vec<u32>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetaCommands {
+    /// show the barriers in flight and the actors not collected yet, with their await trees
+    BarrierStatus,
 }
 
 pub async fn start(opts: CliOpts) {
@@ -47,5 +110,36 @@ pub async fn start(opts: CliOpts) {
             cmd_impl::hummock::list_version().await.unwrap()
         }
         Commands::Hummock(HummockCommands::ListKv) => cmd_impl::hummock::list_kv().await.unwrap(),
+        Commands::Hummock(HummockCommands::TriggerCompaction { level }) => {
+            cmd_impl::hummock::trigger_compaction(*level).await.unwrap()
+        }
+        Commands::Backup(BackupCommands::Create { path }) => {
+            cmd_impl::backup::create_backup(path.clone()).await.unwrap()
+        }
+        Commands::Backup(BackupCommands::List) => cmd_impl::backup::list_backups().await.unwrap(),
+        Commands::Backup(BackupCommands::Delete { id }) => {
+            cmd_impl::backup::delete_backup(*id).await.unwrap()
+        }
+        Commands::Backup(BackupCommands::Restore {
+            path,
+            etcd_endpoints,
+            file_store_dir,
+        }) => cmd_impl::backup::restore_backup(
+            path.clone(),
+            etcd_endpoints.clone(),
+            file_store_dir.clone(),
+        )
+        .await
+        .unwrap(),
+        Commands::Cluster(ClusterCommands::Reschedule {
+            table_id,
+            parallelism,
+            workers,
+        }) => cmd_impl::cluster::reschedule(*table_id, *parallelism, workers.clone())
+            .await
+            .unwrap(),
+        Commands::Meta(MetaCommands::BarrierStatus) => {
+            cmd_impl::meta::barrier_status().await.unwrap()
+        }
     }
 }
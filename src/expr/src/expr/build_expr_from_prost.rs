@@ -15,20 +15,23 @@
 use risingwave_common::array::DataChunk;
 use risingwave_common::ensure;
 use risingwave_common::error::{ErrorCode, Result, RwError};
-use risingwave_common::types::{DataType, ToOwnedDatum};
+use risingwave_common::types::{DataType, ScalarImpl, ToOwnedDatum};
 use risingwave_pb::expr::expr_node::RexNode;
 use risingwave_pb::expr::{expr_node, ExprNode};
 
+use crate::expr::expr_array::{ArrayAccessExpression, ArrayExpression, ArrayLengthExpression};
 use crate::expr::expr_binary_bytes::new_substr_start;
 use crate::expr::expr_binary_nonnull::{new_binary_expr, new_like_default};
 use crate::expr::expr_binary_nullable::new_nullable_binary_expr;
 use crate::expr::expr_case::{CaseExpression, WhenClause};
 use crate::expr::expr_in::InExpression;
+use crate::expr::expr_struct::{FieldExpression, StructExpression};
 use crate::expr::expr_ternary_bytes::{new_replace_expr, new_substr_start_end, new_translate_expr};
 use crate::expr::expr_unary::{
-    new_length_default, new_ltrim_expr, new_rtrim_expr, new_trim_expr, new_unary_expr,
+    new_decimal_sizing_expr, new_length_default, new_ltrim_expr, new_rtrim_expr, new_trim_expr,
+    new_unary_expr,
 };
-use crate::expr::{build_from_prost as expr_build_from_prost, BoxedExpression};
+use crate::expr::{build_from_prost as expr_build_from_prost, BoxedExpression, LiteralExpression};
 
 fn get_return_type_and_children(prost: &ExprNode) -> Result<(Vec<ExprNode>, DataType)> {
     let ret_type = DataType::from(prost.get_return_type()?);
@@ -53,6 +56,21 @@ pub fn build_binary_expr_prost(prost: &ExprNode) -> Result<BoxedExpression> {
     ensure!(children.len() == 2);
     let left_expr = expr_build_from_prost(&children[0])?;
     let right_expr = expr_build_from_prost(&children[1])?;
+    if let DataType::Decimal {
+        precision: Some(precision),
+        scale,
+    } = ret_type
+    {
+        // Arithmetic on `numeric` is exact except for division, so compute with an unconstrained
+        // result and round it to the derived typmod, which also checks for overflow.
+        let expr = new_binary_expr(
+            prost.get_expr_type()?,
+            DataType::UNCONSTRAINED_DECIMAL,
+            left_expr,
+            right_expr,
+        );
+        return Ok(new_decimal_sizing_expr(expr, precision, scale.unwrap_or(0)));
+    }
     Ok(new_binary_expr(
         prost.get_expr_type()?,
         ret_type,
@@ -204,6 +222,56 @@ pub fn build_case_expr(prost: &ExprNode) -> Result<BoxedExpression> {
     )))
 }
 
+pub fn build_array_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_return_type_and_children(prost)?;
+    let elements = children
+        .iter()
+        .map(expr_build_from_prost)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Box::new(ArrayExpression::new(ret_type, elements)))
+}
+
+pub fn build_array_access_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_return_type_and_children(prost)?;
+    ensure!(children.len() == 2);
+    let array = expr_build_from_prost(&children[0])?;
+    let index = expr_build_from_prost(&children[1])?;
+    Ok(Box::new(ArrayAccessExpression::new(ret_type, array, index)))
+}
+
+pub fn build_array_length_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_return_type_and_children(prost)?;
+    ensure!(children.len() == 1);
+    ensure!(ret_type == DataType::Int32);
+    let array = expr_build_from_prost(&children[0])?;
+    Ok(Box::new(ArrayLengthExpression::new(array)))
+}
+
+pub fn build_row_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_return_type_and_children(prost)?;
+    let fields = children
+        .iter()
+        .map(expr_build_from_prost)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Box::new(StructExpression::new(ret_type, fields)))
+}
+
+pub fn build_field_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_return_type_and_children(prost)?;
+    ensure!(children.len() == 2);
+    let input = expr_build_from_prost(&children[0])?;
+    let index = match LiteralExpression::try_from(&children[1])?.literal() {
+        Some(ScalarImpl::Int32(index)) if index >= 0 => index as usize,
+        _ => {
+            return Err(ErrorCode::InternalError(
+                "field index must be a non-negative int32 constant".to_string(),
+            )
+            .into())
+        }
+    };
+    Ok(Box::new(FieldExpression::new(ret_type, input, index)))
+}
+
 pub fn build_translate_expr(prost: &ExprNode) -> Result<BoxedExpression> {
     let (children, ret_type) = get_return_type_and_children(prost)?;
     ensure!(children.len() == 3);
@@ -0,0 +1,199 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use risingwave_common::array::{ArrayRef, DataChunk, ListValue};
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, Datum, ScalarImpl, ScalarRefImpl};
+
+use crate::expr::{BoxedExpression, Expression};
+
+/// `ARRAY[e1, e2, ...]` builds a list from the values of its elements in each row.
+#[derive(Debug)]
+pub struct ArrayExpression {
+    return_type: DataType,
+    elements: Vec<BoxedExpression>,
+}
+
+impl ArrayExpression {
+    pub fn new(return_type: DataType, elements: Vec<BoxedExpression>) -> Self {
+        Self {
+            return_type,
+            elements,
+        }
+    }
+}
+
+impl Expression for ArrayExpression {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let elements = self
+            .elements
+            .iter()
+            .map(|e| e.eval(input))
+            .collect::<Result<Vec<_>>>()?;
+        let mut builder = self.return_type.create_array_builder(input.capacity())?;
+        for idx in 0..input.capacity() {
+            let values = elements.iter().map(|e| e.datum_at(idx)).collect_vec();
+            builder.append_datum(&Some(ListValue::new(values).into()))?;
+        }
+        Ok(builder.finish()?.into())
+    }
+}
+
+/// `array[index]` returns the element at the 1-based `index`, or null if the index is out of
+/// bound.
+#[derive(Debug)]
+pub struct ArrayAccessExpression {
+    return_type: DataType,
+    array: BoxedExpression,
+    index: BoxedExpression,
+}
+
+impl ArrayAccessExpression {
+    pub fn new(return_type: DataType, array: BoxedExpression, index: BoxedExpression) -> Self {
+        Self {
+            return_type,
+            array,
+            index,
+        }
+    }
+}
+
+impl Expression for ArrayAccessExpression {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let array = self.array.eval(input)?;
+        let index = self.index.eval(input)?;
+        let mut builder = self.return_type.create_array_builder(input.capacity())?;
+        for idx in 0..input.capacity() {
+            let datum: Datum = match (array.value_at(idx), index.value_at(idx)) {
+                (Some(ScalarRefImpl::List(list)), Some(ScalarRefImpl::Int32(i))) if i >= 1 => list
+                    .values_ref()
+                    .get(i as usize - 1)
+                    .cloned()
+                    .flatten()
+                    .map(|v| v.into_scalar_impl()),
+                _ => None,
+            };
+            builder.append_datum(&datum)?;
+        }
+        Ok(builder.finish()?.into())
+    }
+}
+
+/// `array_length(array)` returns the number of elements in the array.
+#[derive(Debug)]
+pub struct ArrayLengthExpression {
+    array: BoxedExpression,
+}
+
+impl ArrayLengthExpression {
+    pub fn new(array: BoxedExpression) -> Self {
+        Self { array }
+    }
+}
+
+impl Expression for ArrayLengthExpression {
+    fn return_type(&self) -> DataType {
+        DataType::Int32
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let array = self.array.eval(input)?;
+        let mut builder = DataType::Int32.create_array_builder(input.capacity())?;
+        for idx in 0..input.capacity() {
+            let datum = match array.value_at(idx) {
+                Some(ScalarRefImpl::List(list)) => Some(ScalarImpl::Int32(list.len() as i32)),
+                _ => None,
+            };
+            builder.append_datum(&datum)?;
+        }
+        Ok(builder.finish()?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::column::Column;
+    use risingwave_common::array::{ArrayImpl, I32Array};
+
+    use super::*;
+    use crate::expr::{InputRefExpression, LiteralExpression};
+
+    fn list_type() -> DataType {
+        DataType::List {
+            datatype: Box::new(DataType::Int32),
+        }
+    }
+
+    #[test]
+    fn test_array_access() {
+        let col = Column::new(std::sync::Arc::new(ArrayImpl::from(
+            I32Array::from_slice(&[Some(1), None, Some(3)]).unwrap(),
+        )));
+        let input = DataChunk::builder().columns(vec![col]).build();
+
+        let array = ArrayExpression::new(
+            list_type(),
+            vec![
+                Box::new(InputRefExpression::new(DataType::Int32, 0)),
+                Box::new(LiteralExpression::new(DataType::Int32, Some(10.into()))),
+            ],
+        );
+        let output = array.eval(&input).unwrap();
+        assert_eq!(
+            output.datum_at(1),
+            Some(ListValue::new(vec![None, Some(10.into())]).into())
+        );
+
+        let access = ArrayAccessExpression::new(
+            DataType::Int32,
+            array.boxed(),
+            Box::new(LiteralExpression::new(DataType::Int32, Some(1.into()))),
+        );
+        let output = access.eval(&input).unwrap();
+        assert_eq!(output.datum_at(0), Some(1.into()));
+        assert_eq!(output.datum_at(1), None);
+        assert_eq!(output.datum_at(2), Some(3.into()));
+    }
+
+    #[test]
+    fn test_array_length() {
+        let col = Column::new(std::sync::Arc::new(ArrayImpl::from(
+            I32Array::from_slice(&[Some(1), Some(2)]).unwrap(),
+        )));
+        let input = DataChunk::builder().columns(vec![col]).build();
+        let length = ArrayLengthExpression::new(
+            ArrayExpression::new(
+                list_type(),
+                vec![
+                    Box::new(InputRefExpression::new(DataType::Int32, 0)),
+                    Box::new(InputRefExpression::new(DataType::Int32, 0)),
+                    Box::new(InputRefExpression::new(DataType::Int32, 0)),
+                ],
+            )
+            .boxed(),
+        );
+        let output = length.eval(&input).unwrap();
+        assert_eq!(output.datum_at(0), Some(3.into()));
+        assert_eq!(output.datum_at(1), Some(3.into()));
+    }
+}
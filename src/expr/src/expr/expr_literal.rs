@@ -101,7 +101,7 @@ fn literal_type_match(return_type: &DataType, literal: Option<&ScalarImpl>) -> b
                     | (DataType::Date, ScalarImpl::NaiveDate(_))
                     | (DataType::Time, ScalarImpl::NaiveTime(_))
                     | (DataType::Timestamp, ScalarImpl::NaiveDateTime(_))
-                    | (DataType::Decimal, ScalarImpl::Decimal(_))
+                    | (DataType::Decimal { .. }, ScalarImpl::Decimal(_))
                     | (DataType::Interval, ScalarImpl::Interval(_))
             )
         }
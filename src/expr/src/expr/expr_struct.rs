@@ -0,0 +1,136 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use risingwave_common::array::{ArrayRef, DataChunk, StructValue};
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, Datum, ScalarRefImpl};
+
+use crate::expr::{BoxedExpression, Expression};
+
+/// `ROW(e1, e2, ...)` builds a struct from the values of its fields in each row.
+#[derive(Debug)]
+pub struct StructExpression {
+    return_type: DataType,
+    fields: Vec<BoxedExpression>,
+}
+
+impl StructExpression {
+    pub fn new(return_type: DataType, fields: Vec<BoxedExpression>) -> Self {
+        Self {
+            return_type,
+            fields,
+        }
+    }
+}
+
+impl Expression for StructExpression {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|e| e.eval(input))
+            .collect::<Result<Vec<_>>>()?;
+        let mut builder = self.return_type.create_array_builder(input.capacity())?;
+        for idx in 0..input.capacity() {
+            let values = fields.iter().map(|f| f.datum_at(idx)).collect_vec();
+            builder.append_datum(&Some(StructValue::new(values).into()))?;
+        }
+        Ok(builder.finish()?.into())
+    }
+}
+
+/// `(struct).field` returns the field at `index` of the struct, or null if the struct is null.
+#[derive(Debug)]
+pub struct FieldExpression {
+    return_type: DataType,
+    input: BoxedExpression,
+    index: usize,
+}
+
+impl FieldExpression {
+    pub fn new(return_type: DataType, input: BoxedExpression, index: usize) -> Self {
+        Self {
+            return_type,
+            input,
+            index,
+        }
+    }
+}
+
+impl Expression for FieldExpression {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let array = self.input.eval(input)?;
+        let mut builder = self.return_type.create_array_builder(input.capacity())?;
+        for idx in 0..input.capacity() {
+            let datum: Datum = match array.value_at(idx) {
+                Some(ScalarRefImpl::Struct(s)) => {
+                    s.fields_ref()[self.index].map(|v| v.into_scalar_impl())
+                }
+                _ => None,
+            };
+            builder.append_datum(&datum)?;
+        }
+        Ok(builder.finish()?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::column::Column;
+    use risingwave_common::array::{ArrayImpl, I32Array};
+
+    use super::*;
+    use crate::expr::{InputRefExpression, LiteralExpression};
+
+    #[test]
+    fn test_row_and_field() {
+        let col = Column::new(std::sync::Arc::new(ArrayImpl::from(
+            I32Array::from_slice(&[Some(1), None, Some(3)]).unwrap(),
+        )));
+        let input = DataChunk::builder().columns(vec![col]).build();
+
+        let row = StructExpression::new(
+            DataType::Struct {
+                fields: vec![DataType::Int32, DataType::Varchar].into(),
+            },
+            vec![
+                Box::new(InputRefExpression::new(DataType::Int32, 0)),
+                Box::new(LiteralExpression::new(
+                    DataType::Varchar,
+                    Some("a".to_string().into()),
+                )),
+            ],
+        );
+        let output = row.eval(&input).unwrap();
+        assert_eq!(
+            output.datum_at(1),
+            Some(StructValue::new(vec![None, Some("a".to_string().into())]).into())
+        );
+
+        let field = FieldExpression::new(DataType::Int32, row.boxed(), 0);
+        let output = field.eval(&input).unwrap();
+        assert_eq!(output.datum_at(0), Some(1.into()));
+        assert_eq!(output.datum_at(1), None);
+        assert_eq!(output.datum_at(2), Some(3.into()));
+    }
+}
@@ -192,6 +192,21 @@ pub fn new_unary_expr(
     use crate::expr::data_types::*;
 
     let expr: BoxedExpression = match (expr_type, return_type.clone(), child_expr.return_type()) {
+        (
+            ProstType::Cast,
+            DataType::Decimal {
+                precision: Some(precision),
+                scale,
+            },
+            child_type,
+        ) => {
+            // Cast to an unconstrained numeric first, then round it to the declared typmod.
+            let child_expr = match child_type {
+                DataType::Decimal { .. } => child_expr,
+                _ => new_unary_expr(ProstType::Cast, DataType::UNCONSTRAINED_DECIMAL, child_expr)?,
+            };
+            new_decimal_sizing_expr(child_expr, precision, scale.unwrap_or(0))
+        }
         (ProstType::Cast, _, _) => gen_cast! { child_expr, return_type, },
         (ProstType::Not, _, _) => {
             Box::new(UnaryNullableExpression::<BoolArray, BoolArray, _>::new(
@@ -248,7 +263,9 @@ pub fn new_unary_expr(
         (ProstType::Neg, _, _) => {
             gen_neg! { child_expr, return_type }
         }
-        (ProstType::PgSleep, _, DataType::Decimal) => Box::new(PgSleepExpression::new(child_expr)),
+        (ProstType::PgSleep, _, DataType::Decimal { .. }) => {
+            Box::new(PgSleepExpression::new(child_expr))
+        }
 
         (expr, ret, child) => {
             return Err(ErrorCode::NotImplemented(format!(
@@ -262,6 +279,23 @@ pub fn new_unary_expr(
     Ok(expr)
 }
 
+/// Rounds the decimals evaluated by `child_expr` to `NUMERIC(precision, scale)`, failing on values
+/// that do not fit.
+pub fn new_decimal_sizing_expr(
+    child_expr: BoxedExpression,
+    precision: u32,
+    scale: u32,
+) -> BoxedExpression {
+    Box::new(UnaryExpression::<DecimalArray, DecimalArray, _>::new(
+        child_expr,
+        DataType::Decimal {
+            precision: Some(precision),
+            scale: Some(scale),
+        },
+        move |x| dec_to_typmod(x, precision, scale),
+    ))
+}
+
 pub fn new_length_default(expr_ia1: BoxedExpression, return_type: DataType) -> BoxedExpression {
     Box::new(UnaryExpression::<Utf8Array, I64Array, _>::new(
         expr_ia1,
@@ -541,7 +575,10 @@ mod tests {
         assert_castible(DataType::Boolean, true);
         assert_castible(DataType::Float32, 3.0_f32);
         assert_castible(DataType::Float64, 3.0_f64);
-        assert_castible(DataType::Decimal, Decimal::from_f32(3.15_f32).unwrap());
+        assert_castible(
+            DataType::UNCONSTRAINED_DECIMAL,
+            Decimal::from_f32(3.15_f32).unwrap(),
+        );
         assert_castible(DataType::Varchar, "abc".to_string());
         assert_castible(
             DataType::Date,
@@ -556,4 +593,41 @@ mod tests {
             NaiveDateTimeWrapper::new_with_secs_nsecs(1, 1).unwrap(),
         );
     }
+
+    #[test]
+    fn test_decimal_sizing_cast() {
+        use std::str::FromStr;
+
+        use risingwave_common::types::DataType;
+        use risingwave_pb::expr::expr_node::Type as ExprType;
+
+        let cast = |v: ScalarImpl, child_type: DataType| {
+            new_unary_expr(
+                ExprType::Cast,
+                DataType::Decimal {
+                    precision: Some(5),
+                    scale: Some(2),
+                },
+                Box::new(LiteralExpression::new(child_type, Some(v))) as BoxedExpression,
+            )
+            .unwrap()
+            .eval(&DataChunk::new_dummy(1))
+            .map(|res| res.datum_at(0))
+        };
+
+        let res = cast(
+            Decimal::from_str("1.005").unwrap().into(),
+            DataType::UNCONSTRAINED_DECIMAL,
+        )
+        .unwrap();
+        assert_eq!(res, Some(Decimal::from_str("1.01").unwrap().into()));
+        let res = cast(12_i32.into(), DataType::Int32).unwrap();
+        assert_eq!(res.unwrap().to_string(), "12.00");
+        assert!(cast(1000_i32.into(), DataType::Int32).is_err());
+        assert!(cast(
+            Decimal::from_str("999.995").unwrap().into(),
+            DataType::UNCONSTRAINED_DECIMAL
+        )
+        .is_err());
+    }
 }
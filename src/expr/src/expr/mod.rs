@@ -15,6 +15,7 @@
 mod agg;
 pub mod build_expr_from_prost;
 pub mod data_types;
+mod expr_array;
 mod expr_binary_bytes;
 pub mod expr_binary_nonnull;
 pub mod expr_binary_nullable;
@@ -23,6 +24,7 @@ mod expr_in;
 mod expr_input_ref;
 mod expr_is_null;
 mod expr_literal;
+mod expr_struct;
 mod expr_ternary_bytes;
 pub mod expr_unary;
 mod pg_sleep;
@@ -90,6 +92,11 @@ pub fn build_from_prost(prost: &ExprNode) -> Result<BoxedExpression> {
         Case => build_case_expr(prost),
         Translate => build_translate_expr(prost),
         In => build_in_expr(prost),
+        Array => build_array_expr(prost),
+        ArrayAccess => build_array_access_expr(prost),
+        ArrayLength => build_array_length_expr(prost),
+        Row => build_row_expr(prost),
+        Field => build_field_expr(prost),
         _ => Err(InternalError(format!(
             "Unsupported expression type: {:?}",
             prost.get_expr_type()
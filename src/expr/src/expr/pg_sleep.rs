@@ -84,7 +84,10 @@ mod tests {
 
     #[test]
     fn test_pg_sleep() -> Result<()> {
-        let expr = PgSleepExpression::new(Box::new(InputRefExpression::new(DataType::Decimal, 0)));
+        let expr = PgSleepExpression::new(Box::new(InputRefExpression::new(
+            DataType::UNCONSTRAINED_DECIMAL,
+            0,
+        )));
 
         let input_array = {
             let mut builder = DecimalArrayBuilder::new(3)?;
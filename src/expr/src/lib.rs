@@ -32,4 +32,5 @@
 #![feature(fn_traits)]
 
 pub mod expr;
+pub mod table_function;
 pub mod vector_op;
@@ -0,0 +1,53 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Set-returning functions which are used as relations, e.g. `unnest`.
+
+use risingwave_common::array::{ArrayRef, DataChunk};
+use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
+use risingwave_pb::expr::table_function::Type;
+use risingwave_pb::expr::TableFunction as TableFunctionProst;
+
+mod unnest;
+
+pub use unnest::*;
+
+/// Instance of a table function.
+pub trait TableFunction: std::fmt::Debug + Sync + Send {
+    fn return_type(&self) -> DataType;
+
+    /// Evaluate the table function on each row of `input`. The outputs of a row are returned as
+    /// one array.
+    fn eval(&self, input: &DataChunk) -> Result<Vec<ArrayRef>>;
+
+    fn boxed(self) -> BoxedTableFunction
+    where
+        Self: Sized + Send + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+pub type BoxedTableFunction = Box<dyn TableFunction>;
+
+pub fn build_from_prost(prost: &TableFunctionProst) -> Result<BoxedTableFunction> {
+    match prost.get_function_type()? {
+        Type::Unnest => new_unnest(prost),
+        Type::Invalid => Err(risingwave_common::error::ErrorCode::InternalError(
+            "invalid table function type".to_string(),
+        )
+        .into()),
+    }
+}
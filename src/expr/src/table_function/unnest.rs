@@ -0,0 +1,100 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use risingwave_common::array::{ArrayRef, DataChunk};
+use risingwave_common::ensure;
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, ScalarRefImpl};
+use risingwave_pb::expr::TableFunction as TableFunctionProst;
+
+use super::{BoxedTableFunction, TableFunction};
+use crate::expr::{build_from_prost as expr_build_from_prost, BoxedExpression};
+
+/// `unnest(array)` expands an array into a set of rows, one per element.
+#[derive(Debug)]
+pub struct Unnest {
+    return_type: DataType,
+    list: BoxedExpression,
+}
+
+impl Unnest {
+    pub fn new(return_type: DataType, list: BoxedExpression) -> Self {
+        Self { return_type, list }
+    }
+}
+
+impl TableFunction for Unnest {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<Vec<ArrayRef>> {
+        let list = self.list.eval(input)?;
+        let mut output = Vec::with_capacity(input.capacity());
+        for idx in 0..input.capacity() {
+            let values = match list.value_at(idx) {
+                Some(ScalarRefImpl::List(list)) => list.values_ref(),
+                _ => vec![],
+            };
+            let mut builder = self.return_type.create_array_builder(values.len())?;
+            for value in values {
+                builder.append_datum_ref(value)?;
+            }
+            output.push(Arc::new(builder.finish()?));
+        }
+        Ok(output)
+    }
+}
+
+pub fn new_unnest(prost: &TableFunctionProst) -> Result<BoxedTableFunction> {
+    let return_type = DataType::from(prost.get_return_type()?);
+    let args = prost.get_args();
+    ensure!(args.len() == 1);
+    let list = expr_build_from_prost(&args[0])?;
+    ensure!(
+        list.return_type()
+            == DataType::List {
+                datatype: Box::new(return_type.clone())
+            }
+    );
+    Ok(Unnest::new(return_type, list).boxed())
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::ListValue;
+
+    use super::*;
+    use crate::expr::LiteralExpression;
+
+    #[test]
+    fn test_unnest() {
+        let list_type = DataType::List {
+            datatype: Box::new(DataType::Int32),
+        };
+        let list = ListValue::new(vec![Some(1.into()), None, Some(3.into())]);
+        let unnest = Unnest::new(
+            DataType::Int32,
+            Box::new(LiteralExpression::new(list_type, Some(list.into()))),
+        );
+        let output = unnest.eval(&DataChunk::new_dummy(1)).unwrap();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].len(), 3);
+        assert_eq!(output[0].datum_at(0), Some(1.into()));
+        assert_eq!(output[0].datum_at(1), None);
+        assert_eq!(output[0].datum_at(2), Some(3.into()));
+    }
+}
@@ -18,11 +18,14 @@ use risingwave_common::types::*;
 use risingwave_pb::expr::AggCall;
 
 use crate::expr::AggKind;
+use crate::vector_op::agg::approx_count_distinct::ApproxCountDistinct;
+use crate::vector_op::agg::array_agg::ArrayAggUnordered;
 use crate::vector_op::agg::count_star::CountStar;
 use crate::vector_op::agg::functions::*;
 use crate::vector_op::agg::general_agg::*;
 use crate::vector_op::agg::general_distinct_agg::*;
 use crate::vector_op::agg::general_sorted_grouper::EqGroups;
+use crate::vector_op::agg::variance::VarianceAgg;
 
 /// An `Aggregator` supports `update` data and `output` result.
 pub trait Aggregator: Send + 'static {
@@ -130,6 +133,41 @@ pub fn create_agg_state_unary(
 ) -> Result<Box<dyn Aggregator>> {
     use crate::expr::data_types::*;
 
+    if *agg_type == AggKind::ArrayAgg {
+        if distinct {
+            return Err(
+                ErrorCode::NotImplemented("array_agg with DISTINCT".into(), None.into()).into(),
+            );
+        }
+        return Ok(Box::new(ArrayAggUnordered::new(return_type, input_col_idx)));
+    }
+
+    match agg_type {
+        AggKind::StddevSamp | AggKind::StddevPop | AggKind::VarSamp | AggKind::VarPop => {
+            // `GeneralDistinctAgg` only wraps fold functions.
+            if distinct {
+                return Err(ErrorCode::NotImplemented(
+                    format!("{} with DISTINCT", agg_type),
+                    None.into(),
+                )
+                .into());
+            }
+            return Ok(Box::new(VarianceAgg::new(
+                agg_type.clone(),
+                return_type,
+                input_col_idx,
+            )));
+        }
+        // `DISTINCT` makes no difference as duplicates are counted only once anyway.
+        AggKind::ApproxCountDistinct => {
+            return Ok(Box::new(ApproxCountDistinct::new(
+                return_type,
+                input_col_idx,
+            )));
+        }
+        _ => {}
+    }
+
     macro_rules! gen_arms {
         [$(($agg:ident, $fn:expr, $in:tt, $ret:tt)),* $(,)?] => {
             match (
@@ -198,6 +236,14 @@ pub fn create_agg_state_unary(
         (Max, max, decimal, decimal),
         (Max, max, boolean, boolean), // TODO(#359): remove once unnecessary
         (Max, max_str, varchar, varchar),
+        (BoolAnd, bool_and, boolean, boolean),
+        (BoolOr, bool_or, boolean, boolean),
+        (BitAnd, bit_and, int16, int16),
+        (BitAnd, bit_and, int32, int32),
+        (BitAnd, bit_and, int64, int64),
+        (BitOr, bit_or, int16, int16),
+        (BitOr, bit_or, int32, int32),
+        (BitOr, bit_or, int64, int64),
         // Global Agg
         (Sum, sum, int64, int64),
         // We remark that SingleValue does not produce a runtime error when it receives zero row.
@@ -223,7 +269,7 @@ mod tests {
     #[test]
     fn test_create_agg_state() {
         let int64_type = DataType::Int64;
-        let decimal_type = DataType::Decimal;
+        let decimal_type = DataType::UNCONSTRAINED_DECIMAL;
         let bool_type = DataType::Boolean;
         let char_type = DataType::Varchar;
 
@@ -267,5 +313,10 @@ mod tests {
         test_create! { decimal_type, SingleValue, decimal_type, is_ok }
         test_create! { bool_type, SingleValue, bool_type, is_ok }
         test_create! { char_type, SingleValue, char_type, is_ok }
+
+        test_create! { bool_type, BoolAnd, bool_type, is_ok }
+        test_create! { int64_type, BoolOr, bool_type, is_err }
+        test_create! { int64_type, BitOr, int64_type, is_ok }
+        test_create! { decimal_type, BitAnd, decimal_type, is_err }
     }
 }
@@ -0,0 +1,204 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `approx_count_distinct` estimates the number of distinct non-null inputs with `HyperLogLog`.
+
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use risingwave_common::array::*;
+use risingwave_common::error::Result;
+use risingwave_common::types::*;
+use risingwave_common::util::hash_util::CRC32FastBuilder;
+
+use crate::vector_op::agg::aggregator::Aggregator;
+use crate::vector_op::agg::general_sorted_grouper::EqGroups;
+
+/// Number of hash bits used to choose a register. 1024 registers give a standard error of about
+/// 3.25%.
+pub const HLL_INDEX_BITS: u32 = 10;
+
+/// Number of registers of a `HyperLogLog` sketch.
+pub const HLL_NUM_REGISTERS: usize = 1 << HLL_INDEX_BITS;
+
+/// Maximum rank a register can hold: the position of the lowest set bit in the hash bits left
+/// after taking out the register index, or one past them if they are all zero.
+pub const HLL_MAX_RANK: u8 = (64 - HLL_INDEX_BITS + 1) as u8;
+
+/// Maps a non-null input to its register and rank. The hash must stay stable across versions and
+/// restarts, since streaming states keep the ranks they have seen.
+pub fn hll_register_and_rank(scalar: ScalarRefImpl<'_>) -> (usize, u8) {
+    let mut hasher = CRC32FastBuilder.build_hasher();
+    match scalar {
+        ScalarRefImpl::Utf8(s) => s.hash(&mut hasher),
+        other => other.into_scalar_impl().hash(&mut hasher),
+    }
+    // CRC32 alone is poorly distributed over the high bits, so spread it with the 64-bit
+    // finalizer of MurmurHash3.
+    let mut hash = hasher.finish();
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+
+    let register = (hash & (HLL_NUM_REGISTERS as u64 - 1)) as usize;
+    let rank = ((hash >> HLL_INDEX_BITS).trailing_zeros() + 1).min(HLL_MAX_RANK as u32) as u8;
+    (register, rank)
+}
+
+/// Estimates the cardinality from all the registers of a sketch.
+pub fn hll_estimate(registers: impl Iterator<Item = u8>) -> i64 {
+    let m = HLL_NUM_REGISTERS as f64;
+    let mut zeros = 0;
+    let mut sum = 0.0;
+    for rank in registers {
+        if rank == 0 {
+            zeros += 1;
+        }
+        sum += 2f64.powi(-(rank as i32));
+    }
+    let alpha = 0.7213 / (1.0 + 1.079 / m);
+    let raw = alpha * m * m / sum;
+    let estimate = if raw <= 2.5 * m && zeros > 0 {
+        // Linear counting is more accurate for small cardinalities.
+        m * (m / zeros as f64).ln()
+    } else {
+        raw
+    };
+    estimate.round() as i64
+}
+
+/// Batch aggregator of `approx_count_distinct`. Like `count`, it returns 0 rather than null when
+/// there is no input.
+pub struct ApproxCountDistinct {
+    return_type: DataType,
+    input_col_idx: usize,
+    registers: Vec<u8>,
+}
+
+impl ApproxCountDistinct {
+    pub fn new(return_type: DataType, input_col_idx: usize) -> Self {
+        Self {
+            return_type,
+            input_col_idx,
+            registers: vec![0; HLL_NUM_REGISTERS],
+        }
+    }
+
+    fn add(&mut self, datum: DatumRef<'_>) {
+        if let Some(scalar) = datum {
+            let (register, rank) = hll_register_and_rank(scalar);
+            self.registers[register] = self.registers[register].max(rank);
+        }
+    }
+
+    fn output_datum(&self) -> Datum {
+        Some(ScalarImpl::Int64(hll_estimate(
+            self.registers.iter().copied(),
+        )))
+    }
+}
+
+impl Aggregator for ApproxCountDistinct {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn update_with_row(&mut self, input: &DataChunk, row_id: usize) -> Result<()> {
+        if let Some(visibility) = input.visibility() {
+            if !visibility.is_set(row_id)? {
+                return Ok(());
+            }
+        }
+        let array = input.column_at(self.input_col_idx).array_ref();
+        self.add(array.value_at(row_id));
+        Ok(())
+    }
+
+    fn update(&mut self, input: &DataChunk) -> Result<()> {
+        for row_id in 0..input.capacity() {
+            self.update_with_row(input, row_id)?;
+        }
+        Ok(())
+    }
+
+    fn output(&self, builder: &mut ArrayBuilderImpl) -> Result<()> {
+        builder.append_datum(&self.output_datum())
+    }
+
+    fn update_and_output_with_sorted_groups(
+        &mut self,
+        input: &DataChunk,
+        builder: &mut ArrayBuilderImpl,
+        groups: &EqGroups,
+    ) -> Result<()> {
+        let array = input.column_at(self.input_col_idx).array_ref();
+        let mut groups_iter = groups.get_starting_indices().iter().peekable();
+        for row_id in 0..array.len() {
+            if groups_iter.peek() == Some(&&row_id) {
+                groups_iter.next();
+                builder.append_datum(&self.output_datum())?;
+                self.registers.fill(0);
+            }
+            self.add(array.value_at(row_id));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use risingwave_common::array::column::Column;
+
+    use super::*;
+
+    #[test]
+    fn test_approx_count_distinct() -> Result<()> {
+        let mut agg = ApproxCountDistinct::new(DataType::Int64, 0);
+        let mut builder = DataType::Int64.create_array_builder(0)?;
+        agg.output(&mut builder)?;
+        // 20000 distinct values, each of them appearing twice, and some nulls.
+        for _ in 0..2 {
+            for start in (0..20000).step_by(1000) {
+                let values = (start..start + 1000)
+                    .map(Some)
+                    .chain([None])
+                    .collect::<Vec<_>>();
+                let input = I32Array::from_slice(&values)?;
+                let chunk = DataChunk::builder()
+                    .columns(vec![Column::new(Arc::new(input.into()))])
+                    .build();
+                agg.update(&chunk)?;
+            }
+        }
+        agg.output(&mut builder)?;
+        let output = builder.finish()?;
+        assert_eq!(output.datum_at(0), Some(ScalarImpl::Int64(0)));
+        let estimate = output.datum_at(1).unwrap().into_int64();
+        assert!((estimate - 20000).abs() < 20000 / 10, "{}", estimate);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hll_small_cardinality() {
+        let mut registers = vec![0; HLL_NUM_REGISTERS];
+        for v in 0..10i64 {
+            let (register, rank) = hll_register_and_rank(ScalarRefImpl::Int64(v));
+            registers[register] = registers[register].max(rank);
+        }
+        assert_eq!(hll_estimate(registers.into_iter()), 10);
+    }
+}
@@ -52,7 +52,7 @@ where
 }
 
 use std::convert::From;
-use std::ops::Add;
+use std::ops::{Add, BitAnd, BitOr};
 
 use risingwave_common::types::ScalarRef;
 
@@ -100,6 +100,50 @@ pub fn max_str<'a>(r: Option<&'a str>, i: Option<&'a str>) -> Result<Option<&'a
     max(r, i)
 }
 
+/// `bool_and` is true if all non-null inputs are true, and null if there are none.
+pub fn bool_and(result: Option<bool>, input: Option<bool>) -> Result<Option<bool>> {
+    let res = match (result, input) {
+        (None, _) => input,
+        (_, None) => result,
+        (Some(r), Some(i)) => Some(r && i),
+    };
+    Ok(res)
+}
+
+/// `bool_or` is true if at least one non-null input is true, and null if there are none.
+pub fn bool_or(result: Option<bool>, input: Option<bool>) -> Result<Option<bool>> {
+    let res = match (result, input) {
+        (None, _) => input,
+        (_, None) => result,
+        (Some(r), Some(i)) => Some(r || i),
+    };
+    Ok(res)
+}
+
+pub fn bit_and<T>(result: Option<T>, input: Option<T>) -> Result<Option<T>>
+where
+    T: BitAnd<Output = T> + Copy,
+{
+    let res = match (result, input) {
+        (None, _) => input,
+        (_, None) => result,
+        (Some(r), Some(i)) => Some(r & i),
+    };
+    Ok(res)
+}
+
+pub fn bit_or<T>(result: Option<T>, input: Option<T>) -> Result<Option<T>>
+where
+    T: BitOr<Output = T> + Copy,
+{
+    let res = match (result, input) {
+        (None, _) => input,
+        (_, None) => result,
+        (Some(r), Some(i)) => Some(r | i),
+    };
+    Ok(res)
+}
+
 /// create table t(v1 int);
 /// insert into t values (null);
 /// select count(*) from t; gives 1.
@@ -13,11 +13,18 @@
 // limitations under the License.
 
 mod aggregator;
+mod approx_count_distinct;
+mod array_agg;
 mod count_star;
 mod functions;
 mod general_agg;
 mod general_distinct_agg;
 mod general_sorted_grouper;
+mod variance;
 
 pub use aggregator::{AggStateFactory, BoxedAggState};
+pub use approx_count_distinct::{
+    hll_estimate, hll_register_and_rank, HLL_MAX_RANK, HLL_NUM_REGISTERS,
+};
 pub use general_sorted_grouper::{create_sorted_grouper, BoxedSortedGrouper, EqGroups};
+pub use variance::{numeric_to_f64, VarianceState};
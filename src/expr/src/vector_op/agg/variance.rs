@@ -0,0 +1,258 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistical aggregates `var_samp`, `var_pop`, `stddev_samp` and `stddev_pop`.
+
+use num_traits::ToPrimitive;
+use risingwave_common::array::*;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::*;
+
+use crate::expr::AggKind;
+use crate::vector_op::agg::aggregator::Aggregator;
+use crate::vector_op::agg::general_sorted_grouper::EqGroups;
+
+/// Converts a numeric input of the statistical aggregates to `f64`, which is the type all of them
+/// compute and return in.
+pub fn numeric_to_f64(scalar: ScalarRefImpl<'_>) -> Result<f64> {
+    let value = match scalar {
+        ScalarRefImpl::Int16(v) => v as f64,
+        ScalarRefImpl::Int32(v) => v as f64,
+        ScalarRefImpl::Int64(v) => v as f64,
+        ScalarRefImpl::Float32(v) => v.0 as f64,
+        ScalarRefImpl::Float64(v) => v.0,
+        ScalarRefImpl::Decimal(v) => v
+            .to_f64()
+            .ok_or_else(|| ErrorCode::InternalError(format!("cannot convert {} to float64", v)))?,
+        other => {
+            return Err(ErrorCode::InternalError(format!(
+                "unsupported input of variance aggregation: {}",
+                other.get_ident()
+            ))
+            .into())
+        }
+    };
+    Ok(value)
+}
+
+/// The running state of the variance family, maintained with Welford's algorithm so that it stays
+/// numerically stable. Unlike the sum of squares, the state can also be rolled back value by
+/// value, which is what streaming aggregation needs to handle retractions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VarianceState {
+    count: i64,
+    mean: f64,
+    /// Sum of squared differences from the current mean.
+    m2: f64,
+}
+
+impl VarianceState {
+    pub fn new(count: i64, mean: f64, m2: f64) -> Self {
+        Self { count, mean, m2 }
+    }
+
+    pub fn count(&self) -> i64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn m2(&self) -> f64 {
+        self.m2
+    }
+
+    pub fn accumulate(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Removes a value which has been accumulated before.
+    pub fn retract(&mut self, value: f64) {
+        if self.count <= 1 {
+            // Start over from an exact zero state instead of carrying rounding errors.
+            *self = Self::default();
+            return;
+        }
+        let delta = value - self.mean;
+        self.count -= 1;
+        self.mean -= delta / self.count as f64;
+        self.m2 = (self.m2 - delta * (value - self.mean)).max(0.0);
+    }
+
+    /// Computes the result of `kind`. As in Postgres, the population statistics are null for no
+    /// input and the sample statistics are null for less than two inputs.
+    pub fn output(&self, kind: &AggKind) -> Option<f64> {
+        let variance = match kind {
+            AggKind::VarPop | AggKind::StddevPop if self.count > 0 => self.m2 / self.count as f64,
+            AggKind::VarSamp | AggKind::StddevSamp if self.count > 1 => {
+                self.m2 / (self.count - 1) as f64
+            }
+            _ => return None,
+        };
+        match kind {
+            AggKind::StddevPop | AggKind::StddevSamp => Some(variance.sqrt()),
+            _ => Some(variance),
+        }
+    }
+}
+
+/// Batch aggregator of `var_samp`, `var_pop`, `stddev_samp` and `stddev_pop` over any numeric
+/// input. Null inputs are ignored.
+pub struct VarianceAgg {
+    kind: AggKind,
+    return_type: DataType,
+    input_col_idx: usize,
+    state: VarianceState,
+}
+
+impl VarianceAgg {
+    pub fn new(kind: AggKind, return_type: DataType, input_col_idx: usize) -> Self {
+        Self {
+            kind,
+            return_type,
+            input_col_idx,
+            state: VarianceState::default(),
+        }
+    }
+
+    fn accumulate(&mut self, datum: DatumRef<'_>) -> Result<()> {
+        if let Some(scalar) = datum {
+            self.state.accumulate(numeric_to_f64(scalar)?);
+        }
+        Ok(())
+    }
+
+    fn output_datum(&self) -> Datum {
+        self.state
+            .output(&self.kind)
+            .map(|v| ScalarImpl::Float64(v.into()))
+    }
+}
+
+impl Aggregator for VarianceAgg {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn update_with_row(&mut self, input: &DataChunk, row_id: usize) -> Result<()> {
+        if let Some(visibility) = input.visibility() {
+            if !visibility.is_set(row_id)? {
+                return Ok(());
+            }
+        }
+        let array = input.column_at(self.input_col_idx).array_ref();
+        self.accumulate(array.value_at(row_id))
+    }
+
+    fn update(&mut self, input: &DataChunk) -> Result<()> {
+        for row_id in 0..input.capacity() {
+            self.update_with_row(input, row_id)?;
+        }
+        Ok(())
+    }
+
+    fn output(&self, builder: &mut ArrayBuilderImpl) -> Result<()> {
+        builder.append_datum(&self.output_datum())
+    }
+
+    fn update_and_output_with_sorted_groups(
+        &mut self,
+        input: &DataChunk,
+        builder: &mut ArrayBuilderImpl,
+        groups: &EqGroups,
+    ) -> Result<()> {
+        let array = input.column_at(self.input_col_idx).array_ref();
+        let mut groups_iter = groups.get_starting_indices().iter().peekable();
+        for row_id in 0..array.len() {
+            if groups_iter.peek() == Some(&&row_id) {
+                groups_iter.next();
+                builder.append_datum(&self.output_datum())?;
+                self.state = VarianceState::default();
+            }
+            self.accumulate(array.value_at(row_id))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use risingwave_common::array::column::Column;
+
+    use super::*;
+
+    fn eval(kind: AggKind, values: &[Option<i32>]) -> Result<Option<f64>> {
+        let input = I32Array::from_slice(values)?;
+        let chunk = DataChunk::builder()
+            .columns(vec![Column::new(Arc::new(input.into()))])
+            .build();
+        let mut agg = VarianceAgg::new(kind, DataType::Float64, 0);
+        let mut builder = DataType::Float64.create_array_builder(0)?;
+        agg.update(&chunk)?;
+        agg.output(&mut builder)?;
+        Ok(builder
+            .finish()?
+            .datum_at(0)
+            .map(|v| v.into_float64().into_inner()))
+    }
+
+    fn assert_approx_eq(actual: Option<f64>, expected: f64) {
+        assert!((actual.unwrap() - expected).abs() < 1e-9, "{:?}", actual);
+    }
+
+    #[test]
+    fn test_variance_agg() -> Result<()> {
+        let values = [
+            Some(2),
+            Some(4),
+            None,
+            Some(4),
+            Some(4),
+            Some(5),
+            Some(5),
+            Some(7),
+            Some(9),
+        ];
+        assert_approx_eq(eval(AggKind::VarPop, &values)?, 4.0);
+        assert_approx_eq(eval(AggKind::StddevPop, &values)?, 2.0);
+        assert_approx_eq(eval(AggKind::VarSamp, &values)?, 32.0 / 7.0);
+        assert_approx_eq(eval(AggKind::StddevSamp, &values)?, (32.0f64 / 7.0).sqrt());
+        assert_eq!(eval(AggKind::VarSamp, &[Some(1)])?, None);
+        assert_eq!(eval(AggKind::VarPop, &[Some(1)])?, Some(0.0));
+        assert_eq!(eval(AggKind::StddevPop, &[None])?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_variance_state_retract() {
+        let mut state = VarianceState::default();
+        for v in [1.0, 2.0, 3.0, 10.0] {
+            state.accumulate(v);
+        }
+        state.retract(10.0);
+        assert_eq!(state.count(), 3);
+        assert_approx_eq(state.output(&AggKind::VarSamp), 1.0);
+        for v in [1.0, 2.0, 3.0] {
+            state.retract(v);
+        }
+        assert_eq!(state, VarianceState::default());
+        assert_eq!(state.output(&AggKind::VarPop), None);
+    }
+}
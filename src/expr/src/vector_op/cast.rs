@@ -18,7 +18,9 @@ use std::str::FromStr;
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
 use num_traits::ToPrimitive;
-use risingwave_common::error::ErrorCode::{InternalError, InvalidInputSyntax, ParseError};
+use risingwave_common::error::ErrorCode::{
+    InternalError, InvalidInputSyntax, NumericValueOutOfRange, ParseError,
+};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::{
     Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, OrderedF32, OrderedF64,
@@ -162,6 +164,13 @@ pub fn dec_to_i64(elem: Decimal) -> Result<i64> {
     to_i64(elem.round_dp(0))
 }
 
+/// Sizing cast of `NUMERIC`: rounds to `scale` and fails if the value does not fit in `precision`.
+#[inline(always)]
+pub fn dec_to_typmod(elem: Decimal, precision: u32, scale: u32) -> Result<Decimal> {
+    elem.round_to_typmod(precision, scale)
+        .ok_or_else(|| NumericValueOutOfRange.into())
+}
+
 #[inline(always)]
 pub fn general_cast<T1, T2>(elem: T1) -> Result<T2>
 where
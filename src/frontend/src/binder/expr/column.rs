@@ -12,14 +12,99 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use risingwave_common::catalog::Field;
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_sqlparser::ast::Ident;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_sqlparser::ast::{Expr, Ident};
 
 use crate::binder::Binder;
-use crate::expr::{CorrelatedInputRef, ExprImpl, InputRef};
+use crate::expr::{
+    CorrelatedInputRef, Expr as _, ExprImpl, ExprType, FunctionCall, InputRef, Literal,
+};
 
 impl Binder {
     pub fn bind_column(&mut self, idents: &[Ident]) -> Result<ExprImpl> {
+        self.bind_column_with_field(idents).map(|(expr, _)| expr)
+    }
+
+    /// Binds `(column).field1.field2...`. The field names are resolved against the sub-fields of
+    /// the struct column, and each access becomes a `Field` call with the 0-based field index.
+    pub(super) fn bind_field_access(&mut self, expr: Expr, idents: Vec<Ident>) -> Result<ExprImpl> {
+        let (mut expr, mut field) = match expr {
+            Expr::Identifier(ident) => self.bind_column_with_field(&[ident])?,
+            Expr::CompoundIdentifier(idents) => self.bind_column_with_field(&idents)?,
+            expr => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("field access on expression {}", expr),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+        for ident in idents {
+            let index = field
+                .sub_fields
+                .iter()
+                .position(|f| f.name.rsplit('.').next() == Some(ident.value.as_str()))
+                .ok_or_else(|| {
+                    ErrorCode::BindError(format!(
+                        "column \"{}\" not found in data type {:?}",
+                        ident.value, field.data_type
+                    ))
+                })?;
+            field = field.sub_fields.swap_remove(index);
+            expr = FunctionCall::new_with_return_type(
+                ExprType::Field,
+                vec![
+                    expr,
+                    Literal::new(Some(ScalarImpl::Int32(index as i32)), DataType::Int32).into(),
+                ],
+                field.data_type.clone(),
+            )
+            .into();
+        }
+        Ok(expr)
+    }
+
+    /// Returns the output [`Field`] of a bound select item. Column references and field accesses
+    /// keep the sub-fields of their struct column, so that a query on a subquery can still resolve
+    /// `(subq.col).field`.
+    pub(in crate::binder) fn bind_output_field(&self, expr: &ExprImpl, name: String) -> Field {
+        let mut field = self
+            .output_struct_field(expr)
+            .unwrap_or_else(|| Field::with_name(expr.return_type(), ""));
+        field.name = name;
+        field
+    }
+
+    fn output_struct_field(&self, expr: &ExprImpl) -> Option<Field> {
+        match expr {
+            ExprImpl::InputRef(input_ref) => self
+                .context
+                .columns
+                .get(input_ref.index())
+                .map(|column| column.field.clone()),
+            ExprImpl::FunctionCall(call) if call.get_expr_type() == ExprType::Field => {
+                let [input, index] = call.inputs() else {
+                    return None;
+                };
+                let ExprImpl::Literal(index) = index else {
+                    return None;
+                };
+                let Some(ScalarImpl::Int32(index)) = index.get_data() else {
+                    return None;
+                };
+                let mut field = self.output_struct_field(input)?;
+                let index = *index as usize;
+                (index < field.sub_fields.len()).then(|| field.sub_fields.swap_remove(index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Binds a column, and also returns its [`Field`] so that accesses to the sub-fields of a
+    /// struct column can be resolved.
+    fn bind_column_with_field(&mut self, idents: &[Ident]) -> Result<(ExprImpl, Field)> {
         // TODO: check quote style of `ident`.
         let (_schema_name, table_name, column_name) = match idents {
             [column] => (None, None, &column.value),
@@ -34,7 +119,10 @@ impl Binder {
 
         if let Ok(index) = self.context.get_column_binding(table_name, column_name) {
             let column = &self.context.columns[index];
-            return Ok(InputRef::new(column.index, column.data_type.clone()).into());
+            return Ok((
+                InputRef::new(column.index, column.field.data_type.clone()).into(),
+                column.field.clone(),
+            ));
         }
 
         // Try to find a correlated column in `upper_contexts`, starting from the innermost context.
@@ -45,12 +133,15 @@ impl Binder {
             match context.get_column_binding(table_name, column_name) {
                 Ok(index) => {
                     let column = &context.columns[index];
-                    return Ok(CorrelatedInputRef::new(
-                        column.index,
-                        column.data_type.clone(),
-                        depth,
-                    )
-                    .into());
+                    return Ok((
+                        CorrelatedInputRef::new(
+                            column.index,
+                            column.field.data_type.clone(),
+                            depth,
+                        )
+                        .into(),
+                        column.field.clone(),
+                    ));
                 }
                 Err(e) => {
                     err = e;
@@ -40,11 +40,30 @@ impl Binder {
                 "min" => Some(AggKind::Min),
                 "max" => Some(AggKind::Max),
                 "avg" => Some(AggKind::Avg),
+                "array_agg" => Some(AggKind::ArrayAgg),
+                "stddev" | "stddev_samp" => Some(AggKind::StddevSamp),
+                "stddev_pop" => Some(AggKind::StddevPop),
+                "variance" | "var_samp" => Some(AggKind::VarSamp),
+                "var_pop" => Some(AggKind::VarPop),
+                "bool_and" | "every" => Some(AggKind::BoolAnd),
+                "bool_or" => Some(AggKind::BoolOr),
+                "bit_and" => Some(AggKind::BitAnd),
+                "bit_or" => Some(AggKind::BitOr),
+                "approx_count_distinct" => Some(AggKind::ApproxCountDistinct),
                 _ => None,
             };
             if let Some(kind) = agg_kind {
                 self.ensure_aggregate_allowed()?;
-                return Ok(ExprImpl::AggCall(Box::new(AggCall::new(kind, inputs)?)));
+                return Ok(ExprImpl::AggCall(Box::new(AggCall::new(
+                    kind, inputs, f.distinct,
+                )?)));
+            }
+            if f.distinct {
+                return Err(ErrorCode::InvalidInputSyntax(format!(
+                    "DISTINCT specified, but {} is not an aggregate function",
+                    function_name
+                ))
+                .into());
             }
             let function_type = match function_name.as_str() {
                 "substr" => ExprType::Substr,
@@ -68,6 +87,9 @@ impl Binder {
                     inputs = Self::rewrite_round_args(inputs);
                     ExprType::RoundDigit
                 }
+                "array_length" => {
+                    return Self::bind_array_length(inputs);
+                }
                 _ => {
                     return Err(ErrorCode::NotImplemented(
                         format!("unsupported function: {:?}", function_name),
@@ -101,6 +123,18 @@ impl Binder {
         .into()
     }
 
+    /// `array_length(anyarray) -> int`. The signature is not in the type inference map because the
+    /// argument is a parameterized list type.
+    fn bind_array_length(inputs: Vec<ExprImpl>) -> Result<ExprImpl> {
+        match inputs.as_slice() {
+            [input] if matches!(input.return_type(), DataType::List { .. }) => Ok(
+                FunctionCall::new_with_return_type(ExprType::ArrayLength, inputs, DataType::Int32)
+                    .into(),
+            ),
+            _ => Err(Self::err_unsupported_func("array_length", &inputs)),
+        }
+    }
+
     /// Rewrite the arguments to be consistent with the `round` signature:
     /// - round(Decimal, Int32) -> Decimal
     /// - round(Decimal) -> Decimal
@@ -108,7 +142,7 @@ impl Binder {
         if inputs.len() == 1 {
             // Rewrite round(Decimal) to round(Decimal, 0).
             let input = inputs.pop().unwrap();
-            if input.return_type() == DataType::Decimal {
+            if matches!(input.return_type(), DataType::Decimal { .. }) {
                 vec![input, Literal::new(Some(0.into()), DataType::Int32).into()]
             } else {
                 vec![input]
@@ -116,11 +150,16 @@ impl Binder {
         } else if inputs.len() == 2 {
             let digits = inputs.pop().unwrap();
             let input = inputs.pop().unwrap();
-            vec![
-                input
+            // A constrained numeric is accepted as is since `round` ignores its typmod.
+            let input = match input.return_type() {
+                DataType::Decimal { .. } => input,
+                _ => input
                     .clone()
-                    .cast_implicit(DataType::Decimal)
+                    .cast_implicit(DataType::UNCONSTRAINED_DECIMAL)
                     .unwrap_or(input),
+            };
+            vec![
+                input,
                 digits
                     .clone()
                     .cast_implicit(DataType::Int32)
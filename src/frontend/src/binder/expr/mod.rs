@@ -14,7 +14,7 @@
 
 use itertools::zip_eq;
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_common::types::DataType;
+use risingwave_common::types::{DataType, DECIMAL_MAX_PRECISION};
 use risingwave_sqlparser::ast::{
     BinaryOperator, DataType as AstDataType, DateTimeField, Expr, TrimWhereField, UnaryOperator,
 };
@@ -88,6 +88,10 @@ impl Binder {
                 self.bind_between(*expr, negated, *low, *high)?,
             ))),
             Expr::Extract { field, expr } => self.bind_extract(field, *expr),
+            Expr::Array(exprs) => self.bind_array(exprs),
+            Expr::MapAccess { column, keys } => self.bind_array_index(*column, keys),
+            Expr::Row(exprs) => self.bind_row(exprs),
+            Expr::FieldIdentifier(expr, idents) => self.bind_field_access(*expr, idents),
             _ => Err(ErrorCode::NotImplemented(
                 format!("unsupported expression {:?}", expr),
                 112.into(),
@@ -307,7 +311,29 @@ pub fn bind_data_type(data_type: &AstDataType) -> Result<DataType> {
         AstDataType::BigInt(None) => DataType::Int64,
         AstDataType::Real | AstDataType::Float(Some(1..=24)) => DataType::Float32,
         AstDataType::Double | AstDataType::Float(Some(25..=53) | None) => DataType::Float64,
-        AstDataType::Decimal(None, None) => DataType::Decimal,
+        AstDataType::Decimal(None, None) => DataType::UNCONSTRAINED_DECIMAL,
+        AstDataType::Decimal(Some(precision), scale) => {
+            let precision = *precision;
+            let scale = scale.unwrap_or(0);
+            if precision == 0 || precision > DECIMAL_MAX_PRECISION as u64 {
+                return Err(ErrorCode::BindError(format!(
+                    "NUMERIC precision {} must be between 1 and {}",
+                    precision, DECIMAL_MAX_PRECISION
+                ))
+                .into());
+            }
+            if scale > precision {
+                return Err(ErrorCode::BindError(format!(
+                    "NUMERIC scale {} must be between 0 and precision {}",
+                    scale, precision
+                ))
+                .into());
+            }
+            DataType::Decimal {
+                precision: Some(precision as u32),
+                scale: Some(scale as u32),
+            }
+        }
         AstDataType::Varchar(_) => DataType::Varchar,
         AstDataType::Date => DataType::Date,
         AstDataType::Time(false) => DataType::Time,
@@ -317,6 +343,13 @@ pub fn bind_data_type(data_type: &AstDataType) -> Result<DataType> {
         AstDataType::Array(datatype) => DataType::List {
             datatype: Box::new(bind_data_type(datatype)?),
         },
+        AstDataType::Struct(fields) => DataType::Struct {
+            fields: fields
+                .iter()
+                .map(|f| bind_data_type(&f.data_type))
+                .collect::<Result<Vec<_>>>()?
+                .into(),
+        },
         AstDataType::Char(..) => {
             return Err(ErrorCode::NotImplemented(
                 "CHAR is not supported, please use VARCHAR instead\n".to_string(),
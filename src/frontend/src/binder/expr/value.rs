@@ -14,10 +14,10 @@
 
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::{DataType, Decimal, IntervalUnit, ScalarImpl};
-use risingwave_sqlparser::ast::{DateTimeField, Value};
+use risingwave_sqlparser::ast::{DateTimeField, Expr, Value};
 
 use crate::binder::Binder;
-use crate::expr::Literal;
+use crate::expr::{least_restrictive, Expr as _, ExprImpl, ExprType, FunctionCall, Literal};
 
 impl Binder {
     pub(super) fn bind_value(&mut self, value: Value) -> Result<Literal> {
@@ -58,7 +58,10 @@ impl Binder {
             let decimal = s
                 .parse::<Decimal>()
                 .map_err(|e| ErrorCode::ParseError(Box::new(e)))?;
-            (Some(ScalarImpl::Decimal(decimal)), DataType::Decimal)
+            (
+                Some(ScalarImpl::Decimal(decimal)),
+                DataType::UNCONSTRAINED_DECIMAL,
+            )
         };
         Ok(Literal::new(data, data_type))
     }
@@ -114,6 +117,83 @@ impl Binder {
 
         Ok(literal)
     }
+
+    /// Binds `ARRAY[e1, e2, ...]`. The element type is the least restrictive type of all the
+    /// non-null elements, and falls back to `varchar` when there is no such element.
+    pub(super) fn bind_array(&mut self, exprs: Vec<Expr>) -> Result<ExprImpl> {
+        if exprs.is_empty() {
+            return Err(ErrorCode::BindError("cannot determine type of empty array".into()).into());
+        }
+        let exprs = exprs
+            .into_iter()
+            .map(|e| self.bind_expr(e))
+            .collect::<Result<Vec<ExprImpl>>>()?;
+        let element_type = exprs
+            .iter()
+            .filter(|e| !e.is_null())
+            .map(|e| e.return_type())
+            .try_fold(None, |acc, ty| -> Result<_> {
+                Ok(Some(match acc {
+                    Some(acc) => least_restrictive(acc, ty)?,
+                    None => ty,
+                }))
+            })?
+            .unwrap_or(DataType::Varchar);
+        let exprs = exprs
+            .into_iter()
+            .map(|e| e.cast_implicit(element_type.clone()))
+            .collect::<Result<Vec<ExprImpl>>>()?;
+        Ok(FunctionCall::new_with_return_type(
+            ExprType::Array,
+            exprs,
+            DataType::List {
+                datatype: Box::new(element_type),
+            },
+        )
+        .into())
+    }
+
+    /// Binds `ROW(e1, e2, ...)` into an anonymous struct whose field types are those of the
+    /// elements.
+    pub(super) fn bind_row(&mut self, exprs: Vec<Expr>) -> Result<ExprImpl> {
+        let exprs = exprs
+            .into_iter()
+            .map(|e| self.bind_expr(e))
+            .collect::<Result<Vec<ExprImpl>>>()?;
+        let return_type = DataType::Struct {
+            fields: exprs
+                .iter()
+                .map(|e| e.return_type())
+                .collect::<Vec<_>>()
+                .into(),
+        };
+        Ok(FunctionCall::new_with_return_type(ExprType::Row, exprs, return_type).into())
+    }
+
+    /// Binds `array[index1][index2]...`. Each subscript takes one dimension off the array type.
+    pub(super) fn bind_array_index(&mut self, array: Expr, indices: Vec<Expr>) -> Result<ExprImpl> {
+        let mut array = self.bind_expr(array)?;
+        for index in indices {
+            let return_type = match array.return_type() {
+                DataType::List { datatype } => *datatype,
+                ty => {
+                    return Err(ErrorCode::BindError(format!(
+                        "cannot subscript type {:?} because it is not an array",
+                        ty
+                    ))
+                    .into())
+                }
+            };
+            let index = self.bind_expr(index)?.cast_implicit(DataType::Int32)?;
+            array = FunctionCall::new_with_return_type(
+                ExprType::ArrayAccess,
+                vec![array, index],
+                return_type,
+            )
+            .into();
+        }
+        Ok(array)
+    }
 }
 
 #[cfg(test)]
@@ -151,10 +231,10 @@ mod tests {
         let data_type = vec![
             DataType::Int32,
             DataType::Int64,
-            DataType::Decimal,
-            DataType::Decimal,
-            DataType::Decimal,
-            DataType::Decimal,
+            DataType::UNCONSTRAINED_DECIMAL,
+            DataType::UNCONSTRAINED_DECIMAL,
+            DataType::UNCONSTRAINED_DECIMAL,
+            DataType::UNCONSTRAINED_DECIMAL,
         ];
 
         for i in 0..values.len() {
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use risingwave_common::error::Result;
+use risingwave_pb::meta::GetStreamingStatusResponse;
 use risingwave_sqlparser::ast::Statement;
 
 mod bind_context;
@@ -24,6 +25,7 @@ mod relation;
 mod select;
 mod set_expr;
 mod statement;
+mod table_function;
 mod values;
 mod window_table_function;
 
@@ -31,7 +33,9 @@ pub use bind_context::BindContext;
 pub use delete::BoundDelete;
 pub use insert::BoundInsert;
 pub use query::BoundQuery;
-pub use relation::{BoundBaseTable, BoundJoin, BoundSource, BoundTableSource, Relation};
+pub use relation::{
+    BoundBaseTable, BoundJoin, BoundSource, BoundSystemTable, BoundTableSource, Relation,
+};
 pub use select::BoundSelect;
 pub use set_expr::BoundSetExpr;
 pub use statement::BoundStatement;
@@ -52,6 +56,13 @@ pub struct Binder {
     upper_contexts: Vec<BindContext>,
 
     next_subquery_id: usize,
+
+    /// The runtime status of the streaming jobs, to generate the system tables of `rw_catalog`.
+    streaming_status: Option<GetStreamingStatusResponse>,
+    /// Whether any system table generated from `streaming_status` has been bound.
+    requires_streaming_status: bool,
+    /// Whether the bound query is for a streaming job, which can't read system tables.
+    in_streaming: bool,
 }
 
 impl Binder {
@@ -62,9 +73,31 @@ impl Binder {
             context: BindContext::new(),
             upper_contexts: vec![],
             next_subquery_id: 0,
+            streaming_status: None,
+            requires_streaming_status: false,
+            in_streaming: false,
         }
     }
 
+    /// Bind a query of a streaming job, e.g. a materialized view, which rejects system tables.
+    pub fn for_stream(mut self) -> Self {
+        self.in_streaming = true;
+        self
+    }
+
+    /// Bind the system tables of `rw_catalog` with the rows generated from `streaming_status`.
+    /// Without it, they are bound as empty tables.
+    pub fn with_streaming_status(mut self, streaming_status: GetStreamingStatusResponse) -> Self {
+        self.streaming_status = Some(streaming_status);
+        self
+    }
+
+    /// Whether any system table of `rw_catalog` has been bound, whose rows are generated from the
+    /// runtime status of the streaming jobs.
+    pub fn requires_streaming_status(&self) -> bool {
+        self.requires_streaming_status
+    }
+
     /// Bind a [`Statement`].
     pub fn bind(&mut self, stmt: Statement) -> Result<BoundStatement> {
         self.bind_statement(stmt)
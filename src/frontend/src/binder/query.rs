@@ -14,6 +14,7 @@
 
 use std::collections::HashMap;
 
+use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
 use risingwave_sqlparser::ast::{Expr, OrderByExpr, Query};
@@ -32,6 +33,11 @@ pub struct BoundQuery {
 }
 
 impl BoundQuery {
+    /// The schema returned by this [`BoundQuery`].
+    pub fn schema(&self) -> &Schema {
+        self.body.schema()
+    }
+
     /// The names returned by this [`BoundQuery`].
     pub fn names(&self) -> Vec<String> {
         self.body.names()
@@ -15,9 +15,9 @@
 use std::collections::hash_map::Entry;
 use std::str::FromStr;
 
-use itertools::Itertools;
-use risingwave_common::catalog::{ColumnDesc, DEFAULT_SCHEMA_NAME};
-use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::array::Row;
+use risingwave_common::catalog::{ColumnDesc, Field, DEFAULT_SCHEMA_NAME};
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
 use risingwave_pb::plan::JoinType;
 use risingwave_sqlparser::ast::{
@@ -28,9 +28,10 @@ use super::bind_context::ColumnBinding;
 use super::{BoundQuery, BoundWindowTableFunction, WindowTableFunctionKind, UNNAMED_SUBQUERY};
 use crate::binder::Binder;
 use crate::catalog::source_catalog::SourceCatalog;
+use crate::catalog::system_catalog::{get_sys_catalog, is_system_schema, PG_CATALOG_SCHEMA_NAME};
 use crate::catalog::table_catalog::TableCatalog;
 use crate::catalog::{CatalogError, TableId};
-use crate::expr::{Expr, ExprImpl};
+use crate::expr::{Expr, ExprImpl, TableFunction, TableFunctionType};
 
 /// A validated item that refers to a table-like entity, including base table, subquery, join, etc.
 /// It is usually part of the `from` clause.
@@ -38,9 +39,11 @@ use crate::expr::{Expr, ExprImpl};
 pub enum Relation {
     Source(Box<BoundSource>),
     BaseTable(Box<BoundBaseTable>),
+    SystemTable(Box<BoundSystemTable>),
     Subquery(Box<BoundSubquery>),
     Join(Box<BoundJoin>),
     WindowTableFunction(Box<BoundWindowTableFunction>),
+    TableFunction(Box<TableFunction>),
 }
 
 #[derive(Debug)]
@@ -68,6 +71,15 @@ impl From<&TableCatalog> for BoundBaseTable {
     }
 }
 
+/// `BoundSystemTable` is a system table of `pg_catalog`, `information_schema` or `rw_catalog`, with
+/// its rows generated during binding.
+#[derive(Debug)]
+pub struct BoundSystemTable {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub rows: Vec<Row>,
+}
+
 #[derive(Debug)]
 pub struct BoundSubquery {
     pub query: BoundQuery,
@@ -169,6 +181,8 @@ impl Binder {
                 if args.is_empty() {
                     let (schema_name, table_name) = Self::resolve_table_name(name)?;
                     self.bind_table_or_source(&schema_name, &table_name, alias)
+                } else if let Ok(function_type) = TableFunctionType::from_str(&name.0[0].value) {
+                    self.bind_table_function(function_type, args, alias)
                 } else {
                     let kind =
                         WindowTableFunctionKind::from_str(&name.0[0].value).map_err(|_| {
@@ -209,23 +223,11 @@ impl Binder {
         table_name: &str,
         alias: Option<TableAlias>,
     ) -> Result<Relation> {
-        if schema_name == "pg_catalog" {
-            // TODO: support pg_catalog.
-            return Err(ErrorCode::NotImplemented(
-                // TODO: We can ref the document of `SHOW` commands here if ready.
-                r###"pg_catalog is not supported, please use `SHOW` commands for now.
-`SHOW TABLES`,
-`SHOW MATERIALIZED VIEWS`,
-`DESCRIBE <table>`,
-`SHOW COLUMNS FROM [table]`
-"###
-                .into(),
-                1695.into(),
-            )
-            .into());
+        if is_system_schema(schema_name) {
+            return self.bind_sys_table(schema_name, table_name, alias);
         }
 
-        let (ret, columns) = {
+        let relation = {
             let catalog = &self.catalog;
 
             catalog
@@ -234,30 +236,65 @@ impl Binder {
                 .or_else(|_| {
                     catalog
                         .get_source_by_name(&self.db_name, schema_name, table_name)
-                        .map(|s| {
-                            let source = s.clone().flatten();
-                            (Relation::Source(Box::new((&source).into())), source.columns)
-                        })
+                        .map(|s| (Relation::Source(Box::new(s.into())), s.columns.clone()))
                 })
-                .map_err(|_| {
-                    RwError::from(CatalogError::NotFound(
-                        "table or source",
-                        table_name.to_string(),
-                    ))
-                })?
+        };
+        let (ret, columns) = match relation {
+            Ok(relation) => relation,
+            // Like the search path of Postgres, resolve the unknown relation in `pg_catalog`.
+            Err(_) if get_sys_catalog(PG_CATALOG_SCHEMA_NAME, table_name).is_some() => {
+                return self.bind_sys_table(PG_CATALOG_SCHEMA_NAME, table_name, alias);
+            }
+            Err(_) => {
+                return Err(
+                    CatalogError::NotFound("table or source", table_name.to_string()).into(),
+                );
+            }
         };
 
         self.bind_context(
             columns
                 .iter()
-                .cloned()
-                .map(|c| (c.name().to_string(), c.data_type().clone(), c.is_hidden)),
+                .map(|c| (c.is_hidden, Field::from(&c.column_desc))),
             table_name.to_string(),
             alias,
         )?;
         Ok(ret)
     }
 
+    fn bind_sys_table(
+        &mut self,
+        schema_name: &str,
+        table_name: &str,
+        alias: Option<TableAlias>,
+    ) -> Result<Relation> {
+        let sys_catalog = get_sys_catalog(schema_name, table_name).ok_or_else(|| {
+            CatalogError::NotFound("system table", format!("{}.{}", schema_name, table_name))
+        })?;
+        if self.in_streaming {
+            return Err(ErrorCode::NotImplemented(
+                format!("streaming over system table {}.{}", schema_name, table_name),
+                None.into(),
+            )
+            .into());
+        }
+        self.requires_streaming_status |= sys_catalog.requires_streaming_status();
+        let rows =
+            sys_catalog.rows(&self.catalog, &self.db_name, self.streaming_status.as_ref())?;
+        let fields = sys_catalog.fields();
+
+        self.bind_context(
+            fields.iter().map(|f| (false, f.clone())),
+            table_name.to_string(),
+            alias,
+        )?;
+        Ok(Relation::SystemTable(Box::new(BoundSystemTable {
+            name: table_name.to_string(),
+            fields,
+            rows,
+        })))
+    }
+
     pub(super) fn bind_table(
         &mut self,
         schema_name: &str,
@@ -273,8 +310,7 @@ impl Binder {
         self.bind_context(
             columns
                 .iter()
-                .cloned()
-                .map(|c| (c.name().to_string(), c.data_type().clone(), c.is_hidden)),
+                .map(|c| (c.is_hidden, Field::from(&c.column_desc))),
             table_name.to_string(),
             alias,
         )?;
@@ -330,7 +366,7 @@ impl Binder {
     /// Fill the [`BindContext`](super::BindContext) for table.
     pub(super) fn bind_context(
         &mut self,
-        columns: impl IntoIterator<Item = (String, DataType, bool)>,
+        columns: impl IntoIterator<Item = (bool, Field)>,
         table_name: String,
         alias: Option<TableAlias>,
     ) -> Result<()> {
@@ -346,17 +382,20 @@ impl Binder {
         columns
             .into_iter()
             .enumerate()
-            .for_each(|(index, (name, data_type, is_hidden))| {
+            .for_each(|(index, (is_hidden, mut field))| {
                 let name = match is_hidden {
-                    true => name,
-                    false => alias_iter.next().map(|t| t.value).unwrap_or(name),
+                    true => field.name.clone(),
+                    false => alias_iter
+                        .next()
+                        .map(|t| t.value)
+                        .unwrap_or_else(|| field.name.clone()),
                 };
+                field.name = name.clone();
                 self.context.columns.push(ColumnBinding::new(
                     table_name.clone(),
-                    name.clone(),
                     begin + index,
-                    data_type,
                     is_hidden,
+                    field,
                 ));
                 self.context
                     .indexs_of
@@ -397,10 +436,11 @@ impl Binder {
         let sub_query_id = self.next_subquery_id();
         self.bind_context(
             query
-                .names()
-                .into_iter()
-                .zip_eq(query.data_types().into_iter())
-                .map(|(x, y)| (x, y, false)),
+                .schema()
+                .fields()
+                .iter()
+                .cloned()
+                .map(|field| (false, field)),
             format!("{}_{}", UNNAMED_SUBQUERY, sub_query_id),
             alias,
         )?;
@@ -15,6 +15,7 @@
 use std::fmt::Debug;
 
 use itertools::Itertools;
+use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
 use risingwave_sqlparser::ast::{Expr, Select, SelectItem};
@@ -33,24 +34,24 @@ pub struct BoundSelect {
     pub from: Option<Relation>,
     pub where_clause: Option<ExprImpl>,
     pub group_by: Vec<ExprImpl>,
+    /// The output fields. The sub-fields of struct columns are kept so that a query on this
+    /// select can still resolve field accesses like `(col).field`.
+    pub schema: Schema,
 }
 
 impl BoundSelect {
     /// The names returned by this [`BoundSelect`].
     pub fn names(&self) -> Vec<String> {
-        self.aliases
+        self.schema
+            .fields()
             .iter()
-            .cloned()
-            .map(|alias| alias.unwrap_or_else(|| UNNAMED_COLUMN.to_string()))
+            .map(|f| f.name.clone())
             .collect()
     }
 
     /// The types returned by this [`BoundSelect`].
     pub fn data_types(&self) -> Vec<DataType> {
-        self.select_items
-            .iter()
-            .map(|item| item.return_type())
-            .collect()
+        self.schema.data_types()
     }
 
     pub fn is_correlated(&self) -> bool {
@@ -96,6 +97,15 @@ impl Binder {
         // Bind SELECT clause.
         let (select_items, aliases) = self.bind_project(select.projection)?;
 
+        let fields = select_items
+            .iter()
+            .zip_eq(aliases.iter())
+            .map(|(item, alias)| {
+                let name = alias.clone().unwrap_or_else(|| UNNAMED_COLUMN.to_string());
+                self.bind_output_field(item, name)
+            })
+            .collect();
+
         Ok(BoundSelect {
             distinct: select.distinct,
             select_items,
@@ -103,6 +113,7 @@ impl Binder {
             from,
             where_clause: selection,
             group_by,
+            schema: Schema::new(fields),
         })
     }
 
@@ -158,8 +169,8 @@ impl Binder {
             .iter()
             .map(|column| {
                 (
-                    InputRef::new(column.index, column.data_type.clone()).into(),
-                    Some(column.column_name.clone()),
+                    InputRef::new(column.index, column.field.data_type.clone()).into(),
+                    Some(column.field.name.clone()),
                 )
             })
             .unzip();
@@ -174,8 +185,8 @@ impl Binder {
             .filter_map(|column| {
                 if !column.is_hidden {
                     Some((
-                        InputRef::new(column.index, column.data_type.clone()).into(),
-                        Some(column.column_name.clone()),
+                        InputRef::new(column.index, column.field.data_type.clone()).into(),
+                        Some(column.field.name.clone()),
                     ))
                 } else {
                     None
@@ -0,0 +1,76 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use risingwave_common::catalog::Field;
+use risingwave_common::error::ErrorCode;
+use risingwave_sqlparser::ast::{FunctionArg, TableAlias};
+
+use super::{Binder, Relation, Result};
+use crate::expr::{ExprImpl, TableFunction, TableFunctionType};
+
+impl Binder {
+    /// Binds a set-returning function in the `FROM` clause, e.g. `unnest(array[1, 2]) AS t(x)`.
+    ///
+    /// Lateral references are not supported yet, so the arguments must be constant.
+    pub(super) fn bind_table_function(
+        &mut self,
+        function_type: TableFunctionType,
+        args: Vec<FunctionArg>,
+        alias: Option<TableAlias>,
+    ) -> Result<Relation> {
+        if self.in_streaming {
+            return Err(ErrorCode::NotImplemented(
+                format!("streaming over table function {}", function_type.name()),
+                None.into(),
+            )
+            .into());
+        }
+        self.push_context();
+        let args: Vec<ExprImpl> = args
+            .into_iter()
+            .map(|arg| self.bind_function_arg(arg))
+            .flatten_ok()
+            .try_collect();
+        self.pop_context();
+        let args = args?;
+
+        if args.iter().any(|arg| {
+            arg.has_input_ref()
+                || arg.has_correlated_input_ref()
+                || arg.has_subquery()
+                || arg.has_agg_call()
+        }) {
+            return Err(ErrorCode::NotImplemented(
+                format!(
+                    "non-constant arguments of table function {}",
+                    function_type.name()
+                ),
+                None.into(),
+            )
+            .into());
+        }
+
+        let table_function = TableFunction::new(function_type, args)?;
+        let columns = [(
+            false,
+            Field::with_name(
+                table_function.return_type.clone(),
+                function_type.name().to_string(),
+            ),
+        )];
+        self.bind_context(columns, function_type.name().to_string(), alias)?;
+        Ok(Relation::TableFunction(Box::new(table_function)))
+    }
+}
@@ -114,7 +114,7 @@ mod tests {
         let values = Values(vec![vec![expr1], vec![expr2]]);
         let res = binder.bind_values(values, None).unwrap();
 
-        let types = vec![DataType::Decimal];
+        let types = vec![DataType::UNCONSTRAINED_DECIMAL];
         let schema = Schema::new(types.into_iter().map(Field::unnamed).collect());
 
         assert_eq!(res.schema, schema);
@@ -22,7 +22,6 @@ use crate::catalog::{DatabaseId, SchemaId};
 #[derive(Clone, Debug)]
 pub struct DatabaseCatalog {
     id: DatabaseId,
-    #[allow(dead_code)]
     name: String,
     schema_by_name: HashMap<String, SchemaCatalog>,
     schema_name_by_id: HashMap<SchemaId, String>,
@@ -48,6 +47,13 @@ impl DatabaseCatalog {
         self.schema_by_name.keys().cloned().collect_vec()
     }
 
+    /// Iterate all schemas, ordered by id.
+    pub fn iter_schemas(&self) -> impl Iterator<Item = &SchemaCatalog> {
+        self.schema_by_name
+            .values()
+            .sorted_by_key(|schema| schema.id())
+    }
+
     pub fn get_schema_by_name(&self, name: &str) -> Option<&SchemaCatalog> {
         self.schema_by_name.get(name)
     }
@@ -60,6 +66,10 @@ impl DatabaseCatalog {
     pub fn id(&self) -> DatabaseId {
         self.id
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 }
 impl From<&ProstDatabase> for DatabaseCatalog {
     fn from(db: &ProstDatabase) -> Self {
@@ -23,6 +23,7 @@ pub(crate) mod database_catalog;
 pub(crate) mod root_catalog;
 pub(crate) mod schema_catalog;
 pub(crate) mod source_catalog;
+pub(crate) mod system_catalog;
 pub(crate) mod table_catalog;
 
 #[allow(dead_code)]
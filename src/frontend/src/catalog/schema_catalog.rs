@@ -26,7 +26,6 @@ use crate::catalog::SchemaId;
 #[derive(Clone, Debug)]
 pub struct SchemaCatalog {
     id: SchemaId,
-    #[allow(dead_code)]
     name: String,
     table_by_name: HashMap<String, TableCatalog>,
     table_name_by_id: HashMap<TableId, String>,
@@ -108,6 +107,10 @@ impl SchemaCatalog {
     pub fn id(&self) -> SchemaId {
         self.id
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 }
 
 impl From<&ProstSchema> for SchemaCatalog {
@@ -0,0 +1,94 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::array::Row;
+use risingwave_common::types::{DataType, ScalarImpl};
+
+use super::pg_catalog::pg_type_of;
+use super::{list_relations, RelationKind, SysRows, SystemCatalog, INFORMATION_SCHEMA_SCHEMA_NAME};
+use crate::catalog::database_catalog::DatabaseCatalog;
+
+fn table_type(kind: RelationKind) -> &'static str {
+    match kind {
+        RelationKind::Table => "BASE TABLE",
+        RelationKind::MaterializedView => "VIEW",
+        RelationKind::Source => "FOREIGN",
+    }
+}
+
+pub(super) const TABLES: SystemCatalog = SystemCatalog {
+    schema_name: INFORMATION_SCHEMA_SCHEMA_NAME,
+    name: "tables",
+    columns: &[
+        (DataType::Varchar, "table_catalog"),
+        (DataType::Varchar, "table_schema"),
+        (DataType::Varchar, "table_name"),
+        (DataType::Varchar, "table_type"),
+    ],
+    rows: SysRows::Catalog(tables_rows),
+};
+
+fn tables_rows(database: &DatabaseCatalog) -> Vec<Row> {
+    list_relations(database)
+        .iter()
+        .map(|relation| {
+            Row(vec![
+                Some(ScalarImpl::Utf8(database.name().to_string())),
+                Some(ScalarImpl::Utf8(relation.schema.name().to_string())),
+                Some(ScalarImpl::Utf8(relation.name.to_string())),
+                Some(ScalarImpl::Utf8(table_type(relation.kind).to_string())),
+            ])
+        })
+        .collect()
+}
+
+pub(super) const COLUMNS: SystemCatalog = SystemCatalog {
+    schema_name: INFORMATION_SCHEMA_SCHEMA_NAME,
+    name: "columns",
+    columns: &[
+        (DataType::Varchar, "table_catalog"),
+        (DataType::Varchar, "table_schema"),
+        (DataType::Varchar, "table_name"),
+        (DataType::Varchar, "column_name"),
+        (DataType::Int32, "ordinal_position"),
+        (DataType::Varchar, "is_nullable"),
+        (DataType::Varchar, "data_type"),
+    ],
+    rows: SysRows::Catalog(columns_rows),
+};
+
+fn columns_rows(database: &DatabaseCatalog) -> Vec<Row> {
+    list_relations(database)
+        .iter()
+        .flat_map(|relation| {
+            relation
+                .visible_columns()
+                .enumerate()
+                .map(|(idx, column)| {
+                    Row(vec![
+                        Some(ScalarImpl::Utf8(database.name().to_string())),
+                        Some(ScalarImpl::Utf8(relation.schema.name().to_string())),
+                        Some(ScalarImpl::Utf8(relation.name.to_string())),
+                        Some(ScalarImpl::Utf8(column.name().to_string())),
+                        Some(ScalarImpl::Int32(idx as i32 + 1)),
+                        Some(ScalarImpl::Utf8("YES".to_string())),
+                        Some(ScalarImpl::Utf8(
+                            pg_type_of(column.data_type()).2.to_string(),
+                        )),
+                    ])
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
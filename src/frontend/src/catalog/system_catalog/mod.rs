@@ -0,0 +1,300 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read-only system tables of `pg_catalog`, `information_schema` and `rw_catalog`. They have no
+//! storage: their rows are generated when a query is bound, and scanned by the batch
+//! `SysRowSeqScan` executor. The tables of `rw_catalog` are generated from the runtime status of
+//! the streaming jobs fetched from meta, and the others from the in-memory catalog of the frontend.
+
+mod information_schema;
+mod pg_catalog;
+mod rw_catalog;
+
+use itertools::Itertools;
+use risingwave_common::array::Row;
+use risingwave_common::catalog::Field;
+use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
+use risingwave_pb::meta::GetStreamingStatusResponse;
+
+use super::column_catalog::ColumnCatalog;
+use super::database_catalog::DatabaseCatalog;
+use super::root_catalog::Catalog;
+use super::schema_catalog::SchemaCatalog;
+
+pub const PG_CATALOG_SCHEMA_NAME: &str = "pg_catalog";
+pub const INFORMATION_SCHEMA_SCHEMA_NAME: &str = "information_schema";
+pub const RW_CATALOG_SCHEMA_NAME: &str = "rw_catalog";
+
+/// Where the rows of a system table are generated from.
+enum SysRows {
+    /// The catalog of the current database.
+    Catalog(fn(&DatabaseCatalog) -> Vec<Row>),
+    /// The runtime status of the streaming jobs.
+    Streaming(fn(&GetStreamingStatusResponse) -> Vec<Row>),
+}
+
+/// `SystemCatalog` is a virtual table whose rows are generated from the catalog of a database, or
+/// the runtime status of the streaming jobs.
+pub struct SystemCatalog {
+    pub schema_name: &'static str,
+    pub name: &'static str,
+    pub columns: &'static [(DataType, &'static str)],
+    rows: SysRows,
+}
+
+impl SystemCatalog {
+    /// Get the fields of the system table.
+    pub fn fields(&self) -> Vec<Field> {
+        self.columns
+            .iter()
+            .map(|(data_type, name)| Field::with_name(data_type.clone(), *name))
+            .collect()
+    }
+
+    /// Whether the rows are generated from the runtime status of the streaming jobs, which has to
+    /// be fetched from meta.
+    pub fn requires_streaming_status(&self) -> bool {
+        matches!(self.rows, SysRows::Streaming(_))
+    }
+
+    /// Generate the rows of the system table in database `db_name`. The rows generated from the
+    /// runtime status of the streaming jobs are empty if `streaming_status` is not given.
+    pub fn rows(
+        &self,
+        catalog: &Catalog,
+        db_name: &str,
+        streaming_status: Option<&GetStreamingStatusResponse>,
+    ) -> Result<Vec<Row>> {
+        let database = catalog.get_database_by_name(db_name)?;
+        let rows = match self.rows {
+            SysRows::Catalog(rows) => rows(database),
+            SysRows::Streaming(rows) => streaming_status.map(rows).unwrap_or_default(),
+        };
+        Ok(rows)
+    }
+}
+
+static SYSTEM_CATALOGS: [SystemCatalog; 11] = [
+    pg_catalog::PG_NAMESPACE,
+    pg_catalog::PG_CLASS,
+    pg_catalog::PG_ATTRIBUTE,
+    pg_catalog::PG_TYPE,
+    information_schema::TABLES,
+    information_schema::COLUMNS,
+    rw_catalog::RW_FRAGMENTS,
+    rw_catalog::RW_ACTORS,
+    rw_catalog::RW_SOURCE_SPLITS,
+    rw_catalog::RW_EPOCH,
+    rw_catalog::RW_BARRIERS,
+];
+
+/// Whether `schema_name` is a schema of system tables.
+pub fn is_system_schema(schema_name: &str) -> bool {
+    schema_name == PG_CATALOG_SCHEMA_NAME
+        || schema_name == INFORMATION_SCHEMA_SCHEMA_NAME
+        || schema_name == RW_CATALOG_SCHEMA_NAME
+}
+
+/// Get the system table `schema_name.table_name`.
+pub fn get_sys_catalog(schema_name: &str, table_name: &str) -> Option<&'static SystemCatalog> {
+    SYSTEM_CATALOGS.iter().find(|sys_catalog| {
+        sys_catalog.schema_name == schema_name && sys_catalog.name == table_name
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RelationKind {
+    Table,
+    MaterializedView,
+    Source,
+}
+
+/// A user relation listed in the system tables.
+struct Relation<'a> {
+    schema: &'a SchemaCatalog,
+    id: u32,
+    name: &'a str,
+    kind: RelationKind,
+    columns: &'a [ColumnCatalog],
+}
+
+impl Relation<'_> {
+    /// The visible columns of the relation, i.e. excluding the hidden row id column.
+    fn visible_columns(&self) -> impl Iterator<Item = &ColumnCatalog> {
+        self.columns.iter().filter(|column| !column.is_hidden())
+    }
+}
+
+/// Get all the tables, materialized views and sources in the database, ordered by id.
+fn list_relations(database: &DatabaseCatalog) -> Vec<Relation<'_>> {
+    database
+        .iter_schemas()
+        .flat_map(|schema| {
+            let tables = schema.iter_table().map(move |table| Relation {
+                schema,
+                id: table.id().table_id,
+                name: table.name(),
+                kind: RelationKind::Table,
+                columns: table.columns(),
+            });
+            let mvs = schema.iter_mv().map(move |mv| Relation {
+                schema,
+                id: mv.id().table_id,
+                name: mv.name(),
+                kind: RelationKind::MaterializedView,
+                columns: mv.columns(),
+            });
+            // Materialized sources are listed as tables.
+            let sources = schema
+                .iter_source()
+                .filter(move |source| schema.get_table_by_name(&source.name).is_none())
+                .map(move |source| Relation {
+                    schema,
+                    id: source.id,
+                    name: &source.name,
+                    kind: RelationKind::Source,
+                    columns: &source.columns,
+                });
+            tables.chain(mvs).chain(sources)
+        })
+        .sorted_by_key(|relation| relation.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::Row;
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+    use risingwave_common::types::ScalarImpl;
+    use risingwave_pb::common::HostAddress;
+    use risingwave_pb::meta::get_streaming_status_response::Actor;
+    use risingwave_pb::meta::table_fragments::ActorState;
+
+    use super::*;
+    use crate::test_utils::LocalFrontend;
+
+    fn sys_rows(frontend: &LocalFrontend, schema_name: &str, table_name: &str) -> Vec<Row> {
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        get_sys_catalog(schema_name, table_name)
+            .unwrap()
+            .rows(&catalog_reader, DEFAULT_DATABASE_NAME, None)
+            .unwrap()
+    }
+
+    fn utf8(s: &str) -> Option<ScalarImpl> {
+        Some(ScalarImpl::Utf8(s.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_sys_catalog_rows() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 varchar)")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create materialized view mv as select v1 from t")
+            .await
+            .unwrap();
+
+        assert!(get_sys_catalog(PG_CATALOG_SCHEMA_NAME, "pg_tables").is_none());
+        assert!(get_sys_catalog(DEFAULT_SCHEMA_NAME, "pg_class").is_none());
+
+        let namespaces = sys_rows(&frontend, PG_CATALOG_SCHEMA_NAME, "pg_namespace");
+        assert!(namespaces
+            .iter()
+            .any(|row| row[1] == utf8(DEFAULT_SCHEMA_NAME)));
+
+        let classes = sys_rows(&frontend, PG_CATALOG_SCHEMA_NAME, "pg_class")
+            .into_iter()
+            .map(|row| (row[1].clone(), row[3].clone(), row[4].clone()))
+            .collect_vec();
+        assert_eq!(
+            classes,
+            vec![
+                (utf8("t"), utf8("r"), Some(ScalarImpl::Int16(2))),
+                (utf8("mv"), utf8("m"), Some(ScalarImpl::Int16(1))),
+            ]
+        );
+
+        let columns = sys_rows(&frontend, INFORMATION_SCHEMA_SCHEMA_NAME, "columns")
+            .into_iter()
+            .map(|row| (row[2].clone(), row[3].clone(), row[6].clone()))
+            .collect_vec();
+        assert_eq!(
+            columns,
+            vec![
+                (utf8("t"), utf8("v1"), utf8("integer")),
+                (utf8("t"), utf8("v2"), utf8("character varying")),
+                (utf8("mv"), utf8("v1"), utf8("integer")),
+            ]
+        );
+    }
+    #[tokio::test]
+    async fn test_rw_catalog_rows() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let status = GetStreamingStatusResponse {
+            actors: vec![Actor {
+                actor_id: 1,
+                fragment_id: 2,
+                table_id: 3,
+                worker_id: 4,
+                host: Some(HostAddress {
+                    host: "127.0.0.1".to_string(),
+                    port: 5688,
+                }),
+                state: ActorState::Running as i32,
+                total_row_count: 100,
+            }],
+            current_epoch: 233,
+            ..Default::default()
+        };
+
+        let rw_actors = get_sys_catalog(RW_CATALOG_SCHEMA_NAME, "rw_actors").unwrap();
+        assert!(rw_actors.requires_streaming_status());
+        assert!(rw_actors
+            .rows(&catalog_reader, DEFAULT_DATABASE_NAME, None)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            rw_actors
+                .rows(&catalog_reader, DEFAULT_DATABASE_NAME, Some(&status))
+                .unwrap(),
+            vec![Row(vec![
+                Some(ScalarImpl::Int32(1)),
+                Some(ScalarImpl::Int32(2)),
+                Some(ScalarImpl::Int32(3)),
+                Some(ScalarImpl::Int32(4)),
+                utf8("127.0.0.1:5688"),
+                utf8("running"),
+                Some(ScalarImpl::Int64(100)),
+            ])]
+        );
+
+        let rw_epoch = get_sys_catalog(RW_CATALOG_SCHEMA_NAME, "rw_epoch").unwrap();
+        assert_eq!(
+            rw_epoch
+                .rows(&catalog_reader, DEFAULT_DATABASE_NAME, Some(&status))
+                .unwrap(),
+            vec![Row(vec![Some(ScalarImpl::Int64(233))])]
+        );
+
+        let pg_class = get_sys_catalog(PG_CATALOG_SCHEMA_NAME, "pg_class").unwrap();
+        assert!(!pg_class.requires_streaming_status());
+    }
+}
@@ -0,0 +1,208 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
+use risingwave_common::array::Row;
+use risingwave_common::types::{DataType, ScalarImpl};
+
+use super::{list_relations, RelationKind, SysRows, SystemCatalog, PG_CATALOG_SCHEMA_NAME};
+use crate::catalog::database_catalog::DatabaseCatalog;
+use crate::handler::util::data_type_to_type_oid;
+
+/// The types listed in `pg_type`, with their `typname` and the SQL name used by
+/// `information_schema.columns`.
+const PG_TYPES: [(TypeOid, &str, &str); 12] = [
+    (TypeOid::Boolean, "bool", "boolean"),
+    (TypeOid::SmallInt, "int2", "smallint"),
+    (TypeOid::Int, "int4", "integer"),
+    (TypeOid::BigInt, "int8", "bigint"),
+    (TypeOid::Float4, "float4", "real"),
+    (TypeOid::Float8, "float8", "double precision"),
+    (TypeOid::Decimal, "numeric", "numeric"),
+    (TypeOid::Varchar, "varchar", "character varying"),
+    (TypeOid::Date, "date", "date"),
+    (TypeOid::Time, "time", "time without time zone"),
+    (
+        TypeOid::Timestamp,
+        "timestamp",
+        "timestamp without time zone",
+    ),
+    (
+        TypeOid::Timestampz,
+        "timestamptz",
+        "timestamp with time zone",
+    ),
+];
+
+/// The types sent as `varchar` through the wire protocol, which are still listed in `pg_type`
+/// with their own oids like in Postgres: `(oid, typname, typlen, SQL name)`.
+const PG_VARCHAR_ENCODED_TYPES: [(i32, &str, i16, &str); 3] = [
+    (1186, "interval", 16, "interval"),
+    (2249, "record", -1, "record"),
+    (2277, "anyarray", -1, "ARRAY"),
+];
+
+fn type_len(type_oid: TypeOid) -> i16 {
+    PgFieldDescriptor::new(String::new(), type_oid).get_type_len()
+}
+
+/// Get the oid, the length and the SQL name of `data_type`, like `character varying`.
+pub(super) fn pg_type_of(data_type: &DataType) -> (i32, i16, &'static str) {
+    let varchar_encoded = |oid: i32| {
+        PG_VARCHAR_ENCODED_TYPES
+            .iter()
+            .find(|(type_oid, ..)| *type_oid == oid)
+            .map(|(type_oid, _, len, sql_name)| (*type_oid, *len, *sql_name))
+            .unwrap()
+    };
+    match data_type {
+        DataType::Interval => varchar_encoded(1186),
+        DataType::Struct { .. } => varchar_encoded(2249),
+        DataType::List { .. } => varchar_encoded(2277),
+        _ => {
+            let type_oid = data_type_to_type_oid(data_type.clone());
+            PG_TYPES
+                .iter()
+                .find(|(oid, _, _)| oid.as_number() == type_oid.as_number())
+                .map(|(oid, _, sql_name)| (oid.as_number(), type_len(*oid), *sql_name))
+                .unwrap()
+        }
+    }
+}
+
+fn relkind(kind: RelationKind) -> &'static str {
+    match kind {
+        RelationKind::Table => "r",
+        RelationKind::MaterializedView => "m",
+        RelationKind::Source => "f",
+    }
+}
+
+pub(super) const PG_NAMESPACE: SystemCatalog = SystemCatalog {
+    schema_name: PG_CATALOG_SCHEMA_NAME,
+    name: "pg_namespace",
+    columns: &[(DataType::Int32, "oid"), (DataType::Varchar, "nspname")],
+    rows: SysRows::Catalog(pg_namespace_rows),
+};
+
+fn pg_namespace_rows(database: &DatabaseCatalog) -> Vec<Row> {
+    database
+        .iter_schemas()
+        .map(|schema| {
+            Row(vec![
+                Some(ScalarImpl::Int32(schema.id() as i32)),
+                Some(ScalarImpl::Utf8(schema.name().to_string())),
+            ])
+        })
+        .collect()
+}
+
+pub(super) const PG_CLASS: SystemCatalog = SystemCatalog {
+    schema_name: PG_CATALOG_SCHEMA_NAME,
+    name: "pg_class",
+    columns: &[
+        (DataType::Int32, "oid"),
+        (DataType::Varchar, "relname"),
+        (DataType::Int32, "relnamespace"),
+        (DataType::Varchar, "relkind"),
+        (DataType::Int16, "relnatts"),
+    ],
+    rows: SysRows::Catalog(pg_class_rows),
+};
+
+fn pg_class_rows(database: &DatabaseCatalog) -> Vec<Row> {
+    list_relations(database)
+        .iter()
+        .map(|relation| {
+            Row(vec![
+                Some(ScalarImpl::Int32(relation.id as i32)),
+                Some(ScalarImpl::Utf8(relation.name.to_string())),
+                Some(ScalarImpl::Int32(relation.schema.id() as i32)),
+                Some(ScalarImpl::Utf8(relkind(relation.kind).to_string())),
+                Some(ScalarImpl::Int16(relation.visible_columns().count() as i16)),
+            ])
+        })
+        .collect()
+}
+
+pub(super) const PG_ATTRIBUTE: SystemCatalog = SystemCatalog {
+    schema_name: PG_CATALOG_SCHEMA_NAME,
+    name: "pg_attribute",
+    columns: &[
+        (DataType::Int32, "attrelid"),
+        (DataType::Varchar, "attname"),
+        (DataType::Int32, "atttypid"),
+        (DataType::Int16, "attlen"),
+        (DataType::Int16, "attnum"),
+        (DataType::Boolean, "attnotnull"),
+        (DataType::Boolean, "attisdropped"),
+    ],
+    rows: SysRows::Catalog(pg_attribute_rows),
+};
+
+fn pg_attribute_rows(database: &DatabaseCatalog) -> Vec<Row> {
+    list_relations(database)
+        .iter()
+        .flat_map(|relation| {
+            relation
+                .visible_columns()
+                .enumerate()
+                .map(|(idx, column)| {
+                    let (type_oid, type_len, _) = pg_type_of(column.data_type());
+                    Row(vec![
+                        Some(ScalarImpl::Int32(relation.id as i32)),
+                        Some(ScalarImpl::Utf8(column.name().to_string())),
+                        Some(ScalarImpl::Int32(type_oid)),
+                        Some(ScalarImpl::Int16(type_len)),
+                        Some(ScalarImpl::Int16(idx as i16 + 1)),
+                        Some(ScalarImpl::Bool(false)),
+                        Some(ScalarImpl::Bool(false)),
+                    ])
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+pub(super) const PG_TYPE: SystemCatalog = SystemCatalog {
+    schema_name: PG_CATALOG_SCHEMA_NAME,
+    name: "pg_type",
+    columns: &[
+        (DataType::Int32, "oid"),
+        (DataType::Varchar, "typname"),
+        (DataType::Int16, "typlen"),
+    ],
+    rows: SysRows::Catalog(pg_type_rows),
+};
+
+fn pg_type_rows(_database: &DatabaseCatalog) -> Vec<Row> {
+    let pg_type_row = |oid: i32, typname: &str, typlen: i16| {
+        Row(vec![
+            Some(ScalarImpl::Int32(oid)),
+            Some(ScalarImpl::Utf8(typname.to_string())),
+            Some(ScalarImpl::Int16(typlen)),
+        ])
+    };
+    PG_TYPES
+        .iter()
+        .map(|(type_oid, typname, _)| {
+            pg_type_row(type_oid.as_number(), typname, type_len(*type_oid))
+        })
+        .chain(
+            PG_VARCHAR_ENCODED_TYPES
+                .iter()
+                .map(|(oid, typname, typlen, _)| pg_type_row(*oid, typname, *typlen)),
+        )
+        .collect()
+}
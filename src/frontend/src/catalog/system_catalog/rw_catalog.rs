@@ -0,0 +1,183 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::array::Row;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_pb::meta::table_fragments::fragment::{FragmentDistributionType, FragmentType};
+use risingwave_pb::meta::table_fragments::ActorState;
+use risingwave_pb::meta::GetStreamingStatusResponse;
+
+use super::{SysRows, SystemCatalog, RW_CATALOG_SCHEMA_NAME};
+
+fn fragment_type_name(fragment_type: i32) -> &'static str {
+    match FragmentType::from_i32(fragment_type) {
+        Some(FragmentType::Source) => "source",
+        Some(FragmentType::Sink) => "sink",
+        Some(FragmentType::Others) => "others",
+        None => "unknown",
+    }
+}
+
+fn distribution_type_name(distribution_type: i32) -> &'static str {
+    match FragmentDistributionType::from_i32(distribution_type) {
+        Some(FragmentDistributionType::Single) => "single",
+        Some(FragmentDistributionType::Hash) => "hash",
+        None => "unknown",
+    }
+}
+
+fn actor_state_name(state: i32) -> &'static str {
+    match ActorState::from_i32(state) {
+        Some(ActorState::Inactive) => "inactive",
+        Some(ActorState::Running) => "running",
+        None => "unknown",
+    }
+}
+
+pub(super) const RW_FRAGMENTS: SystemCatalog = SystemCatalog {
+    schema_name: RW_CATALOG_SCHEMA_NAME,
+    name: "rw_fragments",
+    columns: &[
+        (DataType::Int32, "fragment_id"),
+        (DataType::Int32, "table_id"),
+        (DataType::Varchar, "fragment_type"),
+        (DataType::Varchar, "distribution_type"),
+        (DataType::Int32, "parallelism"),
+    ],
+    rows: SysRows::Streaming(rw_fragments_rows),
+};
+
+fn rw_fragments_rows(status: &GetStreamingStatusResponse) -> Vec<Row> {
+    status
+        .fragments
+        .iter()
+        .map(|fragment| {
+            Row(vec![
+                Some(ScalarImpl::Int32(fragment.fragment_id as i32)),
+                Some(ScalarImpl::Int32(fragment.table_id as i32)),
+                Some(ScalarImpl::Utf8(
+                    fragment_type_name(fragment.fragment_type).to_string(),
+                )),
+                Some(ScalarImpl::Utf8(
+                    distribution_type_name(fragment.distribution_type).to_string(),
+                )),
+                Some(ScalarImpl::Int32(fragment.parallelism as i32)),
+            ])
+        })
+        .collect()
+}
+
+pub(super) const RW_ACTORS: SystemCatalog = SystemCatalog {
+    schema_name: RW_CATALOG_SCHEMA_NAME,
+    name: "rw_actors",
+    columns: &[
+        (DataType::Int32, "actor_id"),
+        (DataType::Int32, "fragment_id"),
+        (DataType::Int32, "table_id"),
+        (DataType::Int32, "worker_id"),
+        (DataType::Varchar, "host"),
+        (DataType::Varchar, "state"),
+        (DataType::Int64, "total_row_count"),
+    ],
+    rows: SysRows::Streaming(rw_actors_rows),
+};
+
+fn rw_actors_rows(status: &GetStreamingStatusResponse) -> Vec<Row> {
+    status
+        .actors
+        .iter()
+        .map(|actor| {
+            let host = actor
+                .host
+                .as_ref()
+                .map(|host| ScalarImpl::Utf8(format!("{}:{}", host.host, host.port)));
+            Row(vec![
+                Some(ScalarImpl::Int32(actor.actor_id as i32)),
+                Some(ScalarImpl::Int32(actor.fragment_id as i32)),
+                Some(ScalarImpl::Int32(actor.table_id as i32)),
+                Some(ScalarImpl::Int32(actor.worker_id as i32)),
+                host,
+                Some(ScalarImpl::Utf8(actor_state_name(actor.state).to_string())),
+                Some(ScalarImpl::Int64(actor.total_row_count as i64)),
+            ])
+        })
+        .collect()
+}
+
+pub(super) const RW_SOURCE_SPLITS: SystemCatalog = SystemCatalog {
+    schema_name: RW_CATALOG_SCHEMA_NAME,
+    name: "rw_source_splits",
+    columns: &[
+        (DataType::Int32, "source_id"),
+        (DataType::Int32, "actor_id"),
+        (DataType::Varchar, "split_id"),
+    ],
+    rows: SysRows::Streaming(rw_source_splits_rows),
+};
+
+fn rw_source_splits_rows(status: &GetStreamingStatusResponse) -> Vec<Row> {
+    status
+        .source_splits
+        .iter()
+        .map(|split| {
+            Row(vec![
+                Some(ScalarImpl::Int32(split.source_id as i32)),
+                Some(ScalarImpl::Int32(split.actor_id as i32)),
+                Some(ScalarImpl::Utf8(split.split_id.clone())),
+            ])
+        })
+        .collect()
+}
+
+pub(super) const RW_EPOCH: SystemCatalog = SystemCatalog {
+    schema_name: RW_CATALOG_SCHEMA_NAME,
+    name: "rw_epoch",
+    columns: &[(DataType::Int64, "current_epoch")],
+    rows: SysRows::Streaming(rw_epoch_rows),
+};
+
+fn rw_epoch_rows(status: &GetStreamingStatusResponse) -> Vec<Row> {
+    vec![Row(vec![Some(ScalarImpl::Int64(
+        status.current_epoch as i64,
+    ))])]
+}
+
+pub(super) const RW_BARRIERS: SystemCatalog = SystemCatalog {
+    schema_name: RW_CATALOG_SCHEMA_NAME,
+    name: "rw_barriers",
+    columns: &[
+        (DataType::Int64, "prev_epoch"),
+        (DataType::Int64, "curr_epoch"),
+        (DataType::Varchar, "command"),
+        (DataType::Boolean, "checkpoint"),
+        (DataType::Int64, "elapsed_ms"),
+    ],
+    rows: SysRows::Streaming(rw_barriers_rows),
+};
+
+fn rw_barriers_rows(status: &GetStreamingStatusResponse) -> Vec<Row> {
+    status
+        .in_flight_barriers
+        .iter()
+        .map(|barrier| {
+            Row(vec![
+                Some(ScalarImpl::Int64(barrier.prev_epoch as i64)),
+                Some(ScalarImpl::Int64(barrier.curr_epoch as i64)),
+                Some(ScalarImpl::Utf8(barrier.command.clone())),
+                Some(ScalarImpl::Bool(barrier.checkpoint)),
+                Some(ScalarImpl::Int64(barrier.elapsed_ms as i64)),
+            ])
+        })
+        .collect()
+}
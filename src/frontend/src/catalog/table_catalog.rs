@@ -94,6 +94,9 @@ impl TableCatalog {
             optional_associated_source_id: self
                 .associated_source_id
                 .map(|source_id| OptionalAssociatedSourceId::AssociatedSourceId(source_id.into())),
+            // Set by the `WITH` options of `CREATE MATERIALIZED VIEW`.
+            state_ttl_ms: 0,
+            placement: None,
         }
     }
 }
@@ -200,6 +203,8 @@ mod tests {
             dependent_relations: vec![],
             optional_associated_source_id: OptionalAssociatedSourceId::AssociatedSourceId(233)
                 .into(),
+            state_ttl_ms: 0,
+            placement: None,
         }
         .into();
 
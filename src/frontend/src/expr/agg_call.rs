@@ -14,16 +14,21 @@
 
 use itertools::Itertools;
 use risingwave_common::error::{ErrorCode, Result, RwError};
-use risingwave_common::types::DataType;
+use risingwave_common::types::{DataType, DECIMAL_MAX_PRECISION};
 use risingwave_expr::expr::AggKind;
 
 use super::{Expr, ExprImpl};
 
+/// The minimum scale of `avg` over a constrained numeric, so that averaging integral amounts still
+/// yields fractional digits.
+const DECIMAL_MIN_AVG_SCALE: u32 = 6;
+
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct AggCall {
     agg_kind: AggKind,
     return_type: DataType,
     inputs: Vec<ExprImpl>,
+    distinct: bool,
 }
 
 impl std::fmt::Debug for AggCall {
@@ -33,13 +38,10 @@ impl std::fmt::Debug for AggCall {
                 .field("agg_kind", &self.agg_kind)
                 .field("return_type", &self.return_type)
                 .field("inputs", &self.inputs)
+                .field("distinct", &self.distinct)
                 .finish()
         } else {
-            let mut builder = f.debug_tuple(&format!("{}", self.agg_kind));
-            self.inputs.iter().for_each(|child| {
-                builder.field(child);
-            });
-            builder.finish()
+            fmt_agg_call(f, &self.agg_kind, &self.inputs, self.distinct)
         }
     }
 }
@@ -51,10 +53,20 @@ impl AggCall {
         let return_type = match (&agg_kind, inputs) {
             (AggKind::Min, [input]) => input.clone(),
             (AggKind::Max, [input]) => input.clone(),
+            // The sum of a constrained numeric keeps its scale but may need all the precision, and
+            // the average keeps at least `DECIMAL_MIN_AVG_SCALE` fractional digits.
             (AggKind::Avg, [input]) => match input {
-                DataType::Int16 | DataType::Int32 | DataType::Int64 | DataType::Decimal => {
-                    DataType::Decimal
+                DataType::Int16 | DataType::Int32 | DataType::Int64 => {
+                    DataType::UNCONSTRAINED_DECIMAL
                 }
+                DataType::Decimal {
+                    precision: Some(_),
+                    scale,
+                } => DataType::Decimal {
+                    precision: Some(DECIMAL_MAX_PRECISION),
+                    scale: Some(scale.unwrap_or(0).max(DECIMAL_MIN_AVG_SCALE)),
+                },
+                DataType::Decimal { .. } => DataType::UNCONSTRAINED_DECIMAL,
                 DataType::Float32 | DataType::Float64 => DataType::Float64,
                 DataType::Interval => DataType::Interval,
                 _ => return None,
@@ -62,14 +74,45 @@ impl AggCall {
             (AggKind::Sum, [input]) => match input {
                 DataType::Int16 => DataType::Int64,
                 DataType::Int32 => DataType::Int64,
-                DataType::Int64 => DataType::Decimal,
-                DataType::Decimal => DataType::Decimal,
+                DataType::Int64 => DataType::UNCONSTRAINED_DECIMAL,
+                DataType::Decimal {
+                    precision: Some(_),
+                    scale,
+                } => DataType::Decimal {
+                    precision: Some(DECIMAL_MAX_PRECISION),
+                    scale: Some(scale.unwrap_or(0)),
+                },
+                DataType::Decimal { .. } => DataType::UNCONSTRAINED_DECIMAL,
                 DataType::Float32 => DataType::Float32,
                 DataType::Float64 => DataType::Float64,
                 DataType::Interval => DataType::Interval,
                 _ => return None,
             },
             (AggKind::Count, _) => DataType::Int64,
+            // Unlike postgres, the statistics of integers and numerics are also computed in and
+            // returned as double precision.
+            (
+                AggKind::StddevSamp | AggKind::StddevPop | AggKind::VarSamp | AggKind::VarPop,
+                [input],
+            ) => match input {
+                DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::Float32
+                | DataType::Float64
+                | DataType::Decimal { .. } => DataType::Float64,
+                _ => return None,
+            },
+            (AggKind::BoolAnd | AggKind::BoolOr, [DataType::Boolean]) => DataType::Boolean,
+            (AggKind::BoolAnd | AggKind::BoolOr, _) => return None,
+            (AggKind::BitAnd | AggKind::BitOr, [input]) => match input {
+                DataType::Int16 | DataType::Int32 | DataType::Int64 => input.clone(),
+                _ => return None,
+            },
+            (AggKind::ApproxCountDistinct, [_]) => DataType::Int64,
+            (AggKind::ArrayAgg, [input]) => DataType::List {
+                datatype: Box::new(input.clone()),
+            },
             (other_kind, other_inputs) => {
                 todo!(
                     "Unsupported aggregate function: {:?} with {} inputs",
@@ -83,7 +126,7 @@ impl AggCall {
 
     /// Returns error if the function name matches with an existing function
     /// but with illegal arguments.
-    pub fn new(agg_kind: AggKind, inputs: Vec<ExprImpl>) -> Result<Self> {
+    pub fn new(agg_kind: AggKind, inputs: Vec<ExprImpl>, distinct: bool) -> Result<Self> {
         // TODO(TaoWu): Add arguments validator.
         let data_types = inputs.iter().map(ExprImpl::return_type).collect_vec();
         let return_type = Self::infer_return_type(&agg_kind, &data_types).ok_or_else(|| {
@@ -97,11 +140,12 @@ impl AggCall {
             agg_kind,
             return_type,
             inputs,
+            distinct,
         })
     }
 
-    pub fn decompose(self) -> (AggKind, Vec<ExprImpl>) {
-        (self.agg_kind, self.inputs)
+    pub fn decompose(self) -> (AggKind, Vec<ExprImpl>, bool) {
+        (self.agg_kind, self.inputs, self.distinct)
     }
 
     pub fn agg_kind(&self) -> AggKind {
@@ -112,6 +156,31 @@ impl AggCall {
     pub fn inputs(&self) -> &[ExprImpl] {
         self.inputs.as_ref()
     }
+
+    /// Whether the inputs are deduplicated before aggregation, i.e. `agg(DISTINCT ...)`.
+    pub fn distinct(&self) -> bool {
+        self.distinct
+    }
+}
+
+/// Formats an aggregate call as `kind(inputs)`, or `kind(distinct inputs)`, or `kind` when there
+/// is no input.
+pub fn fmt_agg_call(
+    f: &mut std::fmt::Formatter<'_>,
+    agg_kind: &AggKind,
+    inputs: &[impl std::fmt::Debug],
+    distinct: bool,
+) -> std::fmt::Result {
+    write!(f, "{}", agg_kind)?;
+    if inputs.is_empty() {
+        return Ok(());
+    }
+    write!(f, "(")?;
+    if distinct {
+        write!(f, "distinct ")?;
+    }
+    write!(f, "{:?}", inputs.iter().format(", "))?;
+    write!(f, ")")
 }
 impl Expr for AggCall {
     fn return_type(&self) -> DataType {
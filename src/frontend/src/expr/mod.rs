@@ -26,18 +26,20 @@ mod function_call;
 mod input_ref;
 mod literal;
 mod subquery;
+mod table_function;
 
 mod expr_rewriter;
 mod expr_visitor;
 mod type_inference;
 mod utils;
 
-pub use agg_call::AggCall;
+pub use agg_call::{fmt_agg_call, AggCall};
 pub use correlated_input_ref::CorrelatedInputRef;
 pub use function_call::FunctionCall;
 pub use input_ref::{as_alias_display, input_ref_to_column_indices, InputRef, InputRefDisplay};
 pub use literal::Literal;
 pub use subquery::{Subquery, SubqueryKind};
+pub use table_function::{TableFunction, TableFunctionType};
 
 pub type ExprType = risingwave_pb::expr::expr_node::Type;
 
@@ -82,7 +84,7 @@ impl ExprImpl {
     /// A `count(*)` aggregate function.
     #[inline(always)]
     pub fn count_star() -> Self {
-        AggCall::new(AggKind::Count, vec![]).unwrap().into()
+        AggCall::new(AggKind::Count, vec![], false).unwrap().into()
     }
 
     /// Collect all `InputRef`s' indexes in the expression.
@@ -0,0 +1,123 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+
+use itertools::Itertools;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::DataType;
+use risingwave_pb::expr::table_function::Type;
+use risingwave_pb::expr::TableFunction as TableFunctionProst;
+
+use super::{Expr, ExprImpl};
+
+/// A set-returning function that appears in the `FROM` clause, e.g. `unnest(array[1, 2])`.
+///
+/// Unlike other expressions, it produces a relation with a single column of `return_type`
+/// rather than a single value, so it is not part of [`ExprImpl`].
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct TableFunction {
+    pub args: Vec<ExprImpl>,
+    pub return_type: DataType,
+    pub function_type: TableFunctionType,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TableFunctionType {
+    Unnest,
+}
+
+impl TableFunctionType {
+    fn to_protobuf(self) -> Type {
+        match self {
+            TableFunctionType::Unnest => Type::Unnest,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TableFunctionType::Unnest => "unnest",
+        }
+    }
+}
+
+impl FromStr for TableFunctionType {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("unnest") {
+            Ok(TableFunctionType::Unnest)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TableFunction {
+    /// Create a table function and infer its return type from the arguments.
+    pub fn new(function_type: TableFunctionType, args: Vec<ExprImpl>) -> Result<Self> {
+        let return_type = match (function_type, args.as_slice()) {
+            (TableFunctionType::Unnest, [arg]) => match arg.return_type() {
+                DataType::List { datatype } => Some(*datatype),
+                _ => None,
+            },
+            _ => None,
+        }
+        .ok_or_else(|| {
+            ErrorCode::BindError(format!(
+                "function {}({}) does not exist",
+                function_type.name(),
+                args.iter()
+                    .map(|arg| format!("{:?}", arg.return_type()))
+                    .join(", ")
+            ))
+        })?;
+        Ok(Self {
+            args,
+            return_type,
+            function_type,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.function_type.name()
+    }
+
+    pub fn to_protobuf(&self) -> TableFunctionProst {
+        TableFunctionProst {
+            function_type: self.function_type.to_protobuf() as i32,
+            args: self.args.iter().map(|arg| arg.to_protobuf()).collect(),
+            return_type: Some(self.return_type.to_protobuf()),
+        }
+    }
+}
+
+impl std::fmt::Debug for TableFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.debug_struct("TableFunction")
+                .field("function_type", &self.function_type)
+                .field("return_type", &self.return_type)
+                .field("args", &self.args)
+                .finish()
+        } else {
+            let func_name = format!("{:?}", self.function_type);
+            let mut builder = f.debug_tuple(&func_name);
+            self.args.iter().for_each(|arg| {
+                builder.field(arg);
+            });
+            builder.finish()
+        }
+    }
+}
@@ -20,7 +20,7 @@ use std::vec;
 
 use itertools::iproduct;
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_common::types::DataType;
+use risingwave_common::types::{DataType, DECIMAL_MAX_PRECISION};
 
 use crate::expr::ExprType;
 
@@ -58,7 +58,7 @@ fn name_of(ty: &DataType) -> DataTypeName {
         DataType::Time => DataTypeName::Time,
         DataType::Timestamp => DataTypeName::Timestamp,
         DataType::Timestampz => DataTypeName::Timestampz,
-        DataType::Decimal => DataTypeName::Decimal,
+        DataType::Decimal { .. } => DataTypeName::Decimal,
         DataType::Interval => DataTypeName::Interval,
         DataType::Struct { .. } => DataTypeName::Struct,
         DataType::List { .. } => DataTypeName::List,
@@ -68,30 +68,94 @@ fn name_of(ty: &DataType) -> DataTypeName {
 /// Infers the return type of a function. Returns `None` if the function with specified data types
 /// is not supported on backend.
 pub fn infer_type(func_type: ExprType, inputs_type: Vec<DataType>) -> Option<DataType> {
-    // With our current simplified type system, where all types are nullable and not parameterized
-    // by things like length or precision, the inference can be done with a map lookup.
+    // With our current simplified type system, where all types are nullable and only `numeric` is
+    // parameterized by precision and scale, the inference can be done with a map lookup, followed
+    // by deriving the typmod of a `numeric` result.
     let input_type_names = inputs_type.iter().map(name_of).collect();
-    infer_type_name(func_type, input_type_names).map(|type_name| match type_name {
-        DataTypeName::Int16 => DataType::Int16,
-        DataTypeName::Int32 => DataType::Int32,
-        DataTypeName::Int64 => DataType::Int64,
-        DataTypeName::Float32 => DataType::Float32,
-        DataTypeName::Float64 => DataType::Float64,
-        DataTypeName::Boolean => DataType::Boolean,
-        DataTypeName::Varchar => DataType::Varchar,
-        DataTypeName::Date => DataType::Date,
-        DataTypeName::Time => DataType::Time,
-        DataTypeName::Timestamp => DataType::Timestamp,
-        DataTypeName::Timestampz => DataType::Timestampz,
-        DataTypeName::Decimal => DataType::Decimal,
-        DataTypeName::Interval => DataType::Interval,
-        DataTypeName::Struct => DataType::Struct {
-            fields: Arc::new([]),
-        },
-        DataTypeName::List => DataType::List {
-            datatype: Box::new(DataType::Int32),
-        },
-    })
+    let return_type =
+        infer_type_name(func_type, input_type_names).map(|type_name| match type_name {
+            DataTypeName::Int16 => DataType::Int16,
+            DataTypeName::Int32 => DataType::Int32,
+            DataTypeName::Int64 => DataType::Int64,
+            DataTypeName::Float32 => DataType::Float32,
+            DataTypeName::Float64 => DataType::Float64,
+            DataTypeName::Boolean => DataType::Boolean,
+            DataTypeName::Varchar => DataType::Varchar,
+            DataTypeName::Date => DataType::Date,
+            DataTypeName::Time => DataType::Time,
+            DataTypeName::Timestamp => DataType::Timestamp,
+            DataTypeName::Timestampz => DataType::Timestampz,
+            DataTypeName::Decimal => DataType::UNCONSTRAINED_DECIMAL,
+            DataTypeName::Interval => DataType::Interval,
+            DataTypeName::Struct => DataType::Struct {
+                fields: Arc::new([]),
+            },
+            DataTypeName::List => DataType::List {
+                datatype: Box::new(DataType::Int32),
+            },
+        })?;
+    match return_type {
+        DataType::Decimal { .. } => Some(infer_decimal_typmod(func_type, &inputs_type)),
+        return_type => Some(return_type),
+    }
+}
+
+/// Returns the precision and scale of an exact numeric type, or `None` if it is not one or it is
+/// an unconstrained `numeric`.
+fn decimal_typmod_of(ty: &DataType) -> Option<(u32, u32)> {
+    match ty {
+        DataType::Int16 => Some((5, 0)),
+        DataType::Int32 => Some((10, 0)),
+        DataType::Int64 => Some((19, 0)),
+        DataType::Decimal {
+            precision: Some(precision),
+            scale,
+        } => Some((*precision, scale.unwrap_or(0))),
+        _ => None,
+    }
+}
+
+/// Derives the `numeric` result type of an arithmetic operation from its operands. The result is
+/// unconstrained unless all operands are constrained `numeric` or integers.
+///
+/// The rules follow SQL Server: the result keeps every integral and fractional digit the operation
+/// can produce, and when that exceeds [`DECIMAL_MAX_PRECISION`] the scale is cut first, though not
+/// below 6 fractional digits.
+fn infer_decimal_typmod(func_type: ExprType, inputs_type: &[DataType]) -> DataType {
+    let typmods = match inputs_type {
+        [input @ DataType::Decimal { .. }] if func_type == ExprType::Neg => return input.clone(),
+        [lhs, rhs] => decimal_typmod_of(lhs).zip(decimal_typmod_of(rhs)),
+        _ => None,
+    };
+    let (precision, scale) = match (func_type, typmods) {
+        (ExprType::Add | ExprType::Subtract, Some(((p1, s1), (p2, s2)))) => {
+            let scale = s1.max(s2);
+            ((p1 - s1).max(p2 - s2) + scale + 1, scale)
+        }
+        (ExprType::Multiply, Some(((p1, s1), (p2, s2)))) => (p1 + p2 + 1, s1 + s2),
+        (ExprType::Divide, Some(((p1, s1), (p2, s2)))) => {
+            let scale = (s1 + p2 + 1).max(6);
+            (p1 - s1 + s2 + scale, scale)
+        }
+        (ExprType::Modulus, Some(((p1, s1), (p2, s2)))) => {
+            let scale = s1.max(s2);
+            ((p1 - s1).min(p2 - s2) + scale, scale)
+        }
+        _ => return DataType::UNCONSTRAINED_DECIMAL,
+    };
+    let (precision, scale) = if precision > DECIMAL_MAX_PRECISION {
+        let integral = precision - scale;
+        let scale = DECIMAL_MAX_PRECISION
+            .saturating_sub(integral)
+            .max(scale.min(6));
+        (DECIMAL_MAX_PRECISION, scale)
+    } else {
+        (precision, scale)
+    };
+    DataType::Decimal {
+        precision: Some(precision),
+        scale: Some(scale),
+    }
 }
 
 /// Infer the return type name without parameters like length or precision.
@@ -381,14 +445,23 @@ pub fn least_restrictive(lhs: DataType, rhs: DataType) -> Result<DataType> {
     if lhs == rhs {
         Ok(lhs)
     } else if cast_ok(&lhs, &rhs, &CastContext::Implicit) {
-        Ok(rhs)
+        Ok(drop_decimal_typmod(rhs))
     } else if cast_ok(&rhs, &lhs, &CastContext::Implicit) {
-        Ok(lhs)
+        Ok(drop_decimal_typmod(lhs))
     } else {
         Err(ErrorCode::BindError(format!("types {:?} and {:?} cannot be matched", lhs, rhs)).into())
     }
 }
 
+/// As in PG, a `numeric` typmod survives [`least_restrictive`] only if all inputs agree on it.
+/// Otherwise values of one input would be rounded to the scale of another.
+fn drop_decimal_typmod(ty: DataType) -> DataType {
+    match ty {
+        DataType::Decimal { .. } => DataType::UNCONSTRAINED_DECIMAL,
+        ty => ty,
+    }
+}
+
 /// The context a cast operation is invoked in. An implicit cast operation is allowed in a context
 /// that allows explicit casts, but not vice versa. See details in
 /// [PG](https://www.postgresql.org/docs/current/catalog-pg-cast.html).
@@ -434,7 +507,8 @@ fn build_cast_map() -> HashMap<(DataTypeName, DataTypeName), CastContext> {
     // to `NUMERIC(20,4)`). Sizing casts are only available for `numeric`, `timestamp`,
     // `timestamptz`, `time`, `interval` and these are implicit. https://www.postgresql.org/docs/current/typeconv-query.html
     //
-    // As we do not support size parameters in types, there are no sizing casts.
+    // `numeric` is the only type with size parameters for now, so it is the only one overriding
+    // the same type cast below with an implicit sizing cast.
     m.insert((T::Boolean, T::Boolean), CastContext::Explicit);
     m.insert((T::Varchar, T::Varchar), CastContext::Explicit);
 
@@ -469,6 +543,9 @@ fn build_cast_map() -> HashMap<(DataTypeName, DataTypeName), CastContext> {
     m.insert((T::Timestampz, T::Time), CastContext::Assign);
     m.insert((T::Boolean, T::Int32), CastContext::Explicit);
     m.insert((T::Int32, T::Boolean), CastContext::Explicit);
+
+    // Sizing cast, e.g. `NUMERIC(18,3)` to `NUMERIC(20,4)`.
+    m.insert((T::Decimal, T::Decimal), CastContext::Implicit);
     m
 }
 
@@ -515,6 +592,7 @@ mod tests {
     #[test]
     fn test_arithmetics() {
         use DataType::*;
+        const DECIMAL: DataType = DataType::UNCONSTRAINED_DECIMAL;
         let atm_exprs = vec![
             ExprType::Add,
             ExprType::Subtract,
@@ -528,43 +606,121 @@ mod tests {
             (Int16, Int64, Int64),
             (Int16, Float32, Float32),
             (Int16, Float64, Float64),
-            (Int16, Decimal, Decimal),
+            (Int16, DECIMAL, DECIMAL),
             (Int32, Int16, Int32),
             (Int32, Int32, Int32),
             (Int32, Int64, Int64),
             (Int32, Float32, Float32),
             (Int32, Float64, Float64),
-            (Int32, Decimal, Decimal),
+            (Int32, DECIMAL, DECIMAL),
             (Int64, Int16, Int64),
             (Int64, Int32, Int64),
             (Int64, Int64, Int64),
             (Int64, Float32, Float32),
             (Int64, Float64, Float64),
-            (Int64, Decimal, Decimal),
+            (Int64, DECIMAL, DECIMAL),
             (Float32, Int16, Float32),
             (Float32, Int32, Float32),
             (Float32, Int64, Float32),
             (Float32, Float32, Float32),
             (Float32, Float64, Float64),
-            (Float32, Decimal, Decimal),
+            (Float32, DECIMAL, DECIMAL),
             (Float64, Int16, Float64),
             (Float64, Int32, Float64),
             (Float64, Int64, Float64),
             (Float64, Float32, Float64),
             (Float64, Float64, Float64),
-            (Float64, Decimal, Decimal),
-            (Decimal, Int16, Decimal),
-            (Decimal, Int32, Decimal),
-            (Decimal, Int64, Decimal),
-            (Decimal, Float32, Decimal),
-            (Decimal, Float64, Decimal),
-            (Decimal, Decimal, Decimal),
+            (Float64, DECIMAL, DECIMAL),
+            (DECIMAL, Int16, DECIMAL),
+            (DECIMAL, Int32, DECIMAL),
+            (DECIMAL, Int64, DECIMAL),
+            (DECIMAL, Float32, DECIMAL),
+            (DECIMAL, Float64, DECIMAL),
+            (DECIMAL, DECIMAL, DECIMAL),
         ];
         for (expr, (t1, t2, tr)) in iproduct!(atm_exprs, num_promote_table) {
             test_simple_infer_type(expr, vec![t1, t2], tr);
         }
     }
 
+    #[test]
+    fn test_decimal_typmod() {
+        let decimal = |precision, scale| DataType::Decimal {
+            precision: Some(precision),
+            scale: Some(scale),
+        };
+        let cases = vec![
+            (ExprType::Add, decimal(10, 2), decimal(5, 3), decimal(12, 3)),
+            (
+                ExprType::Subtract,
+                decimal(10, 2),
+                DataType::Int32,
+                decimal(13, 2),
+            ),
+            (
+                ExprType::Multiply,
+                decimal(10, 2),
+                decimal(5, 3),
+                decimal(16, 5),
+            ),
+            (
+                ExprType::Divide,
+                decimal(10, 2),
+                decimal(5, 3),
+                decimal(19, 8),
+            ),
+            (
+                ExprType::Modulus,
+                decimal(10, 2),
+                decimal(5, 3),
+                decimal(5, 3),
+            ),
+            // Precision is capped and the scale is cut first, but not below 6.
+            (
+                ExprType::Multiply,
+                decimal(20, 10),
+                decimal(20, 10),
+                decimal(28, 7),
+            ),
+            (
+                ExprType::Divide,
+                decimal(28, 2),
+                DataType::Int64,
+                decimal(28, 6),
+            ),
+            // Any unconstrained or inexact operand makes the result unconstrained.
+            (
+                ExprType::Add,
+                decimal(10, 2),
+                DataType::UNCONSTRAINED_DECIMAL,
+                DataType::UNCONSTRAINED_DECIMAL,
+            ),
+            (
+                ExprType::Add,
+                decimal(10, 2),
+                DataType::Float32,
+                DataType::UNCONSTRAINED_DECIMAL,
+            ),
+        ];
+        for (expr, t1, t2, tr) in cases {
+            test_simple_infer_type(expr, vec![t1, t2], tr);
+        }
+        test_simple_infer_type(ExprType::Neg, vec![decimal(10, 2)], decimal(10, 2));
+
+        assert_eq!(
+            least_restrictive(decimal(10, 2), decimal(10, 2)).unwrap(),
+            decimal(10, 2)
+        );
+        assert_eq!(
+            least_restrictive(decimal(10, 2), decimal(5, 3)).unwrap(),
+            DataType::UNCONSTRAINED_DECIMAL
+        );
+        assert_eq!(
+            least_restrictive(DataType::Int32, decimal(5, 3)).unwrap(),
+            DataType::UNCONSTRAINED_DECIMAL
+        );
+    }
+
     #[test]
     fn test_bool_num_not_exist() {
         let exprs = vec![
@@ -589,7 +745,7 @@ mod tests {
             DataType::Int64,
             DataType::Float32,
             DataType::Float64,
-            DataType::Decimal,
+            DataType::UNCONSTRAINED_DECIMAL,
         ];
 
         for (expr, num_t) in iproduct!(exprs, num_types) {
@@ -605,7 +761,7 @@ mod tests {
             T::Int16,
             T::Int32,
             T::Int64,
-            T::Decimal,
+            T::UNCONSTRAINED_DECIMAL,
             T::Float32,
             T::Float64,
             T::Varchar,
@@ -643,7 +799,7 @@ mod tests {
                 "  TTTTT      ",
                 "   TTTT      ",
                 "    TTT      ",
-                "     TT      ",
+                "    TTT      ",
                 "      T      ",
                 "             ",
                 "             ", // varchar
@@ -662,7 +818,7 @@ mod tests {
                 "  TTTTTT     ",
                 " T TTTTT     ",
                 " TT TTTT     ",
-                " TTT TTT     ",
+                " TTTTTTT     ",
                 " TTTT TT     ",
                 " TTTTT T     ",
                 "TTTTTTT TTTTT", // varchar
@@ -0,0 +1,95 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_sqlparser::ast::ObjectName;
+
+use crate::binder::Binder;
+use crate::session::OptimizerContext;
+
+pub async fn handle_alter_mv_set_parallelism(
+    context: OptimizerContext,
+    table_name: ObjectName,
+    parallelism: u64,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+
+    let parallelism = match u32::try_from(parallelism) {
+        Ok(parallelism) if parallelism > 0 => parallelism,
+        _ => {
+            return Err(RwError::from(ErrorCode::InvalidInputSyntax(format!(
+                "invalid parallelism {}",
+                parallelism
+            ))))
+        }
+    };
+
+    let catalog_reader = session.env().catalog_reader();
+
+    let table_id = {
+        let reader = catalog_reader.read_guard();
+        let table = reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+
+        // If associated source is `Some`, then it is a actually a materialized source / table v2.
+        if table.associated_source_id().is_some() {
+            return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                "Only materialized views can be rescheduled.".to_owned(),
+            )));
+        }
+        table.id()
+    };
+
+    session
+        .env()
+        .meta_client()
+        .reschedule(table_id.table_id(), parallelism)
+        .await?;
+
+    Ok(PgResponse::empty_result(
+        StatementType::ALTER_MATERIALIZED_VIEW,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_alter_mv_set_parallelism_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 smallint);")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create materialized view mv as select v1 from t;")
+            .await
+            .unwrap();
+
+        frontend
+            .run_sql("alter materialized view mv set parallelism to 4;")
+            .await
+            .unwrap();
+        assert!(frontend
+            .run_sql("alter materialized view mv set parallelism to 0;")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("alter materialized view t set parallelism to 4;")
+            .await
+            .is_err());
+    }
+}
@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::catalog::Table as ProstTable;
-use risingwave_sqlparser::ast::{ObjectName, Query};
+use risingwave_sqlparser::ast::{Ident, ObjectName, Query, SqlOption, Value};
 
 use crate::binder::Binder;
 use crate::optimizer::property::Distribution;
@@ -41,7 +43,8 @@ pub fn gen_create_mv_plan(
         let mut binder = Binder::new(
             session.env().catalog_reader().read_guard(),
             session.database().to_string(),
-        );
+        )
+        .for_stream();
         binder.bind_query(*query)?
     };
 
@@ -54,15 +57,100 @@ pub fn gen_create_mv_plan(
     Ok((plan, table))
 }
 
+/// Parses a TTL like `'7 days'` into milliseconds.
+fn parse_ttl(ttl: &str) -> Result<u64> {
+    let invalid_ttl = || ErrorCode::InvalidInputSyntax(format!("Invalid ttl '{}'", ttl));
+    let (value, unit) = match ttl.split_whitespace().collect::<Vec<_>>()[..] {
+        [value, unit] => (value, unit),
+        _ => return Err(invalid_ttl().into()),
+    };
+    let value = value.parse::<u64>().map_err(|_| invalid_ttl())?;
+    let unit_ms = match unit.to_lowercase().trim_end_matches('s') {
+        "second" => 1000,
+        "minute" => 60 * 1000,
+        "hour" => 3600 * 1000,
+        "day" => 24 * 3600 * 1000,
+        _ => return Err(invalid_ttl().into()),
+    };
+    match value.checked_mul(unit_ms) {
+        Some(ms) if ms > 0 => Ok(ms),
+        _ => Err(invalid_ttl().into()),
+    }
+}
+
+/// Parses worker labels like `'zone=a, rack=1'`.
+fn parse_placement_labels(labels: &str) -> Result<HashMap<String, String>> {
+    labels
+        .split(',')
+        .map(|label| match label.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(
+                ErrorCode::InvalidInputSyntax(format!("Invalid placement label '{}'", label))
+                    .into(),
+            ),
+        })
+        .collect()
+}
+
+/// Resolves the materialized views like `'mv1, schema.mv2'` to their table ids.
+fn resolve_anti_affinity(session: &SessionImpl, names: &str) -> Result<Vec<u32>> {
+    let catalog_reader = session.env().catalog_reader().read_guard();
+    names
+        .split(',')
+        .map(|name| {
+            let name = ObjectName(name.trim().split('.').map(Ident::new).collect());
+            let (schema_name, table_name) = Binder::resolve_table_name(name)?;
+            let table =
+                catalog_reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+            Ok(table.id().table_id)
+        })
+        .collect()
+}
+
+/// Applies the `WITH` options of `CREATE MATERIALIZED VIEW` to the table.
+fn handle_mv_with_options(
+    session: &SessionImpl,
+    table: &mut ProstTable,
+    options: Vec<SqlOption>,
+) -> Result<()> {
+    for option in options {
+        match (option.name.value.to_lowercase().as_str(), option.value) {
+            ("ttl", Value::SingleQuotedString(ttl)) => table.state_ttl_ms = parse_ttl(&ttl)?,
+            ("placement", Value::SingleQuotedString(labels)) => {
+                table.placement.get_or_insert_with(Default::default).labels =
+                    parse_placement_labels(&labels)?
+            }
+            ("anti_affinity", Value::SingleQuotedString(names)) => {
+                table
+                    .placement
+                    .get_or_insert_with(Default::default)
+                    .anti_affinity_table_ids = resolve_anti_affinity(session, &names)?
+            }
+            (name, value) => {
+                return Err(ErrorCode::InvalidInputSyntax(format!(
+                    "Unsupported option {} = {}",
+                    name, value
+                ))
+                .into())
+            }
+        }
+    }
+    Ok(())
+}
+
 pub async fn handle_create_mv(
     context: OptimizerContext,
     name: ObjectName,
     query: Box<Query>,
+    with_options: Vec<SqlOption>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
 
     let (table, stream_plan) = {
-        let (plan, table) = gen_create_mv_plan(&session, context.into(), query, name)?;
+        let (plan, mut table) = gen_create_mv_plan(&session, context.into(), query, name)?;
+        handle_mv_with_options(&session, &mut table, with_options)?;
         let stream_plan = plan.to_stream_prost();
         (table, stream_plan)
     };
@@ -85,9 +173,35 @@ pub mod tests {
     use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
     use risingwave_common::types::DataType;
 
+    use super::{parse_placement_labels, parse_ttl};
     use crate::catalog::gen_row_id_column_name;
     use crate::test_utils::{create_proto_file, LocalFrontend, PROTO_FILE_DATA};
 
+    #[test]
+    fn test_parse_ttl() {
+        assert_eq!(parse_ttl("7 days").unwrap(), 7 * 24 * 3600 * 1000);
+        assert_eq!(parse_ttl("1 HOUR").unwrap(), 3600 * 1000);
+        assert_eq!(parse_ttl(" 30  minutes ").unwrap(), 30 * 60 * 1000);
+        assert!(parse_ttl("0 seconds").is_err());
+        assert!(parse_ttl("7").is_err());
+        assert!(parse_ttl("seven days").is_err());
+        assert!(parse_ttl("1 week").is_err());
+    }
+
+    #[test]
+    fn test_parse_placement_labels() {
+        assert_eq!(
+            parse_placement_labels("zone=a, rack = 1").unwrap(),
+            maplit::hashmap! {
+                "zone".to_string() => "a".to_string(),
+                "rack".to_string() => "1".to_string(),
+            }
+        );
+        assert!(parse_placement_labels("zone").is_err());
+        assert!(parse_placement_labels("=a").is_err());
+        assert!(parse_placement_labels("zone=a,").is_err());
+    }
+
     #[tokio::test]
     async fn test_create_mv_handler() {
         let proto_file = create_proto_file(PROTO_FILE_DATA);
@@ -22,7 +22,7 @@ use risingwave_common::error::Result;
 use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::{Source as ProstSource, Table as ProstTable, TableSourceInfo};
 use risingwave_pb::plan::ColumnCatalog;
-use risingwave_sqlparser::ast::{ColumnDef, ObjectName};
+use risingwave_sqlparser::ast::{ColumnDef, DataType as AstDataType, ObjectName};
 
 use super::create_source::make_prost_source;
 use crate::binder::expr::bind_data_type;
@@ -39,16 +39,13 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
         let mut column_descs = Vec::with_capacity(columns.len() + 1);
         // Put the hidden row id column in the first column. This is used for PK.
         column_descs.push(row_id_column_desc());
-        // Then user columns.
-        for (i, column) in columns.into_iter().enumerate() {
+        // Then user columns. The fields of struct columns also take column ids.
+        let mut column_id = 1;
+        for column in columns {
             check_valid_column_name(&column.name.value)?;
-            column_descs.push(ColumnDesc {
-                data_type: bind_data_type(&column.data_type)?,
-                column_id: ColumnId::new((i + 1) as i32),
-                name: column.name.value,
-                field_descs: vec![],
-                type_name: "".to_string(),
-            });
+            let mut column_desc = bind_column_desc(column.name.value, &column.data_type)?;
+            column_desc.generate_increment_id(&mut column_id);
+            column_descs.push(column_desc);
         }
         column_descs
     };
@@ -64,6 +61,29 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
     Ok(columns_catalog)
 }
 
+/// Binds a column definition into a `ColumnDesc` with a placeholder column id. The fields of a
+/// struct column become its `field_descs` named `<column>.<field>`, the same as the columns mapped
+/// from a nested protobuf message.
+fn bind_column_desc(name: String, data_type: &AstDataType) -> Result<ColumnDesc> {
+    let (field_descs, type_name) = match data_type {
+        AstDataType::Struct(fields) => (
+            fields
+                .iter()
+                .map(|f| bind_column_desc(format!("{}.{}", name, f.name.value), &f.data_type))
+                .collect::<Result<Vec<_>>>()?,
+            data_type.to_string(),
+        ),
+        _ => (vec![], "".to_string()),
+    };
+    Ok(ColumnDesc {
+        data_type: bind_data_type(data_type)?,
+        column_id: ColumnId::new(0),
+        name,
+        field_descs,
+        type_name,
+    })
+}
+
 pub(crate) fn gen_create_table_plan(
     session: &SessionImpl,
     context: OptimizerContextRef,
@@ -192,4 +212,48 @@ mod tests {
 
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_create_table_with_struct() {
+        let sql = "create table t (v1 struct<a int, b struct<c varchar>>, v2 int);";
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap()
+            .clone();
+
+        // Struct fields are flattened into `field_descs` with increasing column ids.
+        let columns = source
+            .columns
+            .iter()
+            .flat_map(|c| c.column_desc.get_column_descs())
+            .map(|c| (c.name, c.column_id.get_id()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                (gen_row_id_column_name(0), 0),
+                ("v1".to_string(), 1),
+                ("v1.a".to_string(), 2),
+                ("v1.b".to_string(), 3),
+                ("v1.b.c".to_string(), 4),
+                ("v2".to_string(), 5),
+            ]
+        );
+
+        let b_type = DataType::Struct {
+            fields: vec![DataType::Varchar].into(),
+        };
+        assert_eq!(
+            source.columns[1].column_desc.data_type,
+            DataType::Struct {
+                fields: vec![DataType::Int32, b_type].into(),
+            }
+        );
+    }
 }
@@ -16,10 +16,13 @@ use std::sync::Arc;
 
 use pgwire::pg_response::PgResponse;
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_sqlparser::ast::{DropStatement, ObjectName, ObjectType, Statement};
+use risingwave_sqlparser::ast::{
+    AlterMaterializedViewOperation, DropStatement, ObjectName, ObjectType, Statement,
+};
 
 use crate::session::{OptimizerContext, SessionImpl};
 
+mod alter_mv;
 pub mod create_mv;
 pub mod create_source;
 pub mod create_table;
@@ -87,8 +90,13 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             or_replace: false,
             name,
             query,
+            with_options,
             ..
-        } => create_mv::handle_create_mv(context, name, query).await,
+        } => create_mv::handle_create_mv(context, name, query, with_options).await,
+        Statement::AlterMaterializedView {
+            name,
+            operation: AlterMaterializedViewOperation::SetParallelism { parallelism },
+        } => alter_mv::handle_alter_mv_set_parallelism(context, name, parallelism).await,
         Statement::Flush => flush::handle_flush(context).await,
         Statement::SetVariable {
             local: _,
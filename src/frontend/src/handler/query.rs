@@ -36,13 +36,7 @@ pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<
     let stmt_type = to_statement_type(&stmt);
     let session = context.session_ctx.clone();
 
-    let bound = {
-        let mut binder = Binder::new(
-            session.env().catalog_reader().read_guard(),
-            session.database().to_string(),
-        );
-        binder.bind(stmt)?
-    };
+    let bound = bind_query(&session, stmt).await?;
 
     let (data_stream, pg_descs) = distribute_execute(context, bound).await?;
 
@@ -76,6 +70,30 @@ pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<
     Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs))
 }
 
+/// Binds the query `stmt`. If it reads any system table of `rw_catalog`, it's bound again with the
+/// runtime status of the streaming jobs fetched from meta.
+pub async fn bind_query(session: &SessionImpl, stmt: Statement) -> Result<BoundStatement> {
+    let (bound, requires_streaming_status) = {
+        let mut binder = Binder::new(
+            session.env().catalog_reader().read_guard(),
+            session.database().to_string(),
+        );
+        let bound = binder.bind(stmt.clone())?;
+        (bound, binder.requires_streaming_status())
+    };
+    if !requires_streaming_status {
+        return Ok(bound);
+    }
+
+    let streaming_status = session.env().meta_client().get_streaming_status().await?;
+    let mut binder = Binder::new(
+        session.env().catalog_reader().read_guard(),
+        session.database().to_string(),
+    )
+    .with_streaming_status(streaming_status);
+    binder.bind(stmt)
+}
+
 async fn flush_for_write(session: &SessionImpl, stmt_type: StatementType) -> Result<()> {
     match stmt_type {
         StatementType::INSERT | StatementType::DELETE | StatementType::UPDATE => {
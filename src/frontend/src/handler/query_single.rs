@@ -17,8 +17,7 @@ use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::Result;
 use risingwave_sqlparser::ast::Statement;
 
-use super::query::IMPLICIT_FLUSH;
-use crate::binder::Binder;
+use super::query::{bind_query, IMPLICIT_FLUSH};
 use crate::handler::util::{to_pg_field, to_pg_rows};
 use crate::planner::Planner;
 use crate::scheduler::{ExecutionContext, ExecutionContextRef};
@@ -28,13 +27,7 @@ pub async fn handle_query_single(context: OptimizerContext, stmt: Statement) ->
     let stmt_type = to_statement_type(&stmt);
     let session = context.session_ctx.clone();
 
-    let bound = {
-        let mut binder = Binder::new(
-            session.env().catalog_reader().read_guard(),
-            session.database().to_string(),
-        );
-        binder.bind(stmt)?
-    };
+    let bound = bind_query(&session, stmt).await?;
 
     let (plan, pg_descs) = {
         // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
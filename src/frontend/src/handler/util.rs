@@ -26,10 +26,41 @@ fn pg_value_format(d: ScalarRefImpl) -> String {
         ScalarRefImpl::Bool(b) => if b { "t" } else { "f" }.to_string(),
         ScalarRefImpl::Float32(v) => pg_float_format(v),
         ScalarRefImpl::Float64(v) => pg_float_format(v),
+        ScalarRefImpl::List(list) => format!(
+            "{{{}}}",
+            list.values_ref()
+                .into_iter()
+                .map(|v| v.map_or_else(|| "NULL".to_string(), pg_value_format))
+                .join(",")
+        ),
+        ScalarRefImpl::Struct(s) => format!(
+            "({})",
+            s.fields_ref()
+                .into_iter()
+                .map(|v| pg_composite_field_format(v.map(pg_value_format)))
+                .join(",")
+        ),
         _ => d.to_string(),
     }
 }
 
+/// Format a field of a composite value like `record_out` in postgres. Nulls are left empty, and
+/// fields that are empty or contain special characters are quoted, with quotes and backslashes
+/// inside doubled.
+fn pg_composite_field_format(field: Option<String>) -> String {
+    match field {
+        None => String::new(),
+        Some(s)
+            if s.is_empty()
+                || s.chars()
+                    .any(|c| matches!(c, '"' | '\\' | '(' | ')' | ',') || c.is_whitespace()) =>
+        {
+            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\"\""))
+        }
+        Some(s) => s,
+    }
+}
+
 fn pg_float_format<T: Float + ToString>(v: T) -> String {
     if v.is_infinite() {
         if v.is_sign_positive() {
@@ -76,7 +107,7 @@ pub fn data_type_to_type_oid(data_type: DataType) -> TypeOid {
         DataType::Time => TypeOid::Time,
         DataType::Timestamp => TypeOid::Timestamp,
         DataType::Timestampz => TypeOid::Timestampz,
-        DataType::Decimal => TypeOid::Decimal,
+        DataType::Decimal { .. } => TypeOid::Decimal,
         DataType::Interval => TypeOid::Varchar,
         DataType::Struct { .. } => TypeOid::Varchar,
         DataType::List { .. } => TypeOid::Varchar,
@@ -86,6 +117,7 @@ pub fn data_type_to_type_oid(data_type: DataType) -> TypeOid {
 #[cfg(test)]
 mod tests {
     use risingwave_common::array::*;
+    use risingwave_common::types::{Scalar, ScalarImpl};
     use risingwave_common::{column, column_nonnull};
 
     use super::*;
@@ -151,5 +183,16 @@ mod tests {
         assert_eq!(&f(S::Float64(f64::NEG_INFINITY.into())), "-Infinity");
         assert_eq!(&f(S::Bool(true)), "t");
         assert_eq!(&f(S::Bool(false)), "f");
+
+        let list = ListValue::new(vec![Some(ScalarImpl::Bool(true)), None]);
+        assert_eq!(&f(S::List(list.as_scalar_ref())), "{t,NULL}");
+
+        let s = StructValue::new(vec![
+            Some(ScalarImpl::Int32(1)),
+            Some(ScalarImpl::Utf8("a \"b\"".to_string())),
+            None,
+            Some(ScalarImpl::Utf8("".to_string())),
+        ]);
+        assert_eq!(&f(S::Struct(s.as_scalar_ref())), r#"(1,"a ""b""",,"")"#);
     }
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use risingwave_common::error::Result;
+use risingwave_pb::meta::GetStreamingStatusResponse;
 use risingwave_rpc_client::{HummockMetaClient, MetaClient};
 
 /// A wrapper around the `MetaClient` that only provides a minor set of meta rpc.
@@ -26,7 +27,11 @@ pub trait FrontendMetaClient: Send + Sync {
 
     async fn flush(&self) -> Result<()>;
 
+    async fn reschedule(&self, table_id: u32, parallelism: u32) -> Result<()>;
+
     async fn unpin_snapshot(&self, epoch: u64) -> Result<()>;
+
+    async fn get_streaming_status(&self) -> Result<GetStreamingStatusResponse>;
 }
 
 pub struct FrontendMetaClientImpl(pub MetaClient);
@@ -41,7 +46,15 @@ impl FrontendMetaClient for FrontendMetaClientImpl {
         self.0.flush().await
     }
 
+    async fn reschedule(&self, table_id: u32, parallelism: u32) -> Result<()> {
+        self.0.reschedule(table_id, parallelism, vec![]).await
+    }
+
     async fn unpin_snapshot(&self, epoch: u64) -> Result<()> {
         self.0.unpin_snapshot(&[epoch]).await
     }
+
+    async fn get_streaming_status(&self) -> Result<GetStreamingStatusResponse> {
+        self.0.get_streaming_status().await
+    }
 }
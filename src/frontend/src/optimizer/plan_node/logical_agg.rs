@@ -27,7 +27,9 @@ use super::{
     BatchHashAgg, BatchSimpleAgg, ColPrunable, PlanBase, PlanNode, PlanRef, PlanTreeNodeUnary,
     StreamHashAgg, StreamSimpleAgg, ToBatch, ToStream,
 };
-use crate::expr::{AggCall, Expr, ExprImpl, ExprRewriter, ExprType, FunctionCall, InputRef};
+use crate::expr::{
+    fmt_agg_call, AggCall, Expr, ExprImpl, ExprRewriter, ExprType, FunctionCall, InputRef,
+};
 use crate::optimizer::plan_node::LogicalProject;
 use crate::optimizer::property::Distribution;
 use crate::utils::ColIndexMapping;
@@ -43,15 +45,14 @@ pub struct PlanAggCall {
 
     /// Column indexes of input columns
     pub inputs: Vec<InputRef>,
+
+    /// Whether the inputs are deduplicated before aggregation
+    pub distinct: bool,
 }
 
 impl fmt::Debug for PlanAggCall {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut builder = f.debug_tuple(&format!("{}", self.agg_kind));
-        self.inputs.iter().for_each(|child| {
-            builder.field(child);
-        });
-        builder.finish()
+        fmt_agg_call(f, &self.agg_kind, &self.inputs, self.distinct)
     }
 }
 
@@ -65,8 +66,7 @@ impl PlanAggCall {
                 .iter()
                 .map(InputRef::to_agg_arg_protobuf)
                 .collect(),
-            // TODO: support distinct
-            distinct: false,
+            distinct: self.distinct,
         }
     }
 
@@ -75,6 +75,7 @@ impl PlanAggCall {
             agg_kind: AggKind::Count,
             return_type: DataType::Int64,
             inputs: vec![],
+            distinct: false,
         }
     }
 }
@@ -153,7 +154,7 @@ impl ExprRewriter for ExprHandler {
     // Note that the rewriter does not traverse into inputs of agg calls.
     fn rewrite_agg_call(&mut self, agg_call: AggCall) -> ExprImpl {
         let return_type = agg_call.return_type();
-        let (agg_kind, inputs) = agg_call.decompose();
+        let (agg_kind, inputs, distinct) = agg_call.decompose();
 
         let mut index = self.project.len();
         let mut input_refs = vec![];
@@ -175,17 +176,19 @@ impl ExprRewriter for ExprHandler {
             let left_return_type =
                 AggCall::infer_return_type(&AggKind::Sum, &[input_refs[0].return_type()]).unwrap();
 
-            // Rewrite avg to cast(sum as avg_return_type) / count.
+            // Rewrite avg to cast(sum as avg_return_type) / count. The division is cast back to
+            // avg_return_type as well, which rounds it when the input is a constrained numeric.
             self.agg_calls.push(PlanAggCall {
                 agg_kind: AggKind::Sum,
                 return_type: left_return_type.clone(),
                 inputs: input_refs.clone(),
+                distinct,
             });
             let left = ExprImpl::from(InputRef::new(
                 self.group_key_len + self.agg_calls.len() - 1,
                 left_return_type,
             ))
-            .cast_implicit(return_type)
+            .cast_implicit(return_type.clone())
             .unwrap();
 
             let right_return_type =
@@ -196,6 +199,7 @@ impl ExprRewriter for ExprHandler {
                 agg_kind: AggKind::Count,
                 return_type: right_return_type.clone(),
                 inputs: input_refs,
+                distinct,
             });
 
             let right = InputRef::new(
@@ -204,11 +208,14 @@ impl ExprRewriter for ExprHandler {
             );
 
             ExprImpl::from(FunctionCall::new(ExprType::Divide, vec![left, right.into()]).unwrap())
+                .cast_implicit(return_type)
+                .unwrap()
         } else {
             self.agg_calls.push(PlanAggCall {
                 agg_kind,
                 return_type: return_type.clone(),
                 inputs: input_refs,
+                distinct,
             });
             ExprImpl::from(InputRef::new(
                 self.group_key_len + self.agg_calls.len() - 1,
@@ -538,6 +545,7 @@ impl ToStream for LogicalAgg {
                 agg_kind: AggKind::Count,
                 return_type: DataType::Int64,
                 inputs: vec![],
+                distinct: false,
             },
         );
         agg_call_alias.insert(0, None);
@@ -619,7 +627,8 @@ mod tests {
 
         // Test case: select v1, min(v2) from test group by v1;
         {
-            let min_v2 = AggCall::new(AggKind::Min, vec![input_ref_2.clone().into()]).unwrap();
+            let min_v2 =
+                AggCall::new(AggKind::Min, vec![input_ref_2.clone().into()], false).unwrap();
             let select_exprs = vec![input_ref_1.clone().into(), min_v2.into()];
             let group_exprs = vec![input_ref_1.clone().into()];
 
@@ -637,8 +646,10 @@ mod tests {
 
         // Test case: select v1, min(v2) + max(v3) from t group by v1;
         {
-            let min_v2 = AggCall::new(AggKind::Min, vec![input_ref_2.clone().into()]).unwrap();
-            let max_v3 = AggCall::new(AggKind::Max, vec![input_ref_3.clone().into()]).unwrap();
+            let min_v2 =
+                AggCall::new(AggKind::Min, vec![input_ref_2.clone().into()], false).unwrap();
+            let max_v3 =
+                AggCall::new(AggKind::Max, vec![input_ref_3.clone().into()], false).unwrap();
             let func_call =
                 FunctionCall::new(ExprType::Add, vec![min_v2.into(), max_v3.into()]).unwrap();
             let select_exprs = vec![input_ref_1.clone().into(), ExprImpl::from(func_call)];
@@ -671,7 +682,7 @@ mod tests {
                 vec![input_ref_1.into(), input_ref_3.into()],
             )
             .unwrap();
-            let agg_call = AggCall::new(AggKind::Min, vec![v1_mult_v3.into()]).unwrap();
+            let agg_call = AggCall::new(AggKind::Min, vec![v1_mult_v3.into()], false).unwrap();
             let select_exprs = vec![input_ref_2.clone().into(), agg_call.into()];
             let group_exprs = vec![input_ref_2.into()];
 
@@ -716,6 +727,7 @@ mod tests {
             agg_kind: AggKind::Min,
             return_type: ty.clone(),
             inputs: vec![InputRef::new(2, ty.clone())],
+            distinct: false,
         };
         let agg = LogicalAgg::new(
             vec![agg_call],
@@ -775,6 +787,7 @@ mod tests {
             agg_kind: AggKind::Min,
             return_type: ty.clone(),
             inputs: vec![InputRef::new(2, ty.clone())],
+            distinct: false,
         };
         let agg = LogicalAgg::new(
             vec![agg_call],
@@ -843,11 +856,13 @@ mod tests {
                 agg_kind: AggKind::Min,
                 return_type: ty.clone(),
                 inputs: vec![InputRef::new(2, ty.clone())],
+                distinct: false,
             },
             PlanAggCall {
                 agg_kind: AggKind::Max,
                 return_type: ty.clone(),
                 inputs: vec![InputRef::new(1, ty.clone())],
+                distinct: false,
             },
         ];
         let agg = LogicalAgg::new(
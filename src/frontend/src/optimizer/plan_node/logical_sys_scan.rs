@@ -0,0 +1,130 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use risingwave_common::array::Row;
+use risingwave_common::catalog::Schema;
+
+use super::{BatchSysSeqScan, ColPrunable, PlanBase, PlanNode, PlanRef, ToBatch, ToStream};
+use crate::session::OptimizerContextRef;
+use crate::utils::ColIndexMapping;
+
+/// `LogicalSysScan` scans a system table, whose rows are generated when binding.
+#[derive(Debug, Clone)]
+pub struct LogicalSysScan {
+    pub base: PlanBase,
+    table_name: String,
+    rows: Arc<[Row]>,
+}
+
+impl LogicalSysScan {
+    /// Create a [`LogicalSysScan`] node. Used internally by optimizer.
+    pub fn new(
+        table_name: String,
+        schema: Schema,
+        rows: Vec<Row>,
+        ctx: OptimizerContextRef,
+    ) -> Self {
+        let base = PlanBase::new_logical(ctx, schema, vec![]);
+        Self {
+            base,
+            table_name,
+            rows: rows.into(),
+        }
+    }
+
+    /// Create a [`LogicalSysScan`] node. Used by planner.
+    pub fn create(
+        table_name: String,
+        schema: Schema,
+        rows: Vec<Row>,
+        ctx: OptimizerContextRef,
+    ) -> PlanRef {
+        Self::new(table_name, schema, rows, ctx).into()
+    }
+
+    /// Get a reference to the logical sys scan's table name.
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    /// Get a reference to the logical sys scan's rows.
+    pub fn rows(&self) -> &[Row] {
+        self.rows.as_ref()
+    }
+
+    pub fn column_names(&self) -> Vec<String> {
+        self.schema()
+            .fields()
+            .iter()
+            .map(|f| f.name.clone())
+            .collect()
+    }
+}
+
+impl_plan_tree_node_for_leaf! { LogicalSysScan }
+
+impl fmt::Display for LogicalSysScan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LogicalSysScan {{ table: {}, columns: [{}] }}",
+            self.table_name,
+            self.column_names().join(", ")
+        )
+    }
+}
+
+impl ColPrunable for LogicalSysScan {
+    fn prune_col(&self, required_cols: &FixedBitSet) -> PlanRef {
+        self.must_contain_columns(required_cols);
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| Row(required_cols.ones().map(|i| row[i].clone()).collect()))
+            .collect();
+        let fields = required_cols
+            .ones()
+            .map(|i| self.schema().fields[i].clone())
+            .collect_vec();
+        Self::new(
+            self.table_name.clone(),
+            Schema { fields },
+            rows,
+            self.base.ctx.clone(),
+        )
+        .into()
+    }
+}
+
+impl ToBatch for LogicalSysScan {
+    fn to_batch(&self) -> PlanRef {
+        BatchSysSeqScan::new(self.clone()).into()
+    }
+}
+
+impl ToStream for LogicalSysScan {
+    fn to_stream(&self) -> PlanRef {
+        unreachable!("system tables are rejected when binding streaming jobs")
+    }
+
+    fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
+        unreachable!("system tables are rejected when binding streaming jobs")
+    }
+}
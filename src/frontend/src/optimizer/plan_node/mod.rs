@@ -220,6 +220,8 @@ mod batch_project;
 mod batch_seq_scan;
 mod batch_simple_agg;
 mod batch_sort;
+mod batch_sys_seq_scan;
+mod batch_table_function;
 mod batch_values;
 mod logical_agg;
 mod logical_apply;
@@ -231,6 +233,8 @@ mod logical_limit;
 mod logical_project;
 mod logical_scan;
 mod logical_source;
+mod logical_sys_scan;
+mod logical_table_function;
 mod logical_topn;
 mod logical_values;
 mod stream_exchange;
@@ -254,6 +258,8 @@ pub use batch_project::BatchProject;
 pub use batch_seq_scan::BatchSeqScan;
 pub use batch_simple_agg::BatchSimpleAgg;
 pub use batch_sort::BatchSort;
+pub use batch_sys_seq_scan::BatchSysSeqScan;
+pub use batch_table_function::BatchTableFunction;
 pub use batch_values::BatchValues;
 pub use logical_agg::{LogicalAgg, PlanAggCall};
 pub use logical_apply::LogicalApply;
@@ -265,6 +271,8 @@ pub use logical_limit::LogicalLimit;
 pub use logical_project::LogicalProject;
 pub use logical_scan::LogicalScan;
 pub use logical_source::LogicalSource;
+pub use logical_sys_scan::LogicalSysScan;
+pub use logical_table_function::LogicalTableFunction;
 pub use logical_topn::LogicalTopN;
 pub use logical_values::LogicalValues;
 pub use stream_exchange::StreamExchange;
@@ -308,6 +316,8 @@ macro_rules! for_all_plan_nodes {
             ,{ Logical, Values }
             ,{ Logical, Limit }
             ,{ Logical, TopN }
+            ,{ Logical, TableFunction }
+            ,{ Logical, SysScan }
             // ,{ Logical, Sort } we don't need a LogicalSort, just require the Order
             ,{ Batch, SimpleAgg }
             ,{ Batch, HashAgg }
@@ -321,6 +331,8 @@ macro_rules! for_all_plan_nodes {
             ,{ Batch, Sort }
             ,{ Batch, Exchange }
             ,{ Batch, Limit }
+            ,{ Batch, TableFunction }
+            ,{ Batch, SysSeqScan }
             ,{ Stream, Project }
             ,{ Stream, Filter }
             ,{ Stream, TableScan }
@@ -351,6 +363,8 @@ macro_rules! for_logical_plan_nodes {
             ,{ Logical, Values }
             ,{ Logical, Limit }
             ,{ Logical, TopN }
+            ,{ Logical, TableFunction }
+            ,{ Logical, SysScan }
             // ,{ Logical, Sort} not sure if we will support Order by clause in subquery/view/MV
             // if we dont support thatk, we don't need LogicalSort, just require the Order at the top of query
         }
@@ -375,6 +389,8 @@ macro_rules! for_batch_plan_nodes {
             ,{ Batch, Exchange }
             ,{ Batch, Insert }
             ,{ Batch, Delete }
+            ,{ Batch, TableFunction }
+            ,{ Batch, SysSeqScan }
         }
     };
 }
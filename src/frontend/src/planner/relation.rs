@@ -14,16 +14,18 @@
 
 use std::rc::Rc;
 
+use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
 
 use crate::binder::{
-    BoundBaseTable, BoundJoin, BoundSource, BoundWindowTableFunction, Relation,
+    BoundBaseTable, BoundJoin, BoundSource, BoundSystemTable, BoundWindowTableFunction, Relation,
     WindowTableFunctionKind,
 };
-use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef};
+use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef, TableFunction};
 use crate::optimizer::plan_node::{
-    LogicalJoin, LogicalProject, LogicalScan, LogicalSource, PlanRef,
+    LogicalJoin, LogicalProject, LogicalScan, LogicalSource, LogicalSysScan, LogicalTableFunction,
+    PlanRef,
 };
 use crate::planner::Planner;
 
@@ -31,11 +33,13 @@ impl Planner {
     pub(super) fn plan_relation(&mut self, relation: Relation) -> Result<PlanRef> {
         match relation {
             Relation::BaseTable(t) => self.plan_base_table(*t),
+            Relation::SystemTable(t) => self.plan_sys_table(*t),
             // TODO: order is ignored in the subquery
             Relation::Subquery(q) => Ok(self.plan_query(q.query)?.as_subplan()),
             Relation::Join(join) => self.plan_join(*join),
             Relation::WindowTableFunction(tf) => self.plan_window_table_function(*tf),
             Relation::Source(s) => self.plan_source(*s),
+            Relation::TableFunction(tf) => self.plan_table_function(*tf),
         }
     }
 
@@ -47,10 +51,23 @@ impl Planner {
         )
     }
 
+    pub(super) fn plan_sys_table(&mut self, sys_table: BoundSystemTable) -> Result<PlanRef> {
+        Ok(LogicalSysScan::create(
+            sys_table.name,
+            Schema::new(sys_table.fields),
+            sys_table.rows,
+            self.ctx(),
+        ))
+    }
+
     pub(super) fn plan_source(&mut self, source: BoundSource) -> Result<PlanRef> {
         Ok(LogicalSource::new(Rc::new(source.catalog), self.ctx()).into())
     }
 
+    pub(super) fn plan_table_function(&mut self, table_function: TableFunction) -> Result<PlanRef> {
+        Ok(LogicalTableFunction::new(table_function, self.ctx()).into())
+    }
+
     pub(super) fn plan_join(&mut self, join: BoundJoin) -> Result<PlanRef> {
         let left = self.plan_relation(join.left)?;
         let right = self.plan_relation(join.right)?;
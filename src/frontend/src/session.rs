@@ -170,7 +170,7 @@ impl FrontendEnv {
             .unwrap();
         // Register in meta by calling `AddWorkerNode` RPC.
         meta_client
-            .register(&frontend_address, WorkerType::Frontend)
+            .register(&frontend_address, WorkerType::Frontend, HashMap::new())
             .await?;
 
         let (heartbeat_join_handle, heartbeat_shutdown_sender) = MetaClient::start_heartbeat_loop(
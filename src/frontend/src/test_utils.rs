@@ -27,6 +27,7 @@ use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::{
     Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
 };
+use risingwave_pb::meta::GetStreamingStatusResponse;
 use risingwave_pb::stream_plan::StreamNode;
 use risingwave_sqlparser::ast::Statement;
 use risingwave_sqlparser::parser::Parser;
@@ -246,9 +247,17 @@ impl FrontendMetaClient for MockFrontendMetaClient {
         Ok(())
     }
 
+    async fn reschedule(&self, _table_id: u32, _parallelism: u32) -> Result<()> {
+        Ok(())
+    }
+
     async fn unpin_snapshot(&self, _epoch: u64) -> Result<()> {
         Ok(())
     }
+
+    async fn get_streaming_status(&self) -> Result<GetStreamingStatusResponse> {
+        Ok(GetStreamingStatusResponse::default())
+    }
 }
 pub static PROTO_FILE_DATA: &str = r#"
     syntax = "proto3";
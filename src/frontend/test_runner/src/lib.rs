@@ -76,6 +76,9 @@ pub struct TestCase {
     /// Error of optimizer
     pub optimizer_error: Option<String>,
 
+    /// Error of generating the create MV plan
+    pub stream_error: Option<String>,
+
     /// Support using file content or file location to create source.
     pub create_source: Option<CreateSource>,
 }
@@ -119,6 +122,9 @@ pub struct TestCaseResult {
 
     /// Error of optimizer
     pub optimizer_error: Option<String>,
+
+    /// Error of generating the create MV plan
+    pub stream_error: Option<String>,
 }
 
 impl TestCaseResult {
@@ -133,6 +139,9 @@ impl TestCaseResult {
         if original_test_case.optimizer_error.is_none() && let Some(ref err) = self.optimizer_error {
             return Err(anyhow!("unexpected optimizer error: {}", err));
         }
+        if original_test_case.stream_error.is_none() && let Some(ref err) = self.stream_error {
+            return Err(anyhow!("unexpected stream error: {}", err));
+        }
 
         let case = TestCase {
             id: original_test_case.id.clone(),
@@ -147,6 +156,7 @@ impl TestCaseResult {
             batch_plan_proto: self.batch_plan_proto,
             planner_error: self.planner_error,
             optimizer_error: self.optimizer_error,
+            stream_error: self.stream_error,
             binder_error: self.binder_error,
             create_source: original_test_case.create_source.clone(),
         };
@@ -247,9 +257,10 @@ impl TestCase {
                     or_replace: false,
                     name,
                     query,
+                    with_options,
                     ..
                 } => {
-                    create_mv::handle_create_mv(context, name, query).await?;
+                    create_mv::handle_create_mv(context, name, query, with_options).await?;
                 }
                 Statement::Drop(drop_statement) => {
                     let table_object_name = ObjectName(vec![drop_statement.name]);
@@ -320,19 +331,28 @@ impl TestCase {
             }
         }
 
-        if self.stream_plan.is_some() || self.stream_plan_proto.is_some() {
+        if self.stream_plan.is_some()
+            || self.stream_plan_proto.is_some()
+            || self.stream_error.is_some()
+        {
             let q = if let Statement::Query(q) = stmt {
                 q.as_ref().clone()
             } else {
                 return Err(anyhow!("expect a query"));
             };
 
-            let (stream_plan, table) = create_mv::gen_create_mv_plan(
+            let (stream_plan, table) = match create_mv::gen_create_mv_plan(
                 &session,
                 context,
                 Box::new(q),
                 ObjectName(vec!["test".into()]),
-            )?;
+            ) {
+                Ok(plan) => plan,
+                Err(err) => {
+                    ret.stream_error = Some(err.to_string());
+                    return Ok(ret);
+                }
+            };
 
             // Only generate stream_plan if it is specified in test case
             if self.stream_plan.is_some() {
@@ -364,6 +384,7 @@ fn check_result(expected: &TestCase, actual: &TestCaseResult) -> Result<()> {
         &expected.optimizer_error,
         &actual.optimizer_error,
     )?;
+    check_err("stream", &expected.stream_error, &actual.stream_error)?;
     check_option_plan_eq("logical_plan", &expected.logical_plan, &actual.logical_plan)?;
     check_option_plan_eq(
         "optimized_logical_plan",
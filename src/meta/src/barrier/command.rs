@@ -12,18 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use futures::future::try_join_all;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::{Result, RwError, ToRwResult};
+use risingwave_connector::{encode_splits, SplitImpl};
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::barrier::Mutation;
-use risingwave_pb::data::{Actors, AddMutation, NothingMutation, StopMutation};
+use risingwave_pb::data::{
+    Actors, AddMutation, ConnectorSplits, NothingMutation, RescheduleMutation,
+    SourceChangeSplitMutation, StopMutation,
+};
 use risingwave_pb::stream_service::DropActorsRequest;
 use uuid::Uuid;
 
 use super::info::BarrierActorInfo;
+use crate::cluster::WorkerId;
 use crate::manager::StreamClientsRef;
 use crate::model::{ActorId, TableFragments};
 use crate::storage::MetaStore;
@@ -60,13 +65,52 @@ pub enum Command {
         table_sink_map: HashMap<TableId, Vec<ActorId>>,
         dispatches: HashMap<ActorId, Vec<ActorInfo>>,
     },
+
+    /// `SourceSplitAssignment` command generates a `Splits` barrier to reassign splits to the
+    /// given stream source actors, whose existing splits are replaced.
+    ///
+    /// After the barrier is collected, the new assignment is persisted in the table fragments, so
+    /// that it's restored on recovery.
+    SourceSplitAssignment(HashMap<ActorId, Vec<SplitImpl>>),
+
+    /// `RescheduleMaterializedView` command generates a `Reschedule` barrier to replace the actors
+    /// of a materialized view with the rebuilt ones in `new_table_fragments`. On this barrier, the
+    /// upstream actors start to dispatch to the new `Chain` actors, and the old actors are
+    /// stopped.
+    ///
+    /// Barriers from both the old and the new actors will be collected.
+    /// After the barrier is collected, it notifies the local stream manager of compute nodes to
+    /// drop the old actors, and then replaces the table fragments info in meta store.
+    RescheduleMaterializedView {
+        old_table_fragments: TableFragments,
+        new_table_fragments: TableFragments,
+        dispatches: HashMap<ActorId, Vec<ActorInfo>>,
+    },
 }
 
 impl Command {
-    pub fn checkpoint() -> Self {
+    /// A periodic barrier that changes nothing in the graph.
+    pub fn barrier() -> Self {
         Self::Plain(Mutation::Nothing(NothingMutation {}))
     }
 
+    /// Returns whether this is a periodic barrier that changes nothing in the graph.
+    pub fn is_barrier(&self) -> bool {
+        matches!(self, Command::Plain(Mutation::Nothing(_)))
+    }
+
+    /// Returns a short name of the command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Plain(Mutation::Nothing(_)) => "Barrier",
+            Command::Plain(_) => "Plain",
+            Command::DropMaterializedView(_) => "DropMaterializedView",
+            Command::CreateMaterializedView { .. } => "CreateMaterializedView",
+            Command::SourceSplitAssignment(_) => "SourceSplitAssignment",
+            Command::RescheduleMaterializedView { .. } => "RescheduleMaterializedView",
+        }
+    }
+
     pub fn creating_table_id(&self) -> Option<TableId> {
         match self {
             Command::CreateMaterializedView {
@@ -91,7 +135,10 @@ pub struct CommandContext<'a, S> {
     pub prev_epoch: u64,
     pub curr_epoch: u64,
 
-    command: Command,
+    pub command: Command,
+
+    /// Whether the states should be synced and the epochs committed on this barrier.
+    pub checkpoint: bool,
 }
 
 impl<'a, S> CommandContext<'a, S> {
@@ -102,6 +149,7 @@ impl<'a, S> CommandContext<'a, S> {
         prev_epoch: u64,
         curr_epoch: u64,
         command: Command,
+        checkpoint: bool,
     ) -> Self {
         Self {
             fragment_manager,
@@ -110,6 +158,7 @@ impl<'a, S> CommandContext<'a, S> {
             prev_epoch,
             curr_epoch,
             command,
+            checkpoint,
         }
     }
 }
@@ -142,16 +191,50 @@ where
                     .collect();
                 Mutation::Add(AddMutation { actors })
             }
+
+            Command::SourceSplitAssignment(assignment) => {
+                let actor_splits = assignment
+                    .iter()
+                    .map(|(&actor_id, splits)| {
+                        let (split_type, splits) = encode_splits(splits).to_rw_result()?;
+                        Ok((actor_id, ConnectorSplits { split_type, splits }))
+                    })
+                    .collect::<Result<_>>()?;
+                Mutation::Splits(SourceChangeSplitMutation { actor_splits })
+            }
+
+            Command::RescheduleMaterializedView {
+                old_table_fragments,
+                dispatches,
+                ..
+            } => {
+                let adds = dispatches
+                    .iter()
+                    .map(|(&up_actor_id, down_actor_infos)| {
+                        (
+                            up_actor_id,
+                            Actors {
+                                info: down_actor_infos.to_vec(),
+                            },
+                        )
+                    })
+                    .collect();
+                Mutation::Reschedule(RescheduleMutation {
+                    adds,
+                    stops: old_table_fragments.actor_ids(),
+                })
+            }
         };
 
         Ok(mutation)
     }
 
-    /// For `CreateMaterializedView`, returns the actors of the `Chain` nodes. For other commands,
-    /// returns an empty set.
+    /// For `CreateMaterializedView` and `RescheduleMaterializedView`, returns the actors of the new
+    /// `Chain` nodes. For other commands, returns an empty set.
     pub fn actors_to_finish(&self) -> HashSet<ActorId> {
         match &self.command {
-            Command::CreateMaterializedView { dispatches, .. } => dispatches
+            Command::CreateMaterializedView { dispatches, .. }
+            | Command::RescheduleMaterializedView { dispatches, .. } => dispatches
                 .iter()
                 .flat_map(|(_, down_actor_infos)| down_actor_infos.iter().map(|info| info.actor_id))
                 .collect(),
@@ -166,26 +249,11 @@ where
             Command::Plain(_) => {}
 
             Command::DropMaterializedView(table_id) => {
-                // Tell compute nodes to drop actors.
+                // Tell compute nodes to drop actors, and delete their states in the epoch of this
+                // barrier.
                 let node_actors = self.fragment_manager.table_node_actors(table_id).await?;
-                let futures = node_actors.iter().map(|(node_id, actors)| {
-                    let node = self.info.node_map.get(node_id).unwrap();
-                    let request_id = Uuid::new_v4().to_string();
-
-                    async move {
-                        let mut client = self.clients.get(node).await?;
-                        tracing::debug!(request_id = %request_id, node = node_id, actors = ?actors, "drop actors");
-                        let request = DropActorsRequest {
-                            request_id,
-                            actor_ids: actors.to_owned(),
-                        };
-                        client.drop_actors(request).await.to_rw_result()?;
-
-                        Ok::<_, RwError>(())
-                    }
-                });
-
-                try_join_all(futures).await?;
+                self.drop_actors(&node_actors, Some(self.curr_epoch))
+                    .await?;
 
                 // Drop fragment info in meta store.
                 self.fragment_manager.drop_table_fragments(table_id).await?;
@@ -220,8 +288,67 @@ where
                     )
                     .await?;
             }
+
+            Command::SourceSplitAssignment(assignment) => {
+                self.fragment_manager
+                    .update_actor_splits(assignment)
+                    .await?;
+            }
+
+            Command::RescheduleMaterializedView {
+                old_table_fragments,
+                new_table_fragments,
+                dispatches,
+            } => {
+                // Tell compute nodes to drop the old actors.
+                self.drop_actors(&old_table_fragments.node_actor_ids(), None)
+                    .await?;
+
+                let dispatches = dispatches
+                    .iter()
+                    .map(|(&up_actor_id, down_actor_infos)| {
+                        (
+                            up_actor_id,
+                            down_actor_infos.iter().map(|info| info.actor_id).collect(),
+                        )
+                    })
+                    .collect();
+                self.fragment_manager
+                    .finish_reschedule_table_fragments(new_table_fragments, &dispatches)
+                    .await?;
+            }
         }
 
         Ok(())
     }
+
+    /// Drops the given actors on the compute nodes. If `delete_states_epoch` is given, their states
+    /// are deleted in that epoch as well.
+    async fn drop_actors(
+        &self,
+        node_actors: &BTreeMap<WorkerId, Vec<ActorId>>,
+        delete_states_epoch: Option<u64>,
+    ) -> Result<()> {
+        let futures = node_actors.iter().map(|(node_id, actors)| {
+            let node = self.info.node_map.get(node_id).unwrap();
+            let request_id = Uuid::new_v4().to_string();
+
+            async move {
+                let mut client = self.clients.get(node).await?;
+                tracing::debug!(request_id = %request_id, node = node_id, actors = ?actors, "drop actors");
+                let request = DropActorsRequest {
+                    request_id,
+                    actor_ids: actors.to_owned(),
+                    delete_states_epoch: delete_states_epoch.unwrap_or_default(),
+                };
+                client.drop_actors(request).await.to_rw_result()?;
+
+                Ok::<_, RwError>(())
+            }
+        });
+
+        try_join_all(futures).await?;
+
+        Ok(())
+    }
 }
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use risingwave_pb::common::WorkerNode;
 
@@ -52,6 +52,30 @@ impl BarrierActorInfo {
         }
     }
 
+    /// Returns the info that only contains `actor_ids`, on the same nodes.
+    pub fn subset(&self, actor_ids: &HashSet<ActorId>) -> Self {
+        let filter = |actor_map: &HashMap<WorkerId, Vec<ActorId>>| {
+            actor_map
+                .iter()
+                .map(|(node_id, actors)| {
+                    let actors = actors
+                        .iter()
+                        .copied()
+                        .filter(|actor_id| actor_ids.contains(actor_id))
+                        .collect::<Vec<_>>();
+                    (*node_id, actors)
+                })
+                .filter(|(_, actors)| !actors.is_empty())
+                .collect()
+        };
+
+        Self {
+            node_map: self.node_map.clone(),
+            actor_map: filter(&self.actor_map),
+            actor_map_to_send: filter(&self.actor_map_to_send),
+        }
+    }
+
     // TODO: should only collect from reachable actors, for mv on mv
     pub fn actor_ids_to_collect(&self, node_id: &WorkerId) -> impl Iterator<Item = ActorId> {
         self.actor_map
@@ -12,14 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::iter::once;
 use std::sync::Arc;
 use std::time::Duration;
 
 use futures::future::try_join_all;
 use itertools::Itertools;
-use risingwave_common::catalog::TableId;
+use parking_lot::Mutex;
 use risingwave_common::error::{ErrorCode, Result, RwError, ToRwResult};
 use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_pb::common::worker_node::State::Running;
@@ -36,10 +36,11 @@ pub use self::command::Command;
 use self::command::CommandContext;
 use self::info::BarrierActorInfo;
 use self::notifier::{Notifier, UnfinishedNotifiers};
+pub use self::status::{BarrierStatus, InFlightBarrier};
 use crate::cluster::{ClusterManagerRef, META_NODE_ID};
 use crate::hummock::HummockManagerRef;
 use crate::manager::{CatalogManagerRef, MetaSrvEnv, INVALID_EPOCH};
-use crate::model::BarrierManagerState;
+use crate::model::{ActorId, BarrierManagerState};
 use crate::rpc::metrics::MetaMetrics;
 use crate::storage::MetaStore;
 use crate::stream::FragmentManagerRef;
@@ -48,6 +49,9 @@ mod command;
 mod info;
 mod notifier;
 mod recovery;
+mod status;
+#[cfg(test)]
+mod tests;
 
 type Scheduled = (Command, SmallVec<[Notifier; 1]>);
 
@@ -71,10 +75,10 @@ impl ScheduledBarriers {
     async fn pop_or_default(&self) -> Scheduled {
         let mut buffer = self.buffer.write().await;
 
-        // If no command scheduled, create periodic checkpoint barrier by default.
+        // If no command scheduled, create periodic barrier by default.
         buffer
             .pop_front()
-            .unwrap_or_else(|| (Command::checkpoint(), Default::default()))
+            .unwrap_or_else(|| (Command::barrier(), Default::default()))
     }
 
     /// Wait for at least one scheduled barrier in the buffer.
@@ -99,14 +103,14 @@ impl ScheduledBarriers {
     }
 
     /// Attach `new_notifiers` to the very first scheduled barrier. If there's no one scheduled, a
-    /// default checkpoint barrier will be created.
+    /// default barrier will be created.
     async fn attach_notifiers(&self, new_notifiers: impl IntoIterator<Item = Notifier>) {
         let mut buffer = self.buffer.write().await;
         match buffer.front_mut() {
             Some((_, notifiers)) => notifiers.extend(new_notifiers),
             None => {
-                // If no command scheduled, create periodic checkpoint barrier by default.
-                buffer.push_back((Command::checkpoint(), new_notifiers.into_iter().collect()));
+                // If no command scheduled, create periodic barrier by default.
+                buffer.push_back((Command::barrier(), new_notifiers.into_iter().collect()));
                 if buffer.len() == 1 {
                     self.changed_tx.send(()).ok();
                 }
@@ -140,6 +144,12 @@ pub struct GlobalBarrierManager<S: MetaStore> {
     /// The maximal interval for sending a barrier.
     interval: Duration,
 
+    /// Make a checkpoint every this many barriers.
+    checkpoint_frequency: usize,
+
+    /// Report the stragglers if a barrier isn't collected within this duration.
+    collect_timeout: Duration,
+
     /// Enable recovery or not when failover.
     enable_recovery: bool,
 
@@ -156,6 +166,9 @@ pub struct GlobalBarrierManager<S: MetaStore> {
 
     metrics: Arc<MetaMetrics>,
 
+    /// The current epoch and the barriers in flight.
+    status: Mutex<BarrierStatus>,
+
     env: MetaSrvEnv<S>,
 }
 
@@ -172,13 +185,16 @@ where
         hummock_manager: HummockManagerRef<S>,
         metrics: Arc<MetaMetrics>,
     ) -> Self {
-        // TODO: make interval configurable.
         // TODO: when tracing is on, warn the developer on this short interval.
-        let interval = Duration::from_millis(100);
+        let interval = env.opts.barrier_interval;
+        let checkpoint_frequency = env.opts.checkpoint_frequency;
+        let collect_timeout = env.opts.barrier_collect_timeout;
         let enable_recovery = env.opts.enable_recovery;
 
         Self {
             interval,
+            checkpoint_frequency,
+            collect_timeout,
             enable_recovery,
             cluster_manager,
             catalog_manager,
@@ -186,6 +202,7 @@ where
             scheduled_barriers: ScheduledBarriers::new(),
             hummock_manager,
             metrics,
+            status: Mutex::new(BarrierStatus::default()),
             env,
         }
     }
@@ -206,6 +223,12 @@ where
         let mut min_interval = tokio::time::interval(self.interval);
         min_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
         let mut unfinished = UnfinishedNotifiers::default();
+        // Chain actors rebuilt by the last partial recovery, which will report finishing on the
+        // next barrier.
+        let mut recovered_chain_actors = HashSet::new();
+        // Epochs collected by non-checkpoint barriers, to be committed by the next checkpoint.
+        let mut uncommitted_epochs = vec![];
+        let mut barriers_since_checkpoint = 0;
         let mut state = BarrierManagerState::create(self.env.meta_store()).await;
 
         if self.enable_recovery {
@@ -223,6 +246,7 @@ where
             }
             state.prev_epoch = new_epoch.into_inner();
             state.update(self.env.meta_store()).await.unwrap();
+            self.status.lock().current_epoch = state.prev_epoch;
         }
 
         loop {
@@ -240,9 +264,18 @@ where
             }
             // Get a barrier to send.
             let (command, notifiers) = self.scheduled_barriers.pop_or_default().await;
-            let info = self.resolve_actor_info(command.creating_table_id()).await;
+            let info = self.resolve_actor_info(Some(&command)).await;
             let new_epoch = self.env.epoch_generator().generate().into_inner();
             assert!(new_epoch > state.prev_epoch);
+            // Commands and barriers waited by someone are always checkpoints, so that their
+            // results are visible once collected.
+            barriers_since_checkpoint += 1;
+            let checkpoint = !command.is_barrier()
+                || !notifiers.is_empty()
+                || barriers_since_checkpoint >= self.checkpoint_frequency;
+            if checkpoint {
+                barriers_since_checkpoint = 0;
+            }
             let command_ctx = CommandContext::new(
                 self.fragment_manager.clone(),
                 self.env.stream_clients_ref(),
@@ -250,73 +283,133 @@ where
                 state.prev_epoch,
                 new_epoch,
                 command.clone(),
+                checkpoint,
             );
 
             let mut notifiers = notifiers;
             notifiers.iter_mut().for_each(Notifier::notify_to_send);
-            match self.run_inner(&command_ctx).await {
+            // The previous command to clean up, if the whole cluster needs to be recovered.
+            let mut to_recover = None;
+            match self.run_inner(&command_ctx, &mut uncommitted_epochs).await {
                 Ok(responses) => {
                     // Notify about collected first.
                     notifiers.iter_mut().for_each(Notifier::notify_collected);
 
                     // Then try to finish the barrier for Create MVs.
-                    let actors_to_finish = command_ctx.actors_to_finish();
+                    let actors_to_finish = command_ctx
+                        .actors_to_finish()
+                        .into_iter()
+                        .chain(std::mem::take(&mut recovered_chain_actors));
                     unfinished.add(new_epoch, actors_to_finish, notifiers);
+                    let failed_actors = Self::failed_actors(&responses);
                     for finished in responses.into_iter().flat_map(|r| r.finished_create_mviews) {
                         unfinished.finish_actors(finished.epoch, once(finished.actor_id));
                     }
 
                     state.prev_epoch = new_epoch;
+
+                    if !failed_actors.is_empty() {
+                        if !self.enable_recovery {
+                            panic!("actors {:?} failed", failed_actors);
+                        }
+                        // Only recover the streaming jobs affected by the failed actors, and fall
+                        // back to recover the whole cluster if the failure can't be scoped.
+                        match self
+                            .partial_recovery(
+                                &failed_actors,
+                                &unfinished,
+                                command_ctx.checkpoint,
+                                &mut uncommitted_epochs,
+                            )
+                            .await
+                        {
+                            Some(chain_actors) => recovered_chain_actors = chain_actors,
+                            None => to_recover = Some(None),
+                        }
+                    }
                 }
                 Err(e) => {
                     notifiers
                         .into_iter()
                         .for_each(|notifier| notifier.notify_collection_failed(e.clone()));
-                    if self.enable_recovery {
-                        // If failed, enter recovery mode.
-                        let (new_epoch, actors_to_finish, finished_create_mviews) =
-                            self.recovery(state.prev_epoch, Some(command)).await;
-                        unfinished = UnfinishedNotifiers::default();
-                        unfinished.add(new_epoch.into_inner(), actors_to_finish, vec![]);
-                        for finished in finished_create_mviews {
-                            unfinished.finish_actors(finished.epoch, once(finished.actor_id));
-                        }
-
-                        state.prev_epoch = new_epoch.into_inner();
-                    } else {
+                    if !self.enable_recovery {
                         panic!("failed to execute barrier: {:?}", e);
                     }
+                    to_recover = Some(Some(command));
                 }
             }
 
+            if let Some(prev_command) = to_recover {
+                // The states of uncommitted epochs are dropped by recovery.
+                for epoch in uncommitted_epochs.drain(..) {
+                    if let Err(err) = self.hummock_manager.abort_epoch(epoch).await {
+                        tracing::warn!("failed to abort epoch {}: {}", epoch, err);
+                    }
+                }
+                barriers_since_checkpoint = 0;
+
+                // If failed, enter recovery mode.
+                let (new_epoch, actors_to_finish, finished_create_mviews) =
+                    self.recovery(state.prev_epoch, prev_command).await;
+                unfinished = UnfinishedNotifiers::default();
+                unfinished.add(new_epoch.into_inner(), actors_to_finish, vec![]);
+                for finished in finished_create_mviews {
+                    unfinished.finish_actors(finished.epoch, once(finished.actor_id));
+                }
+                recovered_chain_actors.clear();
+
+                state.prev_epoch = new_epoch.into_inner();
+            }
+
             state.update(self.env.meta_store()).await.unwrap();
+            self.status.lock().current_epoch = state.prev_epoch;
         }
     }
 
-    /// Running a scheduled command.
+    /// Running a scheduled command. The epochs collected by non-checkpoint barriers are kept in
+    /// `uncommitted_epochs`, and committed or aborted with the next checkpoint.
     async fn run_inner<'a>(
         &self,
         command_context: &CommandContext<'a, S>,
+        uncommitted_epochs: &mut Vec<u64>,
     ) -> Result<Vec<InjectBarrierResponse>> {
         let timer = self.metrics.barrier_latency.start_timer();
 
         // Wait for all barriers collected
-        let result = self.inject_barrier(command_context).await;
+        let result = self
+            .inject_barrier(command_context)
+            .await
+            .and_then(|responses| {
+                // Failed actors are recovered after the barrier is collected only if it's a
+                // plain barrier, since other commands may have changed the streaming graph.
+                let failed_actors = Self::failed_actors(&responses);
+                if failed_actors.is_empty() || command_context.command.is_barrier() {
+                    Ok(responses)
+                } else {
+                    Err(RwError::from(ErrorCode::InternalError(format!(
+                        "actors {:?} failed",
+                        failed_actors
+                    ))))
+                }
+            });
         // Commit this epoch to Hummock
         if command_context.prev_epoch != INVALID_EPOCH {
-            match result {
+            uncommitted_epochs.push(command_context.prev_epoch);
+            match &result {
+                Ok(_) if !command_context.checkpoint => {}
+                // The partial writes of failed actors are committed or aborted by the recovery.
+                Ok(responses) if !Self::failed_actors(responses).is_empty() => {}
                 Ok(_) => {
-                    // We must ensure all epochs are committed in ascending order, because
-                    // the storage engine will query from new to old in the order in which
-                    // the L0 layer files are generated. see https://github.com/singularity-data/risingwave/issues/1251
-                    self.hummock_manager
-                        .commit_epoch(command_context.prev_epoch)
-                        .await?;
+                    // States of all uncommitted epochs are synced to the checkpoint epoch as a
+                    // whole, so committing it commits them all.
+                    let epoch = *uncommitted_epochs.last().unwrap();
+                    uncommitted_epochs.clear();
+                    self.hummock_manager.commit_epoch(epoch).await?;
                 }
                 Err(_) => {
-                    self.hummock_manager
-                        .abort_epoch(command_context.prev_epoch)
-                        .await?;
+                    for epoch in uncommitted_epochs.drain(..) {
+                        self.hummock_manager.abort_epoch(epoch).await?;
+                    }
                 }
             };
         }
@@ -355,6 +448,7 @@ where
                     mutation: Some(mutation),
                     // TODO(chi): add distributed tracing
                     span: vec![],
+                    checkpoint: command_context.checkpoint,
                 };
 
                 async move {
@@ -382,20 +476,62 @@ where
             }
         });
 
-        try_join_all(collect_futures).await
+        let curr_epoch = command_context.curr_epoch;
+        self.status
+            .lock()
+            .in_flight_barriers
+            .push(InFlightBarrier::new(command_context));
+        let collect = try_join_all(collect_futures);
+        tokio::pin!(collect);
+        let result = loop {
+            match tokio::time::timeout(self.collect_timeout, collect.as_mut()).await {
+                Ok(result) => break result,
+                Err(_) => self.report_stragglers(curr_epoch, info).await,
+            }
+        };
+        self.status
+            .lock()
+            .in_flight_barriers
+            .retain(|barrier| barrier.curr_epoch != curr_epoch);
+
+        result
     }
 
-    /// Resolve actor information from cluster and fragment manager.
-    async fn resolve_actor_info(&self, creating_table_id: Option<TableId>) -> BarrierActorInfo {
+    /// Returns the actors reported failed in the `responses`.
+    fn failed_actors(responses: &[InjectBarrierResponse]) -> HashSet<ActorId> {
+        responses
+            .iter()
+            .flat_map(|r| r.failed_actors.iter().copied())
+            .collect()
+    }
+
+    /// Resolve actor information from cluster and fragment manager. The actors to be created by
+    /// the `command` are included as well.
+    async fn resolve_actor_info(&self, command: Option<&Command>) -> BarrierActorInfo {
         let all_nodes = self
             .cluster_manager
             .list_worker_node(WorkerType::ComputeNode, Some(Running))
             .await;
         let all_actor_infos = self
             .fragment_manager
-            .load_all_actors(creating_table_id)
+            .load_all_actors(command.and_then(Command::creating_table_id))
             .await;
-        BarrierActorInfo::resolve(all_nodes, all_actor_infos)
+        let mut info = BarrierActorInfo::resolve(all_nodes, all_actor_infos);
+
+        if let Some(Command::RescheduleMaterializedView {
+            new_table_fragments,
+            ..
+        }) = command
+        {
+            for (node_id, actor_ids) in new_table_fragments.node_actor_ids() {
+                info.actor_map
+                    .entry(node_id)
+                    .or_insert_with(Vec::new)
+                    .extend(actor_ids);
+            }
+        }
+
+        info
     }
 }
 
@@ -471,6 +607,11 @@ where
         Ok(())
     }
 
+    /// Returns the current epoch and the barriers in flight.
+    pub fn status(&self) -> BarrierStatus {
+        self.status.lock().clone()
+    }
+
     /// Wait for the next barrier to collect. Note that the barrier flowing in our stream graph is
     /// ignored, if exists.
     pub async fn wait_for_next_barrier_to_collect(&self) -> Result<()> {
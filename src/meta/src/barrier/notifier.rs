@@ -99,6 +99,13 @@ impl UnfinishedNotifiers {
         }
     }
 
+    /// Returns whether any of `actor_ids` is still to report finishing.
+    pub fn contains_any(&self, actor_ids: &HashSet<ActorId>) -> bool {
+        self.0
+            .values()
+            .any(|(actors, _)| !actors.is_disjoint(actor_ids))
+    }
+
     /// Tell that the command with `epoch` has been reported to be finished on given `actors`. If
     /// we've finished on all actors, [`Notifier::notify_finished`] will be called.
     pub fn finish_actors(&mut self, epoch: u64, actors: impl IntoIterator<Item = ActorId>) {
@@ -19,18 +19,21 @@ use std::time::Duration;
 use futures::future::try_join_all;
 use log::{debug, error};
 use risingwave_common::error::{ErrorCode, Result, RwError, ToRwResult};
+use risingwave_hummock_sdk::key::user_key;
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::Epoch as ProstEpoch;
+use risingwave_pb::hummock::SstableInfo;
 use risingwave_pb::stream_service::inject_barrier_response::FinishedCreateMview;
 use risingwave_pb::stream_service::{
-    BroadcastActorInfoTableRequest, BuildActorsRequest, ForceStopActorsRequest, SyncSourcesRequest,
-    UpdateActorsRequest,
+    BroadcastActorInfoTableRequest, BuildActorsRequest, DropActorsRequest, ForceStopActorsRequest,
+    SyncSourcesRequest, UpdateActorsRequest,
 };
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use uuid::Uuid;
 
 use crate::barrier::command::CommandContext;
 use crate::barrier::info::BarrierActorInfo;
+use crate::barrier::notifier::UnfinishedNotifiers;
 use crate::barrier::{Command, GlobalBarrierManager};
 use crate::manager::Epoch;
 use crate::model::ActorId;
@@ -104,7 +107,8 @@ where
                 &info,
                 prev_epoch,
                 new_epoch.into_inner(),
-                Command::checkpoint(),
+                Command::barrier(),
+                true,
             );
 
             match self.inject_barrier(&command_ctx).await {
@@ -135,6 +139,92 @@ where
         );
     }
 
+    /// Recover only the streaming jobs affected by `failed_actors`, leaving the others running.
+    /// Returns the rebuilt chain actors, or `None` if the failure can't be scoped and the whole
+    /// cluster should be recovered.
+    ///
+    /// The actors of the affected jobs are dropped and rebuilt from the latest committed states,
+    /// and will be initialized by the next barrier. The `uncommitted_epochs` are committed for the
+    /// other jobs only if the affected jobs haven't written in them, since the partial writes of
+    /// the affected jobs can't be excluded from the commit. Otherwise the whole cluster should be
+    /// recovered, which aborts them.
+    pub(super) async fn partial_recovery(
+        &self,
+        failed_actors: &HashSet<ActorId>,
+        unfinished: &UnfinishedNotifiers,
+        checkpoint: bool,
+        uncommitted_epochs: &mut Vec<u64>,
+    ) -> Option<HashSet<ActorId>> {
+        // Writes of non-checkpoint epochs are not uploaded yet, so they can't be checked.
+        if !checkpoint {
+            return None;
+        }
+
+        let table_ids = self
+            .fragment_manager
+            .connected_table_ids(failed_actors)
+            .await?;
+
+        let mut actor_ids = HashSet::new();
+        let mut chain_actor_ids = HashSet::new();
+        let mut key_prefixes = vec![];
+        for table_id in &table_ids {
+            let table_fragments = self
+                .fragment_manager
+                .select_table_fragments_by_table_id(table_id)
+                .await
+                .ok()?;
+            actor_ids.extend(table_fragments.actor_ids());
+            chain_actor_ids.extend(table_fragments.chain_actor_ids());
+            key_prefixes.extend(table_fragments.state_key_prefixes());
+        }
+
+        // The snapshot of creating mview can't be resumed by rebuilt chain actors.
+        if unfinished.contains_any(&chain_actor_ids) {
+            return None;
+        }
+
+        let version = self.hummock_manager.get_current_version().await;
+        if version
+            .uncommitted_epochs
+            .iter()
+            .filter(|epoch| uncommitted_epochs.contains(&epoch.epoch))
+            .flat_map(|epoch| &epoch.tables)
+            .any(|sst| overlaps_any(sst, &key_prefixes))
+        {
+            debug!("partial recovery skipped: tables {:?} written", table_ids);
+            return None;
+        }
+        // States of all uncommitted epochs are synced to the checkpoint epoch as a whole.
+        if let Some(&epoch) = uncommitted_epochs.last() {
+            if let Err(err) = self.hummock_manager.commit_epoch(epoch).await {
+                error!("failed to commit epoch {}: {}", epoch, err);
+                return None;
+            }
+            uncommitted_epochs.clear();
+        }
+
+        debug!("partial recovery start: tables {:?}", table_ids);
+        let info = self.resolve_actor_info(None).await.subset(&actor_ids);
+        let result = async {
+            self.drop_actors(&info).await?;
+            self.update_actors(&info).await?;
+            self.build_actors(&info).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                debug!("partial recovery success");
+                Some(chain_actor_ids)
+            }
+            Err(err) => {
+                error!("partial recovery failed: {}", err);
+                None
+            }
+        }
+    }
+
     /// Clean up previous command dirty data. Currently, we only need to handle table fragments info
     /// for `CreateMaterializedView`. For `DropMaterializedView`, since we already response fail to
     /// frontend and the actors will be rebuild by follow recovery process, it's okay to retain
@@ -202,6 +292,14 @@ where
         for (node_id, actors) in &info.actor_map {
             let node = info.node_map.get(node_id).unwrap();
             let client = self.env.stream_clients().get(node).await?;
+            let actor_ids = actors.iter().copied().collect::<HashSet<_>>();
+            let actors_to_update = node_actors
+                .get(node_id)
+                .into_iter()
+                .flatten()
+                .filter(|actor| actor_ids.contains(&actor.actor_id))
+                .cloned()
+                .collect();
 
             client
                 .to_owned()
@@ -217,7 +315,7 @@ where
                 .to_owned()
                 .update_actors(UpdateActorsRequest {
                     request_id,
-                    actors: node_actors.get(node_id).cloned().unwrap_or_default(),
+                    actors: actors_to_update,
                     ..Default::default()
                 })
                 .await
@@ -227,6 +325,32 @@ where
         Ok(())
     }
 
+    /// Drop actors in compute nodes, no matter whether they're still running.
+    async fn drop_actors(&self, info: &BarrierActorInfo) -> Result<()> {
+        for (node_id, actors) in &info.actor_map {
+            let node = info.node_map.get(node_id).ok_or_else(|| {
+                RwError::from(ErrorCode::InternalError(
+                    "worker evicted, wait for online.".to_string(),
+                ))
+            })?;
+            let client = self.env.stream_clients().get(node).await?;
+
+            let request_id = Uuid::new_v4().to_string();
+            tracing::debug!(request_id = request_id.as_str(), actors = ?actors, "drop actors");
+            client
+                .to_owned()
+                .drop_actors(DropActorsRequest {
+                    request_id,
+                    actor_ids: actors.to_owned(),
+                    delete_states_epoch: 0,
+                })
+                .await
+                .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+        }
+
+        Ok(())
+    }
+
     /// Build all actors in compute nodes.
     async fn build_actors(&self, info: &BarrierActorInfo) -> Result<()> {
         for (node_id, actors) in &info.actor_map {
@@ -280,3 +404,62 @@ where
         debug!("all compute nodes have been reset.");
     }
 }
+
+/// Returns whether the key range of `sst` may contain keys with any of the `key_prefixes`.
+fn overlaps_any(sst: &SstableInfo, key_prefixes: &[Vec<u8>]) -> bool {
+    let key_range = match &sst.key_range {
+        Some(key_range) => key_range,
+        None => return true,
+    };
+    if key_range.inf {
+        return true;
+    }
+    let left = user_key(&key_range.left);
+    let right = user_key(&key_range.right);
+    key_prefixes
+        .iter()
+        .any(|prefix| right >= &prefix[..] && (left < &prefix[..] || left.starts_with(prefix)))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_hummock_sdk::key::key_with_epoch;
+    use risingwave_pb::hummock::KeyRange;
+
+    use super::*;
+
+    #[test]
+    fn test_overlaps_any() {
+        let sst = |left: &[u8], right: &[u8]| SstableInfo {
+            id: 1,
+            key_range: Some(KeyRange {
+                left: key_with_epoch(left.to_vec(), 1),
+                right: key_with_epoch(right.to_vec(), 1),
+                inf: false,
+            }),
+            file_size: 1,
+        };
+        let key_prefixes = vec![b"t\x00\x00\x00\x02".to_vec()];
+
+        assert!(overlaps_any(
+            &sst(b"t\x00\x00\x00\x01", b"t\x00\x00\x00\x03"),
+            &key_prefixes
+        ));
+        assert!(overlaps_any(
+            &sst(b"t\x00\x00\x00\x02a", b"t\x00\x00\x00\x02b"),
+            &key_prefixes
+        ));
+        assert!(overlaps_any(
+            &sst(b"e", b"t\x00\x00\x00\x02"),
+            &key_prefixes
+        ));
+        assert!(!overlaps_any(
+            &sst(b"e", b"t\x00\x00\x00\x01z"),
+            &key_prefixes
+        ));
+        assert!(!overlaps_any(
+            &sst(b"t\x00\x00\x00\x03", b"t\x00\x00\x00\x04"),
+            &key_prefixes
+        ));
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use risingwave_common::error::{Result, ToRwResult};
+use risingwave_pb::common::worker_node::State::Running;
+use risingwave_pb::common::{WorkerNode, WorkerType};
+use risingwave_pb::meta::get_barrier_status_response::ActorBarrierStatus;
+use risingwave_pb::meta::{GetBarrierStatusResponse, InFlightBarrier as ProstInFlightBarrier};
+use risingwave_pb::stream_service::{DumpAwaitTreeRequest, GetBarrierStatusRequest};
+
+use super::command::CommandContext;
+use super::info::BarrierActorInfo;
+use super::GlobalBarrierManager;
+use crate::storage::MetaStore;
+
+/// A barrier injected to the compute nodes and not collected yet.
+#[derive(Clone, Debug)]
+pub struct InFlightBarrier {
+    pub prev_epoch: u64,
+    pub curr_epoch: u64,
+
+    /// The name of the command carried by the barrier.
+    pub command: &'static str,
+
+    pub checkpoint: bool,
+
+    injected_at: Instant,
+}
+
+impl InFlightBarrier {
+    pub(super) fn new<S>(command_context: &CommandContext<'_, S>) -> Self {
+        Self {
+            prev_epoch: command_context.prev_epoch,
+            curr_epoch: command_context.curr_epoch,
+            command: command_context.command.name(),
+            checkpoint: command_context.checkpoint,
+            injected_at: Instant::now(),
+        }
+    }
+
+    /// The time elapsed since the barrier was injected.
+    pub fn elapsed(&self) -> Duration {
+        self.injected_at.elapsed()
+    }
+
+    pub fn to_protobuf(&self) -> ProstInFlightBarrier {
+        ProstInFlightBarrier {
+            prev_epoch: self.prev_epoch,
+            curr_epoch: self.curr_epoch,
+            command: self.command.to_string(),
+            checkpoint: self.checkpoint,
+            elapsed_ms: self.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// The runtime status of [`super::GlobalBarrierManager`], for introspection.
+#[derive(Clone, Debug, Default)]
+pub struct BarrierStatus {
+    /// The epoch of the latest barrier collected from all compute nodes.
+    pub current_epoch: u64,
+
+    /// The barriers injected to the compute nodes and not collected yet.
+    pub in_flight_barriers: Vec<InFlightBarrier>,
+}
+
+impl<S> GlobalBarrierManager<S>
+where
+    S: MetaStore,
+{
+    /// Reports the barrier collection status of the actors on all running compute nodes, along
+    /// with the await trees of the actors not collected yet.
+    pub async fn barrier_status_report(&self) -> GetBarrierStatusResponse {
+        let nodes = self
+            .cluster_manager
+            .list_worker_node(WorkerType::ComputeNode, Some(Running))
+            .await;
+        let actors = self.actor_barrier_status(nodes.iter()).await;
+
+        let status = self.status();
+        GetBarrierStatusResponse {
+            current_epoch: status.current_epoch,
+            in_flight_barriers: status
+                .in_flight_barriers
+                .iter()
+                .map(InFlightBarrier::to_protobuf)
+                .collect(),
+            actors,
+        }
+    }
+
+    /// Logs the actors that haven't collected the barrier of `curr_epoch` yet, with their await
+    /// trees, so that a stuck barrier can be diagnosed.
+    pub(super) async fn report_stragglers(&self, curr_epoch: u64, info: &BarrierActorInfo) {
+        let stragglers = self
+            .actor_barrier_status(info.node_map.values())
+            .await
+            .into_iter()
+            .filter(|actor| !actor.collected)
+            .collect::<Vec<_>>();
+
+        tracing::warn!(
+            "barrier {} is not collected after {:?}, {} actors remaining",
+            curr_epoch,
+            self.collect_timeout,
+            stragglers.len()
+        );
+        for actor in stragglers {
+            tracing::warn!(
+                "actor {} on worker {} has not collected barrier {}, last collected epoch {}\n{}",
+                actor.actor_id,
+                actor.worker_id,
+                curr_epoch,
+                actor.last_epoch,
+                actor.await_tree
+            );
+        }
+    }
+
+    /// Fetches the barrier collection status of the actors on the given compute nodes. The nodes
+    /// failing to respond are skipped.
+    async fn actor_barrier_status(
+        &self,
+        nodes: impl Iterator<Item = &WorkerNode>,
+    ) -> Vec<ActorBarrierStatus> {
+        let futures = nodes.map(|node| async move {
+            let result = self.node_barrier_status(node).await;
+            (node.id, result)
+        });
+
+        let mut actors = vec![];
+        for (node_id, result) in join_all(futures).await {
+            match result {
+                Ok(status) => actors.extend(status),
+                Err(e) => tracing::warn!("failed to get barrier status of node {}: {}", node_id, e),
+            }
+        }
+        actors
+    }
+
+    async fn node_barrier_status(&self, node: &WorkerNode) -> Result<Vec<ActorBarrierStatus>> {
+        let mut client = self.env.stream_clients().get(node).await?;
+        let status = client
+            .get_barrier_status(GetBarrierStatusRequest {})
+            .await
+            .to_rw_result()?
+            .into_inner();
+
+        // Only dump the await trees of the actors not collected yet.
+        let mut actor_traces = if status.remaining_actors.is_empty() {
+            HashMap::new()
+        } else {
+            client
+                .dump_await_tree(DumpAwaitTreeRequest {
+                    actor_ids: status.remaining_actors.clone(),
+                })
+                .await
+                .to_rw_result()?
+                .into_inner()
+                .actor_traces
+        };
+
+        let actor_ids = status
+            .collected_actors
+            .iter()
+            .chain(&status.remaining_actors)
+            .chain(status.actor_last_epochs.keys())
+            .copied()
+            .collect::<BTreeSet<_>>();
+        let actors = actor_ids
+            .into_iter()
+            .map(|actor_id| ActorBarrierStatus {
+                actor_id,
+                worker_id: node.id,
+                collected: !status.remaining_actors.contains(&actor_id),
+                last_epoch: status
+                    .actor_last_epochs
+                    .get(&actor_id)
+                    .copied()
+                    .unwrap_or_default(),
+                await_tree: actor_traces.remove(&actor_id).unwrap_or_default(),
+            })
+            .collect();
+        Ok(actors)
+    }
+}
@@ -0,0 +1,516 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use itertools::Itertools;
+use risingwave_common::catalog::TableId;
+use risingwave_hummock_sdk::key::key_with_epoch;
+use risingwave_pb::common::{HostAddress, WorkerType};
+use risingwave_pb::hummock::{KeyRange, SstableInfo};
+use risingwave_pb::meta::table_fragments::fragment::{FragmentDistributionType, FragmentType};
+use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus, Fragment};
+use risingwave_pb::plan::TableRefId;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_pb::stream_plan::{ChainNode, MaterializeNode, StreamActor, StreamNode};
+use risingwave_pb::stream_service::stream_service_server::{StreamService, StreamServiceServer};
+use risingwave_pb::stream_service::*;
+use tokio::sync::oneshot;
+use tonic::{Request, Response, Status};
+
+use super::info::BarrierActorInfo;
+use super::notifier::UnfinishedNotifiers;
+use super::{Command, CommandContext, GlobalBarrierManager};
+use crate::cluster::ClusterManager;
+use crate::hummock::{HummockManager, HummockManagerRef};
+use crate::manager::{CatalogManager, MetaSrvEnv};
+use crate::model::{ActorId, TableFragments};
+use crate::rpc::metrics::MetaMetrics;
+use crate::storage::MemStore;
+use crate::stream::{FragmentManager, FragmentManagerRef};
+
+#[derive(Default)]
+struct FakeStreamState {
+    dropped_actors: Mutex<HashSet<ActorId>>,
+    built_actors: Mutex<HashSet<ActorId>>,
+    /// Whether to fail the injected barriers.
+    fail_inject: AtomicBool,
+}
+
+struct FakeStreamService {
+    inner: Arc<FakeStreamState>,
+}
+
+#[async_trait::async_trait]
+impl StreamService for FakeStreamService {
+    async fn update_actors(
+        &self,
+        _request: Request<UpdateActorsRequest>,
+    ) -> std::result::Result<Response<UpdateActorsResponse>, Status> {
+        Ok(Response::new(UpdateActorsResponse::default()))
+    }
+
+    async fn build_actors(
+        &self,
+        request: Request<BuildActorsRequest>,
+    ) -> std::result::Result<Response<BuildActorsResponse>, Status> {
+        let req = request.into_inner();
+        self.inner
+            .built_actors
+            .lock()
+            .unwrap()
+            .extend(req.actor_id.iter().copied());
+        Ok(Response::new(BuildActorsResponse::default()))
+    }
+
+    async fn broadcast_actor_info_table(
+        &self,
+        _request: Request<BroadcastActorInfoTableRequest>,
+    ) -> std::result::Result<Response<BroadcastActorInfoTableResponse>, Status> {
+        Ok(Response::new(BroadcastActorInfoTableResponse::default()))
+    }
+
+    async fn drop_actors(
+        &self,
+        request: Request<DropActorsRequest>,
+    ) -> std::result::Result<Response<DropActorsResponse>, Status> {
+        let req = request.into_inner();
+        self.inner
+            .dropped_actors
+            .lock()
+            .unwrap()
+            .extend(req.actor_ids.iter().copied());
+        Ok(Response::new(DropActorsResponse::default()))
+    }
+
+    async fn force_stop_actors(
+        &self,
+        _request: Request<ForceStopActorsRequest>,
+    ) -> std::result::Result<Response<ForceStopActorsResponse>, Status> {
+        Ok(Response::new(ForceStopActorsResponse::default()))
+    }
+
+    async fn inject_barrier(
+        &self,
+        _request: Request<InjectBarrierRequest>,
+    ) -> std::result::Result<Response<InjectBarrierResponse>, Status> {
+        if self.inner.fail_inject.load(Ordering::SeqCst) {
+            return Err(Status::internal("injected failure"));
+        }
+        Ok(Response::new(InjectBarrierResponse::default()))
+    }
+
+    async fn create_source(
+        &self,
+        _request: Request<CreateSourceRequest>,
+    ) -> std::result::Result<Response<CreateSourceResponse>, Status> {
+        unimplemented!()
+    }
+
+    async fn sync_sources(
+        &self,
+        _request: Request<SyncSourcesRequest>,
+    ) -> std::result::Result<Response<SyncSourcesResponse>, Status> {
+        Ok(Response::new(SyncSourcesResponse::default()))
+    }
+
+    async fn drop_source(
+        &self,
+        _request: Request<DropSourceRequest>,
+    ) -> std::result::Result<Response<DropSourceResponse>, Status> {
+        unimplemented!()
+    }
+
+    async fn get_actor_stats(
+        &self,
+        _request: Request<GetActorStatsRequest>,
+    ) -> std::result::Result<Response<GetActorStatsResponse>, Status> {
+        Ok(Response::new(GetActorStatsResponse::default()))
+    }
+
+    async fn get_barrier_status(
+        &self,
+        _request: Request<GetBarrierStatusRequest>,
+    ) -> std::result::Result<Response<GetBarrierStatusResponse>, Status> {
+        Ok(Response::new(GetBarrierStatusResponse::default()))
+    }
+
+    async fn dump_await_tree(
+        &self,
+        _request: Request<DumpAwaitTreeRequest>,
+    ) -> std::result::Result<Response<DumpAwaitTreeResponse>, Status> {
+        Ok(Response::new(DumpAwaitTreeResponse::default()))
+    }
+}
+
+struct MockServices {
+    env: MetaSrvEnv<MemStore>,
+    barrier_manager: GlobalBarrierManager<MemStore>,
+    fragment_manager: FragmentManagerRef<MemStore>,
+    hummock_manager: HummockManagerRef<MemStore>,
+    state: Arc<FakeStreamState>,
+    /// The id of the only compute node.
+    worker_id: u32,
+    shutdown_tx: oneshot::Sender<()>,
+}
+
+impl MockServices {
+    async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(FakeStreamState::default());
+        let stream_srv = StreamServiceServer::new(FakeStreamService {
+            inner: state.clone(),
+        });
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(stream_srv)
+                .serve_with_incoming_shutdown(
+                    tokio_stream::wrappers::TcpListenerStream::new(listener),
+                    async move {
+                        shutdown_rx.await.ok();
+                    },
+                )
+                .await
+                .unwrap();
+        });
+
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager = Arc::new(
+            ClusterManager::new(env.clone(), Duration::from_secs(3600))
+                .await
+                .unwrap(),
+        );
+        let host = HostAddress {
+            host: "127.0.0.1".to_string(),
+            port: port as i32,
+        };
+        let (worker, _) = cluster_manager
+            .add_worker_node(host.clone(), WorkerType::ComputeNode, Default::default())
+            .await
+            .unwrap();
+        cluster_manager.activate_worker_node(host).await.unwrap();
+
+        let catalog_manager = Arc::new(CatalogManager::new(env.clone()).await.unwrap());
+        let fragment_manager = Arc::new(FragmentManager::new(env.meta_store_ref()).await.unwrap());
+        let meta_metrics = Arc::new(MetaMetrics::new());
+        let hummock_manager = Arc::new(
+            HummockManager::new(
+                env.clone(),
+                cluster_manager.clone(),
+                fragment_manager.clone(),
+                meta_metrics.clone(),
+            )
+            .await
+            .unwrap(),
+        );
+        let barrier_manager = GlobalBarrierManager::new(
+            env.clone(),
+            cluster_manager,
+            catalog_manager,
+            fragment_manager.clone(),
+            hummock_manager.clone(),
+            meta_metrics,
+        );
+
+        Self {
+            env,
+            barrier_manager,
+            fragment_manager,
+            hummock_manager,
+            state,
+            worker_id: worker.id,
+            shutdown_tx,
+        }
+    }
+
+    /// Creates a materialized view of `table_id` on `actor_ids`, which reads from `upstream` if
+    /// given.
+    async fn create_mview(&self, table_id: u32, actor_ids: &[ActorId], upstream: Option<u32>) {
+        let table_ref_id = |table_id: u32| {
+            Some(TableRefId {
+                schema_ref_id: None,
+                table_id: table_id as i32,
+            })
+        };
+        let input: Vec<_> = upstream
+            .map(|upstream| StreamNode {
+                node: Some(Node::ChainNode(ChainNode {
+                    table_ref_id: table_ref_id(upstream),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            })
+            .into_iter()
+            .collect();
+        let actors = actor_ids
+            .iter()
+            .map(|&actor_id| StreamActor {
+                actor_id,
+                fragment_id: table_id,
+                nodes: Some(StreamNode {
+                    node: Some(Node::MaterializeNode(MaterializeNode {
+                        table_ref_id: table_ref_id(table_id),
+                        ..Default::default()
+                    })),
+                    input: input.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .collect();
+        let fragments = BTreeMap::from([(
+            table_id,
+            Fragment {
+                fragment_id: table_id,
+                fragment_type: FragmentType::Sink as i32,
+                distribution_type: FragmentDistributionType::Hash as i32,
+                actors,
+            },
+        )]);
+        let table_id = TableId::new(table_id);
+        let mut table_fragments = TableFragments::new(table_id, fragments);
+        table_fragments.set_actor_status(
+            actor_ids
+                .iter()
+                .map(|&actor_id| {
+                    (
+                        actor_id,
+                        ActorStatus {
+                            node_id: self.worker_id,
+                            state: ActorState::Inactive as i32,
+                        },
+                    )
+                })
+                .collect(),
+        );
+
+        self.fragment_manager
+            .start_create_table_fragments(table_fragments)
+            .await
+            .unwrap();
+        self.fragment_manager
+            .finish_create_table_fragments(&table_id, &[])
+            .await
+            .unwrap();
+    }
+
+    /// Uploads an SST of `epoch` containing keys of `table_id`.
+    async fn add_table_sst(&self, table_id: u32, epoch: u64) {
+        let key = |suffix: &[u8]| {
+            key_with_epoch([&b"t"[..], &table_id.to_be_bytes(), suffix].concat(), epoch)
+        };
+        let sst = SstableInfo {
+            id: self.hummock_manager.get_new_table_id().await.unwrap(),
+            key_range: Some(KeyRange {
+                left: key(b"a"),
+                right: key(b"z"),
+                inf: false,
+            }),
+            file_size: 1,
+        };
+        self.hummock_manager
+            .add_tables(self.worker_id as _, vec![sst], epoch)
+            .await
+            .unwrap();
+    }
+
+    /// Runs a plain barrier of `prev_epoch` on the actors in `info`. Returns whether it succeeds.
+    async fn run_barrier(
+        &self,
+        info: &BarrierActorInfo,
+        prev_epoch: u64,
+        checkpoint: bool,
+        uncommitted_epochs: &mut Vec<u64>,
+    ) -> bool {
+        let command_ctx = CommandContext::new(
+            self.fragment_manager.clone(),
+            self.env.stream_clients_ref(),
+            info,
+            prev_epoch,
+            prev_epoch + 1,
+            Command::barrier(),
+            checkpoint,
+        );
+        self.barrier_manager
+            .run_inner(&command_ctx, uncommitted_epochs)
+            .await
+            .is_ok()
+    }
+
+    fn stop(self) {
+        self.shutdown_tx.send(()).unwrap();
+    }
+}
+
+/// Returns the sorted ids of the tables connected to `actor_ids`.
+async fn connected_table_ids(
+    fragment_manager: &FragmentManager<MemStore>,
+    actor_ids: &[ActorId],
+) -> Option<Vec<u32>> {
+    let table_ids = fragment_manager
+        .connected_table_ids(&actor_ids.iter().copied().collect())
+        .await?;
+    Some(
+        table_ids
+            .into_iter()
+            .map(|table_id| table_id.table_id())
+            .sorted()
+            .collect(),
+    )
+}
+
+#[tokio::test]
+async fn test_connected_table_ids() {
+    let services = MockServices::start().await;
+    services.create_mview(1, &[1, 2], None).await;
+    services.create_mview(2, &[3, 4], Some(1)).await;
+    services.create_mview(3, &[5], None).await;
+    services.create_mview(4, &[6], Some(2)).await;
+
+    let fragment_manager = &services.fragment_manager;
+
+    // Both the upstream and downstream tables are connected.
+    assert_eq!(
+        connected_table_ids(fragment_manager, &[3]).await,
+        Some(vec![1, 2, 4])
+    );
+    assert_eq!(
+        connected_table_ids(fragment_manager, &[1, 6]).await,
+        Some(vec![1, 2, 4])
+    );
+    assert_eq!(
+        connected_table_ids(fragment_manager, &[5]).await,
+        Some(vec![3])
+    );
+    assert_eq!(
+        connected_table_ids(fragment_manager, &[2, 5]).await,
+        Some(vec![1, 2, 3, 4])
+    );
+    // Unknown actors can't be scoped.
+    assert_eq!(connected_table_ids(fragment_manager, &[5, 100]).await, None);
+
+    services.stop();
+}
+
+#[tokio::test]
+async fn test_partial_recovery() {
+    let services = MockServices::start().await;
+    services.create_mview(1, &[1, 2], None).await;
+    services.create_mview(2, &[3], None).await;
+    let failed_actors = HashSet::from([3]);
+    let unfinished = UnfinishedNotifiers::default();
+
+    // Writes of non-checkpoint epochs can't be checked.
+    let mut uncommitted_epochs = vec![];
+    assert!(services
+        .barrier_manager
+        .partial_recovery(&failed_actors, &unfinished, false, &mut uncommitted_epochs)
+        .await
+        .is_none());
+
+    // The affected materialized view has written in the epoch to commit.
+    services.add_table_sst(2, 1).await;
+    let mut uncommitted_epochs = vec![1];
+    assert!(services
+        .barrier_manager
+        .partial_recovery(&failed_actors, &unfinished, true, &mut uncommitted_epochs)
+        .await
+        .is_none());
+    assert_eq!(uncommitted_epochs, vec![1]);
+    services.hummock_manager.abort_epoch(1).await.unwrap();
+    assert!(services.state.dropped_actors.lock().unwrap().is_empty());
+
+    // Only the other materialized view has written, so the epochs are committed and the affected
+    // actors are rebuilt. States of all the epochs are synced to the checkpoint epoch.
+    services.add_table_sst(1, 3).await;
+    let mut uncommitted_epochs = vec![2, 3];
+    let chain_actors = services
+        .barrier_manager
+        .partial_recovery(&failed_actors, &unfinished, true, &mut uncommitted_epochs)
+        .await;
+    assert_eq!(chain_actors, Some(HashSet::new()));
+    assert!(uncommitted_epochs.is_empty());
+    let version = services.hummock_manager.get_current_version().await;
+    assert_eq!(version.max_committed_epoch, 3);
+    assert!(version.uncommitted_epochs.is_empty());
+    assert_eq!(
+        *services.state.dropped_actors.lock().unwrap(),
+        failed_actors
+    );
+    assert_eq!(*services.state.built_actors.lock().unwrap(), failed_actors);
+
+    services.stop();
+}
+
+#[tokio::test]
+async fn test_checkpoint_frequency() {
+    let services = MockServices::start().await;
+    services.create_mview(1, &[1], None).await;
+    let info = services.barrier_manager.resolve_actor_info(None).await;
+    let mut uncommitted_epochs = vec![];
+
+    // Epochs of non-checkpoint barriers are kept uncommitted.
+    assert!(
+        services
+            .run_barrier(&info, 1, false, &mut uncommitted_epochs)
+            .await
+    );
+    assert!(
+        services
+            .run_barrier(&info, 2, false, &mut uncommitted_epochs)
+            .await
+    );
+    assert_eq!(uncommitted_epochs, vec![1, 2]);
+    let version = services.hummock_manager.get_current_version().await;
+    assert_eq!(version.max_committed_epoch, 0);
+
+    // The checkpoint commits all the epochs at once, whose states are synced to it as a whole.
+    services.add_table_sst(1, 3).await;
+    assert!(
+        services
+            .run_barrier(&info, 3, true, &mut uncommitted_epochs)
+            .await
+    );
+    assert!(uncommitted_epochs.is_empty());
+    let version = services.hummock_manager.get_current_version().await;
+    assert_eq!(version.max_committed_epoch, 3);
+    assert!(version.uncommitted_epochs.is_empty());
+    assert_eq!(version.levels[0].table_infos.len(), 1);
+
+    // A failed checkpoint aborts all the epochs.
+    assert!(
+        services
+            .run_barrier(&info, 4, false, &mut uncommitted_epochs)
+            .await
+    );
+    services.add_table_sst(1, 5).await;
+    services.state.fail_inject.store(true, Ordering::SeqCst);
+    assert!(
+        !services
+            .run_barrier(&info, 5, true, &mut uncommitted_epochs)
+            .await
+    );
+    assert!(uncommitted_epochs.is_empty());
+    let version = services.hummock_manager.get_current_version().await;
+    assert_eq!(version.max_committed_epoch, 3);
+    assert!(version.uncommitted_epochs.is_empty());
+    assert_eq!(version.levels[0].table_infos.len(), 1);
+
+    services.stop();
+}
@@ -103,6 +103,7 @@ where
         &self,
         host_address: HostAddress,
         r#type: WorkerType,
+        labels: HashMap<String, String>,
     ) -> Result<(WorkerNode, bool)> {
         let mut core = self.core.write().await;
         match core.get_worker_by_host(host_address.clone()) {
@@ -130,6 +131,7 @@ where
                     host: Some(host_address.clone()),
                     state: State::Starting as i32,
                     parallel_units,
+                    labels,
                 };
 
                 // Alter consistent hash mapping.
@@ -363,6 +365,10 @@ where
         self.dispatch_manager.get_worker_mapping().await
     }
 
+    pub async fn set_hash_mapping(&self, hash_mapping: Vec<ParallelUnitId>) -> Result<()> {
+        self.dispatch_manager.set_worker_mapping(hash_mapping).await
+    }
+
     async fn generate_cn_parallel_units(
         &self,
         parallel_degree: usize,
@@ -568,7 +574,11 @@ mod tests {
                 port: 5000 + i as i32,
             };
             let (worker_node, _) = cluster_manager
-                .add_worker_node(fake_host_address, WorkerType::ComputeNode)
+                .add_worker_node(
+                    fake_host_address,
+                    WorkerType::ComputeNode,
+                    Default::default(),
+                )
                 .await
                 .unwrap();
             worker_nodes.push(worker_node);
@@ -627,7 +637,11 @@ mod tests {
             port: 2,
         };
         let (_worker_node_2, _) = cluster_manager
-            .add_worker_node(fake_host_address_2, WorkerType::ComputeNode)
+            .add_worker_node(
+                fake_host_address_2,
+                WorkerType::ComputeNode,
+                Default::default(),
+            )
             .await
             .unwrap();
         // Two live nodes
@@ -216,7 +216,7 @@ where
             port: 4567,
         };
         self.cluster_manager
-            .add_worker_node(host.clone(), WorkerType::Frontend)
+            .add_worker_node(host.clone(), WorkerType::Frontend, Default::default())
             .await?;
         self.cluster_manager.activate_worker_node(host).await?;
 
@@ -0,0 +1,260 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use prost::Message;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::hummock::{
+    CompactTaskAssignment, HummockBackup, HummockBackupManifest, HummockPinnedSnapshot,
+    HummockPinnedVersion, HummockStaleSstables, HummockVersion, MetaStoreCfSnapshot, MetaStoreKv,
+    SstableIdInfo,
+};
+use risingwave_pb::{catalog, meta};
+
+use crate::hummock::model::{CurrentHummockVersionId, HUMMOCK_DEFAULT_CF_NAME};
+use crate::model::{CatalogVersionGenerator, ConsistentHashMapping, MetadataModel, TableFragments};
+use crate::storage::{MetaStore, Snapshot, DEFAULT_COLUMN_FAMILY};
+
+/// Column families of the meta store required to bootstrap a fresh meta node, i.e. all but the
+/// one of workers.
+///
+/// Workers are not included because a restored cluster is made up of new worker nodes. Contexts
+/// pinned by the old workers are released when the restored meta node starts.
+fn backup_column_families() -> Vec<String> {
+    vec![
+        // id generators and barrier manager state
+        DEFAULT_COLUMN_FAMILY.to_string(),
+        // catalog
+        meta::Database::cf_name(),
+        meta::Schema::cf_name(),
+        meta::Table::cf_name(),
+        CatalogVersionGenerator::cf_name(),
+        catalog::Database::cf_name(),
+        catalog::Schema::cf_name(),
+        catalog::Table::cf_name(),
+        catalog::Source::cf_name(),
+        // streaming
+        TableFragments::cf_name(),
+        ConsistentHashMapping::cf_name(),
+        // hummock
+        HUMMOCK_DEFAULT_CF_NAME.to_string(),
+        HummockVersion::cf_name(),
+        HummockStaleSstables::cf_name(),
+        SstableIdInfo::cf_name(),
+        HummockPinnedVersion::cf_name(),
+        HummockPinnedSnapshot::cf_name(),
+        CompactTaskAssignment::cf_name(),
+        HummockBackup::cf_name(),
+    ]
+}
+
+/// Dumps the meta store state required to bootstrap a fresh meta node, i.e. all key-value pairs
+/// of [`backup_column_families`].
+pub async fn dump_meta_store<T>(snapshot: &T) -> Result<Vec<MetaStoreCfSnapshot>>
+where
+    T: Snapshot,
+{
+    let mut cf_snapshots = vec![];
+    for cf in backup_column_families() {
+        let kvs = snapshot
+            .list_cf_kvs(&cf)
+            .await?
+            .into_iter()
+            .map(|(key, value)| MetaStoreKv { key, value })
+            .collect();
+        cf_snapshots.push(MetaStoreCfSnapshot { cf, kvs });
+    }
+    Ok(cf_snapshots)
+}
+
+/// Writes the meta store dump of `manifest` into `meta_store`, which must not have been used by
+/// any meta node before.
+pub async fn restore_meta_store<S>(meta_store: &S, manifest: &HummockBackupManifest) -> Result<()>
+where
+    S: MetaStore,
+{
+    if CurrentHummockVersionId::get(meta_store).await?.is_some() {
+        return Err(RwError::from(ErrorCode::InternalError(
+            "meta store to restore into is not empty".to_string(),
+        )));
+    }
+    let current_version_id_key = CurrentHummockVersionId::new().key()?.encode_to_vec();
+    // The current version id is written last, so that an interrupted restore can be retried.
+    let mut current_version_id = None;
+    for cf_snapshot in &manifest.meta_store {
+        for kv in &cf_snapshot.kvs {
+            if cf_snapshot.cf == HUMMOCK_DEFAULT_CF_NAME && kv.key == current_version_id_key {
+                current_version_id = Some(kv.value.clone());
+                continue;
+            }
+            meta_store
+                .put_cf(&cf_snapshot.cf, kv.key.clone(), kv.value.clone())
+                .await?;
+        }
+    }
+    let current_version_id = current_version_id.ok_or_else(|| {
+        RwError::from(ErrorCode::InternalError(
+            "backup manifest has no current hummock version".to_string(),
+        ))
+    })?;
+    meta_store
+        .put_cf(
+            HUMMOCK_DEFAULT_CF_NAME,
+            current_version_id_key,
+            current_version_id,
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::cluster::ClusterManager;
+    use crate::hummock::test_utils::{add_test_tables, setup_compute_env};
+    use crate::hummock::HummockManager;
+    use crate::manager::{MemEpochGenerator, MetaOpts, MetaSrvEnv};
+    use crate::model::Worker;
+    use crate::rpc::metrics::MetaMetrics;
+    use crate::storage::{FileMetaStore, MemStore, Operation};
+    use crate::stream::FragmentManager;
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let (env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+        let sst_infos = add_test_tables(hummock_manager.as_ref(), worker_node.id).await;
+        let manifest = hummock_manager
+            .create_backup("backup/1".to_string())
+            .await
+            .unwrap();
+        let backup = manifest.backup.as_ref().unwrap();
+        let current_version = hummock_manager.get_current_version().await;
+        assert_eq!(backup.version_id, current_version.id);
+        // test_tables_2 is committed and test_tables_3 is uncommitted in current version.
+        let mut expected_sst_ids = sst_infos[1..]
+            .iter()
+            .flatten()
+            .map(|sst| sst.id)
+            .collect_vec();
+        expected_sst_ids.sort_unstable();
+        let mut sst_ids = backup.sstable_ids.clone();
+        sst_ids.sort_unstable();
+        assert_eq!(sst_ids, expected_sst_ids);
+        assert_eq!(hummock_manager.list_backups().await, vec![backup.clone()]);
+        // Everything in meta store but the workers is dumped.
+        let dumped_keys: HashSet<_> = manifest
+            .meta_store
+            .iter()
+            .flat_map(|cf_snapshot| {
+                cf_snapshot
+                    .kvs
+                    .iter()
+                    .map(|kv| (cf_snapshot.cf.clone(), kv.key.clone()))
+            })
+            .collect();
+        for op in env.meta_store().snapshot().await.to_operations() {
+            if let Operation::Put { cf, key, .. } = op {
+                if cf != Worker::cf_name() {
+                    assert!(
+                        dumped_keys.contains(&(cf.clone(), key)),
+                        "{} not dumped",
+                        cf
+                    );
+                }
+            }
+        }
+
+        // Bootstrap a fresh meta from the backup.
+        let meta_store = Arc::new(MemStore::default());
+        restore_meta_store(meta_store.as_ref(), &manifest)
+            .await
+            .unwrap();
+        // Restoring into a used meta store is rejected.
+        assert!(restore_meta_store(meta_store.as_ref(), &manifest)
+            .await
+            .is_err());
+        let env = MetaSrvEnv::new(
+            MetaOpts::default(),
+            meta_store,
+            Arc::new(MemEpochGenerator::new()),
+        )
+        .await;
+        let cluster_manager = Arc::new(
+            ClusterManager::new(env.clone(), Duration::from_secs(1))
+                .await
+                .unwrap(),
+        );
+        let fragment_manager = Arc::new(FragmentManager::new(env.meta_store_ref()).await.unwrap());
+        let restored = HummockManager::new(
+            env,
+            cluster_manager,
+            fragment_manager,
+            Arc::new(MetaMetrics::new()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(restored.get_current_version().await, current_version);
+        assert_eq!(restored.list_backups().await, vec![backup.clone()]);
+        // SST ids are not reused by the restored meta.
+        let new_sst_id = restored.get_new_table_id().await.unwrap();
+        assert!(sst_infos.iter().flatten().all(|sst| sst.id < new_sst_id));
+    }
+
+    #[tokio::test]
+    async fn test_restore_into_file_meta_store() {
+        let (_env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+        add_test_tables(hummock_manager.as_ref(), worker_node.id).await;
+        let manifest = hummock_manager
+            .create_backup("backup/1".to_string())
+            .await
+            .unwrap();
+        let current_version = hummock_manager.get_current_version().await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let meta_store = FileMetaStore::open(dir.path()).await.unwrap();
+        restore_meta_store(&meta_store, &manifest).await.unwrap();
+        drop(meta_store);
+
+        // The restored meta store is recovered on reopening.
+        let meta_store = Arc::new(FileMetaStore::open(dir.path()).await.unwrap());
+        assert!(restore_meta_store(meta_store.as_ref(), &manifest)
+            .await
+            .is_err());
+        let env = MetaSrvEnv::new(
+            MetaOpts::default(),
+            meta_store,
+            Arc::new(MemEpochGenerator::new()),
+        )
+        .await;
+        let cluster_manager = Arc::new(
+            ClusterManager::new(env.clone(), Duration::from_secs(1))
+                .await
+                .unwrap(),
+        );
+        let fragment_manager = Arc::new(FragmentManager::new(env.meta_store_ref()).await.unwrap());
+        let restored = HummockManager::new(
+            env,
+            cluster_manager,
+            fragment_manager,
+            Arc::new(MetaMetrics::new()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(restored.get_current_version().await, current_version);
+    }
+}
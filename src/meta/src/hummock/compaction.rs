@@ -12,13 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Reverse;
 use std::io::Cursor;
 
 use bytes::Bytes;
 use itertools::{EitherOrBoth, Itertools};
 use prost::Message;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
 use risingwave_common::error::Result;
 use risingwave_hummock_sdk::key::{user_key, FullKey};
 use risingwave_hummock_sdk::key_range::KeyRange;
@@ -27,6 +26,7 @@ use risingwave_pb::hummock::{
     CompactMetrics, CompactTask, Level, LevelEntry, LevelType, SstableInfo, TableSetStatistics,
 };
 
+use crate::hummock::compaction_picker::{CompactionConfig, CompactionPicker};
 use crate::hummock::level_handler::{LevelHandler, SSTableStat};
 use crate::hummock::model::HUMMOCK_DEFAULT_CF_NAME;
 use crate::model::Transactional;
@@ -44,14 +44,24 @@ pub struct CompactStatus {
 }
 
 impl CompactStatus {
-    pub fn new() -> CompactStatus {
-        let vec_handler_having_l0 = vec![
-            LevelHandler::Overlapping(vec![], vec![]),
-            LevelHandler::Nonoverlapping(vec![], vec![]),
-        ];
-        CompactStatus {
-            level_handlers: vec_handler_having_l0,
+    /// Creates a compact status with an overlapping L0 followed by `num_levels - 1`
+    /// non-overlapping levels.
+    pub fn new(num_levels: usize) -> CompactStatus {
+        let mut compact_status = CompactStatus {
+            level_handlers: vec![LevelHandler::Overlapping(vec![], vec![])],
             next_compact_task_id: 1,
+        };
+        compact_status.ensure_num_levels(num_levels);
+        compact_status
+    }
+
+    /// Appends empty non-overlapping levels until there are at least `num_levels` levels, e.g.
+    /// after the number of levels is raised in the config. Levels are never removed.
+    pub fn ensure_num_levels(&mut self, num_levels: usize) {
+        assert!(num_levels >= 2);
+        while self.level_handlers.len() < num_levels {
+            self.level_handlers
+                .push(LevelHandler::Nonoverlapping(vec![], vec![]));
         }
     }
 
@@ -80,30 +90,26 @@ impl CompactStatus {
         }
     }
 
-    pub fn get_compact_task(&mut self) -> Option<CompactTask> {
+    pub fn get_compact_task(&mut self, picker: &dyn CompactionPicker) -> Option<CompactTask> {
+        picker
+            .pick_levels(&self.level_handlers)
+            .into_iter()
+            .find_map(|select_level| {
+                let tier = picker.pick_tier(select_level, &self.level_handlers);
+                self.get_compact_task_from_level(select_level, tier)
+            })
+    }
+
+    /// Tries to compact SSTs in `select_level` into the level right below it. SSTs whose sizes are
+    /// within `tier` are preferred, and the largest SSTs are preferred without `tier`.
+    fn get_compact_task_from_level(
+        &mut self,
+        select_level: u32,
+        tier: Option<(u64, u64)>,
+    ) -> Option<CompactTask> {
         // When we compact the files, we must make the result of compaction meet the following
         // conditions, for any user key, the epoch of it in the file existing in the lower
         // layer must be larger.
-        let num_levels = self.level_handlers.len();
-        let mut idle_levels = Vec::with_capacity(num_levels - 1);
-        for (level_handler_idx, level_handler) in
-            self.level_handlers[..num_levels - 1].iter().enumerate()
-        {
-            match level_handler {
-                LevelHandler::Overlapping(_, compacting_key_ranges)
-                | LevelHandler::Nonoverlapping(_, compacting_key_ranges) => {
-                    if compacting_key_ranges.is_empty() {
-                        idle_levels.push(level_handler_idx);
-                    }
-                }
-            }
-        }
-        let select_level = if idle_levels.is_empty() {
-            0
-        } else {
-            *idle_levels.first().unwrap() as u32
-        };
-
         enum SearchResult {
             Found(Vec<u64>, Vec<u64>, Vec<KeyRange>),
             NotFound,
@@ -178,8 +184,20 @@ impl CompactStatus {
                     }
                 }
 
-                let mut rng = thread_rng();
-                polysst_candidates.shuffle(&mut rng);
+                // Candidates with more SSTs in the tier first, then larger ones first. The sort is
+                // stable, so candidates of the same priority are tried in key order.
+                polysst_candidates.sort_by_key(|((sst_idx, next_sst_idx), _, _)| {
+                    let ssts = &l_n[*sst_idx..*next_sst_idx];
+                    let in_tier = match tier {
+                        Some((min_size, max_size)) => ssts
+                            .iter()
+                            .filter(|sst| (min_size..=max_size).contains(&sst.file_size))
+                            .count(),
+                        None => 0,
+                    };
+                    let total_size: u64 = ssts.iter().map(|sst| sst.file_size).sum();
+                    Reverse((in_tier, total_size))
+                });
 
                 for ((sst_idx, next_sst_idx), select_level_inputs, key_range) in polysst_candidates
                 {
@@ -324,6 +342,7 @@ impl CompactStatus {
                                             id,
                                             // compact node will never use key_range in SstableInfo.
                                             key_range: None,
+                                            file_size: 0,
                                         })
                                         .collect_vec(),
                                 })
@@ -335,6 +354,7 @@ impl CompactStatus {
                                         .map(|id| SstableInfo {
                                             id,
                                             key_range: None,
+                                            file_size: 0,
                                         })
                                         .collect_vec(),
                                 })
@@ -350,6 +370,7 @@ impl CompactStatus {
                                         .map(|id| SstableInfo {
                                             id,
                                             key_range: None,
+                                            file_size: 0,
                                         })
                                         .collect_vec(),
                                 })
@@ -361,6 +382,7 @@ impl CompactStatus {
                                         .map(|id| SstableInfo {
                                             id,
                                             key_range: None,
+                                            file_size: 0,
                                         })
                                         .collect_vec(),
                                 })
@@ -393,6 +415,7 @@ impl CompactStatus {
                         }),
                     }),
                     task_status: false,
+                    state_ttls: vec![],
                 };
                 Some(compact_task)
             }
@@ -484,7 +507,7 @@ impl Transactional for CompactStatus {
 
 impl Default for CompactStatus {
     fn default() -> Self {
-        Self::new()
+        Self::new(CompactionConfig::default().num_levels)
     }
 }
 
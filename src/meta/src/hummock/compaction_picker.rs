@@ -0,0 +1,490 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::cmp::Ordering;
+
+use crate::hummock::level_handler::LevelHandler;
+
+/// Decides which levels of the LSM tree should be compacted next. A picked level is always
+/// compacted into the level right below it, so the bottommost level is never picked.
+pub trait CompactionPicker: Send + Sync {
+    /// Returns the levels to compact from, most urgent first. `CompactStatus` tries them in order
+    /// until it finds idle SSTs to compact.
+    fn pick_levels(&self, level_handlers: &[LevelHandler]) -> Vec<u32>;
+
+    /// Returns the size range `(min, max)` of the SSTs to compact first from `level_idx`, if the
+    /// level is compacted by tiers.
+    fn pick_tier(&self, _level_idx: u32, _level_handlers: &[LevelHandler]) -> Option<(u64, u64)> {
+        None
+    }
+}
+
+/// The strategy to pick levels to compact.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ArgEnum)]
+pub enum CompactionStrategy {
+    /// See [`LeveledCompactionPicker`].
+    Leveled,
+    /// See [`SizeTieredCompactionPicker`].
+    SizeTiered,
+}
+
+impl CompactionStrategy {
+    pub fn build_picker(self, config: CompactionConfig) -> Box<dyn CompactionPicker> {
+        match self {
+            CompactionStrategy::Leveled => Box::new(LeveledCompactionPicker::new(config)),
+            CompactionStrategy::SizeTiered => Box::new(SizeTieredCompactionPicker::new(config)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CompactionConfig {
+    /// Number of levels, including L0.
+    pub num_levels: usize,
+    /// Target total size of L1. Level N (N >= 1) targets `max_bytes_for_level_base *
+    /// max_bytes_for_level_multiplier ^ (N - 1)` bytes.
+    pub max_bytes_for_level_base: u64,
+    pub max_bytes_for_level_multiplier: u64,
+    /// L0 is compacted once it holds this many SSTs of similar size.
+    pub level0_tier_compact_file_number: usize,
+    /// SSTs whose sizes differ by no more than this ratio are considered to be in the same tier.
+    pub level0_tier_size_ratio: f64,
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            num_levels: 7,
+            max_bytes_for_level_base: 512 * 1024 * 1024,
+            max_bytes_for_level_multiplier: 10,
+            level0_tier_compact_file_number: 4,
+            level0_tier_size_ratio: 2.0,
+        }
+    }
+}
+
+/// Picks L0 once enough SSTs of similar size have piled up in it, so that each compaction merges
+/// SSTs of the same tier and no SST is rewritten over and over with much smaller ones.
+#[derive(Clone, Debug, Default)]
+pub struct SizeTieredCompactionPicker {
+    config: CompactionConfig,
+}
+
+impl SizeTieredCompactionPicker {
+    pub fn new(config: CompactionConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the largest group of idle SSTs whose sizes are within `level0_tier_size_ratio`, as
+    /// `(number of SSTs, min size, max size)`.
+    fn largest_tier(&self, level_handler: &LevelHandler) -> (usize, u64, u64) {
+        let mut sizes = level_handler
+            .idle_ssts()
+            .map(|sst| sst.file_size)
+            .collect::<Vec<_>>();
+        sizes.sort_unstable();
+        let mut largest_tier = (0, 0, 0);
+        let mut tier_begin = 0;
+        for (idx, size) in sizes.iter().enumerate() {
+            while (*size as f64) > (sizes[tier_begin] as f64) * self.config.level0_tier_size_ratio {
+                tier_begin += 1;
+            }
+            if idx + 1 - tier_begin > largest_tier.0 {
+                largest_tier = (idx + 1 - tier_begin, sizes[tier_begin], *size);
+            }
+        }
+        largest_tier
+    }
+
+    /// Returns the score of L0. L0 needs compaction when its score is no less than 1.
+    pub fn score(&self, level_handler: &LevelHandler) -> f64 {
+        let (tier_len, _, _) = self.largest_tier(level_handler);
+        tier_len as f64 / self.config.level0_tier_compact_file_number.max(1) as f64
+    }
+}
+
+impl CompactionPicker for SizeTieredCompactionPicker {
+    fn pick_levels(&self, level_handlers: &[LevelHandler]) -> Vec<u32> {
+        if level_handlers.len() > 1 && self.score(&level_handlers[0]) >= 1.0 {
+            vec![0]
+        } else {
+            vec![]
+        }
+    }
+
+    fn pick_tier(&self, level_idx: u32, level_handlers: &[LevelHandler]) -> Option<(u64, u64)> {
+        if level_idx != 0 {
+            return None;
+        }
+        match self.largest_tier(&level_handlers[0]) {
+            (0, _, _) => None,
+            (_, min_size, max_size) => Some((min_size, max_size)),
+        }
+    }
+}
+
+/// Picks levels by score. L0 is scored by [`SizeTieredCompactionPicker`], and each other level is
+/// scored by the ratio of its size to its target size, which grows by
+/// `max_bytes_for_level_multiplier` per level.
+#[derive(Clone, Debug, Default)]
+pub struct LeveledCompactionPicker {
+    config: CompactionConfig,
+    level0_picker: SizeTieredCompactionPicker,
+}
+
+impl LeveledCompactionPicker {
+    pub fn new(config: CompactionConfig) -> Self {
+        Self {
+            level0_picker: SizeTieredCompactionPicker::new(config.clone()),
+            config,
+        }
+    }
+
+    fn level_target_size(&self, level_idx: usize) -> u64 {
+        let mut target = self.config.max_bytes_for_level_base;
+        for _ in 1..level_idx {
+            target = target.saturating_mul(self.config.max_bytes_for_level_multiplier);
+        }
+        target
+    }
+
+    /// Returns the score of each level except the bottommost one.
+    pub fn level_scores(&self, level_handlers: &[LevelHandler]) -> Vec<f64> {
+        let num_levels = level_handlers.len();
+        if num_levels < 2 {
+            return vec![];
+        }
+        level_handlers[..num_levels - 1]
+            .iter()
+            .enumerate()
+            .map(|(level_idx, level_handler)| {
+                if level_idx == 0 {
+                    self.level0_picker.score(level_handler)
+                } else {
+                    let level_size: u64 = level_handler.idle_ssts().map(|sst| sst.file_size).sum();
+                    level_size as f64 / self.level_target_size(level_idx).max(1) as f64
+                }
+            })
+            .collect()
+    }
+}
+
+impl CompactionPicker for LeveledCompactionPicker {
+    fn pick_levels(&self, level_handlers: &[LevelHandler]) -> Vec<u32> {
+        let mut scores = self
+            .level_scores(level_handlers)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, score)| *score >= 1.0)
+            .collect::<Vec<_>>();
+        // Higher score first. Upper level first among equal scores.
+        scores.sort_by(|(l_idx, l_score), (r_idx, r_score)| {
+            r_score
+                .partial_cmp(l_score)
+                .unwrap_or(Ordering::Equal)
+                .then(l_idx.cmp(r_idx))
+        });
+        scores
+            .into_iter()
+            .map(|(level_idx, _)| level_idx as u32)
+            .collect()
+    }
+
+    fn pick_tier(&self, level_idx: u32, level_handlers: &[LevelHandler]) -> Option<(u64, u64)> {
+        self.level0_picker.pick_tier(level_idx, level_handlers)
+    }
+}
+
+/// Picks the given level regardless of its score. Used by manual compaction.
+#[derive(Clone, Debug)]
+pub struct ManualCompactionPicker {
+    level: u32,
+}
+
+impl ManualCompactionPicker {
+    pub fn new(level: u32) -> Self {
+        Self { level }
+    }
+}
+
+impl CompactionPicker for ManualCompactionPicker {
+    fn pick_levels(&self, level_handlers: &[LevelHandler]) -> Vec<u32> {
+        if (self.level as usize) + 1 < level_handlers.len() {
+            vec![self.level]
+        } else {
+            vec![]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use itertools::Itertools;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use risingwave_hummock_sdk::key::key_with_epoch;
+    use risingwave_hummock_sdk::key_range::KeyRange;
+
+    use super::*;
+    use crate::hummock::compaction::CompactStatus;
+    use crate::hummock::level_handler::SSTableStat;
+
+    const SST_SIZE: u64 = 1 << 20;
+    const KEY_SPACE: u64 = 1 << 20;
+
+    fn full_key(user_key: u64, epoch: u64) -> Vec<u8> {
+        key_with_epoch(format!("key{:010}", user_key).into_bytes(), epoch)
+    }
+
+    fn test_sst(table_id: u64, file_size: u64) -> SSTableStat {
+        SSTableStat {
+            key_range: KeyRange::new(full_key(table_id, 1).into(), full_key(table_id, 1).into()),
+            table_id,
+            file_size,
+            compact_task: None,
+        }
+    }
+
+    #[test]
+    fn test_size_tiered_score() {
+        let picker = SizeTieredCompactionPicker::new(CompactionConfig {
+            level0_tier_compact_file_number: 3,
+            ..Default::default()
+        });
+        let mut l0 = vec![test_sst(1, 10), test_sst(2, 100), test_sst(3, 15)];
+        // The largest tier is {10, 15}.
+        assert_eq!(
+            picker.score(&LevelHandler::Overlapping(l0.clone(), vec![])),
+            2.0 / 3.0
+        );
+        l0.push(test_sst(4, 20));
+        assert_eq!(
+            picker.score(&LevelHandler::Overlapping(l0.clone(), vec![])),
+            1.0
+        );
+        // SSTs being compacted are not counted.
+        l0[0].compact_task = Some(1);
+        assert_eq!(
+            picker.score(&LevelHandler::Overlapping(l0, vec![])),
+            2.0 / 3.0
+        );
+    }
+
+    #[test]
+    fn test_pick_levels() {
+        let picker = LeveledCompactionPicker::new(CompactionConfig {
+            num_levels: 4,
+            max_bytes_for_level_base: 100,
+            max_bytes_for_level_multiplier: 10,
+            level0_tier_compact_file_number: 4,
+            level0_tier_size_ratio: 2.0,
+        });
+        let level_handlers = vec![
+            LevelHandler::Overlapping(vec![test_sst(1, 10), test_sst(2, 10)], vec![]),
+            LevelHandler::Nonoverlapping(vec![test_sst(3, 150)], vec![]),
+            LevelHandler::Nonoverlapping(vec![test_sst(4, 3000)], vec![]),
+            LevelHandler::Nonoverlapping(vec![test_sst(5, u64::MAX)], vec![]),
+        ];
+        assert_eq!(picker.level_scores(&level_handlers), vec![0.5, 1.5, 3.0]);
+        assert_eq!(picker.pick_levels(&level_handlers), vec![2, 1]);
+
+        assert_eq!(
+            ManualCompactionPicker::new(0).pick_levels(&level_handlers),
+            vec![0]
+        );
+        // The bottommost level can't be compacted.
+        assert!(ManualCompactionPicker::new(3)
+            .pick_levels(&level_handlers)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_pick_tier() {
+        let picker = SizeTieredCompactionPicker::new(CompactionConfig {
+            level0_tier_compact_file_number: 3,
+            ..Default::default()
+        });
+        let mut compact_status = CompactStatus::new(2);
+        compact_status.level_handlers[0] = LevelHandler::Overlapping(
+            vec![
+                test_sst(1, 100),
+                test_sst(2, 10),
+                test_sst(3, 12),
+                test_sst(4, 11),
+            ],
+            vec![],
+        );
+        assert_eq!(
+            picker.pick_tier(0, &compact_status.level_handlers),
+            Some((10, 12))
+        );
+        // The largest SST of the tier is compacted first, rather than the much larger SST 1.
+        let compact_task = compact_status.get_compact_task(&picker).unwrap();
+        let input_ssts = compact_task.input_ssts[0]
+            .level
+            .as_ref()
+            .unwrap()
+            .table_infos
+            .iter()
+            .map(|sst| sst.id)
+            .collect_vec();
+        assert_eq!(input_ssts, vec![3]);
+        // The rest of the tier is too small to compact.
+        assert!(compact_status.get_compact_task(&picker).is_none());
+    }
+
+    /// Flushes SSTs into L0 and runs every compact task right away. The simulated compactor
+    /// merges the input SSTs of a task and evenly splits the merged key range into SSTs of about
+    /// `SST_SIZE` bytes. No key is dropped in compaction.
+    struct Simulator {
+        compact_status: CompactStatus,
+        /// table id -> (smallest user key, largest user key, file size)
+        ssts: HashMap<u64, (u64, u64, u64)>,
+        next_table_id: u64,
+        epoch: u64,
+        flushed_bytes: u64,
+        compacted_bytes: u64,
+    }
+
+    impl Simulator {
+        fn new(num_levels: usize) -> Self {
+            Self {
+                compact_status: CompactStatus::new(num_levels),
+                ssts: HashMap::new(),
+                next_table_id: 1,
+                epoch: 0,
+                flushed_bytes: 0,
+                compacted_bytes: 0,
+            }
+        }
+
+        fn new_sst(&mut self, left: u64, right: u64, file_size: u64) -> SSTableStat {
+            let table_id = self.next_table_id;
+            self.next_table_id += 1;
+            self.ssts.insert(table_id, (left, right, file_size));
+            SSTableStat {
+                key_range: KeyRange::new(
+                    full_key(left, self.epoch).into(),
+                    full_key(right, self.epoch).into(),
+                ),
+                table_id,
+                file_size,
+                compact_task: None,
+            }
+        }
+
+        fn flush(&mut self, left: u64, right: u64, file_size: u64) {
+            self.epoch += 1;
+            self.flushed_bytes += file_size;
+            let sst = self.new_sst(left, right, file_size);
+            match &mut self.compact_status.level_handlers[0] {
+                LevelHandler::Overlapping(l0, _) => {
+                    let insert_point = l0.partition_point(|other| other.key_range <= sst.key_range);
+                    l0.insert(insert_point, sst);
+                }
+                LevelHandler::Nonoverlapping(_, _) => unreachable!(),
+            }
+        }
+
+        fn compact(&mut self, picker: &dyn CompactionPicker) {
+            while let Some(mut compact_task) = self.compact_status.get_compact_task(picker) {
+                let (left, right, input_size) = compact_task
+                    .input_ssts
+                    .iter()
+                    .flat_map(|level| level.level.as_ref().unwrap().table_infos.iter())
+                    .map(|sst| self.ssts.remove(&sst.id).unwrap())
+                    .fold(
+                        (u64::MAX, 0, 0),
+                        |(l, r, size), (sst_l, sst_r, sst_size)| {
+                            (l.min(sst_l), r.max(sst_r), size + sst_size)
+                        },
+                    );
+                self.compacted_bytes += input_size;
+
+                let key_count = right - left + 1;
+                let output_count = ((input_size + SST_SIZE - 1) / SST_SIZE).min(key_count);
+                let output_ssts = (0..output_count)
+                    .map(|idx| {
+                        self.new_sst(
+                            left + key_count * idx / output_count,
+                            left + key_count * (idx + 1) / output_count - 1,
+                            input_size / output_count,
+                        )
+                    })
+                    .collect_vec();
+
+                compact_task.task_status = true;
+                self.compact_status
+                    .report_compact_task(output_ssts, compact_task)
+                    .unwrap();
+            }
+        }
+
+        fn write_amplification(&self) -> f64 {
+            (self.flushed_bytes + self.compacted_bytes) as f64 / self.flushed_bytes as f64
+        }
+    }
+
+    fn simulate(config: CompactionConfig) -> Simulator {
+        let mut simulator = Simulator::new(config.num_levels);
+        let picker = LeveledCompactionPicker::new(config);
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..2000 {
+            let left = rng.gen_range(0..KEY_SPACE);
+            let right = (left + rng.gen_range(0..KEY_SPACE / 16)).min(KEY_SPACE - 1);
+            let file_size = rng.gen_range(SST_SIZE / 2..=SST_SIZE);
+            simulator.flush(left, right, file_size);
+            simulator.compact(&picker);
+        }
+        simulator
+    }
+
+    #[test]
+    fn test_simulated_write_amplification() {
+        let config = CompactionConfig {
+            num_levels: 3,
+            max_bytes_for_level_base: 128 * SST_SIZE,
+            max_bytes_for_level_multiplier: 10,
+            level0_tier_compact_file_number: 4,
+            level0_tier_size_ratio: 2.0,
+        };
+        let leveled = simulate(config.clone());
+        // The simulation is deterministic.
+        let leveled_again = simulate(config.clone());
+        assert_eq!(leveled.compact_status, leveled_again.compact_status);
+        assert_eq!(leveled.compacted_bytes, leveled_again.compacted_bytes);
+        // No level exceeds its target after compaction.
+        assert!(LeveledCompactionPicker::new(config)
+            .level_scores(&leveled.compact_status.level_handlers)
+            .into_iter()
+            .all(|score| score < 1.0));
+
+        // Compacting every SST down as soon as it is flushed rewrites the bottommost level with
+        // small SSTs spanning wide key ranges, which costs much more.
+        let eager = simulate(CompactionConfig {
+            num_levels: 3,
+            max_bytes_for_level_base: 1,
+            max_bytes_for_level_multiplier: 1,
+            level0_tier_compact_file_number: 1,
+            level0_tier_size_ratio: 2.0,
+        });
+        assert!(
+            leveled.write_amplification() < eager.write_amplification(),
+            "leveled {} eager {}",
+            leveled.write_amplification(),
+            eager.write_amplification()
+        );
+    }
+}
@@ -14,8 +14,10 @@
 
 use std::sync::Arc;
 
+use itertools::Itertools;
 use risingwave_common::error::{ErrorCode, Result, ToErrorStr};
 use risingwave_hummock_sdk::HummockContextId;
+use risingwave_pb::common::WorkerType;
 use risingwave_pb::hummock::{CompactTask, SubscribeCompactTasksResponse, VacuumTask};
 use tokio::sync::mpsc::{Receiver, Sender};
 
@@ -23,6 +25,8 @@ const STREAM_BUFFER_SIZE: usize = 4;
 
 pub struct Compactor {
     context_id: HummockContextId,
+    /// Either a dedicated compactor or a compute node that runs compaction along with streaming.
+    worker_type: WorkerType,
     sender: Sender<Result<SubscribeCompactTasksResponse>>,
 }
 
@@ -44,6 +48,10 @@ impl Compactor {
     pub fn context_id(&self) -> HummockContextId {
         self.context_id
     }
+
+    pub fn worker_type(&self) -> WorkerType {
+        self.worker_type
+    }
 }
 
 struct CompactorManagerInner {
@@ -90,15 +98,26 @@ impl CompactorManager {
         }
     }
 
-    /// Gets next compactor to assign task.
+    /// Gets next compactor to assign task. Dedicated compactors are preferred, and compute nodes
+    /// are used only if there is no dedicated compactor.
     pub fn next_compactor(&self) -> Option<Arc<Compactor>> {
         let mut guard = self.inner.write();
         if guard.compactors.is_empty() {
             tracing::warn!("No compactor is available.");
             return None;
         }
-        let compactor_index = guard.next_compactor % guard.compactors.len();
-        let compactor = guard.compactors[compactor_index].clone();
+        let dedicated_compactors = guard
+            .compactors
+            .iter()
+            .filter(|c| c.worker_type == WorkerType::Compactor)
+            .cloned()
+            .collect_vec();
+        let candidates = if dedicated_compactors.is_empty() {
+            &guard.compactors
+        } else {
+            &dedicated_compactors
+        };
+        let compactor = candidates[guard.next_compactor % candidates.len()].clone();
         guard.next_compactor += 1;
         Some(compactor)
     }
@@ -107,15 +126,17 @@ impl CompactorManager {
     pub fn add_compactor(
         &self,
         context_id: HummockContextId,
+        worker_type: WorkerType,
     ) -> Receiver<Result<SubscribeCompactTasksResponse>> {
         let (tx, rx) = tokio::sync::mpsc::channel(STREAM_BUFFER_SIZE);
         let mut guard = self.inner.write();
         guard.compactors.retain(|c| c.context_id != context_id);
         guard.compactors.push(Arc::new(Compactor {
             context_id,
+            worker_type,
             sender: tx,
         }));
-        tracing::info!("Added compactor {}", context_id);
+        tracing::info!("Added compactor {} of {:?}", context_id, worker_type);
         rx
     }
 
@@ -130,6 +151,7 @@ impl CompactorManager {
 
 #[cfg(test)]
 mod tests {
+    use risingwave_pb::common::WorkerType;
     use risingwave_pb::hummock::{CompactMetrics, CompactTask, TableSetStatistics};
     use tokio::sync::mpsc::error::TryRecvError;
 
@@ -170,6 +192,7 @@ mod tests {
                 write: Some(TableSetStatistics::default()),
             }),
             task_status: false,
+            state_ttls: vec![],
         }
     }
 
@@ -179,9 +202,9 @@ mod tests {
         // No compactors by default.
         assert_eq!(compactor_manager.inner.read().compactors.len(), 0);
 
-        let mut receiver = compactor_manager.add_compactor(1);
+        let mut receiver = compactor_manager.add_compactor(1, WorkerType::ComputeNode);
         assert_eq!(compactor_manager.inner.read().compactors.len(), 1);
-        let _receiver_2 = compactor_manager.add_compactor(2);
+        let _receiver_2 = compactor_manager.add_compactor(2, WorkerType::ComputeNode);
         assert_eq!(compactor_manager.inner.read().compactors.len(), 2);
         compactor_manager.remove_compactor(2);
         assert_eq!(compactor_manager.inner.read().compactors.len(), 1);
@@ -227,7 +250,7 @@ mod tests {
         assert!(compactor_manager.next_compactor().is_none());
 
         // Add a compactor.
-        let mut receiver = compactor_manager.add_compactor(context_id);
+        let mut receiver = compactor_manager.add_compactor(context_id, WorkerType::ComputeNode);
         assert_eq!(compactor_manager.inner.read().compactors.len(), 1);
         let compactor = compactor_manager.next_compactor().unwrap();
         // No compact task.
@@ -258,7 +281,7 @@ mod tests {
         let compactor_manager = CompactorManager::new();
         let mut receivers = vec![];
         for context_id in 0..5 {
-            receivers.push(compactor_manager.add_compactor(context_id));
+            receivers.push(compactor_manager.add_compactor(context_id, WorkerType::ComputeNode));
         }
         assert_eq!(compactor_manager.inner.read().compactors.len(), 5);
         for i in 0..receivers.len() * 3 {
@@ -266,4 +289,24 @@ mod tests {
             assert_eq!(compactor.context_id as usize, i % receivers.len());
         }
     }
+
+    #[tokio::test]
+    async fn test_next_compactor_prefer_dedicated() {
+        let compactor_manager = CompactorManager::new();
+        let _receiver_1 = compactor_manager.add_compactor(1, WorkerType::ComputeNode);
+        let _receiver_2 = compactor_manager.add_compactor(2, WorkerType::Compactor);
+        let _receiver_3 = compactor_manager.add_compactor(3, WorkerType::ComputeNode);
+        let _receiver_4 = compactor_manager.add_compactor(4, WorkerType::Compactor);
+        for i in 0..6 {
+            let compactor = compactor_manager.next_compactor().unwrap();
+            assert_eq!(compactor.worker_type(), WorkerType::Compactor);
+            assert_eq!(compactor.context_id(), [2, 4][i % 2]);
+        }
+
+        // Fall back to compute nodes.
+        compactor_manager.remove_compactor(2);
+        compactor_manager.remove_compactor(4);
+        let compactor = compactor_manager.next_compactor().unwrap();
+        assert_eq!(compactor.worker_type(), WorkerType::ComputeNode);
+    }
 }
@@ -26,14 +26,16 @@ use risingwave_hummock_sdk::{
     INVALID_EPOCH,
 };
 use risingwave_pb::hummock::{
-    CompactTask, CompactTaskAssignment, HummockPinnedSnapshot, HummockPinnedVersion,
-    HummockSnapshot, HummockStaleSstables, HummockVersion, Level, LevelType, SstableIdInfo,
-    SstableInfo, UncommittedEpoch,
+    CompactTask, CompactTaskAssignment, HummockBackup, HummockBackupManifest,
+    HummockPinnedSnapshot, HummockPinnedVersion, HummockSnapshot, HummockStaleSstables,
+    HummockVersion, Level, LevelType, SstableIdInfo, SstableInfo, UncommittedEpoch,
 };
 use tokio::sync::{Mutex, RwLock};
 
 use crate::cluster::{ClusterManagerRef, META_NODE_ID};
+use crate::hummock::backup::dump_meta_store;
 use crate::hummock::compaction::CompactStatus;
+use crate::hummock::compaction_picker::{CompactionPicker, ManualCompactionPicker};
 use crate::hummock::level_handler::{LevelHandler, SSTableStat};
 use crate::hummock::metrics_utils::{trigger_commit_stat, trigger_rw_stat, trigger_sst_stat};
 use crate::hummock::model::{
@@ -44,6 +46,7 @@ use crate::manager::{IdCategory, MetaSrvEnv};
 use crate::model::{MetadataModel, ValTransaction, VarTransaction, Worker};
 use crate::rpc::metrics::MetaMetrics;
 use crate::storage::{Error, MetaStore, Transaction};
+use crate::stream::FragmentManagerRef;
 
 // Update to states are performed as follow:
 // - Initialize ValTransaction for the meta state to update
@@ -54,10 +57,12 @@ use crate::storage::{Error, MetaStore, Transaction};
 pub struct HummockManager<S: MetaStore> {
     env: MetaSrvEnv<S>,
     cluster_manager: ClusterManagerRef<S>,
+    fragment_manager: FragmentManagerRef<S>,
     // When trying to locks compaction and versioning at the same time, compaction lock should
     // be requested before versioning lock.
     compaction: Mutex<Compaction>,
     versioning: RwLock<Versioning>,
+    compaction_picker: Box<dyn CompactionPicker>,
 
     metrics: Arc<MetaMetrics>,
 }
@@ -110,6 +115,7 @@ struct Versioning {
     pinned_snapshots: BTreeMap<HummockContextId, HummockPinnedSnapshot>,
     stale_sstables: BTreeMap<HummockVersionId, HummockStaleSstables>,
     sstable_id_infos: BTreeMap<HummockSSTableId, SstableIdInfo>,
+    backups: BTreeMap<u64, HummockBackup>,
 }
 
 impl<S> HummockManager<S>
@@ -119,8 +125,14 @@ where
     pub async fn new(
         env: MetaSrvEnv<S>,
         cluster_manager: ClusterManagerRef<S>,
+        fragment_manager: FragmentManagerRef<S>,
         metrics: Arc<MetaMetrics>,
     ) -> Result<HummockManager<S>> {
+        let num_levels = env.opts.compaction_config.num_levels;
+        let compaction_picker = env
+            .opts
+            .compaction_strategy
+            .build_picker(env.opts.compaction_config.clone());
         let instance = HummockManager {
             env,
             versioning: RwLock::new(Versioning {
@@ -130,13 +142,16 @@ where
                 pinned_snapshots: Default::default(),
                 stale_sstables: Default::default(),
                 sstable_id_infos: Default::default(),
+                backups: Default::default(),
             }),
             compaction: Mutex::new(Compaction {
-                compact_status: CompactStatus::new(),
+                compact_status: CompactStatus::new(num_levels),
                 compact_task_assignment: Default::default(),
             }),
+            compaction_picker,
             metrics,
             cluster_manager,
+            fragment_manager,
         };
 
         instance.load_meta_store_state().await?;
@@ -150,9 +165,13 @@ where
     /// Load state from meta store.
     async fn load_meta_store_state(&self) -> Result<()> {
         let mut compaction_guard = self.compaction.lock().await;
+        let num_levels = self.env.opts.compaction_config.num_levels;
         compaction_guard.compact_status = CompactStatus::get(self.env.meta_store())
             .await?
-            .unwrap_or_else(CompactStatus::new);
+            .unwrap_or_else(|| CompactStatus::new(num_levels));
+        compaction_guard
+            .compact_status
+            .ensure_num_levels(num_levels);
 
         compaction_guard.compact_task_assignment =
             CompactTaskAssignment::list(self.env.meta_store())
@@ -176,16 +195,16 @@ where
         if versioning_guard.hummock_versions.is_empty() {
             let init_version = HummockVersion {
                 id: versioning_guard.current_version_id.id(),
-                levels: vec![
-                    Level {
-                        level_type: LevelType::Overlapping as i32,
+                levels: (0..num_levels)
+                    .map(|level_idx| Level {
+                        level_type: if level_idx == 0 {
+                            LevelType::Overlapping
+                        } else {
+                            LevelType::Nonoverlapping
+                        } as i32,
                         table_infos: vec![],
-                    },
-                    Level {
-                        level_type: LevelType::Nonoverlapping as i32,
-                        table_infos: vec![],
-                    },
-                ],
+                    })
+                    .collect(),
                 uncommitted_epochs: vec![],
                 max_committed_epoch: INVALID_EPOCH,
                 safe_epoch: INVALID_EPOCH,
@@ -219,6 +238,12 @@ where
             .map(|s| (s.id, s))
             .collect();
 
+        versioning_guard.backups = HummockBackup::list(self.env.meta_store())
+            .await?
+            .into_iter()
+            .map(|b| (b.id, b))
+            .collect();
+
         Ok(())
     }
 
@@ -524,6 +549,27 @@ where
         &self,
         assignee_context_id: HummockContextId,
     ) -> Result<Option<CompactTask>> {
+        self.get_compact_task_impl(assignee_context_id, self.compaction_picker.as_ref())
+            .await
+    }
+
+    /// Gets a compact task that compacts `level` into the level below it, no matter whether
+    /// `level` needs compaction.
+    pub async fn get_manual_compact_task(
+        &self,
+        assignee_context_id: HummockContextId,
+        level: u32,
+    ) -> Result<Option<CompactTask>> {
+        self.get_compact_task_impl(assignee_context_id, &ManualCompactionPicker::new(level))
+            .await
+    }
+
+    async fn get_compact_task_impl(
+        &self,
+        assignee_context_id: HummockContextId,
+        picker: &dyn CompactionPicker,
+    ) -> Result<Option<CompactTask>> {
+        let state_ttls = self.fragment_manager.state_ttls().await;
         let mut compaction_guard = self.compaction.lock().await;
 
         let compaction = compaction_guard.deref_mut();
@@ -537,11 +583,12 @@ where
             }
         }
 
-        let compact_task = compact_status.get_compact_task();
+        let compact_task = compact_status.get_compact_task(picker);
         let mut should_commit = false;
         let ret = match compact_task {
             None => Ok(None),
             Some(mut compact_task) => {
+                compact_task.state_ttls = state_ttls;
                 compact_task_assignment.insert(
                     compact_task.task_id,
                     CompactTaskAssignment {
@@ -661,11 +708,13 @@ where
                                     |SSTableStat {
                                          table_id,
                                          key_range,
+                                         file_size,
                                          ..
                                      }| {
                                         SstableInfo {
                                             id: *table_id,
                                             key_range: Some(key_range.clone().into()),
+                                            file_size: *file_size,
                                         }
                                     },
                                 )
@@ -679,11 +728,13 @@ where
                                     |SSTableStat {
                                          table_id,
                                          key_range,
+                                         file_size,
                                          ..
                                      }| {
                                         SstableInfo {
                                             id: *table_id,
                                             key_range: Some(key_range.clone().into()),
+                                            file_size: *file_size,
                                         }
                                     },
                                 )
@@ -1123,6 +1174,7 @@ where
             let pinned_snapshots_copy = versioning_guard.pinned_snapshots.clone();
             let stale_sstables_copy = versioning_guard.stale_sstables.clone();
             let sst_id_infos_copy = versioning_guard.sstable_id_infos.clone();
+            let backups_copy = versioning_guard.backups.clone();
             (
                 compact_status_copy,
                 compact_task_assignment_copy,
@@ -1132,6 +1184,7 @@ where
                 pinned_snapshots_copy,
                 stale_sstables_copy,
                 sst_id_infos_copy,
+                backups_copy,
             )
         };
         let mem_state = get_state().await;
@@ -1247,6 +1300,99 @@ where
         Ok(marked)
     }
 
+    /// Creates a backup of the current version, whose SSTs are retained until the backup is
+    /// deleted. Returns the backup together with a dump of meta store taken at the same time.
+    pub async fn create_backup(&self, path: String) -> Result<HummockBackupManifest> {
+        let mut versioning_guard = self.versioning.write().await;
+        let versioning = versioning_guard.deref_mut();
+        let current_version = versioning
+            .hummock_versions
+            .get(&versioning.current_version_id.id())
+            .unwrap();
+        let backup = HummockBackup {
+            id: versioning.backups.keys().last().map_or(1, |id| id + 1),
+            version_id: current_version.id,
+            sstable_ids: current_version
+                .levels
+                .iter()
+                .flat_map(|level| level.table_infos.iter())
+                .chain(
+                    current_version
+                        .uncommitted_epochs
+                        .iter()
+                        .flat_map(|epoch| epoch.tables.iter()),
+                )
+                .map(|sst| sst.id)
+                .collect_vec(),
+            path,
+            create_timestamp: sstable_id_info::get_timestamp_now(),
+        };
+        let mut backups = VarTransaction::new(&mut versioning.backups);
+        backups.insert(backup.id, backup.clone());
+        commit_multi_var!(self, None, backups)?;
+
+        // Versions can't change while the versioning lock is held, so the dump contains exactly
+        // the version being backed up as the current version.
+        let snapshot = self.env.meta_store().snapshot().await;
+        let dump = dump_meta_store(&snapshot).await;
+        drop(snapshot);
+        let meta_store = match dump {
+            Ok(meta_store) => meta_store,
+            Err(e) => {
+                // The backup is useless without the dump, so its SSTs are not retained.
+                let mut backups = VarTransaction::new(&mut versioning.backups);
+                backups.remove(&backup.id);
+                commit_multi_var!(self, None, backups)?;
+                return Err(e);
+            }
+        };
+
+        #[cfg(test)]
+        {
+            drop(versioning_guard);
+            self.check_state_consistency().await;
+        }
+
+        Ok(HummockBackupManifest {
+            backup: Some(backup),
+            meta_store,
+        })
+    }
+
+    pub async fn list_backups(&self) -> Vec<HummockBackup> {
+        let versioning_guard = self.versioning.read().await;
+        versioning_guard.backups.values().cloned().collect_vec()
+    }
+
+    /// Deletes the backup, after which its SSTs are no longer retained.
+    pub async fn delete_backup(&self, backup_id: u64) -> Result<()> {
+        let mut versioning_guard = self.versioning.write().await;
+        if !versioning_guard.backups.contains_key(&backup_id) {
+            return Err(ErrorCode::MetaError(format!("backup {} not found", backup_id)).into());
+        }
+        let mut backups = VarTransaction::new(&mut versioning_guard.backups);
+        backups.remove(&backup_id);
+        commit_multi_var!(self, None, backups)?;
+
+        #[cfg(test)]
+        {
+            drop(versioning_guard);
+            self.check_state_consistency().await;
+        }
+
+        Ok(())
+    }
+
+    /// Gets ids of the SSTs referenced by any backup, which must not be vacuumed.
+    pub async fn get_retained_sst_ids(&self) -> HashSet<HummockSSTableId> {
+        let versioning_guard = self.versioning.read().await;
+        versioning_guard
+            .backups
+            .values()
+            .flat_map(|backup| backup.sstable_ids.iter().cloned())
+            .collect()
+    }
+
     #[cfg(any(test, feature = "test"))]
     pub async fn get_current_version(&self) -> HummockVersion {
         let versioning_guard = self.versioning.read().await;
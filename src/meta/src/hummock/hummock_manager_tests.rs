@@ -13,9 +13,12 @@
 // limitations under the License.
 
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 use itertools::Itertools;
+use risingwave_common::catalog::TableId;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_hummock_sdk::compact::compact_task_to_string;
 use risingwave_hummock_sdk::{
@@ -24,12 +27,17 @@ use risingwave_hummock_sdk::{
 use risingwave_pb::common::{HostAddress, WorkerType};
 use risingwave_pb::hummock::{
     HummockPinnedSnapshot, HummockPinnedVersion, HummockSnapshot, HummockVersion,
-    HummockVersionRefId,
+    HummockVersionRefId, StateTtl,
 };
+use risingwave_pb::meta::table_fragments::Fragment;
+use risingwave_pb::stream_plan::StreamActor;
 
 use crate::hummock::model::CurrentHummockVersionId;
 use crate::hummock::test_utils::*;
-use crate::model::MetadataModel;
+use crate::hummock::HummockManager;
+use crate::model::TableFragments;
+use crate::rpc::metrics::MetaMetrics;
+use crate::stream::FragmentManager;
 
 fn pin_versions_sum(pin_versions: &[HummockPinnedVersion]) -> usize {
     pin_versions.iter().map(|p| p.version_id.len()).sum()
@@ -477,7 +485,11 @@ async fn test_release_context_resource() -> Result<()> {
         port: 2,
     };
     let (worker_node_2, _) = cluster_manager
-        .add_worker_node(fake_host_address_2, WorkerType::ComputeNode)
+        .add_worker_node(
+            fake_host_address_2,
+            WorkerType::ComputeNode,
+            Default::default(),
+        )
         .await
         .unwrap();
     let context_id_2 = worker_node_2.id;
@@ -600,7 +612,11 @@ async fn test_hummock_manager_basic() {
         port: 2,
     };
     let (worker_node_2, _) = cluster_manager
-        .add_worker_node(fake_host_address_2, WorkerType::ComputeNode)
+        .add_worker_node(
+            fake_host_address_2,
+            WorkerType::ComputeNode,
+            Default::default(),
+        )
         .await
         .unwrap();
     let context_id_2 = worker_node_2.id;
@@ -970,3 +986,72 @@ async fn test_mark_orphan_ssts() {
         )
     );
 }
+
+#[tokio::test]
+async fn test_compact_task_state_ttls() -> Result<()> {
+    let (env, _hummock_manager, cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    let fragment_manager = Arc::new(FragmentManager::new(env.meta_store_ref()).await?);
+    let hummock_manager = HummockManager::new(
+        env.clone(),
+        cluster_manager,
+        fragment_manager.clone(),
+        Arc::new(MetaMetrics::new()),
+    )
+    .await?;
+
+    // A materialized view whose fragment 1 has actors 1 and 2 with TTL, and whose fragment 2 has
+    // no TTL.
+    let fragment = |fragment_id, actor_ids: Vec<u32>, state_ttl_ms| Fragment {
+        fragment_id,
+        actors: actor_ids
+            .into_iter()
+            .map(|actor_id| StreamActor {
+                actor_id,
+                fragment_id,
+                state_ttl_ms,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let table_fragments = TableFragments::new(
+        TableId::new(1),
+        BTreeMap::from([
+            (1, fragment(1, vec![1, 2], 1000)),
+            (2, fragment(2, vec![3], 0)),
+        ]),
+    );
+    fragment_manager
+        .start_create_table_fragments(table_fragments)
+        .await?;
+
+    let epoch: u64 = 1;
+    let original_tables = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 2).await);
+    hummock_manager
+        .add_tables(context_id, original_tables, epoch)
+        .await?;
+    hummock_manager.commit_epoch(epoch).await?;
+
+    // The TTLs are carried in the compact task, keyed by the actor and fragment roots.
+    let compact_task = hummock_manager.get_compact_task(context_id).await?.unwrap();
+    assert_eq!(
+        compact_task.state_ttls,
+        vec![
+            StateTtl {
+                key_prefix: vec![b'e', 0, 0, 0, 1],
+                ttl_ms: 1000,
+            },
+            StateTtl {
+                key_prefix: vec![b'e', 0, 0, 0, 2],
+                ttl_ms: 1000,
+            },
+            StateTtl {
+                key_prefix: vec![b's', 0, 0, 0, 1],
+                ttl_ms: 1000,
+            },
+        ]
+    );
+
+    Ok(())
+}
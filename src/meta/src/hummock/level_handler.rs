@@ -21,6 +21,7 @@ use risingwave_pb::hummock::SstableInfo;
 pub struct SSTableStat {
     pub key_range: KeyRange,
     pub table_id: u64,
+    pub file_size: u64,
     pub compact_task: Option<u64>,
 }
 
@@ -29,6 +30,7 @@ impl From<&SstableInfo> for SSTableStat {
         SSTableStat {
             key_range: info.key_range.as_ref().unwrap().into(),
             table_id: info.id,
+            file_size: info.file_size,
             compact_task: None,
         }
     }
@@ -42,6 +44,7 @@ impl From<&SSTableStat> for risingwave_pb::hummock::SstableStat {
             compact_task: stat
                 .compact_task
                 .map(|it| risingwave_pb::hummock::sstable_stat::CompactTaskId { id: it }),
+            file_size: stat.file_size,
         }
     }
 }
@@ -51,6 +54,7 @@ impl From<&risingwave_pb::hummock::SstableStat> for SSTableStat {
         SSTableStat {
             key_range: stat.key_range.as_ref().unwrap().into(),
             table_id: stat.table_id,
+            file_size: stat.file_size,
             compact_task: stat.compact_task.as_ref().map(|it| it.id),
         }
     }
@@ -67,6 +71,18 @@ pub enum LevelHandler {
 }
 
 impl LevelHandler {
+    /// Returns SSTs in this level.
+    pub fn ssts(&self) -> &[SSTableStat] {
+        match self {
+            LevelHandler::Overlapping(l_n, _) | LevelHandler::Nonoverlapping(l_n, _) => l_n,
+        }
+    }
+
+    /// Returns SSTs in this level that are not assigned to any compact task.
+    pub fn idle_ssts(&self) -> impl Iterator<Item = &SSTableStat> {
+        self.ssts().iter().filter(|sst| sst.compact_task.is_none())
+    }
+
     fn clear_compacting_range(&mut self, clear_task_id: u64) {
         match self {
             LevelHandler::Overlapping(_, compacting_key_ranges)
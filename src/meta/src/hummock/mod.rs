@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod backup;
 mod compaction;
+mod compaction_picker;
 mod compactor_manager;
 mod hummock_manager;
 #[cfg(test)]
@@ -28,11 +30,14 @@ mod vacuum;
 use std::sync::Arc;
 use std::time::Duration;
 
+pub use backup::*;
+pub use compaction_picker::{CompactionConfig, CompactionStrategy};
 pub use compactor_manager::*;
 pub use hummock_manager::*;
 use itertools::Itertools;
 #[cfg(any(test, feature = "test"))]
 pub use mock_hummock_meta_client::MockHummockMetaClient;
+use risingwave_common::error::{ErrorCode, Result, RwError};
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::JoinHandle;
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
@@ -192,6 +197,43 @@ where
     (join_handle, shutdown_tx)
 }
 
+/// Assigns a compact task that compacts `level` into the level below it to a compactor, no matter
+/// whether `level` needs compaction. Returns the id of the assigned task.
+pub async fn trigger_manual_compaction<S>(
+    hummock_manager: HummockManagerRef<S>,
+    compactor_manager: Arc<CompactorManager>,
+    level: u32,
+) -> Result<u64>
+where
+    S: MetaStore,
+{
+    let compactor = compactor_manager.next_compactor().ok_or_else(|| {
+        RwError::from(ErrorCode::InternalError(
+            "no compactor is available".to_string(),
+        ))
+    })?;
+    let compact_task = hummock_manager
+        .get_manual_compact_task(compactor.context_id(), level)
+        .await?
+        .ok_or_else(|| {
+            RwError::from(ErrorCode::InternalError(format!(
+                "no SST in level {} can be compacted",
+                level
+            )))
+        })?;
+    if let Err(err) = compactor.send_task(Some(compact_task.clone()), None).await {
+        compactor_manager.remove_compactor(compactor.context_id());
+        return Err(err);
+    }
+    tracing::info!(
+        "Triggered manual compaction task {} of level {} in worker {}.",
+        compact_task.task_id,
+        level,
+        compactor.context_id()
+    );
+    Ok(compact_task.task_id)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
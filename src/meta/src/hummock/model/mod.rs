@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod backup;
 mod compact_task_assignment;
 mod current_version_id;
 mod pinned_snapshot;
@@ -20,6 +21,7 @@ pub mod sstable_id_info;
 mod stale_sstables;
 mod version;
 
+pub use backup::*;
 pub use current_version_id::*;
 pub use pinned_snapshot::*;
 pub use pinned_version::*;
@@ -26,6 +26,7 @@ use crate::hummock::{HummockManager, HummockManagerRef};
 use crate::manager::MetaSrvEnv;
 use crate::rpc::metrics::MetaMetrics;
 use crate::storage::{MemStore, MetaStore};
+use crate::stream::FragmentManager;
 
 pub async fn add_test_tables<S>(
     hummock_manager: &HummockManager<S>,
@@ -93,6 +94,7 @@ pub fn generate_test_tables(epoch: u64, table_ids: Vec<u64>) -> Vec<SstableInfo>
                 right: iterator_test_key_of_epoch(table_id, (i + 1) * 10, epoch),
                 inf: false,
             }),
+            file_size: 2,
         });
     }
     sst_info
@@ -140,6 +142,7 @@ pub async fn setup_compute_env(
         HummockManager::new(
             env.clone(),
             cluster_manager.clone(),
+            Arc::new(FragmentManager::new(env.meta_store_ref()).await.unwrap()),
             Arc::new(MetaMetrics::new()),
         )
         .await
@@ -150,7 +153,11 @@ pub async fn setup_compute_env(
         port,
     };
     let (worker_node, _) = cluster_manager
-        .add_worker_node(fake_host_address, WorkerType::ComputeNode)
+        .add_worker_node(
+            fake_host_address,
+            WorkerType::ComputeNode,
+            Default::default(),
+        )
         .await
         .unwrap();
     (env, hummock_manager, cluster_manager, worker_node)
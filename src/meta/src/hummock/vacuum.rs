@@ -40,6 +40,7 @@ const ORPHAN_SST_RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24
 ///   `SstableIdInfo::meta_delete_timestamp` and delete asynchronously.
 /// - 4.2 Or if step 3 didn't happen after some time, the SST is delete as orphan data
 ///   asynchronously.
+/// SSTs referenced by a `HummockBackup` are skipped in step 4.1 until the backup is deleted.
 pub struct VacuumTrigger<S: MetaStore> {
     hummock_manager: HummockManagerRef<S>,
     /// Use the CompactorManager to dispatch VacuumTask.
@@ -160,6 +161,8 @@ where
                     .hummock_manager
                     .mark_orphan_ssts(orphan_sst_retention_interval)
                     .await?;
+                // SSTs referenced by backups are retained even if they're stale.
+                let retained_sst_ids = vacuum.hummock_manager.get_retained_sst_ids().await;
                 let ssts_to_delete = vacuum
                     .hummock_manager
                     .list_sstable_id_infos()
@@ -167,6 +170,7 @@ where
                     .into_iter()
                     .filter(|sstable_id_info| {
                         sstable_id_info.meta_delete_timestamp != INVALID_TIMESTAMP
+                            && !retained_sst_ids.contains(&sstable_id_info.id)
                     })
                     .map(|sstable_id_info| sstable_id_info.id)
                     .collect_vec();
@@ -247,9 +251,10 @@ mod tests {
     use std::time::Duration;
 
     use itertools::Itertools;
+    use risingwave_pb::common::WorkerType;
     use risingwave_pb::hummock::VacuumTask;
 
-    use crate::hummock::test_utils::{add_test_tables, setup_compute_env};
+    use crate::hummock::test_utils::{add_test_tables, generate_test_tables, setup_compute_env};
     use crate::hummock::{CompactorManager, VacuumTrigger};
 
     #[tokio::test]
@@ -326,7 +331,7 @@ mod tests {
                 .len(),
             0
         );
-        let _receiver = compactor_manager.add_compactor(0);
+        let _receiver = compactor_manager.add_compactor(0, WorkerType::ComputeNode);
         // 4. 2 expired SST ids.
         let sst_ids = VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(0))
             .await
@@ -363,7 +368,7 @@ mod tests {
             hummock_manager.clone(),
             compactor_manager.clone(),
         ));
-        let _receiver = compactor_manager.add_compactor(0);
+        let _receiver = compactor_manager.add_compactor(0, WorkerType::ComputeNode);
 
         let sst_infos = add_test_tables(hummock_manager.as_ref(), context_id).await;
         // Current state: {v0: [], v1: [test_tables uncommitted], v2: [test_tables], v3:
@@ -417,4 +422,88 @@ mod tests {
             0
         );
     }
+
+    #[tokio::test]
+    async fn test_vacuum_retain_backup_sst_data() {
+        let (_env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+        let context_id = worker_node.id;
+        let compactor_manager = Arc::new(CompactorManager::default());
+        let vacuum = Arc::new(VacuumTrigger::new(
+            hummock_manager.clone(),
+            compactor_manager.clone(),
+        ));
+        let _receiver = compactor_manager.add_compactor(0, WorkerType::ComputeNode);
+
+        let epoch = 1;
+        let test_tables = generate_test_tables(
+            epoch,
+            vec![
+                hummock_manager.get_new_table_id().await.unwrap(),
+                hummock_manager.get_new_table_id().await.unwrap(),
+            ],
+        );
+        hummock_manager
+            .add_tables(context_id, test_tables.clone(), epoch)
+            .await
+            .unwrap();
+        hummock_manager.commit_epoch(epoch).await.unwrap();
+        let backup = hummock_manager
+            .create_backup("backup/1".to_string())
+            .await
+            .unwrap()
+            .backup
+            .unwrap();
+
+        // Compact test_tables so that they are stale in the new version.
+        let mut compact_task = hummock_manager
+            .get_compact_task(context_id)
+            .await
+            .unwrap()
+            .unwrap();
+        compact_task.sorted_output_ssts = generate_test_tables(
+            epoch,
+            vec![hummock_manager.get_new_table_id().await.unwrap()],
+        );
+        compact_task.task_status = true;
+        hummock_manager
+            .report_compact_task(compact_task)
+            .await
+            .unwrap();
+        // Increase version, so that the version where test_tables become stale can be vacuumed.
+        hummock_manager
+            .add_tables(
+                context_id,
+                generate_test_tables(
+                    epoch + 1,
+                    vec![hummock_manager.get_new_table_id().await.unwrap()],
+                ),
+                epoch + 1,
+            )
+            .await
+            .unwrap();
+        assert!(
+            VacuumTrigger::vacuum_version_metadata(&vacuum)
+                .await
+                .unwrap()
+                > 0
+        );
+
+        // Stale SSTs referenced by the backup are retained.
+        assert!(
+            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(600))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Stale SSTs are vacuumed once the backup is deleted.
+        hummock_manager.delete_backup(backup.id).await.unwrap();
+        let sst_ids = VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(600))
+            .await
+            .unwrap();
+        assert!(!sst_ids.is_empty());
+        assert!(sst_ids
+            .iter()
+            .all(|sst_id| test_tables.iter().any(|sst| sst.id == *sst_id)));
+    }
 }
@@ -44,10 +44,12 @@ pub mod storage;
 mod stream;
 pub mod test_utils;
 
+use std::num::NonZeroUsize;
 use std::time::Duration;
 
 use clap::{ArgEnum, Parser};
 
+use crate::hummock::{CompactionConfig, CompactionStrategy};
 use crate::manager::MetaOpts;
 use crate::rpc::server::{rpc_serve, MetaStoreBackend};
 
@@ -55,6 +57,7 @@ use crate::rpc::server::{rpc_serve, MetaStoreBackend};
 enum Backend {
     Mem,
     Etcd,
+    File,
 }
 
 #[derive(Debug, Parser)]
@@ -63,6 +66,10 @@ pub struct MetaNodeOpts {
     #[clap(long, default_value = "127.0.0.1:5690")]
     host: String,
 
+    /// The address for clients to connect once elected as leader. Use `host` if not set.
+    #[clap(long)]
+    advertise_host: Option<String>,
+
     #[clap(long)]
     dashboard_host: Option<String>,
 
@@ -75,6 +82,10 @@ pub struct MetaNodeOpts {
     #[clap(long, default_value_t = String::from(""))]
     etcd_endpoints: String,
 
+    /// The directory to persist metadata in, for the `file` backend.
+    #[clap(long, default_value = "meta_store")]
+    file_store_dir: String,
+
     /// Maximum allowed heartbeat interval in ms
     #[clap(long, default_value = "60000")]
     max_heartbeat_interval: u32,
@@ -86,11 +97,50 @@ pub struct MetaNodeOpts {
     /// e2e tests.
     #[clap(long)]
     disable_recovery: bool,
+
+    /// Lease of the meta leader in seconds. Followers take over after the lease expires.
+    #[clap(long, default_value = "10")]
+    leader_lease_secs: u64,
+
+    /// The interval of periodic barriers in ms.
+    #[clap(long, default_value = "100")]
+    barrier_interval_ms: u32,
+
+    /// Make a checkpoint every this many barriers. Non-checkpoint barriers only advance the epoch,
+    /// without syncing states to the storage.
+    #[clap(long, default_value = "1")]
+    checkpoint_frequency: NonZeroUsize,
+
+    /// Report the actors not collected yet, with their await trees, if a barrier isn't collected
+    /// within this many seconds.
+    #[clap(long, default_value = "60")]
+    barrier_collect_timeout_secs: u64,
+
+    /// The strategy to pick levels of hummock to compact.
+    #[clap(long, arg_enum, default_value_t = CompactionStrategy::Leveled)]
+    compaction_strategy: CompactionStrategy,
+
+    /// Number of levels of hummock, including L0.
+    #[clap(long, default_value = "7", parse(try_from_str = parse_num_levels))]
+    num_levels: usize,
+
+    /// Compact L0 once it holds this many SSTs of similar size.
+    #[clap(long, default_value = "4")]
+    level0_tier_compact_file_number: usize,
+}
+
+fn parse_num_levels(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(num_levels) if num_levels >= 2 => Ok(num_levels),
+        Ok(_) => Err("at least 2 levels are required".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 /// Start meta node
 pub async fn start(opts: MetaNodeOpts) {
     let addr = opts.host.parse().unwrap();
+    let advertise_addr = opts.advertise_host.unwrap_or_else(|| opts.host.clone());
     let dashboard_addr = opts.dashboard_host.map(|x| x.parse().unwrap());
     let prometheus_addr = opts.prometheus_host.map(|x| x.parse().unwrap());
     let backend = match opts.backend {
@@ -102,12 +152,16 @@ pub async fn start(opts: MetaNodeOpts) {
                 .collect(),
         },
         Backend::Mem => MetaStoreBackend::Mem,
+        Backend::File => MetaStoreBackend::File {
+            dir: opts.file_store_dir.into(),
+        },
     };
     let max_heartbeat_interval = Duration::from_millis(opts.max_heartbeat_interval as u64);
 
     tracing::info!("Meta server listening at {}", addr);
     let (join_handle, _shutdown_send) = rpc_serve(
         addr,
+        advertise_addr,
         prometheus_addr,
         dashboard_addr,
         backend,
@@ -115,6 +169,16 @@ pub async fn start(opts: MetaNodeOpts) {
         opts.dashboard_ui_path,
         MetaOpts {
             enable_recovery: !opts.disable_recovery,
+            leader_lease: Duration::from_secs(opts.leader_lease_secs),
+            barrier_interval: Duration::from_millis(opts.barrier_interval_ms as u64),
+            checkpoint_frequency: opts.checkpoint_frequency.get(),
+            barrier_collect_timeout: Duration::from_secs(opts.barrier_collect_timeout_secs),
+            compaction_strategy: opts.compaction_strategy,
+            compaction_config: CompactionConfig {
+                num_levels: opts.num_levels,
+                level0_tier_compact_file_number: opts.level0_tier_compact_file_number,
+                ..Default::default()
+            },
         },
     )
     .await
@@ -14,8 +14,10 @@
 
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
 
 use super::{StreamClients, StreamClientsRef};
+use crate::hummock::{CompactionConfig, CompactionStrategy};
 #[cfg(any(test, feature = "test"))]
 use crate::manager::MemEpochGenerator;
 use crate::manager::{
@@ -53,9 +55,33 @@ where
 }
 
 /// Options shared by all meta service instances
-#[derive(Default)]
 pub struct MetaOpts {
     pub enable_recovery: bool,
+    /// Lease of the meta leader. Followers take over after the lease expires.
+    pub leader_lease: Duration,
+    /// The interval of periodic barriers.
+    pub barrier_interval: Duration,
+    /// Make a checkpoint, i.e. sync states and commit the epoch, every this many barriers.
+    pub checkpoint_frequency: usize,
+    /// Report the actors not collected yet if a barrier isn't collected within this duration.
+    pub barrier_collect_timeout: Duration,
+    /// The strategy to pick levels of hummock to compact.
+    pub compaction_strategy: CompactionStrategy,
+    pub compaction_config: CompactionConfig,
+}
+
+impl Default for MetaOpts {
+    fn default() -> Self {
+        Self {
+            enable_recovery: false,
+            leader_lease: Duration::from_secs(10),
+            barrier_interval: Duration::from_millis(100),
+            checkpoint_frequency: 1,
+            barrier_collect_timeout: Duration::from_secs(60),
+            compaction_strategy: CompactionStrategy::Leveled,
+            compaction_config: CompactionConfig::default(),
+        }
+    }
 }
 
 impl<S> MetaSrvEnv<S>
@@ -140,7 +166,16 @@ impl MetaSrvEnv<MemStore> {
             epoch_generator,
             notification_manager,
             stream_clients,
-            opts: MetaOpts::default().into(),
+            opts: MetaOpts {
+                // Compact every SST flushed to L0 right away, which most tests rely on.
+                compaction_config: CompactionConfig {
+                    num_levels: 2,
+                    level0_tier_compact_file_number: 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .into(),
         }
     }
 }
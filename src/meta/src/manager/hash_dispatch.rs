@@ -89,6 +89,12 @@ where
         let core = self.core.lock().await;
         core.key_mapping.get_mapping()
     }
+
+    /// Replaces the mapping with the given one, e.g. after the actors are rescheduled.
+    pub async fn set_worker_mapping(&self, key_mapping: Vec<ParallelUnitId>) -> Result<()> {
+        let mut core = self.core.lock().await;
+        core.set_worker_mapping(key_mapping).await
+    }
 }
 
 /// [`HashDispatchManagerCore`] contains the core logic for mapping change when one or more nodes
@@ -252,6 +258,37 @@ where
         Ok(())
     }
 
+    async fn set_worker_mapping(&mut self, key_mapping: Vec<ParallelUnitId>) -> Result<()> {
+        assert_eq!(key_mapping.len(), VIRTUAL_KEY_COUNT);
+
+        // Parallel units owning no virtual keys are kept, so that they can still be deleted or
+        // take over keys later.
+        self.owner_mapping
+            .values_mut()
+            .for_each(|virtual_keys| virtual_keys.clear());
+        for (virtual_key, &parallel_unit_id) in key_mapping.iter().enumerate() {
+            self.owner_mapping
+                .entry(parallel_unit_id)
+                .or_default()
+                .push(virtual_key);
+        }
+
+        self.load_balancer.clear();
+        for (&parallel_unit_id, virtual_keys) in &self.owner_mapping {
+            self.load_balancer
+                .entry(virtual_keys.len())
+                .or_default()
+                .push(parallel_unit_id);
+        }
+        self.total_hash_parallels = self.owner_mapping.len();
+
+        // Persist mapping
+        self.key_mapping.set_mapping(key_mapping)?;
+        self.key_mapping.insert(&*self.meta_store).await?;
+
+        Ok(())
+    }
+
     async fn delete_worker_mapping(&mut self, parallel_units: &[ParallelUnit]) -> Result<()> {
         assert!(
             !self.owner_mapping.is_empty(),
@@ -369,6 +406,7 @@ mod tests {
                     host: Some(host_address.clone()),
                     state: State::Starting as i32,
                     parallel_units,
+                    labels: Default::default(),
                 }
             })
             .collect_vec();
@@ -450,6 +488,7 @@ mod tests {
                     host: Some(host_address.clone()),
                     state: State::Starting as i32,
                     parallel_units,
+                    labels: Default::default(),
                 }
             })
             .collect_vec();
@@ -484,6 +523,7 @@ mod tests {
                     host: Some(host_address.clone()),
                     state: State::Starting as i32,
                     parallel_units,
+                    labels: Default::default(),
                 }
             })
             .collect_vec();
@@ -502,6 +542,68 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_hash_dispatch_manager_set_mapping() -> Result<()> {
+        let meta_store = Arc::new(MemStore::default());
+        let worker_nodes = (0..2u32)
+            .map(|node_id| WorkerNode {
+                id: node_id,
+                r#type: WorkerType::ComputeNode as i32,
+                host: Some(HostAddress {
+                    host: "127.0.0.1".to_string(),
+                    port: 80,
+                }),
+                state: State::Starting as i32,
+                parallel_units: (0..2u32)
+                    .map(|idx| ParallelUnit {
+                        id: node_id * 2 + idx,
+                        r#type: ParallelUnitType::Hash as i32,
+                        worker_node_id: node_id,
+                    })
+                    .collect_vec(),
+                labels: Default::default(),
+            })
+            .collect_vec();
+
+        let hash_dispatch_manager =
+            HashDispatchManager::new(&worker_nodes, meta_store.clone()).await?;
+
+        // The virtual keys are moved to parallel units 0 and 2, e.g. after rescheduling.
+        let key_mapping = [
+            vec![0; VIRTUAL_KEY_COUNT / 2],
+            vec![2; VIRTUAL_KEY_COUNT / 2],
+        ]
+        .concat();
+        hash_dispatch_manager
+            .set_worker_mapping(key_mapping.clone())
+            .await?;
+        assert_core(&hash_dispatch_manager).await;
+        assert_parallel_unit_count(&hash_dispatch_manager, 4).await;
+        assert_eq!(
+            hash_dispatch_manager.get_worker_mapping().await,
+            key_mapping
+        );
+
+        // The mapping is persisted.
+        let persisted = ConsistentHashMapping::list(&*meta_store).await?;
+        assert_eq!(persisted.len(), 1);
+        assert_eq!(persisted[0].get_mapping(), key_mapping);
+
+        // Parallel units owning no virtual keys can still be deleted.
+        hash_dispatch_manager
+            .delete_worker_mapping(&worker_nodes[1])
+            .await?;
+        assert_core(&hash_dispatch_manager).await;
+        assert_parallel_unit_count(&hash_dispatch_manager, 2).await;
+        assert!(hash_dispatch_manager
+            .get_worker_mapping()
+            .await
+            .iter()
+            .all(|&parallel_unit_id| parallel_unit_id < 2));
+
+        Ok(())
+    }
+
     async fn assert_core(hash_dispatch_manager: &HashDispatchManager<MemStore>) {
         let core = hash_dispatch_manager.core.lock().await;
         assert_eq!(
@@ -16,13 +16,16 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
-use risingwave_common::error::Result;
+use risingwave_common::error::{Result, ToRwResult};
+use risingwave_connector::{decode_splits, encode_splits, SplitImpl};
+use risingwave_pb::catalog::Placement;
+use risingwave_pb::hummock::StateTtl;
 use risingwave_pb::meta::table_fragments::fragment::FragmentType;
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus, Fragment};
 use risingwave_pb::meta::TableFragments as ProstTableFragments;
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::stream_node::Node;
-use risingwave_pb::stream_plan::{StreamActor, StreamNode};
+use risingwave_pb::stream_plan::{SourceNode, StreamActor, StreamNode, StreamSourceState};
 
 use super::{ActorId, FragmentId};
 use crate::cluster::WorkerId;
@@ -46,6 +49,9 @@ pub struct TableFragments {
 
     /// The status of actors
     actor_status: BTreeMap<ActorId, ActorStatus>,
+
+    /// The placement constraints of actors
+    placement: Option<Placement>,
 }
 
 impl MetadataModel for TableFragments {
@@ -61,6 +67,7 @@ impl MetadataModel for TableFragments {
             table_id: self.table_id.table_id(),
             fragments: self.fragments.clone().into_iter().collect(),
             actor_status: self.actor_status.clone().into_iter().collect(),
+            placement: self.placement.clone(),
         }
     }
 
@@ -69,6 +76,7 @@ impl MetadataModel for TableFragments {
             table_id: TableId::new(prost.table_id),
             fragments: prost.fragments.into_iter().collect(),
             actor_status: prost.actor_status.into_iter().collect(),
+            placement: prost.placement,
         }
     }
 
@@ -83,6 +91,7 @@ impl TableFragments {
             table_id,
             fragments,
             actor_status: BTreeMap::default(),
+            placement: None,
         }
     }
 
@@ -95,6 +104,21 @@ impl TableFragments {
         self.actor_status = actor_status;
     }
 
+    /// Returns the status of the actors.
+    pub fn actor_status(&self) -> &BTreeMap<ActorId, ActorStatus> {
+        &self.actor_status
+    }
+
+    /// Set the placement constraints of the actors.
+    pub fn set_placement(&mut self, placement: Option<Placement>) {
+        self.placement = placement;
+    }
+
+    /// Returns the placement constraints of the actors.
+    pub fn placement(&self) -> Option<&Placement> {
+        self.placement.as_ref()
+    }
+
     /// Returns the table id.
     pub fn table_id(&self) -> TableId {
         self.table_id
@@ -172,6 +196,88 @@ impl TableFragments {
         None
     }
 
+    fn is_stream_source_node(stream_node: &StreamNode) -> bool {
+        matches!(
+            stream_node.node.as_ref(),
+            Some(Node::SourceNode(s)) if s.source_type == SourceType::Source as i32
+        )
+    }
+
+    fn fetch_stream_source_node(stream_node: &StreamNode) -> Option<&SourceNode> {
+        if Self::is_stream_source_node(stream_node) {
+            return match stream_node.node.as_ref() {
+                Some(Node::SourceNode(s)) => Some(s),
+                _ => unreachable!(),
+            };
+        }
+        stream_node
+            .input
+            .iter()
+            .find_map(Self::fetch_stream_source_node)
+    }
+
+    fn fetch_stream_source_node_mut(stream_node: &mut StreamNode) -> Option<&mut SourceNode> {
+        if Self::is_stream_source_node(stream_node) {
+            return match stream_node.node.as_mut() {
+                Some(Node::SourceNode(s)) => Some(s),
+                _ => unreachable!(),
+            };
+        }
+        stream_node
+            .input
+            .iter_mut()
+            .find_map(Self::fetch_stream_source_node_mut)
+    }
+
+    /// Returns the splits assigned to the stream source in `stream_node`.
+    pub fn fetch_stream_source_splits(stream_node: &StreamNode) -> Result<Vec<SplitImpl>> {
+        match Self::fetch_stream_source_node(stream_node)
+            .and_then(|s| s.stream_source_state.as_ref())
+        {
+            Some(state) => {
+                decode_splits(&state.split_type, &state.stream_source_splits).to_rw_result()
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Assigns splits to the stream source actors in `actor_splits`, whose existing splits are
+    /// replaced.
+    pub fn update_actor_splits(
+        &mut self,
+        actor_splits: &HashMap<ActorId, Vec<SplitImpl>>,
+    ) -> Result<()> {
+        for fragment in self.fragments.values_mut() {
+            for actor in &mut fragment.actors {
+                let splits = match actor_splits.get(&actor.actor_id) {
+                    Some(splits) => splits,
+                    None => continue,
+                };
+                if let Some(s) = Self::fetch_stream_source_node_mut(actor.nodes.as_mut().unwrap()) {
+                    s.stream_source_state = if splits.is_empty() {
+                        None
+                    } else {
+                        let (split_type, stream_source_splits) =
+                            encode_splits(splits).to_rw_result()?;
+                        Some(StreamSourceState {
+                            split_type,
+                            stream_source_splits,
+                        })
+                    };
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether all actors are created and running.
+    pub fn is_created(&self) -> bool {
+        self.actor_status
+            .values()
+            .all(|status| status.state == ActorState::Running as i32)
+    }
+
     /// Returns actors that contains Chain node.
     pub fn chain_actor_ids(&self) -> Vec<ActorId> {
         self.fragments
@@ -197,6 +303,74 @@ impl TableFragments {
         }
     }
 
+    /// Returns the state tables allocated for the operators in `stream_node`.
+    fn resolve_state_tables(stream_node: &StreamNode, table_ids: &mut Vec<u32>) {
+        let table_id = match stream_node.node.as_ref() {
+            Some(Node::HashAggNode(node)) => node.table_id,
+            Some(Node::GlobalSimpleAggNode(node)) => node.table_id,
+            Some(Node::TopNNode(node)) => node.table_id,
+            _ => 0,
+        };
+        if table_id != 0 {
+            table_ids.push(table_id);
+        }
+
+        for child in &stream_node.input {
+            Self::resolve_state_tables(child, table_ids);
+        }
+    }
+
+    /// Returns the time-to-live of the states of the actors. The key prefixes are the roots of the
+    /// executors of each actor, the shared roots of the operators of each fragment and the state
+    /// tables, which are `Keyspace::actor_root`, `Keyspace::fragment_root` and
+    /// `Keyspace::table_root` in storage.
+    pub fn state_ttls(&self) -> Vec<StateTtl> {
+        let mut state_ttls = vec![];
+        for (fragment_id, fragment) in &self.fragments {
+            let ttl_ms = match fragment.actors.first() {
+                Some(actor) if actor.state_ttl_ms > 0 => actor.state_ttl_ms,
+                _ => continue,
+            };
+            for actor in &fragment.actors {
+                state_ttls.push(StateTtl {
+                    key_prefix: [&b"e"[..], &actor.actor_id.to_be_bytes()].concat(),
+                    ttl_ms,
+                });
+            }
+            state_ttls.push(StateTtl {
+                key_prefix: [&b"s"[..], &fragment_id.to_be_bytes()].concat(),
+                ttl_ms,
+            });
+            let mut table_ids = vec![];
+            Self::resolve_state_tables(fragment.actors[0].nodes.as_ref().unwrap(), &mut table_ids);
+            for table_id in table_ids {
+                state_ttls.push(StateTtl {
+                    key_prefix: [&b"t"[..], &table_id.to_be_bytes()].concat(),
+                    ttl_ms,
+                });
+            }
+        }
+        state_ttls
+    }
+
+    /// Returns the key prefixes of all the states written by the actors, i.e. the roots of the
+    /// actors and fragments, the state tables and the materialized view itself.
+    pub fn state_key_prefixes(&self) -> Vec<Vec<u8>> {
+        let mut key_prefixes = vec![[&b"t"[..], &self.table_id.table_id().to_be_bytes()].concat()];
+        for (fragment_id, fragment) in &self.fragments {
+            for actor in &fragment.actors {
+                key_prefixes.push([&b"e"[..], &actor.actor_id.to_be_bytes()].concat());
+            }
+            key_prefixes.push([&b"s"[..], &fragment_id.to_be_bytes()].concat());
+            let mut table_ids = vec![];
+            Self::resolve_state_tables(fragment.actors[0].nodes.as_ref().unwrap(), &mut table_ids);
+            for table_id in table_ids {
+                key_prefixes.push([&b"t"[..], &table_id.to_be_bytes()].concat());
+            }
+        }
+        key_prefixes
+    }
+
     /// Returns dependent table ids.
     pub fn dependent_table_ids(&self) -> HashSet<TableId> {
         let mut table_ids = HashSet::new();
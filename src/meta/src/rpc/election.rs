@@ -0,0 +1,325 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use etcd_client::{Client, Compare, CompareOp, PutOptions, Txn, TxnOp};
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use tokio::sync::{watch, Mutex, Notify};
+use tokio::task::JoinHandle;
+
+use crate::storage::LeaderFence;
+
+/// Key of the meta leader in etcd, whose value is the address of the leader.
+const META_LEADER_KEY: &str = "__meta_leader";
+
+/// Interval to retry campaigning when there's already a leader.
+const CAMPAIGN_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+pub type ElectionClientRef = Arc<dyn ElectionClient>;
+
+/// [`ElectionClient`] elects the leader among meta nodes. Only the leader serves meta services,
+/// while followers redirect clients to the leader.
+#[async_trait::async_trait]
+pub trait ElectionClient: Send + Sync + 'static {
+    /// Returns the address of this meta node, which is advertised once elected as leader.
+    fn id(&self) -> &str;
+
+    /// Campaigns for leadership, and returns once this meta node is elected as leader.
+    async fn campaign(&self) -> Result<()>;
+
+    /// Gives up the leadership if this meta node is the leader.
+    async fn resign(&self) -> Result<()>;
+
+    /// Returns the address of the current leader, or `None` if there's no leader.
+    async fn leader(&self) -> Result<Option<String>>;
+
+    /// Subscribes to whether this meta node is the leader.
+    fn subscribe(&self) -> watch::Receiver<bool>;
+
+    fn is_leader(&self) -> bool {
+        *self.subscribe().borrow()
+    }
+}
+
+fn etcd_err(e: etcd_client::Error) -> RwError {
+    RwError::from(InternalError(format!("etcd error: {}", e)))
+}
+
+/// [`EtcdElectionClient`] elects the leader by creating [`META_LEADER_KEY`] with a lease in etcd.
+/// The leader keeps the lease alive, and followers take over once the lease expires.
+pub struct EtcdElectionClient {
+    client: Client,
+    id: String,
+    lease_ttl: i64,
+    /// The lease of the leader key and the task keeping it alive, if elected.
+    keep_alive: Mutex<Option<(i64, JoinHandle<()>)>>,
+    /// The create revision of the leader key once elected, which fences the meta store writes.
+    leader_revision: Arc<AtomicI64>,
+    is_leader_tx: Arc<watch::Sender<bool>>,
+    is_leader_rx: watch::Receiver<bool>,
+}
+
+impl EtcdElectionClient {
+    pub fn new(client: Client, id: String, lease_ttl: Duration) -> Self {
+        let (is_leader_tx, is_leader_rx) = watch::channel(false);
+        Self {
+            client,
+            id,
+            lease_ttl: lease_ttl.as_secs().max(1) as i64,
+            keep_alive: Mutex::new(None),
+            leader_revision: Arc::new(AtomicI64::new(0)),
+            is_leader_tx: Arc::new(is_leader_tx),
+            is_leader_rx,
+        }
+    }
+
+    /// Returns the fence that only lets the meta store writes through while this meta node holds
+    /// the leader key it created.
+    pub fn leader_fence(&self) -> LeaderFence {
+        LeaderFence::new(META_LEADER_KEY, self.leader_revision.clone())
+    }
+
+    /// Keeps `lease_id` alive until it expires, after which the leadership is lost.
+    async fn keep_alive(
+        client: Client,
+        lease_id: i64,
+        lease_ttl: i64,
+        is_leader_tx: Arc<watch::Sender<bool>>,
+    ) {
+        let result: Result<()> = async {
+            let (mut keeper, mut stream) = client
+                .lease_client()
+                .keep_alive(lease_id)
+                .await
+                .map_err(etcd_err)?;
+            let mut interval =
+                tokio::time::interval(Duration::from_millis(lease_ttl as u64 * 1000 / 3));
+            loop {
+                interval.tick().await;
+                keeper.keep_alive().await.map_err(etcd_err)?;
+                match stream.message().await.map_err(etcd_err)? {
+                    Some(resp) if resp.ttl() > 0 => {}
+                    _ => {
+                        return Err(RwError::from(InternalError(format!(
+                            "lease {} expired",
+                            lease_id
+                        ))))
+                    }
+                }
+            }
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::error!("lost meta leadership: {}", e);
+        }
+        let _ = is_leader_tx.send(false);
+    }
+}
+
+#[async_trait::async_trait]
+impl ElectionClient for EtcdElectionClient {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn campaign(&self) -> Result<()> {
+        let mut kv_client = self.client.kv_client();
+        let mut lease_client = self.client.lease_client();
+        loop {
+            let lease_id = lease_client
+                .grant(self.lease_ttl, None)
+                .await
+                .map_err(etcd_err)?
+                .id();
+            let txn = Txn::new()
+                .when(vec![Compare::create_revision(
+                    META_LEADER_KEY,
+                    CompareOp::Equal,
+                    0,
+                )])
+                .and_then(vec![TxnOp::put(
+                    META_LEADER_KEY,
+                    self.id.clone(),
+                    Some(PutOptions::new().with_lease(lease_id)),
+                )]);
+            let resp = kv_client.txn(txn).await.map_err(etcd_err)?;
+            if resp.succeeded() {
+                // The leader key is created by this transaction, so its create revision is the
+                // revision of the transaction.
+                let revision = resp
+                    .header()
+                    .ok_or_else(|| {
+                        RwError::from(InternalError("etcd response missing header".to_string()))
+                    })?
+                    .revision();
+                self.leader_revision.store(revision, Ordering::Release);
+                let join_handle = tokio::spawn(Self::keep_alive(
+                    self.client.clone(),
+                    lease_id,
+                    self.lease_ttl,
+                    self.is_leader_tx.clone(),
+                ));
+                *self.keep_alive.lock().await = Some((lease_id, join_handle));
+                let _ = self.is_leader_tx.send(true);
+                tracing::info!(
+                    "elected as meta leader with lease {} at revision {}",
+                    lease_id,
+                    revision
+                );
+                return Ok(());
+            }
+
+            lease_client.revoke(lease_id).await.map_err(etcd_err)?;
+            tokio::time::sleep(CAMPAIGN_RETRY_INTERVAL).await;
+        }
+    }
+
+    async fn resign(&self) -> Result<()> {
+        if let Some((lease_id, join_handle)) = self.keep_alive.lock().await.take() {
+            join_handle.abort();
+            // The leader key is deleted along with the lease.
+            self.client
+                .lease_client()
+                .revoke(lease_id)
+                .await
+                .map_err(etcd_err)?;
+            let _ = self.is_leader_tx.send(false);
+        }
+        Ok(())
+    }
+
+    async fn leader(&self) -> Result<Option<String>> {
+        let resp = self
+            .client
+            .kv_client()
+            .get(META_LEADER_KEY, None)
+            .await
+            .map_err(etcd_err)?;
+        Ok(resp
+            .kvs()
+            .first()
+            .map(|kv| String::from_utf8_lossy(kv.value()).into_owned()))
+    }
+
+    fn subscribe(&self) -> watch::Receiver<bool> {
+        self.is_leader_rx.clone()
+    }
+}
+
+/// [`MemElection`] is an in-memory election shared by [`MemElectionClient`]s. It's used for the
+/// meta nodes with memory backend, which can't be shared by other meta nodes anyway, and tests.
+#[derive(Default)]
+pub struct MemElection {
+    leader: Mutex<Option<String>>,
+    leader_changed: Notify,
+}
+
+pub struct MemElectionClient {
+    election: Arc<MemElection>,
+    id: String,
+    is_leader_tx: watch::Sender<bool>,
+    is_leader_rx: watch::Receiver<bool>,
+}
+
+impl MemElectionClient {
+    pub fn new(election: Arc<MemElection>, id: String) -> Self {
+        let (is_leader_tx, is_leader_rx) = watch::channel(false);
+        Self {
+            election,
+            id,
+            is_leader_tx,
+            is_leader_rx,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ElectionClient for MemElectionClient {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn campaign(&self) -> Result<()> {
+        loop {
+            let leader_changed = self.election.leader_changed.notified();
+            {
+                let mut leader = self.election.leader.lock().await;
+                if leader.is_none() {
+                    *leader = Some(self.id.clone());
+                    let _ = self.is_leader_tx.send(true);
+                    return Ok(());
+                }
+            }
+            leader_changed.await;
+        }
+    }
+
+    async fn resign(&self) -> Result<()> {
+        let mut leader = self.election.leader.lock().await;
+        if leader.as_ref() == Some(&self.id) {
+            *leader = None;
+            let _ = self.is_leader_tx.send(false);
+            self.election.leader_changed.notify_waiters();
+        }
+        Ok(())
+    }
+
+    async fn leader(&self) -> Result<Option<String>> {
+        Ok(self.election.leader.lock().await.clone())
+    }
+
+    fn subscribe(&self) -> watch::Receiver<bool> {
+        self.is_leader_rx.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mem_election() -> Result<()> {
+        let election = Arc::new(MemElection::default());
+        let client1 = Arc::new(MemElectionClient::new(
+            election.clone(),
+            "meta-1".to_string(),
+        ));
+        let client2 = Arc::new(MemElectionClient::new(election, "meta-2".to_string()));
+        assert_eq!(client1.leader().await?, None);
+
+        client1.campaign().await?;
+        assert!(client1.is_leader());
+        assert_eq!(client2.leader().await?, Some("meta-1".to_string()));
+
+        // The second node becomes leader only after the first one resigns.
+        let campaign = tokio::spawn({
+            let client2 = client2.clone();
+            async move { client2.campaign().await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!client2.is_leader());
+
+        client1.resign().await?;
+        campaign.await.unwrap()?;
+        assert!(!client1.is_leader());
+        assert!(client2.is_leader());
+        assert_eq!(client1.leader().await?, Some("meta-2".to_string()));
+
+        Ok(())
+    }
+}
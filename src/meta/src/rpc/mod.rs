@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod election;
 mod intercept;
 pub mod metrics;
 pub mod server;
@@ -23,5 +24,6 @@ pub use service::ddl_service::DdlServiceImpl;
 pub use service::epoch_service::EpochServiceImpl;
 pub use service::heartbeat_service::HeartbeatServiceImpl;
 pub use service::hummock_service::HummockServiceImpl;
+pub use service::leader_service::LeaderServiceImpl;
 pub use service::notification_service::NotificationServiceImpl;
 pub use service::stream_service::StreamServiceImpl;
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -25,15 +26,18 @@ use risingwave_pb::meta::catalog_service_server::CatalogServiceServer;
 use risingwave_pb::meta::cluster_service_server::ClusterServiceServer;
 use risingwave_pb::meta::epoch_service_server::EpochServiceServer;
 use risingwave_pb::meta::heartbeat_service_server::HeartbeatServiceServer;
+use risingwave_pb::meta::leader_service_server::LeaderServiceServer;
 use risingwave_pb::meta::notification_service_server::NotificationServiceServer;
 use risingwave_pb::meta::stream_manager_service_server::StreamManagerServiceServer;
 use tokio::net::TcpListener;
 use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
+use super::election::{ElectionClientRef, EtcdElectionClient, MemElection, MemElectionClient};
 use super::intercept::MetricsMiddlewareLayer;
 use super::service::notification_service::NotificationServiceImpl;
-use super::DdlServiceImpl;
+use super::{DdlServiceImpl, LeaderServiceImpl};
 use crate::barrier::GlobalBarrierManager;
 use crate::cluster::ClusterManager;
 use crate::dashboard::DashboardService;
@@ -48,17 +52,23 @@ use crate::rpc::service::epoch_service::EpochServiceImpl;
 use crate::rpc::service::heartbeat_service::HeartbeatServiceImpl;
 use crate::rpc::service::hummock_service::HummockServiceImpl;
 use crate::rpc::service::stream_service::StreamServiceImpl;
-use crate::storage::{EtcdMetaStore, MemStore, MetaStore};
+use crate::storage::{EtcdMetaStore, FileMetaStore, MemStore, MetaStore};
 use crate::stream::{FragmentManager, GlobalStreamManager, SourceManager};
 
+/// Backoff bounds to retry campaigning for leadership on errors, e.g. etcd being unavailable.
+const CAMPAIGN_MIN_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+const CAMPAIGN_MAX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
 #[derive(Debug)]
 pub enum MetaStoreBackend {
     Etcd { endpoints: Vec<String> },
     Mem,
+    File { dir: PathBuf },
 }
 
 pub async fn rpc_serve(
     addr: SocketAddr,
+    advertise_addr: String,
     prometheus_addr: Option<SocketAddr>,
     dashboard_addr: Option<SocketAddr>,
     meta_store_backend: MetaStoreBackend,
@@ -77,12 +87,21 @@ pub async fn rpc_serve(
             )
             .await
             .map_err(|e| RwError::from(InternalError(format!("failed to connect etcd {}", e))))?;
-            let meta_store = Arc::new(EtcdMetaStore::new(client));
+            let election_client = Arc::new(EtcdElectionClient::new(
+                client.clone(),
+                advertise_addr,
+                opts.leader_lease,
+            ));
+            let meta_store = Arc::new(EtcdMetaStore::with_leader_fence(
+                client,
+                election_client.leader_fence(),
+            ));
             rpc_serve_with_store(
                 addr,
                 prometheus_addr,
                 dashboard_addr,
                 meta_store,
+                election_client,
                 max_heartbeat_interval,
                 ui_path,
                 opts,
@@ -90,12 +109,39 @@ pub async fn rpc_serve(
             .await
         }
         MetaStoreBackend::Mem => {
+            // The memory backend can't be shared with other meta nodes, so the only meta node
+            // is always elected as leader.
+            let election_client = Arc::new(MemElectionClient::new(
+                Arc::new(MemElection::default()),
+                advertise_addr,
+            ));
             let meta_store = Arc::new(MemStore::default());
             rpc_serve_with_store(
                 addr,
                 prometheus_addr,
                 dashboard_addr,
                 meta_store,
+                election_client,
+                max_heartbeat_interval,
+                ui_path,
+                opts,
+            )
+            .await
+        }
+        MetaStoreBackend::File { dir } => {
+            // The file backend is for single-node deployments, so the only meta node is always
+            // elected as leader as well.
+            let election_client = Arc::new(MemElectionClient::new(
+                Arc::new(MemElection::default()),
+                advertise_addr,
+            ));
+            let meta_store = Arc::new(FileMetaStore::open(dir).await?);
+            rpc_serve_with_store(
+                addr,
+                prometheus_addr,
+                dashboard_addr,
+                meta_store,
+                election_client,
                 max_heartbeat_interval,
                 ui_path,
                 opts,
@@ -110,10 +156,43 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
     prometheus_addr: Option<SocketAddr>,
     dashboard_addr: Option<SocketAddr>,
     meta_store: Arc<S>,
+    election_client: ElectionClientRef,
     max_heartbeat_interval: Duration,
     ui_path: Option<String>,
     opts: MetaOpts,
 ) -> (JoinHandle<()>, UnboundedSender<()>) {
+    // Serve as a follower until elected as leader, so that clients can find the current leader.
+    let listener = TcpListener::bind(addr).await.unwrap();
+    let (follower_shutdown_send, follower_shutdown_recv) = oneshot::channel::<()>();
+    let follower_join_handle = {
+        let leader_srv = LeaderServiceImpl::new(election_client.clone());
+        tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(LeaderServiceServer::new(leader_srv))
+                .serve_with_incoming_shutdown(
+                    tokio_stream::wrappers::TcpListenerStream::new(listener),
+                    async move {
+                        let _ = follower_shutdown_recv.await;
+                    },
+                )
+                .await
+                .unwrap();
+        })
+    };
+    let mut retry_interval = CAMPAIGN_MIN_RETRY_INTERVAL;
+    while let Err(err) = election_client.campaign().await {
+        tracing::warn!(
+            "failed to campaign for meta leadership, retry in {:?}: {}",
+            retry_interval,
+            err
+        );
+        tokio::time::sleep(retry_interval).await;
+        retry_interval = (retry_interval * 2).min(CAMPAIGN_MAX_RETRY_INTERVAL);
+    }
+    follower_shutdown_send.send(()).unwrap();
+    follower_join_handle.await.unwrap();
+    tracing::info!("elected as meta leader at {}", election_client.id());
+
     let listener = TcpListener::bind(addr).await.unwrap();
     let epoch_generator = Arc::new(MemEpochGenerator::new());
     let env = MetaSrvEnv::<S>::new(opts, meta_store.clone(), epoch_generator.clone()).await;
@@ -128,9 +207,14 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             .unwrap(),
     );
     let hummock_manager = Arc::new(
-        hummock::HummockManager::new(env.clone(), cluster_manager.clone(), meta_metrics.clone())
-            .await
-            .unwrap(),
+        hummock::HummockManager::new(
+            env.clone(),
+            cluster_manager.clone(),
+            fragment_manager.clone(),
+            meta_metrics.clone(),
+        )
+        .await
+        .unwrap(),
     );
 
     if let Some(dashboard_addr) = dashboard_addr {
@@ -167,6 +251,7 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             cluster_manager.clone(),
             barrier_manager.clone(),
             catalog_manager_v2.clone(),
+            fragment_manager.clone(),
         )
         .await
         .unwrap(),
@@ -218,10 +303,12 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
         hummock_manager.clone(),
         compactor_manager.clone(),
         vacuum_trigger.clone(),
+        cluster_manager.clone(),
     );
     let notification_manager = env.notification_manager_ref();
     let notification_srv =
         NotificationServiceImpl::new(env, catalog_manager_v2, cluster_manager.clone());
+    let leader_srv = LeaderServiceImpl::new(election_client.clone());
 
     if let Some(prometheus_addr) = prometheus_addr {
         meta_metrics.boot_metrics_service(prometheus_addr);
@@ -246,6 +333,7 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
     }
 
     let (shutdown_send, mut shutdown_recv) = mpsc::unbounded_channel();
+    let mut is_leader = election_client.subscribe();
     let join_handle = tokio::spawn(async move {
         tonic::transport::Server::builder()
             .layer(MetricsMiddlewareLayer::new(meta_metrics.clone()))
@@ -257,28 +345,110 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             .add_service(HummockManagerServiceServer::new(hummock_srv))
             .add_service(NotificationServiceServer::new(notification_srv))
             .add_service(DdlServiceServer::new(ddl_srv))
+            .add_service(LeaderServiceServer::new(leader_srv))
             .serve_with_incoming_shutdown(
                 tokio_stream::wrappers::TcpListenerStream::new(listener),
                 async move {
+                    let leadership_lost = async move {
+                        while *is_leader.borrow() {
+                            if is_leader.changed().await.is_err() {
+                                break;
+                            }
+                        }
+                    };
                     tokio::select! {
                         _ = tokio::signal::ctrl_c() => {},
-                        _ = shutdown_recv.recv() => {
-                            for (join_handle, shutdown_sender) in sub_tasks {
-                                if let Err(err) = shutdown_sender.send(()) {
-                                    tracing::warn!("Failed to send shutdown: {:?}", err);
-                                    continue;
-                                }
-                                if let Err(err) = join_handle.await {
-                                    tracing::warn!("Failed to join shutdown: {:?}", err);
-                                }
-                            }
+                        _ = shutdown_recv.recv() => shutdown_sub_tasks(sub_tasks).await,
+                        // Stop serving once another meta node may have been elected as leader.
+                        _ = leadership_lost => {
+                            tracing::error!("meta leadership is lost, shutting down");
+                            shutdown_sub_tasks(sub_tasks).await;
                         },
                     }
                 },
             )
             .await
             .unwrap();
+
+        // Let followers take over immediately.
+        if let Err(err) = election_client.resign().await {
+            tracing::warn!("Failed to resign meta leadership: {:?}", err);
+        }
     });
 
     (join_handle, shutdown_send)
 }
+
+async fn shutdown_sub_tasks(sub_tasks: Vec<(JoinHandle<()>, UnboundedSender<()>)>) {
+    for (join_handle, shutdown_sender) in sub_tasks {
+        if let Err(err) = shutdown_sender.send(()) {
+            tracing::warn!("Failed to send shutdown: {:?}", err);
+            continue;
+        }
+        if let Err(err) = join_handle.await {
+            tracing::warn!("Failed to join shutdown: {:?}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::common::WorkerType;
+    use risingwave_rpc_client::MetaClient;
+
+    use super::*;
+
+    /// Returns an address with a port picked by the OS, which is free once the listener drops.
+    async fn free_addr() -> SocketAddr {
+        TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_meta_leader_failover() {
+        let meta_store = Arc::new(MemStore::default());
+        let election = Arc::new(MemElection::default());
+        let start_meta = |addr: SocketAddr| {
+            let addr = addr.to_string();
+            rpc_serve_with_store(
+                addr.parse().unwrap(),
+                None,
+                None,
+                meta_store.clone(),
+                Arc::new(MemElectionClient::new(election.clone(), addr)),
+                Duration::from_secs(3600),
+                None,
+                MetaOpts::default(),
+            )
+        };
+
+        let addr1 = free_addr().await;
+        let addr2 = free_addr().await;
+        let (join_handle1, shutdown_send1) = start_meta(addr1).await;
+        // The second meta node serves as a follower until the first one shuts down.
+        let meta2 = tokio::spawn(start_meta(addr2));
+        let client = MetaClient::new(&format!("{},{}", addr2, addr1))
+            .await
+            .unwrap();
+        client
+            .list_all_nodes(WorkerType::ComputeNode, false)
+            .await
+            .unwrap();
+
+        shutdown_send1.send(()).unwrap();
+        join_handle1.await.unwrap();
+        let (join_handle2, shutdown_send2) = meta2.await.unwrap();
+
+        // The client fails over to the second meta node.
+        client
+            .list_all_nodes(WorkerType::ComputeNode, false)
+            .await
+            .unwrap();
+
+        shutdown_send2.send(()).unwrap();
+        join_handle2.await.unwrap();
+    }
+}
@@ -54,7 +54,7 @@ where
             .map_err(|e| e.to_grpc_status())?;
         let (worker_node, _added) = self
             .cluster_manager
-            .add_worker_node(host, worker_type)
+            .add_worker_node(host, worker_type, req.labels)
             .await
             .map_err(|e| e.to_grpc_status())?;
         Ok(Response::new(AddWorkerNodeResponse {
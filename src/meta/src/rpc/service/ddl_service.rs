@@ -281,7 +281,13 @@ where
 
         // 3. Create mview in stream manager. The id in stream node will be filled.
         if let Err(e) = self
-            .create_mview_on_compute_node(stream_node, id, None)
+            .create_mview_on_compute_node(
+                stream_node,
+                id,
+                None,
+                mview.state_ttl_ms,
+                mview.placement.clone(),
+            )
             .await
         {
             self.catalog_manager
@@ -382,6 +388,8 @@ where
         mut stream_node: StreamNode,
         id: TableId,
         affiliated_source: Option<Source>,
+        state_ttl_ms: u64,
+        placement: Option<Placement>,
     ) -> RwResult<()> {
         use risingwave_common::catalog::TableId;
 
@@ -411,6 +419,7 @@ where
         let hash_mapping = self.cluster_manager.get_hash_mapping().await;
         let mut ctx = CreateMaterializedViewContext {
             affiliated_source,
+            placement,
             ..Default::default()
         };
         let fragmenter = StreamFragmenter::new(
@@ -418,7 +427,13 @@ where
             self.fragment_manager.clone(),
             hash_mapping,
         );
-        let graph = fragmenter.generate_graph(&stream_node, &mut ctx).await?;
+        let mut graph = fragmenter.generate_graph(&stream_node, &mut ctx).await?;
+        for actor in graph
+            .values_mut()
+            .flat_map(|fragment| fragment.actors.iter_mut())
+        {
+            actor.state_ttl_ms = state_ttl_ms;
+        }
         let table_fragments = TableFragments::new(mview_id, graph);
 
         // Create on compute node.
@@ -491,7 +506,13 @@ where
         // Create mview on compute node.
         // Noted that this progress relies on the source just created, so we pass it here.
         if let Err(e) = self
-            .create_mview_on_compute_node(stream_node, mview_id, Some(source.clone()))
+            .create_mview_on_compute_node(
+                stream_node,
+                mview_id,
+                Some(source.clone()),
+                mview.state_ttl_ms,
+                mview.placement.clone(),
+            )
             .await
         {
             self.catalog_manager
@@ -14,11 +14,15 @@
 
 use std::sync::Arc;
 
+use risingwave_common::error::{ErrorCode, RwError};
 use risingwave_pb::hummock::hummock_manager_service_server::HummockManagerService;
 use risingwave_pb::hummock::*;
 use tonic::{Request, Response, Status};
 
-use crate::hummock::{CompactorManager, HummockManagerRef, VacuumTrigger};
+use crate::cluster::ClusterManagerRef;
+use crate::hummock::{
+    trigger_manual_compaction, CompactorManager, HummockManagerRef, VacuumTrigger,
+};
 use crate::rpc::service::RwReceiverStream;
 use crate::storage::MetaStore;
 
@@ -29,6 +33,7 @@ where
     hummock_manager: HummockManagerRef<S>,
     compactor_manager: Arc<CompactorManager>,
     vacuum_trigger: Arc<VacuumTrigger<S>>,
+    cluster_manager: ClusterManagerRef<S>,
 }
 
 impl<S> HummockServiceImpl<S>
@@ -39,11 +44,13 @@ where
         hummock_manager: HummockManagerRef<S>,
         compactor_manager: Arc<CompactorManager>,
         vacuum_trigger: Arc<VacuumTrigger<S>>,
+        cluster_manager: ClusterManagerRef<S>,
     ) -> Self {
         HummockServiceImpl {
             hummock_manager,
             compactor_manager,
             vacuum_trigger,
+            cluster_manager,
         }
     }
 }
@@ -202,9 +209,20 @@ where
         &self,
         request: Request<SubscribeCompactTasksRequest>,
     ) -> Result<Response<Self::SubscribeCompactTasksStream>, Status> {
+        let context_id = request.into_inner().context_id;
+        let worker_type = match self.cluster_manager.get_worker_by_id(context_id).await {
+            Some(worker) => worker.worker_type(),
+            None => {
+                return Err(RwError::from(ErrorCode::InternalError(format!(
+                    "worker {} not found",
+                    context_id
+                )))
+                .to_grpc_status())
+            }
+        };
         let rx = self
             .compactor_manager
-            .add_compactor(request.into_inner().context_id);
+            .add_compactor(context_id, worker_type);
         Ok(Response::new(RwReceiverStream::new(rx)))
     }
 
@@ -220,4 +238,62 @@ where
         }
         Ok(Response::new(ReportVacuumTaskResponse { status: None }))
     }
+
+    async fn trigger_manual_compaction(
+        &self,
+        request: Request<TriggerManualCompactionRequest>,
+    ) -> Result<Response<TriggerManualCompactionResponse>, Status> {
+        let result = trigger_manual_compaction(
+            self.hummock_manager.clone(),
+            self.compactor_manager.clone(),
+            request.into_inner().level,
+        )
+        .await;
+        match result {
+            Ok(task_id) => Ok(Response::new(TriggerManualCompactionResponse {
+                status: None,
+                task_id,
+            })),
+            Err(e) => Err(e.to_grpc_status()),
+        }
+    }
+
+    async fn create_backup(
+        &self,
+        request: Request<CreateBackupRequest>,
+    ) -> Result<Response<CreateBackupResponse>, Status> {
+        let result = self
+            .hummock_manager
+            .create_backup(request.into_inner().path)
+            .await;
+        match result {
+            Ok(manifest) => Ok(Response::new(CreateBackupResponse {
+                status: None,
+                manifest: Some(manifest),
+            })),
+            Err(e) => Err(e.to_grpc_status()),
+        }
+    }
+
+    async fn list_backups(
+        &self,
+        _request: Request<ListBackupsRequest>,
+    ) -> Result<Response<ListBackupsResponse>, Status> {
+        let backups = self.hummock_manager.list_backups().await;
+        Ok(Response::new(ListBackupsResponse {
+            status: None,
+            backups,
+        }))
+    }
+
+    async fn delete_backup(
+        &self,
+        request: Request<DeleteBackupRequest>,
+    ) -> Result<Response<DeleteBackupResponse>, Status> {
+        self.hummock_manager
+            .delete_backup(request.into_inner().backup_id)
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+        Ok(Response::new(DeleteBackupResponse { status: None }))
+    }
 }
@@ -0,0 +1,48 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::meta::leader_service_server::LeaderService;
+use risingwave_pb::meta::{GetLeaderRequest, GetLeaderResponse};
+use tonic::{Request, Response, Status};
+
+use crate::rpc::election::ElectionClientRef;
+
+#[derive(Clone)]
+pub struct LeaderServiceImpl {
+    election_client: ElectionClientRef,
+}
+
+impl LeaderServiceImpl {
+    pub fn new(election_client: ElectionClientRef) -> Self {
+        LeaderServiceImpl { election_client }
+    }
+}
+
+#[async_trait::async_trait]
+impl LeaderService for LeaderServiceImpl {
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_leader(
+        &self,
+        request: Request<GetLeaderRequest>,
+    ) -> Result<Response<GetLeaderResponse>, Status> {
+        let _req = request.into_inner();
+        let leader_addr = self
+            .election_client
+            .leader()
+            .await
+            .map_err(|e| e.to_grpc_status())?
+            .unwrap_or_default();
+        Ok(Response::new(GetLeaderResponse { leader_addr }))
+    }
+}
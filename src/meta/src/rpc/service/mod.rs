@@ -18,6 +18,7 @@ pub mod ddl_service;
 pub mod epoch_service;
 pub mod heartbeat_service;
 pub mod hummock_service;
+pub mod leader_service;
 pub mod notification_service;
 pub mod stream_service;
 
@@ -130,4 +130,48 @@ where
             .map_err(|e| e.to_grpc_status())?;
         Ok(Response::new(FlushResponse { status: None }))
     }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn reschedule(
+        &self,
+        request: Request<RescheduleRequest>,
+    ) -> TonicResponse<RescheduleResponse> {
+        let req = request.into_inner();
+        let parallelism = match req.parallelism {
+            0 => None,
+            parallelism => Some(parallelism as usize),
+        };
+
+        self.global_stream_manager
+            .reschedule_materialized_view(&TableId::new(req.table_id), parallelism, req.worker_ids)
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+        Ok(Response::new(RescheduleResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_streaming_status(
+        &self,
+        request: Request<GetStreamingStatusRequest>,
+    ) -> TonicResponse<GetStreamingStatusResponse> {
+        let _req = request.into_inner();
+
+        let response = self
+            .global_stream_manager
+            .streaming_status()
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+        Ok(Response::new(response))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_barrier_status(
+        &self,
+        request: Request<GetBarrierStatusRequest>,
+    ) -> TonicResponse<GetBarrierStatusResponse> {
+        let _req = request.into_inner();
+
+        let response = self.global_stream_manager.barrier_status().await;
+        Ok(Response::new(response))
+    }
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::sync::atomic::{self, AtomicI64};
+use std::sync::Arc;
 
 use anyhow;
 use async_trait::async_trait;
@@ -35,6 +36,33 @@ const REVISION_UNINITIALIZED: i64 = -1;
 #[derive(Clone)]
 pub struct EtcdMetaStore {
     client: Client,
+    fence: Option<LeaderFence>,
+}
+
+/// [`LeaderFence`] guards the writes of [`EtcdMetaStore`] with the create revision of the leader
+/// key, so that a deposed meta leader can't overwrite the meta data of the new leader.
+#[derive(Clone)]
+pub struct LeaderFence {
+    key: Vec<u8>,
+    /// The create revision of the leader key written by this meta node, or 0 if not elected.
+    revision: Arc<AtomicI64>,
+}
+
+impl LeaderFence {
+    pub fn new(key: impl Into<Vec<u8>>, revision: Arc<AtomicI64>) -> Self {
+        Self {
+            key: key.into(),
+            revision,
+        }
+    }
+
+    fn compare(&self) -> Compare {
+        Compare::create_revision(
+            self.key.clone(),
+            CompareOp::Equal,
+            self.revision.load(atomic::Ordering::Acquire),
+        )
+    }
 }
 pub struct EtcdSnapshot {
     client: KvClient,
@@ -123,7 +151,8 @@ struct ListViewer {
 }
 
 impl SnapshotViewer for ListViewer {
-    type Output = Vec<Vec<u8>>;
+    /// Key-value pairs with `key` as prefix, whose keys are stripped of the prefix.
+    type Output = Vec<(Vec<u8>, Vec<u8>)>;
 
     type OutputFuture<'a> = impl Future<Output = Result<(i64, Self::Output)>> + 'a;
 
@@ -142,8 +171,12 @@ impl SnapshotViewer for ListViewer {
                     "Etcd response missing header"
                 )));
             };
-            let value = res.kvs().iter().map(|kv| kv.value().to_vec()).collect();
-            Ok((new_revision, value))
+            let kvs = res
+                .kvs()
+                .iter()
+                .map(|kv| (kv.key()[self.key.len()..].to_vec(), kv.value().to_vec()))
+                .collect();
+            Ok((new_revision, kvs))
         }
     }
 }
@@ -151,6 +184,11 @@ impl SnapshotViewer for ListViewer {
 #[async_trait]
 impl Snapshot for EtcdSnapshot {
     async fn list_cf(&self, cf: &str) -> Result<Vec<Vec<u8>>> {
+        let kvs = self.list_cf_kvs(cf).await?;
+        Ok(kvs.into_iter().map(|(_, value)| value).collect())
+    }
+
+    async fn list_cf_kvs(&self, cf: &str) -> Result<Vec<(Key, Value)>> {
         let view = ListViewer {
             key: encode_etcd_key(cf, &[]),
         };
@@ -167,7 +205,47 @@ impl Snapshot for EtcdSnapshot {
 
 impl EtcdMetaStore {
     pub fn new(client: Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            fence: None,
+        }
+    }
+
+    /// Creates a meta store whose writes only succeed while `fence` holds the leadership.
+    pub fn with_leader_fence(client: Client, fence: LeaderFence) -> Self {
+        Self {
+            client,
+            fence: Some(fence),
+        }
+    }
+
+    /// Commits `when` and `then` in a transaction guarded by the leader fence if any.
+    async fn commit(&self, mut when: Vec<Compare>, then: Vec<TxnOp>) -> Result<()> {
+        if let Some(fence) = &self.fence {
+            when.push(fence.compare());
+        }
+        let etcd_txn = Txn::new().when(when).and_then(then);
+        let mut kv_client = self.client.kv_client();
+        if kv_client.txn(etcd_txn).await?.succeeded() {
+            return Ok(());
+        }
+        if let Some(fence) = &self.fence {
+            let revision = fence.revision.load(atomic::Ordering::Acquire);
+            let leader_revision = kv_client
+                .get(fence.key.clone(), None)
+                .await?
+                .kvs()
+                .first()
+                .map(|kv| kv.create_revision());
+            if leader_revision != Some(revision) {
+                return Err(Error::Internal(anyhow::anyhow!(
+                    "not the meta leader any more, leader key revision {:?}, expected {}",
+                    leader_revision,
+                    revision
+                )));
+            }
+        }
+        Err(Error::TransactionAbort())
     }
 }
 
@@ -184,19 +262,16 @@ impl MetaStore for EtcdMetaStore {
     }
 
     async fn put_cf(&self, cf: &str, key: Key, value: Value) -> Result<()> {
-        self.client
-            .kv_client()
-            .put(encode_etcd_key(cf, &key), value, None)
-            .await?;
-        Ok(())
+        self.commit(
+            vec![],
+            vec![TxnOp::put(encode_etcd_key(cf, &key), value, None)],
+        )
+        .await
     }
 
     async fn delete_cf(&self, cf: &str, key: &[u8]) -> Result<()> {
-        self.client
-            .kv_client()
-            .delete(encode_etcd_key(cf, key), None)
-            .await?;
-        Ok(())
+        self.commit(vec![], vec![TxnOp::delete(encode_etcd_key(cf, key), None)])
+            .await
     }
 
     async fn txn(&self, trx: Transaction) -> Result<()> {
@@ -225,11 +300,6 @@ impl MetaStore for EtcdMetaStore {
             })
             .collect::<Vec<_>>();
 
-        let etcd_txn = Txn::new().when(when).and_then(then);
-        if !self.client.kv_client().txn(etcd_txn).await?.succeeded() {
-            Err(Error::TransactionAbort())
-        } else {
-            Ok(())
-        }
+        self.commit(when, then).await
     }
 }
@@ -0,0 +1,327 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::{Buf, BufMut};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use super::{
+    Error, Key, MemSnapshot, MemStore, MetaStore, Operation, Precondition, Result, Snapshot,
+    Transaction, Value,
+};
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Internal(anyhow::Error::new(err))
+    }
+}
+
+const WAL_FILE: &str = "wal";
+const SNAPSHOT_FILE: &str = "snapshot";
+const SNAPSHOT_TMP_FILE: &str = "snapshot.tmp";
+
+/// The WAL is compacted into a new snapshot once it grows larger than this.
+const DEFAULT_WAL_COMPACT_THRESHOLD: u64 = 64 << 20;
+
+/// Length of payload (u64) and its checksum (u32).
+const RECORD_HEADER_LEN: usize = 12;
+
+const OP_PUT: u8 = 1;
+const OP_DELETE: u8 = 2;
+
+/// [`MetaStore`] persisted in a local directory, for single-node deployments without etcd.
+///
+/// All data is served from memory. Every write is appended to a write-ahead log and synced before
+/// being applied, and the log is compacted into a snapshot file once it grows large. On opening,
+/// the snapshot and the log are replayed to recover the data.
+#[derive(Clone)]
+pub struct FileMetaStore {
+    mem: MemStore,
+    wal: Arc<Mutex<Wal>>,
+}
+
+struct Wal {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+    compact_threshold: u64,
+}
+
+impl FileMetaStore {
+    /// Open the store in `dir`, which is created if not exists.
+    pub async fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_compact_threshold(dir, DEFAULT_WAL_COMPACT_THRESHOLD).await
+    }
+
+    pub(super) async fn open_with_compact_threshold(
+        dir: impl AsRef<Path>,
+        compact_threshold: u64,
+    ) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let mut ops = vec![];
+        if let Some(buf) = read_if_exists(&dir.join(SNAPSHOT_FILE))? {
+            let (snapshot_ops, len) = decode_records(&buf);
+            if len != buf.len() {
+                return Err(Error::Internal(anyhow::anyhow!(
+                    "corrupted snapshot in {}",
+                    dir.display()
+                )));
+            }
+            ops.extend(snapshot_ops);
+        }
+
+        let wal_path = dir.join(WAL_FILE);
+        let wal_buf = read_if_exists(&wal_path)?.unwrap_or_default();
+        let (wal_ops, len) = decode_records(&wal_buf);
+        ops.extend(wal_ops);
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&wal_path)?;
+        if len != wal_buf.len() {
+            // The last record may be torn by a crash while appending, which was never applied.
+            tracing::warn!(
+                "truncate {} bytes at the end of {}",
+                wal_buf.len() - len,
+                wal_path.display()
+            );
+            file.set_len(len as u64)?;
+            file.sync_all()?;
+        }
+
+        let mem = MemStore::default();
+        let mut txn = Transaction::default();
+        txn.add_operations(ops);
+        mem.txn(txn).await?;
+
+        Ok(Self {
+            mem,
+            wal: Arc::new(Mutex::new(Wal {
+                dir,
+                file,
+                size: len as u64,
+                compact_threshold,
+            })),
+        })
+    }
+
+    async fn write(&self, txn: Transaction) -> Result<()> {
+        // Writes are serialized by the WAL lock, so the preconditions stay valid until applied.
+        let wal = self.wal.clone().lock_owned().await;
+        let (conds, ops) = txn.into_parts();
+
+        let snapshot = self.mem.snapshot().await;
+        for cond in conds {
+            match cond {
+                Precondition::KeyExists { cf, key } => {
+                    if snapshot.get_cf(&cf, &key).await.is_err() {
+                        return Err(Error::TransactionAbort());
+                    }
+                }
+            }
+        }
+        drop(snapshot);
+
+        if ops.is_empty() {
+            return Ok(());
+        }
+        let record = encode_record(&ops);
+        let wal = run_blocking(wal, move |wal| wal.append(&record)).await?;
+
+        let mut txn = Transaction::default();
+        txn.add_operations(ops);
+        self.mem.txn(txn).await?;
+
+        if wal.size > wal.compact_threshold {
+            let snapshot = encode_record(&self.mem.snapshot().await.to_operations());
+            run_blocking(wal, move |wal| wal.compact(&snapshot)).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs `f` on the locked WAL in the blocking thread pool, so that the disk I/O doesn't stall the
+/// async runtime. The WAL stays locked until `f` returns.
+async fn run_blocking(
+    mut wal: OwnedMutexGuard<Wal>,
+    f: impl FnOnce(&mut Wal) -> Result<()> + Send + 'static,
+) -> Result<OwnedMutexGuard<Wal>> {
+    tokio::task::spawn_blocking(move || f(&mut wal).map(|_| wal))
+        .await
+        .map_err(|err| Error::Internal(anyhow::Error::new(err)))?
+}
+
+impl Wal {
+    /// Append `record` encoded by [`encode_record`] and sync it to disk.
+    fn append(&mut self, record: &[u8]) -> Result<()> {
+        let result = self
+            .file
+            .write_all(record)
+            .and_then(|_| self.file.sync_data());
+        if let Err(err) = result {
+            // Remove the partially written record, so that later records won't be lost on replay.
+            self.file.set_len(self.size).ok();
+            return Err(err.into());
+        }
+        self.size += record.len() as u64;
+
+        Ok(())
+    }
+
+    /// Replace the snapshot with `snapshot`, the record of all data, and truncate the WAL. Since
+    /// replaying the WAL is idempotent, it's safe to crash before the WAL is truncated.
+    fn compact(&mut self, snapshot: &[u8]) -> Result<()> {
+        let tmp_path = self.dir.join(SNAPSHOT_TMP_FILE);
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(snapshot)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, self.dir.join(SNAPSHOT_FILE))?;
+        File::open(&self.dir)?.sync_all()?;
+
+        self.file.set_len(0)?;
+        self.file.sync_all()?;
+        self.size = 0;
+
+        Ok(())
+    }
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(buf) => Ok(Some(buf)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn put_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    buf.put_u32_le(bytes.len() as u32);
+    buf.put_slice(bytes);
+}
+
+fn get_bytes(buf: &mut &[u8]) -> Option<Vec<u8>> {
+    if buf.remaining() < 4 {
+        return None;
+    }
+    let len = buf.get_u32_le() as usize;
+    if buf.remaining() < len {
+        return None;
+    }
+    let bytes = buf[..len].to_vec();
+    buf.advance(len);
+    Some(bytes)
+}
+
+/// Encode `ops` into a record, which is applied atomically on replay.
+fn encode_record(ops: &[Operation]) -> Vec<u8> {
+    let mut payload = vec![];
+    for op in ops {
+        match op {
+            Operation::Put { cf, key, value } => {
+                payload.put_u8(OP_PUT);
+                put_bytes(&mut payload, cf.as_bytes());
+                put_bytes(&mut payload, key);
+                put_bytes(&mut payload, value);
+            }
+            Operation::Delete { cf, key } => {
+                payload.put_u8(OP_DELETE);
+                put_bytes(&mut payload, cf.as_bytes());
+                put_bytes(&mut payload, key);
+            }
+        }
+    }
+
+    let mut record = Vec::with_capacity(RECORD_HEADER_LEN + payload.len());
+    record.put_u64_le(payload.len() as u64);
+    record.put_u32_le(crc32fast::hash(&payload));
+    record.put_slice(&payload);
+    record
+}
+
+fn decode_operations(mut payload: &[u8]) -> Option<Vec<Operation>> {
+    let mut ops = vec![];
+    while payload.has_remaining() {
+        let op = payload.get_u8();
+        let cf = String::from_utf8(get_bytes(&mut payload)?).ok()?;
+        let key = get_bytes(&mut payload)?;
+        ops.push(match op {
+            OP_PUT => Operation::Put {
+                cf,
+                key,
+                value: get_bytes(&mut payload)?,
+            },
+            OP_DELETE => Operation::Delete { cf, key },
+            _ => return None,
+        });
+    }
+    Some(ops)
+}
+
+/// Decode records from `buf` until the first incomplete or corrupted one. Returns the operations
+/// and the length of valid records.
+fn decode_records(buf: &[u8]) -> (Vec<Operation>, usize) {
+    let mut ops = vec![];
+    let mut offset = 0;
+    loop {
+        let mut rest = &buf[offset..];
+        if rest.len() < RECORD_HEADER_LEN {
+            break;
+        }
+        let len = rest.get_u64_le() as usize;
+        let checksum = rest.get_u32_le();
+        if rest.len() < len || crc32fast::hash(&rest[..len]) != checksum {
+            break;
+        }
+        match decode_operations(&rest[..len]) {
+            Some(record_ops) => ops.extend(record_ops),
+            None => break,
+        }
+        offset += RECORD_HEADER_LEN + len;
+    }
+    (ops, offset)
+}
+
+#[async_trait]
+impl MetaStore for FileMetaStore {
+    type Snapshot = MemSnapshot;
+
+    async fn snapshot(&self) -> Self::Snapshot {
+        self.mem.snapshot().await
+    }
+
+    async fn put_cf(&self, cf: &str, key: Key, value: Value) -> Result<()> {
+        let mut txn = Transaction::default();
+        txn.put(cf.to_string(), key, value);
+        self.write(txn).await
+    }
+
+    async fn delete_cf(&self, cf: &str, key: &[u8]) -> Result<()> {
+        let mut txn = Transaction::default();
+        txn.delete(cf.to_string(), key.to_vec());
+        self.write(txn).await
+    }
+
+    async fn txn(&self, txn: Transaction) -> Result<()> {
+        self.write(txn).await
+    }
+}
@@ -18,10 +18,26 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use tokio::sync::{OwnedRwLockReadGuard, RwLock};
 
-use super::{ColumnFamily, Error, Key, MetaStore, Result, Snapshot, Transaction, Value};
+use super::{ColumnFamily, Error, Key, MetaStore, Operation, Result, Snapshot, Transaction, Value};
 
 pub struct MemSnapshot(OwnedRwLockReadGuard<MemStoreInner>);
 
+impl MemSnapshot {
+    /// Returns all key-value pairs in the snapshot as `Put` operations.
+    pub(crate) fn to_operations(&self) -> Vec<Operation> {
+        let MemStoreInner(cfs) = &*self.0;
+        cfs.iter()
+            .flat_map(|(cf, kvs)| {
+                kvs.iter().map(|(key, value)| Operation::Put {
+                    cf: cf.clone(),
+                    key: key.clone(),
+                    value: value.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
 /// [`MetaStore`] implemented in memory.
 ///
 /// Note: Don't use in production.
@@ -56,6 +72,17 @@ impl Snapshot for MemSnapshot {
         })
     }
 
+    #[inline(always)]
+    async fn list_cf_kvs(&self, cf: &str) -> Result<Vec<(Key, Value)>> {
+        Ok(match self.0.cf_ref(cf) {
+            Some(cf) => cf
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            None => vec![],
+        })
+    }
+
     #[inline(always)]
     async fn get_cf(&self, cf: &str, key: &[u8]) -> Result<Value> {
         self.0
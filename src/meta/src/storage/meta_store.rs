@@ -25,6 +25,8 @@ pub const DEFAULT_COLUMN_FAMILY: &str = "default";
 #[async_trait]
 pub trait Snapshot: Sync + Send + 'static {
     async fn list_cf(&self, cf: &str) -> Result<Vec<Vec<u8>>>;
+    /// Lists the key-value pairs of `cf`, ordered by key.
+    async fn list_cf_kvs(&self, cf: &str) -> Result<Vec<(Key, Value)>>;
     async fn get_cf(&self, cf: &str, key: &[u8]) -> Result<Vec<u8>>;
 }
 
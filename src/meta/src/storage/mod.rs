@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod etcd_meta_store;
+mod file_meta_store;
 mod mem_meta_store;
 mod meta_store;
 #[cfg(test)]
@@ -24,6 +25,7 @@ pub type Key = Vec<u8>;
 pub type Value = Vec<u8>;
 
 pub use etcd_meta_store::*;
+pub use file_meta_store::*;
 pub use mem_meta_store::*;
 pub use meta_store::*;
 pub use transaction::*;
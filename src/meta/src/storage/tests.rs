@@ -18,7 +18,7 @@ use itertools::Itertools;
 use risingwave_common::error::Result;
 
 use super::{Key, Result as MetaResult, Value};
-use crate::storage::{Error, MemStore, MetaStore, Operation, Snapshot, Transaction};
+use crate::storage::{Error, FileMetaStore, MemStore, MetaStore, Operation, Snapshot, Transaction};
 
 const TEST_DEFAULT_CF: &str = "TEST_DEFAULT";
 
@@ -96,6 +96,14 @@ async fn test_meta_store_basic<S: MetaStore>(store: &S) -> MetaResult<()> {
         assert_eq!(vals.len(), 2);
         let vals = snapshot.list_cf(TEST_DEFAULT_CF).await?;
         assert_eq!(vals.len(), 3);
+        let kvs = snapshot.list_cf_kvs("test_cf").await?;
+        assert_eq!(
+            kvs,
+            vec![
+                (b"key_1".to_vec(), b"value_1".to_vec()),
+                (b"key_2".to_vec(), b"value_2".to_vec()),
+            ]
+        );
     }
 
     assert!(store
@@ -251,3 +259,69 @@ async fn test_mem_store() -> Result<()> {
     test_meta_store_transaction(&store).await.unwrap();
     Ok(())
 }
+
+#[tokio::test]
+async fn test_file_store() -> Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    let store = FileMetaStore::open(dir.path()).await.unwrap();
+    test_meta_store_basic(&store).await.unwrap();
+    test_meta_store_keys_share_prefix(&store).await.unwrap();
+    test_meta_store_overlapped_cf(&store).await.unwrap();
+    test_meta_store_transaction(&store).await.unwrap();
+    drop(store);
+
+    // All data should be recovered after reopening.
+    let store = FileMetaStore::open(dir.path()).await.unwrap();
+    let mut values = store.list().await.unwrap();
+    values.sort();
+    let expected: Vec<Vec<u8>> = vec![
+        b"value_1".to_vec(),
+        b"value_2".to_vec(),
+        b"value_3_new".to_vec(),
+    ];
+    assert_eq!(values, expected);
+    assert_eq!(store.list_cf("test_cf").await.unwrap().len(), 2);
+    assert_eq!(
+        store.list_cf("test_overlapped_key_cf").await.unwrap().len(),
+        3
+    );
+    assert!(store.list_cf("test_trx_cf").await.unwrap().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_store_recovery() -> Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    // Compact the WAL into a snapshot on every write.
+    let store = FileMetaStore::open_with_compact_threshold(dir.path(), 0)
+        .await
+        .unwrap();
+    store.put(b"key_1".to_vec(), b"value_1".to_vec()).await?;
+    store.put(b"key_2".to_vec(), b"value_2".to_vec()).await?;
+    store.delete(b"key_1").await?;
+    drop(store);
+
+    let store = FileMetaStore::open(dir.path()).await.unwrap();
+    store.put(b"key_3".to_vec(), b"value_3".to_vec()).await?;
+    drop(store);
+
+    // Simulate a torn record at the end of the WAL.
+    let mut wal = std::fs::OpenOptions::new()
+        .append(true)
+        .open(dir.path().join("wal"))
+        .unwrap();
+    std::io::Write::write_all(&mut wal, &[42; 20]).unwrap();
+    drop(wal);
+
+    let store = FileMetaStore::open(dir.path()).await.unwrap();
+    assert!(store.get(b"key_1").await.is_err());
+    assert_eq!(store.get(b"key_2").await.unwrap(), b"value_2".to_vec());
+    assert_eq!(store.get(b"key_3").await.unwrap(), b"value_3".to_vec());
+
+    // Writes after the truncated record should be recovered as well.
+    store.put(b"key_4".to_vec(), b"value_4".to_vec()).await?;
+    drop(store);
+    let store = FileMetaStore::open(dir.path()).await.unwrap();
+    assert_eq!(store.list().await.unwrap().len(), 3);
+    Ok(())
+}
@@ -123,7 +123,7 @@ where
         let stream_graph = self.stream_graph.build(ctx, start_actor_id, actor_len)?;
 
         // Serialize the graph
-        stream_graph
+        let mut fragments = stream_graph
             .iter()
             .map(|(fragment_id, actors)| {
                 Ok::<_, RwError>((
@@ -149,7 +149,48 @@ where
                     },
                 ))
             })
-            .collect::<Result<BTreeMap<_, _>>>()
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        self.assign_state_table_ids(&mut fragments).await?;
+
+        Ok(fragments)
+    }
+
+    /// Allocates state tables for the stateful operators whose states can be shared by all the
+    /// actors of a fragment, so that the states are kept in place when the actors are rescheduled.
+    /// The states of hash aggregations are partitioned by the group keys, while those of simple
+    /// aggregations and top-n are only shareable in singleton fragments.
+    async fn assign_state_table_ids(
+        &self,
+        fragments: &mut BTreeMap<FragmentId, Fragment>,
+    ) -> Result<()> {
+        for fragment in fragments.values_mut() {
+            let is_singleton =
+                fragment.distribution_type == FragmentDistributionType::Single as i32;
+            let mut table_count = 0;
+            visit_state_table_ids(
+                &mut fragment.actors[0].get_nodes()?.clone(),
+                is_singleton,
+                &mut |_| table_count += 1,
+            );
+            if table_count == 0 {
+                continue;
+            }
+
+            let start_table_id = self
+                .id_gen_manager
+                .generate_interval::<{ IdCategory::Table }>(table_count)
+                .await? as u32;
+            for actor in &mut fragment.actors {
+                let mut next_table_id = start_table_id;
+                visit_state_table_ids(actor.nodes.as_mut().unwrap(), is_singleton, &mut |id| {
+                    *id = next_table_id;
+                    next_table_id += 1;
+                });
+            }
+        }
+
+        Ok(())
     }
 
     /// Do some dirty rewrites on meta. Currently, it will split stateful operators into two
@@ -488,3 +529,21 @@ where
         Ok(())
     }
 }
+
+/// Visits the state table ids of the operators in `stream_node` whose states can be shared by all
+/// the actors of the fragment.
+fn visit_state_table_ids(
+    stream_node: &mut StreamNode,
+    is_singleton: bool,
+    f: &mut impl FnMut(&mut u32),
+) {
+    match stream_node.node.as_mut() {
+        Some(Node::HashAggNode(node)) => f(&mut node.table_id),
+        Some(Node::GlobalSimpleAggNode(node)) if is_singleton => f(&mut node.table_id),
+        Some(Node::TopNNode(node)) if is_singleton => f(&mut node.table_id),
+        _ => {}
+    }
+    for input in &mut stream_node.input {
+        visit_state_table_ids(input, is_singleton, f);
+    }
+}
@@ -268,6 +268,8 @@ impl StreamActorBuilder {
                     },
                 )| *same_worker_node,
             ),
+            // Filled in by the DDL service from the catalog.
+            state_ttl_ms: 0,
         }
     }
 }
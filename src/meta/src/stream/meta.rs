@@ -13,13 +13,15 @@
 // limitations under the License.
 
 use std::collections::hash_map::Entry;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::try_match_expand;
+use risingwave_connector::SplitImpl;
+use risingwave_pb::hummock::StateTtl;
 use risingwave_pb::meta::table_fragments::fragment::FragmentType;
 use risingwave_pb::meta::table_fragments::ActorState;
 use risingwave_pb::stream_plan::StreamActor;
@@ -78,6 +80,20 @@ where
         Ok(map.values().cloned().collect())
     }
 
+    pub async fn select_table_fragments_by_table_id(
+        &self,
+        table_id: &TableId,
+    ) -> Result<TableFragments> {
+        let map = &self.core.read().await.table_fragments;
+        match map.get(table_id) {
+            Some(table_fragments) => Ok(table_fragments.clone()),
+            None => Err(RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))),
+        }
+    }
+
     pub async fn update_table_fragments(&self, table_fragment: TableFragments) -> Result<()> {
         let map = &mut self.core.write().await.table_fragments;
 
@@ -95,6 +111,31 @@ where
         }
     }
 
+    /// Updates the splits assigned to stream source actors and persists them, so that the actors
+    /// are rebuilt with these splits on recovery.
+    pub async fn update_actor_splits(
+        &self,
+        actor_splits: &HashMap<ActorId, Vec<SplitImpl>>,
+    ) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+
+        for table_fragments in map.values_mut() {
+            if !table_fragments
+                .actor_ids()
+                .iter()
+                .any(|actor_id| actor_splits.contains_key(actor_id))
+            {
+                continue;
+            }
+            let mut new_table_fragments = table_fragments.clone();
+            new_table_fragments.update_actor_splits(actor_splits)?;
+            new_table_fragments.insert(&*self.meta_store).await?;
+            *table_fragments = new_table_fragments;
+        }
+
+        Ok(())
+    }
+
     /// Start create a new `TableFragments` and insert it into meta store, currently the actors'
     /// state is `ActorState::Inactive`.
     pub async fn start_create_table_fragments(&self, table_fragment: TableFragments) -> Result<()> {
@@ -170,6 +211,64 @@ where
         }
     }
 
+    /// Replace the table fragments of a rescheduled materialized view with the rebuilt one, whose
+    /// actors are marked as `ActorState::Running`. Besides, the downstream actors of dependent
+    /// tables are updated from the stopped chain actors to the new ones.
+    pub async fn finish_reschedule_table_fragments(
+        &self,
+        table_fragments: &TableFragments,
+        dispatches: &HashMap<ActorId, Vec<ActorId>>,
+    ) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+        let old_table_fragments = map.get(&table_fragments.table_id()).ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_fragments.table_id()
+            )))
+        })?;
+        let old_chain_actor_ids = old_table_fragments.chain_actor_ids();
+
+        let mut transaction = Transaction::default();
+        let mut table_fragments = table_fragments.clone();
+        table_fragments.update_actors_state(ActorState::Running);
+        table_fragments.upsert_in_transaction(&mut transaction)?;
+
+        let dependent_table_ids = table_fragments.dependent_table_ids();
+        let mut dependent_tables = Vec::with_capacity(dependent_table_ids.len());
+        for dependent_table_id in dependent_table_ids {
+            let mut dependent_table = map
+                .get(&dependent_table_id)
+                .ok_or_else(|| {
+                    RwError::from(InternalError(format!(
+                        "table_fragment not exist: id={}",
+                        dependent_table_id
+                    )))
+                })?
+                .clone();
+            for fragment in dependent_table.fragments.values_mut() {
+                if fragment.fragment_type == FragmentType::Sink as i32 {
+                    for actor in &mut fragment.actors {
+                        let downstream_actor_id = &mut actor.dispatcher[0].downstream_actor_id;
+                        downstream_actor_id.retain(|x| !old_chain_actor_ids.contains(x));
+                        if let Some(downstream_actors) = dispatches.get(&actor.actor_id) {
+                            downstream_actor_id.extend(downstream_actors.iter().cloned());
+                        }
+                    }
+                }
+            }
+            dependent_table.upsert_in_transaction(&mut transaction)?;
+            dependent_tables.push(dependent_table);
+        }
+
+        self.meta_store.txn(transaction).await?;
+        map.insert(table_fragments.table_id(), table_fragments);
+        for dependent_table in dependent_tables {
+            map.insert(dependent_table.table_id(), dependent_table);
+        }
+
+        Ok(())
+    }
+
     /// Drop table fragments info and remove downstream actor infos in fragments from its dependent
     /// tables.
     pub async fn drop_table_fragments(&self, table_id: &TableId) -> Result<()> {
@@ -220,6 +319,13 @@ where
         }
     }
 
+    /// Returns the time-to-live of the states of all actors, for compactions to drop expired keys
+    /// with.
+    pub async fn state_ttls(&self) -> Vec<StateTtl> {
+        let map = &self.core.read().await.table_fragments;
+        map.values().flat_map(TableFragments::state_ttls).collect()
+    }
+
     /// Used in [`crate::barrier::GlobalBarrierManager`]
     pub async fn load_all_actors(&self, with_creating_table: Option<TableId>) -> ActorInfos {
         let mut actor_maps = HashMap::new();
@@ -287,6 +393,63 @@ where
             .collect::<HashSet<_>>()
     }
 
+    /// Resolves the tables that `actor_ids` belong to, together with all tables connected to them
+    /// by mview-on-mview dependencies, since the actors of them are linked by dispatchers. Returns
+    /// `None` if any actor is not found, or any of these tables is still being created.
+    pub async fn connected_table_ids(
+        &self,
+        actor_ids: &HashSet<ActorId>,
+    ) -> Option<HashSet<TableId>> {
+        let map = &self.core.read().await.table_fragments;
+
+        let mut table_ids = HashSet::new();
+        let mut found_actor_count = 0;
+        for table_fragments in map.values() {
+            let count = table_fragments
+                .actor_ids()
+                .iter()
+                .filter(|actor_id| actor_ids.contains(actor_id))
+                .count();
+            if count > 0 {
+                table_ids.insert(table_fragments.table_id());
+                found_actor_count += count;
+            }
+        }
+        if found_actor_count != actor_ids.len() {
+            return None;
+        }
+
+        // Upstream table => downstream tables, and vice versa.
+        let mut connections: HashMap<TableId, Vec<TableId>> = HashMap::new();
+        for table_fragments in map.values() {
+            let table_id = table_fragments.table_id();
+            for dependent_table_id in table_fragments.dependent_table_ids() {
+                connections
+                    .entry(table_id)
+                    .or_default()
+                    .push(dependent_table_id);
+                connections
+                    .entry(dependent_table_id)
+                    .or_default()
+                    .push(table_id);
+            }
+        }
+
+        let mut queue = table_ids.iter().copied().collect::<VecDeque<_>>();
+        while let Some(table_id) = queue.pop_front() {
+            for &connected in connections.get(&table_id).into_iter().flatten() {
+                if table_ids.insert(connected) {
+                    queue.push_back(connected);
+                }
+            }
+        }
+
+        table_ids
+            .iter()
+            .all(|table_id| map.get(table_id).map_or(false, |tf| tf.is_created()))
+            .then(|| table_ids)
+    }
+
     pub async fn table_node_actors(
         &self,
         table_id: &TableId,
@@ -15,6 +15,7 @@
 mod fragmenter;
 mod graph;
 mod meta;
+mod reschedule;
 mod rewrite;
 mod scheduler;
 mod source_manager;
@@ -25,6 +26,7 @@ mod test_fragmenter;
 
 pub use fragmenter::*;
 pub use meta::*;
+pub use reschedule::*;
 pub use scheduler::*;
 pub use source_manager::*;
 pub use stream_manager::*;
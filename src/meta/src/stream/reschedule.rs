@@ -0,0 +1,490 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+
+use itertools::Itertools;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::hash::VIRTUAL_KEY_COUNT;
+use risingwave_pb::common::ParallelUnit;
+use risingwave_pb::meta::table_fragments::fragment::{FragmentDistributionType, FragmentType};
+use risingwave_pb::meta::table_fragments::Fragment;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_pb::stream_plan::{ActorMapping, Dispatcher, DispatcherType, StreamNode};
+
+use crate::cluster::ParallelUnitId;
+use crate::model::{ActorId, FragmentId, TableFragments};
+
+/// Returns the number of actors of `fragment` after rescheduling. Singleton fragments always have
+/// one actor, while hash distributed ones have `parallelism` actors if specified.
+fn rescheduled_parallelism(fragment: &Fragment, parallelism: Option<usize>) -> usize {
+    if fragment.distribution_type == FragmentDistributionType::Single as i32 {
+        1
+    } else {
+        parallelism.unwrap_or(fragment.actors.len())
+    }
+}
+
+/// Returns the number of actors to be generated for rescheduling `table_fragments`.
+pub fn rescheduled_actor_count(
+    table_fragments: &TableFragments,
+    parallelism: Option<usize>,
+) -> usize {
+    table_fragments
+        .fragments
+        .values()
+        .map(|fragment| rescheduled_parallelism(fragment, parallelism))
+        .sum()
+}
+
+/// Checks whether the actors in `stream_node` can be rescheduled. The states of the source
+/// executors, the append-only top-n executors and the stateful executors without state tables are
+/// owned by actors instead of virtual nodes, so they can't be handed off to other actors.
+fn check_reschedulable(stream_node: &StreamNode) -> Result<()> {
+    let state_table_id = match stream_node.get_node()? {
+        Node::SourceNode(_) => {
+            return Err(RwError::from(InternalError(
+                "rescheduling materialized view with source is not supported".to_string(),
+            )))
+        }
+        Node::AppendOnlyTopNNode(_) => {
+            return Err(RwError::from(InternalError(
+                "rescheduling materialized view with append-only top-n is not supported"
+                    .to_string(),
+            )))
+        }
+        Node::HashAggNode(node) => Some(node.table_id),
+        Node::GlobalSimpleAggNode(node) => Some(node.table_id),
+        Node::TopNNode(node) => Some(node.table_id),
+        _ => None,
+    };
+    if state_table_id == Some(0) {
+        return Err(RwError::from(InternalError(format!(
+            "rescheduling materialized view with states owned by actors is not supported: {}",
+            stream_node.identity
+        ))));
+    }
+    stream_node.input.iter().try_for_each(check_reschedulable)
+}
+
+/// Assigns contiguous ranges of virtual nodes to the given actors.
+fn build_vnode_mapping(actor_ids: &[ActorId]) -> Vec<ActorId> {
+    (0..VIRTUAL_KEY_COUNT)
+        .map(|vnode| actor_ids[vnode * actor_ids.len() / VIRTUAL_KEY_COUNT])
+        .collect()
+}
+
+/// Returns the mapping from virtual nodes to the parallel units of the rescheduled sink actors of
+/// `table_fragments`, or `None` if the sink fragment is a singleton.
+pub fn rescheduled_hash_mapping(
+    table_fragments: &TableFragments,
+    actor_locations: &BTreeMap<ActorId, ParallelUnit>,
+) -> Option<Vec<ParallelUnitId>> {
+    let sink_fragment = table_fragments.fragments.values().find(|fragment| {
+        fragment.fragment_type == FragmentType::Sink as i32
+            && fragment.distribution_type == FragmentDistributionType::Hash as i32
+    })?;
+    let actor_ids = sink_fragment
+        .actors
+        .iter()
+        .map(|actor| actor.actor_id)
+        .collect_vec();
+    Some(
+        build_vnode_mapping(&actor_ids)
+            .into_iter()
+            .map(|actor_id| actor_locations[&actor_id].id)
+            .collect(),
+    )
+}
+
+/// Context for rewiring the actors of a materialized view to their new ids.
+struct RescheduleContext<'a> {
+    /// The fragment of each old actor.
+    actor_fragments: HashMap<ActorId, FragmentId>,
+
+    /// The new actor ids of each fragment.
+    fragment_actors: BTreeMap<FragmentId, Vec<ActorId>>,
+
+    table_fragments: &'a TableFragments,
+}
+
+impl RescheduleContext<'_> {
+    /// Returns the new actors connected with the `idx`-th new actor of `fragment_id` by an
+    /// exchange of `dispatcher_type`, in the fragment of `old_actor_id`. Returns `None` if the old
+    /// actor is not in this materialized view.
+    fn resolve_actors(
+        &self,
+        old_actor_id: ActorId,
+        dispatcher_type: DispatcherType,
+        fragment_id: FragmentId,
+        idx: usize,
+    ) -> Result<Option<Vec<ActorId>>> {
+        let other_fragment_id = match self.actor_fragments.get(&old_actor_id) {
+            Some(fragment_id) => fragment_id,
+            None => return Ok(None),
+        };
+        let other_actors = &self.fragment_actors[other_fragment_id];
+        if dispatcher_type == DispatcherType::NoShuffle {
+            if other_actors.len() != self.fragment_actors[&fragment_id].len() {
+                return Err(RwError::from(InternalError(format!(
+                    "fragments {} and {} connected without shuffle must have the same parallelism",
+                    fragment_id, other_fragment_id
+                ))));
+            }
+            Ok(Some(vec![other_actors[idx]]))
+        } else {
+            Ok(Some(other_actors.clone()))
+        }
+    }
+
+    /// Returns the dispatcher type of the fragment of `actor_id`.
+    fn dispatcher_type_of(&self, actor_id: ActorId) -> Result<DispatcherType> {
+        let fragment = &self.table_fragments.fragments[&self.actor_fragments[&actor_id]];
+        Ok(fragment.actors[0].dispatcher[0].get_type()?)
+    }
+
+    /// Rewrites the upstream actors of the merge nodes in `stream_node`, and collects them into
+    /// `upstream_actor_ids`. Merge nodes from other materialized views are left untouched.
+    fn rewire_merge_nodes(
+        &self,
+        stream_node: &mut StreamNode,
+        fragment_id: FragmentId,
+        idx: usize,
+        upstream_actor_ids: &mut Vec<ActorId>,
+    ) -> Result<()> {
+        if let Some(Node::MergeNode(merge_node)) = stream_node.node.as_mut() {
+            let old_upstream = merge_node.upstream_actor_id.first().copied();
+            if let Some(old_upstream) =
+                old_upstream.filter(|actor_id| self.actor_fragments.contains_key(actor_id))
+            {
+                let dispatcher_type = self.dispatcher_type_of(old_upstream)?;
+                merge_node.upstream_actor_id = self
+                    .resolve_actors(old_upstream, dispatcher_type, fragment_id, idx)?
+                    .unwrap();
+            }
+            upstream_actor_ids.extend(merge_node.upstream_actor_id.iter().copied());
+        }
+
+        for input in &mut stream_node.input {
+            self.rewire_merge_nodes(input, fragment_id, idx, upstream_actor_ids)?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the downstream actors of `dispatcher`, and reassigns the virtual nodes among them
+    /// for hash dispatchers.
+    fn rewire_dispatcher(
+        &self,
+        dispatcher: &Dispatcher,
+        fragment_id: FragmentId,
+        idx: usize,
+    ) -> Result<Dispatcher> {
+        let mut dispatcher = dispatcher.clone();
+        let old_downstream = match dispatcher.downstream_actor_id.first() {
+            Some(&actor_id) => actor_id,
+            None => return Ok(dispatcher),
+        };
+        let downstream_actors = self
+            .resolve_actors(old_downstream, dispatcher.get_type()?, fragment_id, idx)?
+            .ok_or_else(|| {
+                RwError::from(InternalError(format!(
+                    "materialized view {} has dependent materialized views",
+                    self.table_fragments.table_id()
+                )))
+            })?;
+        if dispatcher.get_type()? == DispatcherType::Hash {
+            dispatcher.hash_mapping = Some(ActorMapping {
+                hash_mapping: build_vnode_mapping(&downstream_actors),
+            });
+        }
+        dispatcher.downstream_actor_id = downstream_actors;
+
+        Ok(dispatcher)
+    }
+}
+
+/// Rebuilds the actors of `table_fragments` with new actor ids starting from `next_actor_id`, so
+/// that they can be built while the old actors are still running. The exchanges inside the
+/// materialized view are rewired to the new actors, and the virtual nodes are reassigned among the
+/// downstream actors of hash dispatchers by contiguous ranges.
+///
+/// The returned table fragments have no actor status, which should be set after scheduling.
+pub fn rebuild_table_fragments(
+    table_fragments: &TableFragments,
+    parallelism: Option<usize>,
+    next_actor_id: ActorId,
+) -> Result<TableFragments> {
+    for actor in table_fragments.actors() {
+        check_reschedulable(actor.get_nodes()?)?;
+    }
+
+    let mut actor_fragments = HashMap::new();
+    let mut fragment_actors = BTreeMap::new();
+    let mut next_actor_id = next_actor_id;
+    for (&fragment_id, fragment) in &table_fragments.fragments {
+        for actor in &fragment.actors {
+            actor_fragments.insert(actor.actor_id, fragment_id);
+        }
+        let parallelism = rescheduled_parallelism(fragment, parallelism) as ActorId;
+        fragment_actors.insert(
+            fragment_id,
+            (next_actor_id..next_actor_id + parallelism).collect(),
+        );
+        next_actor_id += parallelism;
+    }
+
+    let ctx = RescheduleContext {
+        actor_fragments,
+        fragment_actors,
+        table_fragments,
+    };
+
+    let mut fragments = BTreeMap::new();
+    for (&fragment_id, fragment) in &table_fragments.fragments {
+        let template = &fragment.actors[0];
+        let actors = ctx.fragment_actors[&fragment_id]
+            .iter()
+            .enumerate()
+            .map(|(idx, &actor_id)| {
+                let mut nodes = template.get_nodes()?.clone();
+                let mut upstream_actor_id = vec![];
+                ctx.rewire_merge_nodes(&mut nodes, fragment_id, idx, &mut upstream_actor_id)?;
+                let dispatcher = template
+                    .dispatcher
+                    .iter()
+                    .map(|dispatcher| ctx.rewire_dispatcher(dispatcher, fragment_id, idx))
+                    .collect::<Result<Vec<_>>>()?;
+
+                let mut actor = template.clone();
+                actor.actor_id = actor_id;
+                actor.nodes = Some(nodes);
+                actor.dispatcher = dispatcher;
+                actor.upstream_actor_id = upstream_actor_id;
+                Ok::<_, RwError>(actor)
+            })
+            .collect::<Result<_>>()?;
+
+        fragments.insert(
+            fragment_id,
+            Fragment {
+                actors,
+                ..fragment.clone()
+            },
+        );
+    }
+
+    let mut new_table_fragments = TableFragments::new(table_fragments.table_id(), fragments);
+    new_table_fragments.set_placement(table_fragments.placement().cloned());
+    Ok(new_table_fragments)
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::TableId;
+    use risingwave_pb::catalog::Placement;
+    use risingwave_pb::stream_plan::{
+        HashAggNode, MaterializeNode, MergeNode, ProjectNode, SourceNode, StreamActor,
+    };
+
+    use super::*;
+
+    fn make_actor(
+        actor_id: ActorId,
+        node: Node,
+        upstream_actor_id: Vec<ActorId>,
+        dispatcher: Dispatcher,
+    ) -> StreamActor {
+        let merge_node = StreamNode {
+            node: Some(Node::MergeNode(MergeNode {
+                upstream_actor_id: upstream_actor_id.clone(),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        StreamActor {
+            actor_id,
+            nodes: Some(StreamNode {
+                node: Some(node),
+                input: vec![merge_node],
+                ..Default::default()
+            }),
+            dispatcher: vec![dispatcher],
+            upstream_actor_id,
+            ..Default::default()
+        }
+    }
+
+    /// A materialized view with a singleton fragment `2` reading from actor `100` of another
+    /// materialized view, and a hash distributed sink fragment `1` with 2 actors.
+    fn make_table_fragments(sink_downstream: Vec<ActorId>) -> TableFragments {
+        let sink_actors = [1, 2]
+            .into_iter()
+            .map(|actor_id| {
+                make_actor(
+                    actor_id,
+                    Node::MaterializeNode(MaterializeNode::default()),
+                    vec![3],
+                    Dispatcher {
+                        r#type: DispatcherType::Broadcast as i32,
+                        downstream_actor_id: sink_downstream.clone(),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect_vec();
+        let project_actor = make_actor(
+            3,
+            Node::ProjectNode(ProjectNode::default()),
+            vec![100],
+            Dispatcher {
+                r#type: DispatcherType::Hash as i32,
+                downstream_actor_id: vec![1, 2],
+                hash_mapping: Some(ActorMapping {
+                    hash_mapping: build_vnode_mapping(&[1, 2]),
+                }),
+                ..Default::default()
+            },
+        );
+
+        let fragments = BTreeMap::from([
+            (
+                1,
+                Fragment {
+                    fragment_id: 1,
+                    fragment_type: FragmentType::Sink as i32,
+                    distribution_type: FragmentDistributionType::Hash as i32,
+                    actors: sink_actors,
+                },
+            ),
+            (
+                2,
+                Fragment {
+                    fragment_id: 2,
+                    fragment_type: FragmentType::Others as i32,
+                    distribution_type: FragmentDistributionType::Single as i32,
+                    actors: vec![project_actor],
+                },
+            ),
+        ]);
+        TableFragments::new(TableId::new(1), fragments)
+    }
+
+    fn merge_upstreams(actor: &StreamActor) -> Vec<ActorId> {
+        match actor.get_nodes().unwrap().input[0].get_node().unwrap() {
+            Node::MergeNode(merge_node) => merge_node.upstream_actor_id.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_rebuild_table_fragments() -> Result<()> {
+        let table_fragments = make_table_fragments(vec![]);
+        assert_eq!(rescheduled_actor_count(&table_fragments, Some(4)), 5);
+
+        let new_table_fragments = rebuild_table_fragments(&table_fragments, Some(4), 10)?;
+        assert_eq!(new_table_fragments.actor_ids(), vec![10, 11, 12, 13, 14]);
+
+        // The sink actors merge from the new project actor.
+        for actor in &new_table_fragments.fragments[&1].actors {
+            assert_eq!(merge_upstreams(actor), vec![14]);
+            assert_eq!(actor.upstream_actor_id, vec![14]);
+        }
+
+        // The project actor still merges from the upstream materialized view, and dispatches to
+        // the new sink actors by ranges of virtual nodes.
+        let project_actor = &new_table_fragments.fragments[&2].actors[0];
+        assert_eq!(merge_upstreams(project_actor), vec![100]);
+        let dispatcher = &project_actor.dispatcher[0];
+        assert_eq!(dispatcher.downstream_actor_id, vec![10, 11, 12, 13]);
+        let hash_mapping = &dispatcher.hash_mapping.as_ref().unwrap().hash_mapping;
+        assert_eq!(hash_mapping.len(), VIRTUAL_KEY_COUNT);
+        for (idx, actor_id) in (10..14).enumerate() {
+            let vnodes = (VIRTUAL_KEY_COUNT * idx / 4)..(VIRTUAL_KEY_COUNT * (idx + 1) / 4);
+            assert!(hash_mapping[vnodes].iter().all(|id| *id == actor_id));
+        }
+
+        // Keep the parallelism if not specified.
+        let new_table_fragments = rebuild_table_fragments(&table_fragments, None, 10)?;
+        assert_eq!(new_table_fragments.actor_ids(), vec![10, 11, 12]);
+
+        // Keep the placement.
+        let mut table_fragments = table_fragments;
+        let placement = Placement {
+            labels: HashMap::from([("zone".to_string(), "a".to_string())]),
+            anti_affinity_table_ids: vec![2],
+        };
+        table_fragments.set_placement(Some(placement.clone()));
+        let new_table_fragments = rebuild_table_fragments(&table_fragments, None, 10)?;
+        assert_eq!(new_table_fragments.placement(), Some(&placement));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rescheduled_hash_mapping() -> Result<()> {
+        let table_fragments = make_table_fragments(vec![]);
+        let new_table_fragments = rebuild_table_fragments(&table_fragments, Some(4), 10)?;
+        let actor_locations = (10..15)
+            .map(|actor_id| {
+                (
+                    actor_id,
+                    ParallelUnit {
+                        id: actor_id % 2,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        // The virtual nodes of the sink actors are mapped to their parallel units.
+        let hash_mapping =
+            rescheduled_hash_mapping(&new_table_fragments, &actor_locations).unwrap();
+        assert_eq!(hash_mapping.len(), VIRTUAL_KEY_COUNT);
+        for idx in 0..4 {
+            let vnodes = (VIRTUAL_KEY_COUNT * idx / 4)..(VIRTUAL_KEY_COUNT * (idx + 1) / 4);
+            assert!(hash_mapping[vnodes].iter().all(|id| *id == idx as u32 % 2));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rebuild_table_fragments_unsupported() {
+        // Materialized view with dependent materialized views.
+        let table_fragments = make_table_fragments(vec![200]);
+        assert!(rebuild_table_fragments(&table_fragments, Some(4), 10).is_err());
+
+        // Materialized view with source.
+        let mut table_fragments = make_table_fragments(vec![]);
+        let project_actor = &mut table_fragments.fragments.get_mut(&2).unwrap().actors[0];
+        project_actor.nodes.as_mut().unwrap().node = Some(Node::SourceNode(SourceNode::default()));
+        assert!(rebuild_table_fragments(&table_fragments, Some(4), 10).is_err());
+
+        // Materialized view with hash aggregation, whose states are owned by actors unless a state
+        // table is allocated.
+        let mut table_fragments = make_table_fragments(vec![]);
+        let project_actor = &mut table_fragments.fragments.get_mut(&2).unwrap().actors[0];
+        project_actor.nodes.as_mut().unwrap().node =
+            Some(Node::HashAggNode(HashAggNode::default()));
+        assert!(rebuild_table_fragments(&table_fragments, Some(4), 10).is_err());
+        let project_actor = &mut table_fragments.fragments.get_mut(&2).unwrap().actors[0];
+        project_actor.nodes.as_mut().unwrap().node = Some(Node::HashAggNode(HashAggNode {
+            table_id: 1,
+            ..Default::default()
+        }));
+        assert!(rebuild_table_fragments(&table_fragments, Some(4), 10).is_ok());
+    }
+}
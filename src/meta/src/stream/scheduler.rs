@@ -12,11 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
+use risingwave_pb::catalog::Placement;
 use risingwave_pb::common::{ActorInfo, ParallelUnit, ParallelUnitType};
 use risingwave_pb::meta::table_fragments::fragment::FragmentDistributionType;
 use risingwave_pb::meta::table_fragments::Fragment;
@@ -80,6 +81,26 @@ impl ScheduledLocations {
             .collect::<HashMap<_, _>>()
     }
 
+    /// [`Self::placement_workers`] returns the workers having all the labels of `placement`,
+    /// excluding `anti_affinity_workers`.
+    pub fn placement_workers(
+        &self,
+        placement: &Placement,
+        anti_affinity_workers: &HashSet<WorkerId>,
+    ) -> HashSet<WorkerId> {
+        self.node_locations
+            .values()
+            .filter(|node| !anti_affinity_workers.contains(&node.id))
+            .filter(|node| {
+                placement
+                    .labels
+                    .iter()
+                    .all(|(key, value)| node.labels.get(key) == Some(value))
+            })
+            .map(|node| node.id)
+            .collect()
+    }
+
     /// [`Self::actor_infos`] returns the `ActorInfo` slice.
     pub fn actor_infos(&self) -> Vec<ActorInfo> {
         self.actor_locations
@@ -115,6 +136,29 @@ where
         &self,
         fragment: Fragment,
         locations: &mut ScheduledLocations,
+    ) -> Result<()> {
+        self.schedule_inner(fragment, locations, |_| true).await
+    }
+
+    /// [`Self::schedule_on_workers`] schedules input fragments like [`Self::schedule`], but only to
+    /// the parallel units on the given workers.
+    pub async fn schedule_on_workers(
+        &self,
+        fragment: Fragment,
+        locations: &mut ScheduledLocations,
+        worker_ids: &HashSet<WorkerId>,
+    ) -> Result<()> {
+        self.schedule_inner(fragment, locations, |parallel_unit| {
+            worker_ids.contains(&parallel_unit.worker_node_id)
+        })
+        .await
+    }
+
+    async fn schedule_inner(
+        &self,
+        fragment: Fragment,
+        locations: &mut ScheduledLocations,
+        filter: impl Fn(&ParallelUnit) -> bool,
     ) -> Result<()> {
         if fragment.actors.is_empty() {
             return Err(InternalError("fragment has no actor".to_string()).into());
@@ -125,24 +169,31 @@ where
             let single_parallel_units = self
                 .cluster_manager
                 .list_parallel_units(Some(ParallelUnitType::Single))
-                .await;
-            if let Ok(single_idx) =
-                self.single_rr
-                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |idx| {
-                        Some((idx + 1) % single_parallel_units.len())
-                    })
-            {
-                locations.actor_locations.insert(
-                    fragment.actors[0].actor_id,
-                    single_parallel_units[single_idx].clone(),
-                );
+                .await
+                .into_iter()
+                .filter(&filter)
+                .collect::<Vec<_>>();
+            if single_parallel_units.is_empty() {
+                return Err(InternalError("no available parallel unit".to_string()).into());
             }
+            let single_idx =
+                self.single_rr.fetch_add(1, Ordering::SeqCst) % single_parallel_units.len();
+            locations.actor_locations.insert(
+                fragment.actors[0].actor_id,
+                single_parallel_units[single_idx].clone(),
+            );
         } else {
             // normal fragment
             let parallel_units = self
                 .cluster_manager
                 .list_parallel_units(Some(ParallelUnitType::Hash))
-                .await;
+                .await
+                .into_iter()
+                .filter(&filter)
+                .collect::<Vec<_>>();
+            if parallel_units.is_empty() {
+                return Err(InternalError("no available parallel unit".to_string()).into());
+            }
             fragment.actors.iter().enumerate().for_each(|(idx, actor)| {
                 locations.actor_locations.insert(
                     actor.actor_id,
@@ -182,7 +233,7 @@ mod test {
                 port: i as i32,
             };
             cluster_manager
-                .add_worker_node(host.clone(), WorkerType::ComputeNode)
+                .add_worker_node(host.clone(), WorkerType::ComputeNode, Default::default())
                 .await?;
             cluster_manager.activate_worker_node(host).await?;
         }
@@ -204,6 +255,7 @@ mod test {
                         dispatcher: vec![],
                         upstream_actor_id: vec![],
                         same_worker_node_as_upstream: false,
+                        state_ttl_ms: 0,
                     }],
                 };
                 actor_id += 1;
@@ -221,6 +273,7 @@ mod test {
                         dispatcher: vec![],
                         upstream_actor_id: vec![],
                         same_worker_node_as_upstream: false,
+                        state_ttl_ms: 0,
                     })
                     .collect_vec();
                 actor_id += node_count * 7;
@@ -267,4 +320,94 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_schedule_with_placement() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager =
+            Arc::new(ClusterManager::new(env.clone(), Duration::from_secs(3600)).await?);
+
+        for i in 0..4 {
+            let host = HostAddress {
+                host: "127.0.0.1".to_string(),
+                port: i as i32,
+            };
+            let zone = if i % 2 == 0 { "a" } else { "b" };
+            let labels = HashMap::from([("zone".to_string(), zone.to_string())]);
+            cluster_manager
+                .add_worker_node(host.clone(), WorkerType::ComputeNode, labels)
+                .await?;
+            cluster_manager.activate_worker_node(host).await?;
+        }
+
+        let mut locations = ScheduledLocations::new();
+        locations.node_locations = cluster_manager
+            .list_worker_node(WorkerType::ComputeNode, None)
+            .await
+            .into_iter()
+            .map(|node| (node.id, node))
+            .collect();
+        let zone_workers = |zone: &str| {
+            locations
+                .node_locations
+                .values()
+                .filter(|node| node.labels["zone"] == zone)
+                .map(|node| node.id)
+                .collect::<HashSet<_>>()
+        };
+        let zone_a_workers = zone_workers("a");
+        assert_eq!(zone_a_workers.len(), 2);
+
+        let placement = Placement {
+            labels: HashMap::from([("zone".to_string(), "a".to_string())]),
+            anti_affinity_table_ids: vec![],
+        };
+        assert_eq!(
+            locations.placement_workers(&placement, &HashSet::new()),
+            zone_a_workers
+        );
+        let excluded = zone_a_workers.iter().take(1).copied().collect();
+        let worker_ids = locations.placement_workers(&placement, &excluded);
+        assert_eq!(worker_ids.len(), 1);
+        assert!(worker_ids.is_disjoint(&excluded));
+        assert!(locations
+            .placement_workers(&Placement::default(), &excluded)
+            .is_disjoint(&excluded));
+        let unknown_zone = Placement {
+            labels: HashMap::from([("zone".to_string(), "c".to_string())]),
+            anti_affinity_table_ids: vec![],
+        };
+        assert!(locations
+            .placement_workers(&unknown_zone, &HashSet::new())
+            .is_empty());
+
+        // All the actors are scheduled to the workers satisfying the placement.
+        let fragment = Fragment {
+            fragment_id: 1,
+            fragment_type: 0,
+            distribution_type: FragmentDistributionType::Hash as i32,
+            actors: (1..17)
+                .map(|actor_id| StreamActor {
+                    actor_id,
+                    fragment_id: 1,
+                    nodes: None,
+                    dispatcher: vec![],
+                    upstream_actor_id: vec![],
+                    same_worker_node_as_upstream: false,
+                    state_ttl_ms: 0,
+                })
+                .collect_vec(),
+        };
+        let scheduler = Scheduler::new(cluster_manager);
+        scheduler
+            .schedule_on_workers(fragment, &mut locations, &worker_ids)
+            .await?;
+        assert_eq!(locations.actor_locations.len(), 16);
+        assert!(locations
+            .actor_locations
+            .values()
+            .all(|parallel_unit| worker_ids.contains(&parallel_unit.worker_node_id)));
+
+        Ok(())
+    }
 }
@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::future::try_join_all;
 use itertools::Itertools;
@@ -29,19 +30,24 @@ use risingwave_pb::stream_service::{
     DropSourceRequest as ComputeNodeDropSourceRequest,
 };
 
-use crate::barrier::BarrierManagerRef;
+use crate::barrier::{BarrierManagerRef, Command};
 use crate::cluster::ClusterManagerRef;
 use crate::manager::{CatalogManagerRef, MetaSrvEnv, SourceId, StreamClient};
-use crate::model::ActorId;
+use crate::model::{ActorId, TableFragments};
 use crate::storage::MetaStore;
+use crate::stream::FragmentManagerRef;
 
 pub type SourceManagerRef<S> = Arc<SourceManager<S>>;
 
-#[allow(dead_code)]
+/// Interval to discover new splits of stream sources.
+const SPLIT_DISCOVERY_INTERVAL: Duration = Duration::from_secs(10);
+
 pub struct SourceManager<S: MetaStore> {
     env: MetaSrvEnv<S>,
     cluster_manager: ClusterManagerRef<S>,
+    barrier_manager: BarrierManagerRef<S>,
     catalog_manager: CatalogManagerRef<S>,
+    fragment_manager: FragmentManagerRef<S>,
 }
 
 impl<S> SourceManager<S>
@@ -51,13 +57,26 @@ where
     pub async fn new(
         env: MetaSrvEnv<S>,
         cluster_manager: ClusterManagerRef<S>,
-        _barrier_manager: BarrierManagerRef<S>,
+        barrier_manager: BarrierManagerRef<S>,
         catalog_manager: CatalogManagerRef<S>,
+        fragment_manager: FragmentManagerRef<S>,
     ) -> Result<Self> {
         Ok(Self {
             env,
             cluster_manager,
+            barrier_manager,
             catalog_manager,
+            fragment_manager,
+        })
+    }
+
+    async fn get_source(&self, source_id: SourceId) -> Result<Source> {
+        let catalog_guard = self.catalog_manager.get_catalog_core_guard().await;
+        catalog_guard.get_source(source_id).await?.ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "could not find source catalog for {}",
+                source_id
+            )))
         })
     }
 
@@ -94,13 +113,7 @@ where
                 // we are creating materialized source
                 self.fetch_splits_for_source(affiliated_source).await
             } else {
-                let catalog_guard = self.catalog_manager.get_catalog_core_guard().await;
-                let source = catalog_guard.get_source(*source_id).await?.ok_or_else(|| {
-                        RwError::from(InternalError(format!(
-                            "could not find source catalog for {}",
-                            source_id
-                        )))
-                    })?;
+                let source = self.get_source(*source_id).await?;
                 self.fetch_splits_for_source(&source).await
             }
         }))
@@ -180,8 +193,154 @@ where
         Ok(())
     }
 
+    /// Assigns the splits not assigned to any actor in `actor_splits` yet, each to the actor with
+    /// the fewest splits. Returns the new splits of the actors changed.
+    fn assign_new_splits(
+        splits: &[SplitImpl],
+        mut actor_splits: BTreeMap<ActorId, Vec<SplitImpl>>,
+    ) -> HashMap<ActorId, Vec<SplitImpl>> {
+        let assigned_split_ids: HashSet<_> =
+            actor_splits.values().flatten().map(|s| s.id()).collect();
+        let mut changed_actors = HashSet::new();
+        for split in splits {
+            if assigned_split_ids.contains(&split.id()) {
+                continue;
+            }
+            let (&actor_id, assigned_splits) = actor_splits
+                .iter_mut()
+                .min_by_key(|(_, assigned_splits)| assigned_splits.len())
+                .unwrap();
+            assigned_splits.push(split.clone());
+            changed_actors.insert(actor_id);
+        }
+
+        actor_splits
+            .into_iter()
+            .filter(|(actor_id, _)| changed_actors.contains(actor_id))
+            .collect()
+    }
+
+    /// Lists the splits of all stream sources, and assigns new splits to the source actors of the
+    /// created materialized views with a [`Command::SourceSplitAssignment`] barrier.
+    async fn discover_splits(&self) -> Result<()> {
+        // Source actors of each source, grouped by fragment: `source_id` => [`actor_id` =>
+        // splits].
+        let mut source_actor_groups: HashMap<SourceId, Vec<BTreeMap<ActorId, Vec<SplitImpl>>>> =
+            HashMap::new();
+        for table_fragments in self.fragment_manager.list_table_fragments().await? {
+            // Splits of creating materialized views are assigned in `schedule_split_for_actors`.
+            if !table_fragments.is_created() {
+                continue;
+            }
+            for fragment in table_fragments.fragments() {
+                let mut actor_groups: HashMap<SourceId, BTreeMap<ActorId, Vec<SplitImpl>>> =
+                    HashMap::new();
+                for actor in &fragment.actors {
+                    let stream_node = actor.nodes.as_ref().unwrap();
+                    if let Some(source_id) = TableFragments::fetch_stream_source_id(stream_node) {
+                        actor_groups.entry(source_id).or_default().insert(
+                            actor.actor_id,
+                            TableFragments::fetch_stream_source_splits(stream_node)?,
+                        );
+                    }
+                }
+                for (source_id, actor_group) in actor_groups {
+                    source_actor_groups
+                        .entry(source_id)
+                        .or_default()
+                        .push(actor_group);
+                }
+            }
+        }
+
+        let mut assignment = HashMap::new();
+        for (source_id, actor_groups) in source_actor_groups {
+            let splits = match self.get_source(source_id).await {
+                Ok(source) => self.fetch_splits_for_source(&source).await,
+                Err(e) => Err(e),
+            };
+            let splits = match splits {
+                Ok(splits) => splits,
+                Err(e) => {
+                    tracing::warn!("failed to list splits of source {}: {}", source_id, e);
+                    continue;
+                }
+            };
+            for actor_splits in actor_groups {
+                assignment.extend(Self::assign_new_splits(&splits, actor_splits));
+            }
+        }
+
+        if assignment.is_empty() {
+            return Ok(());
+        }
+        tracing::info!("assigning new splits to source actors: {:?}", assignment);
+        self.barrier_manager
+            .run_command(Command::SourceSplitAssignment(assignment))
+            .await
+    }
+
+    /// Discovers new splits of stream sources periodically, e.g. new Kafka partitions, and assigns
+    /// them to the source actors.
     pub async fn run(&self) -> Result<()> {
-        // todo: in the future, split change will be pushed as a long running service
-        Ok(())
+        let mut ticker = tokio::time::interval(SPLIT_DISCOVERY_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.discover_splits().await {
+                tracing::warn!("failed to discover source splits: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_connector::kinesis::split::{KinesisOffset, KinesisSplit};
+
+    use super::*;
+    use crate::storage::MemStore;
+
+    fn new_split(shard_id: &str) -> SplitImpl {
+        SplitImpl::Kinesis(KinesisSplit::new(
+            shard_id.to_string(),
+            KinesisOffset::Earliest,
+            KinesisOffset::None,
+        ))
+    }
+
+    #[test]
+    fn test_assign_new_splits() {
+        let splits = (0..5)
+            .map(|i| new_split(&format!("shard-{}", i)))
+            .collect_vec();
+
+        // Initial assignment.
+        let actor_splits = BTreeMap::from([(1, vec![]), (2, vec![])]);
+        let assignment = SourceManager::<MemStore>::assign_new_splits(&splits[..3], actor_splits);
+        assert_eq!(
+            assignment,
+            HashMap::from([
+                (1, vec![splits[0].clone(), splits[2].clone()]),
+                (2, vec![splits[1].clone()]),
+            ])
+        );
+
+        // Only new splits are assigned, to the actor with fewer splits first.
+        let actor_splits = assignment.into_iter().collect();
+        let assignment = SourceManager::<MemStore>::assign_new_splits(&splits, actor_splits);
+        assert_eq!(
+            assignment,
+            HashMap::from([
+                (
+                    1,
+                    vec![splits[0].clone(), splits[2].clone(), splits[4].clone()]
+                ),
+                (2, vec![splits[1].clone(), splits[3].clone()]),
+            ])
+        );
+
+        // Nothing changes without new splits.
+        let actor_splits = assignment.into_iter().collect();
+        assert!(SourceManager::<MemStore>::assign_new_splits(&splits, actor_splits).is_empty());
     }
 }
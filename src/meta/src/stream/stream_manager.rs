@@ -12,35 +12,46 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use futures::future::join_all;
 use itertools::Itertools;
 use log::{debug, info};
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::ErrorCode::InternalError;
-use risingwave_common::error::{Result, ToRwResult};
-use risingwave_pb::catalog::Source;
-use risingwave_pb::common::{ActorInfo, WorkerType};
+use risingwave_common::error::{Result, RwError, ToRwResult};
+use risingwave_pb::catalog::{Placement, Source};
+use risingwave_pb::common::worker_node::State::Running;
+use risingwave_pb::common::{ActorInfo, WorkerNode, WorkerType};
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus};
-use risingwave_pb::stream_plan::stream_node::Node;
-use risingwave_pb::stream_plan::StreamSourceState;
+use risingwave_pb::meta::{
+    get_streaming_status_response as status, GetBarrierStatusResponse, GetStreamingStatusResponse,
+};
 use risingwave_pb::stream_service::{
-    BroadcastActorInfoTableRequest, BuildActorsRequest, HangingChannel, UpdateActorsRequest,
+    BroadcastActorInfoTableRequest, BuildActorsRequest, GetActorStatsRequest, HangingChannel,
+    UpdateActorsRequest,
 };
 use uuid::Uuid;
 
 use super::ScheduledLocations;
-use crate::barrier::{BarrierManagerRef, Command};
+use crate::barrier::{BarrierManagerRef, Command, InFlightBarrier};
 use crate::cluster::{ClusterManagerRef, WorkerId};
-use crate::manager::{MetaSrvEnv, StreamClientsRef};
+use crate::manager::{IdCategory, IdGeneratorManagerRef, MetaSrvEnv, StreamClientsRef};
 use crate::model::{ActorId, TableFragments};
 use crate::storage::MetaStore;
-use crate::stream::{FragmentManagerRef, Scheduler, SourceManagerRef};
+use crate::stream::{
+    rebuild_table_fragments, rescheduled_actor_count, rescheduled_hash_mapping, FragmentManagerRef,
+    Scheduler, SourceManagerRef,
+};
 
 pub type GlobalStreamManagerRef<S> = Arc<GlobalStreamManager<S>>;
 
+/// Timeout to fetch the actor stats from a compute node, after which the node is skipped, so that
+/// an unresponsive node doesn't block the streaming status.
+const GET_ACTOR_STATS_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// [`CreateMaterializedViewContext`] carries one-time infos.
 #[derive(Default)]
 pub struct CreateMaterializedViewContext {
@@ -52,6 +63,8 @@ pub struct CreateMaterializedViewContext {
     pub table_sink_map: HashMap<TableId, Vec<ActorId>>,
     /// Temporary source info used during `create_materialized_source`
     pub affiliated_source: Option<Source>,
+    /// Placement constraints of the actors of the materialized view.
+    pub placement: Option<Placement>,
 }
 
 /// `GlobalStreamManager` manages all the streams in the system.
@@ -73,6 +86,9 @@ pub struct GlobalStreamManager<S: MetaStore> {
 
     /// Clients to stream service on compute nodes
     clients: StreamClientsRef,
+
+    /// Generates ids of the rescheduled actors
+    id_gen_manager: IdGeneratorManagerRef<S>,
 }
 
 impl<S> GlobalStreamManager<S>
@@ -93,6 +109,7 @@ where
             cluster_manager,
             clients: env.stream_clients_ref(),
             source_manager,
+            id_gen_manager: env.id_gen_manager_ref(),
         })
     }
 
@@ -125,10 +142,24 @@ where
         let mut locations = ScheduledLocations::new();
         locations.node_locations = nodes.into_iter().map(|node| (node.id, node)).collect();
 
+        let placement_workers = match &ctx.placement {
+            Some(placement) => Some(self.placement_workers(&locations, placement).await?),
+            None => None,
+        };
+        table_fragments.set_placement(ctx.placement.clone());
         for fragment in table_fragments.fragments() {
-            self.scheduler
-                .schedule(fragment.clone(), &mut locations)
-                .await?;
+            match &placement_workers {
+                Some(worker_ids) => {
+                    self.scheduler
+                        .schedule_on_workers(fragment.clone(), &mut locations, worker_ids)
+                        .await?
+                }
+                None => {
+                    self.scheduler
+                        .schedule(fragment.clone(), &mut locations)
+                        .await?
+                }
+            }
         }
 
         let actor_info = locations
@@ -146,7 +177,6 @@ where
             .collect();
 
         table_fragments.set_actor_status(actor_info);
-        let mut actor_map = table_fragments.actor_map();
 
         let mut source_actors_group_by_fragment = HashMap::new();
         for fragment in table_fragments.fragments() {
@@ -176,38 +206,234 @@ where
             .await?;
 
         // patch source actors with splits
-        for (actor_id, actor) in &mut actor_map {
-            if let Some(splits) = split_assignment.get(actor_id) {
-                let mut node = actor.nodes.as_mut().unwrap();
-                while !node.input.is_empty() {
-                    node = node.input.first_mut().unwrap();
+        debug!("patching source actors with splits {:?}", split_assignment);
+        table_fragments.update_actor_splits(&split_assignment)?;
+        let dispatches = self
+            .build_actors(
+                &table_fragments,
+                &locations,
+                &ctx.dispatches,
+                &ctx.upstream_node_actors,
+            )
+            .await?;
+
+        // Add table fragments to meta store with state: `State::Creating`.
+        self.fragment_manager
+            .start_create_table_fragments(table_fragments.clone())
+            .await?;
+        self.barrier_manager
+            .run_command(Command::CreateMaterializedView {
+                table_fragments,
+                table_sink_map: ctx.table_sink_map,
+                dispatches,
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the running workers satisfying `placement`, i.e. having all the labels of it and
+    /// hosting no actors of its anti-affinity tables.
+    async fn placement_workers(
+        &self,
+        locations: &ScheduledLocations,
+        placement: &Placement,
+    ) -> Result<HashSet<WorkerId>> {
+        let mut anti_affinity_workers = HashSet::new();
+        for table_id in &placement.anti_affinity_table_ids {
+            let node_actors = self
+                .fragment_manager
+                .table_node_actors(&TableId::new(*table_id))
+                .await?;
+            anti_affinity_workers.extend(node_actors.into_keys());
+        }
+
+        let worker_ids = locations.placement_workers(placement, &anti_affinity_workers);
+        if worker_ids.is_empty() {
+            return Err(InternalError(format!(
+                "no available node satisfies the placement {:?}",
+                placement
+            ))
+            .into());
+        }
+        Ok(worker_ids)
+    }
+
+    /// Reschedule materialized view, it works as follows:
+    /// 1. rebuild the actors of the materialized view with new actor ids and `parallelism`.
+    /// 2. schedule the new actors to the given workers, or all workers if not specified.
+    /// 3. notify related nodes to update and build the new actors, like creating materialized
+    /// view.
+    /// 4. replace the old actors with the new ones in a barrier. Check
+    /// [`Command::RescheduleMaterializedView`] for details.
+    pub async fn reschedule_materialized_view(
+        &self,
+        table_id: &TableId,
+        parallelism: Option<usize>,
+        worker_ids: Vec<WorkerId>,
+    ) -> Result<()> {
+        let old_table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(table_id)
+            .await?;
+        if !old_table_fragments.is_created() {
+            return Err(InternalError(format!(
+                "materialized view {} is still being created",
+                table_id
+            ))
+            .into());
+        }
+        if parallelism == Some(0) {
+            return Err(InternalError("parallelism must be positive".to_string()).into());
+        }
+
+        let nodes = self
+            .cluster_manager
+            .list_worker_node(
+                WorkerType::ComputeNode,
+                Some(risingwave_pb::common::worker_node::State::Running),
+            )
+            .await;
+        let mut locations = ScheduledLocations::new();
+        locations.node_locations = nodes.into_iter().map(|node| (node.id, node)).collect();
+        let worker_ids = if worker_ids.is_empty() {
+            locations
+                .node_locations
+                .keys()
+                .copied()
+                .collect::<HashSet<_>>()
+        } else {
+            if let Some(worker_id) = worker_ids
+                .iter()
+                .find(|worker_id| !locations.node_locations.contains_key(worker_id))
+            {
+                return Err(InternalError(format!(
+                    "worker {} is not a running compute node",
+                    worker_id
+                ))
+                .into());
+            }
+            worker_ids.into_iter().collect()
+        };
+        // The placement of the materialized view still holds for the rescheduled actors.
+        let worker_ids = match old_table_fragments.placement() {
+            Some(placement) => {
+                let placement_workers = self.placement_workers(&locations, placement).await?;
+                let worker_ids = worker_ids
+                    .intersection(&placement_workers)
+                    .copied()
+                    .collect::<HashSet<_>>();
+                if worker_ids.is_empty() {
+                    return Err(InternalError(format!(
+                        "no given worker satisfies the placement {:?}",
+                        placement
+                    ))
+                    .into());
                 }
+                worker_ids
+            }
+            None => worker_ids,
+        };
 
-                if let Some(Node::SourceNode(s)) = node.node.as_mut() {
-                    log::debug!(
-                        "patching source node #{} with splits {:?}",
-                        actor_id,
-                        splits
-                    );
-
-                    if !splits.is_empty() {
-                        s.stream_source_state = Some(StreamSourceState {
-                            split_type: splits.first().unwrap().get_type(),
-                            stream_source_splits: splits
-                                .iter()
-                                .map(|split| split.to_string().unwrap().as_bytes().to_vec())
-                                .collect(),
-                        });
-                    }
+        let actor_count = rescheduled_actor_count(&old_table_fragments, parallelism);
+        let next_actor_id = self
+            .id_gen_manager
+            .generate_interval::<{ IdCategory::Actor }>(actor_count as i32)
+            .await? as ActorId;
+        let mut table_fragments =
+            rebuild_table_fragments(&old_table_fragments, parallelism, next_actor_id)?;
+
+        for fragment in table_fragments.fragments() {
+            self.scheduler
+                .schedule_on_workers(fragment.clone(), &mut locations, &worker_ids)
+                .await?;
+        }
+
+        let actor_info = locations
+            .actor_locations
+            .iter()
+            .map(|(&actor_id, parallel_unit)| {
+                (
+                    actor_id,
+                    ActorStatus {
+                        node_id: parallel_unit.worker_node_id,
+                        state: ActorState::Inactive as i32,
+                    },
+                )
+            })
+            .collect();
+        table_fragments.set_actor_status(actor_info);
+
+        // The new chain actors are added to the dispatchers of the upstream materialized views.
+        let actor_map = table_fragments.actor_map();
+        let mut dispatches: HashMap<ActorId, Vec<ActorId>> = HashMap::new();
+        for actor_id in table_fragments.chain_actor_ids() {
+            for up_id in &actor_map[&actor_id].upstream_actor_id {
+                if !actor_map.contains_key(up_id) {
+                    dispatches.entry(*up_id).or_default().push(actor_id);
                 }
             }
         }
 
+        let mut upstream_node_actors: HashMap<WorkerId, Vec<ActorId>> = HashMap::new();
+        for dependent_table_id in old_table_fragments.dependent_table_ids() {
+            let table_node_actors = self
+                .fragment_manager
+                .table_node_actors(&dependent_table_id)
+                .await?;
+            for (node_id, actor_ids) in table_node_actors {
+                upstream_node_actors.entry(node_id).or_default().extend(
+                    actor_ids
+                        .into_iter()
+                        .filter(|actor_id| dispatches.contains_key(actor_id)),
+                );
+            }
+        }
+        upstream_node_actors.retain(|_, actor_ids| !actor_ids.is_empty());
+
+        let dispatches = self
+            .build_actors(
+                &table_fragments,
+                &locations,
+                &dispatches,
+                &upstream_node_actors,
+            )
+            .await?;
+
+        let hash_mapping = rescheduled_hash_mapping(&table_fragments, &locations.actor_locations);
+        self.barrier_manager
+            .run_command(Command::RescheduleMaterializedView {
+                old_table_fragments,
+                new_table_fragments: table_fragments,
+                dispatches,
+            })
+            .await?;
+
+        // The virtual nodes of the materialized view are now owned by the new actors.
+        if let Some(hash_mapping) = hash_mapping {
+            self.cluster_manager.set_hash_mapping(hash_mapping).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds the scheduled actors of `table_fragments` on compute nodes. Channels from the
+    /// upstream actors in `upstream_node_actors` to the new actors are created according to
+    /// `dispatches`. Returns the infos of the new downstream actors for each upstream actor.
+    async fn build_actors(
+        &self,
+        table_fragments: &TableFragments,
+        locations: &ScheduledLocations,
+        dispatches: &HashMap<ActorId, Vec<ActorId>>,
+        upstream_node_actors: &HashMap<WorkerId, Vec<ActorId>>,
+    ) -> Result<HashMap<ActorId, Vec<ActorInfo>>> {
+        let actor_map = table_fragments.actor_map();
+
         // Actors on each stream node will need to know where their upstream lies. `actor_info`
         // includes such information. It contains: 1. actors in the current create
         // materialized view request. 2. all upstream actors.
         let mut actor_infos_to_broadcast = locations.actor_infos();
-        actor_infos_to_broadcast.extend(ctx.upstream_node_actors.iter().flat_map(
+        actor_infos_to_broadcast.extend(upstream_node_actors.iter().flat_map(
             |(node_id, upstreams)| {
                 upstreams.iter().map(|up_id| ActorInfo {
                     actor_id: *up_id,
@@ -220,8 +446,7 @@ where
 
         let node_actors = locations.node_actors();
 
-        let dispatches = ctx
-            .dispatches
+        let dispatches = dispatches
             .iter()
             .map(|(up_id, down_ids)| {
                 (
@@ -239,8 +464,7 @@ where
             })
             .collect::<HashMap<_, _>>();
 
-        let mut node_hanging_channels = ctx
-            .upstream_node_actors
+        let mut node_hanging_channels = upstream_node_actors
             .iter()
             .map(|(node_id, up_ids)| {
                 (
@@ -336,19 +560,7 @@ where
                 .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
         }
 
-        // Add table fragments to meta store with state: `State::Creating`.
-        self.fragment_manager
-            .start_create_table_fragments(table_fragments.clone())
-            .await?;
-        self.barrier_manager
-            .run_command(Command::CreateMaterializedView {
-                table_fragments,
-                table_sink_map: ctx.table_sink_map,
-                dispatches,
-            })
-            .await?;
-
-        Ok(())
+        Ok(dispatches)
     }
 
     /// Dropping materialized view is done by barrier manager. Check
@@ -361,6 +573,126 @@ where
         Ok(())
     }
 
+    /// Collects the runtime status of the streaming jobs, including the fragments, the actors with
+    /// their placement and row counts, the split assignments of the sources and the barriers in
+    /// flight.
+    pub async fn streaming_status(&self) -> Result<GetStreamingStatusResponse> {
+        let nodes: HashMap<WorkerId, WorkerNode> = self
+            .cluster_manager
+            .list_worker_node(WorkerType::ComputeNode, None)
+            .await
+            .into_iter()
+            .map(|node| (node.id, node))
+            .collect();
+        let actor_row_counts = self.actor_row_counts(nodes.values()).await;
+
+        let mut fragments = vec![];
+        let mut actors = vec![];
+        let mut source_splits = vec![];
+        for table_fragments in self.fragment_manager.list_table_fragments().await? {
+            let table_id = table_fragments.table_id().table_id();
+            for fragment in table_fragments.fragments() {
+                fragments.push(status::Fragment {
+                    fragment_id: fragment.fragment_id,
+                    table_id,
+                    fragment_type: fragment.fragment_type,
+                    distribution_type: fragment.distribution_type,
+                    parallelism: fragment.actors.len() as u32,
+                });
+                for actor in &fragment.actors {
+                    let actor_status = table_fragments
+                        .actor_status()
+                        .get(&actor.actor_id)
+                        .cloned()
+                        .unwrap_or_default();
+                    actors.push(status::Actor {
+                        actor_id: actor.actor_id,
+                        fragment_id: fragment.fragment_id,
+                        table_id,
+                        worker_id: actor_status.node_id,
+                        host: nodes
+                            .get(&actor_status.node_id)
+                            .and_then(|node| node.host.clone()),
+                        state: actor_status.state,
+                        total_row_count: actor_row_counts
+                            .get(&actor.actor_id)
+                            .copied()
+                            .unwrap_or_default(),
+                    });
+
+                    let stream_node = actor.nodes.as_ref().unwrap();
+                    if let Some(source_id) = TableFragments::fetch_stream_source_id(stream_node) {
+                        for split in TableFragments::fetch_stream_source_splits(stream_node)? {
+                            source_splits.push(status::SourceSplit {
+                                source_id,
+                                actor_id: actor.actor_id,
+                                split_id: split.id(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let barrier_status = self.barrier_manager.status();
+        let in_flight_barriers = barrier_status
+            .in_flight_barriers
+            .iter()
+            .map(InFlightBarrier::to_protobuf)
+            .collect();
+
+        Ok(GetStreamingStatusResponse {
+            fragments,
+            actors,
+            source_splits,
+            current_epoch: barrier_status.current_epoch,
+            in_flight_barriers,
+        })
+    }
+
+    /// Collects the barrier collection status of the actors, with the await trees of the actors
+    /// not collected yet.
+    pub async fn barrier_status(&self) -> GetBarrierStatusResponse {
+        self.barrier_manager.barrier_status_report().await
+    }
+
+    /// Fetches the total row counts of the actors from the streaming metrics of the running
+    /// compute nodes. The nodes failing to respond within [`GET_ACTOR_STATS_TIMEOUT`] are skipped.
+    async fn actor_row_counts(
+        &self,
+        nodes: impl Iterator<Item = &WorkerNode>,
+    ) -> HashMap<ActorId, u64> {
+        let futures = nodes
+            .filter(|node| node.state == Running as i32)
+            .map(|node| async move {
+                let get_actor_stats = async {
+                    let mut client = self.clients.get(node).await?;
+                    let response = client
+                        .get_actor_stats(GetActorStatsRequest {})
+                        .await
+                        .to_rw_result()?;
+                    Ok::<_, RwError>(response.into_inner().actor_row_counts)
+                };
+                tokio::time::timeout(GET_ACTOR_STATS_TIMEOUT, get_actor_stats)
+                    .await
+                    .map_err(|_| {
+                        RwError::from(InternalError(format!(
+                            "timed out getting actor stats of node {}",
+                            node.id
+                        )))
+                    })?
+            });
+
+        let mut actor_row_counts = HashMap::new();
+        for result in join_all(futures).await {
+            match result {
+                Ok(row_counts) => actor_row_counts.extend(row_counts),
+                Err(e) => tracing::warn!("failed to get actor stats: {}", e),
+            }
+        }
+        actor_row_counts
+    }
+
     /// Flush means waiting for the next barrier to collect.
     pub async fn flush(&self) -> Result<()> {
         let start = Instant::now();
@@ -513,6 +845,27 @@ mod tests {
         ) -> std::result::Result<Response<SyncSourcesResponse>, Status> {
             Ok(Response::new(SyncSourcesResponse::default()))
         }
+
+        async fn get_actor_stats(
+            &self,
+            _request: Request<GetActorStatsRequest>,
+        ) -> std::result::Result<Response<GetActorStatsResponse>, Status> {
+            Ok(Response::new(GetActorStatsResponse::default()))
+        }
+
+        async fn get_barrier_status(
+            &self,
+            _request: Request<GetBarrierStatusRequest>,
+        ) -> std::result::Result<Response<GetBarrierStatusResponse>, Status> {
+            Ok(Response::new(GetBarrierStatusResponse::default()))
+        }
+
+        async fn dump_await_tree(
+            &self,
+            _request: Request<DumpAwaitTreeRequest>,
+        ) -> std::result::Result<Response<DumpAwaitTreeResponse>, Status> {
+            Ok(Response::new(DumpAwaitTreeResponse::default()))
+        }
     }
 
     struct MockServices {
@@ -556,7 +909,7 @@ mod tests {
                 port: port as i32,
             };
             cluster_manager
-                .add_worker_node(host.clone(), WorkerType::ComputeNode)
+                .add_worker_node(host.clone(), WorkerType::ComputeNode, Default::default())
                 .await?;
             cluster_manager.activate_worker_node(host).await?;
 
@@ -564,8 +917,13 @@ mod tests {
             let fragment_manager = Arc::new(FragmentManager::new(env.meta_store_ref()).await?);
             let meta_metrics = Arc::new(MetaMetrics::new());
             let hummock_manager = Arc::new(
-                HummockManager::new(env.clone(), cluster_manager.clone(), meta_metrics.clone())
-                    .await?,
+                HummockManager::new(
+                    env.clone(),
+                    cluster_manager.clone(),
+                    fragment_manager.clone(),
+                    meta_metrics.clone(),
+                )
+                .await?,
             );
             let barrier_manager = Arc::new(GlobalBarrierManager::new(
                 env.clone(),
@@ -582,6 +940,7 @@ mod tests {
                     cluster_manager.clone(),
                     barrier_manager.clone(),
                     catalog_manager.clone(),
+                    fragment_manager.clone(),
                 )
                 .await?,
             );
@@ -175,6 +175,7 @@ fn make_stream_node() -> StreamNode {
         node: Some(Node::GlobalSimpleAggNode(SimpleAggNode {
             agg_calls: vec![make_sum_aggcall(0), make_sum_aggcall(1)],
             distribution_keys: Default::default(),
+            table_id: 0,
         })),
         input: vec![filter_node],
         fields: vec![], // TODO: fill this later
@@ -203,6 +204,7 @@ fn make_stream_node() -> StreamNode {
         node: Some(Node::GlobalSimpleAggNode(SimpleAggNode {
             agg_calls: vec![make_sum_aggcall(0), make_sum_aggcall(1)],
             distribution_keys: Default::default(),
+            table_id: 0,
         })),
         fields: vec![], // TODO: fill this later
         input: vec![exchange_node_1],
@@ -327,5 +329,21 @@ async fn test_fragmenter() -> Result<()> {
         }
     }
 
+    // Only the simple aggregation in the singleton fragment has a state table.
+    fn simple_agg_table_id(node: &StreamNode) -> Option<u32> {
+        match node.get_node().unwrap() {
+            Node::GlobalSimpleAggNode(simple_agg_node) => Some(simple_agg_node.table_id),
+            _ => node.get_input().iter().find_map(simple_agg_table_id),
+        }
+    }
+    for actor in table_fragments.actors() {
+        let table_id = simple_agg_table_id(actor.get_nodes().unwrap());
+        match actor.get_actor_id() {
+            1 => assert_ne!(table_id, Some(0)),
+            2..=5 => assert_eq!(table_id, Some(0)),
+            _ => assert_eq!(table_id, None),
+        }
+    }
+
     Ok(())
 }
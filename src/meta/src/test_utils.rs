@@ -36,6 +36,7 @@ impl LocalMeta {
         let addr = Self::meta_addr_inner(port).parse().unwrap();
         let (join_handle, shutdown_sender) = crate::rpc::server::rpc_serve(
             addr,
+            Self::meta_addr_inner(port),
             None,
             None,
             MetaStoreBackend::Mem,
@@ -30,10 +30,10 @@ use console::style;
 use indicatif::{MultiProgress, ProgressBar};
 use risedev::util::{complete_spin, fail_spin};
 use risedev::{
-    AwsS3Config, ComputeNodeService, ConfigExpander, ConfigureTmuxTask, EnsureStopService,
-    ExecuteContext, FrontendService, FrontendServiceV2, GrafanaService, JaegerService,
-    KafkaService, MetaNodeService, MinioService, PrometheusService, ServiceConfig, Task,
-    ZooKeeperService, RISEDEV_SESSION_NAME,
+    AwsS3Config, CompactorService, ComputeNodeService, ConfigExpander, ConfigureTmuxTask,
+    EnsureStopService, ExecuteContext, FrontendService, FrontendServiceV2, GrafanaService,
+    JaegerService, KafkaService, MetaNodeService, MinioService, PrometheusService, ServiceConfig,
+    Task, ZooKeeperService, RISEDEV_SESSION_NAME,
 };
 use tempfile::tempdir;
 use yaml_rust::YamlEmitter;
@@ -120,6 +120,8 @@ fn task_main(
             ServiceConfig::Etcd(c) => Some((c.port, c.id.clone())),
             ServiceConfig::Prometheus(c) => Some((c.port, c.id.clone())),
             ServiceConfig::ComputeNode(c) => Some((c.port, c.id.clone())),
+            // The compactor doesn't listen on its port.
+            ServiceConfig::Compactor(_) => None,
             ServiceConfig::MetaNode(c) => Some((c.port, c.id.clone())),
             ServiceConfig::Frontend(c) => Some((c.port, c.id.clone())),
             ServiceConfig::FrontendV2(c) => Some((c.port, c.id.clone())),
@@ -189,6 +191,12 @@ fn task_main(
                 ctx.pb
                     .set_message(format!("api grpc://{}:{}/", c.address, c.port));
             }
+            ServiceConfig::Compactor(c) => {
+                let mut ctx =
+                    ExecuteContext::new(&mut logger, manager.new_progress(), status_dir.clone());
+                let mut service = CompactorService::new(c.clone())?;
+                service.execute(&mut ctx)?;
+            }
             ServiceConfig::MetaNode(c) => {
                 let mut ctx =
                     ExecuteContext::new(&mut logger, manager.new_progress(), status_dir.clone());
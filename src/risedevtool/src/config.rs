@@ -108,6 +108,7 @@ impl ConfigExpander {
                     "frontend-legacy" => ServiceConfig::Frontend(serde_yaml::from_str(&out_str)?),
                     "frontend" => ServiceConfig::FrontendV2(serde_yaml::from_str(&out_str)?),
                     "compute-node" => ServiceConfig::ComputeNode(serde_yaml::from_str(&out_str)?),
+                    "compactor" => ServiceConfig::Compactor(serde_yaml::from_str(&out_str)?),
                     "meta-node" => ServiceConfig::MetaNode(serde_yaml::from_str(&out_str)?),
                     "prometheus" => ServiceConfig::Prometheus(serde_yaml::from_str(&out_str)?),
                     "grafana" => ServiceConfig::Grafana(serde_yaml::from_str(&out_str)?),
@@ -32,6 +32,22 @@ pub struct ComputeNodeConfig {
     pub provide_jaeger: Option<Vec<JaegerConfig>>,
     pub user_managed: bool,
     pub enable_in_memory_kv_state_backend: bool,
+    pub labels: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+pub struct CompactorConfig {
+    #[serde(rename = "use")]
+    phantom_use: Option<String>,
+    pub id: String,
+    pub address: String,
+    pub port: u16,
+    pub provide_minio: Option<Vec<MinioConfig>>,
+    pub provide_meta_node: Option<Vec<MetaNodeConfig>>,
+    pub provide_aws_s3: Option<Vec<AwsS3Config>>,
+    pub user_managed: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -176,6 +192,7 @@ pub struct ZooKeeperConfig {
 #[derive(Clone, Debug, PartialEq)]
 pub enum ServiceConfig {
     ComputeNode(ComputeNodeConfig),
+    Compactor(CompactorConfig),
     MetaNode(MetaNodeConfig),
     Frontend(FrontendConfig),
     FrontendV2(FrontendConfig),
@@ -193,6 +210,7 @@ impl ServiceConfig {
     pub fn id(&self) -> &str {
         match self {
             Self::ComputeNode(c) => &c.id,
+            Self::Compactor(c) => &c.id,
             Self::MetaNode(c) => &c.id,
             Self::Frontend(c) => &c.id,
             Self::FrontendV2(c) => &c.id,
@@ -65,6 +65,10 @@ impl Task for ComputeNodeService {
             .arg("--metrics-level")
             .arg("1");
 
+        for label in &self.config.labels {
+            cmd.arg("--label").arg(label);
+        }
+
         let provide_jaeger = self.config.provide_jaeger.as_ref().unwrap();
         match provide_jaeger.len() {
             0 => {}
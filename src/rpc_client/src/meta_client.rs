@@ -11,7 +11,9 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -38,35 +40,47 @@ use risingwave_pb::ddl_service::{
 use risingwave_pb::hummock::hummock_manager_service_client::HummockManagerServiceClient;
 use risingwave_pb::hummock::{
     AbortEpochRequest, AbortEpochResponse, AddTablesRequest, AddTablesResponse, CommitEpochRequest,
-    CommitEpochResponse, CompactTask, GetNewTableIdRequest, GetNewTableIdResponse, HummockSnapshot,
-    HummockVersion, PinSnapshotRequest, PinSnapshotResponse, PinVersionRequest, PinVersionResponse,
-    ReportCompactionTasksRequest, ReportCompactionTasksResponse, ReportVacuumTaskRequest,
-    ReportVacuumTaskResponse, SstableInfo, SubscribeCompactTasksRequest,
-    SubscribeCompactTasksResponse, UnpinSnapshotRequest, UnpinSnapshotResponse,
-    UnpinVersionRequest, UnpinVersionResponse, VacuumTask,
+    CommitEpochResponse, CompactTask, CreateBackupRequest, CreateBackupResponse,
+    DeleteBackupRequest, DeleteBackupResponse, GetNewTableIdRequest, GetNewTableIdResponse,
+    HummockBackup, HummockBackupManifest, HummockSnapshot, HummockVersion, ListBackupsRequest,
+    ListBackupsResponse, PinSnapshotRequest, PinSnapshotResponse, PinVersionRequest,
+    PinVersionResponse, ReportCompactionTasksRequest, ReportCompactionTasksResponse,
+    ReportVacuumTaskRequest, ReportVacuumTaskResponse, SstableInfo, SubscribeCompactTasksRequest,
+    SubscribeCompactTasksResponse, TriggerManualCompactionRequest, TriggerManualCompactionResponse,
+    UnpinSnapshotRequest, UnpinSnapshotResponse, UnpinVersionRequest, UnpinVersionResponse,
+    VacuumTask,
 };
 use risingwave_pb::meta::catalog_service_client::CatalogServiceClient;
 use risingwave_pb::meta::cluster_service_client::ClusterServiceClient;
 use risingwave_pb::meta::heartbeat_service_client::HeartbeatServiceClient;
+use risingwave_pb::meta::leader_service_client::LeaderServiceClient;
 use risingwave_pb::meta::notification_service_client::NotificationServiceClient;
 use risingwave_pb::meta::stream_manager_service_client::StreamManagerServiceClient;
 use risingwave_pb::meta::{
     ActivateWorkerNodeRequest, ActivateWorkerNodeResponse, AddWorkerNodeRequest,
     AddWorkerNodeResponse, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, FlushRequest,
-    FlushResponse, HeartbeatRequest, HeartbeatResponse, ListAllNodesRequest, ListAllNodesResponse,
+    FlushResponse, GetBarrierStatusRequest, GetBarrierStatusResponse, GetLeaderRequest,
+    GetStreamingStatusRequest, GetStreamingStatusResponse, HeartbeatRequest, HeartbeatResponse,
+    ListAllNodesRequest, ListAllNodesResponse, RescheduleRequest, RescheduleResponse,
     SubscribeRequest, SubscribeResponse,
 };
 use risingwave_pb::stream_plan::StreamNode;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
+use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tonic::transport::{Channel, Endpoint};
-use tonic::{Status, Streaming};
+use tonic::{Code, Status, Streaming};
 
 use crate::hummock_meta_client::HummockMetaClient;
 
 type DatabaseId = u32;
 type SchemaId = u32;
 
+/// Times to retry finding the meta leader, during which a new leader may be elected.
+const FAILOVER_RETRY_TIMES: usize = 30;
+/// Interval between the retries of finding the meta leader.
+const FAILOVER_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Client to meta server. Cloning the instance is lightweight.
 #[derive(Clone)]
 pub struct MetaClient {
@@ -75,7 +89,7 @@ pub struct MetaClient {
 }
 
 impl MetaClient {
-    /// Connect to the meta server `addr`.
+    /// Connect to the leader among meta nodes `meta_addr`, which are separated by commas.
     pub async fn new(meta_addr: &str) -> Result<Self> {
         Ok(Self {
             inner: GrpcMetaClient::new(meta_addr).await?,
@@ -104,11 +118,18 @@ impl MetaClient {
         self.inner.subscribe(request).await
     }
 
-    /// Register the current node to the cluster and set the corresponding worker id.
-    pub async fn register(&mut self, addr: &HostAddr, worker_type: WorkerType) -> Result<u32> {
+    /// Register the current node to the cluster with its `labels` and set the corresponding worker
+    /// id.
+    pub async fn register(
+        &mut self,
+        addr: &HostAddr,
+        worker_type: WorkerType,
+        labels: HashMap<String, String>,
+    ) -> Result<u32> {
         let request = AddWorkerNodeRequest {
             worker_type: worker_type as i32,
             host: Some(addr.to_protobuf()),
+            labels,
         };
         let resp = self.inner.add_worker_node(request).await?;
         let worker_node =
@@ -289,6 +310,64 @@ impl MetaClient {
         self.inner.flush(request).await?;
         Ok(())
     }
+
+    /// Reschedule the actors of materialized view `table_id` with `parallelism` onto `worker_ids`.
+    /// Keep the current parallelism if `parallelism` is 0, and use all workers if `worker_ids` is
+    /// empty.
+    pub async fn reschedule(
+        &self,
+        table_id: u32,
+        parallelism: u32,
+        worker_ids: Vec<u32>,
+    ) -> Result<()> {
+        let request = RescheduleRequest {
+            table_id,
+            parallelism,
+            worker_ids,
+        };
+        self.inner.reschedule(request).await?;
+        Ok(())
+    }
+
+    /// Get the runtime status of the streaming jobs, for introspection.
+    pub async fn get_streaming_status(&self) -> Result<GetStreamingStatusResponse> {
+        let request = GetStreamingStatusRequest::default();
+        self.inner.get_streaming_status(request).await
+    }
+
+    /// Get the barrier collection status of the actors, for diagnosing stuck barriers.
+    pub async fn get_barrier_status(&self) -> Result<GetBarrierStatusResponse> {
+        let request = GetBarrierStatusRequest::default();
+        self.inner.get_barrier_status(request).await
+    }
+
+    /// Compact SSTs in `level` into the level below it. Returns the id of the compact task.
+    pub async fn trigger_manual_compaction(&self, level: u32) -> Result<u64> {
+        let request = TriggerManualCompactionRequest { level };
+        let resp = self.inner.trigger_manual_compaction(request).await?;
+        Ok(resp.task_id)
+    }
+
+    /// Retains the SSTs of the current Hummock version and dumps meta store for a backup at
+    /// `path`.
+    pub async fn create_backup(&self, path: String) -> Result<HummockBackupManifest> {
+        let request = CreateBackupRequest { path };
+        let resp = self.inner.create_backup(request).await?;
+        Ok(resp.manifest.unwrap())
+    }
+
+    pub async fn list_backups(&self) -> Result<Vec<HummockBackup>> {
+        let request = ListBackupsRequest {};
+        let resp = self.inner.list_backups(request).await?;
+        Ok(resp.backups)
+    }
+
+    /// Deletes the backup so that its SSTs can be vacuumed.
+    pub async fn delete_backup(&self, backup_id: u64) -> Result<()> {
+        let request = DeleteBackupRequest { backup_id };
+        self.inner.delete_backup(request).await?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -390,26 +469,32 @@ impl HummockMetaClient for MetaClient {
 }
 
 /// Client to meta server. Cloning the instance is lightweight.
+///
+/// Requests are sent to the leader among meta nodes. Once the leader is unavailable, the client
+/// asks all meta nodes for the new leader and fails over to it.
 #[derive(Debug, Clone)]
 pub struct GrpcMetaClient {
-    pub cluster_client: ClusterServiceClient<Channel>,
-    pub heartbeat_client: HeartbeatServiceClient<Channel>,
-    pub catalog_client: CatalogServiceClient<Channel>,
-    pub ddl_client: DdlServiceClient<Channel>,
-    pub hummock_client: HummockManagerServiceClient<Channel>,
-    pub notification_client: NotificationServiceClient<Channel>,
-    pub stream_client: StreamManagerServiceClient<Channel>,
+    /// Addresses of all meta nodes.
+    addrs: Arc<Vec<String>>,
+    core: Arc<RwLock<GrpcMetaClientCore>>,
 }
 
-impl GrpcMetaClient {
-    /// Connect to the meta server `addr`.
-    pub async fn new(addr: &str) -> Result<Self> {
-        let channel = Endpoint::from_shared(addr.to_string())
-            .map_err(|e| InternalError(format!("{}", e)))?
-            .connect_timeout(Duration::from_secs(5))
-            .connect()
-            .await
-            .to_rw_result_with(|| format!("failed to connect to {}", addr))?;
+/// Clients to the current meta leader.
+#[derive(Debug, Clone)]
+struct GrpcMetaClientCore {
+    leader_addr: String,
+    cluster_client: ClusterServiceClient<Channel>,
+    heartbeat_client: HeartbeatServiceClient<Channel>,
+    catalog_client: CatalogServiceClient<Channel>,
+    ddl_client: DdlServiceClient<Channel>,
+    hummock_client: HummockManagerServiceClient<Channel>,
+    notification_client: NotificationServiceClient<Channel>,
+    stream_client: StreamManagerServiceClient<Channel>,
+}
+
+impl GrpcMetaClientCore {
+    async fn new(leader_addr: String) -> Result<Self> {
+        let channel = GrpcMetaClient::connect(&leader_addr).await?;
         let cluster_client = ClusterServiceClient::new(channel.clone());
         let heartbeat_client = HeartbeatServiceClient::new(channel.clone());
         let catalog_client = CatalogServiceClient::new(channel.clone());
@@ -418,6 +503,7 @@ impl GrpcMetaClient {
         let notification_client = NotificationServiceClient::new(channel.clone());
         let stream_client = StreamManagerServiceClient::new(channel);
         Ok(Self {
+            leader_addr,
             cluster_client,
             heartbeat_client,
             catalog_client,
@@ -429,18 +515,118 @@ impl GrpcMetaClient {
     }
 }
 
+impl GrpcMetaClient {
+    /// Connect to the leader among meta nodes `addrs`, which are separated by commas.
+    pub async fn new(addrs: &str) -> Result<Self> {
+        let addrs = addrs.split(',').map(Self::http_addr).collect::<Vec<_>>();
+        let leader_addr = Self::find_leader(&addrs, None).await?;
+        let core = GrpcMetaClientCore::new(leader_addr).await?;
+        Ok(Self {
+            addrs: Arc::new(addrs),
+            core: Arc::new(RwLock::new(core)),
+        })
+    }
+
+    fn http_addr(addr: &str) -> String {
+        let addr = addr.trim();
+        if addr.contains("://") {
+            addr.to_string()
+        } else {
+            format!("http://{}", addr)
+        }
+    }
+
+    async fn connect(addr: &str) -> Result<Channel> {
+        Endpoint::from_shared(addr.to_string())
+            .map_err(|e| InternalError(format!("{}", e)))?
+            .connect_timeout(Duration::from_secs(5))
+            .connect()
+            .await
+            .to_rw_result_with(|| format!("failed to connect to {}", addr))
+    }
+
+    /// Asks the meta nodes in `addrs` for the address of the current leader. If `old_leader` is
+    /// given, waits until another leader is elected. Gives up after [`FAILOVER_RETRY_TIMES`]
+    /// retries.
+    async fn find_leader(addrs: &[String], old_leader: Option<&str>) -> Result<String> {
+        for _ in 0..FAILOVER_RETRY_TIMES {
+            for addr in addrs {
+                let channel = match Self::connect(addr).await {
+                    Ok(channel) => channel,
+                    Err(err) => {
+                        tracing::warn!("failed to connect to meta node {}: {}", addr, err);
+                        continue;
+                    }
+                };
+                let leader_addr = match LeaderServiceClient::new(channel)
+                    .get_leader(GetLeaderRequest {})
+                    .await
+                {
+                    Ok(resp) => resp.into_inner().leader_addr,
+                    Err(status) => {
+                        tracing::warn!("failed to get leader from meta node {}: {}", addr, status);
+                        continue;
+                    }
+                };
+                if leader_addr.is_empty() {
+                    continue;
+                }
+                let leader_addr = Self::http_addr(&leader_addr);
+                if old_leader != Some(leader_addr.as_str()) {
+                    return Ok(leader_addr);
+                }
+            }
+            tokio::time::sleep(FAILOVER_RETRY_INTERVAL).await;
+        }
+
+        Err(InternalError(format!("no meta leader is found in {:?}", addrs)).into())
+    }
+
+    /// Fails over to the new leader if `status` is returned from the unavailable `old_leader`.
+    /// Returns whether the leader is changed, after which the request should be retried.
+    async fn failover(&self, old_leader: &str, status: &Status) -> Result<bool> {
+        if !matches!(
+            status.code(),
+            Code::Unavailable | Code::Unimplemented | Code::Unknown
+        ) {
+            return Ok(false);
+        }
+
+        let mut core = self.core.write().await;
+        if core.leader_addr != old_leader {
+            // Another request has already failed over.
+            return Ok(true);
+        }
+        // The old leader may still be alive if it doesn't return `Unavailable`.
+        let leader_addr = match Self::find_leader(&self.addrs, None).await? {
+            leader_addr if leader_addr == old_leader && status.code() != Code::Unavailable => {
+                return Ok(false);
+            }
+            leader_addr if leader_addr == old_leader => {
+                Self::find_leader(&self.addrs, Some(old_leader)).await?
+            }
+            leader_addr => leader_addr,
+        };
+        tracing::info!("meta leader changed from {} to {}", old_leader, leader_addr);
+        *core = GrpcMetaClientCore::new(leader_addr).await?;
+        Ok(true)
+    }
+}
+
 macro_rules! grpc_meta_client_impl {
     ([], $( { $client:ident, $fn_name:ident, $req:ty, $resp:ty }),*) => {
         $(paste! {
             impl GrpcMetaClient {
                 pub async fn [<$fn_name>](&self, request: $req) -> Result<$resp> {
-                    Ok(self
-                        .$client
-                        .to_owned()
-                        .$fn_name(request)
-                        .await
-                        .to_rw_result()?
-                        .into_inner())
+                    let core = self.core.read().await.clone();
+                    let result = match core.$client.to_owned().$fn_name(request.clone()).await {
+                        Err(status) if self.failover(&core.leader_addr, &status).await? => {
+                            let core = self.core.read().await.clone();
+                            core.$client.to_owned().$fn_name(request).await
+                        }
+                        result => result,
+                    };
+                    Ok(result.to_rw_result()?.into_inner())
                 }
             }
         })*
@@ -457,6 +643,9 @@ macro_rules! for_all_meta_rpc {
             ,{ cluster_client, list_all_nodes, ListAllNodesRequest, ListAllNodesResponse }
             ,{ heartbeat_client, heartbeat, HeartbeatRequest, HeartbeatResponse }
             ,{ stream_client, flush, FlushRequest, FlushResponse }
+            ,{ stream_client, reschedule, RescheduleRequest, RescheduleResponse }
+            ,{ stream_client, get_streaming_status, GetStreamingStatusRequest, GetStreamingStatusResponse }
+            ,{ stream_client, get_barrier_status, GetBarrierStatusRequest, GetBarrierStatusResponse }
             ,{ ddl_client, create_materialized_source, CreateMaterializedSourceRequest, CreateMaterializedSourceResponse }
             ,{ ddl_client, create_materialized_view, CreateMaterializedViewRequest, CreateMaterializedViewResponse }
             ,{ ddl_client, create_source, CreateSourceRequest, CreateSourceResponse }
@@ -476,6 +665,10 @@ macro_rules! for_all_meta_rpc {
             ,{ hummock_client, report_vacuum_task, ReportVacuumTaskRequest, ReportVacuumTaskResponse }
             ,{ hummock_client, commit_epoch, CommitEpochRequest, CommitEpochResponse }
             ,{ hummock_client, abort_epoch, AbortEpochRequest, AbortEpochResponse }
+            ,{ hummock_client, trigger_manual_compaction, TriggerManualCompactionRequest, TriggerManualCompactionResponse }
+            ,{ hummock_client, create_backup, CreateBackupRequest, CreateBackupResponse }
+            ,{ hummock_client, list_backups, ListBackupsRequest, ListBackupsResponse }
+            ,{ hummock_client, delete_backup, DeleteBackupRequest, DeleteBackupResponse }
         }
     };
 }
@@ -489,14 +682,20 @@ impl GrpcMetaClient {
         &self,
         request: SubscribeRequest,
     ) -> Result<Box<dyn NotificationStream>> {
-        Ok(Box::new(
-            self.notification_client
-                .to_owned()
-                .subscribe(request)
-                .await
-                .to_rw_result()?
-                .into_inner(),
-        ))
+        let core = self.core.read().await.clone();
+        let result = match core
+            .notification_client
+            .to_owned()
+            .subscribe(request.clone())
+            .await
+        {
+            Err(status) if self.failover(&core.leader_addr, &status).await? => {
+                let core = self.core.read().await.clone();
+                core.notification_client.to_owned().subscribe(request).await
+            }
+            result => result,
+        };
+        Ok(Box::new(result.to_rw_result()?.into_inner()))
     }
 }
 
@@ -12,30 +12,68 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::marker::Send;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use futures::stream::{select_all, BoxStream, SelectAll};
+use futures::StreamExt;
 use lazy_static::__Deref;
 use risingwave_common::array::StreamChunk;
-use risingwave_common::error::ErrorCode::ProtocolError;
+use risingwave_common::error::ErrorCode::{InternalError, ProtocolError};
 use risingwave_common::error::{Result, RwError};
-use risingwave_connector::base::SourceReader;
-use risingwave_connector::state;
+use risingwave_connector::base::{InnerMessage, SourceReader};
+use risingwave_connector::{new_connector, state, ConnectorState, SplitImpl};
 use risingwave_storage::StateStore;
 use tokio::sync::Mutex;
 
 use crate::common::SourceChunkBuilder;
 use crate::{SourceColumnDesc, SourceParser, StreamSourceReader};
 
+/// The offset of the last message read from each split, keyed by the split id.
+pub type SplitOffsets = Arc<parking_lot::Mutex<HashMap<String, String>>>;
+
+/// Creates the [`SourceReader`]s of a [`ConnectorSource`].
+#[async_trait]
+pub trait SplitReaderBuilder: Send + Sync {
+    /// Creates a reader of the split described by `state`, or of the whole source if `state` is
+    /// `None`.
+    async fn build(
+        &self,
+        state: Option<ConnectorState>,
+    ) -> anyhow::Result<Box<dyn SourceReader + Send + Sync>>;
+}
+
+/// Creates readers by [`new_connector`] with the properties of the source.
+pub struct ConnectorReaderBuilder {
+    config: HashMap<String, String>,
+}
+
+impl ConnectorReaderBuilder {
+    pub fn new(config: HashMap<String, String>) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl SplitReaderBuilder for ConnectorReaderBuilder {
+    async fn build(
+        &self,
+        state: Option<ConnectorState>,
+    ) -> anyhow::Result<Box<dyn SourceReader + Send + Sync>> {
+        new_connector(self.config.clone(), state).await
+    }
+}
+
 /// [`ConnectorSource`] serves as a bridge between external components and streaming or batch
 /// processing. [`ConnectorSource`] introduces schema at this level while [`SourceReader`] simply
 /// loads raw content from message queue or file system.
 #[derive(Clone)]
 pub struct ConnectorSource {
     pub parser: Arc<dyn SourceParser + Send + Sync>,
-    pub reader: Arc<Mutex<Box<dyn SourceReader + Send + Sync>>>,
+    pub reader_builder: Arc<dyn SplitReaderBuilder>,
     pub column_descs: Vec<SourceColumnDesc>,
 }
 
@@ -50,58 +88,96 @@ impl Debug for ConnectorSource {
 impl ConnectorSource {
     pub fn new(
         parser: Arc<dyn SourceParser + Send + Sync>,
-        reader: Arc<Mutex<Box<dyn SourceReader + Send + Sync>>>,
+        reader_builder: Arc<dyn SplitReaderBuilder>,
         column_descs: Vec<SourceColumnDesc>,
     ) -> Self {
         Self {
             parser,
-            reader,
+            reader_builder,
             column_descs,
         }
     }
 
-    pub async fn next(&mut self) -> Result<StreamChunk> {
-        let payload = self
-            .reader
-            .lock()
-            .await
-            .next()
-            .await
-            .map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
+    /// Creates a stream reader of `splits`, which reads each split from its start offset, or the
+    /// whole source if there is no split. The offsets read are recorded in `split_offsets`.
+    pub async fn stream_reader<S: StateStore>(
+        &self,
+        splits: Vec<SplitImpl>,
+        split_offsets: SplitOffsets,
+        state_store: state::SourceStateHandler<S>,
+    ) -> Result<ConnectorStreamSource<S>> {
+        let states = if splits.is_empty() {
+            vec![None]
+        } else {
+            splits.iter().map(|split| Some(split.to_state())).collect()
+        };
 
-        match payload {
-            None => Ok(StreamChunk::default()),
-            Some(batch) => {
-                let mut events = Vec::with_capacity(batch.len());
-                for msg in batch {
-                    if let Some(content) = msg.payload {
-                        events.push(self.parser.parse(content.deref(), &self.column_descs)?);
-                    }
-                }
+        let mut readers = Vec::with_capacity(states.len());
+        for state in states {
+            let reader = self
+                .reader_builder
+                .build(state)
+                .await
+                .map_err(|e| RwError::from(InternalError(e.to_string())))?;
+            readers.push(
+                futures::stream::unfold(reader, |mut reader| async move {
+                    let batch = reader.next().await;
+                    Some((batch, reader))
+                })
+                .boxed(),
+            );
+        }
 
-                let mut ops = Vec::with_capacity(events.iter().map(|e| e.ops.len()).sum());
-                let mut rows = Vec::with_capacity(events.iter().map(|e| e.rows.len()).sum());
+        Ok(ConnectorStreamSource {
+            source_reader: self.clone(),
+            readers: Mutex::new(select_all(readers)),
+            split_offsets,
+            state_store,
+        })
+    }
 
-                for event in events {
-                    rows.extend(event.rows);
-                    ops.extend(event.ops);
-                }
-                Ok(StreamChunk::new(
-                    ops,
-                    Self::build_columns(&self.column_descs, rows.as_ref())?,
-                    None,
-                ))
+    fn parse(&self, batch: Vec<InnerMessage>) -> Result<StreamChunk> {
+        let mut events = Vec::with_capacity(batch.len());
+        for msg in batch {
+            if let Some(content) = msg.payload {
+                events.push(self.parser.parse(content.deref(), &self.column_descs)?);
             }
         }
+
+        let mut ops = Vec::with_capacity(events.iter().map(|e| e.ops.len()).sum());
+        let mut rows = Vec::with_capacity(events.iter().map(|e| e.rows.len()).sum());
+
+        for event in events {
+            rows.extend(event.rows);
+            ops.extend(event.ops);
+        }
+        Ok(StreamChunk::new(
+            ops,
+            Self::build_columns(&self.column_descs, rows.as_ref())?,
+            None,
+        ))
     }
 }
 
-#[derive(Debug)]
+type SplitReaderStream = BoxStream<'static, anyhow::Result<Option<Vec<InnerMessage>>>>;
+
 pub struct ConnectorStreamSource<S: StateStore> {
     pub source_reader: ConnectorSource,
+    /// The readers of the splits, polled together. The mutex only makes the readers `Sync`.
+    readers: Mutex<SelectAll<SplitReaderStream>>,
+    split_offsets: SplitOffsets,
     pub state_store: state::SourceStateHandler<S>,
 }
 
+impl<S: StateStore> Debug for ConnectorStreamSource<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectorStreamSource")
+            .field("source_reader", &self.source_reader)
+            .field("split_offsets", &self.split_offsets)
+            .finish()
+    }
+}
+
 #[async_trait]
 impl<S: StateStore> StreamSourceReader for ConnectorStreamSource<S> {
     async fn open(&mut self) -> Result<()> {
@@ -109,6 +185,22 @@ impl<S: StateStore> StreamSourceReader for ConnectorStreamSource<S> {
     }
 
     async fn next(&mut self) -> Result<StreamChunk> {
-        self.source_reader.next().await
+        let batch = match self.readers.get_mut().next().await {
+            Some(batch) => batch.map_err(|e| RwError::from(ProtocolError(e.to_string())))?,
+            None => unreachable!("split readers never end"),
+        };
+
+        match batch {
+            None => Ok(StreamChunk::default()),
+            Some(batch) => {
+                {
+                    let mut split_offsets = self.split_offsets.lock();
+                    for msg in &batch {
+                        split_offsets.insert(msg.split_id.clone(), msg.offset.clone());
+                    }
+                }
+                self.source_reader.parse(batch)
+            }
+        }
     }
 }
@@ -23,11 +23,10 @@ use risingwave_common::ensure;
 use risingwave_common::error::ErrorCode::{InternalError, ProtocolError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::DataType;
-use risingwave_connector::base::SourceReader;
-use risingwave_connector::{new_connector, Properties};
+use risingwave_connector::Properties;
 use risingwave_pb::catalog::{RowFormatType, StreamSourceInfo};
 
-use crate::connector_source::ConnectorSource;
+use crate::connector_source::{ConnectorReaderBuilder, ConnectorSource};
 use crate::table_v2::TableSourceV2;
 use crate::{
     DebeziumJsonParser, HighLevelKafkaSource, JSONParser, ProtobufParser, SourceConfig,
@@ -75,15 +74,32 @@ pub struct SourceColumnDesc {
     pub data_type: DataType,
     pub column_id: ColumnId,
     pub skip_parse: bool,
+    /// The fields of a struct column, named as the keys in the nested payload.
+    pub fields: Vec<SourceColumnDesc>,
 }
 
 impl From<&ColumnDesc> for SourceColumnDesc {
     fn from(c: &ColumnDesc) -> Self {
+        // The `field_descs` are named by their path from the top-level column, e.g.
+        // `country.city`, while the nested payload is keyed by the field name only.
+        let prefix = format!("{}.", c.name);
+        let fields = c
+            .field_descs
+            .iter()
+            .map(|f| {
+                let mut field = SourceColumnDesc::from(f);
+                if let Some(name) = f.name.strip_prefix(&prefix) {
+                    field.name = name.to_string();
+                }
+                field
+            })
+            .collect();
         Self {
             name: c.name.clone(),
             data_type: c.data_type.clone(),
             column_id: c.column_id,
             skip_parse: false,
+            fields,
         }
     }
 }
@@ -121,19 +137,11 @@ impl SourceManager for MemSourceManager {
                 Arc::new(columns.clone()),
                 parser.clone(),
             )),
-            SourceConfig::Connector(config) => {
-                let split_reader: Arc<tokio::sync::Mutex<Box<dyn SourceReader + Send + Sync>>> =
-                    Arc::new(tokio::sync::Mutex::new(
-                        new_connector(config.clone(), None)
-                            .await
-                            .map_err(|e| RwError::from(InternalError(e.to_string())))?,
-                    ));
-                SourceImpl::Connector(ConnectorSource {
-                    parser: parser.clone(),
-                    reader: split_reader,
-                    column_descs: columns.clone(),
-                })
-            }
+            SourceConfig::Connector(config) => SourceImpl::Connector(ConnectorSource::new(
+                parser.clone(),
+                Arc::new(ConnectorReaderBuilder::new(config.clone())),
+                columns.clone(),
+            )),
         };
 
         let desc = SourceDesc {
@@ -175,12 +183,10 @@ impl SourceManager for MemSourceManager {
             .iter()
             .enumerate()
             .map(|(idx, c)| {
-                let c = c.column_desc.as_ref().unwrap().clone();
+                let c = ColumnDesc::from(c.column_desc.as_ref().unwrap());
                 SourceColumnDesc {
-                    name: c.name.clone(),
-                    data_type: DataType::from(&c.column_type.unwrap()),
-                    column_id: ColumnId::from(c.column_id),
                     skip_parse: idx as i32 == info.row_id_index,
+                    ..(&c).into()
                 }
             })
             .collect::<Vec<SourceColumnDesc>>();
@@ -207,19 +213,11 @@ impl SourceManager for MemSourceManager {
                 SourceConfig::Kafka(config) => SourceImpl::HighLevelKafka(
                     HighLevelKafkaSource::new(config, Arc::new(columns.clone()), parser.clone()),
                 ),
-                SourceConfig::Connector(config) => {
-                    let split_reader: Arc<tokio::sync::Mutex<Box<dyn SourceReader + Send + Sync>>> =
-                        Arc::new(tokio::sync::Mutex::new(
-                            new_connector(config.clone(), None)
-                                .await
-                                .map_err(|e| RwError::from(InternalError(e.to_string())))?,
-                        ));
-                    SourceImpl::Connector(ConnectorSource {
-                        parser: parser.clone(),
-                        reader: split_reader,
-                        column_descs: columns.clone(),
-                    })
-                }
+                SourceConfig::Connector(config) => SourceImpl::Connector(ConnectorSource::new(
+                    parser.clone(),
+                    Arc::new(ConnectorReaderBuilder::new(config)),
+                    columns.clone(),
+                )),
             };
 
         let desc = SourceDesc {
@@ -378,6 +376,7 @@ mod tests {
                 data_type: c.data_type.clone(),
                 column_id: c.column_id,
                 skip_parse: false,
+                fields: vec![],
             })
             .collect();
 
@@ -441,8 +440,8 @@ mod tests {
 
         let schema = Schema {
             fields: vec![
-                Field::unnamed(DataType::Decimal),
-                Field::unnamed(DataType::Decimal),
+                Field::unnamed(DataType::UNCONSTRAINED_DECIMAL),
+                Field::unnamed(DataType::UNCONSTRAINED_DECIMAL),
             ],
         };
 
@@ -14,6 +14,7 @@
 
 use chrono::NaiveDate;
 use num_traits::FromPrimitive;
+use risingwave_common::array::StructValue;
 use risingwave_common::error::ErrorCode::{self, InternalError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::{DataType, Decimal, NaiveDateWrapper, ScalarImpl, ScalarRef};
@@ -74,7 +75,7 @@ pub(crate) fn json_parse_value(
                 |v: f64| ScalarImpl::Float64(v.into())
             )
         }
-        DataType::Decimal => match value.and_then(|v| v.as_f64()) {
+        DataType::Decimal { .. } => match value.and_then(|v| v.as_f64()) {
             Some(v) => match Decimal::from_f64(v) {
                 Some(v) => Ok(ScalarImpl::Decimal(v)),
                 None => Err(RwError::from(InternalError(
@@ -95,6 +96,21 @@ pub(crate) fn json_parse_value(
                 Err(e) => Err(e),
             },
         },
+        DataType::Struct { .. } => match value.and_then(|v| v.as_object()) {
+            None => Err(RwError::from(InternalError("json parse error".to_string()))),
+            Some(object) => Ok(ScalarImpl::Struct(StructValue::new(
+                column
+                    .fields
+                    .iter()
+                    // A missing or null field is a null datum, while a malformed one fails the
+                    // whole struct.
+                    .map(|field| match object.get(&field.name) {
+                        None | Some(Value::Null) => Ok(None),
+                        value => json_parse_value(field, value).map(Some),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            ))),
+        },
         _ => Err(ErrorCode::NotImplemented(
             "unsupported type for json_parse_value".to_string(),
             None.into(),
@@ -48,7 +48,8 @@ impl SourceParser for JSONParser {
 
 #[cfg(test)]
 mod tests {
-    use risingwave_common::catalog::ColumnId;
+    use risingwave_common::array::StructValue;
+    use risingwave_common::catalog::{ColumnDesc, ColumnId};
     use risingwave_common::types::{DataType, ScalarImpl};
 
     use super::super::common::str_to_date;
@@ -65,48 +66,56 @@ mod tests {
                 data_type: DataType::Int32,
                 column_id: ColumnId::from(0),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "bool".to_string(),
                 data_type: DataType::Boolean,
                 column_id: ColumnId::from(2),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "i16".to_string(),
                 data_type: DataType::Int16,
                 column_id: ColumnId::from(3),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "i64".to_string(),
                 data_type: DataType::Int64,
                 column_id: ColumnId::from(4),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "f32".to_string(),
                 data_type: DataType::Float32,
                 column_id: ColumnId::from(5),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "f64".to_string(),
                 data_type: DataType::Float64,
                 column_id: ColumnId::from(6),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "varchar".to_string(),
                 data_type: DataType::Varchar,
                 column_id: ColumnId::from(7),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "date".to_string(),
                 data_type: DataType::Date,
                 column_id: ColumnId::from(8),
                 skip_parse: false,
+                fields: vec![],
             },
         ];
 
@@ -139,4 +148,48 @@ mod tests {
         let result = parser.parse(payload, &descs);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_json_parser_struct() {
+        let parser = JSONParser {};
+
+        let payload = r#"{"country":{"address":"a","city":{"zipcode":"z"}}}"#.as_bytes();
+        let city = ColumnDesc::new_struct(
+            "country.city",
+            3,
+            "",
+            vec![
+                ColumnDesc::new_atomic(DataType::Varchar, "country.city.address", 4),
+                ColumnDesc::new_atomic(DataType::Varchar, "country.city.zipcode", 5),
+            ],
+        );
+        let country = ColumnDesc::new_struct(
+            "country",
+            1,
+            "",
+            vec![
+                ColumnDesc::new_atomic(DataType::Varchar, "country.address", 2),
+                city,
+            ],
+        );
+        let descs = vec![SourceColumnDesc::from(&country)];
+
+        let event = parser.parse(payload, &descs).unwrap();
+        let row = event.rows.first().unwrap();
+        assert_eq!(
+            row[0],
+            Some(ScalarImpl::Struct(StructValue::new(vec![
+                Some(ScalarImpl::Utf8("a".to_string())),
+                Some(ScalarImpl::Struct(StructValue::new(vec![
+                    None,
+                    Some(ScalarImpl::Utf8("z".to_string())),
+                ]))),
+            ])))
+        );
+
+        // A malformed field is not silently turned into a null field.
+        let payload = r#"{"country":{"address":1,"city":{"zipcode":"z"}}}"#.as_bytes();
+        let event = parser.parse(payload, &descs).unwrap();
+        assert_eq!(event.rows.first().unwrap()[0], None);
+    }
 }
@@ -16,7 +16,7 @@ use std::path::Path;
 
 use protobuf::descriptor::FileDescriptorSet;
 use protobuf::RepeatedField;
-use risingwave_common::array::Op;
+use risingwave_common::array::{Op, StructValue};
 use risingwave_common::error::ErrorCode::{self, InternalError, ItemNotFound, ProtocolError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::{DataType, Datum, Decimal, OrderedF32, OrderedF64, ScalarImpl};
@@ -224,6 +224,67 @@ fn protobuf_type_mapping(f: &FieldDescriptor, descriptors: &Descriptors) -> Resu
     Ok(t)
 }
 
+/// Converts a decoded protobuf value to a datum of the column's type. Nested messages are decoded
+/// as maps keyed by field names, and are converted to structs with the column's fields.
+fn protobuf_parse_value(column: &SourceColumnDesc, value: Option<Value>) -> Datum {
+    match column.data_type {
+        DataType::Boolean => {
+            protobuf_match_type!(value, ScalarImpl::Bool, { Bool }, bool)
+        }
+        DataType::Int16 => {
+            protobuf_match_type!(value, ScalarImpl::Int16, { I8, I16, U8 }, i16)
+        }
+        DataType::Int32 => {
+            protobuf_match_type!(value, ScalarImpl::Int32, { I8, I16, I32, U8, U16 }, i32)
+        }
+        DataType::Int64 => {
+            protobuf_match_type!(value, ScalarImpl::Int64, { I8, I16, I32, I64, U8, U16, U32 }, i64)
+        }
+        DataType::Float32 => {
+            protobuf_match_type!(value, ScalarImpl::Float32, { I8, I16, U8, U16, F32 }, OrderedF32)
+        }
+        DataType::Float64 => {
+            protobuf_match_type!(value, ScalarImpl::Float64, { I8, I16, I32, U8, U16, U32, F32, F64}, OrderedF64)
+        }
+        DataType::Decimal { .. } => {
+            protobuf_match_type!(value, ScalarImpl::Decimal, { I8, I16, I32, I64, U8, U16, U32, U64}, Decimal)
+        }
+        DataType::Varchar => {
+            protobuf_match_type!(value, ScalarImpl::Utf8, { String }, String)
+        }
+        DataType::Date => value
+            .and_then(|v| match v {
+                Value::String(b) => str_to_date(&b).ok(),
+                Value::Option(Some(boxed_value)) => match *boxed_value {
+                    Value::String(b) => str_to_date(&b).ok(),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .map(ScalarImpl::NaiveDate),
+        DataType::Struct { .. } => {
+            let mut map = match value {
+                Some(Value::Map(m)) => m,
+                Some(Value::Option(Some(boxed_value))) => match *boxed_value {
+                    Value::Map(m) => m,
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            Some(ScalarImpl::Struct(StructValue::new(
+                column
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        protobuf_parse_value(field, map.remove(&Value::String(field.name.clone())))
+                    })
+                    .collect(),
+            )))
+        }
+        _ => unimplemented!(),
+    }
+}
+
 impl SourceParser for ProtobufParser {
     fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
         let mut map = match self.decode(payload)? {
@@ -231,53 +292,20 @@ impl SourceParser for ProtobufParser {
             _ => return Err(RwError::from(ProtocolError("".to_string()))),
         };
 
-        let row = columns.iter().map(|column| {
-            if column.skip_parse {
-                return None;
-            }
+        let row = columns
+            .iter()
+            .map(|column| {
+                if column.skip_parse {
+                    return None;
+                }
 
-            let key = Value::String(column.name.clone());
+                let key = Value::String(column.name.clone());
 
-            // Use `remove` instead of `get` to take the ownership of the value
-            let value = map.remove(&key);
-            match column.data_type {
-                DataType::Boolean => {
-                    protobuf_match_type!(value, ScalarImpl::Bool, { Bool }, bool)
-                }
-                DataType::Int16 => {
-                    protobuf_match_type!(value, ScalarImpl::Int16, { I8, I16, U8 }, i16)
-                }
-                DataType::Int32 => {
-                    protobuf_match_type!(value, ScalarImpl::Int32, { I8, I16, I32, U8, U16 }, i32)
-                }
-                DataType::Int64 => {
-                    protobuf_match_type!(value, ScalarImpl::Int64, { I8, I16, I32, I64, U8, U16, U32 }, i64)
-                }
-                DataType::Float32 => {
-                    protobuf_match_type!(value, ScalarImpl::Float32, { I8, I16, U8, U16, F32 }, OrderedF32)
-                }
-                DataType::Float64 => {
-                    protobuf_match_type!(value, ScalarImpl::Float64, { I8, I16, I32, U8, U16, U32, F32, F64}, OrderedF64)
-                }
-                DataType::Decimal => {
-                    protobuf_match_type!(value, ScalarImpl::Decimal, { I8, I16, I32, I64, U8, U16, U32, U64}, Decimal)
-                }
-                DataType::Varchar => {
-                    protobuf_match_type!(value, ScalarImpl::Utf8, { String }, String)
-                }
-                DataType::Date => {
-                    value.and_then(|v| match v {
-                        Value::String(b) => str_to_date(&b).ok(),
-                        Value::Option(Some(boxed_value)) => match *boxed_value {
-                            Value::String(b) => str_to_date(&b).ok(),
-                            _ => None,
-                        }
-                        _ => None,
-                    }).map(ScalarImpl::NaiveDate)
-                }
-                _ => unimplemented!(),
-            }
-        }).collect::<Vec<Datum>>();
+                // Use `remove` instead of `get` to take the ownership of the value
+                let value = map.remove(&key);
+                protobuf_parse_value(column, value)
+            })
+            .collect::<Vec<Datum>>();
 
         Ok(Event {
             ops: vec![Op::Insert],
@@ -291,6 +319,7 @@ mod tests {
     use std::io::Write;
 
     use maplit::hashmap;
+    use risingwave_common::array::StructValue;
     use risingwave_common::catalog::ColumnId;
     use risingwave_common::error::Result;
     use risingwave_common::types::{DataType, ScalarImpl};
@@ -428,36 +457,42 @@ mod tests {
                 data_type: DataType::Int32,
                 column_id: ColumnId::from(0),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "address".to_string(),
                 data_type: DataType::Varchar,
                 column_id: ColumnId::from(1),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "city".to_string(),
                 data_type: DataType::Varchar,
                 column_id: ColumnId::from(2),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "zipcode".to_string(),
                 data_type: DataType::Int64,
                 column_id: ColumnId::from(3),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "rate".to_string(),
                 data_type: DataType::Float32,
                 column_id: ColumnId::from(4),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "date".to_string(),
                 data_type: DataType::Date,
                 column_id: ColumnId::from(5),
                 skip_parse: false,
+                fields: vec![],
             },
         ];
 
@@ -501,4 +536,39 @@ mod tests {
             ]
         );
     }
+
+    //    Id:      1,
+    //    Country: {
+    //        Address: "a",
+    //        City:    { Address: "b", Zipcode: "c" },
+    //        Zipcode: "z"
+    //    }
+    static PRE_GEN_PROTO_NESTED_DATA: &[u8] =
+        b"\x08\x01\x1a\x0e\x0a\x01\x61\x12\x06\x0a\x01\x62\x12\x01\x63\x1a\x01\x7a";
+
+    #[test]
+    fn test_parser_parse_nested() {
+        let parser = create_parser(PROTO_NESTED_FILE_DATA).unwrap();
+        let descs = parser
+            .map_to_columns()
+            .unwrap()
+            .iter()
+            .map(|c| SourceColumnDesc::from(&risingwave_common::catalog::ColumnDesc::from(c)))
+            .collect::<Vec<_>>();
+
+        let event = parser.parse(PRE_GEN_PROTO_NESTED_DATA, &descs).unwrap();
+        let row = event.rows.first().unwrap();
+        assert_eq!(row[0], Some(ScalarImpl::Int32(1)));
+        assert_eq!(
+            row[1],
+            Some(ScalarImpl::Struct(StructValue::new(vec![
+                Some(ScalarImpl::Utf8("a".to_string())),
+                Some(ScalarImpl::Struct(StructValue::new(vec![
+                    Some(ScalarImpl::Utf8("b".to_string())),
+                    Some(ScalarImpl::Utf8("c".to_string())),
+                ]))),
+                Some(ScalarImpl::Utf8("z".to_string())),
+            ])))
+        );
+    }
 }
@@ -117,6 +117,24 @@ impl fmt::Display for AlterTableOperation {
     }
 }
 
+/// An `ALTER MATERIALIZED VIEW` (`Statement::AlterMaterializedView`) operation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AlterMaterializedViewOperation {
+    /// `SET PARALLELISM TO <parallelism>`
+    SetParallelism { parallelism: u64 },
+}
+
+impl fmt::Display for AlterMaterializedViewOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlterMaterializedViewOperation::SetParallelism { parallelism } => {
+                write!(f, "SET PARALLELISM TO {}", parallelism)
+            }
+        }
+    }
+}
+
 /// An `ALTER COLUMN` (`Statement::AlterTable`) operation
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -30,10 +30,10 @@ use itertools::Itertools;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-pub use self::data_type::DataType;
+pub use self::data_type::{DataType, StructField};
 pub use self::ddl::{
-    AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, ColumnOptionDef,
-    ReferentialAction, TableConstraint,
+    AlterColumnOperation, AlterMaterializedViewOperation, AlterTableOperation, ColumnDef,
+    ColumnOption, ColumnOptionDef, ReferentialAction, TableConstraint,
 };
 pub use self::operator::{BinaryOperator, UnaryOperator};
 pub use self::query::{
@@ -767,6 +767,12 @@ pub enum Statement {
         name: ObjectName,
         operation: AlterTableOperation,
     },
+    /// ALTER MATERIALIZED VIEW
+    AlterMaterializedView {
+        /// Materialized view name
+        name: ObjectName,
+        operation: AlterMaterializedViewOperation,
+    },
     /// DESCRIBE TABLE OR SOURCE
     Describe {
         /// Table or Source name
@@ -1107,6 +1113,9 @@ impl fmt::Display for Statement {
             Statement::AlterTable { name, operation } => {
                 write!(f, "ALTER TABLE {} {}", name, operation)
             }
+            Statement::AlterMaterializedView { name, operation } => {
+                write!(f, "ALTER MATERIALIZED VIEW {} {}", name, operation)
+            }
             Statement::Drop(stmt) => write!(f, "DROP {}", stmt),
             Statement::SetVariable {
                 local,
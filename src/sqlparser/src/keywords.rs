@@ -316,6 +316,7 @@ define_keywords!(
     OVER,
     OVERLAPS,
     OVERLAY,
+    PARALLELISM,
     PARAMETER,
     PARQUET,
     PARTITION,
@@ -417,6 +418,7 @@ define_keywords!(
     STDIN,
     STORED,
     STRING,
+    STRUCT,
     SUBMULTISET,
     SUBSTRING,
     SUBSTRING_REGEX,
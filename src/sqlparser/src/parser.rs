@@ -1058,13 +1058,10 @@ impl Parser {
             debug!("Tok: {}", tok);
             key_parts.push(key);
         }
-        match expr {
-            e @ Expr::Identifier(_) | e @ Expr::CompoundIdentifier(_) => Ok(Expr::MapAccess {
-                column: Box::new(e),
-                keys: key_parts,
-            }),
-            _ => Ok(expr),
-        }
+        Ok(Expr::MapAccess {
+            column: Box::new(expr),
+            keys: key_parts,
+        })
     }
 
     /// Parses the parens following the `[ NOT ] IN` operator
@@ -1716,8 +1713,25 @@ impl Parser {
     }
 
     pub fn parse_alter(&mut self) -> Result<Statement, ParserError> {
-        self.expect_keyword(Keyword::TABLE)?;
-        self.parse_alter_table()
+        if self.parse_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW]) {
+            self.parse_alter_materialized_view()
+        } else {
+            self.expect_keyword(Keyword::TABLE)?;
+            self.parse_alter_table()
+        }
+    }
+
+    pub fn parse_alter_materialized_view(&mut self) -> Result<Statement, ParserError> {
+        let name = self.parse_object_name()?;
+        self.expect_keywords(&[Keyword::SET, Keyword::PARALLELISM])?;
+        if !self.consume_token(&Token::Eq) {
+            self.expect_keyword(Keyword::TO)?;
+        }
+        let parallelism = self.parse_literal_uint()?;
+        Ok(Statement::AlterMaterializedView {
+            name,
+            operation: AlterMaterializedViewOperation::SetParallelism { parallelism },
+        })
     }
 
     pub fn parse_alter_table(&mut self) -> Result<Statement, ParserError> {
@@ -1991,6 +2005,7 @@ impl Parser {
                     let (precision, scale) = self.parse_optional_precision_scale()?;
                     Ok(DataType::Decimal(precision, scale))
                 }
+                Keyword::STRUCT => Ok(DataType::Struct(self.parse_struct_fields()?)),
                 _ => {
                     self.prev_token();
                     let type_name = self.parse_object_name()?;
@@ -2001,6 +2016,33 @@ impl Parser {
         }
     }
 
+    /// Parse the field list of a struct type, e.g. `<a INT, b STRUCT<c VARCHAR>>`
+    pub fn parse_struct_fields(&mut self) -> Result<Vec<StructField>, ParserError> {
+        self.expect_token(&Token::Lt)?;
+        let fields = self.parse_comma_separated(|parser| {
+            Ok(StructField {
+                name: parser.parse_identifier()?,
+                data_type: parser.parse_data_type()?,
+            })
+        })?;
+        self.expect_closing_angle_bracket()?;
+        Ok(fields)
+    }
+
+    /// Consume a `>`. The tokenizer produces `>>` for the end of nested struct types such as
+    /// `STRUCT<a STRUCT<b INT>>`, in which case only the first `>` is consumed.
+    fn expect_closing_angle_bracket(&mut self) -> Result<(), ParserError> {
+        match self.next_token() {
+            Token::Gt => Ok(()),
+            Token::ShiftRight => {
+                self.index -= 1;
+                self.tokens[self.index] = Token::Gt;
+                Ok(())
+            }
+            unexpected => self.expected(">", unexpected),
+        }
+    }
+
     /// Parse `AS identifier` (or simply `identifier` if it's not a reserved keyword)
     /// Some examples with aliases: `SELECT 1 foo`, `SELECT COUNT(*) AS cnt`,
     /// `SELECT ... FROM t1 foo, t2 bar`, `SELECT ... FROM (...) AS bar`
@@ -1550,6 +1550,27 @@ fn parse_alter_table() {
     }
 }
 
+#[test]
+fn parse_alter_materialized_view() {
+    let set_parallelism = "ALTER MATERIALIZED VIEW mv SET PARALLELISM TO 4";
+    match verified_stmt(set_parallelism) {
+        Statement::AlterMaterializedView {
+            name,
+            operation: AlterMaterializedViewOperation::SetParallelism { parallelism },
+        } => {
+            assert_eq!("mv", name.to_string());
+            assert_eq!(parallelism, 4);
+        }
+        _ => unreachable!(),
+    }
+
+    one_statement_parses_to(
+        "ALTER MATERIALIZED VIEW mv SET PARALLELISM = 4",
+        "ALTER MATERIALIZED VIEW mv SET PARALLELISM TO 4",
+    );
+    assert!(parse_sql_statements("ALTER MATERIALIZED VIEW mv SET PARALLELISM TO x").is_err());
+}
+
 #[test]
 fn parse_alter_table_constraints() {
     check_one("CONSTRAINT address_pkey PRIMARY KEY (address_id)");
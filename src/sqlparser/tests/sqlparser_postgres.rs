@@ -231,6 +231,33 @@ fn parse_create_table_empty() {
     let _ = verified_stmt("CREATE TABLE t ()");
 }
 
+#[test]
+fn parse_create_table_with_struct() {
+    let sql = "CREATE TABLE t (v1 STRUCT<a INT, b STRUCT<c CHARACTER VARYING>>, v2 INT)";
+    match verified_stmt(sql) {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(
+                columns[0].data_type,
+                DataType::Struct(vec![
+                    StructField {
+                        name: "a".into(),
+                        data_type: DataType::Int(None),
+                    },
+                    StructField {
+                        name: "b".into(),
+                        data_type: DataType::Struct(vec![StructField {
+                            name: "c".into(),
+                            data_type: DataType::Varchar(None),
+                        }]),
+                    },
+                ])
+            );
+            assert_eq!(columns[1].data_type, DataType::Int(None));
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_create_table_constraints_only() {
     // Zero-column tables can also have constraints in PostgreSQL
@@ -741,6 +768,18 @@ fn parse_map_access_expr() {
         },
         expr_from_projection(only(&select.projection)),
     );
+    let sql = "SELECT (ARRAY[1, 2])[2] FROM foos";
+    let select = verified_only_select(sql);
+    assert_eq!(
+        &MapAccess {
+            column: Box::new(Expr::Nested(Box::new(Expr::Array(vec![
+                Expr::Value(Value::Number("1".to_string(), false)),
+                Expr::Value(Value::Number("2".to_string(), false))
+            ])))),
+            keys: vec![Expr::Value(Value::Number("2".to_string(), false))]
+        },
+        expr_from_projection(only(&select.projection)),
+    );
 }
 
 #[test]
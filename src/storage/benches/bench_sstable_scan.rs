@@ -0,0 +1,115 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use risingwave_hummock_sdk::key::key_with_epoch;
+use risingwave_storage::hummock::value::HummockValue;
+use risingwave_storage::hummock::{
+    CachePolicy, SSTableBuilder, SSTableBuilderOptions, Sstable, SstableStore,
+};
+use risingwave_storage::monitor::StateStoreMetrics;
+use risingwave_storage::object::{InMemObjectStore, ObjectStoreImpl};
+use tokio::runtime::Runtime;
+
+const KEY_COUNT: usize = 100_000;
+const BLOCK_CAPACITY: usize = 4096;
+const PREFETCH_BLOCK_COUNTS: [usize; 3] = [4, 16, 64];
+
+fn build_sstable_store(prefetch_block_count: usize) -> Arc<SstableStore> {
+    let object_store = Arc::new(ObjectStoreImpl::Mem(InMemObjectStore::new()));
+    Arc::new(
+        SstableStore::new(
+            object_store,
+            "bench".to_string(),
+            Arc::new(StateStoreMetrics::unused()),
+            64 << 20,
+            64 << 20,
+        )
+        .with_prefetch_block_count(prefetch_block_count),
+    )
+}
+
+async fn build_sstable(sstable_store: &SstableStore) -> Sstable {
+    let mut builder = SSTableBuilder::new(SSTableBuilderOptions {
+        capacity: 256 << 20,
+        block_capacity: BLOCK_CAPACITY,
+        ..Default::default()
+    });
+    let value = vec![b'v'; 64];
+    for i in 0..KEY_COUNT {
+        let key = key_with_epoch(format!("key_{:08}", i).into_bytes(), 1);
+        builder.add(&key, HummockValue::put(value.as_slice()));
+    }
+    let (data, meta) = builder.finish();
+    let sst = Sstable { id: 1, meta };
+    // Blocks are not cached, so that every scan reads the object store.
+    sstable_store
+        .put(&sst, data, CachePolicy::Disable)
+        .await
+        .unwrap();
+    sst
+}
+
+/// Scans all blocks one at a time, like a scan without prefetching.
+async fn scan_block_by_block(sstable_store: &SstableStore, sst: &Sstable) {
+    for block_index in 0..sst.block_count() as u64 {
+        sstable_store
+            .get(sst, block_index, CachePolicy::Disable)
+            .await
+            .unwrap();
+    }
+}
+
+/// Scans all blocks reading `prefetch_block_count` blocks ahead at a time.
+async fn scan_with_prefetch(sstable_store: &SstableStore, sst: &Sstable) {
+    let block_count = sst.block_count() as u64;
+    let prefetch_block_count = sstable_store.prefetch_block_count() as u64;
+    let mut block_index = 0;
+    while block_index < block_count {
+        let end_index = std::cmp::min(block_index + prefetch_block_count, block_count);
+        sstable_store
+            .get_blocks(sst, block_index..end_index)
+            .await
+            .unwrap();
+        block_index = end_index;
+    }
+}
+
+fn bench_sstable_scan(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let sstable_store = build_sstable_store(0);
+    let sst = runtime.block_on(build_sstable(&sstable_store));
+    println!("block count: {}", sst.block_count());
+    c.bench_function("sstable scan - block by block", |b| {
+        b.iter(|| runtime.block_on(scan_block_by_block(&sstable_store, &sst)));
+    });
+
+    for prefetch_block_count in PREFETCH_BLOCK_COUNTS {
+        let sstable_store = build_sstable_store(prefetch_block_count);
+        let sst = runtime.block_on(build_sstable(&sstable_store));
+        c.bench_with_input(
+            BenchmarkId::new("sstable scan - prefetch", prefetch_block_count),
+            &(sstable_store, sst),
+            |b, (sstable_store, sst)| {
+                b.iter(|| runtime.block_on(scan_with_prefetch(sstable_store, sst)));
+            },
+        );
+    }
+}
+
+criterion_group!(benches, bench_sstable_scan);
+criterion_main!(benches);
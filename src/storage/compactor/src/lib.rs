@@ -0,0 +1,61 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![warn(clippy::dbg_macro)]
+#![warn(clippy::disallowed_methods)]
+#![warn(clippy::doc_markdown)]
+#![warn(clippy::explicit_into_iter_loop)]
+#![warn(clippy::explicit_iter_loop)]
+#![warn(clippy::inconsistent_struct_constructor)]
+#![warn(clippy::map_flatten)]
+#![warn(clippy::no_effect_underscore_binding)]
+#![warn(clippy::await_holding_lock)]
+#![deny(unused_must_use)]
+#![deny(rustdoc::broken_intra_doc_links)]
+#![cfg_attr(coverage, feature(no_coverage))]
+
+pub mod server;
+
+use clap::Parser;
+
+/// Command-line arguments for compactor.
+#[derive(Parser, Debug)]
+pub struct CompactorOpts {
+    /// The address registered to meta. The compactor doesn't serve any service on it for now.
+    #[clap(long, default_value = "127.0.0.1:6660")]
+    pub host: String,
+
+    /// Hummock state store url shared with compute nodes, e.g. `hummock+s3://bucket`.
+    #[clap(long)]
+    pub state_store: String,
+
+    #[clap(long, default_value = "http://127.0.0.1:5690")]
+    pub meta_address: String,
+
+    /// No given `config_path` means to use default config.
+    #[clap(long, default_value = "")]
+    pub config_path: String,
+}
+
+use crate::server::compactor_serve;
+
+/// Start compactor
+pub async fn start(opts: CompactorOpts) {
+    tracing::info!("meta address: {}", opts.meta_address.clone());
+
+    let client_address = opts.host.parse().unwrap();
+    tracing::info!("Client address is {}", client_address);
+
+    let (join_handle, _shutdown_send) = compactor_serve(client_address, opts).await;
+    join_handle.await.unwrap();
+}
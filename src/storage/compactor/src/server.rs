@@ -0,0 +1,116 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use risingwave_common::config::ComputeNodeConfig;
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::common::WorkerType;
+use risingwave_rpc_client::MetaClient;
+use risingwave_storage::hummock::compactor::Compactor;
+use risingwave_storage::hummock::hummock_meta_client::MonitoredHummockMetaClient;
+use risingwave_storage::monitor::{HummockMetrics, StateStoreMetrics};
+use risingwave_storage::store_impl::new_hummock_sstable_store;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+
+use crate::CompactorOpts;
+
+/// The compactor shares `risingwave.toml` with compute nodes, and only reads its `server` and
+/// `storage` sections.
+fn load_config(opts: &CompactorOpts) -> ComputeNodeConfig {
+    if opts.config_path.is_empty() {
+        return ComputeNodeConfig::default();
+    }
+
+    let config_path = PathBuf::from(opts.config_path.to_owned());
+    ComputeNodeConfig::init(config_path).unwrap()
+}
+
+/// Bootstraps the compactor, which executes compact tasks and vacuum tasks assigned by meta.
+pub async fn compactor_serve(
+    client_addr: HostAddr,
+    opts: CompactorOpts,
+) -> (JoinHandle<()>, UnboundedSender<()>) {
+    // Load the configuration.
+    let config = load_config(&opts);
+    tracing::info!("Starting compactor with config {:?}", config);
+
+    let mut meta_client = MetaClient::new(&opts.meta_address).await.unwrap();
+
+    // Register to the cluster.
+    let worker_id = meta_client
+        .register(&client_addr, WorkerType::Compactor, HashMap::new())
+        .await
+        .unwrap();
+    tracing::info!("Assigned compactor id {}", worker_id);
+
+    let mut sub_tasks: Vec<(JoinHandle<()>, UnboundedSender<()>)> =
+        vec![MetaClient::start_heartbeat_loop(
+            meta_client.clone(),
+            Duration::from_millis(config.server.heartbeat_interval as u64),
+        )];
+
+    // Initialize the metrics subsystem.
+    let registry = prometheus::Registry::new();
+    let hummock_metrics = Arc::new(HummockMetrics::new(registry.clone()));
+    let state_store_metrics = Arc::new(StateStoreMetrics::new(registry));
+
+    let storage_config = Arc::new(config.storage);
+    let sstable_store = new_hummock_sstable_store(
+        &opts.state_store,
+        &storage_config,
+        state_store_metrics.clone(),
+    )
+    .await
+    .unwrap();
+    let hummock_meta_client = Arc::new(MonitoredHummockMetaClient::new(
+        meta_client.clone(),
+        hummock_metrics,
+    ));
+
+    // State TTLs come with the compact tasks, and prefix extractors with the input SSTs, so the
+    // compactor doesn't rely on the executors on compute nodes.
+    sub_tasks.push(Compactor::start_compactor(
+        storage_config,
+        hummock_meta_client,
+        sstable_store,
+        state_store_metrics,
+    ));
+
+    // All set, let the meta service know we're ready.
+    meta_client.activate(&client_addr).await.unwrap();
+
+    let (shutdown_send, mut shutdown_recv) = tokio::sync::mpsc::unbounded_channel();
+    let join_handle = tokio::spawn(async move {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {},
+            _ = shutdown_recv.recv() => {
+                for (join_handle, shutdown_sender) in sub_tasks {
+                    if let Err(err) = shutdown_sender.send(()) {
+                        tracing::warn!("Failed to send shutdown: {:?}", err);
+                        continue;
+                    }
+                    if let Err(err) = join_handle.await {
+                        tracing::warn!("Failed to join shutdown: {:?}", err);
+                    }
+                }
+            },
+        }
+    });
+
+    (join_handle, shutdown_send)
+}
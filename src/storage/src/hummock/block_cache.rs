@@ -12,35 +12,101 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
-use bytes::{BufMut, Bytes, BytesMut};
 use futures::Future;
-use moka::future::Cache;
+use lru::LruCache;
+use parking_lot::Mutex;
+use tokio::sync::OnceCell;
 
-use super::{Block, HummockError, HummockResult, DEFAULT_ENTRY_SIZE};
+use super::{Block, HummockResult};
+
+const BLOCK_CACHE_SHARD_COUNT: usize = 16;
+
+/// Called with the sst id, the block index and the block of each entry evicted from the
+/// [`BlockCache`].
+pub type BlockEvictionListener = Box<dyn Fn(u64, u64, Arc<Block>) + Send + Sync>;
+
+/// A shard of the [`BlockCache`], evicting in LRU order once the size of the blocks exceeds its
+/// capacity.
+struct BlockCacheShard {
+    lru: LruCache<(u64, u64), Arc<Block>>,
+    capacity: usize,
+    size: usize,
+}
+
+impl BlockCacheShard {
+    /// Inserts `block`, and returns the entries evicted to make room for it.
+    fn insert(&mut self, key: (u64, u64), block: Arc<Block>) -> Vec<((u64, u64), Arc<Block>)> {
+        self.size += block.len();
+        if let Some(old) = self.lru.put(key, block) {
+            self.size -= old.len();
+        }
+        let mut evicted = vec![];
+        while self.size > self.capacity {
+            match self.lru.pop_lru() {
+                Some((key, block)) => {
+                    self.size -= block.len();
+                    evicted.push((key, block));
+                }
+                None => break,
+            }
+        }
+        evicted
+    }
+}
 
 pub struct BlockCache {
-    inner: Cache<Bytes, Arc<Block>>,
+    shards: Vec<Mutex<BlockCacheShard>>,
+    /// Blocks being loaded by [`BlockCache::get_or_insert_with`], so that concurrent misses of a
+    /// block load it only once.
+    loading: Mutex<HashMap<(u64, u64), Arc<OnceCell<Arc<Block>>>>>,
+    eviction_listener: Option<BlockEvictionListener>,
 }
 
 impl BlockCache {
     pub fn new(capacity: usize) -> Self {
-        let cache: Cache<Bytes, Arc<Block>> = Cache::builder()
-            .weigher(|_k, v: &Arc<Block>| v.len() as u32)
-            .initial_capacity(capacity / DEFAULT_ENTRY_SIZE)
-            .max_capacity(capacity as u64)
-            .build();
-        Self { inner: cache }
+        let shards = (0..BLOCK_CACHE_SHARD_COUNT)
+            .map(|_| {
+                Mutex::new(BlockCacheShard {
+                    lru: LruCache::unbounded(),
+                    capacity: capacity / BLOCK_CACHE_SHARD_COUNT,
+                    size: 0,
+                })
+            })
+            .collect();
+        Self {
+            shards,
+            loading: Mutex::new(HashMap::new()),
+            eviction_listener: None,
+        }
+    }
+
+    /// Calls `listener` with the blocks evicted from the cache. Blocks replaced by an insert of
+    /// the same key are not evicted.
+    #[must_use]
+    pub fn with_eviction_listener(mut self, listener: BlockEvictionListener) -> Self {
+        self.eviction_listener = Some(listener);
+        self
     }
 
-    // TODO: Optimize for concurrent get https://github.com/singularity-data/risingwave/pull/627#discussion_r817354730.
     pub fn get(&self, sst_id: u64, block_idx: u64) -> Option<Arc<Block>> {
-        self.inner.get(&Self::key(sst_id, block_idx))
+        let key = (sst_id, block_idx);
+        self.shard(key).lock().lru.get(&key).cloned()
     }
 
-    pub async fn insert(&self, sst_id: u64, block_idx: u64, block: Arc<Block>) {
-        self.inner.insert(Self::key(sst_id, block_idx), block).await
+    pub fn insert(&self, sst_id: u64, block_idx: u64, block: Arc<Block>) {
+        let key = (sst_id, block_idx);
+        let evicted = self.shard(key).lock().insert(key, block);
+        // The listener is called without holding the lock of the shard.
+        if let Some(listener) = &self.eviction_listener {
+            for ((sst_id, block_idx), block) in evicted {
+                listener(sst_id, block_idx, block);
+            }
+        }
     }
 
     pub async fn get_or_insert_with<F>(
@@ -52,16 +118,86 @@ impl BlockCache {
     where
         F: Future<Output = HummockResult<Arc<Block>>>,
     {
-        self.inner
-            .try_get_with(Self::key(sst_id, block_idx), f)
+        if let Some(block) = self.get(sst_id, block_idx) {
+            return Ok(block);
+        }
+
+        let key = (sst_id, block_idx);
+        let cell = self.loading.lock().entry(key).or_default().clone();
+        let result = cell
+            .get_or_try_init(|| async {
+                let block = f.await?;
+                self.insert(sst_id, block_idx, block.clone());
+                Ok(block)
+            })
             .await
-            .map_err(HummockError::other)
+            .cloned();
+        // Once the block is in the cache or failed to load, later misses start a new load.
+        let mut loading = self.loading.lock();
+        if matches!(loading.get(&key), Some(loading_cell) if Arc::ptr_eq(loading_cell, &cell)) {
+            loading.remove(&key);
+        }
+        result
+    }
+
+    fn shard(&self, key: (u64, u64)) -> &Mutex<BlockCacheShard> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % BLOCK_CACHE_SHARD_COUNT]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::hummock::{BlockBuilder, BlockBuilderOptions};
+
+    fn test_block() -> Arc<Block> {
+        let mut builder = BlockBuilder::new(BlockBuilderOptions::default());
+        builder.add(b"k1", b"v1");
+        Arc::new(Block::decode(builder.build()).unwrap())
+    }
+
+    #[test]
+    fn test_block_cache_eviction_listener() {
+        let evicted = Arc::new(Mutex::new(vec![]));
+        let cache = BlockCache::new(0).with_eviction_listener(Box::new({
+            let evicted = evicted.clone();
+            move |sst_id, block_idx, _| evicted.lock().push((sst_id, block_idx))
+        }));
+        // Blocks are evicted right away as there's no room for them.
+        cache.insert(1, 2, test_block());
+        assert!(cache.get(1, 2).is_none());
+        assert_eq!(*evicted.lock(), vec![(1, 2)]);
+
+        let cache = BlockCache::new(1 << 20).with_eviction_listener(Box::new({
+            let evicted = evicted.clone();
+            move |sst_id, block_idx, _| evicted.lock().push((sst_id, block_idx))
+        }));
+        cache.insert(1, 3, test_block());
+        cache.insert(1, 3, test_block());
+        assert!(cache.get(1, 3).is_some());
+        assert_eq!(*evicted.lock(), vec![(1, 2)]);
     }
 
-    fn key(sst_id: u64, block_idx: u64) -> Bytes {
-        let mut key = BytesMut::with_capacity(16);
-        key.put_u64_le(sst_id);
-        key.put_u64_le(block_idx);
-        key.freeze()
+    #[tokio::test]
+    async fn test_block_cache_load_once() {
+        let cache = BlockCache::new(1 << 20);
+        let loads = AtomicUsize::new(0);
+        let load = || async {
+            loads.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            Ok(test_block())
+        };
+        let (block1, block2) = futures::future::join(
+            cache.get_or_insert_with(1, 2, load()),
+            cache.get_or_insert_with(1, 2, load()),
+        )
+        .await;
+        assert!(Arc::ptr_eq(&block1.unwrap(), &block2.unwrap()));
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert!(cache.loading.lock().is_empty());
     }
 }
@@ -21,7 +21,7 @@ use futures::Future;
 use itertools::Itertools;
 use risingwave_common::config::StorageConfig;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
-use risingwave_hummock_sdk::key::{get_epoch, Epoch, FullKey};
+use risingwave_hummock_sdk::key::{get_epoch, key_with_epoch, user_key, Epoch, FullKey};
 use risingwave_hummock_sdk::key_range::KeyRange;
 use risingwave_hummock_sdk::VersionedComparator;
 use risingwave_pb::hummock::{
@@ -36,7 +36,9 @@ use super::multi_builder::CapacitySplitTableBuilder;
 use super::shared_buffer::shared_buffer_batch::SharedBufferBatch;
 use super::sstable_store::SstableStoreRef;
 use super::{
-    HummockError, HummockResult, HummockStorage, SSTableBuilder, SSTableIterator, Sstable,
+    deleted_by_range_tombstones, HummockError, HummockResult, HummockStorage, PrefixExtractor,
+    PrefixExtractorRegistry, RangeTombstone, SSTableBuilder, SSTableIterator, Sstable,
+    StateTtlRegistry,
 };
 use crate::hummock::vacuum::Vacuum;
 use crate::monitor::StateStoreMetrics;
@@ -58,6 +60,11 @@ pub struct CompactorContext {
 
     /// True if it is a memory compaction (from shared buffer).
     pub is_share_buffer_compact: bool,
+
+    /// Prefix extractors registered by the executors, to build the prefix bloom filters of the
+    /// tables compacted from shared buffer with. Compactions of tables keep the extractors
+    /// recorded in the input tables instead, so that they don't rely on the executors.
+    pub prefix_extractors: Option<Arc<PrefixExtractorRegistry>>,
 }
 
 #[derive(Clone)]
@@ -70,14 +77,22 @@ pub struct Compactor {
     /// When it's local compaction from memory, it uses a locally
     /// constructed compaction task.
     compact_task: CompactTask,
+
+    /// Extractors to build the prefix bloom filters of the output tables with.
+    prefix_extractors: Vec<PrefixExtractor>,
 }
 
 impl Compactor {
     /// Create a new compactor.
-    pub fn new(context: Arc<CompactorContext>, compact_task: CompactTask) -> Self {
+    pub fn new(
+        context: Arc<CompactorContext>,
+        compact_task: CompactTask,
+        prefix_extractors: Vec<PrefixExtractor>,
+    ) -> Self {
         Self {
             context,
             compact_task,
+            prefix_extractors,
         }
     }
 
@@ -122,13 +137,21 @@ impl Compactor {
             is_target_ultimate_and_leveling: false,
             metrics: None,
             task_status: false,
+            // Data in shared buffer is just written, and the watermark above doesn't tell the read
+            // epochs to expire keys for.
+            state_ttls: vec![],
         };
 
         let parallelism = compact_task.splits.len();
         let mut compact_success = true;
         let mut output_ssts = Vec::with_capacity(parallelism);
         let mut compaction_futures = vec![];
-        let compactor = Compactor::new(context, compact_task.clone());
+        let prefix_extractors = context
+            .prefix_extractors
+            .as_ref()
+            .map(|registry| registry.extractors())
+            .unwrap_or_default();
+        let compactor = Compactor::new(context, compact_task.clone(), prefix_extractors);
 
         for (split_index, _) in compact_task.splits.iter().enumerate() {
             let compactor = compactor.clone();
@@ -176,18 +199,40 @@ impl Compactor {
 
     /// Handle a compaction task and report its status to hummock manager.
     /// Always return `Ok` and let hummock manager handle errors.
-    pub async fn compact(context: Arc<CompactorContext>, compact_task: CompactTask) {
+    pub async fn compact(context: Arc<CompactorContext>, mut compact_task: CompactTask) {
+        if let Err(e) = Compactor::split_by_block_boundaries(&context, &mut compact_task).await {
+            tracing::warn!(
+                "Failed to split compaction task {} into sub-compactions: {}",
+                compact_task.task_id,
+                e
+            );
+        }
         tracing::debug!(
             "Ready to handle compaction task: \n{}",
             compact_task_to_string(compact_task.clone())
         );
 
+        let prefix_extractors =
+            match Compactor::input_prefix_extractors(&context, &compact_task).await {
+                Ok(prefix_extractors) => prefix_extractors,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to get the prefix extractors of compaction task {}: {}",
+                        compact_task.task_id,
+                        e
+                    );
+                    let mut compactor = Compactor::new(context, compact_task, vec![]);
+                    compactor.compact_done(&[], false).await;
+                    return;
+                }
+            };
+
         // Number of splits (key ranges) is equal to number of compaction tasks
         let parallelism = compact_task.splits.len();
         let mut compact_success = true;
         let mut output_ssts = Vec::with_capacity(parallelism);
         let mut compaction_futures = vec![];
-        let mut compactor = Compactor::new(context, compact_task.clone());
+        let mut compactor = Compactor::new(context, compact_task.clone(), prefix_extractors);
 
         for (split_index, _) in compact_task.splits.iter().enumerate() {
             let compactor = compactor.clone();
@@ -221,6 +266,65 @@ impl Compactor {
         compactor.compact_done(&output_ssts, compact_success).await;
     }
 
+    /// Refines the splits of `compact_task` with the smallest keys of the blocks of the input ssts,
+    /// so that a large task runs in up to `max_sub_compaction` concurrent sub-compactions.
+    async fn split_by_block_boundaries(
+        context: &CompactorContext,
+        compact_task: &mut CompactTask,
+    ) -> HummockResult<()> {
+        let max_sub_compaction = context.options.max_sub_compaction as usize;
+        if compact_task.splits.is_empty() || compact_task.splits.len() >= max_sub_compaction {
+            return Ok(());
+        }
+
+        let mut block_keys = vec![];
+        for level_entry in &compact_task.input_ssts {
+            let level = level_entry.level.as_ref().unwrap();
+            let table_ids = level.table_infos.iter().map(|sst| sst.id).collect_vec();
+            for table in context.sstable_store.sstables(&table_ids).await? {
+                block_keys.extend(
+                    table
+                        .meta
+                        .block_metas
+                        .iter()
+                        .map(|block_meta| user_key(&block_meta.smallest_key).to_vec()),
+                );
+            }
+        }
+
+        let splits = compact_task
+            .splits
+            .iter()
+            .map(|split| KeyRange::new(split.left.clone().into(), split.right.clone().into()))
+            .collect_vec();
+        compact_task.splits = split_key_ranges(&splits, block_keys, max_sub_compaction)
+            .into_iter()
+            .map(|split| split.into())
+            .collect_vec();
+        Ok(())
+    }
+
+    /// Returns the prefix extractors recorded in the input ssts of `compact_task`, so that the
+    /// output ssts can still be filtered by the prefixes.
+    async fn input_prefix_extractors(
+        context: &CompactorContext,
+        compact_task: &CompactTask,
+    ) -> HummockResult<Vec<PrefixExtractor>> {
+        let mut prefix_extractors = vec![];
+        for level_entry in &compact_task.input_ssts {
+            let level = level_entry.level.as_ref().unwrap();
+            let table_ids = level.table_infos.iter().map(|sst| sst.id).collect_vec();
+            for table in context.sstable_store.sstables(&table_ids).await? {
+                for extractor in &table.meta.prefix_extractors {
+                    if !prefix_extractors.contains(extractor) {
+                        prefix_extractors.push(extractor.clone());
+                    }
+                }
+            }
+        }
+        Ok(prefix_extractors)
+    }
+
     /// Fill in the compact task and let hummock manager know the compaction output ssts.
     async fn compact_done(&mut self, output_ssts: &[(usize, Vec<Sstable>)], task_ok: bool) {
         self.compact_task.task_status = task_ok;
@@ -251,6 +355,7 @@ impl Compactor {
                         right: sst.meta.largest_key.clone(),
                         inf: false,
                     }),
+                    file_size: sst.meta.estimated_size as u64,
                 }));
         }
 
@@ -289,7 +394,8 @@ impl Compactor {
                 .get_new_table_id()
                 .await
                 .map_err(HummockError::meta_error)?;
-            let builder = HummockStorage::get_builder(&self.context.options);
+            let builder = HummockStorage::get_builder(&self.context.options)
+                .with_prefix_extractors(self.prefix_extractors.clone());
             Ok((table_id, builder))
         });
 
@@ -305,6 +411,7 @@ impl Compactor {
             iter,
             !self.compact_task.is_target_ultimate_and_leveling,
             self.compact_task.watermark,
+            &StateTtlRegistry::from(&self.compact_task.state_ttls[..]),
         )
         .await?;
         if let Some(timer) = build_l0_sst_timer {
@@ -424,6 +531,7 @@ impl Compactor {
             sstable_store: sstable_store.clone(),
             stats,
             is_share_buffer_compact: false,
+            prefix_extractors: None,
         });
         let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::unbounded_channel();
         let stream_retry_interval = Duration::from_secs(60);
@@ -510,6 +618,7 @@ impl Compactor {
         mut iter: MergeIterator<'_>,
         has_user_key_overlap: bool,
         watermark: Epoch,
+        state_ttls: &StateTtlRegistry,
     ) -> HummockResult<()>
     where
         B: FnMut() -> F,
@@ -521,6 +630,9 @@ impl Compactor {
             iter.rewind().await?;
         }
 
+        let mut range_tombstones = vec![];
+        iter.collect_range_tombstones(&mut range_tombstones);
+
         let mut skip_key = BytesMut::new();
         let mut last_key = BytesMut::new();
 
@@ -561,6 +673,22 @@ impl Compactor {
                     iter.next().await?;
                     continue;
                 }
+                // Versions deleted by a range tombstone below `watermark` are invisible to all
+                // reads, and so are their older versions.
+                if deleted_by_range_tombstones(
+                    &range_tombstones,
+                    user_key(iter_key),
+                    epoch,
+                    watermark,
+                ) {
+                    iter.next().await?;
+                    continue;
+                }
+                // Likewise for expired versions, since all the reads are at `watermark` or later.
+                if state_ttls.is_expired(user_key(iter_key), epoch, watermark) {
+                    iter.next().await?;
+                    continue;
+                }
             }
 
             // Don't allow two SSTs to share same user key
@@ -570,6 +698,122 @@ impl Compactor {
 
             iter.next().await?;
         }
+
+        // Range tombstones below `watermark` can be dropped once no older version can exist below
+        // the target level. The others are clipped to the key range of this split.
+        let start_user_key: &[u8] = if kr.left.is_empty() {
+            &[]
+        } else {
+            user_key(&kr.left)
+        };
+        let end_user_key: &[u8] = if kr.right.is_empty() {
+            &[]
+        } else {
+            user_key(&kr.right)
+        };
+        let range_tombstones: Vec<RangeTombstone> = range_tombstones
+            .into_iter()
+            .filter(|tombstone| tombstone.epoch >= watermark || has_user_key_overlap)
+            .filter_map(|tombstone| tombstone.clip(start_user_key, end_user_key))
+            .collect();
+        if !range_tombstones.is_empty() {
+            sst_builder.add_range_tombstones(range_tombstones).await?;
+        }
         Ok(())
     }
 }
+
+/// Adds up to `max_sub_compaction - splits.len()` boundaries to `splits`, picked evenly from the
+/// user keys in `block_keys`, so that the new splits cover roughly the same number of blocks. All
+/// the versions of a user key stay in the same split.
+fn split_key_ranges(
+    splits: &[KeyRange],
+    mut block_keys: Vec<Vec<u8>>,
+    max_sub_compaction: usize,
+) -> Vec<KeyRange> {
+    let left = splits.first().unwrap().left.clone();
+    let right = splits.last().unwrap().right.clone();
+    block_keys.sort();
+    block_keys.dedup();
+    block_keys.retain(|key| {
+        (left.is_empty() || key.as_slice() > user_key(&left))
+            && (right.is_empty() || key.as_slice() < user_key(&right))
+    });
+
+    let mut boundaries = splits
+        .iter()
+        .skip(1)
+        .map(|split| user_key(&split.left).to_vec())
+        .collect_vec();
+    block_keys.retain(|key| !boundaries.contains(key));
+    let new_boundary_count = max_sub_compaction.saturating_sub(splits.len());
+    if !block_keys.is_empty() {
+        boundaries.extend(
+            (1..=new_boundary_count)
+                .map(|i| block_keys[i * block_keys.len() / (new_boundary_count + 1)].clone()),
+        );
+    }
+    boundaries.sort();
+    boundaries.dedup();
+
+    let mut new_splits = Vec::with_capacity(boundaries.len() + 1);
+    let mut split_left = left;
+    for boundary in boundaries {
+        let boundary = Bytes::from(key_with_epoch(boundary, Epoch::MAX));
+        new_splits.push(KeyRange::new(split_left, boundary.clone()));
+        split_left = boundary;
+    }
+    new_splits.push(KeyRange::new(split_left, right));
+    new_splits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boundary(user_key: &[u8]) -> Bytes {
+        Bytes::from(key_with_epoch(user_key.to_vec(), Epoch::MAX))
+    }
+
+    #[test]
+    fn test_split_key_ranges() {
+        let block_keys = (0..8u8).map(|i| vec![b'a' + i]).collect_vec();
+        let whole = vec![KeyRange::new(Bytes::new(), Bytes::new())];
+
+        let splits = split_key_ranges(&whole, block_keys.clone(), 4);
+        assert_eq!(
+            splits,
+            vec![
+                KeyRange::new(Bytes::new(), boundary(b"c")),
+                KeyRange::new(boundary(b"c"), boundary(b"e")),
+                KeyRange::new(boundary(b"e"), boundary(b"g")),
+                KeyRange::new(boundary(b"g"), Bytes::new()),
+            ]
+        );
+
+        // Splits given are kept, and the keys outside them are ignored.
+        let given = vec![
+            KeyRange::new(boundary(b"b"), boundary(b"d")),
+            KeyRange::new(boundary(b"d"), boundary(b"f")),
+        ];
+        let splits = split_key_ranges(&given, block_keys.clone(), 3);
+        assert_eq!(
+            splits,
+            vec![
+                KeyRange::new(boundary(b"b"), boundary(b"d")),
+                KeyRange::new(boundary(b"d"), boundary(b"e")),
+                KeyRange::new(boundary(b"e"), boundary(b"f")),
+            ]
+        );
+
+        // Blocks of the same user key are never split.
+        let splits = split_key_ranges(&whole, vec![b"a".to_vec(); 8], 4);
+        assert_eq!(
+            splits,
+            vec![
+                KeyRange::new(Bytes::new(), boundary(b"a")),
+                KeyRange::new(boundary(b"a"), Bytes::new()),
+            ]
+        );
+    }
+}
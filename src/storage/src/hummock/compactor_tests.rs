@@ -16,15 +16,17 @@
 mod tests {
 
     use std::sync::Arc;
+    use std::time::Duration;
 
     use bytes::Bytes;
     use risingwave_common::config::StorageConfig;
     use risingwave_meta::hummock::test_utils::setup_compute_env;
     use risingwave_meta::hummock::MockHummockMetaClient;
+    use risingwave_pb::hummock::StateTtl;
     use risingwave_rpc_client::HummockMetaClient;
 
     use crate::hummock::compactor::{Compactor, CompactorContext};
-    use crate::hummock::{HummockStorage, LocalVersionManager, SstableStore};
+    use crate::hummock::{HummockStorage, LocalVersionManager, PrefixExtractor, SstableStore};
     use crate::monitor::StateStoreMetrics;
     use crate::object::{InMemObjectStore, ObjectStoreImpl};
     use crate::storage_value::StorageValue;
@@ -88,6 +90,7 @@ mod tests {
             hummock_meta_client: hummock_meta_client.clone(),
             stats: Arc::new(StateStoreMetrics::unused()),
             is_share_buffer_compact: false,
+            prefix_extractors: None,
         };
 
         // 1. add sstables
@@ -172,4 +175,170 @@ mod tests {
 
         assert!(compact_task.is_none());
     }
+
+    #[tokio::test]
+    async fn test_compaction_drop_expired_keys() {
+        let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+            setup_compute_env(8080).await;
+        let hummock_meta_client = Arc::new(MockHummockMetaClient::new(
+            hummock_manager_ref.clone(),
+            worker_node.id,
+        ));
+        let storage = get_hummock_storage(hummock_meta_client.clone()).await;
+        let compact_ctx = CompactorContext {
+            options: storage.options().clone(),
+            sstable_store: storage.sstable_store(),
+            hummock_meta_client: hummock_meta_client.clone(),
+            stats: Arc::new(StateStoreMetrics::unused()),
+            is_share_buffer_compact: false,
+            prefix_extractors: None,
+        };
+        // The TTL is registered for reads, while the compactor only gets it from the compact task.
+        storage.set_ttl(b"ttl_", Duration::from_millis(4));
+
+        // 1. add sstables, each of which has a key expiring and a key never expiring. The physical
+        // time of the epochs are 1ms..=8ms.
+        let kv_count = 8;
+        let val = Bytes::from(&b"value"[..]);
+        let mut epoch: u64 = 0;
+        for i in 1..=kv_count {
+            epoch = i << 16;
+            storage
+                .ingest_batch(
+                    vec![
+                        (
+                            Bytes::from(format!("key_{}", i)),
+                            StorageValue::new_default_put(val.clone()),
+                        ),
+                        (
+                            Bytes::from(format!("ttl_{}", i)),
+                            StorageValue::new_default_put(val.clone()),
+                        ),
+                    ],
+                    epoch,
+                )
+                .await
+                .unwrap();
+            storage.sync(Some(epoch)).await.unwrap();
+            hummock_meta_client.commit_epoch(epoch).await.unwrap();
+        }
+
+        // Expired keys are invisible before compaction.
+        let version = hummock_manager_ref.get_current_version().await;
+        storage.local_version_manager().try_set_version(version);
+        assert!(storage.get(b"ttl_4", epoch).await.unwrap().is_none());
+        assert!(storage.get(b"ttl_5", epoch).await.unwrap().is_some());
+        assert!(storage.get(b"key_1", epoch).await.unwrap().is_some());
+
+        // 2. compact
+        let mut compact_task = hummock_manager_ref
+            .get_compact_task(worker_node.id)
+            .await
+            .unwrap()
+            .unwrap();
+        compact_task.state_ttls = vec![StateTtl {
+            key_prefix: b"ttl_".to_vec(),
+            ttl_ms: 4,
+        }];
+        Compactor::compact(Arc::new(compact_ctx), compact_task.clone()).await;
+
+        // 3. the keys written at 1ms..=4ms with TTL are dropped.
+        let version = hummock_manager_ref.get_current_version().await;
+        let output_table_ids = version
+            .get_levels()
+            .last()
+            .unwrap()
+            .table_infos
+            .iter()
+            .map(|table| table.id)
+            .collect::<Vec<_>>();
+        let key_count: u32 = storage
+            .sstable_store()
+            .sstables(&output_table_ids)
+            .await
+            .unwrap()
+            .iter()
+            .map(|table| table.meta.key_count)
+            .sum();
+        assert_eq!(key_count, kv_count as u32 + 4);
+
+        storage.local_version_manager().try_set_version(version);
+        assert!(storage.get(b"ttl_4", epoch).await.unwrap().is_none());
+        assert!(storage.get(b"ttl_5", epoch).await.unwrap().is_some());
+        assert!(storage.get(b"key_1", epoch).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_compaction_keeps_prefix_extractors() {
+        let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+            setup_compute_env(8080).await;
+        let hummock_meta_client = Arc::new(MockHummockMetaClient::new(
+            hummock_manager_ref.clone(),
+            worker_node.id,
+        ));
+        let storage = get_hummock_storage(hummock_meta_client.clone()).await;
+        // Like a dedicated compactor, the context has no registered prefix extractor.
+        let compact_ctx = CompactorContext {
+            options: storage.options().clone(),
+            sstable_store: storage.sstable_store(),
+            hummock_meta_client: hummock_meta_client.clone(),
+            stats: Arc::new(StateStoreMetrics::unused()),
+            is_share_buffer_compact: false,
+            prefix_extractors: None,
+        };
+        storage.set_prefix_extractor(b"key_", 2);
+
+        // 1. add sstables built from shared buffer, which record the registered extractor.
+        let val = Bytes::from(&b"value"[..]);
+        let mut epoch: u64 = 0;
+        for i in 1..=4 {
+            epoch = i;
+            storage
+                .ingest_batch(
+                    vec![(
+                        Bytes::from(format!("key_{:02}", i)),
+                        StorageValue::new_default_put(val.clone()),
+                    )],
+                    epoch,
+                )
+                .await
+                .unwrap();
+            storage.sync(Some(epoch)).await.unwrap();
+            hummock_meta_client.commit_epoch(epoch).await.unwrap();
+        }
+
+        // 2. compact
+        let compact_task = hummock_manager_ref
+            .get_compact_task(worker_node.id)
+            .await
+            .unwrap()
+            .unwrap();
+        Compactor::compact(Arc::new(compact_ctx), compact_task).await;
+
+        // 3. the output tables keep the extractor of the input tables.
+        let version = hummock_manager_ref.get_current_version().await;
+        let output_table_ids = version
+            .get_levels()
+            .last()
+            .unwrap()
+            .table_infos
+            .iter()
+            .map(|table| table.id)
+            .collect::<Vec<_>>();
+        assert!(!output_table_ids.is_empty());
+        for table in storage
+            .sstable_store()
+            .sstables(&output_table_ids)
+            .await
+            .unwrap()
+        {
+            assert_eq!(
+                table.meta.prefix_extractors,
+                vec![PrefixExtractor::new(b"key_".to_vec(), 2)]
+            );
+        }
+
+        storage.local_version_manager().try_set_version(version);
+        assert!(storage.get(b"key_01", epoch).await.unwrap().is_some());
+    }
 }
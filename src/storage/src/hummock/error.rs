@@ -47,6 +47,8 @@ enum HummockErrorInner {
     WaitEpoch(String),
     #[error("Expired Epoch: watermark {safe_epoch}, epoch {epoch}.")]
     ExpiredEpoch { safe_epoch: u64, epoch: u64 },
+    #[error("FileCache error {0}.")]
+    FileCacheError(String),
     #[error("Other error {0}.")]
     Other(String),
 }
@@ -108,6 +110,10 @@ impl HummockError {
         HummockErrorInner::ExpiredEpoch { safe_epoch, epoch }.into()
     }
 
+    pub fn file_cache_error(error: impl ToString) -> HummockError {
+        HummockErrorInner::FileCacheError(error.to_string()).into()
+    }
+
     pub fn other(error: impl ToString) -> HummockError {
         HummockErrorInner::Other(error.to_string()).into()
     }
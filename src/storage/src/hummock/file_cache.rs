@@ -0,0 +1,353 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+
+use super::{HummockError, HummockResult};
+
+const TMP_FILE_SUFFIX: &str = ".tmp";
+
+/// Key of an entry in the [`FileCache`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FileCacheKey {
+    Block { sst_id: u64, block_idx: u64 },
+    Meta { sst_id: u64 },
+}
+
+impl FileCacheKey {
+    fn file_name(&self) -> String {
+        match self {
+            Self::Block { sst_id, block_idx } => format!("{}-{}.block", sst_id, block_idx),
+            Self::Meta { sst_id } => format!("{}.meta", sst_id),
+        }
+    }
+
+    fn from_file_name(file_name: &str) -> Option<Self> {
+        if let Some(name) = file_name.strip_suffix(".block") {
+            let (sst_id, block_idx) = name.split_once('-')?;
+            Some(Self::Block {
+                sst_id: sst_id.parse().ok()?,
+                block_idx: block_idx.parse().ok()?,
+            })
+        } else if let Some(sst_id) = file_name.strip_suffix(".meta") {
+            Some(Self::Meta {
+                sst_id: sst_id.parse().ok()?,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// In-memory index of the entries in the [`FileCache`], evicting in LRU order.
+#[derive(Default)]
+struct FileCacheIndex {
+    /// Maps keys to their sizes and the ticks of their last accesses.
+    entries: HashMap<FileCacheKey, (usize, u64)>,
+    /// Keys ordered by the ticks of their last accesses, the least recently used first.
+    lru: BTreeMap<u64, FileCacheKey>,
+    size: usize,
+    tick: u64,
+}
+
+impl FileCacheIndex {
+    /// Marks `key` as the most recently used. Returns false if it's not in the index.
+    fn touch(&mut self, key: FileCacheKey) -> bool {
+        match self.entries.get_mut(&key) {
+            Some((_, tick)) => {
+                self.lru.remove(tick);
+                self.tick += 1;
+                *tick = self.tick;
+                self.lru.insert(self.tick, key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&mut self, key: FileCacheKey, size: usize) {
+        self.remove(key);
+        self.tick += 1;
+        self.entries.insert(key, (size, self.tick));
+        self.lru.insert(self.tick, key);
+        self.size += size;
+    }
+
+    fn remove(&mut self, key: FileCacheKey) -> bool {
+        match self.entries.remove(&key) {
+            Some((size, tick)) => {
+                self.lru.remove(&tick);
+                self.size -= size;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the least recently used entries until the size is within `capacity`, and returns
+    /// their keys.
+    fn evict(&mut self, capacity: usize) -> Vec<FileCacheKey> {
+        let mut evicted = vec![];
+        while self.size > capacity {
+            let key = match self.lru.values().next() {
+                Some(key) => *key,
+                None => break,
+            };
+            self.remove(key);
+            evicted.push(key);
+        }
+        evicted
+    }
+}
+
+/// A cache of sstable blocks and metas on the local disk, as the secondary tier behind the
+/// in-memory caches of [`super::SstableStore`].
+///
+/// Each entry is kept in its own file under `dir`, which is written to a temporary file first and
+/// then renamed, so that a crash never leaves a partial entry. The entries are recovered by
+/// scanning `dir` when the cache is opened. The content of an entry is verified by the checksum of
+/// the block or meta by the caller, and which entries to admit is up to the caller as well.
+pub struct FileCache {
+    dir: PathBuf,
+    capacity: usize,
+    index: Mutex<FileCacheIndex>,
+    /// Generates names of temporary files.
+    next_tmp_id: AtomicU64,
+}
+
+impl FileCache {
+    /// Opens the cache in `dir` with `capacity` in bytes, recovering the entries left there.
+    pub async fn open(dir: impl Into<PathBuf>, capacity: usize) -> HummockResult<Self> {
+        let dir = dir.into();
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(HummockError::file_cache_error)?;
+
+        let mut recovered = vec![];
+        let mut read_dir = tokio::fs::read_dir(&dir)
+            .await
+            .map_err(HummockError::file_cache_error)?;
+        while let Some(entry) = read_dir
+            .next_entry()
+            .await
+            .map_err(HummockError::file_cache_error)?
+        {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            match FileCacheKey::from_file_name(&file_name) {
+                Some(key) => {
+                    let metadata = entry
+                        .metadata()
+                        .await
+                        .map_err(HummockError::file_cache_error)?;
+                    let modified = metadata
+                        .modified()
+                        .map_err(HummockError::file_cache_error)?;
+                    recovered.push((modified, key, metadata.len() as usize));
+                }
+                // Temporary files of unfinished inserts.
+                None if file_name.ends_with(TMP_FILE_SUFFIX) => {
+                    tokio::fs::remove_file(entry.path())
+                        .await
+                        .map_err(HummockError::file_cache_error)?;
+                }
+                None => {}
+            }
+        }
+
+        // Recover the LRU order by the modification time.
+        recovered.sort_by_key(|(modified, _, _)| *modified);
+        let mut index = FileCacheIndex::default();
+        for (_, key, size) in recovered {
+            index.insert(key, size);
+        }
+
+        let cache = Self {
+            dir,
+            capacity,
+            index: Mutex::new(FileCacheIndex::default()),
+            next_tmp_id: AtomicU64::new(0),
+        };
+        // The capacity might have been shrunk since last time.
+        let evicted = index.evict(capacity);
+        *cache.index.lock() = index;
+        cache.remove_files(evicted).await;
+        Ok(cache)
+    }
+
+    /// Gets the content of `key`, or `None` if it's not cached or can't be read.
+    pub async fn get(&self, key: FileCacheKey) -> Option<Bytes> {
+        if !self.index.lock().touch(key) {
+            return None;
+        }
+        match tokio::fs::read(self.path_of(key)).await {
+            Ok(data) => Some(Bytes::from(data)),
+            Err(e) => {
+                tracing::warn!("failed to read {:?} from file cache: {}", key, e);
+                self.index.lock().remove(key);
+                None
+            }
+        }
+    }
+
+    /// Caches `data` as the content of `key`, evicting the least recently used entries if the
+    /// capacity is exceeded.
+    pub async fn insert(&self, key: FileCacheKey, data: Bytes) -> HummockResult<()> {
+        if data.len() > self.capacity || self.index.lock().touch(key) {
+            return Ok(());
+        }
+
+        let tmp_path = self.dir.join(format!(
+            "{}{}",
+            self.next_tmp_id.fetch_add(1, Ordering::Relaxed),
+            TMP_FILE_SUFFIX
+        ));
+        tokio::fs::write(&tmp_path, &data)
+            .await
+            .map_err(HummockError::file_cache_error)?;
+        tokio::fs::rename(&tmp_path, self.path_of(key))
+            .await
+            .map_err(HummockError::file_cache_error)?;
+
+        let evicted = {
+            let mut index = self.index.lock();
+            index.insert(key, data.len());
+            index.evict(self.capacity)
+        };
+        self.remove_files(evicted).await;
+        Ok(())
+    }
+
+    /// Removes `key` from the cache, e.g. when its content is found corrupted.
+    pub async fn remove(&self, key: FileCacheKey) {
+        if self.index.lock().remove(key) {
+            self.remove_files(vec![key]).await;
+        }
+    }
+
+    /// Returns the total size of the cached entries in bytes.
+    pub fn size(&self) -> usize {
+        self.index.lock().size
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.lock().entries.is_empty()
+    }
+
+    fn path_of(&self, key: FileCacheKey) -> PathBuf {
+        self.dir.join(key.file_name())
+    }
+
+    async fn remove_files(&self, keys: Vec<FileCacheKey>) {
+        for key in keys {
+            if let Err(e) = tokio::fs::remove_file(self.path_of(key)).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("failed to remove {:?} from file cache: {}", key, e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("hummock_file_cache_{}", uuid::Uuid::new_v4()))
+    }
+
+    fn block_key(block_idx: u64) -> FileCacheKey {
+        FileCacheKey::Block {
+            sst_id: 1,
+            block_idx,
+        }
+    }
+
+    #[test]
+    fn test_file_cache_key() {
+        for key in [block_key(2), FileCacheKey::Meta { sst_id: 3 }] {
+            assert_eq!(FileCacheKey::from_file_name(&key.file_name()), Some(key));
+        }
+        assert_eq!(FileCacheKey::from_file_name("1.tmp"), None);
+        assert_eq!(FileCacheKey::from_file_name("x-1.block"), None);
+    }
+
+    #[tokio::test]
+    async fn test_file_cache_evict_and_recover() {
+        let dir = test_dir();
+        let cache = FileCache::open(&dir, 30).await.unwrap();
+        for i in 0..3 {
+            cache
+                .insert(block_key(i), Bytes::from(vec![i as u8; 10]))
+                .await
+                .unwrap();
+        }
+        assert_eq!(cache.size(), 30);
+        // Make block 0 the most recently used.
+        assert_eq!(
+            cache.get(block_key(0)).await,
+            Some(Bytes::from(vec![0; 10]))
+        );
+
+        // Block 1 is evicted.
+        cache
+            .insert(FileCacheKey::Meta { sst_id: 1 }, Bytes::from(vec![9; 10]))
+            .await
+            .unwrap();
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.get(block_key(1)).await, None);
+        assert!(!dir.join(block_key(1).file_name()).exists());
+        // Entries larger than the capacity are never admitted.
+        cache
+            .insert(block_key(4), Bytes::from(vec![4; 31]))
+            .await
+            .unwrap();
+        assert_eq!(cache.get(block_key(4)).await, None);
+
+        // Leave a temporary file behind as if a crash happened during an insert.
+        tokio::fs::write(dir.join("100.tmp"), b"partial")
+            .await
+            .unwrap();
+        drop(cache);
+
+        let cache = FileCache::open(&dir, 30).await.unwrap();
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.size(), 30);
+        assert_eq!(
+            cache.get(block_key(0)).await,
+            Some(Bytes::from(vec![0; 10]))
+        );
+        assert_eq!(
+            cache.get(block_key(2)).await,
+            Some(Bytes::from(vec![2; 10]))
+        );
+        assert!(!dir.join("100.tmp").exists());
+
+        // Reopening with a smaller capacity evicts the entries over it.
+        drop(cache);
+        let cache = FileCache::open(&dir, 10).await.unwrap();
+        assert_eq!(cache.len(), 1);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
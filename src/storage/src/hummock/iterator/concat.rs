@@ -28,6 +28,7 @@ mod tests {
         iterator_test_value_of, mock_sstable_store, TEST_KEYS_COUNT,
     };
     use crate::hummock::iterator::HummockIterator;
+    use crate::hummock::{CachePolicy, RangeTombstone, SSTableBuilder, Sstable};
 
     #[tokio::test]
     async fn test_concat_iterator() {
@@ -223,4 +224,61 @@ mod tests {
             iterator_test_value_of(TEST_KEYS_COUNT * 4).as_slice()
         );
     }
+
+    #[tokio::test]
+    async fn test_concat_with_range_tombstone_only_table() {
+        let sstable_store = mock_sstable_store();
+        let table0 = gen_iterator_test_sstable_base(
+            0,
+            default_builder_opt_for_test(),
+            |x| x,
+            sstable_store.clone(),
+            TEST_KEYS_COUNT,
+        )
+        .await;
+        // A table with no blocks, whose iterator is invalid right after rewinding or seeking.
+        let mut builder = SSTableBuilder::new(default_builder_opt_for_test());
+        builder.add_range_tombstone(RangeTombstone::new(
+            format!("key_test_{:05}", TEST_KEYS_COUNT).into_bytes(),
+            format!("key_test_{:05}", TEST_KEYS_COUNT * 2).into_bytes(),
+            233,
+        ));
+        let (data, meta) = builder.finish();
+        let table1 = Sstable { id: 1, meta };
+        sstable_store
+            .put(&table1, data, CachePolicy::Fill)
+            .await
+            .unwrap();
+        let table2 = gen_iterator_test_sstable_base(
+            2,
+            default_builder_opt_for_test(),
+            |x| TEST_KEYS_COUNT * 2 + x,
+            sstable_store.clone(),
+            TEST_KEYS_COUNT,
+        )
+        .await;
+        let mut iter = ConcatIterator::new(
+            vec![Arc::new(table0), Arc::new(table1), Arc::new(table2)],
+            sstable_store,
+        );
+
+        // The keys of the tables after the tombstone-only one are not skipped.
+        let expected_idx = (0..TEST_KEYS_COUNT).chain(TEST_KEYS_COUNT * 2..TEST_KEYS_COUNT * 3);
+        iter.rewind().await.unwrap();
+        for i in expected_idx {
+            assert!(iter.is_valid());
+            assert_eq!(iter.key(), iterator_test_key_of(i).as_slice());
+            iter.next().await.unwrap();
+        }
+        assert!(!iter.is_valid());
+
+        // Seeking into the range of the tombstone-only table lands on the following table.
+        iter.seek(iterator_test_key_of(TEST_KEYS_COUNT + 1).as_slice())
+            .await
+            .unwrap();
+        assert_eq!(
+            iter.key(),
+            iterator_test_key_of(TEST_KEYS_COUNT * 2).as_slice()
+        );
+    }
 }
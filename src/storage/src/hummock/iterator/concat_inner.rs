@@ -21,7 +21,10 @@ use risingwave_hummock_sdk::VersionedComparator;
 use super::variants::*;
 use crate::hummock::iterator::HummockIterator;
 use crate::hummock::value::HummockValue;
-use crate::hummock::{HummockResult, SSTableIteratorType, Sstable, SstableStoreRef};
+use crate::hummock::{
+    HummockResult, RangeTombstone, SSTableIteratorType, Sstable, SstableStoreRef,
+};
+use crate::store::ReadOptions;
 
 /// Served as the concrete implementation of `ConcatIterator` and `ReverseConcatIterator`.
 pub struct ConcatIteratorInner<TI: SSTableIteratorType> {
@@ -35,6 +38,9 @@ pub struct ConcatIteratorInner<TI: SSTableIteratorType> {
     tables: Vec<Arc<Sstable>>,
 
     sstable_store: SstableStoreRef,
+
+    /// Options to create the iterator of each table with.
+    read_options: ReadOptions,
 }
 
 impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
@@ -47,23 +53,40 @@ impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
             cur_idx: 0,
             tables,
             sstable_store,
+            read_options: ReadOptions::default(),
         }
     }
 
-    /// Seeks to a table, and then seeks to the key if `seek_key` is given.
-    async fn seek_idx(&mut self, idx: usize, seek_key: Option<&[u8]>) -> HummockResult<()> {
-        if idx >= self.tables.len() {
-            self.sstable_iter = None;
-        } else {
-            let mut sstable_iter = TI::new(self.tables[idx].clone(), self.sstable_store.clone());
-            if let Some(key) = seek_key {
+    #[must_use]
+    pub fn with_read_options(mut self, read_options: ReadOptions) -> Self {
+        self.read_options = read_options;
+        self
+    }
+
+    /// Seeks to a table, and then seeks to the key if `seek_key` is given. If the iterator of the
+    /// table is invalid, e.g. the table only has range tombstones, moves on to the first valid one
+    /// of the following tables.
+    async fn seek_idx(&mut self, mut idx: usize, mut seek_key: Option<&[u8]>) -> HummockResult<()> {
+        self.sstable_iter = None;
+        while idx < self.tables.len() {
+            let mut sstable_iter = TI::new(
+                self.tables[idx].clone(),
+                self.sstable_store.clone(),
+                self.read_options,
+            );
+            if let Some(key) = seek_key.take() {
                 sstable_iter.seek(key).await?;
             } else {
                 sstable_iter.rewind().await?;
             }
 
+            let is_valid = sstable_iter.is_valid();
             self.sstable_iter = Some(sstable_iter);
             self.cur_idx = idx;
+            if is_valid {
+                break;
+            }
+            idx += 1;
         }
         Ok(())
     }
@@ -95,6 +118,12 @@ impl<TI: SSTableIteratorType> HummockIterator for ConcatIteratorInner<TI> {
         self.sstable_iter.as_ref().map_or(false, |i| i.is_valid())
     }
 
+    fn collect_range_tombstones(&self, range_tombstones: &mut Vec<RangeTombstone>) {
+        for table in &self.tables {
+            range_tombstones.extend_from_slice(&table.meta.range_tombstones);
+        }
+    }
+
     async fn rewind(&mut self) -> HummockResult<()> {
         self.seek_idx(0, None).await
     }
@@ -115,11 +144,6 @@ impl<TI: SSTableIteratorType> HummockIterator for ConcatIteratorInner<TI> {
             })
             .saturating_sub(1); // considering the boundary of 0
 
-        self.seek_idx(table_idx, Some(key)).await?;
-        if !self.is_valid() {
-            // Seek to next table
-            self.seek_idx(table_idx + 1, None).await?;
-        }
-        Ok(())
+        self.seek_idx(table_idx, Some(key)).await
     }
 }
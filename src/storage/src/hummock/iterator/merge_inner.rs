@@ -22,7 +22,7 @@ use risingwave_hummock_sdk::VersionedComparator;
 use super::variants::*;
 use crate::hummock::iterator::{BoxedHummockIterator, HummockIterator};
 use crate::hummock::value::HummockValue;
-use crate::hummock::HummockResult;
+use crate::hummock::{HummockResult, RangeTombstone};
 use crate::monitor::StateStoreMetrics;
 
 pub struct Node<'a, const DIRECTION: usize>(BoxedHummockIterator<'a>);
@@ -138,6 +138,16 @@ impl<const DIRECTION: usize> HummockIterator for MergeIteratorInner<'_, DIRECTIO
         self.heap.peek().map_or(false, |n| n.0.is_valid())
     }
 
+    fn collect_range_tombstones(&self, range_tombstones: &mut Vec<RangeTombstone>) {
+        for iter in self
+            .unused_iters
+            .iter()
+            .chain(self.heap.iter().map(|n| &n.0))
+        {
+            iter.collect_range_tombstones(range_tombstones);
+        }
+    }
+
     async fn rewind(&mut self) -> HummockResult<()> {
         self.reset_heap();
         futures::future::try_join_all(self.unused_iters.iter_mut().map(|x| x.rewind())).await?;
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{HummockResult, HummockValue};
+use super::{HummockResult, HummockValue, RangeTombstone};
 
 mod concat;
 pub use concat::*;
@@ -101,6 +101,14 @@ pub trait HummockIterator: Send + Sync {
     ///   function. This function WON'T return an `Err` if invalid. You should check `is_valid`
     ///   before starting iteration.
     async fn seek(&mut self, key: &[u8]) -> HummockResult<()>;
+
+    /// Collects the range tombstones of the data this iterator reads from. Iterators combining
+    /// other iterators collect the range tombstones of all of them.
+    ///
+    /// Note:
+    /// - The range tombstones are not filtered by the position of the iterator, so this function
+    ///   can be called at any time.
+    fn collect_range_tombstones(&self, range_tombstones: &mut Vec<RangeTombstone>);
 }
 
 pub type BoxedHummockIterator<'a> = Box<dyn HummockIterator + 'a>;
@@ -20,7 +20,9 @@ use risingwave_hummock_sdk::key::{get_epoch, key_with_epoch, user_key as to_user
 use crate::hummock::iterator::{HummockIterator, ReverseMergeIterator};
 use crate::hummock::local_version_manager::ScopedLocalVersion;
 use crate::hummock::value::HummockValue;
-use crate::hummock::HummockResult;
+use crate::hummock::{
+    deleted_by_range_tombstones, HummockResult, RangeTombstone, StateTtlRegistry,
+};
 
 /// [`ReverseUserIterator`] can be used by user directly.
 pub struct ReverseUserIterator<'a> {
@@ -48,6 +50,12 @@ pub struct ReverseUserIterator<'a> {
     /// Only reads values if `epoch <= self.read_epoch`.
     read_epoch: Epoch,
 
+    /// Range tombstones visible to `read_epoch`, collected from `iterator`.
+    range_tombstones: Vec<RangeTombstone>,
+
+    /// Expired versions are treated as deleted ones.
+    state_ttls: Option<Arc<StateTtlRegistry>>,
+
     /// Ensures the SSTs needed by `iterator` won't be vacuumed.
     _version: Option<Arc<ScopedLocalVersion>>,
 }
@@ -69,6 +77,9 @@ impl<'a> ReverseUserIterator<'a> {
         read_epoch: u64,
         version: Option<Arc<ScopedLocalVersion>>,
     ) -> Self {
+        let mut range_tombstones = vec![];
+        iterator.collect_range_tombstones(&mut range_tombstones);
+        range_tombstones.retain(|t| t.epoch <= read_epoch);
         Self {
             iterator,
             out_of_range: false,
@@ -78,10 +89,25 @@ impl<'a> ReverseUserIterator<'a> {
             last_val: Vec::new(),
             last_delete: true,
             read_epoch,
+            range_tombstones,
+            state_ttls: None,
             _version: version,
         }
     }
 
+    /// Filters out the versions expired according to `state_ttls`.
+    #[must_use]
+    pub fn with_state_ttls(mut self, state_ttls: Arc<StateTtlRegistry>) -> Self {
+        self.state_ttls = Some(state_ttls);
+        self
+    }
+
+    fn expired(&self, user_key: &[u8], epoch: Epoch) -> bool {
+        self.state_ttls.as_ref().map_or(false, |ttls| {
+            ttls.is_expired(user_key, epoch, self.read_epoch)
+        })
+    }
+
     fn out_of_range(&self, key: &[u8]) -> bool {
         match &self.key_range.0 {
             Included(begin_key) => key < begin_key.as_slice(),
@@ -167,14 +193,21 @@ impl<'a> ReverseUserIterator<'a> {
                 // been seeing the same key for too many times.
 
                 // 1 and 2(a)
-                match self.iterator.value() {
-                    HummockValue::Put(_, val) => {
-                        self.last_val.clear();
-                        self.last_val.extend_from_slice(val);
-                        self.last_delete = false;
-                    }
-                    HummockValue::Delete(_) => {
-                        self.last_delete = true;
+                // A version covered by a range tombstone or expired is treated as a delete.
+                if deleted_by_range_tombstones(&self.range_tombstones, key, epoch, self.read_epoch)
+                    || self.expired(key, epoch)
+                {
+                    self.last_delete = true;
+                } else {
+                    match self.iterator.value() {
+                        HummockValue::Put(_, val) => {
+                            self.last_val.clear();
+                            self.last_val.extend_from_slice(val);
+                            self.last_delete = false;
+                        }
+                        HummockValue::Delete(_) => {
+                            self.last_delete = true;
+                        }
                     }
                 }
             }
@@ -21,7 +21,9 @@ use super::{HummockIterator, MergeIterator};
 use crate::hummock::iterator::ReverseUserIterator;
 use crate::hummock::local_version_manager::ScopedLocalVersion;
 use crate::hummock::value::HummockValue;
-use crate::hummock::HummockResult;
+use crate::hummock::{
+    deleted_by_range_tombstones, HummockResult, RangeTombstone, StateTtlRegistry,
+};
 
 pub enum DirectedUserIterator<'a> {
     Forward(UserIterator<'a>),
@@ -96,6 +98,12 @@ pub struct UserIterator<'a> {
     /// Only reads values if `ts <= self.read_epoch`.
     read_epoch: Epoch,
 
+    /// Range tombstones visible to `read_epoch`, collected from `iterator`.
+    range_tombstones: Vec<RangeTombstone>,
+
+    /// Expired versions are skipped like deleted ones.
+    state_ttls: Option<Arc<StateTtlRegistry>>,
+
     /// Ensures the SSTs needed by `iterator` won't be vacuumed.
     _version: Option<Arc<ScopedLocalVersion>>,
 }
@@ -118,6 +126,9 @@ impl<'a> UserIterator<'a> {
         read_epoch: u64,
         version: Option<Arc<ScopedLocalVersion>>,
     ) -> Self {
+        let mut range_tombstones = vec![];
+        iterator.collect_range_tombstones(&mut range_tombstones);
+        range_tombstones.retain(|t| t.epoch <= read_epoch);
         Self {
             iterator,
             out_of_range: false,
@@ -125,10 +136,25 @@ impl<'a> UserIterator<'a> {
             last_key: Vec::new(),
             last_val: Vec::new(),
             read_epoch,
+            range_tombstones,
+            state_ttls: None,
             _version: version,
         }
     }
 
+    /// Filters out the versions expired according to `state_ttls`.
+    #[must_use]
+    pub fn with_state_ttls(mut self, state_ttls: Arc<StateTtlRegistry>) -> Self {
+        self.state_ttls = Some(state_ttls);
+        self
+    }
+
+    fn expired(&self, user_key: &[u8], epoch: Epoch) -> bool {
+        self.state_ttls.as_ref().map_or(false, |ttls| {
+            ttls.is_expired(user_key, epoch, self.read_epoch)
+        })
+    }
+
     /// Gets the iterator move to the next step.
     ///
     /// Returned result:
@@ -146,6 +172,14 @@ impl<'a> UserIterator<'a> {
                 self.last_key.clear();
                 self.last_key.extend_from_slice(key);
 
+                // Keys deleted by a range tombstone or expired are skipped like deleted ones.
+                if deleted_by_range_tombstones(&self.range_tombstones, key, epoch, self.read_epoch)
+                    || self.expired(key, epoch)
+                {
+                    self.iterator.next().await?;
+                    continue;
+                }
+
                 // handle delete operation
                 match self.iterator.value() {
                     HummockValue::Put(_, val) => {
@@ -376,6 +376,7 @@ mod tests {
     use crate::hummock::shared_buffer::shared_buffer_manager::SharedBufferManager;
     use crate::hummock::test_utils::default_config_for_test;
     use crate::hummock::value::HummockValue;
+    use crate::hummock::PrefixExtractorRegistry;
     use crate::monitor::StateStoreMetrics;
     use crate::object::{InMemObjectStore, ObjectStoreImpl};
 
@@ -403,6 +404,7 @@ mod tests {
             sstable_store,
             Arc::new(StateStoreMetrics::unused()),
             mock_hummock_meta_client,
+            Arc::new(PrefixExtractorRegistry::default()),
         ));
 
         let epochs = vec![1, 2, 3, 4];
@@ -19,6 +19,7 @@ use std::fmt;
 use std::future::Future;
 use std::ops::RangeBounds;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::Bytes;
 use itertools::Itertools;
@@ -36,6 +37,8 @@ pub mod compactor;
 mod compactor_tests;
 mod conflict_detector;
 mod error;
+mod file_cache;
+pub use file_cache::*;
 pub mod hummock_meta_client;
 pub(crate) mod iterator;
 pub mod local_version_manager;
@@ -43,6 +46,8 @@ mod shared_buffer;
 #[cfg(test)]
 mod snapshot_tests;
 mod sstable_store;
+mod state_ttl;
+pub use state_ttl::*;
 #[cfg(test)]
 mod state_store_tests;
 pub mod test_runner;
@@ -59,10 +64,11 @@ use self::iterator::{
     BoxedHummockIterator, DirectedUserIterator, HummockIterator, MergeIterator,
     ReverseMergeIterator, UserIterator,
 };
-use self::key::{key_with_epoch, user_key, FullKey};
+use self::key::{get_epoch, key_with_epoch, user_key, FullKey};
 pub use self::sstable_store::*;
-use self::utils::range_overlap;
+use self::utils::{range_common_prefix, range_overlap};
 use super::monitor::StateStoreMetrics;
+use crate::error::StorageResult;
 use crate::hummock::iterator::{ConcatIterator, ReverseConcatIterator, ReverseUserIterator};
 use crate::hummock::local_version_manager::LocalVersionManager;
 use crate::hummock::shared_buffer::shared_buffer_manager::SharedBufferManager;
@@ -87,6 +93,12 @@ pub struct HummockStorage {
 
     /// Statistics
     stats: Arc<StateStoreMetrics>,
+
+    /// Time-to-live of streaming states
+    state_ttls: Arc<StateTtlRegistry>,
+
+    /// Prefix extractors of keyspaces for building prefix bloom filters
+    prefix_extractors: Arc<PrefixExtractorRegistry>,
 }
 
 impl HummockStorage {
@@ -117,12 +129,14 @@ impl HummockStorage {
         // TODO: separate `HummockStats` from `StateStoreMetrics`.
         stats: Arc<StateStoreMetrics>,
     ) -> HummockResult<Self> {
+        let prefix_extractors = Arc::new(PrefixExtractorRegistry::default());
         let shared_buffer_manager = Arc::new(SharedBufferManager::new(
             options.clone(),
             local_version_manager.clone(),
             sstable_store.clone(),
             stats.clone(),
             hummock_meta_client.clone(),
+            prefix_extractors.clone(),
         ));
 
         LocalVersionManager::start_workers(
@@ -140,6 +154,8 @@ impl HummockStorage {
             sstable_store,
             shared_buffer_manager,
             stats,
+            state_ttls: Arc::new(StateTtlRegistry::default()),
+            prefix_extractors,
         };
         Ok(instance)
     }
@@ -155,12 +171,55 @@ impl HummockStorage {
         })
     }
 
+    /// Filters out the tables that surely have no key starting with `prefix` by their prefix bloom
+    /// filters.
+    fn filter_tables_by_prefix(
+        &self,
+        tables: Vec<Arc<Sstable>>,
+        prefix: &[u8],
+    ) -> Vec<Arc<Sstable>> {
+        if prefix.is_empty() {
+            return tables;
+        }
+        tables
+            .into_iter()
+            .filter(|table| {
+                if !table.has_prefix_bloom_filter() {
+                    return true;
+                }
+                if table.surely_not_have_prefix(prefix) {
+                    self.stats.prefix_bloom_filter_true_negative_counts.inc();
+                    false
+                } else {
+                    self.stats.prefix_bloom_filter_might_positive_counts.inc();
+                    true
+                }
+            })
+            .collect()
+    }
+
+    /// Gets the newest version of `key` in the table visible to the epoch of `internal_key`.
+    ///
+    /// Range tombstones of the table covering `key` are collected into `range_tombstones`, which
+    /// should contain those of all the tables searched before. A version deleted by any of them or
+    /// expired is returned as `HummockValue::Delete`.
     async fn get_from_table(
         &self,
         table: Arc<Sstable>,
         internal_key: &[u8],
         key: &[u8],
-    ) -> HummockResult<Option<Bytes>> {
+        range_tombstones: &mut Vec<RangeTombstone>,
+    ) -> HummockResult<Option<HummockValue<Bytes>>> {
+        let read_epoch = get_epoch(internal_key);
+        range_tombstones.extend(
+            table
+                .meta
+                .range_tombstones
+                .iter()
+                .filter(|t| t.epoch <= read_epoch && t.covers(key))
+                .cloned(),
+        );
+
         if table.surely_not_have_user_key(key) {
             self.stats.bloom_filter_true_negative_counts.inc();
             return Ok(None);
@@ -176,11 +235,20 @@ impl HummockStorage {
 
         // Iterator gets us the key, we tell if it's the key we want
         // or key next to it.
-        let value = match user_key(iter.key()) == key {
-            true => iter.value().into_user_value().map(Bytes::copy_from_slice),
-            false => None,
+        if user_key(iter.key()) != key {
+            return Ok(None);
+        }
+        let key_epoch = get_epoch(iter.key());
+        if deleted_by_range_tombstones(range_tombstones, key, key_epoch, read_epoch)
+            || self.state_ttls.is_expired(key, key_epoch, read_epoch)
+        {
+            return Ok(Some(HummockValue::delete()));
+        }
+        let value = match iter.value().into_user_value() {
+            Some(value) => HummockValue::put(Bytes::copy_from_slice(value)),
+            None => HummockValue::delete(),
         };
-        Ok(value)
+        Ok(Some(value))
     }
 
     pub fn hummock_meta_client(&self) -> &Arc<dyn HummockMetaClient> {
@@ -202,6 +270,128 @@ impl HummockStorage {
     pub fn shared_buffer_manager(&self) -> &SharedBufferManager {
         &self.shared_buffer_manager
     }
+
+    pub fn prefix_extractors(&self) -> Arc<PrefixExtractorRegistry> {
+        self.prefix_extractors.clone()
+    }
+
+    /// Scans `limit` keys from `key_range` with an iterator opened with `read_options`.
+    async fn scan_inner<R, B>(
+        &self,
+        key_range: R,
+        limit: Option<usize>,
+        epoch: u64,
+        read_options: ReadOptions,
+    ) -> StorageResult<Vec<(Bytes, Bytes)>>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        let mut iter = self.iter_inner(key_range, epoch, read_options).await?;
+        let mut kvs = Vec::with_capacity(limit.unwrap_or_default());
+
+        for _ in 0..limit.unwrap_or(usize::MAX) {
+            match iter.next().await? {
+                Some(kv) => kvs.push(kv),
+                None => break,
+            }
+        }
+
+        Ok(kvs)
+    }
+
+    /// Opens an iterator over `key_range`, whose sstable iterators read with `read_options`.
+    async fn iter_inner<R, B>(
+        &self,
+        key_range: R,
+        epoch: u64,
+        read_options: ReadOptions,
+    ) -> StorageResult<HummockStateStoreIter<'_>>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        let version = self.local_version_manager.get_version()?;
+        // Check epoch validity
+        validate_epoch(version.safe_epoch(), epoch)?;
+        let levels = version.levels();
+        validate_table_key_range(&levels)?;
+        let prefix = range_common_prefix(&key_range, false);
+
+        // Filter out tables that overlap with given `key_range`
+        let mut overlapped_sstable_iters = vec![];
+        for level in &version.levels() {
+            let table_ids = level
+                .table_infos
+                .iter()
+                .filter(|info| {
+                    let table_range = info.key_range.as_ref().unwrap();
+                    let table_start = user_key(table_range.left.as_slice());
+                    let table_end = user_key(table_range.right.as_slice());
+                    range_overlap(&key_range, table_start, table_end, false)
+                })
+                .map(|info| info.id)
+                .collect_vec();
+            if table_ids.is_empty() {
+                continue;
+            }
+            let tables = self.sstable_store.sstables(&table_ids).await?;
+            let tables = self.filter_tables_by_prefix(tables, prefix);
+            if tables.is_empty() {
+                continue;
+            }
+            match level.level_type() {
+                LevelType::Overlapping => {
+                    for table in tables.into_iter().rev() {
+                        overlapped_sstable_iters.push(Box::new(
+                            SSTableIterator::new(table, self.sstable_store.clone())
+                                .with_read_options(read_options),
+                        )
+                            as BoxedHummockIterator);
+                    }
+                }
+                LevelType::Nonoverlapping => overlapped_sstable_iters.push(Box::new(
+                    ConcatIterator::new(tables, self.sstable_store.clone())
+                        .with_read_options(read_options),
+                )),
+            }
+        }
+        self.stats
+            .iter_merge_sstable_counts
+            .observe(overlapped_sstable_iters.len() as f64);
+        let mi = if version.max_committed_epoch() < epoch {
+            // Take shared buffers into consideration if the read epoch is above the max
+            // committed epoch
+            let overlapped_shared_buffer_iters = self
+                .shared_buffer_manager
+                .iters(&key_range, (version.max_committed_epoch() + 1)..=epoch)
+                .into_iter()
+                .map(|i| Box::new(i) as BoxedHummockIterator);
+            MergeIterator::new(
+                overlapped_shared_buffer_iters.chain(overlapped_sstable_iters),
+                self.stats.clone(),
+            )
+        } else {
+            MergeIterator::new(overlapped_sstable_iters, self.stats.clone())
+        };
+
+        // TODO: avoid this clone
+        let mut user_iter = DirectedUserIterator::Forward(
+            UserIterator::new(
+                mi,
+                (
+                    key_range.start_bound().map(|b| b.as_ref().to_owned()),
+                    key_range.end_bound().map(|b| b.as_ref().to_owned()),
+                ),
+                epoch,
+                Some(version),
+            )
+            .with_state_ttls(self.state_ttls.clone()),
+        );
+
+        user_iter.rewind().await?;
+        Ok(HummockStateStoreIter::new(user_iter))
+    }
 }
 
 impl fmt::Debug for HummockStorage {
@@ -229,17 +419,22 @@ impl StateStore for HummockStorage {
 
             // Query shared buffer. Return the value without iterating SSTs if found
             if version.max_committed_epoch() < epoch {
-                if let Some(v) = self
+                if let Some((key_epoch, v)) = self
                     .shared_buffer_manager
-                    .get(key, (version.max_committed_epoch() + 1)..=epoch)
+                    .get_with_epoch(key, (version.max_committed_epoch() + 1)..=epoch)
+                    .await?
                 {
                     self.stats.get_shared_buffer_hit_counts.inc();
+                    if self.state_ttls.is_expired(key, key_epoch, epoch) {
+                        return Ok(None);
+                    }
                     return Ok(v.into_user_value().map(|v| v.into()));
                 }
             }
             let internal_key = key_with_epoch(key.to_vec(), epoch);
 
             let mut table_counts = 0;
+            let mut range_tombstones = vec![];
             for level in &version.levels() {
                 if level.table_infos.is_empty() {
                     continue;
@@ -260,8 +455,11 @@ impl StateStore for HummockStorage {
                         let tables = self.sstable_store.sstables(&table_infos).await?;
                         for table in tables.into_iter().rev() {
                             table_counts += 1;
-                            if let Some(v) = self.get_from_table(table, &internal_key, key).await? {
-                                return Ok(Some(v));
+                            if let Some(v) = self
+                                .get_from_table(table, &internal_key, key, &mut range_tombstones)
+                                .await?
+                            {
+                                return Ok(v.into_user_value());
                             }
                         }
                     }
@@ -285,10 +483,15 @@ impl StateStore for HummockStorage {
                             .sstables(&[level.table_infos[table_idx].id])
                             .await?;
                         if let Some(v) = self
-                            .get_from_table(tables.first().unwrap().clone(), &internal_key, key)
+                            .get_from_table(
+                                tables.first().unwrap().clone(),
+                                &internal_key,
+                                key,
+                                &mut range_tombstones,
+                            )
                             .await?
                         {
-                            return Ok(Some(v));
+                            return Ok(v.into_user_value());
                         }
                     }
                 }
@@ -311,19 +514,21 @@ impl StateStore for HummockStorage {
         R: RangeBounds<B> + Send,
         B: AsRef<[u8]> + Send,
     {
-        async move {
-            let mut iter = self.iter(key_range, epoch).await?;
-            let mut kvs = Vec::with_capacity(limit.unwrap_or_default());
-
-            for _ in 0..limit.unwrap_or(usize::MAX) {
-                match iter.next().await? {
-                    Some(kv) => kvs.push(kv),
-                    None => break,
-                }
-            }
+        self.scan_inner(key_range, limit, epoch, ReadOptions::default())
+    }
 
-            Ok(kvs)
-        }
+    fn scan_with_options<R, B>(
+        &self,
+        key_range: R,
+        limit: Option<usize>,
+        epoch: u64,
+        read_options: ReadOptions,
+    ) -> Self::ScanFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        self.scan_inner(key_range, limit, epoch, read_options)
     }
 
     fn reverse_scan<R, B>(
@@ -408,6 +613,27 @@ impl StateStore for HummockStorage {
         }
     }
 
+    /// Writes a range tombstone to shared buffer. Like `ingest_batch`, it is synced right away
+    /// unless async checkpoint is enabled.
+    fn delete_range(
+        &self,
+        start_key: Bytes,
+        end_key: Bytes,
+        epoch: u64,
+    ) -> Self::DeleteRangeFuture<'_> {
+        async move {
+            let tombstone = RangeTombstone::new(start_key.to_vec(), end_key.to_vec(), epoch);
+            self.shared_buffer_manager
+                .write_range_tombstones(vec![tombstone], epoch)
+                .await?;
+
+            if !self.options.async_checkpoint_enabled {
+                self.shared_buffer_manager.sync(Some(epoch)).await?;
+            }
+            Ok(())
+        }
+    }
+
     /// Returns an iterator that scan from the begin key to the end key
     /// The result is based on a snapshot corresponding to the given `epoch`.
     fn iter<R, B>(&self, key_range: R, epoch: u64) -> Self::IterFuture<'_, R, B>
@@ -415,79 +641,20 @@ impl StateStore for HummockStorage {
         R: RangeBounds<B> + Send,
         B: AsRef<[u8]> + Send,
     {
-        async move {
-            let version = self.local_version_manager.get_version()?;
-            // Check epoch validity
-            validate_epoch(version.safe_epoch(), epoch)?;
-            let levels = version.levels();
-            validate_table_key_range(&levels)?;
-
-            // Filter out tables that overlap with given `key_range`
-            let mut overlapped_sstable_iters = vec![];
-            for level in &version.levels() {
-                let table_ids = level
-                    .table_infos
-                    .iter()
-                    .filter(|info| {
-                        let table_range = info.key_range.as_ref().unwrap();
-                        let table_start = user_key(table_range.left.as_slice());
-                        let table_end = user_key(table_range.right.as_slice());
-                        range_overlap(&key_range, table_start, table_end, false)
-                    })
-                    .map(|info| info.id)
-                    .collect_vec();
-                if table_ids.is_empty() {
-                    continue;
-                }
-                let tables = self.sstable_store.sstables(&table_ids).await?;
-                match level.level_type() {
-                    LevelType::Overlapping => {
-                        for table in tables.into_iter().rev() {
-                            overlapped_sstable_iters.push(Box::new(SSTableIterator::new(
-                                table,
-                                self.sstable_store.clone(),
-                            ))
-                                as BoxedHummockIterator);
-                        }
-                    }
-                    LevelType::Nonoverlapping => overlapped_sstable_iters.push(Box::new(
-                        ConcatIterator::new(tables, self.sstable_store.clone()),
-                    )),
-                }
-            }
-            self.stats
-                .iter_merge_sstable_counts
-                .observe(overlapped_sstable_iters.len() as f64);
-            let mi = if version.max_committed_epoch() < epoch {
-                // Take shared buffers into consideration if the read epoch is above the max
-                // committed epoch
-                let overlapped_shared_buffer_iters = self
-                    .shared_buffer_manager
-                    .iters(&key_range, (version.max_committed_epoch() + 1)..=epoch)
-                    .into_iter()
-                    .map(|i| Box::new(i) as BoxedHummockIterator);
-                MergeIterator::new(
-                    overlapped_shared_buffer_iters.chain(overlapped_sstable_iters),
-                    self.stats.clone(),
-                )
-            } else {
-                MergeIterator::new(overlapped_sstable_iters, self.stats.clone())
-            };
-
-            // TODO: avoid this clone
-            let mut user_iter = DirectedUserIterator::Forward(UserIterator::new(
-                mi,
-                (
-                    key_range.start_bound().map(|b| b.as_ref().to_owned()),
-                    key_range.end_bound().map(|b| b.as_ref().to_owned()),
-                ),
-                epoch,
-                Some(version),
-            ));
+        self.iter_inner(key_range, epoch, ReadOptions::default())
+    }
 
-            user_iter.rewind().await?;
-            Ok(HummockStateStoreIter::new(user_iter))
-        }
+    fn iter_with_options<R, B>(
+        &self,
+        key_range: R,
+        epoch: u64,
+        read_options: ReadOptions,
+    ) -> Self::IterFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        self.iter_inner(key_range, epoch, read_options)
     }
 
     /// Returns a reversed iterator that scans from the end key to the begin key
@@ -501,6 +668,7 @@ impl StateStore for HummockStorage {
             let version = self.local_version_manager.get_version()?;
             // Check epoch validity
             validate_epoch(version.safe_epoch(), epoch)?;
+            let prefix = range_common_prefix(&key_range, true);
             // Filter out tables that overlap with given `key_range`
             let mut overlapped_sstable_iters = vec![];
             for level in &version.levels() {
@@ -518,7 +686,13 @@ impl StateStore for HummockStorage {
                 if table_ids.is_empty() {
                     continue;
                 }
-                let mut tables = self.sstable_store.sstables(&table_ids).await?;
+                let mut tables = self.filter_tables_by_prefix(
+                    self.sstable_store.sstables(&table_ids).await?,
+                    prefix,
+                );
+                if tables.is_empty() {
+                    continue;
+                }
                 match level.level_type() {
                     LevelType::Overlapping => {
                         for table in tables.into_iter().rev() {
@@ -566,8 +740,8 @@ impl StateStore for HummockStorage {
             };
 
             // TODO: avoid this clone
-            let mut reverse_user_iter =
-                DirectedUserIterator::Backward(ReverseUserIterator::new_with_epoch(
+            let mut reverse_user_iter = DirectedUserIterator::Backward(
+                ReverseUserIterator::new_with_epoch(
                     reverse_merge_iterator,
                     (
                         key_range.end_bound().map(|b| b.as_ref().to_owned()),
@@ -575,7 +749,9 @@ impl StateStore for HummockStorage {
                     ),
                     epoch,
                     Some(version),
-                ));
+                )
+                .with_state_ttls(self.state_ttls.clone()),
+            );
 
             reverse_user_iter.rewind().await?;
             Ok(HummockStateStoreIter::new(reverse_user_iter))
@@ -586,12 +762,30 @@ impl StateStore for HummockStorage {
         async move { Ok(self.local_version_manager.wait_epoch(epoch).await?) }
     }
 
+    fn set_ttl(&self, prefix: &[u8], ttl: Duration) {
+        self.state_ttls.register(prefix, ttl);
+    }
+
+    fn clear_ttl(&self, prefix: &[u8]) {
+        self.state_ttls.unregister(prefix);
+    }
+
+    fn set_prefix_extractor(&self, prefix: &[u8], prefix_len: usize) {
+        self.prefix_extractors.register(prefix, prefix_len as u32);
+    }
+
     fn sync(&self, epoch: Option<u64>) -> Self::SyncFuture<'_> {
         async move {
             self.shared_buffer_manager.sync(epoch).await?;
             Ok(())
         }
     }
+
+    fn reset_epochs(&self, epochs: &[u64]) {
+        for &epoch in epochs {
+            self.shared_buffer_manager.reset(epoch);
+        }
+    }
 }
 
 pub struct HummockStateStoreIter<'a> {
@@ -15,3 +15,4 @@
 pub mod shared_buffer_batch;
 pub mod shared_buffer_manager;
 pub mod shared_buffer_uploader;
+mod spill;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -19,22 +20,54 @@ use bytes::Bytes;
 
 use crate::hummock::iterator::variants::*;
 use crate::hummock::iterator::HummockIterator;
+use crate::hummock::shared_buffer::spill::SpilledItems;
 use crate::hummock::value::HummockValue;
-use crate::hummock::{key, HummockEpoch, HummockResult};
+use crate::hummock::{key, HummockEpoch, HummockResult, RangeTombstone};
 use crate::storage_value::VALUE_META_SIZE;
 
 pub(super) type SharedBufferItem = (Bytes, HummockValue<Bytes>);
 
+/// Items of a batch, which are kept in memory until the batch is spilled to local disk.
+#[derive(Clone, Debug)]
+pub(super) enum SharedBufferItems {
+    InMemory(Arc<[SharedBufferItem]>),
+    Spilled {
+        items: SpilledItems,
+        first_key: Option<Bytes>,
+        last_key: Option<Bytes>,
+    },
+}
+
 /// A write batch stored in the shared buffer.
 #[derive(Clone, Debug)]
 pub struct SharedBufferBatch {
-    pub(super) inner: Arc<[SharedBufferItem]>,
+    pub(super) inner: SharedBufferItems,
+    /// Range tombstones of the batch, whose epochs are all `epoch`.
+    pub(super) range_tombstones: Arc<[RangeTombstone]>,
     pub(super) epoch: HummockEpoch,
     pub(super) size: u64,
+    /// Whether the size of the batch has been released from the shared buffer memory, which is
+    /// shared with the spilled copy of the batch.
+    memory_released: Arc<AtomicBool>,
 }
 
 impl SharedBufferBatch {
     pub fn new(sorted_items: Vec<SharedBufferItem>, epoch: HummockEpoch) -> Self {
+        Self::with_range_tombstones(sorted_items, vec![], epoch)
+    }
+
+    /// Creates a batch with range tombstones. The batch must not be empty, and the range
+    /// tombstones must not overlap with the items, as they don't delete versions of the same
+    /// epoch.
+    pub fn with_range_tombstones(
+        sorted_items: Vec<SharedBufferItem>,
+        mut range_tombstones: Vec<RangeTombstone>,
+        epoch: HummockEpoch,
+    ) -> Self {
+        assert!(!sorted_items.is_empty() || !range_tombstones.is_empty());
+        assert!(range_tombstones.iter().all(|t| t.epoch == epoch));
+        range_tombstones.sort_by(|l, r| l.start_user_key.cmp(&r.start_user_key));
+
         // size = Sum(length of full key + length of user value)
         let size: u64 = sorted_items
             .iter()
@@ -47,51 +80,148 @@ impl SharedBufferBatch {
                 };
                 (k.len() + vsize) as u64
             })
+            .chain(range_tombstones.iter().map(|t| t.encoded_size() as u64))
             .sum();
 
         Self {
-            inner: sorted_items.into(),
+            inner: SharedBufferItems::InMemory(sorted_items.into()),
+            range_tombstones: range_tombstones.into(),
             epoch,
             size,
+            memory_released: Arc::new(AtomicBool::new(false)),
         }
     }
 
-    pub fn get(&self, user_key: &[u8]) -> Option<HummockValue<Vec<u8>>> {
-        // Perform binary search on user key because the items in SharedBufferBatch is ordered by
-        // user key.
-        match self
-            .inner
-            .binary_search_by(|m| key::user_key(&m.0).cmp(user_key))
-        {
-            Ok(i) => Some(self.inner[i].1.to_vec()),
-            Err(_) => None,
+    /// Returns a copy of the batch whose items are read from `spilled_items` on demand, so that
+    /// the in-memory items can be released.
+    pub(super) fn spilled(&self, spilled_items: SpilledItems) -> Self {
+        Self {
+            inner: SharedBufferItems::Spilled {
+                items: spilled_items,
+                first_key: self.first_key().map(Bytes::copy_from_slice),
+                last_key: self.last_key().map(Bytes::copy_from_slice),
+            },
+            range_tombstones: self.range_tombstones.clone(),
+            epoch: self.epoch,
+            size: self.size,
+            memory_released: self.memory_released.clone(),
+        }
+    }
+
+    /// Marks the memory of the batch as released, once it's spilled, synced or deleted. Returns
+    /// the size to release, which is 0 if it has been released already.
+    pub(super) fn release_memory(&self) -> u64 {
+        if self.memory_released.swap(true, Ordering::SeqCst) {
+            0
+        } else {
+            self.size
+        }
+    }
+
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.inner, SharedBufferItems::Spilled { .. })
+    }
+
+    /// Returns true if both batches refer to the same in-memory items.
+    pub(super) fn shares_items_with(&self, other: &SharedBufferBatch) -> bool {
+        match (&self.inner, &other.inner) {
+            (SharedBufferItems::InMemory(l), SharedBufferItems::InMemory(r)) => Arc::ptr_eq(l, r),
+            _ => false,
+        }
+    }
+
+    /// Returns the items of the batch, which are loaded from local disk if the batch is spilled.
+    pub(super) async fn items(&self) -> HummockResult<Arc<[SharedBufferItem]>> {
+        match &self.inner {
+            SharedBufferItems::InMemory(items) => Ok(items.clone()),
+            SharedBufferItems::Spilled { items, .. } => Ok(items.load().await?.into()),
+        }
+    }
+
+    fn first_key(&self) -> Option<&[u8]> {
+        match &self.inner {
+            SharedBufferItems::InMemory(items) => items.first().map(|item| item.0.as_ref()),
+            SharedBufferItems::Spilled { first_key, .. } => first_key.as_deref(),
+        }
+    }
+
+    fn last_key(&self) -> Option<&[u8]> {
+        match &self.inner {
+            SharedBufferItems::InMemory(items) => items.last().map(|item| item.0.as_ref()),
+            SharedBufferItems::Spilled { last_key, .. } => last_key.as_deref(),
         }
     }
 
+    pub async fn get(&self, user_key: &[u8]) -> HummockResult<Option<HummockValue<Vec<u8>>>> {
+        match &self.inner {
+            SharedBufferItems::InMemory(items) => {
+                // Perform binary search on user key because the items in SharedBufferBatch is
+                // ordered by user key.
+                match items.binary_search_by(|m| key::user_key(&m.0).cmp(user_key)) {
+                    Ok(i) => Ok(Some(items[i].1.to_vec())),
+                    Err(_) => Ok(None),
+                }
+            }
+            SharedBufferItems::Spilled { items, .. } => {
+                Ok(items.get(user_key).await?.map(|value| value.to_vec()))
+            }
+        }
+    }
+
+    /// Returns true if a range tombstone of the batch covers `user_key`, i.e. deletes the versions
+    /// of it before `epoch`.
+    pub fn range_deleted(&self, user_key: &[u8]) -> bool {
+        self.range_tombstones.iter().any(|t| t.covers(user_key))
+    }
+
+    pub fn range_tombstones(&self) -> &[RangeTombstone] {
+        &self.range_tombstones
+    }
+
     pub fn iter(&self) -> SharedBufferBatchIterator<FORWARD> {
-        SharedBufferBatchIterator::<FORWARD>::new(self.inner.clone())
+        SharedBufferBatchIterator::<FORWARD>::from_batch(self)
     }
 
     pub fn reverse_iter(&self) -> SharedBufferBatchIterator<BACKWARD> {
-        SharedBufferBatchIterator::<BACKWARD>::new(self.inner.clone())
+        SharedBufferBatchIterator::<BACKWARD>::from_batch(self)
     }
 
     #[allow(dead_code)]
     pub fn start_key(&self) -> &[u8] {
-        &self.inner.first().unwrap().0
+        self.first_key().unwrap()
     }
 
     #[allow(dead_code)]
     pub fn end_key(&self) -> &[u8] {
-        &self.inner.last().unwrap().0
+        self.last_key().unwrap()
     }
 
+    /// The smallest user key of the batch, including the start user keys of range tombstones.
     pub fn start_user_key(&self) -> &[u8] {
-        key::user_key(&self.inner.first().unwrap().0)
+        let item_key = self.first_key().map(key::user_key);
+        let tombstone_key = self
+            .range_tombstones
+            .first()
+            .map(|t| t.start_user_key.as_slice());
+        match (item_key, tombstone_key) {
+            (Some(l), Some(r)) => std::cmp::min(l, r),
+            (l, r) => l.or(r).unwrap(),
+        }
     }
 
+    /// The largest user key of the batch, including the (exclusive) end user keys of range
+    /// tombstones.
     pub fn end_user_key(&self) -> &[u8] {
-        key::user_key(&self.inner.last().unwrap().0)
+        let item_key = self.last_key().map(key::user_key);
+        let tombstone_key = self
+            .range_tombstones
+            .iter()
+            .map(|t| t.end_user_key.as_slice())
+            .max();
+        match (item_key, tombstone_key) {
+            (Some(l), Some(r)) => std::cmp::max(l, r),
+            (l, r) => l.or(r).unwrap(),
+        }
     }
 
     pub fn epoch(&self) -> u64 {
@@ -101,17 +231,44 @@ impl SharedBufferBatch {
 
 pub struct SharedBufferBatchIterator<const DIRECTION: usize> {
     inner: Arc<[SharedBufferItem]>,
+    /// Items of a spilled batch, which are loaded on the first rewind or seek.
+    spilled_items: Option<SpilledItems>,
+    range_tombstones: Arc<[RangeTombstone]>,
     current_idx: usize,
 }
 
 impl<const DIRECTION: usize> SharedBufferBatchIterator<DIRECTION> {
-    pub fn new(inner: Arc<[SharedBufferItem]>) -> Self {
+    pub fn new(inner: Arc<[SharedBufferItem]>, range_tombstones: Arc<[RangeTombstone]>) -> Self {
         Self {
             inner,
+            spilled_items: None,
+            range_tombstones,
             current_idx: 0,
         }
     }
 
+    fn from_batch(batch: &SharedBufferBatch) -> Self {
+        match &batch.inner {
+            SharedBufferItems::InMemory(items) => {
+                Self::new(items.clone(), batch.range_tombstones.clone())
+            }
+            SharedBufferItems::Spilled { items, .. } => Self {
+                inner: Arc::new([]),
+                spilled_items: Some(items.clone()),
+                range_tombstones: batch.range_tombstones.clone(),
+                current_idx: 0,
+            },
+        }
+    }
+
+    /// Loads the items from local disk if the batch is spilled.
+    async fn load(&mut self) -> HummockResult<()> {
+        if let Some(spilled_items) = self.spilled_items.take() {
+            self.inner = spilled_items.load().await?.into();
+        }
+        Ok(())
+    }
+
     fn current_item(&self) -> &SharedBufferItem {
         assert!(self.is_valid());
         let idx = match DIRECTION {
@@ -143,12 +300,18 @@ impl<const DIRECTION: usize> HummockIterator for SharedBufferBatchIterator<DIREC
         self.current_idx < self.inner.len()
     }
 
+    fn collect_range_tombstones(&self, range_tombstones: &mut Vec<RangeTombstone>) {
+        range_tombstones.extend_from_slice(&self.range_tombstones);
+    }
+
     async fn rewind(&mut self) -> HummockResult<()> {
+        self.load().await?;
         self.current_idx = 0;
         Ok(())
     }
 
     async fn seek(&mut self, key: &[u8]) -> HummockResult<()> {
+        self.load().await?;
         // Perform binary search on user key because the items in SharedBufferBatch is ordered by
         // user key.
         let partition_point = self
@@ -249,16 +412,25 @@ mod tests {
         // Point lookup
         for (k, v) in &shared_buffer_items {
             assert_eq!(
-                shared_buffer_batch.get(user_key(k.as_slice())),
+                shared_buffer_batch
+                    .get(user_key(k.as_slice()))
+                    .await
+                    .unwrap(),
                 Some(v.clone())
             );
         }
         assert_eq!(
-            shared_buffer_batch.get(iterator_test_key_of(3).as_slice()),
+            shared_buffer_batch
+                .get(iterator_test_key_of(3).as_slice())
+                .await
+                .unwrap(),
             None
         );
         assert_eq!(
-            shared_buffer_batch.get(iterator_test_key_of(4).as_slice()),
+            shared_buffer_batch
+                .get(iterator_test_key_of(4).as_slice())
+                .await
+                .unwrap(),
             None
         );
 
@@ -18,10 +18,10 @@ use std::sync::atomic::Ordering;
 use std::sync::{atomic, Arc};
 
 use itertools::Itertools;
-use parking_lot::{Mutex, RwLock as PLRwLock};
+use parking_lot::RwLock as PLRwLock;
 use risingwave_common::config::StorageConfig;
 use risingwave_rpc_client::HummockMetaClient;
-use tokio::sync::watch::{Receiver as WatchReceiver, Sender as WatchSender};
+use tokio::sync::Notify;
 use tokio::task::JoinHandle;
 
 use crate::error::StorageResult;
@@ -33,13 +33,18 @@ use crate::hummock::shared_buffer::shared_buffer_batch::{
 use crate::hummock::shared_buffer::shared_buffer_uploader::{
     SharedBufferUploader, SharedBufferUploaderItem, SyncItem,
 };
+use crate::hummock::shared_buffer::spill::SpillFile;
 use crate::hummock::utils::range_overlap;
 use crate::hummock::value::HummockValue;
-use crate::hummock::{HummockEpoch, HummockError, HummockResult, SstableStoreRef};
+use crate::hummock::{
+    HummockEpoch, HummockError, HummockResult, PrefixExtractorRegistry, RangeTombstone,
+    SstableStoreRef,
+};
 use crate::monitor::StateStoreMetrics;
 
 #[derive(Debug)]
 pub struct SharedBufferMetrics {
+    /// Size of batches not synced yet, excluding spilled ones.
     pub shared_buffer_cur_size: atomic::AtomicU64,
     pub shared_buffer_threshold_size: u64,
 }
@@ -61,40 +66,12 @@ pub struct SharedBufferManager {
     uploader_tx: tokio::sync::mpsc::UnboundedSender<SharedBufferUploaderItem>,
     uploader_handle: JoinHandle<StorageResult<()>>,
     stats: SharedBufferMetrics,
-    ongoing_flush: OnGoingFlush,
-}
-
-#[derive(Debug)]
-struct OnGoingFlush {
-    data: Mutex<Option<WatchSender<bool>>>,
-}
-
-impl OnGoingFlush {
-    pub fn new() -> Self {
-        Self {
-            data: Mutex::new(Option::None),
-        }
-    }
-
-    pub fn subscribe(&self) -> Option<WatchReceiver<bool>> {
-        let guard = self.data.lock();
-        guard.as_ref().map(|tx| tx.subscribe())
-    }
-
-    pub fn init(&self) {
-        let mut guard = self.data.lock();
-        assert!(guard.is_none());
-        let (watch_tx, _) = tokio::sync::watch::channel(false);
-        guard.replace(watch_tx);
-    }
-
-    pub fn notify(&self, success: bool) {
-        let mut guard = self.data.lock();
-        assert!(guard.is_some());
-        if let Some(watch_tx) = guard.take() {
-            watch_tx.send(success).ok(); // ignore send error
-        }
-    }
+    /// Notified when in-memory batches are synced, deleted or spilled.
+    memory_released: Notify,
+    /// Held by the ongoing flush triggered by exceeding the threshold.
+    flush_lock: tokio::sync::Mutex<()>,
+    /// Local directory to spill sealed epochs to. Empty if spilling is disabled.
+    spill_dir: String,
 }
 
 impl SharedBufferManager {
@@ -105,15 +82,18 @@ impl SharedBufferManager {
         // TODO: separate `HummockStats` from `StateStoreMetrics`.
         state_store_stats: Arc<StateStoreMetrics>,
         hummock_meta_client: Arc<dyn HummockMetaClient>,
+        prefix_extractors: Arc<PrefixExtractorRegistry>,
     ) -> Self {
         let (uploader_tx, uploader_rx) = tokio::sync::mpsc::unbounded_channel();
         let stats = SharedBufferMetrics::new(options.as_ref());
+        let spill_dir = options.shared_buffer_spill_dir.clone();
         let uploader = SharedBufferUploader::new(
             options,
             local_version_manager,
             sstable_store,
             state_store_stats,
             hummock_meta_client,
+            prefix_extractors,
             uploader_rx,
         );
         let uploader_handle = tokio::spawn(uploader.run());
@@ -122,51 +102,175 @@ impl SharedBufferManager {
             uploader_tx,
             uploader_handle,
             stats,
-            ongoing_flush: OnGoingFlush::new(),
+            memory_released: Notify::new(),
+            flush_lock: tokio::sync::Mutex::new(()),
+            spill_dir,
         }
     }
 
-    fn empty(&self) -> bool {
-        self.stats.shared_buffer_cur_size.load(Ordering::SeqCst) == 0
+    /// Reserves memory for a batch of `epoch`. When the shared buffer threshold is exceeded, the
+    /// sealed epochs, i.e. those before `epoch`, are spilled to local disk if spilling is enabled.
+    /// Otherwise all the batches are flushed, i.e. synced, to release memory before the batch is
+    /// admitted.
+    ///
+    /// A batch larger than the threshold alone is only admitted to an empty shared buffer.
+    async fn reserve_memory(&self, epoch: HummockEpoch, batch_size: u64) -> HummockResult<()> {
+        let threshold = self.stats.shared_buffer_threshold_size;
+        loop {
+            let cur_size = self.stats.shared_buffer_cur_size.load(Ordering::SeqCst);
+            let new_size = cur_size + batch_size;
+            if new_size > threshold && cur_size > 0 {
+                if self.spill_enabled()
+                    && self
+                        .spill_sealed_epochs(epoch, new_size - threshold)
+                        .await?
+                        > 0
+                {
+                    continue;
+                }
+                self.flush(threshold, new_size).await?;
+                continue;
+            }
+            if self
+                .stats
+                .shared_buffer_cur_size
+                .compare_exchange(cur_size, new_size, Ordering::SeqCst, Ordering::Acquire)
+                .is_ok()
+            {
+                return Ok(());
+            }
+        }
     }
 
-    /// Allocates a shared buffer budget.
-    async fn allocate_space(&self, batch_size: u64) -> HummockResult<()> {
-        let mut current_size = self.stats.shared_buffer_cur_size.load(Ordering::SeqCst);
-        let threshold = self.stats.shared_buffer_threshold_size;
+    /// Syncs all the batches to release memory. If another flush is ongoing, waits for it to
+    /// release memory instead.
+    async fn flush(&self, threshold: u64, new_size: u64) -> HummockResult<()> {
+        // Created before the flush, so that no release in between is missed.
+        let released = self.memory_released.notified();
+        let _guard = match self.flush_lock.try_lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                released.await;
+                return Ok(());
+            }
+        };
+        log::debug!(
+            "trigger flush: threshold {}, new_size {}",
+            threshold,
+            new_size
+        );
+        let result = self.sync(None).await;
+        // Wake up the waiters even if nothing is released, so that they check again.
+        self.memory_released.notify_waiters();
+        result
+    }
 
-        // Atomically allocates space,
-        // since there could be concurrent Actors write to the shared buffer.
-        'retry_allocate: loop {
-            // flush shared buffer if there is no enough space
-            while threshold < current_size + batch_size {
-                log::debug!(
-                    "trigge flush: threshold {}, new_size {}",
-                    threshold,
-                    current_size + batch_size
-                );
-                self.flush().await?;
-                current_size = self.stats.shared_buffer_cur_size.load(Ordering::SeqCst);
+    fn spill_enabled(&self) -> bool {
+        !self.spill_dir.is_empty()
+    }
+
+    /// Spills in-memory batches of epochs before `epoch` to local disk, from the latest epoch,
+    /// until at least `target_size` bytes are released. Returns the size released.
+    async fn spill_sealed_epochs(
+        &self,
+        epoch: HummockEpoch,
+        target_size: u64,
+    ) -> HummockResult<u64> {
+        let mut spilled_size = 0;
+        let sealed_epochs = self
+            .shared_buffer
+            .read()
+            .range(..epoch)
+            .rev()
+            .map(|(epoch, _)| *epoch)
+            .collect_vec();
+        for sealed_epoch in sealed_epochs {
+            if spilled_size >= target_size {
+                break;
             }
+            spilled_size += self.spill_epoch(sealed_epoch).await?;
+        }
+        Ok(spilled_size)
+    }
 
-            let res = self.stats.shared_buffer_cur_size.compare_exchange(
-                current_size,
-                current_size + batch_size,
-                Ordering::SeqCst,
-                Ordering::Acquire,
-            );
-            match res {
-                Ok(_) => {
-                    assert!(current_size + batch_size <= threshold);
-                    break; // success
-                }
-                Err(old_val) => {
-                    current_size = old_val;
-                    continue 'retry_allocate;
+    /// Spills in-memory batches of `epoch` to a local file. Returns the size released.
+    async fn spill_epoch(&self, epoch: HummockEpoch) -> HummockResult<u64> {
+        let batches = match self.shared_buffer.read().get(&epoch) {
+            Some(buffers) => buffers
+                .values()
+                .filter(|batch| !batch.is_spilled())
+                .cloned()
+                .collect_vec(),
+            None => return Ok(0),
+        };
+        if batches.is_empty() {
+            return Ok(0);
+        }
+        // Write the file without holding the lock.
+        let mut items = Vec::with_capacity(batches.len());
+        for batch in &batches {
+            items.push(batch.items().await?);
+        }
+        let spilled_items = SpillFile::create(
+            &self.spill_dir,
+            epoch,
+            &items.iter().map(|items| items.as_ref()).collect_vec(),
+        )
+        .await?;
+
+        let mut spilled_size = 0;
+        let mut spilled_batches = vec![];
+        {
+            let mut guard = self.shared_buffer.write();
+            // Batches may have been deleted in the meantime.
+            if let Some(buffers) = guard.get_mut(&epoch) {
+                for (batch, spilled_items) in batches.into_iter().zip_eq(spilled_items) {
+                    if let Some(buffer) = buffers.values_mut().find(|b| b.shares_items_with(&batch))
+                    {
+                        *buffer = batch.spilled(spilled_items);
+                        spilled_size += batch.release_memory();
+                        spilled_batches.push((batch, buffer.clone()));
+                    }
                 }
             }
+            // Sent while holding the lock, so that the uploader receives it after the batches.
+            if !spilled_batches.is_empty() {
+                self.uploader_tx
+                    .send(SharedBufferUploaderItem::Spill(spilled_batches))
+                    .map_err(HummockError::shared_buffer_error)?;
+            }
         }
-        Ok(())
+        self.release_memory(spilled_size);
+        log::debug!(
+            "spill {} bytes of epoch {} to local disk",
+            spilled_size,
+            epoch
+        );
+        Ok(spilled_size)
+    }
+
+    fn release_memory(&self, size: u64) {
+        if size > 0 {
+            let prev_size = self
+                .stats
+                .shared_buffer_cur_size
+                .fetch_sub(size, Ordering::SeqCst);
+            assert!(prev_size >= size);
+            self.memory_released.notify_waiters();
+        }
+    }
+
+    /// Releases the memory of the given epochs, which is 0 for the batches synced or spilled.
+    fn release_epochs<'a>(
+        &self,
+        buffers: impl Iterator<Item = &'a BTreeMap<Vec<u8>, SharedBufferBatch>>,
+    ) {
+        self.release_memory(
+            buffers
+                .flat_map(|buffers| buffers.values())
+                .map(|batch| batch.release_memory())
+                .sum(),
+        );
     }
 
     /// Puts a write batch into shared buffer. The batch will be synced to S3 asynchronously.
@@ -175,16 +279,41 @@ impl SharedBufferManager {
         batch: Vec<SharedBufferItem>,
         epoch: HummockEpoch,
     ) -> HummockResult<u64> {
-        let batch = SharedBufferBatch::new(batch, epoch);
+        self.write_shared_buffer_batch(SharedBufferBatch::new(batch, epoch))
+            .await
+    }
+
+    /// Puts range tombstones into shared buffer. They will be synced to S3 asynchronously, along
+    /// with the write batches of the same epoch.
+    pub async fn write_range_tombstones(
+        &self,
+        range_tombstones: Vec<RangeTombstone>,
+        epoch: HummockEpoch,
+    ) -> HummockResult<u64> {
+        self.write_shared_buffer_batch(SharedBufferBatch::with_range_tombstones(
+            vec![],
+            range_tombstones,
+            epoch,
+        ))
+        .await
+    }
+
+    async fn write_shared_buffer_batch(&self, batch: SharedBufferBatch) -> HummockResult<u64> {
+        let epoch = batch.epoch();
         let size = batch.size;
 
-        self.allocate_space(size).await?;
+        self.reserve_memory(epoch, size).await?;
 
-        self.shared_buffer
-            .write()
-            .entry(epoch)
-            .or_insert(BTreeMap::new())
-            .insert(batch.end_user_key().to_vec(), batch.clone());
+        let mut guard = self.shared_buffer.write();
+        let buffers = guard.entry(epoch).or_insert(BTreeMap::new());
+        // A range tombstone may end at the last key of another batch in the same epoch. Both
+        // batches are kept by suffixing the index key, which still sorts after the end key.
+        let mut index_key = batch.end_user_key().to_vec();
+        while buffers.contains_key(&index_key) {
+            index_key.push(0);
+        }
+        buffers.insert(index_key, batch.clone());
+        // Sent while holding the lock, so that a spill of the batch is received after it.
         self.uploader_tx
             .send(SharedBufferUploaderItem::Batch(batch))
             .map_err(HummockError::shared_buffer_error)?;
@@ -198,45 +327,18 @@ impl SharedBufferManager {
         epoch: u64,
     ) -> HummockResult<()> {
         let batch = SharedBufferBatch::new(batch, epoch);
+        // Replicated batches are never synced by this node, so they're not accounted.
+        batch.release_memory();
         self.shared_buffer
             .write()
             .entry(epoch)
             .or_insert(BTreeMap::new())
-            .insert(batch.end_user_key().to_vec(), batch.clone());
+            .insert(batch.end_user_key().to_vec(), batch);
         Ok(())
     }
 
-    async fn flush(&self) -> HummockResult<()> {
-        assert!(!self.empty());
-        let mut res = Ok(());
-
-        let notifier = self.ongoing_flush.subscribe();
-        if let Some(mut rx) = notifier {
-            log::debug!("flush: wait for notification");
-            // Wait for notification
-            rx.changed().await.unwrap();
-            if !(*rx.borrow()) {
-                res = Err(HummockError::shared_buffer_error(
-                    "Fail to flush shared buffer",
-                ));
-            }
-        } else {
-            self.ongoing_flush.init();
-            // Flush all batches in the shared buffer
-            res = self.sync(None).await;
-            // Notify other waiters if any
-            self.ongoing_flush.notify(res.is_ok());
-            log::debug!("flush: notify subscribers, result {}", res.is_ok());
-        }
-        res
-    }
-
     // TODO: support time-based syncing
     pub async fn sync(&self, epoch: Option<HummockEpoch>) -> HummockResult<()> {
-        if self.empty() {
-            return Ok(());
-        }
-
         let (tx, rx) = tokio::sync::oneshot::channel();
         self.uploader_tx
             .send(SharedBufferUploaderItem::Sync(SyncItem {
@@ -244,47 +346,64 @@ impl SharedBufferManager {
                 notifier: Some(tx),
             }))
             .unwrap();
-
-        let mut res = Ok(());
-        let sync_res = rx.await.unwrap();
-        match sync_res {
-            Ok(sync_size) => {
-                // Update the shared buffer size
-                let shared_buff_prev_size = self
-                    .stats
-                    .shared_buffer_cur_size
-                    .fetch_sub(sync_size, Ordering::SeqCst);
-                assert!(shared_buff_prev_size >= sync_size);
-            }
-            Err(e) => {
-                res = Err(e);
-            }
-        }
-        res
+        let sync_size = rx.await.unwrap()?;
+        self.release_memory(sync_size);
+        Ok(())
     }
 
     /// Searches shared buffers within the `epoch_range` for the given key.
     /// Return:
     /// - None: the key doesn't exist in the shared buffer.
-    /// - Some(`HummockValue`): the `HummockValue` corresponding to the key.
-    pub fn get(
+    /// - Some(`HummockValue`): the `HummockValue` corresponding to the key. A key deleted by a
+    ///   range tombstone is returned as `HummockValue::Delete`.
+    pub async fn get(
         &self,
         user_key: &[u8],
         epoch_range: impl RangeBounds<u64>,
-    ) -> Option<HummockValue<Vec<u8>>> {
-        let guard = self.shared_buffer.read();
-        for (_epoch, buffers) in guard.range(epoch_range).rev() {
-            for (_, m) in buffers.range(user_key.to_vec()..) {
-                if m.start_user_key() > user_key {
-                    continue;
-                }
-                match m.get(user_key) {
-                    Some(v) => return Some(v),
-                    None => continue,
+    ) -> HummockResult<Option<HummockValue<Vec<u8>>>> {
+        Ok(self
+            .get_with_epoch(user_key, epoch_range)
+            .await?
+            .map(|(_, value)| value))
+    }
+
+    /// Same as `get`, but also returns the epoch of the `HummockValue`.
+    pub async fn get_with_epoch(
+        &self,
+        user_key: &[u8],
+        epoch_range: impl RangeBounds<u64>,
+    ) -> HummockResult<Option<(HummockEpoch, HummockValue<Vec<u8>>)>> {
+        // The batches that may contain the key, from the latest epoch. They are searched without
+        // holding the lock, since spilled ones are read from local disk.
+        let candidates = self
+            .shared_buffer
+            .read()
+            .range(epoch_range)
+            .rev()
+            .map(|(epoch, buffers)| {
+                let batches = buffers
+                    .range(user_key.to_vec()..)
+                    .map(|(_, m)| m)
+                    .filter(|m| m.start_user_key() <= user_key)
+                    .cloned()
+                    .collect_vec();
+                (*epoch, batches)
+            })
+            .collect_vec();
+        for (epoch, batches) in candidates {
+            let mut range_deleted = false;
+            for m in batches {
+                match m.get(user_key).await? {
+                    Some(v) => return Ok(Some((epoch, v))),
+                    None => range_deleted |= m.range_deleted(user_key),
                 }
             }
+            // Range tombstones only delete the versions of previous epochs.
+            if range_deleted {
+                return Ok(Some((epoch, HummockValue::delete())));
+            }
         }
-        None
+        Ok(None)
     }
 
     /// Gets a collection of forward `SharedBufferBatchIterator` to iterate data of shared buffer
@@ -349,7 +468,9 @@ impl SharedBufferManager {
     pub fn delete_before(&self, epoch: u64) {
         let mut guard = self.shared_buffer.write();
         let new = guard.split_off(&epoch);
-        *guard = new;
+        let deleted = std::mem::replace(&mut *guard, new);
+        drop(guard);
+        self.release_epochs(deleted.values());
     }
 
     /// This function was called while [`SharedBufferManager`] exited.
@@ -357,13 +478,14 @@ impl SharedBufferManager {
         self.uploader_handle.await.unwrap()
     }
 
-    pub fn reset(&mut self, epoch: u64) {
+    pub fn reset(&self, epoch: u64) {
         // Reset uploader item.
         self.uploader_tx
             .send(SharedBufferUploaderItem::Reset(epoch))
             .unwrap();
         // Remove items of the given epoch from shared buffer
-        self.shared_buffer.write().remove(&epoch);
+        let removed = self.shared_buffer.write().remove(&epoch);
+        self.release_epochs(removed.iter());
     }
 
     pub fn stats(&self) -> &SharedBufferMetrics {
@@ -394,6 +516,10 @@ mod tests {
     use crate::object::{InMemObjectStore, ObjectStoreImpl};
 
     async fn new_shared_buffer_manager() -> SharedBufferManager {
+        new_shared_buffer_manager_with_config(default_config_for_test()).await
+    }
+
+    async fn new_shared_buffer_manager_with_config(config: StorageConfig) -> SharedBufferManager {
         let obj_client = Arc::new(ObjectStoreImpl::Mem(InMemObjectStore::new()));
         let remote_dir = "/test";
         let sstable_store = Arc::new(SstableStore::new(
@@ -411,11 +537,12 @@ mod tests {
             worker_node.id,
         ));
         SharedBufferManager::new(
-            Arc::new(default_config_for_test()),
+            Arc::new(config),
             vm,
             sstable_store,
             Arc::new(StateStoreMetrics::unused()),
             mock_hummock_meta_client,
+            Arc::new(PrefixExtractorRegistry::default()),
         )
     }
 
@@ -490,12 +617,17 @@ mod tests {
             assert_eq!(
                 shared_buffer_manager
                     .get(keys[i].as_slice(), ..=epoch1)
+                    .await
+                    .unwrap()
                     .unwrap(),
                 shared_buffer_items1[i].1
             );
         }
         assert_eq!(
-            shared_buffer_manager.get(keys[3].as_slice(), ..=epoch1),
+            shared_buffer_manager
+                .get(keys[3].as_slice(), ..=epoch1)
+                .await
+                .unwrap(),
             None
         );
 
@@ -503,43 +635,121 @@ mod tests {
         assert_eq!(
             shared_buffer_manager
                 .get(keys[0].as_slice(), ..=epoch2)
+                .await
+                .unwrap()
                 .unwrap(),
             shared_buffer_items1[0].1
         );
         assert_eq!(
             shared_buffer_manager
                 .get(keys[1].as_slice(), ..=epoch2)
+                .await
+                .unwrap()
                 .unwrap(),
             shared_buffer_items2[0].1
         );
         assert_eq!(
             shared_buffer_manager
                 .get(keys[2].as_slice(), ..=epoch2)
+                .await
+                .unwrap()
                 .unwrap(),
             HummockValue::delete()
         );
         assert_eq!(
             shared_buffer_manager
                 .get(keys[3].as_slice(), ..=epoch2)
+                .await
+                .unwrap()
                 .unwrap(),
             shared_buffer_items2[2].1
         );
 
         // Get and check value with epoch epoch2..=epoch2
         assert_eq!(
-            shared_buffer_manager.get(keys[0].as_slice(), epoch2..=epoch2),
+            shared_buffer_manager
+                .get(keys[0].as_slice(), epoch2..=epoch2)
+                .await
+                .unwrap(),
             None
         );
         for i in 0..3 {
             assert_eq!(
                 shared_buffer_manager
                     .get(keys[i + 1].as_slice(), epoch2..=epoch2)
+                    .await
+                    .unwrap()
                     .unwrap(),
                 shared_buffer_items2[i].1
             );
         }
     }
 
+    #[tokio::test]
+    async fn test_shared_buffer_manager_get_range_deleted() {
+        let shared_buffer_manager = new_shared_buffer_manager().await;
+
+        let mut keys = Vec::new();
+        for i in 0..3 {
+            keys.push(format!("key_test_{:05}", i).as_bytes().to_vec());
+        }
+        let mut idx = 0;
+
+        let epoch1 = 1;
+        let shared_buffer_items1 =
+            generate_and_write_batch(&keys, &[], epoch1, &mut idx, &shared_buffer_manager).await;
+
+        // Delete key0 and key1 in epoch2, and rewrite key1 in the same epoch.
+        let epoch2 = epoch1 + 1;
+        shared_buffer_manager
+            .write_range_tombstones(
+                vec![RangeTombstone::new(
+                    keys[0].clone(),
+                    keys[2].clone(),
+                    epoch2,
+                )],
+                epoch2,
+            )
+            .await
+            .unwrap();
+        let shared_buffer_items2 =
+            generate_and_write_batch(&keys[1..2], &[], epoch2, &mut idx, &shared_buffer_manager)
+                .await;
+
+        assert_eq!(
+            shared_buffer_manager
+                .get(keys[0].as_slice(), ..=epoch1)
+                .await
+                .unwrap()
+                .unwrap(),
+            shared_buffer_items1[0].1
+        );
+        assert_eq!(
+            shared_buffer_manager
+                .get(keys[0].as_slice(), ..=epoch2)
+                .await
+                .unwrap()
+                .unwrap(),
+            HummockValue::delete()
+        );
+        assert_eq!(
+            shared_buffer_manager
+                .get(keys[1].as_slice(), ..=epoch2)
+                .await
+                .unwrap()
+                .unwrap(),
+            shared_buffer_items2[0].1
+        );
+        assert_eq!(
+            shared_buffer_manager
+                .get(keys[2].as_slice(), ..=epoch2)
+                .await
+                .unwrap()
+                .unwrap(),
+            shared_buffer_items1[2].1
+        );
+    }
+
     #[tokio::test]
     async fn test_shared_buffer_manager_iter() {
         let shared_buffer_manager = new_shared_buffer_manager().await;
@@ -818,7 +1028,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_shared_buffer_manager_reset() {
-        let mut shared_buffer_manager = new_shared_buffer_manager().await;
+        let shared_buffer_manager = new_shared_buffer_manager().await;
 
         let mut keys = Vec::new();
         for i in 0..4 {
@@ -834,7 +1044,11 @@ mod tests {
         // Get and check value with epoch 0..=epoch1
         for (idx, key) in keys.iter().enumerate() {
             assert_eq!(
-                shared_buffer_manager.get(key.as_slice(), ..=epoch).unwrap(),
+                shared_buffer_manager
+                    .get(key.as_slice(), ..=epoch)
+                    .await
+                    .unwrap()
+                    .unwrap(),
                 shared_buffer_items[idx].1
             );
         }
@@ -842,7 +1056,13 @@ mod tests {
         // Reset shared buffer. Expect all keys are gone.
         shared_buffer_manager.reset(epoch);
         for item in &shared_buffer_items {
-            assert_eq!(shared_buffer_manager.get(item.0.as_slice(), ..=epoch), None);
+            assert_eq!(
+                shared_buffer_manager
+                    .get(item.0.as_slice(), ..=epoch)
+                    .await
+                    .unwrap(),
+                None
+            );
         }
 
         // Generate new items overlapping with old items and check
@@ -852,9 +1072,146 @@ mod tests {
             generate_and_write_batch(&keys, &[], epoch, &mut idx, &shared_buffer_manager).await;
         for (idx, key) in keys.iter().enumerate() {
             assert_eq!(
-                shared_buffer_manager.get(key.as_slice(), ..=epoch).unwrap(),
+                shared_buffer_manager
+                    .get(key.as_slice(), ..=epoch)
+                    .await
+                    .unwrap()
+                    .unwrap(),
                 new_shared_buffer_items[idx].1
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_shared_buffer_manager_spill() {
+        let spill_dir = std::env::temp_dir().join(format!(
+            "hummock_shared_buffer_spill_{}",
+            uuid::Uuid::new_v4()
+        ));
+        let shared_buffer_manager = new_shared_buffer_manager_with_config(StorageConfig {
+            shared_buffer_threshold_size: 100,
+            shared_buffer_spill_dir: spill_dir.to_string_lossy().to_string(),
+            ..default_config_for_test()
+        })
+        .await;
+        let spill_file_count = || std::fs::read_dir(&spill_dir).map_or(0, |dir| dir.count());
+
+        let keys = (0..4)
+            .map(|i| format!("key_test_{:05}", i).as_bytes().to_vec())
+            .collect_vec();
+        let mut idx = 0;
+
+        // The first epoch exceeds the threshold alone, which is admitted.
+        let epoch1 = 1;
+        let shared_buffer_items1 =
+            generate_and_write_batch(&keys, &[], epoch1, &mut idx, &shared_buffer_manager).await;
+        assert_eq!(spill_file_count(), 0);
+
+        // Writing to the next epoch spills the sealed epoch.
+        let epoch2 = 2;
+        let shared_buffer_items2 =
+            generate_and_write_batch(&keys[..2], &[], epoch2, &mut idx, &shared_buffer_manager)
+                .await;
+        assert_eq!(spill_file_count(), 1);
+        let shared_buffer = shared_buffer_manager.get_shared_buffer();
+        assert!(shared_buffer[&epoch1]
+            .values()
+            .all(|batch| batch.is_spilled()));
+        assert!(shared_buffer[&epoch2]
+            .values()
+            .all(|batch| !batch.is_spilled()));
+        drop(shared_buffer);
+        assert_eq!(
+            shared_buffer_manager
+                .stats()
+                .shared_buffer_cur_size
+                .load(Ordering::SeqCst),
+            shared_buffer_manager.get_shared_buffer()[&epoch2]
+                .values()
+                .map(|batch| batch.size)
+                .sum::<u64>()
+        );
+
+        // Spilled data is still readable.
+        for (idx, key) in keys.iter().enumerate() {
+            assert_eq!(
+                shared_buffer_manager
+                    .get(key.as_slice(), ..=epoch1)
+                    .await
+                    .unwrap()
+                    .unwrap(),
+                shared_buffer_items1[idx].1
+            );
+        }
+        for (idx, key) in keys[..2].iter().enumerate() {
+            assert_eq!(
+                shared_buffer_manager
+                    .get(key.as_slice(), ..=epoch2)
+                    .await
+                    .unwrap()
+                    .unwrap(),
+                shared_buffer_items2[idx].1
+            );
+        }
+
+        // Spilled data is uploaded on sync, and the file is removed after the epoch is deleted.
+        shared_buffer_manager.sync(Some(epoch1)).await.unwrap();
+        shared_buffer_manager.delete_before(epoch2);
+        assert_eq!(spill_file_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shared_buffer_manager_backpressure() {
+        let shared_buffer_manager = new_shared_buffer_manager_with_config(StorageConfig {
+            shared_buffer_threshold_size: 100,
+            ..default_config_for_test()
+        })
+        .await;
+        let cur_size = || {
+            shared_buffer_manager
+                .stats()
+                .shared_buffer_cur_size
+                .load(Ordering::SeqCst)
+        };
+        let keys = (0..4)
+            .map(|i| format!("key_test_{:05}", i).as_bytes().to_vec())
+            .collect_vec();
+        let mut idx = 0;
+
+        // The first batch exceeds the threshold alone, which is admitted to the empty shared
+        // buffer.
+        let epoch1 = 1;
+        let shared_buffer_items1 =
+            generate_and_write_batch(&keys, &[], epoch1, &mut idx, &shared_buffer_manager).await;
+        assert!(cur_size() > 100);
+
+        // The next write is only admitted after the unsynced batches are flushed.
+        let epoch2 = 2;
+        generate_and_write_batch(&keys[..1], &[], epoch2, &mut idx, &shared_buffer_manager).await;
+        let epoch2_size = shared_buffer_manager.get_shared_buffer()[&epoch2]
+            .values()
+            .map(|batch| batch.size)
+            .sum::<u64>();
+        assert_eq!(cur_size(), epoch2_size);
+        // Syncing the flushed epoch again releases nothing.
+        shared_buffer_manager.sync(Some(epoch1)).await.unwrap();
+        assert_eq!(cur_size(), epoch2_size);
+
+        // The flushed batches are still readable until deleted.
+        for (idx, key) in keys.iter().enumerate() {
+            assert_eq!(
+                shared_buffer_manager
+                    .get(key.as_slice(), ..=epoch1)
+                    .await
+                    .unwrap()
+                    .unwrap(),
+                shared_buffer_items1[idx].1
+            );
+        }
+        shared_buffer_manager.delete_before(epoch2);
+        assert_eq!(cur_size(), epoch2_size);
+
+        shared_buffer_manager.sync(Some(epoch2)).await.unwrap();
+        assert_eq!(cur_size(), 0);
+    }
 }
@@ -25,14 +25,14 @@ use crate::hummock::compactor::{Compactor, CompactorContext};
 use crate::hummock::conflict_detector::ConflictDetector;
 use crate::hummock::local_version_manager::LocalVersionManager;
 use crate::hummock::shared_buffer::shared_buffer_batch::SharedBufferBatch;
-use crate::hummock::{HummockError, HummockResult, SstableStoreRef};
+use crate::hummock::{HummockError, HummockResult, PrefixExtractorRegistry, SstableStoreRef};
 use crate::monitor::StateStoreMetrics;
 
 #[derive(Debug)]
 pub struct SyncItem {
     /// Epoch to sync. None means syncing all epochs.
     pub(super) epoch: Option<u64>,
-    /// Notifier to notify on sync finishes
+    /// Notifier to notify on sync finishes, with the size of memory released by the sync.
     pub(super) notifier: Option<tokio::sync::oneshot::Sender<HummockResult<u64>>>,
 }
 
@@ -41,6 +41,8 @@ pub enum SharedBufferUploaderItem {
     Batch(SharedBufferBatch),
     Sync(SyncItem),
     Reset(u64),
+    /// Pairs of an in-memory batch and its spilled copy, which replaces the former to upload.
+    Spill(Vec<(SharedBufferBatch, SharedBufferBatch)>),
 }
 
 pub struct SharedBufferUploader {
@@ -54,6 +56,7 @@ pub struct SharedBufferUploader {
     stats: Arc<StateStoreMetrics>,
     hummock_meta_client: Arc<dyn HummockMetaClient>,
     sstable_store: SstableStoreRef,
+    prefix_extractors: Arc<PrefixExtractorRegistry>,
 
     /// For conflict key detection. Enabled by setting `write_conflict_detection_enabled` to true
     /// in `StorageConfig`
@@ -69,6 +72,7 @@ impl SharedBufferUploader {
         sstable_store: SstableStoreRef,
         stats: Arc<StateStoreMetrics>,
         hummock_meta_client: Arc<dyn HummockMetaClient>,
+        prefix_extractors: Arc<PrefixExtractorRegistry>,
         uploader_rx: tokio::sync::mpsc::UnboundedReceiver<SharedBufferUploaderItem>,
     ) -> Self {
         Self {
@@ -79,6 +83,7 @@ impl SharedBufferUploader {
             stats,
             hummock_meta_client,
             sstable_store,
+            prefix_extractors,
             write_conflict_detector: if options.write_conflict_detection_enabled {
                 Some(Arc::new(ConflictDetector::new()))
             } else {
@@ -88,18 +93,28 @@ impl SharedBufferUploader {
         }
     }
 
-    /// Uploads buffer batches to S3.
+    /// Uploads buffer batches of the epochs up to `epoch` inclusively to S3 as a whole, and adds
+    /// the SSTs to `epoch`. Returns the size of the batches whose memory is released by the sync.
     async fn sync(&mut self, epoch: u64) -> HummockResult<u64> {
+        let epochs = self
+            .batches_to_upload
+            .range(..=epoch)
+            .map(|(epoch, _)| *epoch)
+            .collect_vec();
         if let Some(detector) = &self.write_conflict_detector {
+            for &epoch in epochs.iter().filter(|e| **e != epoch) {
+                detector.archive_epoch(epoch);
+            }
             detector.archive_epoch(epoch);
         }
 
-        let buffers = match self.batches_to_upload.remove(&epoch) {
-            Some(m) => m,
-            None => return Ok(0),
-        };
-
-        let sync_size: u64 = buffers.iter().map(|batch| batch.size).sum();
+        let buffers = epochs
+            .iter()
+            .flat_map(|epoch| self.batches_to_upload.remove(epoch).unwrap())
+            .collect_vec();
+        if buffers.is_empty() {
+            return Ok(0);
+        }
 
         // Compact buffers into SSTs
         let mem_compactor_ctx = CompactorContext {
@@ -108,11 +123,12 @@ impl SharedBufferUploader {
             sstable_store: self.sstable_store.clone(),
             stats: self.stats.clone(),
             is_share_buffer_compact: true,
+            prefix_extractors: Some(self.prefix_extractors.clone()),
         };
 
         let tables = Compactor::compact_shared_buffer(
             Arc::new(mem_compactor_ctx),
-            buffers,
+            buffers.clone(),
             self.stats.clone(),
         )
         .await?;
@@ -131,6 +147,7 @@ impl SharedBufferUploader {
                             right: sst.meta.largest_key.clone(),
                             inf: false,
                         }),
+                        file_size: sst.meta.estimated_size as u64,
                     })
                     .collect(),
             )
@@ -140,6 +157,8 @@ impl SharedBufferUploader {
         // Ensure the added data is available locally
         self.local_version_manager.try_set_version(version);
 
+        // Spilled batches have released their memory already.
+        let sync_size = buffers.iter().map(|batch| batch.release_memory()).sum();
         Ok(sync_size)
     }
 
@@ -147,7 +166,7 @@ impl SharedBufferUploader {
         match item {
             SharedBufferUploaderItem::Batch(m) => {
                 if let Some(detector) = &self.write_conflict_detector {
-                    detector.check_conflict_and_track_write_batch(&m.inner, m.epoch);
+                    detector.check_conflict_and_track_write_batch(&m.items().await?, m.epoch);
                 }
 
                 self.batches_to_upload
@@ -157,27 +176,13 @@ impl SharedBufferUploader {
                 Ok(())
             }
             SharedBufferUploaderItem::Sync(sync_item) => {
-                let res = match sync_item.epoch {
-                    Some(e) => {
-                        // Sync a specific epoch
-                        self.sync(e).await
-                    }
-                    None => {
-                        // Sync all epochs
-                        let epochs = self.batches_to_upload.keys().copied().collect_vec();
-                        let mut res = Ok(0);
-                        let mut size_total: u64 = 0;
-
-                        for e in epochs {
-                            res = self.sync(e).await;
-                            if res.is_err() {
-                                break;
-                            }
-                            size_total += res.unwrap();
-                            res = Ok(size_total);
-                        }
-                        res
-                    }
+                // Sync all epochs if no epoch is provided.
+                let epoch = sync_item
+                    .epoch
+                    .or_else(|| self.batches_to_upload.keys().last().copied());
+                let res = match epoch {
+                    Some(epoch) => self.sync(epoch).await,
+                    None => Ok(0),
                 };
 
                 if let Some(tx) = sync_item.notifier {
@@ -193,6 +198,21 @@ impl SharedBufferUploader {
                 self.batches_to_upload.remove(&epoch);
                 Ok(())
             }
+            SharedBufferUploaderItem::Spill(spilled_batches) => {
+                // Batches that have been synced or reset are no longer here, which is fine.
+                for (batch, spilled_batch) in spilled_batches {
+                    if let Some(to_upload) = self
+                        .batches_to_upload
+                        .get_mut(&batch.epoch())
+                        .and_then(|batches| {
+                            batches.iter_mut().find(|b| b.shares_items_with(&batch))
+                        })
+                    {
+                        *to_upload = spilled_batch;
+                    }
+                }
+                Ok(())
+            }
         }
     }
 
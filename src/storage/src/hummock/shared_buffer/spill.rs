@@ -0,0 +1,248 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use bytes::{Buf, BufMut, Bytes};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::hummock::shared_buffer::shared_buffer_batch::SharedBufferItem;
+use crate::hummock::value::HummockValue;
+use crate::hummock::{key, HummockEpoch, HummockError, HummockResult};
+
+/// Distinguishes spill files of the same epoch.
+static NEXT_SPILL_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Approximate size of the blocks of a spilled batch, which are the units read by point gets.
+const SPILL_BLOCK_SIZE: usize = 4096;
+
+/// A local temporary file holding the items of shared buffer batches that are spilled under memory
+/// pressure. Each batch occupies a contiguous region of the file, in which every item is encoded
+/// as `key_len (u32) | key | value_len (u32) | value`.
+///
+/// The file is removed once no batch refers to it any more.
+#[derive(Debug)]
+pub struct SpillFile {
+    path: PathBuf,
+}
+
+/// Location of the items of a spilled batch in its [`SpillFile`].
+#[derive(Clone, Debug)]
+pub struct SpilledItems {
+    file: Arc<SpillFile>,
+    offset: u64,
+    len: u64,
+    item_count: usize,
+    /// The first user key and the offset in the region of each block, so that a point get only
+    /// reads the block that may contain the key.
+    block_index: Arc<[(Bytes, u64)]>,
+}
+
+impl SpillFile {
+    /// Writes the items of `batches` of `epoch` into a new file under `dir`. Returns the location
+    /// of each batch in the same order.
+    pub async fn create(
+        dir: &str,
+        epoch: HummockEpoch,
+        batches: &[&[SharedBufferItem]],
+    ) -> HummockResult<Vec<SpilledItems>> {
+        let path = PathBuf::from(dir).join(format!(
+            "{}_{}.spill",
+            epoch,
+            NEXT_SPILL_FILE_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut buf = vec![];
+        let mut regions = Vec::with_capacity(batches.len());
+        for items in batches {
+            let offset = buf.len();
+            let mut block_index = vec![];
+            let mut block_offset = None;
+            for (key, value) in items.iter() {
+                if block_offset.map_or(true, |block_offset| {
+                    buf.len() - block_offset >= SPILL_BLOCK_SIZE
+                }) {
+                    block_offset = Some(buf.len());
+                    block_index.push((
+                        Bytes::copy_from_slice(key::user_key(key)),
+                        (buf.len() - offset) as u64,
+                    ));
+                }
+                buf.put_u32_le(key.len() as u32);
+                buf.put_slice(key);
+                buf.put_u32_le(value.encoded_len() as u32);
+                value.encode(&mut buf);
+            }
+            regions.push((
+                offset as u64,
+                (buf.len() - offset) as u64,
+                items.len(),
+                block_index,
+            ));
+        }
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(HummockError::shared_buffer_error)?;
+        tokio::fs::write(&path, buf)
+            .await
+            .map_err(HummockError::shared_buffer_error)?;
+
+        let file = Arc::new(SpillFile { path });
+        Ok(regions
+            .into_iter()
+            .map(|(offset, len, item_count, block_index)| SpilledItems {
+                file: file.clone(),
+                offset,
+                len,
+                item_count,
+                block_index: block_index.into(),
+            })
+            .collect())
+    }
+
+    /// Reads `len` bytes at `offset` of the file.
+    async fn read(&self, offset: u64, len: u64) -> HummockResult<Bytes> {
+        let mut file = tokio::fs::File::open(&self.path)
+            .await
+            .map_err(HummockError::shared_buffer_error)?;
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(HummockError::shared_buffer_error)?;
+        let mut buf = vec![0; len as usize];
+        file.read_exact(&mut buf)
+            .await
+            .map_err(HummockError::shared_buffer_error)?;
+        Ok(Bytes::from(buf))
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            tracing::warn!("failed to remove spill file {:?}: {}", self.path, e);
+        }
+    }
+}
+
+impl SpilledItems {
+    /// Reads the items back from the spill file.
+    pub async fn load(&self) -> HummockResult<Vec<SharedBufferItem>> {
+        let buf = self.file.read(self.offset, self.len).await?;
+        let mut items = Vec::with_capacity(self.item_count);
+        decode_items(buf, |key, value| {
+            items.push((key, value));
+            true
+        })?;
+        Ok(items)
+    }
+
+    /// Reads the value of `user_key` back from the spill file, which only reads the block that may
+    /// contain it.
+    pub async fn get(&self, user_key: &[u8]) -> HummockResult<Option<HummockValue<Bytes>>> {
+        let block_idx = self
+            .block_index
+            .partition_point(|(first_key, _)| first_key.as_ref() <= user_key);
+        if block_idx == 0 {
+            return Ok(None);
+        }
+        let block_offset = self.block_index[block_idx - 1].1;
+        let block_end = self
+            .block_index
+            .get(block_idx)
+            .map_or(self.len, |(_, offset)| *offset);
+        let buf = self
+            .file
+            .read(self.offset + block_offset, block_end - block_offset)
+            .await?;
+
+        let mut found = None;
+        decode_items(buf, |key, value| match key::user_key(&key).cmp(user_key) {
+            std::cmp::Ordering::Less => true,
+            std::cmp::Ordering::Equal => {
+                found = Some(value);
+                false
+            }
+            std::cmp::Ordering::Greater => false,
+        })?;
+        Ok(found)
+    }
+}
+
+/// Decodes the items in `buf` in order, until `f` returns false.
+fn decode_items(
+    mut buf: Bytes,
+    mut f: impl FnMut(Bytes, HummockValue<Bytes>) -> bool,
+) -> HummockResult<()> {
+    while buf.has_remaining() {
+        let key_len = buf.get_u32_le() as usize;
+        let key = buf.split_to(key_len);
+        let value_len = buf.get_u32_le() as usize;
+        let value = buf.split_to(value_len);
+        let value = match HummockValue::from_slice(&value)? {
+            // Slice the user value out of the encoded value without copying.
+            HummockValue::Put(meta, user_value) => {
+                HummockValue::Put(meta, value.slice(value_len - user_value.len()..))
+            }
+            HummockValue::Delete(meta) => HummockValue::Delete(meta),
+        };
+        if !f(key, value) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use risingwave_hummock_sdk::key::key_with_epoch;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spilled_items_get() {
+        let dir = std::env::temp_dir().join(format!("hummock_spill_{}", uuid::Uuid::new_v4()));
+        let dir = dir.to_string_lossy().to_string();
+        let epoch = 1;
+        let user_key = |i: usize| format!("key_{:05}", i * 2).into_bytes();
+        let items = (0..1000)
+            .map(|i| {
+                (
+                    Bytes::from(key_with_epoch(user_key(i), epoch)),
+                    HummockValue::put(Bytes::from(format!("value_{}", i))),
+                )
+            })
+            .collect_vec();
+
+        let spilled_items = SpillFile::create(&dir, epoch, &[&items[..]]).await.unwrap();
+        let spilled_items = &spilled_items[0];
+        assert!(spilled_items.block_index.len() > 1);
+        assert_eq!(spilled_items.load().await.unwrap(), items);
+
+        // Each key is found in its block.
+        for (i, (_, value)) in items.iter().enumerate() {
+            assert_eq!(
+                spilled_items.get(&user_key(i)).await.unwrap().as_ref(),
+                Some(value)
+            );
+        }
+        // Keys before, between and after the items are not found.
+        assert_eq!(spilled_items.get(b"key").await.unwrap(), None);
+        assert_eq!(spilled_items.get(b"key_00001").await.unwrap(), None);
+        assert_eq!(spilled_items.get(b"key_99999").await.unwrap(), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
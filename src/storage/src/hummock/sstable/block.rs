@@ -111,6 +111,20 @@ impl Block {
     pub fn data(&self) -> &Bytes {
         &self.data
     }
+
+    /// Encodes the block without compression, which can be decoded by [`Block::decode`].
+    pub fn encode(&self) -> Bytes {
+        let mut buf = BytesMut::with_capacity(self.data.len() + 4 * self.restart_points.len() + 13);
+        buf.put_slice(&self.data);
+        for restart_point in &self.restart_points {
+            buf.put_u32_le(*restart_point);
+        }
+        buf.put_u32_le(self.restart_points.len() as u32);
+        CompressionAlgorithm::None.encode(&mut buf);
+        let checksum = xxhash64_checksum(&buf);
+        buf.put_u64_le(checksum);
+        buf.freeze()
+    }
 }
 
 /// [`KeyPrefix`] contains info for prefix compression.
@@ -366,7 +380,9 @@ mod tests {
         builder.add(&full_key(b"k3", 3), b"v03");
         builder.add(&full_key(b"k4", 4), b"v04");
         let buf = builder.build();
-        let block = Arc::new(Block::decode(buf).unwrap());
+        let block = Block::decode(buf).unwrap();
+        // Re-encoding the decompressed block gives the same entries.
+        let block = Arc::new(Block::decode(block.encode()).unwrap());
         let mut bi = BlockIterator::new(block);
 
         bi.seek_to_first();
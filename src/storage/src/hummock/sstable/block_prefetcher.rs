@@ -0,0 +1,120 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+
+use super::{Block, Sstable};
+use crate::hummock::{HummockError, HummockResult, SstableStoreRef};
+
+/// Reads the blocks of an sstable ahead of a sequential scan. Once a batch of blocks is read, the
+/// next batch is read in the background, so that fetching it overlaps with consuming the current
+/// one.
+pub struct BlockPrefetcher {
+    sst: Arc<Sstable>,
+    sstable_store: SstableStoreRef,
+    /// Whether the scan goes from the last block to the first one.
+    reverse: bool,
+    /// Blocks read ahead in the scan order, starting from the block at `next_idx`.
+    blocks: VecDeque<Arc<Block>>,
+    /// The read of the batch following `blocks`.
+    pending: Option<JoinHandle<HummockResult<Vec<Arc<Block>>>>>,
+    /// The block expected to be requested next, if the scan goes on sequentially.
+    next_idx: Option<usize>,
+}
+
+impl BlockPrefetcher {
+    pub fn new(sst: Arc<Sstable>, sstable_store: SstableStoreRef, reverse: bool) -> Self {
+        Self {
+            sst,
+            sstable_store,
+            reverse,
+            blocks: VecDeque::new(),
+            pending: None,
+            next_idx: None,
+        }
+    }
+
+    /// Returns the block at `idx`. The blocks read ahead are dropped if `idx` doesn't follow the
+    /// previously requested block in the scan order.
+    pub async fn get(&mut self, idx: usize) -> HummockResult<Arc<Block>> {
+        if self.next_idx != Some(idx) {
+            self.reset();
+        }
+        if self.blocks.is_empty() {
+            let blocks = match self.pending.take() {
+                Some(pending) => pending.await.map_err(HummockError::other)??,
+                None => {
+                    self.sstable_store
+                        .get_blocks(&self.sst, self.batch_range(idx))
+                        .await?
+                }
+            };
+            self.blocks = if self.reverse {
+                blocks.into_iter().rev().collect()
+            } else {
+                blocks.into()
+            };
+            if let Some(following_idx) = self.step(idx, self.blocks.len()) {
+                let sstable_store = self.sstable_store.clone();
+                let sst = self.sst.clone();
+                let range = self.batch_range(following_idx);
+                self.pending = Some(tokio::spawn(async move {
+                    sstable_store.get_blocks(&sst, range).await
+                }));
+            }
+        }
+        self.next_idx = self.step(idx, 1);
+        Ok(self.blocks.pop_front().unwrap())
+    }
+
+    /// Drops the blocks read ahead, e.g. when the scan seeks elsewhere.
+    pub fn reset(&mut self) {
+        self.blocks.clear();
+        if let Some(pending) = self.pending.take() {
+            pending.abort();
+        }
+        self.next_idx = None;
+    }
+
+    /// Returns the index `n` blocks after `idx` in the scan order, if any.
+    fn step(&self, idx: usize, n: usize) -> Option<usize> {
+        if self.reverse {
+            idx.checked_sub(n)
+        } else {
+            Some(idx + n).filter(|idx| *idx < self.sst.block_count())
+        }
+    }
+
+    /// Returns the range of the batch of blocks to read, which begins at `idx` in the scan order.
+    fn batch_range(&self, idx: usize) -> Range<u64> {
+        let batch_size = self.sstable_store.prefetch_block_count();
+        if self.reverse {
+            (idx + 1).saturating_sub(batch_size) as u64..(idx + 1) as u64
+        } else {
+            idx as u64..std::cmp::min(idx + batch_size, self.sst.block_count()) as u64
+        }
+    }
+}
+
+impl Drop for BlockPrefetcher {
+    fn drop(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            pending.abort();
+        }
+    }
+}
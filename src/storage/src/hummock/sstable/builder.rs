@@ -12,14 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
+
 use bytes::{BufMut, Bytes, BytesMut};
 use risingwave_hummock_sdk::key::user_key;
+use risingwave_hummock_sdk::VersionedComparator;
 
 use super::bloom::Bloom;
 use super::utils::CompressionAlgorithm;
 use super::{
-    BlockBuilder, BlockBuilderOptions, BlockMeta, SstableMeta, DEFAULT_BLOCK_SIZE,
-    DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
+    BlockBuilder, BlockBuilderOptions, BlockMeta, PrefixExtractor, RangeTombstone, SstableMeta,
+    DEFAULT_BLOCK_SIZE, DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
 };
 use crate::hummock::value::HummockValue;
 
@@ -66,6 +69,12 @@ pub struct SSTableBuilder {
     /// Last added full key.
     last_full_key: Bytes,
     key_count: usize,
+    /// Range tombstones, sorted by start user key.
+    range_tombstones: Vec<RangeTombstone>,
+    /// Extractors of the prefixes to put into the prefix bloom filter.
+    prefix_extractors: Vec<PrefixExtractor>,
+    /// Hashes of extracted prefixes.
+    prefix_hashes: Vec<u32>,
 }
 
 impl SSTableBuilder {
@@ -78,9 +87,20 @@ impl SSTableBuilder {
             user_key_hashes: Vec::with_capacity(options.capacity / DEFAULT_ENTRY_SIZE + 1),
             last_full_key: Bytes::default(),
             key_count: 0,
+            range_tombstones: vec![],
+            prefix_extractors: vec![],
+            prefix_hashes: vec![],
         }
     }
 
+    /// Builds a prefix bloom filter with the prefixes extracted from the user keys by
+    /// `prefix_extractors`.
+    #[must_use]
+    pub fn with_prefix_extractors(mut self, prefix_extractors: Vec<PrefixExtractor>) -> Self {
+        self.prefix_extractors = prefix_extractors;
+        self
+    }
+
     /// Add kv pair to sstable.
     pub fn add(&mut self, full_key: &[u8], value: HummockValue<&[u8]>) {
         // Rotate block builder if the previous one has been built.
@@ -109,6 +129,15 @@ impl SSTableBuilder {
 
         let user_key = user_key(full_key);
         self.user_key_hashes.push(farmhash::fingerprint32(user_key));
+        for extractor in &self.prefix_extractors {
+            if let Some(prefix) = extractor.extract(user_key) {
+                let hash = farmhash::fingerprint32(prefix);
+                // Adjacent keys mostly share the prefix.
+                if self.prefix_hashes.last() != Some(&hash) {
+                    self.prefix_hashes.push(hash);
+                }
+            }
+        }
 
         if self.last_full_key.is_empty() {
             self.block_metas.last_mut().unwrap().smallest_key = full_key.to_vec();
@@ -121,6 +150,15 @@ impl SSTableBuilder {
         self.key_count += 1;
     }
 
+    /// Add a range tombstone to sstable. Range tombstones are written to a separate tombstone block
+    /// in the meta, and extend the key range of the sstable to cover them.
+    pub fn add_range_tombstone(&mut self, range_tombstone: RangeTombstone) {
+        let idx = self
+            .range_tombstones
+            .partition_point(|t| t.start_user_key <= range_tombstone.start_user_key);
+        self.range_tombstones.insert(idx, range_tombstone);
+    }
+
     /// Finish building sst.
     ///
     /// Unlike most LSM-Tree implementations, sstable meta and data are encoded separately.
@@ -133,12 +171,54 @@ impl SSTableBuilder {
     /// ```plain
     /// | Block 0 | ... | Block N-1 | N (4B) |
     /// ```
+    ///
+    /// An sstable may have no block if it only contains range tombstones.
     pub fn finish(mut self) -> (Bytes, SstableMeta) {
-        let smallest_key = self.block_metas[0].smallest_key.clone();
-        let largest_key = self.last_full_key.to_vec();
+        assert!(
+            !self.block_metas.is_empty() || !self.range_tombstones.is_empty(),
+            "sstable must not be empty"
+        );
+        let mut smallest_key = self
+            .block_metas
+            .first()
+            .map(|meta| meta.smallest_key.clone());
+        let mut largest_key = self
+            .block_metas
+            .first()
+            .map(|_| self.last_full_key.to_vec());
+        for range_tombstone in &self.range_tombstones {
+            let start = range_tombstone.smallest_full_key();
+            let end = range_tombstone.largest_full_key();
+            if smallest_key.as_ref().map_or(true, |key| {
+                VersionedComparator::compare_key(&start, key) == Ordering::Less
+            }) {
+                smallest_key = Some(start);
+            }
+            if largest_key.as_ref().map_or(true, |key| {
+                VersionedComparator::compare_key(&end, key) == Ordering::Greater
+            }) {
+                largest_key = Some(end);
+            }
+        }
         self.build_block();
         self.buf.put_u32_le(self.block_metas.len() as u32);
 
+        // Only the extractors of the keyspaces overlapping the table are recorded, since scans over
+        // the other keyspaces skip the table by its key range anyway.
+        let prefix_extractors = match (&smallest_key, &largest_key) {
+            (Some(smallest_key), Some(largest_key)) => {
+                let (smallest, largest) = (user_key(smallest_key), user_key(largest_key));
+                self.prefix_extractors
+                    .into_iter()
+                    .filter(|extractor| {
+                        let prefix = extractor.keyspace_prefix.as_slice();
+                        largest >= prefix && (smallest < prefix || smallest.starts_with(prefix))
+                    })
+                    .collect()
+            }
+            _ => vec![],
+        };
+
         let meta = SstableMeta {
             block_metas: self.block_metas,
             bloom_filter: if self.options.bloom_false_positive > 0.0 {
@@ -152,8 +232,21 @@ impl SSTableBuilder {
             },
             estimated_size: self.buf.len() as u32,
             key_count: self.key_count as u32,
-            smallest_key,
-            largest_key,
+            smallest_key: smallest_key.unwrap(),
+            largest_key: largest_key.unwrap(),
+            range_tombstones: self.range_tombstones,
+            prefix_bloom_filter: if self.options.bloom_false_positive > 0.0
+                && !prefix_extractors.is_empty()
+            {
+                let bits_per_key = Bloom::bloom_bits_per_key(
+                    self.prefix_hashes.len().max(1),
+                    self.options.bloom_false_positive,
+                );
+                Bloom::build_from_key_hashes(&self.prefix_hashes, bits_per_key).to_vec()
+            } else {
+                vec![]
+            },
+            prefix_extractors,
             version: VERSION,
         };
 
@@ -175,6 +268,13 @@ impl SSTableBuilder {
         block_meta.len = self.buf.len() as u32 - block_meta.offset;
     }
 
+    /// Returns the smallest user key added, not considering range tombstones.
+    pub fn smallest_user_key(&self) -> Option<&[u8]> {
+        self.block_metas
+            .first()
+            .map(|block_meta| user_key(&block_meta.smallest_key))
+    }
+
     pub fn len(&self) -> usize {
         self.user_key_hashes.len()
     }
@@ -193,6 +293,7 @@ impl SSTableBuilder {
 pub(super) mod tests {
     use super::*;
     use crate::hummock::iterator::test_utils::mock_sstable_store;
+    use crate::hummock::sstable::Sstable;
     use crate::hummock::test_utils::{
         default_builder_opt_for_test, gen_default_test_sstable, test_key_of, test_value_of,
         TEST_KEYS_COUNT,
@@ -228,6 +329,66 @@ pub(super) mod tests {
         assert_eq!(test_key_of(TEST_KEYS_COUNT - 1), meta.largest_key);
     }
 
+    #[test]
+    fn test_range_tombstones() {
+        let mut b = SSTableBuilder::new(default_builder_opt_for_test());
+        for i in 1..TEST_KEYS_COUNT {
+            b.add(&test_key_of(i), HummockValue::put(&test_value_of(i)));
+        }
+        let before_first = RangeTombstone::new(
+            b"key_test".to_vec(),
+            user_key(&test_key_of(1)).to_vec(),
+            234,
+        );
+        let after_last = RangeTombstone::new(
+            user_key(&test_key_of(TEST_KEYS_COUNT - 1)).to_vec(),
+            b"key_test_z".to_vec(),
+            234,
+        );
+        b.add_range_tombstone(after_last.clone());
+        b.add_range_tombstone(before_first.clone());
+
+        let (_, meta) = b.finish();
+
+        // The key range is extended to cover the range tombstones.
+        assert_eq!(before_first.smallest_full_key(), meta.smallest_key);
+        assert_eq!(after_last.largest_full_key(), meta.largest_key);
+        assert_eq!(vec![before_first, after_last], meta.range_tombstones);
+    }
+
+    #[test]
+    fn test_range_tombstone_only() {
+        let mut b = SSTableBuilder::new(default_builder_opt_for_test());
+        let tombstone = RangeTombstone::new(b"a".to_vec(), b"b".to_vec(), 1);
+        b.add_range_tombstone(tombstone.clone());
+
+        let (data, meta) = b.finish();
+
+        assert!(meta.block_metas.is_empty());
+        assert_eq!(4, data.len());
+        assert_eq!(tombstone.smallest_full_key(), meta.smallest_key);
+        assert_eq!(tombstone.largest_full_key(), meta.largest_key);
+    }
+
+    #[test]
+    fn test_prefix_extractors_of_overlapping_keyspaces() {
+        let overlapping = PrefixExtractor::new(b"key_test".to_vec(), 4);
+        let mut b =
+            SSTableBuilder::new(default_builder_opt_for_test()).with_prefix_extractors(vec![
+                PrefixExtractor::new(b"a".to_vec(), 4),
+                overlapping.clone(),
+                PrefixExtractor::new(b"z".to_vec(), 4),
+            ]);
+        for i in 0..TEST_KEYS_COUNT {
+            b.add(&test_key_of(i), HummockValue::put(&test_value_of(i)));
+        }
+
+        let (_, meta) = b.finish();
+
+        assert_eq!(vec![overlapping], meta.prefix_extractors);
+        assert!(!meta.prefix_bloom_filter.is_empty());
+    }
+
     async fn test_with_bloom_filter(with_blooms: bool) {
         let key_count = 1000;
 
@@ -255,4 +416,35 @@ pub(super) mod tests {
         test_with_bloom_filter(false).await;
         test_with_bloom_filter(true).await;
     }
+
+    #[test]
+    fn test_prefix_bloom_filter() {
+        let mut b = SSTableBuilder::new(default_builder_opt_for_test())
+            .with_prefix_extractors(vec![PrefixExtractor::new(b"key_test_".to_vec(), 3)]);
+        // Keys of prefixes `key_test_000` and `key_test_001`.
+        for i in 0..100 {
+            b.add(&test_key_of(i), HummockValue::put(&test_value_of(i)));
+        }
+        let (_, meta) = b.finish();
+        let mut table = Sstable::new(0, meta);
+        assert!(table.has_prefix_bloom_filter());
+
+        assert!(!table.surely_not_have_prefix(b"key_test_000"));
+        assert!(!table.surely_not_have_prefix(b"key_test_0019"));
+        // Prefixes too short to extract can't be filtered.
+        assert!(!table.surely_not_have_prefix(b"key_test_1"));
+        assert!(!table.surely_not_have_prefix(b"other_key_100"));
+        let filtered = (100..200)
+            .filter(|i| table.surely_not_have_prefix(format!("key_test_{:03}", i).as_bytes()))
+            .count();
+        assert!(filtered > 90);
+
+        // Tables with range tombstones overlapping the prefix can't be skipped.
+        table.meta.range_tombstones = vec![RangeTombstone::new(
+            b"key_test_150".to_vec(),
+            b"key_test_160".to_vec(),
+            234,
+        )];
+        assert!(!table.surely_not_have_prefix(b"key_test_155"));
+    }
 }
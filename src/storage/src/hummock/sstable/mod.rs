@@ -19,17 +19,24 @@ mod block;
 pub use block::*;
 mod block_iterator;
 pub use block_iterator::*;
+mod block_prefetcher;
+pub use block_prefetcher::*;
 mod bloom;
 use bloom::Bloom;
 pub mod builder;
 pub use builder::*;
 pub mod multi_builder;
+mod prefix_extractor;
+pub use prefix_extractor::*;
+mod range_tombstone;
+pub use range_tombstone::*;
 mod sstable_iterator;
 use bytes::{Buf, BufMut};
 pub use sstable_iterator::*;
 mod reverse_sstable_iterator;
 pub use reverse_sstable_iterator::*;
 mod utils;
+use risingwave_hummock_sdk::key::next_key;
 pub use utils::CompressionAlgorithm;
 use utils::{get_length_prefixed_slice, put_length_prefixed_slice};
 
@@ -38,7 +45,9 @@ use super::{HummockError, HummockResult};
 
 const DEFAULT_META_BUFFER_CAPACITY: usize = 4096;
 const MAGIC: u32 = 0x5785ab73;
-const VERSION: u32 = 1;
+/// Version 2 adds range tombstones to the meta.
+/// Version 3 adds the prefix bloom filter and its extractors to the meta.
+const VERSION: u32 = 3;
 
 #[derive(Clone, Debug)]
 /// [`Sstable`] is a handle for accessing SST.
@@ -66,6 +75,40 @@ impl Sstable {
         }
     }
 
+    pub fn has_prefix_bloom_filter(&self) -> bool {
+        !self.meta.prefix_bloom_filter.is_empty()
+    }
+
+    /// Returns true if the table surely has no key starting with `prefix`, judging by the prefix
+    /// bloom filter.
+    pub fn surely_not_have_prefix(&self, prefix: &[u8]) -> bool {
+        if !self.has_prefix_bloom_filter() {
+            return false;
+        }
+        // Range tombstones of the table might delete keys of the prefix in older tables.
+        let end = next_key(prefix);
+        if self
+            .meta
+            .range_tombstones
+            .iter()
+            .any(|tombstone| tombstone.clip(prefix, &end).is_some())
+        {
+            return false;
+        }
+        let bloom = Bloom::new(&self.meta.prefix_bloom_filter);
+        self.meta
+            .prefix_extractors
+            .iter()
+            .filter_map(|extractor| extractor.extract(prefix))
+            .any(|extracted| bloom.surely_not_have_hash(farmhash::fingerprint32(extracted)))
+    }
+
+    /// Returns true if the version of `user_key` written in `key_epoch` is deleted by a range
+    /// tombstone of the table for reads at `read_epoch`.
+    pub fn range_deleted(&self, user_key: &[u8], key_epoch: u64, read_epoch: u64) -> bool {
+        deleted_by_range_tombstones(&self.meta.range_tombstones, user_key, key_epoch, read_epoch)
+    }
+
     pub fn block_count(&self) -> usize {
         self.meta.block_metas.len()
     }
@@ -120,6 +163,13 @@ pub struct SstableMeta {
     pub key_count: u32,
     pub smallest_key: Vec<u8>,
     pub largest_key: Vec<u8>,
+    /// Range tombstones, which are kept in the meta so that reads can check them without fetching
+    /// any block.
+    pub range_tombstones: Vec<RangeTombstone>,
+    /// Bloom filter of the prefixes extracted from the user keys by `prefix_extractors`.
+    pub prefix_bloom_filter: Vec<u8>,
+    /// Prefix extractors the prefix bloom filter is built with.
+    pub prefix_extractors: Vec<PrefixExtractor>,
     /// Format version, for further compatibility.
     pub version: u32,
 }
@@ -134,6 +184,9 @@ impl SstableMeta {
     /// | estimated size (4B) | key count (4B) |
     /// | smallest key len (4B) | smallest key |
     /// | largest key len (4B) | largest key |
+    /// | M (4B) | range tombstone 0 | ... | range tombstone M-1 |
+    /// | prefix bloom filter len (4B) | prefix bloom filter |
+    /// | K (4B) | prefix extractor 0 | ... | prefix extractor K-1 |
     /// | checksum (8B) | version (4B) | magic (4B) |
     /// ```
     pub fn encode_to_bytes(&self) -> Vec<u8> {
@@ -147,6 +200,15 @@ impl SstableMeta {
         buf.put_u32_le(self.key_count as u32);
         put_length_prefixed_slice(&mut buf, &self.smallest_key);
         put_length_prefixed_slice(&mut buf, &self.largest_key);
+        buf.put_u32_le(self.range_tombstones.len() as u32);
+        for range_tombstone in &self.range_tombstones {
+            range_tombstone.encode(&mut buf);
+        }
+        put_length_prefixed_slice(&mut buf, &self.prefix_bloom_filter);
+        buf.put_u32_le(self.prefix_extractors.len() as u32);
+        for prefix_extractor in &self.prefix_extractors {
+            prefix_extractor.encode(&mut buf);
+        }
         let checksum = xxhash64_checksum(&buf);
         buf.put_u64_le(checksum);
         buf.put_u32_le(VERSION);
@@ -165,7 +227,7 @@ impl SstableMeta {
 
         cursor -= 4;
         let version = (&buf[cursor..cursor + 4]).get_u32_le();
-        if version != VERSION {
+        if version == 0 || version > VERSION {
             return Err(HummockError::invalid_format_version(version));
         }
 
@@ -184,6 +246,26 @@ impl SstableMeta {
         let key_count = buf.get_u32_le();
         let smallest_key = get_length_prefixed_slice(buf);
         let largest_key = get_length_prefixed_slice(buf);
+        // Tables of version 1 have no range tombstones.
+        let range_tombstones = if version >= 2 {
+            let range_tombstone_count = buf.get_u32_le() as usize;
+            (0..range_tombstone_count)
+                .map(|_| RangeTombstone::decode(buf))
+                .collect()
+        } else {
+            vec![]
+        };
+        // Tables before version 3 have no prefix bloom filter.
+        let (prefix_bloom_filter, prefix_extractors) = if version >= 3 {
+            let prefix_bloom_filter = get_length_prefixed_slice(buf);
+            let prefix_extractor_count = buf.get_u32_le() as usize;
+            let prefix_extractors = (0..prefix_extractor_count)
+                .map(|_| PrefixExtractor::decode(buf))
+                .collect();
+            (prefix_bloom_filter, prefix_extractors)
+        } else {
+            (vec![], vec![])
+        };
 
         Ok(Self {
             block_metas,
@@ -192,6 +274,9 @@ impl SstableMeta {
             key_count,
             smallest_key,
             largest_key,
+            range_tombstones,
+            prefix_bloom_filter,
+            prefix_extractors,
             version,
         })
     }
@@ -212,6 +297,20 @@ impl SstableMeta {
             + self.smallest_key.len()
             + 4 // key len
             + self.largest_key.len()
+            + 4 // range tombstone count
+            + self
+            .range_tombstones
+            .iter()
+            .map(|range_tombstone| range_tombstone.encoded_size())
+            .sum::<usize>()
+            + 4 // prefix bloom filter len
+            + self.prefix_bloom_filter.len()
+            + 4 // prefix extractor count
+            + self
+            .prefix_extractors
+            .iter()
+            .map(|prefix_extractor| prefix_extractor.encoded_size())
+            .sum::<usize>()
             + 8 // checksum
             + 4 // version
             + 4 // magic
@@ -242,9 +341,17 @@ mod tests {
             key_count: 123,
             smallest_key: b"0-smallest-key".to_vec(),
             largest_key: b"9-largest-key".to_vec(),
+            range_tombstones: vec![RangeTombstone::new(
+                b"1-start-key".to_vec(),
+                b"3-end-key".to_vec(),
+                233,
+            )],
+            prefix_bloom_filter: b"9876543210".to_vec(),
+            prefix_extractors: vec![PrefixExtractor::new(b"t1".to_vec(), 4)],
             version: VERSION,
         };
         let buf = meta.encode_to_bytes();
+        assert_eq!(buf.len(), meta.encoded_size());
         let decoded_meta = SstableMeta::decode(&mut &buf[..]).unwrap();
         assert_eq!(decoded_meta, meta);
     }
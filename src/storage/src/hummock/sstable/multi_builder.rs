@@ -16,7 +16,7 @@ use bytes::Bytes;
 use futures::Future;
 use risingwave_hummock_sdk::key::{Epoch, FullKey};
 
-use super::SstableMeta;
+use super::{RangeTombstone, SstableMeta};
 use crate::hummock::value::HummockValue;
 use crate::hummock::{HummockResult, SSTableBuilder};
 
@@ -112,6 +112,50 @@ where
         Ok(())
     }
 
+    /// Adds range tombstones to the underlying builders. Should be called after all keys are added.
+    ///
+    /// Each tombstone is split at the smallest user keys of the tables, so that the key ranges of
+    /// the tables don't overlap after being extended to cover their tombstones. A new table is
+    /// created if there's no table yet.
+    pub async fn add_range_tombstones(
+        &mut self,
+        range_tombstones: Vec<RangeTombstone>,
+    ) -> HummockResult<()> {
+        if range_tombstones.is_empty() {
+            return Ok(());
+        }
+        if self.builders.is_empty() {
+            let (id, builder) = (self.get_id_and_builder)().await?;
+            self.builders.push(SSTableBuilderWrapper {
+                id,
+                builder,
+                sealed: false,
+            });
+        }
+
+        // Each table but the first one starts from its smallest user key.
+        let boundaries = self
+            .builders
+            .iter()
+            .skip(1)
+            .map(|b| b.builder.smallest_user_key().unwrap().to_vec())
+            .collect::<Vec<_>>();
+        for (idx, b) in self.builders.iter_mut().enumerate() {
+            let start = if idx == 0 {
+                &[][..]
+            } else {
+                &boundaries[idx - 1]
+            };
+            let end = boundaries.get(idx).map_or(&[][..], |key| key.as_slice());
+            for range_tombstone in &range_tombstones {
+                if let Some(range_tombstone) = range_tombstone.clip(start, end) {
+                    b.builder.add_range_tombstone(range_tombstone);
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Marks the current builder as sealed. Next call of `add` will always create a new table.
     ///
     /// If there's no builder created, or current one is already sealed before, then this function
@@ -140,6 +184,7 @@ mod tests {
     use std::sync::atomic::Ordering::SeqCst;
 
     use itertools::Itertools;
+    use risingwave_hummock_sdk::VersionedComparator;
 
     use super::*;
     use crate::hummock::sstable::utils::CompressionAlgorithm;
@@ -264,4 +309,51 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_split_range_tombstones() {
+        let next_id = AtomicU64::new(1001);
+        let mut builder = CapacitySplitTableBuilder::new(|| async {
+            Ok((
+                next_id.fetch_add(1, SeqCst),
+                SSTableBuilder::new(default_builder_opt_for_test()),
+            ))
+        });
+
+        builder
+            .add_user_key(b"b".to_vec(), HummockValue::put(b"v"), 233)
+            .await
+            .unwrap();
+        builder.seal_current();
+        builder
+            .add_user_key(b"d".to_vec(), HummockValue::put(b"v"), 233)
+            .await
+            .unwrap();
+        builder
+            .add_range_tombstones(vec![
+                RangeTombstone::new(b"a".to_vec(), b"z".to_vec(), 234),
+                RangeTombstone::new(b"x".to_vec(), b"y".to_vec(), 235),
+            ])
+            .await
+            .unwrap();
+
+        let results = builder.finish();
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].2.range_tombstones,
+            vec![RangeTombstone::new(b"a".to_vec(), b"d".to_vec(), 234)]
+        );
+        assert_eq!(
+            results[1].2.range_tombstones,
+            vec![
+                RangeTombstone::new(b"d".to_vec(), b"z".to_vec(), 234),
+                RangeTombstone::new(b"x".to_vec(), b"y".to_vec(), 235),
+            ]
+        );
+        // The tables still don't overlap.
+        assert_eq!(
+            VersionedComparator::compare_key(&results[0].2.largest_key, &results[1].2.smallest_key),
+            std::cmp::Ordering::Less
+        );
+    }
 }
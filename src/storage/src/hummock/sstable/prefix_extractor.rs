@@ -0,0 +1,125 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use bytes::{Buf, BufMut};
+use parking_lot::RwLock;
+
+use super::utils::{get_length_prefixed_slice, put_length_prefixed_slice};
+
+/// Extracts the first `prefix_len` bytes after `keyspace_prefix` from the keys of a keyspace, along
+/// with the keyspace prefix itself. The extracted prefixes are put into the prefix bloom filter of
+/// the sstables, so that prefix scans can skip the tables that cannot contain the prefix.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PrefixExtractor {
+    pub keyspace_prefix: Vec<u8>,
+    pub prefix_len: u32,
+}
+
+impl PrefixExtractor {
+    pub fn new(keyspace_prefix: Vec<u8>, prefix_len: u32) -> Self {
+        Self {
+            keyspace_prefix,
+            prefix_len,
+        }
+    }
+
+    /// Returns the prefix of `user_key` to put into or check against the bloom filter, or `None`
+    /// if the key is out of the keyspace or too short.
+    pub fn extract<'a>(&self, user_key: &'a [u8]) -> Option<&'a [u8]> {
+        let len = self.keyspace_prefix.len() + self.prefix_len as usize;
+        if user_key.len() >= len && user_key.starts_with(&self.keyspace_prefix) {
+            Some(&user_key[..len])
+        } else {
+            None
+        }
+    }
+
+    /// Format:
+    ///
+    /// ```plain
+    /// | keyspace prefix len (4B) | keyspace prefix | prefix len (4B) |
+    /// ```
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        put_length_prefixed_slice(buf, &self.keyspace_prefix);
+        buf.put_u32_le(self.prefix_len);
+    }
+
+    pub fn decode(buf: &mut &[u8]) -> Self {
+        let keyspace_prefix = get_length_prefixed_slice(buf);
+        let prefix_len = buf.get_u32_le();
+        Self {
+            keyspace_prefix,
+            prefix_len,
+        }
+    }
+
+    #[inline]
+    pub fn encoded_size(&self) -> usize {
+        8 /* keyspace prefix len + prefix len */ + self.keyspace_prefix.len()
+    }
+}
+
+/// Prefix extractors registered by keyspaces.
+///
+/// The extractors used to build an sstable are recorded in its meta, so a table built before or
+/// elsewhere than a registration is never filtered wrongly, but only misses the chance to be
+/// skipped.
+#[derive(Default, Debug)]
+pub struct PrefixExtractorRegistry {
+    /// Maps keyspace prefixes to prefix lengths.
+    extractors: RwLock<BTreeMap<Vec<u8>, u32>>,
+}
+
+impl PrefixExtractorRegistry {
+    /// Sets the prefix length of the keys with `keyspace_prefix`.
+    pub fn register(&self, keyspace_prefix: &[u8], prefix_len: u32) {
+        self.extractors
+            .write()
+            .insert(keyspace_prefix.to_vec(), prefix_len);
+    }
+
+    /// Returns all the registered extractors.
+    pub fn extractors(&self) -> Vec<PrefixExtractor> {
+        self.extractors
+            .read()
+            .iter()
+            .map(|(keyspace_prefix, prefix_len)| {
+                PrefixExtractor::new(keyspace_prefix.clone(), *prefix_len)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_extractor() {
+        let extractor = PrefixExtractor::new(b"t1".to_vec(), 3);
+        assert_eq!(extractor.extract(b"t1abcde"), Some(&b"t1abc"[..]));
+        assert_eq!(extractor.extract(b"t1abc"), Some(&b"t1abc"[..]));
+        // Too short.
+        assert_eq!(extractor.extract(b"t1ab"), None);
+        // Out of the keyspace.
+        assert_eq!(extractor.extract(b"t2abcde"), None);
+
+        let mut buf = vec![];
+        extractor.encode(&mut buf);
+        assert_eq!(buf.len(), extractor.encoded_size());
+        assert_eq!(PrefixExtractor::decode(&mut &buf[..]), extractor);
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::{Buf, BufMut};
+use risingwave_hummock_sdk::key::{key_with_epoch, Epoch};
+
+use super::utils::{get_length_prefixed_slice, put_length_prefixed_slice};
+
+/// A range tombstone deletes all versions of the user keys in `[start_user_key, end_user_key)`
+/// written in epochs before `epoch`. Versions written in `epoch` itself are not affected, so that a
+/// range can be cleared and refilled in the same epoch.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RangeTombstone {
+    pub start_user_key: Vec<u8>,
+    pub end_user_key: Vec<u8>,
+    pub epoch: Epoch,
+}
+
+impl RangeTombstone {
+    pub fn new(start_user_key: Vec<u8>, end_user_key: Vec<u8>, epoch: Epoch) -> Self {
+        assert!(
+            start_user_key < end_user_key,
+            "range tombstone must not be empty"
+        );
+        Self {
+            start_user_key,
+            end_user_key,
+            epoch,
+        }
+    }
+
+    /// Returns true if `user_key` is in the range of the tombstone.
+    pub fn covers(&self, user_key: &[u8]) -> bool {
+        self.start_user_key.as_slice() <= user_key && user_key < self.end_user_key.as_slice()
+    }
+
+    /// Returns true if the version of `user_key` written in `key_epoch` is deleted by this
+    /// tombstone for reads at `read_epoch`.
+    pub fn deletes(&self, user_key: &[u8], key_epoch: Epoch, read_epoch: Epoch) -> bool {
+        key_epoch < self.epoch && self.epoch <= read_epoch && self.covers(user_key)
+    }
+
+    /// Returns the part of the tombstone in `[start_user_key, end_user_key)`, where an empty bound
+    /// means unbounded, or `None` if they don't overlap.
+    pub fn clip(&self, start_user_key: &[u8], end_user_key: &[u8]) -> Option<Self> {
+        let start = std::cmp::max(self.start_user_key.as_slice(), start_user_key);
+        let end = if end_user_key.is_empty() {
+            self.end_user_key.as_slice()
+        } else {
+            std::cmp::min(self.end_user_key.as_slice(), end_user_key)
+        };
+        if start < end {
+            Some(Self::new(start.to_vec(), end.to_vec(), self.epoch))
+        } else {
+            None
+        }
+    }
+
+    /// The full key bounding the tombstone from below. Only versions before `epoch` are deleted, so
+    /// it sorts after the largest full key of a table whose tombstones end at the start user key.
+    pub fn smallest_full_key(&self) -> Vec<u8> {
+        key_with_epoch(self.start_user_key.clone(), self.epoch)
+    }
+
+    /// The full key bounding the tombstone from above. As the end user key is exclusive, it's the
+    /// smallest full key of the end user key, which sorts before any real version of it.
+    pub fn largest_full_key(&self) -> Vec<u8> {
+        key_with_epoch(self.end_user_key.clone(), Epoch::MAX)
+    }
+
+    /// Format:
+    ///
+    /// ```plain
+    /// | start user key len (4B) | start user key | end user key len (4B) | end user key |
+    /// | epoch (8B) |
+    /// ```
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        put_length_prefixed_slice(buf, &self.start_user_key);
+        put_length_prefixed_slice(buf, &self.end_user_key);
+        buf.put_u64_le(self.epoch);
+    }
+
+    pub fn decode(buf: &mut &[u8]) -> Self {
+        let start_user_key = get_length_prefixed_slice(buf);
+        let end_user_key = get_length_prefixed_slice(buf);
+        let epoch = buf.get_u64_le();
+        Self {
+            start_user_key,
+            end_user_key,
+            epoch,
+        }
+    }
+
+    #[inline]
+    pub fn encoded_size(&self) -> usize {
+        16 /* key lens + epoch */ + self.start_user_key.len() + self.end_user_key.len()
+    }
+}
+
+/// Returns true if the version of `user_key` written in `key_epoch` is deleted by any of the
+/// `tombstones` for reads at `read_epoch`.
+pub fn deleted_by_range_tombstones(
+    tombstones: &[RangeTombstone],
+    user_key: &[u8],
+    key_epoch: Epoch,
+    read_epoch: Epoch,
+) -> bool {
+    tombstones
+        .iter()
+        .any(|tombstone| tombstone.deletes(user_key, key_epoch, read_epoch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_tombstone_deletes() {
+        let tombstone = RangeTombstone::new(b"b".to_vec(), b"d".to_vec(), 5);
+        assert!(tombstone.deletes(b"b", 4, 5));
+        assert!(tombstone.deletes(b"c", 1, 10));
+        // The end user key is exclusive.
+        assert!(!tombstone.deletes(b"d", 4, 5));
+        assert!(!tombstone.deletes(b"a", 4, 5));
+        // Versions written in the same epoch survive.
+        assert!(!tombstone.deletes(b"c", 5, 5));
+        // Reads before the tombstone don't see it.
+        assert!(!tombstone.deletes(b"c", 3, 4));
+    }
+
+    #[test]
+    fn test_range_tombstone_clip() {
+        let tombstone = RangeTombstone::new(b"b".to_vec(), b"e".to_vec(), 5);
+        assert_eq!(tombstone.clip(b"", b""), Some(tombstone.clone()));
+        assert_eq!(
+            tombstone.clip(b"c", b""),
+            Some(RangeTombstone::new(b"c".to_vec(), b"e".to_vec(), 5))
+        );
+        assert_eq!(
+            tombstone.clip(b"a", b"c"),
+            Some(RangeTombstone::new(b"b".to_vec(), b"c".to_vec(), 5))
+        );
+        assert_eq!(tombstone.clip(b"e", b""), None);
+        assert_eq!(tombstone.clip(b"", b"b"), None);
+    }
+
+    #[test]
+    fn test_range_tombstone_enc_dec() {
+        let tombstone = RangeTombstone::new(b"start".to_vec(), b"start-end".to_vec(), 233);
+        let mut buf = vec![];
+        tombstone.encode(&mut buf);
+        assert_eq!(buf.len(), tombstone.encoded_size());
+        assert_eq!(RangeTombstone::decode(&mut &buf[..]), tombstone);
+    }
+}
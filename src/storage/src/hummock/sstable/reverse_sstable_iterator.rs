@@ -22,9 +22,10 @@ use crate::hummock::iterator::variants::BACKWARD;
 use crate::hummock::iterator::HummockIterator;
 use crate::hummock::value::HummockValue;
 use crate::hummock::{
-    BlockIterator, HummockResult, SSTableIteratorBase, SSTableIteratorType, Sstable,
-    SstableStoreRef,
+    Block, BlockIterator, BlockPrefetcher, HummockResult, RangeTombstone, SSTableIteratorBase,
+    SSTableIteratorType, Sstable, SstableStoreRef,
 };
+use crate::store::ReadOptions;
 
 /// Reversely iterates on a table.
 pub struct ReverseSSTableIterator {
@@ -34,6 +35,9 @@ pub struct ReverseSSTableIterator {
     /// Current block index.
     cur_idx: usize,
 
+    /// Reads the blocks preceding the current one ahead, if prefetching is enabled.
+    prefetcher: Option<BlockPrefetcher>,
+
     /// Reference to the table
     pub sst: Arc<Sstable>,
 
@@ -44,14 +48,33 @@ impl ReverseSSTableIterator {
     pub fn new(table: Arc<Sstable>, sstable_store: SstableStoreRef) -> Self {
         Self {
             block_iter: None,
-            cur_idx: table.meta.block_metas.len() - 1,
+            // A table with only range tombstones has no block.
+            cur_idx: table.meta.block_metas.len().saturating_sub(1),
+            prefetcher: None,
             sst: table,
             sstable_store,
         }
     }
 
+    /// Reads blocks ahead when moving to the previous block if `read_options` asks for
+    /// prefetching, and the sstable store reads any block ahead.
+    #[must_use]
+    pub fn with_read_options(mut self, read_options: ReadOptions) -> Self {
+        if read_options.prefetch && self.sstable_store.prefetch_block_count() > 0 {
+            self.prefetcher = Some(BlockPrefetcher::new(
+                self.sst.clone(),
+                self.sstable_store.clone(),
+                true,
+            ));
+        }
+        self
+    }
+
     /// Seeks to a block, and then seeks to the key if `seek_key` is given.
     async fn seek_idx(&mut self, idx: isize, seek_key: Option<&[u8]>) -> HummockResult<()> {
+        if let Some(prefetcher) = &mut self.prefetcher {
+            prefetcher.reset();
+        }
         if idx >= self.sst.block_count() as isize || idx < 0 {
             self.block_iter = None;
         } else {
@@ -59,18 +82,40 @@ impl ReverseSSTableIterator {
                 .sstable_store
                 .get(&self.sst, idx as u64, crate::hummock::CachePolicy::Fill)
                 .await?;
-            let mut block_iter = BlockIterator::new(block);
-            if let Some(key) = seek_key {
-                block_iter.seek_le(key);
-            } else {
-                block_iter.seek_to_last();
+            self.set_block(idx as usize, block, seek_key);
+        }
+
+        Ok(())
+    }
+
+    /// Moves to the last key of the previous block. If prefetching is enabled, the preceding
+    /// blocks are read ahead, since the iterator is scanning sequentially.
+    async fn prev_block(&mut self) -> HummockResult<()> {
+        if self.cur_idx == 0 {
+            self.block_iter = None;
+            return Ok(());
+        }
+        let idx = self.cur_idx - 1;
+        match &mut self.prefetcher {
+            Some(prefetcher) => {
+                let block = prefetcher.get(idx).await?;
+                self.set_block(idx, block, None);
+                Ok(())
             }
+            None => self.seek_idx(idx as isize, None).await,
+        }
+    }
 
-            self.block_iter = Some(block_iter);
-            self.cur_idx = idx as usize;
+    fn set_block(&mut self, idx: usize, block: Arc<Block>, seek_key: Option<&[u8]>) {
+        let mut block_iter = BlockIterator::new(block);
+        if let Some(key) = seek_key {
+            block_iter.seek_le(key);
+        } else {
+            block_iter.seek_to_last();
         }
 
-        Ok(())
+        self.block_iter = Some(block_iter);
+        self.cur_idx = idx;
     }
 }
 
@@ -84,7 +129,7 @@ impl HummockIterator for ReverseSSTableIterator {
             Ok(())
         } else {
             // seek to the previous block
-            self.seek_idx(self.cur_idx as isize - 1, None).await
+            self.prev_block().await
         }
     }
 
@@ -102,6 +147,10 @@ impl HummockIterator for ReverseSSTableIterator {
         self.block_iter.as_ref().map_or(false, |i| i.is_valid())
     }
 
+    fn collect_range_tombstones(&self, range_tombstones: &mut Vec<RangeTombstone>) {
+        range_tombstones.extend_from_slice(&self.sst.meta.range_tombstones);
+    }
+
     /// Instead of setting idx to 0th block, a `ReverseSSTableIterator` rewinds to the last block in
     /// the table.
     async fn rewind(&mut self) -> HummockResult<()> {
@@ -141,8 +190,12 @@ impl SSTableIteratorType for ReverseSSTableIterator {
 
     const DIRECTION: usize = BACKWARD;
 
-    fn new(table: Arc<Sstable>, sstable_store: SstableStoreRef) -> Self::SSTableIterator {
-        ReverseSSTableIterator::new(table, sstable_store)
+    fn new(
+        table: Arc<Sstable>,
+        sstable_store: SstableStoreRef,
+        read_options: ReadOptions,
+    ) -> Self::SSTableIterator {
+        ReverseSSTableIterator::new(table, sstable_store).with_read_options(read_options)
     }
 }
 
@@ -160,6 +213,8 @@ mod tests {
         default_builder_opt_for_test, gen_default_test_sstable, test_key_of, test_value_of,
         TEST_KEYS_COUNT,
     };
+    use crate::hummock::SstableStore;
+    use crate::monitor::StateStoreMetrics;
 
     #[tokio::test]
     async fn test_reverse_sstable_iterator() {
@@ -256,4 +311,47 @@ mod tests {
         }
         assert!(!sstable_iter.is_valid());
     }
+
+    #[tokio::test]
+    async fn test_reverse_sstable_iterator_with_prefetch() {
+        let sstable_store = mock_sstable_store();
+        let table =
+            gen_default_test_sstable(default_builder_opt_for_test(), 0, sstable_store.clone())
+                .await;
+        assert!(table.meta.block_metas.len() > 10);
+        let table = Arc::new(table);
+        let sstable_store = Arc::new(
+            SstableStore::new(
+                sstable_store.store(),
+                "test".to_string(),
+                Arc::new(StateStoreMetrics::unused()),
+                64 << 20,
+                64 << 20,
+            )
+            .with_prefetch_block_count(4),
+        );
+
+        let mut sstable_iter = ReverseSSTableIterator::new(table.clone(), sstable_store)
+            .with_read_options(ReadOptions { prefetch: true });
+        let mut cnt = TEST_KEYS_COUNT;
+        sstable_iter.rewind().await.unwrap();
+        while sstable_iter.is_valid() {
+            cnt -= 1;
+            assert_bytes_eq!(sstable_iter.key(), test_key_of(cnt));
+            assert_bytes_eq!(
+                sstable_iter.value().into_user_value().unwrap(),
+                test_value_of(cnt)
+            );
+            sstable_iter.next().await.unwrap();
+        }
+        assert_eq!(cnt, 0);
+
+        // Seeking drops the blocks read ahead.
+        sstable_iter.seek(&test_key_of(500)).await.unwrap();
+        for i in (0..=500).rev() {
+            assert_bytes_eq!(sstable_iter.key(), test_key_of(i));
+            sstable_iter.next().await.unwrap();
+        }
+        assert!(!sstable_iter.is_valid());
+    }
 }
@@ -19,10 +19,11 @@ use async_trait::async_trait;
 use risingwave_hummock_sdk::VersionedComparator;
 
 use super::super::{HummockResult, HummockValue};
-use super::Sstable;
+use super::{BlockPrefetcher, Sstable};
 use crate::hummock::iterator::variants::FORWARD;
 use crate::hummock::iterator::HummockIterator;
-use crate::hummock::{BlockIterator, SstableStoreRef};
+use crate::hummock::{Block, BlockIterator, RangeTombstone, SstableStoreRef};
+use crate::store::ReadOptions;
 
 pub trait SSTableIteratorBase: HummockIterator {}
 
@@ -30,7 +31,11 @@ pub trait SSTableIteratorType {
     type SSTableIterator: SSTableIteratorBase;
     const DIRECTION: usize;
 
-    fn new(table: Arc<Sstable>, sstable_store: SstableStoreRef) -> Self::SSTableIterator;
+    fn new(
+        table: Arc<Sstable>,
+        sstable_store: SstableStoreRef,
+        read_options: ReadOptions,
+    ) -> Self::SSTableIterator;
 }
 
 /// Iterates on a table.
@@ -41,6 +46,9 @@ pub struct SSTableIterator {
     /// Current block index.
     cur_idx: usize,
 
+    /// Reads the blocks following the current one ahead, if prefetching is enabled.
+    prefetcher: Option<BlockPrefetcher>,
+
     /// Reference to the sst
     pub sst: Arc<Sstable>,
 
@@ -52,11 +60,26 @@ impl SSTableIterator {
         Self {
             block_iter: None,
             cur_idx: 0,
+            prefetcher: None,
             sst: table,
             sstable_store,
         }
     }
 
+    /// Reads blocks ahead when moving to the next block if `read_options` asks for prefetching,
+    /// and the sstable store reads any block ahead.
+    #[must_use]
+    pub fn with_read_options(mut self, read_options: ReadOptions) -> Self {
+        if read_options.prefetch && self.sstable_store.prefetch_block_count() > 0 {
+            self.prefetcher = Some(BlockPrefetcher::new(
+                self.sst.clone(),
+                self.sstable_store.clone(),
+                false,
+            ));
+        }
+        self
+    }
+
     /// Seeks to a block, and then seeks to the key if `seek_key` is given.
     async fn seek_idx(&mut self, idx: usize, seek_key: Option<&[u8]>) -> HummockResult<()> {
         tracing::trace!(
@@ -65,6 +88,9 @@ impl SSTableIterator {
             self.sst.id,
             idx,
         );
+        if let Some(prefetcher) = &mut self.prefetcher {
+            prefetcher.reset();
+        }
         if idx >= self.sst.block_count() {
             self.block_iter = None;
         } else {
@@ -72,18 +98,40 @@ impl SSTableIterator {
                 .sstable_store
                 .get(&self.sst, idx as u64, crate::hummock::CachePolicy::Fill)
                 .await?;
-            let mut block_iter = BlockIterator::new(block);
-            if let Some(key) = seek_key {
-                block_iter.seek(key);
-            } else {
-                block_iter.seek_to_first();
+            self.set_block(idx, block, seek_key);
+        }
+
+        Ok(())
+    }
+
+    /// Moves to the first key of the next block. If prefetching is enabled, the following blocks
+    /// are read ahead, since the iterator is scanning sequentially.
+    async fn next_block(&mut self) -> HummockResult<()> {
+        let idx = self.cur_idx + 1;
+        if idx >= self.sst.block_count() {
+            self.block_iter = None;
+            return Ok(());
+        }
+        match &mut self.prefetcher {
+            Some(prefetcher) => {
+                let block = prefetcher.get(idx).await?;
+                self.set_block(idx, block, None);
+                Ok(())
             }
+            None => self.seek_idx(idx, None).await,
+        }
+    }
 
-            self.block_iter = Some(block_iter);
-            self.cur_idx = idx;
+    fn set_block(&mut self, idx: usize, block: Arc<Block>, seek_key: Option<&[u8]>) {
+        let mut block_iter = BlockIterator::new(block);
+        if let Some(key) = seek_key {
+            block_iter.seek(key);
+        } else {
+            block_iter.seek_to_first();
         }
 
-        Ok(())
+        self.block_iter = Some(block_iter);
+        self.cur_idx = idx;
     }
 }
 
@@ -97,7 +145,7 @@ impl HummockIterator for SSTableIterator {
             Ok(())
         } else {
             // seek to next block
-            self.seek_idx(self.cur_idx + 1, None).await
+            self.next_block().await
         }
     }
 
@@ -115,6 +163,10 @@ impl HummockIterator for SSTableIterator {
         self.block_iter.as_ref().map_or(false, |i| i.is_valid())
     }
 
+    fn collect_range_tombstones(&self, range_tombstones: &mut Vec<RangeTombstone>) {
+        range_tombstones.extend_from_slice(&self.sst.meta.range_tombstones);
+    }
+
     async fn rewind(&mut self) -> HummockResult<()> {
         self.seek_idx(0, None).await
     }
@@ -150,8 +202,12 @@ impl SSTableIteratorType for SSTableIterator {
 
     const DIRECTION: usize = FORWARD;
 
-    fn new(table: Arc<Sstable>, sstable_store: SstableStoreRef) -> Self::SSTableIterator {
-        SSTableIterator::new(table, sstable_store)
+    fn new(
+        table: Arc<Sstable>,
+        sstable_store: SstableStoreRef,
+        read_options: ReadOptions,
+    ) -> Self::SSTableIterator {
+        SSTableIterator::new(table, sstable_store).with_read_options(read_options)
     }
 }
 
@@ -163,11 +219,15 @@ mod tests {
 
     use super::*;
     use crate::assert_bytes_eq;
-    use crate::hummock::iterator::test_utils::mock_sstable_store;
+    use crate::hummock::iterator::test_utils::{
+        mock_sstable_store, mock_sstable_store_with_object_store,
+    };
     use crate::hummock::test_utils::{
         default_builder_opt_for_test, gen_default_test_sstable, test_key_of, test_value_of,
         TEST_KEYS_COUNT,
     };
+    use crate::hummock::SstableStore;
+    use crate::monitor::StateStoreMetrics;
     #[tokio::test]
     async fn test_table_iterator() {
         // Build remote table
@@ -261,4 +321,142 @@ mod tests {
         }
         assert!(!sstable_iter.is_valid());
     }
+
+    #[tokio::test]
+    async fn test_table_iterator_with_prefetch() {
+        let sstable_store = mock_sstable_store();
+        let table =
+            gen_default_test_sstable(default_builder_opt_for_test(), 0, sstable_store.clone())
+                .await;
+        assert!(table.meta.block_metas.len() > 10);
+        let table = Arc::new(table);
+        let sstable_store = Arc::new(
+            SstableStore::new(
+                sstable_store.store(),
+                "test".to_string(),
+                Arc::new(StateStoreMetrics::unused()),
+                64 << 20,
+                64 << 20,
+            )
+            .with_prefetch_block_count(4),
+        );
+
+        let mut sstable_iter = SSTableIterator::new(table.clone(), sstable_store.clone())
+            .with_read_options(ReadOptions { prefetch: true });
+        let mut cnt = 0;
+        sstable_iter.rewind().await.unwrap();
+        while sstable_iter.is_valid() {
+            assert_bytes_eq!(sstable_iter.key(), test_key_of(cnt));
+            assert_bytes_eq!(
+                sstable_iter.value().into_user_value().unwrap(),
+                test_value_of(cnt)
+            );
+            cnt += 1;
+            sstable_iter.next().await.unwrap();
+        }
+        assert_eq!(cnt, TEST_KEYS_COUNT);
+
+        // Seeking drops the blocks read ahead.
+        sstable_iter.seek(&test_key_of(500)).await.unwrap();
+        for i in 500..TEST_KEYS_COUNT {
+            assert_bytes_eq!(sstable_iter.key(), test_key_of(i));
+            sstable_iter.next().await.unwrap();
+        }
+        assert!(!sstable_iter.is_valid());
+
+        let block_count = table.block_count() as u64;
+        let blocks = sstable_store
+            .get_blocks(&table, block_count - 2..block_count)
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert!(sstable_store
+            .get_blocks(&table, block_count..block_count + 1)
+            .await
+            .is_err());
+    }
+
+    /// Scans `table` in pages of `page_size` keys, opening a new iterator for each page like the
+    /// paged scans of the cell-based table. Returns the number of pages and the number of reads
+    /// sent to the object store.
+    async fn scan_in_pages(
+        table: Arc<Sstable>,
+        sstable_store: SstableStoreRef,
+        stats: &StateStoreMetrics,
+        page_size: usize,
+    ) -> (usize, u64) {
+        let remote_reads = stats.sst_store_get_remote_duration.get_sample_count();
+        let mut pages = 0;
+        let mut cnt = 0;
+        while cnt < TEST_KEYS_COUNT {
+            let mut sstable_iter = SSTableIterator::new(table.clone(), sstable_store.clone())
+                .with_read_options(ReadOptions { prefetch: true });
+            sstable_iter.seek(&test_key_of(cnt)).await.unwrap();
+            for _ in 0..page_size {
+                if !sstable_iter.is_valid() {
+                    break;
+                }
+                assert_bytes_eq!(sstable_iter.key(), test_key_of(cnt));
+                cnt += 1;
+                sstable_iter.next().await.unwrap();
+            }
+            pages += 1;
+        }
+        (
+            pages,
+            stats.sst_store_get_remote_duration.get_sample_count() - remote_reads,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_table_iterator_paged_scan_with_prefetch() {
+        const PAGE_SIZE: usize = 1024;
+        const PREFETCH_BLOCK_COUNT: usize = 4;
+
+        let object_store = mock_sstable_store().store();
+        let table = Arc::new(
+            gen_default_test_sstable(
+                default_builder_opt_for_test(),
+                0,
+                mock_sstable_store_with_object_store(object_store.clone()),
+            )
+            .await,
+        );
+        let block_count = table.block_count();
+        assert!(block_count > 10 * PREFETCH_BLOCK_COUNT);
+        let new_sstable_store = |prefetch_block_count| {
+            let stats = Arc::new(StateStoreMetrics::unused());
+            let sstable_store = Arc::new(
+                SstableStore::new(
+                    object_store.clone(),
+                    "test".to_string(),
+                    stats.clone(),
+                    64 << 20,
+                    64 << 20,
+                )
+                .with_prefetch_block_count(prefetch_block_count),
+            );
+            (sstable_store, stats)
+        };
+
+        // Without prefetching, every block is read on its own.
+        let (sstable_store, stats) = new_sstable_store(0);
+        let (_, remote_reads) =
+            scan_in_pages(table.clone(), sstable_store, &stats, PAGE_SIZE).await;
+        assert_eq!(remote_reads, block_count as u64);
+
+        // The blocks read ahead are kept for the following pages, so that each page reads at
+        // most one partial batch on top of the full ones.
+        let (sstable_store, stats) = new_sstable_store(PREFETCH_BLOCK_COUNT);
+        let (pages, remote_reads) =
+            scan_in_pages(table.clone(), sstable_store.clone(), &stats, PAGE_SIZE).await;
+        assert!(pages > 1);
+        let batch_count = (block_count + PREFETCH_BLOCK_COUNT - 1) / PREFETCH_BLOCK_COUNT;
+        assert!(remote_reads <= (batch_count + 2 * pages) as u64);
+        assert!(remote_reads < block_count as u64);
+
+        // Scanning again reads nothing from the object store.
+        let (_, remote_reads) = scan_in_pages(table, sstable_store, &stats, PAGE_SIZE).await;
+        assert_eq!(remote_reads, 0);
+    }
 }
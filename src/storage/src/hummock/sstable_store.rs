@@ -12,19 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Range;
 use std::sync::Arc;
 
 use bytes::Bytes;
 use fail::fail_point;
+use itertools::Itertools;
 use moka::future::Cache;
+use tokio::sync::Semaphore;
 
-use super::{Block, BlockCache, Sstable, SstableMeta};
+use super::{Block, BlockCache, FileCache, FileCacheKey, Sstable, SstableMeta};
 use crate::hummock::{HummockError, HummockResult};
 use crate::monitor::StateStoreMetrics;
 use crate::object::{BlockLocation, ObjectStoreRef};
 
 const DEFAULT_META_CACHE_INIT_CAPACITY: usize = 1024;
 
+/// Max number of inserts into the file cache running in background. Entries admitted beyond it are
+/// dropped, so that a slow disk doesn't pile up the entries in memory.
+const MAX_PENDING_FILE_CACHE_INSERTS: usize = 64;
+
+/// The prefetch buffer takes `1 / PREFETCH_BUFFER_CAPACITY_RATIO` of the block cache capacity.
+const PREFETCH_BUFFER_CAPACITY_RATIO: usize = 8;
+
 // TODO: Define policy based on use cases (read / compaction / ...).
 pub enum CachePolicy {
     Disable,
@@ -36,7 +46,15 @@ pub struct SstableStore {
     path: String,
     store: ObjectStoreRef,
     block_cache: BlockCache,
+    /// Blocks read ahead by sequential scans. They are kept apart from `block_cache`, so that
+    /// large scans don't evict the hot blocks, while the scans reopened over the same range, e.g.
+    /// to fetch the next page, don't read them again.
+    prefetch_buffer: BlockCache,
     meta_cache: Cache<u64, Arc<Sstable>>,
+    /// Secondary cache of blocks and metas on the local disk.
+    file_cache: Option<FileCacheWriter>,
+    /// Number of blocks read ahead by sequential scans. 0 to disable prefetching.
+    prefetch_block_count: usize,
     /// Statistics.
     stats: Arc<StateStoreMetrics>,
 }
@@ -59,11 +77,46 @@ impl SstableStore {
             path,
             store,
             block_cache: BlockCache::new(block_cache_capacity),
+            prefetch_buffer: BlockCache::new(block_cache_capacity / PREFETCH_BUFFER_CAPACITY_RATIO),
             meta_cache,
+            file_cache: None,
+            prefetch_block_count: 0,
             stats,
         }
     }
 
+    /// Uses `file_cache` as the secondary tier behind the in-memory block and meta caches. Blocks
+    /// evicted from the block cache and all the metas fetched are admitted to it.
+    #[must_use]
+    pub fn with_file_cache(mut self, file_cache: FileCache) -> Self {
+        let file_cache = FileCacheWriter {
+            file_cache: Arc::new(file_cache),
+            insert_permits: Arc::new(Semaphore::new(MAX_PENDING_FILE_CACHE_INSERTS)),
+        };
+        let writer = file_cache.clone();
+        self.block_cache =
+            self.block_cache
+                .with_eviction_listener(Box::new(move |sst_id, block_idx, block| {
+                    writer.insert(FileCacheKey::Block { sst_id, block_idx }, move || {
+                        block.encode()
+                    });
+                }));
+        self.file_cache = Some(file_cache);
+        self
+    }
+
+    /// Makes sequential scans of sstables read `prefetch_block_count` blocks ahead with
+    /// [`SstableStore::get_blocks`].
+    #[must_use]
+    pub fn with_prefetch_block_count(mut self, prefetch_block_count: usize) -> Self {
+        self.prefetch_block_count = prefetch_block_count;
+        self
+    }
+
+    pub fn prefetch_block_count(&self) -> usize {
+        self.prefetch_block_count
+    }
+
     pub async fn put(
         &self,
         sst: &Sstable,
@@ -99,9 +152,7 @@ impl SstableStore {
                 let offset = meta.offset as usize;
                 let len = meta.len as usize;
                 let block = Arc::new(Block::decode(data.slice(offset..offset + len))?);
-                self.block_cache
-                    .insert(sst.id, block_idx as u64, block)
-                    .await
+                self.block_cache.insert(sst.id, block_idx as u64, block);
             }
         }
 
@@ -115,8 +166,22 @@ impl SstableStore {
         policy: CachePolicy,
     ) -> HummockResult<Arc<Block>> {
         self.stats.sst_store_block_request_counts.inc();
+        if let Some(block) = self.prefetch_buffer.get(sst.id, block_index) {
+            return Ok(block);
+        }
 
         let fetch_block = async move {
+            let file_cache_key = FileCacheKey::Block {
+                sst_id: sst.id,
+                block_idx: block_index,
+            };
+            if let Some(block) = self
+                .get_from_file_cache(file_cache_key, Block::decode)
+                .await
+            {
+                return Ok(Arc::new(block));
+            }
+
             let timer = self.stats.sst_store_get_remote_duration.start_timer();
 
             let block_meta = sst
@@ -154,15 +219,92 @@ impl SstableStore {
         }
     }
 
-    pub async fn sstable(&self, sst_id: u64) -> HummockResult<Arc<Sstable>> {
-        let fetch = async move {
-            let path = self.get_sst_meta_path(sst_id);
-            let buf = self
+    /// Gets the blocks in `block_range` of `sst` for a sequential scan, fetching the missing ones
+    /// with a single vectored read. Blocks fetched are admitted to the prefetch buffer rather than
+    /// the block cache, so that large scans don't evict the hot blocks.
+    pub async fn get_blocks(
+        &self,
+        sst: &Sstable,
+        block_range: Range<u64>,
+    ) -> HummockResult<Vec<Arc<Block>>> {
+        let mut blocks = Vec::with_capacity((block_range.end - block_range.start) as usize);
+        let mut missing_positions = vec![];
+        let mut missing_block_locs = vec![];
+        for block_index in block_range {
+            self.stats.sst_store_block_request_counts.inc();
+            if let Some(block) = self.block_cache.get(sst.id, block_index) {
+                blocks.push(Some(block));
+                continue;
+            }
+            if let Some(block) = self.prefetch_buffer.get(sst.id, block_index) {
+                blocks.push(Some(block));
+                continue;
+            }
+            let file_cache_key = FileCacheKey::Block {
+                sst_id: sst.id,
+                block_idx: block_index,
+            };
+            if let Some(block) = self
+                .get_from_file_cache(file_cache_key, Block::decode)
+                .await
+            {
+                blocks.push(Some(Arc::new(block)));
+                continue;
+            }
+            let block_meta = sst
+                .meta
+                .block_metas
+                .get(block_index as usize)
+                .ok_or_else(HummockError::invalid_block)?;
+            missing_positions.push((blocks.len(), block_index));
+            missing_block_locs.push(BlockLocation {
+                offset: block_meta.offset as usize,
+                size: block_meta.len as usize,
+            });
+            blocks.push(None);
+        }
+
+        if !missing_block_locs.is_empty() {
+            let timer = self.stats.sst_store_get_remote_duration.start_timer();
+            let data_path = self.get_sst_data_path(sst.id);
+            let block_data = self
                 .store
-                .read(&path, None)
+                .readv(&data_path, missing_block_locs)
                 .await
                 .map_err(HummockError::object_io_error)?;
-            let meta = SstableMeta::decode(&mut &buf[..])?;
+            timer.observe_duration();
+            for ((position, block_index), data) in missing_positions.into_iter().zip_eq(block_data)
+            {
+                let block = Arc::new(Block::decode(data)?);
+                self.prefetch_buffer
+                    .insert(sst.id, block_index, block.clone());
+                blocks[position] = Some(block);
+            }
+        }
+
+        Ok(blocks.into_iter().map(Option::unwrap).collect())
+    }
+
+    pub async fn sstable(&self, sst_id: u64) -> HummockResult<Arc<Sstable>> {
+        let fetch = async move {
+            let file_cache_key = FileCacheKey::Meta { sst_id };
+            let decode = |buf: Bytes| SstableMeta::decode(&mut &buf[..]);
+            let meta = match self.get_from_file_cache(file_cache_key, decode).await {
+                Some(meta) => meta,
+                None => {
+                    let path = self.get_sst_meta_path(sst_id);
+                    let buf = self
+                        .store
+                        .read(&path, None)
+                        .await
+                        .map_err(HummockError::object_io_error)?;
+                    let meta = decode(buf.clone())?;
+                    if let Some(file_cache) = &self.file_cache {
+                        file_cache.insert(file_cache_key, move || buf);
+                    }
+                    meta
+                }
+            };
             let sst = Arc::new(Sstable { id: sst_id, meta });
             Ok::<_, HummockError>(sst)
         };
@@ -173,6 +315,29 @@ impl SstableStore {
             .map_err(HummockError::other)
     }
 
+    /// Gets the entry of `key` from the file cache and decodes it. Entries failed to decode are
+    /// removed.
+    async fn get_from_file_cache<T>(
+        &self,
+        key: FileCacheKey,
+        decode: impl FnOnce(Bytes) -> HummockResult<T>,
+    ) -> Option<T> {
+        let file_cache = &self.file_cache.as_ref()?.file_cache;
+        match file_cache.get(key).await.map(decode) {
+            Some(Ok(value)) => {
+                self.stats.file_cache_hit_counts.inc();
+                return Some(value);
+            }
+            Some(Err(e)) => {
+                tracing::warn!("corrupted {:?} in file cache: {}", key, e);
+                file_cache.remove(key).await;
+            }
+            None => {}
+        }
+        self.stats.file_cache_miss_counts.inc();
+        None
+    }
+
     pub fn get_sst_meta_path(&self, sst_id: u64) -> String {
         format!("{}/{}.meta", self.path, sst_id)
     }
@@ -195,3 +360,32 @@ impl SstableStore {
 }
 
 pub type SstableStoreRef = Arc<SstableStore>;
+
+/// Inserts entries into the file cache in background, so that reads never wait for the disk.
+#[derive(Clone)]
+struct FileCacheWriter {
+    file_cache: Arc<FileCache>,
+    /// Bounds the inserts running in background.
+    insert_permits: Arc<Semaphore>,
+}
+
+impl FileCacheWriter {
+    /// Inserts the entry of `key` with the content given by `data` in background, or drops it if
+    /// there are already [`MAX_PENDING_FILE_CACHE_INSERTS`] inserts running.
+    fn insert(&self, key: FileCacheKey, data: impl FnOnce() -> Bytes + Send + 'static) {
+        let permit = match self.insert_permits.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                tracing::debug!("too many pending inserts into file cache, drop {:?}", key);
+                return;
+            }
+        };
+        let file_cache = self.file_cache.clone();
+        tokio::spawn(async move {
+            if let Err(e) = file_cache.insert(key, data()).await {
+                tracing::warn!("failed to insert {:?} into file cache: {}", key, e);
+            }
+            drop(permit);
+        });
+    }
+}
@@ -377,3 +377,88 @@ async fn test_reload_storage() {
     let len = count_iter(&mut iter).await;
     assert_eq!(len, 3);
 }
+
+#[tokio::test]
+async fn test_delete_range() {
+    let object_client = Arc::new(ObjectStoreImpl::Mem(InMemObjectStore::new()));
+    let sstable_store = mock_sstable_store_with_object_store(object_client.clone());
+    let hummock_options = Arc::new(default_config_for_test());
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref.clone(),
+        worker_node.id,
+    ));
+    let local_version_manager = Arc::new(LocalVersionManager::new());
+    let hummock_storage = HummockStorage::with_default_stats(
+        hummock_options,
+        sstable_store,
+        local_version_manager,
+        meta_client.clone(),
+        Arc::new(StateStoreMetrics::unused()),
+    )
+    .await
+    .unwrap();
+
+    let batch1 = vec![
+        (Bytes::from("aa"), StorageValue::new_default_put("111")),
+        (Bytes::from("bb"), StorageValue::new_default_put("222")),
+        (Bytes::from("cc"), StorageValue::new_default_put("333")),
+    ];
+    // Rewrites `bb` in the same epoch as the range deletion.
+    let batch2 = vec![(Bytes::from("bb"), StorageValue::new_default_put("222222"))];
+
+    let epoch1: u64 = 1;
+    hummock_storage.ingest_batch(batch1, epoch1).await.unwrap();
+    let epoch2 = epoch1 + 1;
+    hummock_storage
+        .delete_range(Bytes::from("aa"), Bytes::from("cc"), epoch2)
+        .await
+        .unwrap();
+    hummock_storage.ingest_batch(batch2, epoch2).await.unwrap();
+
+    // Reads from shared buffer.
+    check_delete_range(&hummock_storage, epoch1, epoch2).await;
+
+    // Reads from SSTs.
+    hummock_storage.sync(None).await.unwrap();
+    meta_client.commit_epoch(epoch1).await.unwrap();
+    meta_client.commit_epoch(epoch2).await.unwrap();
+    hummock_storage.wait_epoch(epoch2).await.unwrap();
+    check_delete_range(&hummock_storage, epoch1, epoch2).await;
+}
+
+async fn check_delete_range(hummock_storage: &HummockStorage, epoch1: u64, epoch2: u64) {
+    let value = hummock_storage
+        .get(&Bytes::from("aa"), epoch1)
+        .await
+        .unwrap();
+    assert_eq!(value, Some(Bytes::from("111")));
+    let value = hummock_storage
+        .get(&Bytes::from("aa"), epoch2)
+        .await
+        .unwrap();
+    assert_eq!(value, None);
+    let value = hummock_storage
+        .get(&Bytes::from("bb"), epoch2)
+        .await
+        .unwrap();
+    assert_eq!(value, Some(Bytes::from("222222")));
+    // The end key is exclusive.
+    let value = hummock_storage
+        .get(&Bytes::from("cc"), epoch2)
+        .await
+        .unwrap();
+    assert_eq!(value, Some(Bytes::from("333")));
+
+    let mut iter = hummock_storage
+        .iter(..=b"ee".to_vec(), epoch1)
+        .await
+        .unwrap();
+    assert_eq!(count_iter(&mut iter).await, 3);
+    let mut iter = hummock_storage
+        .iter(..=b"ee".to_vec(), epoch2)
+        .await
+        .unwrap();
+    assert_eq!(count_iter(&mut iter).await, 2);
+}
@@ -0,0 +1,128 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use risingwave_hummock_sdk::key::Epoch;
+use risingwave_pb::hummock::StateTtl;
+
+/// Epochs are generated by meta with the physical time in milliseconds in the high bits.
+const EPOCH_PHYSICAL_SHIFT_BITS: u8 = 16;
+
+fn physical_time_ms(epoch: Epoch) -> u64 {
+    epoch >> EPOCH_PHYSICAL_SHIFT_BITS
+}
+
+/// Time-to-live of streaming states, registered by the prefixes of their keyspaces.
+///
+/// A version of a key is expired if it was written more than the TTL before the epoch it is read
+/// at. Expired versions are invisible to reads and are dropped by compaction. The executors
+/// register the TTLs of their states to filter reads with, while compactions use the TTLs carried
+/// in the compact tasks, which meta derives from the catalog.
+#[derive(Default, Debug)]
+pub struct StateTtlRegistry {
+    /// Maps key prefixes to TTLs in milliseconds. The prefixes never overlap with each other.
+    ttls: RwLock<BTreeMap<Vec<u8>, u64>>,
+}
+
+impl StateTtlRegistry {
+    /// Sets the TTL of the keys with `prefix`.
+    pub fn register(&self, prefix: &[u8], ttl: Duration) {
+        self.ttls
+            .write()
+            .insert(prefix.to_vec(), ttl.as_millis() as u64);
+    }
+
+    /// Removes the TTLs of the keys with `prefix`, including the ones registered with longer
+    /// prefixes.
+    pub fn unregister(&self, prefix: &[u8]) {
+        self.ttls
+            .write()
+            .retain(|registered, _| !registered.starts_with(prefix));
+    }
+
+    /// Returns the TTL of `user_key` in milliseconds, or `None` if it never expires.
+    pub fn ttl_of(&self, user_key: &[u8]) -> Option<u64> {
+        let ttls = self.ttls.read();
+        ttls.range::<[u8], _>(..=user_key)
+            .next_back()
+            .filter(|(prefix, _)| user_key.starts_with(prefix))
+            .map(|(_, ttl)| *ttl)
+    }
+
+    /// Returns true if the version of `user_key` written in `key_epoch` is expired for reads at
+    /// `read_epoch`.
+    pub fn is_expired(&self, user_key: &[u8], key_epoch: Epoch, read_epoch: Epoch) -> bool {
+        match self.ttl_of(user_key) {
+            Some(ttl) => {
+                physical_time_ms(read_epoch).saturating_sub(physical_time_ms(key_epoch)) >= ttl
+            }
+            None => false,
+        }
+    }
+}
+
+impl From<&[StateTtl]> for StateTtlRegistry {
+    fn from(state_ttls: &[StateTtl]) -> Self {
+        Self {
+            ttls: RwLock::new(
+                state_ttls
+                    .iter()
+                    .map(|state_ttl| (state_ttl.key_prefix.clone(), state_ttl.ttl_ms))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch_of_ms(ms: u64) -> Epoch {
+        ms << EPOCH_PHYSICAL_SHIFT_BITS
+    }
+
+    #[test]
+    fn test_state_ttl_registry() {
+        let registry = StateTtlRegistry::default();
+        registry.register(b"s1", Duration::from_millis(100));
+        registry.register(b"s3", Duration::from_millis(10));
+
+        assert_eq!(registry.ttl_of(b"s1key"), Some(100));
+        assert_eq!(registry.ttl_of(b"s2key"), None);
+        assert_eq!(registry.ttl_of(b"s3"), Some(10));
+        assert_eq!(registry.ttl_of(b"s"), None);
+
+        assert!(!registry.is_expired(b"s1key", epoch_of_ms(1000), epoch_of_ms(1099)));
+        assert!(registry.is_expired(b"s1key", epoch_of_ms(1000), epoch_of_ms(1100)));
+        assert!(!registry.is_expired(b"s2key", epoch_of_ms(0), epoch_of_ms(1100)));
+        // Reads at epochs before the key never see it expired.
+        assert!(!registry.is_expired(b"s3key", epoch_of_ms(1000), epoch_of_ms(900)));
+
+        registry.unregister(b"s");
+        assert_eq!(registry.ttl_of(b"s1key"), None);
+        assert_eq!(registry.ttl_of(b"s3key"), None);
+
+        let registry = StateTtlRegistry::from(
+            &[StateTtl {
+                key_prefix: b"s1".to_vec(),
+                ttl_ms: 100,
+            }][..],
+        );
+        assert_eq!(registry.ttl_of(b"s1key"), Some(100));
+    }
+}
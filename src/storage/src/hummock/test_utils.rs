@@ -37,6 +37,7 @@ use crate::store::StateStoreIter;
 pub fn default_config_for_test() -> StorageConfig {
     StorageConfig {
         shared_buffer_threshold_size: 67108864, // 64MB
+        shared_buffer_spill_dir: "".to_string(),
         sstable_size: 256 * (1 << 20),
         block_size: 64 * (1 << 10),
         bloom_false_positive: 0.1,
@@ -46,6 +47,10 @@ pub fn default_config_for_test() -> StorageConfig {
         write_conflict_detection_enabled: true,
         block_cache_capacity: 64 << 20,
         meta_cache_capacity: 64 << 20,
+        max_sub_compaction: 4,
+        file_cache_dir: "".to_string(),
+        file_cache_capacity: 64 << 20,
+        block_prefetch_count: 0,
     }
 }
 
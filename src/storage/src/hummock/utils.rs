@@ -15,6 +15,7 @@
 use std::ops::Bound::{Excluded, Included, Unbounded};
 use std::ops::RangeBounds;
 
+use risingwave_hummock_sdk::key::next_key;
 use risingwave_pb::hummock::Level;
 
 use super::{HummockError, HummockResult};
@@ -53,6 +54,39 @@ where
     !too_left && !too_right
 }
 
+/// Returns the longest prefix shared by all the keys in `search_key_range`, which is empty if
+/// there's none.
+pub fn range_common_prefix<R, B>(search_key_range: &R, reverse: bool) -> &[u8]
+where
+    R: RangeBounds<B>,
+    B: AsRef<[u8]>,
+{
+    let (start_bound, end_bound) = if reverse {
+        (search_key_range.end_bound(), search_key_range.start_bound())
+    } else {
+        (search_key_range.start_bound(), search_key_range.end_bound())
+    };
+    let start = match start_bound {
+        Included(start) | Excluded(start) => start.as_ref(),
+        Unbounded => return &[],
+    };
+    // All the keys in the range start with a prefix of `start` if the range ends before the
+    // smallest key after all the keys with the prefix.
+    (1..=start.len())
+        .rev()
+        .map(|len| &start[..len])
+        .find(|prefix| {
+            let prefix_end = next_key(prefix);
+            !prefix_end.is_empty()
+                && match end_bound {
+                    Included(end) => end.as_ref() < prefix_end.as_slice(),
+                    Excluded(end) => end.as_ref() <= prefix_end.as_slice(),
+                    Unbounded => false,
+                }
+        })
+        .unwrap_or_default()
+}
+
 pub fn validate_epoch(safe_epoch: u64, epoch: u64) -> HummockResult<()> {
     if epoch < safe_epoch {
         return Err(HummockError::expired_epoch(safe_epoch, epoch));
@@ -74,3 +108,41 @@ pub fn validate_table_key_range(levels: &[Level]) -> HummockResult<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_common_prefix() {
+        let prefix = b"abc".to_vec();
+        assert_eq!(
+            range_common_prefix(&(prefix.clone()..next_key(&prefix)), false),
+            b"abc"
+        );
+        assert_eq!(
+            range_common_prefix(&(b"abcd".to_vec()..b"abd".to_vec()), false),
+            b"abc"
+        );
+        assert_eq!(
+            range_common_prefix(&(b"abcd".to_vec()..=b"abce".to_vec()), false),
+            b"abc"
+        );
+        assert_eq!(
+            range_common_prefix(&(b"abc".to_vec()..b"abe".to_vec()), false),
+            b"ab"
+        );
+        assert_eq!(
+            range_common_prefix(&(b"abc".to_vec()..b"b".to_vec()), false),
+            b"a"
+        );
+        assert!(range_common_prefix(&(b"abc".to_vec()..b"c".to_vec()), false).is_empty());
+        assert!(range_common_prefix(&(b"abc".to_vec()..), false).is_empty());
+        assert!(range_common_prefix::<_, Vec<u8>>(&(..), false).is_empty());
+        // Reversed ranges start from the end bound.
+        assert_eq!(
+            range_common_prefix(&(b"abd".to_vec()..=b"abc".to_vec()), true),
+            b"ab"
+        );
+    }
+}
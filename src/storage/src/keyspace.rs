@@ -12,11 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use bytes::{BufMut, Bytes, BytesMut};
 use risingwave_common::catalog::TableId;
 use risingwave_hummock_sdk::key::next_key;
 
 use crate::error::StorageResult;
+use crate::store::ReadOptions;
 use crate::StateStore;
 
 /// Provides API to read key-value pairs of a prefix in the storage backend.
@@ -47,6 +50,19 @@ impl<S: StateStore> Keyspace<S> {
         Self { store, prefix }
     }
 
+    /// Creates a [`Keyspace`] covering the shared roots of all operators of a fragment, whose
+    /// operator ids are `(fragment_id << 32) + operator_id`. See
+    /// [`Keyspace::shared_executor_root`].
+    pub fn fragment_root(store: S, fragment_id: u32) -> Self {
+        let prefix = {
+            let mut buf = BytesMut::with_capacity(5);
+            buf.put_u8(b's');
+            buf.put_u32(fragment_id);
+            buf.to_vec()
+        };
+        Self { store, prefix }
+    }
+
     /// Creates a root [`Keyspace`] for an executor.
     pub fn executor_root(store: S, executor_id: u64) -> Self {
         let prefix = {
@@ -58,6 +74,18 @@ impl<S: StateStore> Keyspace<S> {
         Self { store, prefix }
     }
 
+    /// Creates a [`Keyspace`] covering the roots of all executors of an actor, whose executor ids
+    /// are `(actor_id << 32) + operator_id`. See [`Keyspace::executor_root`].
+    pub fn actor_root(store: S, actor_id: u32) -> Self {
+        let prefix = {
+            let mut buf = BytesMut::with_capacity(5);
+            buf.put_u8(b'e');
+            buf.put_u32(actor_id);
+            buf.to_vec()
+        };
+        Self { store, prefix }
+    }
+
     /// Creates a root [`Keyspace`] for a table.
     pub fn table_root(store: S, id: &TableId) -> Self {
         let prefix = {
@@ -90,6 +118,22 @@ impl<S: StateStore> Keyspace<S> {
         self.append(val.to_be_bytes().to_vec())
     }
 
+    /// Sets the time-to-live of the keys in the keyspace. See [`StateStore::set_ttl`].
+    pub fn set_ttl(&self, ttl: Duration) {
+        self.store.set_ttl(&self.prefix, ttl)
+    }
+
+    /// Removes the time-to-live of the keys in the keyspace. See [`StateStore::clear_ttl`].
+    pub fn clear_ttl(&self) {
+        self.store.clear_ttl(&self.prefix)
+    }
+
+    /// Sets the length of the key prefixes after the keyspace prefix to filter scans with. See
+    /// [`StateStore::set_prefix_extractor`].
+    pub fn set_prefix_extractor(&self, prefix_len: usize) {
+        self.store.set_prefix_extractor(&self.prefix, prefix_len)
+    }
+
     /// Treats the keyspace as a single key, and returns the key.
     pub fn key(&self) -> &[u8] {
         &self.prefix
@@ -119,9 +163,22 @@ impl<S: StateStore> Keyspace<S> {
         &self,
         limit: Option<usize>,
         epoch: u64,
+    ) -> StorageResult<Vec<(Bytes, Bytes)>> {
+        self.scan_with_options(limit, epoch, ReadOptions::default())
+            .await
+    }
+
+    /// Same as [`Keyspace::scan`], but reads with given `read_options`.
+    pub async fn scan_with_options(
+        &self,
+        limit: Option<usize>,
+        epoch: u64,
+        read_options: ReadOptions,
     ) -> StorageResult<Vec<(Bytes, Bytes)>> {
         let range = self.prefix.to_owned()..next_key(self.prefix.as_slice());
-        self.store.scan(range, limit, epoch).await
+        self.store
+            .scan_with_options(range, limit, epoch, read_options)
+            .await
     }
 
     /// Scans `limit` keys from the keyspace using an inclusive `start_key` and get their values. If
@@ -132,6 +189,18 @@ impl<S: StateStore> Keyspace<S> {
         start_key: Vec<u8>,
         limit: Option<usize>,
         epoch: u64,
+    ) -> StorageResult<Vec<(Bytes, Bytes)>> {
+        self.scan_with_start_key_and_options(start_key, limit, epoch, ReadOptions::default())
+            .await
+    }
+
+    /// Same as [`Keyspace::scan_with_start_key`], but reads with given `read_options`.
+    pub async fn scan_with_start_key_and_options(
+        &self,
+        start_key: Vec<u8>,
+        limit: Option<usize>,
+        epoch: u64,
+        read_options: ReadOptions,
     ) -> StorageResult<Vec<(Bytes, Bytes)>> {
         assert!(
             start_key[..self.prefix.len()] == self.prefix,
@@ -140,7 +209,17 @@ impl<S: StateStore> Keyspace<S> {
             self.prefix
         );
         let range = start_key..next_key(self.prefix.as_slice());
-        self.store.scan(range, limit, epoch).await
+        self.store
+            .scan_with_options(range, limit, epoch, read_options)
+            .await
+    }
+
+    /// Deletes all the keys in the keyspace written before `epoch` with a range tombstone. See
+    /// [`StateStore::delete_range`].
+    pub async fn delete_all(&self, epoch: u64) -> StorageResult<()> {
+        let start_key = Bytes::from(self.prefix.clone());
+        let end_key = Bytes::from(next_key(self.prefix.as_slice()));
+        self.store.delete_range(start_key, end_key, epoch).await
     }
 
     /// Scans from the keyspace, and then strips the prefix of this keyspace.
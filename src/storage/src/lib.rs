@@ -69,7 +69,7 @@ pub mod tikv;
 
 pub use keyspace::Keyspace;
 extern crate test;
-pub use store::{StateStore, StateStoreIter};
+pub use store::{ReadOptions, StateStore, StateStoreIter};
 pub use store_impl::StateStoreImpl;
 
 pub enum TableScanOptions {
@@ -171,6 +171,36 @@ impl StateStore for MemoryStateStore {
         async move { unimplemented!() }
     }
 
+    fn delete_range(
+        &self,
+        start_key: Bytes,
+        end_key: Bytes,
+        epoch: u64,
+    ) -> Self::DeleteRangeFuture<'_> {
+        async move {
+            let mut inner = self.inner.lock().await;
+            let mut deleted_keys = vec![];
+            let mut last_key = None;
+            let range = to_bytes_range(start_key..end_key);
+            for ((key, Reverse(key_epoch)), value) in inner.range(range) {
+                if *key_epoch > epoch {
+                    continue;
+                }
+                if Some(key) != last_key.as_ref() {
+                    // Versions written in `epoch` itself are not deleted.
+                    if *key_epoch < epoch && value.is_some() {
+                        deleted_keys.push(key.clone());
+                    }
+                    last_key = Some(key.clone());
+                }
+            }
+            for key in deleted_keys {
+                inner.insert((key, Reverse(epoch)), None);
+            }
+            Ok(())
+        }
+    }
+
     fn iter<R, B>(&self, key_range: R, epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -293,4 +323,57 @@ mod tests {
         assert_eq!(state_store.get(b"b", 1).await.unwrap(), None);
         assert_eq!(state_store.get(b"c", 1).await.unwrap(), None);
     }
+
+    #[tokio::test]
+    async fn test_delete_range() {
+        let state_store = MemoryStateStore::new();
+        state_store
+            .ingest_batch(
+                vec![
+                    (
+                        b"a".to_vec().into(),
+                        StorageValue::new_default_put(b"v1".to_vec()),
+                    ),
+                    (
+                        b"b".to_vec().into(),
+                        StorageValue::new_default_put(b"v1".to_vec()),
+                    ),
+                    (
+                        b"c".to_vec().into(),
+                        StorageValue::new_default_put(b"v1".to_vec()),
+                    ),
+                ],
+                0,
+            )
+            .await
+            .unwrap();
+        state_store
+            .ingest_batch(
+                vec![(
+                    b"b".to_vec().into(),
+                    StorageValue::new_default_put(b"v2".to_vec()),
+                )],
+                1,
+            )
+            .await
+            .unwrap();
+        state_store
+            .delete_range(b"a".to_vec().into(), b"c".to_vec().into(), 1)
+            .await
+            .unwrap();
+
+        // Keys written in the same epoch survive, and `c` is out of the range.
+        assert_eq!(
+            state_store.scan("a"..="c", None, 1).await.unwrap(),
+            vec![
+                (b"b".to_vec().into(), b"v2".to_vec().into()),
+                (b"c".to_vec().into(), b"v1".to_vec().into())
+            ]
+        );
+        // Snapshots before the deletion are not affected.
+        assert_eq!(
+            state_store.get(b"a", 0).await.unwrap(),
+            Some(b"v1".to_vec().into())
+        );
+    }
 }
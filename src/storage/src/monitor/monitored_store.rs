@@ -14,6 +14,7 @@
 
 use std::ops::RangeBounds;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::Bytes;
 use futures::Future;
@@ -59,6 +60,21 @@ where
         Ok(monitored)
     }
 
+    async fn monitored_scan<I>(&self, scan: I) -> StorageResult<Vec<(Bytes, Bytes)>>
+    where
+        I: Future<Output = StorageResult<Vec<(Bytes, Bytes)>>>,
+    {
+        let timer = self.stats.range_scan_duration.start_timer();
+        let result = scan.await?;
+        timer.observe_duration();
+
+        self.stats
+            .range_scan_size
+            .observe(result.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>() as _);
+
+        Ok(result)
+    }
+
     pub fn stats(&self) -> Arc<StateStoreMetrics> {
         self.stats.clone()
     }
@@ -97,17 +113,24 @@ where
         R: RangeBounds<B> + Send,
         B: AsRef<[u8]> + Send,
     {
-        async move {
-            let timer = self.stats.range_scan_duration.start_timer();
-            let result = self.inner.scan(key_range, limit, epoch).await?;
-            timer.observe_duration();
-
-            self.stats
-                .range_scan_size
-                .observe(result.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>() as _);
+        self.monitored_scan(self.inner.scan(key_range, limit, epoch))
+    }
 
-            Ok(result)
-        }
+    fn scan_with_options<R, B>(
+        &self,
+        key_range: R,
+        limit: Option<usize>,
+        epoch: u64,
+        read_options: ReadOptions,
+    ) -> Self::ScanFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        self.monitored_scan(
+            self.inner
+                .scan_with_options(key_range, limit, epoch, read_options),
+        )
     }
 
     fn reverse_scan<R, B>(
@@ -160,7 +183,20 @@ where
         R: RangeBounds<B> + Send,
         B: AsRef<[u8]> + Send,
     {
-        async move { self.monitored_iter(self.inner.iter(key_range, epoch)).await }
+        self.monitored_iter(self.inner.iter(key_range, epoch))
+    }
+
+    fn iter_with_options<R, B>(
+        &self,
+        key_range: R,
+        epoch: u64,
+        read_options: ReadOptions,
+    ) -> Self::IterFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        self.monitored_iter(self.inner.iter_with_options(key_range, epoch, read_options))
     }
 
     fn reverse_iter<R, B>(&self, key_range: R, epoch: u64) -> Self::ReverseIterFuture<'_, R, B>
@@ -187,6 +223,22 @@ where
         }
     }
 
+    fn reset_epochs(&self, epochs: &[u64]) {
+        self.inner.reset_epochs(epochs)
+    }
+
+    fn set_ttl(&self, prefix: &[u8], ttl: Duration) {
+        self.inner.set_ttl(prefix, ttl)
+    }
+
+    fn clear_ttl(&self, prefix: &[u8]) {
+        self.inner.clear_ttl(prefix)
+    }
+
+    fn set_prefix_extractor(&self, prefix: &[u8], prefix_len: usize) {
+        self.inner.set_prefix_extractor(prefix, prefix_len)
+    }
+
     fn monitored(self, _stats: Arc<StateStoreMetrics>) -> MonitoredStateStore<Self> {
         panic!("the state store is already monitored")
     }
@@ -198,6 +250,15 @@ where
     ) -> Self::ReplicateBatchFuture<'_> {
         async move { self.inner.replicate_batch(kv_pairs, epoch).await }
     }
+
+    fn delete_range(
+        &self,
+        start_key: Bytes,
+        end_key: Bytes,
+        epoch: u64,
+    ) -> Self::DeleteRangeFuture<'_> {
+        async move { self.inner.delete_range(start_key, end_key, epoch).await }
+    }
 }
 
 /// A state store iterator wrapper for monitoring metrics.
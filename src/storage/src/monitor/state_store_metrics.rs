@@ -53,6 +53,8 @@ macro_rules! for_all_metrics {
 
             bloom_filter_true_negative_counts: GenericCounter<AtomicU64>,
             bloom_filter_might_positive_counts: GenericCounter<AtomicU64>,
+            prefix_bloom_filter_true_negative_counts: GenericCounter<AtomicU64>,
+            prefix_bloom_filter_might_positive_counts: GenericCounter<AtomicU64>,
 
             range_scan_size: Histogram,
             range_scan_duration: Histogram,
@@ -70,6 +72,8 @@ macro_rules! for_all_metrics {
             sst_store_block_request_counts: GenericCounter<AtomicU64>,
             sst_store_get_remote_duration: Histogram,
             sst_store_put_remote_duration: Histogram,
+            file_cache_hit_counts: GenericCounter<AtomicU64>,
+            file_cache_miss_counts: GenericCounter<AtomicU64>,
 
             shared_buffer_to_l0_duration: Histogram,
             shared_buffer_to_sstable_size: Histogram,
@@ -144,6 +148,20 @@ impl StateStoreMetrics {
         )
         .unwrap();
 
+        let prefix_bloom_filter_true_negative_counts = register_int_counter_with_registry!(
+            "state_store_prefix_bloom_filter_true_negative_counts",
+            "Total number of sst tables that have been skipped by prefix bloom filters in scans.",
+            registry
+        )
+        .unwrap();
+
+        let prefix_bloom_filter_might_positive_counts = register_int_counter_with_registry!(
+            "state_store_prefix_bloom_filter_might_positive_counts",
+            "Total number of sst tables that have been considered possibly positive by prefix bloom filters in scans.",
+            registry
+        )
+        .unwrap();
+
         // ----- range_scan -----
         let buckets = DEFAULT_BUCKETS.map(|x| x * RANGE_SCAN_SIZE_SCALE).to_vec();
         let opts = histogram_opts!(
@@ -282,6 +300,20 @@ impl StateStoreMetrics {
         let sst_store_put_remote_duration =
             register_histogram_with_registry!(opts, registry).unwrap();
 
+        let file_cache_hit_counts = register_int_counter_with_registry!(
+            "state_store_file_cache_hit_counts",
+            "Total number of blocks and metas that have been read from the local file cache",
+            registry
+        )
+        .unwrap();
+
+        let file_cache_miss_counts = register_int_counter_with_registry!(
+            "state_store_file_cache_miss_counts",
+            "Total number of blocks and metas that have been missed in the local file cache",
+            registry
+        )
+        .unwrap();
+
         // --
         let compaction_upload_sst_counts = register_int_counter_with_registry!(
             "state_store_compaction_upload_sst_counts",
@@ -299,6 +331,8 @@ impl StateStoreMetrics {
             get_shared_buffer_hit_counts,
             bloom_filter_true_negative_counts,
             bloom_filter_might_positive_counts,
+            prefix_bloom_filter_true_negative_counts,
+            prefix_bloom_filter_might_positive_counts,
 
             range_scan_size,
             range_scan_duration,
@@ -316,6 +350,8 @@ impl StateStoreMetrics {
             sst_store_block_request_counts,
             sst_store_get_remote_duration,
             sst_store_put_remote_duration,
+            file_cache_hit_counts,
+            file_cache_miss_counts,
 
             shared_buffer_to_l0_duration,
             shared_buffer_to_sstable_size,
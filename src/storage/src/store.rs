@@ -14,6 +14,7 @@
 use std::future::Future;
 use std::ops::RangeBounds;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::Bytes;
 
@@ -35,6 +36,7 @@ macro_rules! define_state_store_associated_type {
         type ReverseScanFuture<'a, R, B> = impl ScanFutureTrait<'a, R, B> where R: 'static + Send, B: 'static + Send;
         type IngestBatchFuture<'a> = impl IngestBatchFutureTrait<'a>;
         type ReplicateBatchFuture<'a> = impl EmptyFutureTrait<'a>;
+        type DeleteRangeFuture<'a> = impl EmptyFutureTrait<'a>;
         type WaitEpochFuture<'a> = impl EmptyFutureTrait<'a>;
         type SyncFuture<'a> = impl EmptyFutureTrait<'a>;
         type IterFuture<'a, R, B> = impl Future<Output = $crate::error::StorageResult<Self::Iter<'a>>> + Send where R: 'static + Send, B: 'static + Send;
@@ -42,6 +44,14 @@ macro_rules! define_state_store_associated_type {
     }
 }
 
+/// Options of a read from the state store, which only affect how data is read, but not the result.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReadOptions {
+    /// Whether to read the blocks of sstables ahead, which pays off for long sequential scans,
+    /// e.g. batch table scans, but wastes I/O for short ones.
+    pub prefetch: bool,
+}
+
 pub trait StateStore: Send + Sync + 'static + Clone {
     type Iter<'a>: StateStoreIter<Item = (Bytes, Bytes)>
     where
@@ -63,6 +73,8 @@ pub trait StateStore: Send + Sync + 'static + Clone {
 
     type ReplicateBatchFuture<'a>: EmptyFutureTrait<'a>;
 
+    type DeleteRangeFuture<'a>: EmptyFutureTrait<'a>;
+
     type WaitEpochFuture<'a>: EmptyFutureTrait<'a>;
 
     type SyncFuture<'a>: EmptyFutureTrait<'a>;
@@ -96,6 +108,22 @@ pub trait StateStore: Send + Sync + 'static + Clone {
         R: RangeBounds<B> + Send,
         B: AsRef<[u8]> + Send;
 
+    /// Same as [`StateStore::scan`], but reads with given `read_options`. By default, the options
+    /// are ignored.
+    fn scan_with_options<R, B>(
+        &self,
+        key_range: R,
+        limit: Option<usize>,
+        epoch: u64,
+        _read_options: ReadOptions,
+    ) -> Self::ScanFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        self.scan(key_range, limit, epoch)
+    }
+
     fn reverse_scan<R, B>(
         &self,
         key_range: R,
@@ -128,6 +156,15 @@ pub trait StateStore: Send + Sync + 'static + Clone {
         epoch: u64,
     ) -> Self::ReplicateBatchFuture<'_>;
 
+    /// Deletes all keys in `[start_key, end_key)` written before `epoch`. Keys written in `epoch`
+    /// itself or later are not affected. The deletion is only visible to reads at `epoch` or later.
+    fn delete_range(
+        &self,
+        start_key: Bytes,
+        end_key: Bytes,
+        epoch: u64,
+    ) -> Self::DeleteRangeFuture<'_>;
+
     /// Opens and returns an iterator for given `key_range`.
     /// The returned iterator will iterate data based on a snapshot corresponding to the given
     /// `epoch`.
@@ -136,6 +173,21 @@ pub trait StateStore: Send + Sync + 'static + Clone {
         R: RangeBounds<B> + Send,
         B: AsRef<[u8]> + Send;
 
+    /// Same as [`StateStore::iter`], but reads with given `read_options`. By default, the options
+    /// are ignored.
+    fn iter_with_options<R, B>(
+        &self,
+        key_range: R,
+        epoch: u64,
+        _read_options: ReadOptions,
+    ) -> Self::IterFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        self.iter(key_range, epoch)
+    }
+
     /// Opens and returns a reversed iterator for given `key_range`.
     /// The returned iterator will iterate data based on a snapshot corresponding to the given
     /// `epoch`
@@ -154,9 +206,27 @@ pub trait StateStore: Send + Sync + 'static + Clone {
 
     /// Syncs buffered data to S3.
     /// If the epoch is None, all buffered data will be synced.
-    /// Otherwise, only data of the provided epoch will be synced.
+    /// Otherwise, data of the epochs up to the provided epoch inclusively will be synced.
     fn sync(&self, epoch: Option<u64>) -> Self::SyncFuture<'_>;
 
+    /// Drops the buffered data of `epochs` that are not synced yet, which will never be committed.
+    /// By default, there's no buffered data to drop.
+    fn reset_epochs(&self, _epochs: &[u64]) {}
+
+    /// Sets the time-to-live of the keys with `prefix`. A version expires once it was written more
+    /// than `ttl` before the read epoch. Expired versions are invisible to reads, and might be
+    /// dropped by compaction. By default, the TTL is ignored.
+    fn set_ttl(&self, _prefix: &[u8], _ttl: Duration) {}
+
+    /// Removes the time-to-live of the keys with `prefix`, including the ones set with longer
+    /// prefixes.
+    fn clear_ttl(&self, _prefix: &[u8]) {}
+
+    /// Sets the length of the key prefixes after `prefix` to filter prefix scans with. Scans over
+    /// keys sharing a prefix of at least `prefix.len() + prefix_len` bytes might skip the data
+    /// that cannot contain it. By default, the prefix extractor is ignored.
+    fn set_prefix_extractor(&self, _prefix: &[u8], _prefix_len: usize) {}
+
     /// Creates a [`MonitoredStateStore`] from this state store, with given `stats`.
     fn monitored(self, stats: Arc<StateStoreMetrics>) -> MonitoredStateStore<Self> {
         MonitoredStateStore::new(self, stats)
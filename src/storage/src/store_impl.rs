@@ -21,7 +21,7 @@ use risingwave_rpc_client::HummockMetaClient;
 
 use crate::error::StorageResult;
 use crate::hummock::local_version_manager::LocalVersionManager;
-use crate::hummock::{HummockStorage, SstableStore};
+use crate::hummock::{FileCache, HummockStorage, SstableStore, SstableStoreRef};
 use crate::memory::MemoryStateStore;
 use crate::monitor::{MonitoredStateStore as Monitored, StateStoreMetrics};
 use crate::object::{InMemObjectStore, ObjectStoreImpl, S3ObjectStore};
@@ -92,37 +92,11 @@ impl StateStoreImpl {
     ) -> StorageResult<Self> {
         let store = match s {
             hummock if hummock.starts_with("hummock") => {
-                let object_store = Arc::new(match hummock {
-                    s3 if s3.starts_with("hummock+s3://") => ObjectStoreImpl::S3(
-                        S3ObjectStore::new(s3.strip_prefix("hummock+s3://").unwrap().to_string())
-                            .await,
-                    ),
-                    minio if minio.starts_with("hummock+minio://") => ObjectStoreImpl::S3(
-                        S3ObjectStore::new_with_minio(minio.strip_prefix("hummock+").unwrap())
-                            .await,
-                    ),
-                    memory if memory.starts_with("hummock+memory") => {
-                        tracing::warn!("You're using Hummock in-memory object store. This should never be used in benchmarks and production environment.");
-                        ObjectStoreImpl::Mem(InMemObjectStore::new())
-                    }
-                    other => {
-                        unimplemented!(
-                            "{} Hummock only supports s3, minio and memory for now.",
-                            other
-                        )
-                    }
-                });
-
-                let sstable_store = Arc::new(SstableStore::new(
-                    object_store,
-                    config.data_directory.to_string(),
-                    state_store_stats.clone(),
-                    config.block_cache_capacity,
-                    config.meta_cache_capacity,
-                ));
+                let sstable_store =
+                    new_hummock_sstable_store(hummock, &config, state_store_stats.clone()).await?;
                 let inner = HummockStorage::new(
                     config.clone(),
-                    sstable_store.clone(),
+                    sstable_store,
                     Arc::new(LocalVersionManager::new()),
                     hummock_meta_client,
                     state_store_stats.clone(),
@@ -154,3 +128,51 @@ impl StateStoreImpl {
         Ok(store)
     }
 }
+
+/// Creates the `SstableStore` of Hummock on the object store specified by `url`, which is a
+/// Hummock state store url like `hummock+s3://bucket`.
+pub async fn new_hummock_sstable_store(
+    url: &str,
+    config: &StorageConfig,
+    state_store_stats: Arc<StateStoreMetrics>,
+) -> StorageResult<SstableStoreRef> {
+    let object_store = Arc::new(new_hummock_object_store(url).await);
+
+    let mut sstable_store = SstableStore::new(
+        object_store,
+        config.data_directory.to_string(),
+        state_store_stats,
+        config.block_cache_capacity,
+        config.meta_cache_capacity,
+    )
+    .with_prefetch_block_count(config.block_prefetch_count);
+    if !config.file_cache_dir.is_empty() {
+        let file_cache =
+            FileCache::open(&config.file_cache_dir, config.file_cache_capacity).await?;
+        sstable_store = sstable_store.with_file_cache(file_cache);
+    }
+    Ok(Arc::new(sstable_store))
+}
+
+/// Creates the object store of Hummock specified by `url`, which is a Hummock state store url like
+/// `hummock+s3://bucket`.
+pub async fn new_hummock_object_store(url: &str) -> ObjectStoreImpl {
+    match url {
+        s3 if s3.starts_with("hummock+s3://") => ObjectStoreImpl::S3(
+            S3ObjectStore::new(s3.strip_prefix("hummock+s3://").unwrap().to_string()).await,
+        ),
+        minio if minio.starts_with("hummock+minio://") => ObjectStoreImpl::S3(
+            S3ObjectStore::new_with_minio(minio.strip_prefix("hummock+").unwrap()).await,
+        ),
+        memory if memory.starts_with("hummock+memory") => {
+            tracing::warn!("You're using Hummock in-memory object store. This should never be used in benchmarks and production environment.");
+            ObjectStoreImpl::Mem(InMemObjectStore::new())
+        }
+        other => {
+            unimplemented!(
+                "{} Hummock only supports s3, minio and memory for now.",
+                other
+            )
+        }
+    }
+}
@@ -30,7 +30,7 @@ use crate::cell_based_row_serializer::CellBasedRowSerializer;
 use crate::error::{StorageError, StorageResult};
 use crate::monitor::StateStoreMetrics;
 use crate::storage_value::StorageValue;
-use crate::{Keyspace, StateStore};
+use crate::{Keyspace, ReadOptions, StateStore};
 
 /// `CellBasedTable` is the interface accessing relational data in KV(`StateStore`) with encoding
 /// format: [keyspace | pk | `column_id` (4B)] -> value.
@@ -210,10 +210,20 @@ impl<S: StateStore> CellBasedTable<S> {
 
     // The returned iterator will iterate data from a snapshot corresponding to the given `epoch`
     pub async fn iter(&self, epoch: u64) -> StorageResult<CellBasedTableRowIter<S>> {
+        self.iter_with_options(epoch, ReadOptions::default()).await
+    }
+
+    /// Same as [`CellBasedTable::iter`], but reads from the state store with `read_options`.
+    pub async fn iter_with_options(
+        &self,
+        epoch: u64,
+        read_options: ReadOptions,
+    ) -> StorageResult<CellBasedTableRowIter<S>> {
         CellBasedTableRowIter::new(
             self.keyspace.clone(),
             self.column_descs.clone(),
             epoch,
+            read_options,
             self.stats.clone(),
         )
         .await
@@ -239,6 +249,8 @@ pub struct CellBasedTableRowIter<S: StateStore> {
     done: bool,
     /// An epoch representing the read snapshot
     epoch: u64,
+    /// Options to read from the state store with
+    read_options: ReadOptions,
     /// Cell-based row deserializer
     cell_based_row_deserializer: CellBasedRowDeserializer,
     /// Statistics
@@ -252,6 +264,7 @@ impl<S: StateStore> CellBasedTableRowIter<S> {
         keyspace: Keyspace<S>,
         table_descs: Vec<ColumnDesc>,
         epoch: u64,
+        read_options: ReadOptions,
         _stats: Arc<StateStoreMetrics>,
     ) -> StorageResult<Self> {
         keyspace.state_store().wait_epoch(epoch).await?;
@@ -264,6 +277,7 @@ impl<S: StateStore> CellBasedTableRowIter<S> {
             next_idx: 0,
             done: false,
             epoch,
+            read_options,
             cell_based_row_deserializer,
             _stats,
         };
@@ -276,13 +290,18 @@ impl<S: StateStore> CellBasedTableRowIter<S> {
         if self.buf.is_empty() {
             self.buf = self
                 .keyspace
-                .scan(Some(Self::SCAN_LIMIT), self.epoch)
+                .scan_with_options(Some(Self::SCAN_LIMIT), self.epoch, self.read_options)
                 .await?;
         } else {
             let last_key = self.buf.last().unwrap().0.clone();
             let buf = self
                 .keyspace
-                .scan_with_start_key(last_key.to_vec(), Some(Self::SCAN_LIMIT), self.epoch)
+                .scan_with_start_key_and_options(
+                    last_key.to_vec(),
+                    Some(Self::SCAN_LIMIT),
+                    self.epoch,
+                    self.read_options,
+                )
                 .await?;
             assert!(!buf.is_empty());
             assert_eq!(buf.first().as_ref().unwrap().0, last_key);
@@ -64,6 +64,15 @@ impl StateStore for TikvStateStore {
         async move { unimplemented!() }
     }
 
+    fn delete_range(
+        &self,
+        _start_key: Bytes,
+        _end_key: Bytes,
+        _epoch: u64,
+    ) -> Self::DeleteRangeFuture<'_> {
+        async move { unimplemented!() }
+    }
+
     fn reverse_iter<R, B>(&self, _key_range: R, _epoch: u64) -> Self::ReverseIterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -0,0 +1,250 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module implements `StreamingBitwiseAgg`.
+
+use risingwave_common::array::stream_chunk::Ops;
+use risingwave_common::array::*;
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::{DataType, Datum, ScalarImpl, ScalarRefImpl};
+use risingwave_expr::expr::AggKind;
+
+use super::StreamingAggStateImpl;
+
+/// `StreamingBitwiseAgg` computes `bit_and`, `bit_or`, `bool_and` and `bool_or`, treating a
+/// boolean as a single bit. Neither AND nor OR can be undone from the result alone, so it counts
+/// how many non-null inputs have each bit set: a bit of `bit_or` is set if any input has it, and a
+/// bit of `bit_and` if all of them do. The non-null count followed by the bit counts are persisted
+/// as a `bigint[]`.
+#[derive(Clone, Debug)]
+pub struct StreamingBitwiseAgg {
+    kind: AggKind,
+    return_type: DataType,
+    non_null_count: i64,
+    bit_counts: Vec<i64>,
+}
+
+impl StreamingBitwiseAgg {
+    pub fn new(kind: AggKind, return_type: DataType) -> Self {
+        let width = match return_type {
+            DataType::Boolean => 1,
+            DataType::Int16 => 16,
+            DataType::Int32 => 32,
+            DataType::Int64 => 64,
+            _ => panic!("unsupported return type of {}: {:?}", kind, return_type),
+        };
+        Self {
+            kind,
+            return_type,
+            non_null_count: 0,
+            bit_counts: vec![0; width],
+        }
+    }
+
+    /// Restore the state persisted by [`StreamingAggStateImpl::get_state`].
+    pub fn with_state(kind: AggKind, return_type: DataType, datum: Datum) -> Result<Self> {
+        let mut agg = Self::new(kind, return_type);
+        if let Some(scalar) = datum {
+            let counts = match &scalar {
+                ScalarImpl::List(list) if list.values().len() == agg.bit_counts.len() + 1 => list
+                    .values()
+                    .iter()
+                    .map(|v| match v {
+                        Some(ScalarImpl::Int64(count)) => Some(*count),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>(),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                ErrorCode::InternalError(format!("invalid state of {}: {:?}", agg.kind, scalar))
+            })?;
+            agg.non_null_count = counts[0];
+            agg.bit_counts.copy_from_slice(&counts[1..]);
+        }
+        Ok(agg)
+    }
+
+    /// The type of the persisted state.
+    pub fn state_type() -> DataType {
+        DataType::List {
+            datatype: Box::new(DataType::Int64),
+        }
+    }
+
+    fn to_bits(scalar: ScalarRefImpl<'_>) -> Result<u64> {
+        let bits = match scalar {
+            ScalarRefImpl::Bool(v) => v as u64,
+            ScalarRefImpl::Int16(v) => v as u16 as u64,
+            ScalarRefImpl::Int32(v) => v as u32 as u64,
+            ScalarRefImpl::Int64(v) => v as u64,
+            other => {
+                return Err(ErrorCode::InternalError(format!(
+                    "unsupported input of bitwise aggregation: {}",
+                    other.get_ident()
+                ))
+                .into())
+            }
+        };
+        Ok(bits)
+    }
+}
+
+impl StreamingAggStateImpl for StreamingBitwiseAgg {
+    fn apply_batch(
+        &mut self,
+        ops: Ops<'_>,
+        visibility: Option<&Bitmap>,
+        data: &[&ArrayImpl],
+    ) -> Result<()> {
+        for (row_id, op) in ops.iter().enumerate() {
+            if let Some(visibility) = visibility {
+                if !visibility.is_set(row_id)? {
+                    continue;
+                }
+            }
+            if let Some(scalar) = data[0].value_at(row_id) {
+                let delta = match op {
+                    Op::Insert | Op::UpdateInsert => 1,
+                    Op::Delete | Op::UpdateDelete => -1,
+                };
+                let bits = Self::to_bits(scalar)?;
+                self.non_null_count += delta;
+                for (i, count) in self.bit_counts.iter_mut().enumerate() {
+                    if bits & (1 << i) != 0 {
+                        *count += delta;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get_output(&self) -> Result<Datum> {
+        if self.non_null_count == 0 {
+            return Ok(None);
+        }
+        let all_set = matches!(self.kind, AggKind::BitAnd | AggKind::BoolAnd);
+        let bits = self
+            .bit_counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| {
+                if all_set {
+                    count == self.non_null_count
+                } else {
+                    count > 0
+                }
+            })
+            .fold(0u64, |bits, (i, _)| bits | (1 << i));
+        let output = match self.return_type {
+            DataType::Boolean => ScalarImpl::Bool(bits != 0),
+            DataType::Int16 => ScalarImpl::Int16(bits as u16 as i16),
+            DataType::Int32 => ScalarImpl::Int32(bits as u32 as i32),
+            _ => ScalarImpl::Int64(bits as i64),
+        };
+        Ok(Some(output))
+    }
+
+    fn get_state(&self) -> Result<Datum> {
+        let counts = std::iter::once(self.non_null_count)
+            .chain(self.bit_counts.iter().copied())
+            .map(|count| Some(ScalarImpl::Int64(count)))
+            .collect();
+        Ok(Some(ScalarImpl::List(ListValue::new(counts))))
+    }
+
+    fn new_builder(&self) -> ArrayBuilderImpl {
+        self.return_type.create_array_builder(0).unwrap()
+    }
+
+    fn reset(&mut self) {
+        self.non_null_count = 0;
+        self.bit_counts.fill(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_bit_agg() {
+        let mut bit_and = StreamingBitwiseAgg::new(AggKind::BitAnd, DataType::Int32);
+        let mut bit_or = StreamingBitwiseAgg::new(AggKind::BitOr, DataType::Int32);
+        let ops = [Op::Insert, Op::Insert, Op::Insert, Op::Insert];
+        let data: ArrayImpl = I32Array::from_slice(&[Some(0b1110), Some(0b0111), None, Some(-1)])
+            .unwrap()
+            .into();
+        bit_and.apply_batch(&ops, None, &[&data]).unwrap();
+        bit_or.apply_batch(&ops, None, &[&data]).unwrap();
+        assert_eq!(
+            bit_and.get_output().unwrap(),
+            Some(ScalarImpl::Int32(0b0110))
+        );
+        assert_eq!(bit_or.get_output().unwrap(), Some(ScalarImpl::Int32(-1)));
+
+        // Retract -1 and 0b0111.
+        let ops = [Op::Delete, Op::UpdateDelete];
+        let data: ArrayImpl = I32Array::from_slice(&[Some(-1), Some(0b0111)])
+            .unwrap()
+            .into();
+        bit_and.apply_batch(&ops, None, &[&data]).unwrap();
+        bit_or.apply_batch(&ops, None, &[&data]).unwrap();
+        assert_eq!(
+            bit_and.get_output().unwrap(),
+            Some(ScalarImpl::Int32(0b1110))
+        );
+        assert_eq!(
+            bit_or.get_output().unwrap(),
+            Some(ScalarImpl::Int32(0b1110))
+        );
+
+        let restored = StreamingBitwiseAgg::with_state(
+            AggKind::BitOr,
+            DataType::Int32,
+            bit_or.get_state().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            restored.get_output().unwrap(),
+            Some(ScalarImpl::Int32(0b1110))
+        );
+    }
+
+    #[test]
+    fn test_streaming_bool_agg() {
+        let mut bool_and = StreamingBitwiseAgg::new(AggKind::BoolAnd, DataType::Boolean);
+        let mut bool_or = StreamingBitwiseAgg::new(AggKind::BoolOr, DataType::Boolean);
+        assert_eq!(bool_and.get_output().unwrap(), None);
+
+        let ops = [Op::Insert, Op::Insert, Op::Insert];
+        let data: ArrayImpl = BoolArray::from_slice(&[Some(true), Some(false), None])
+            .unwrap()
+            .into();
+        bool_and.apply_batch(&ops, None, &[&data]).unwrap();
+        bool_or.apply_batch(&ops, None, &[&data]).unwrap();
+        assert_eq!(
+            bool_and.get_output().unwrap(),
+            Some(ScalarImpl::Bool(false))
+        );
+        assert_eq!(bool_or.get_output().unwrap(), Some(ScalarImpl::Bool(true)));
+
+        // Retract false.
+        let data: ArrayImpl = BoolArray::from_slice(&[Some(false)]).unwrap().into();
+        bool_and.apply_batch(&[Op::Delete], None, &[&data]).unwrap();
+        assert_eq!(bool_and.get_output().unwrap(), Some(ScalarImpl::Bool(true)));
+    }
+}
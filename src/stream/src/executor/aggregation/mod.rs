@@ -16,6 +16,8 @@ use std::any::Any;
 
 pub use agg_call::*;
 pub use agg_executor::*;
+pub use approx_count_distinct::*;
+pub use bitwise::*;
 use dyn_clone::{self, DynClone};
 pub use foldable::*;
 use risingwave_common::array::stream_chunk::Ops;
@@ -29,15 +31,19 @@ use risingwave_common::types::{DataType, Datum};
 use risingwave_expr::expr::AggKind;
 use risingwave_expr::*;
 pub use row_count::*;
+pub use variance::*;
 
 pub use super::aggregation::StreamingRowCountAgg;
 use crate::executor::aggregation::single_value::StreamingSingleValueAgg;
 
 mod agg_call;
 mod agg_executor;
+mod approx_count_distinct;
+mod bitwise;
 mod foldable;
 mod row_count;
 mod single_value;
+mod variance;
 
 /// `StreamingSumAgg` sums data of the same type.
 pub type StreamingSumAgg<R, I> =
@@ -83,6 +89,12 @@ pub trait StreamingAggStateImpl: Any + std::fmt::Debug + DynClone + Send + Sync
     /// Get the output value
     fn get_output(&self) -> Result<Datum>;
 
+    /// Get the value to persist, from which [`create_streaming_agg_state`] restores the state. It
+    /// is the output value unless the output is not enough to maintain the state incrementally.
+    fn get_state(&self) -> Result<Datum> {
+        self.get_output()
+    }
+
     /// Get the builder of the state output
     fn new_builder(&self) -> ArrayBuilderImpl;
 
@@ -92,6 +104,20 @@ pub trait StreamingAggStateImpl: Any + std::fmt::Debug + DynClone + Send + Sync
 
 dyn_clone::clone_trait_object!(StreamingAggStateImpl);
 
+/// Returns the type of the value persisted by [`StreamingAggStateImpl::get_state`].
+pub fn streaming_agg_state_type(agg_type: &AggKind, return_type: &DataType) -> DataType {
+    match agg_type {
+        AggKind::StddevSamp | AggKind::StddevPop | AggKind::VarSamp | AggKind::VarPop => {
+            StreamingVarianceAgg::state_type()
+        }
+        AggKind::BoolAnd | AggKind::BoolOr | AggKind::BitAnd | AggKind::BitOr => {
+            StreamingBitwiseAgg::state_type()
+        }
+        AggKind::ApproxCountDistinct => StreamingApproxCountDistinct::state_type(),
+        _ => return_type.clone(),
+    }
+}
+
 /// [postgresql specification of aggregate functions](https://www.postgresql.org/docs/13/functions-aggregate.html)
 /// Most of the general-purpose aggregate functions have one input except for:
 /// 1. `count(*) -> bigint`. The input type of count(*)
@@ -133,6 +159,30 @@ pub fn create_streaming_agg_state(
         }
     }
 
+    // These states are restored from a persisted state rather than the output, see
+    // `streaming_agg_state_type`.
+    match agg_type {
+        AggKind::StddevSamp | AggKind::StddevPop | AggKind::VarSamp | AggKind::VarPop => {
+            return Ok(Box::new(StreamingVarianceAgg::with_state(
+                agg_type.clone(),
+                datum.flatten(),
+            )?));
+        }
+        AggKind::BoolAnd | AggKind::BoolOr | AggKind::BitAnd | AggKind::BitOr => {
+            return Ok(Box::new(StreamingBitwiseAgg::with_state(
+                agg_type.clone(),
+                return_type.clone(),
+                datum.flatten(),
+            )?));
+        }
+        AggKind::ApproxCountDistinct => {
+            return Ok(Box::new(StreamingApproxCountDistinct::with_state(
+                datum.flatten(),
+            )?));
+        }
+        _ => {}
+    }
+
     let state: Box<dyn StreamingAggStateImpl> = match input_types {
         [input_type] => {
             gen_unary_agg_state_match!(
@@ -0,0 +1,178 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module implements `StreamingVarianceAgg`.
+
+use std::sync::Arc;
+
+use risingwave_common::array::stream_chunk::Ops;
+use risingwave_common::array::*;
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+use risingwave_expr::expr::AggKind;
+use risingwave_expr::vector_op::agg::{numeric_to_f64, VarianceState};
+
+use super::StreamingAggStateImpl;
+
+/// `StreamingVarianceAgg` computes `var_samp`, `var_pop`, `stddev_samp` and `stddev_pop`. It keeps
+/// the count, mean and sum of squared differences of the inputs, which can be rolled back on
+/// retractions, and persists them as a `struct<bigint, double, double>`.
+#[derive(Clone, Debug)]
+pub struct StreamingVarianceAgg {
+    kind: AggKind,
+    state: VarianceState,
+}
+
+impl StreamingVarianceAgg {
+    pub fn new(kind: AggKind) -> Self {
+        Self {
+            kind,
+            state: VarianceState::default(),
+        }
+    }
+
+    /// Restore the state persisted by [`StreamingAggStateImpl::get_state`].
+    pub fn with_state(kind: AggKind, datum: Datum) -> Result<Self> {
+        let state = match datum {
+            None => VarianceState::default(),
+            Some(ScalarImpl::Struct(value)) => match value.fields() {
+                [Some(count), Some(mean), Some(m2)] => {
+                    VarianceState::new(*count.as_int64(), mean.as_float64().0, m2.as_float64().0)
+                }
+                _ => {
+                    return Err(ErrorCode::InternalError(format!(
+                        "invalid state of {}: {:?}",
+                        kind, value
+                    ))
+                    .into())
+                }
+            },
+            Some(other) => {
+                return Err(ErrorCode::InternalError(format!(
+                    "invalid state of {}: {}",
+                    kind,
+                    other.get_ident()
+                ))
+                .into())
+            }
+        };
+        Ok(Self { kind, state })
+    }
+
+    /// The type of the persisted state.
+    pub fn state_type() -> DataType {
+        DataType::Struct {
+            fields: Arc::new([DataType::Int64, DataType::Float64, DataType::Float64]),
+        }
+    }
+}
+
+impl StreamingAggStateImpl for StreamingVarianceAgg {
+    fn apply_batch(
+        &mut self,
+        ops: Ops<'_>,
+        visibility: Option<&Bitmap>,
+        data: &[&ArrayImpl],
+    ) -> Result<()> {
+        for (row_id, op) in ops.iter().enumerate() {
+            if let Some(visibility) = visibility {
+                if !visibility.is_set(row_id)? {
+                    continue;
+                }
+            }
+            if let Some(scalar) = data[0].value_at(row_id) {
+                let value = numeric_to_f64(scalar)?;
+                match op {
+                    Op::Insert | Op::UpdateInsert => self.state.accumulate(value),
+                    Op::Delete | Op::UpdateDelete => self.state.retract(value),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get_output(&self) -> Result<Datum> {
+        Ok(self
+            .state
+            .output(&self.kind)
+            .map(|v| ScalarImpl::Float64(v.into())))
+    }
+
+    fn get_state(&self) -> Result<Datum> {
+        Ok(Some(ScalarImpl::Struct(StructValue::new(vec![
+            Some(ScalarImpl::Int64(self.state.count())),
+            Some(ScalarImpl::Float64(self.state.mean().into())),
+            Some(ScalarImpl::Float64(self.state.m2().into())),
+        ]))))
+    }
+
+    fn new_builder(&self) -> ArrayBuilderImpl {
+        ArrayBuilderImpl::Float64(F64ArrayBuilder::new(0).unwrap())
+    }
+
+    fn reset(&mut self) {
+        self.state = VarianceState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_variance_agg() {
+        let mut agg = StreamingVarianceAgg::new(AggKind::VarPop);
+        agg.apply_batch(
+            &[Op::Insert, Op::Insert, Op::Insert, Op::Insert],
+            None,
+            &[&I32Array::from_slice(&[Some(1), Some(3), None, Some(100)])
+                .unwrap()
+                .into()],
+        )
+        .unwrap();
+        agg.apply_batch(
+            &[Op::Delete, Op::Insert],
+            Some(&Bitmap::try_from(vec![true, false]).unwrap()),
+            &[&I32Array::from_slice(&[Some(100), Some(7)]).unwrap().into()],
+        )
+        .unwrap();
+        let output = agg.get_output().unwrap().unwrap().into_float64();
+        assert!((output.0 - 1.0).abs() < 1e-9);
+
+        // Restore from the persisted state.
+        let mut agg =
+            StreamingVarianceAgg::with_state(AggKind::StddevSamp, agg.get_state().unwrap())
+                .unwrap();
+        agg.apply_batch(
+            &[Op::UpdateDelete],
+            None,
+            &[&I32Array::from_slice(&[Some(3)]).unwrap().into()],
+        )
+        .unwrap();
+        assert_eq!(agg.get_output().unwrap(), None);
+        agg.apply_batch(
+            &[Op::Delete],
+            None,
+            &[&I32Array::from_slice(&[Some(1)]).unwrap().into()],
+        )
+        .unwrap();
+        assert_eq!(
+            agg.get_state().unwrap(),
+            StreamingVarianceAgg::new(AggKind::VarPop)
+                .get_state()
+                .unwrap()
+        );
+    }
+}
@@ -29,7 +29,7 @@ impl ExecutorBuilder for ChainExecutorBuilder {
     fn new_boxed_executor(
         mut params: ExecutorParams,
         node: &stream_plan::StreamNode,
-        _store: impl StateStore,
+        store: impl StateStore,
         stream: &mut LocalStreamManagerCore,
     ) -> Result<Box<dyn Executor>> {
         let node = try_match_expand!(node.get_node().unwrap(), Node::ChainNode)?;
@@ -54,6 +54,7 @@ impl ExecutorBuilder for ChainExecutorBuilder {
             snapshot,
             mview,
             notifier,
+            store,
             schema,
             column_idxs,
             params.op_info,
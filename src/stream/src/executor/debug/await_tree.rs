@@ -0,0 +1,181 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::future::poll_fn;
+use risingwave_common::error::Result;
+
+use crate::executor::{Executor, Message};
+
+/// A node in the await tree of an actor. It mirrors one executor in the executor chain, and records
+/// whether the executor is currently awaiting its input and the epoch of the last barrier it has
+/// passed through.
+#[derive(Debug)]
+pub struct AwaitNode {
+    identity: String,
+
+    /// The time the node was created, which `pending_since` is relative to.
+    created_at: Instant,
+
+    /// Microseconds since `created_at` when the executor started awaiting its input plus one, or 0
+    /// if the executor is not awaiting.
+    pending_since: AtomicU64,
+
+    /// Epoch of the last barrier yielded by the executor, or 0 if none.
+    last_epoch: AtomicU64,
+
+    inputs: Vec<Arc<AwaitNode>>,
+}
+
+impl AwaitNode {
+    pub fn new(identity: impl Into<String>, inputs: Vec<Arc<AwaitNode>>) -> Self {
+        Self {
+            identity: identity.into(),
+            created_at: Instant::now(),
+            pending_since: AtomicU64::new(0),
+            last_epoch: AtomicU64::new(0),
+            inputs,
+        }
+    }
+
+    /// Marks the executor as awaiting its input.
+    fn enter(&self) {
+        let since = self.created_at.elapsed().as_micros() as u64 + 1;
+        self.pending_since.store(since, Ordering::Relaxed);
+    }
+
+    fn exit(&self, message: Option<&Message>) {
+        self.pending_since.store(0, Ordering::Relaxed);
+        if let Some(Message::Barrier(barrier)) = message {
+            self.last_epoch.store(barrier.epoch.curr, Ordering::Relaxed);
+        }
+    }
+
+    /// Epoch of the last barrier yielded by the executor, or 0 if none.
+    pub fn last_epoch(&self) -> u64 {
+        self.last_epoch.load(Ordering::Relaxed)
+    }
+
+    /// Render the tree as indented text, one executor per line.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        self.render_inner(&mut output, 0, Instant::now());
+        output
+    }
+
+    fn render_inner(&self, output: &mut String, depth: usize, now: Instant) {
+        let pending_since = self.pending_since.load(Ordering::Relaxed);
+        let state = if pending_since == 0 {
+            "idle".to_owned()
+        } else {
+            let pending_since = self.created_at + Duration::from_micros(pending_since - 1);
+            format!(
+                "pending for {}ms",
+                now.saturating_duration_since(pending_since).as_millis()
+            )
+        };
+        writeln!(
+            output,
+            "{:indent$}{} [{}, last epoch {}]",
+            "",
+            self.identity,
+            state,
+            self.last_epoch(),
+            indent = depth * 2
+        )
+        .unwrap();
+        for input in &self.inputs {
+            input.render_inner(output, depth + 1, now);
+        }
+    }
+}
+
+/// [`AwaitTreeExecutor`] updates the [`AwaitNode`] of its input executor on every `next` call, so
+/// that a stuck actor can be diagnosed by rendering the await tree.
+#[derive(Debug)]
+pub struct AwaitTreeExecutor {
+    /// The input of the current executor.
+    input: Box<dyn Executor>,
+
+    node: Arc<AwaitNode>,
+}
+
+impl AwaitTreeExecutor {
+    pub fn new(input: Box<dyn Executor>, node: Arc<AwaitNode>) -> Self {
+        Self { input, node }
+    }
+}
+
+#[async_trait]
+impl super::DebugExecutor for AwaitTreeExecutor {
+    async fn next(&mut self) -> Result<Message> {
+        // Only the executors actually waiting for their inputs are marked as pending, so that the
+        // clock is not read for the messages ready right away.
+        let mut next = self.input.next();
+        let first_poll = poll_fn(|cx| Poll::Ready(next.as_mut().poll(cx))).await;
+        let message = match first_poll {
+            Poll::Ready(message) => message,
+            Poll::Pending => {
+                self.node.enter();
+                next.await
+            }
+        };
+        self.node.exit(message.as_ref().ok());
+        message
+    }
+
+    fn input(&self) -> &dyn Executor {
+        self.input.as_ref()
+    }
+
+    fn input_mut(&mut self) -> &mut dyn Executor {
+        self.input.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::StreamChunk;
+
+    use super::*;
+    use crate::executor::test_utils::MockSource;
+
+    #[tokio::test]
+    async fn test_await_tree() {
+        let mut source = MockSource::new(Default::default(), vec![]);
+        source.push_chunks([StreamChunk::default()].into_iter());
+        source.push_barrier(114, false);
+
+        let input = Arc::new(AwaitNode::new("Source", vec![]));
+        let node = Arc::new(AwaitNode::new("Project", vec![input.clone()]));
+        let source = AwaitTreeExecutor::new(Box::new(source), input);
+        let mut executor = AwaitTreeExecutor::new(Box::new(source), node.clone());
+
+        executor.next().await.unwrap();
+        assert_eq!(node.last_epoch(), 0);
+        executor.next().await.unwrap();
+        assert_eq!(node.last_epoch(), 114);
+        assert_eq!(
+            node.render(),
+            "Project [idle, last epoch 114]\n  Source [idle, last epoch 114]\n"
+        );
+    }
+}
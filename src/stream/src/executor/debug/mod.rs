@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod await_tree;
 mod cache_clear;
 mod epoch_check;
 mod schema_check;
@@ -23,6 +24,7 @@ use async_trait::async_trait;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
 
+pub use self::await_tree::*;
 pub use self::cache_clear::*;
 pub use self::epoch_check::*;
 pub use self::schema_check::*;
@@ -177,7 +177,7 @@ impl DispatchExecutor {
         Ok(())
     }
 
-    /// For `Add` and `Update`, update the outputs before we dispatch the barrier.
+    /// For `Add`, `Update` and `Reschedule`, update the outputs before we dispatch the barrier.
     async fn pre_mutate_outputs(&mut self, mutation: &Option<Arc<Mutation>>) -> Result<()> {
         match mutation.as_deref() {
             Some(Mutation::UpdateOutputs(updates)) => {
@@ -203,7 +203,7 @@ impl DispatchExecutor {
                     self.inner.set_outputs(new_outputs)
                 }
             }
-            Some(Mutation::AddOutput(adds)) => {
+            Some(Mutation::AddOutput(adds)) | Some(Mutation::Reschedule { adds, .. }) => {
                 if let Some(downstream_actor_infos) = adds.get(&self.actor_id) {
                     let mut outputs_to_add = Vec::with_capacity(downstream_actor_infos.len());
                     for downstream_actor_info in downstream_actor_infos {
@@ -225,11 +225,10 @@ impl DispatchExecutor {
         Ok(())
     }
 
-    /// For `Stop`, update the outputs after we dispatch the barrier.
+    /// For `Stop` and `Reschedule`, update the outputs after we dispatch the barrier.
     async fn post_mutate_outputs(&mut self, mutation: &Option<Arc<Mutation>>) -> Result<()> {
-        #[allow(clippy::single_match)]
         match mutation.as_deref() {
-            Some(Mutation::Stop(stops)) => {
+            Some(Mutation::Stop(stops)) | Some(Mutation::Reschedule { stops, .. }) => {
                 // Remove outputs only if this actor itself is not to be stopped.
                 if !stops.contains(&self.actor_id) {
                     self.inner.remove_outputs(stops);
@@ -716,11 +715,12 @@ pub use sender_consumer::SenderConsumer;
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::hash::{BuildHasher, Hasher};
     use std::sync::{Arc, Mutex};
 
     use futures::channel::mpsc::channel;
+    use futures::StreamExt;
     use itertools::Itertools;
     use risingwave_common::array::column::Column;
     use risingwave_common::array::{Array, ArrayBuilder, I32ArrayBuilder, I64Array, Op};
@@ -963,6 +963,59 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_reschedule_outputs() {
+        let schema = Schema { fields: vec![] };
+        let (mut tx, rx) = channel(16);
+        let input = Box::new(ReceiverExecutor::new(schema.clone(), vec![], rx)).v1();
+        let actor_id = 233;
+        let data_sinks = (0..2).map(|_| Arc::new(Mutex::new(vec![]))).collect_vec();
+        let outputs = data_sinks
+            .iter()
+            .enumerate()
+            .map(|(idx, sink)| {
+                Box::new(MockOutput::new(234 + idx as u32, sink.clone())) as BoxedOutput
+            })
+            .collect_vec();
+        let ctx = Arc::new(SharedContext::for_test());
+
+        let mut executor = Box::new(DispatchExecutor::new(
+            Box::new(input),
+            DispatcherImpl::Broadcast(BroadcastDispatcher::new(outputs)),
+            actor_id,
+            ctx.clone(),
+        ));
+
+        // Replace actor 235 with actor 236.
+        add_local_channels(ctx.clone(), vec![(233, 236)]);
+        let mut new_output = ctx.take_receiver(&(233, 236)).unwrap();
+        let barrier = Barrier::new_test_barrier(1).with_mutation(Mutation::Reschedule {
+            adds: HashMap::from([(actor_id, vec![helper_make_local_actor(236)])]),
+            stops: HashSet::from([235]),
+        });
+        tx.send(Message::Barrier(barrier)).await.unwrap();
+        executor.next().await.unwrap();
+
+        // All of the old and new downstream actors should receive the barrier.
+        for sink in &data_sinks {
+            assert!(sink.lock().unwrap()[0].is_barrier());
+        }
+        assert!(new_output.next().await.unwrap().is_barrier());
+
+        // The stopped actor won't receive any message after the barrier.
+        tx.send(Message::Chunk(StreamChunk::new(
+            vec![Op::Insert],
+            vec![column_nonnull! { I64Array, [1] }],
+            None,
+        )))
+        .await
+        .unwrap();
+        executor.next().await.unwrap();
+        assert_eq!(data_sinks[0].lock().unwrap().len(), 2);
+        assert_eq!(data_sinks[1].lock().unwrap().len(), 1);
+        assert!(new_output.next().await.unwrap().is_chunk());
+    }
+
     #[tokio::test]
     async fn test_hash_dispatcher() {
         let num_outputs = 5; // actor id ranges from 1 to 5
@@ -19,10 +19,10 @@ use risingwave_common::error::Result;
 use risingwave_common::try_match_expand;
 use risingwave_pb::stream_plan;
 use risingwave_pb::stream_plan::stream_node::Node;
-use risingwave_storage::{Keyspace, StateStore};
+use risingwave_storage::StateStore;
 
 use super::aggregation::*;
-use crate::executor::{Executor, ExecutorBuilder};
+use crate::executor::{state_keyspace, Executor, ExecutorBuilder};
 use crate::executor_v2::{Executor as ExecutorV2, SimpleAggExecutor as SimpleAggExecutorV2};
 use crate::task::{build_agg_call_from_prost, ExecutorParams, LocalStreamManagerCore};
 
@@ -41,7 +41,10 @@ impl ExecutorBuilder for SimpleAggExecutorBuilder {
             .iter()
             .map(build_agg_call_from_prost)
             .try_collect()?;
-        let keyspace = Keyspace::executor_root(store, params.executor_id);
+        let keyspace = state_keyspace(store, params.executor_id, node.table_id);
+        if let Some(ttl) = params.state_ttl {
+            keyspace.set_ttl(ttl);
+        }
         let key_indices = node
             .get_distribution_keys()
             .iter()
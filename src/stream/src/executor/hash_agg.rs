@@ -25,7 +25,7 @@ use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::{AggCall, Executor};
-use crate::executor::{ExecutorBuilder, PkIndices};
+use crate::executor::{state_keyspace, ExecutorBuilder, PkIndices};
 use crate::executor_v2::{Executor as ExecutorV2, HashAggExecutor};
 use crate::task::{build_agg_call_from_prost, ExecutorParams, LocalStreamManagerCore};
 
@@ -81,7 +81,10 @@ impl ExecutorBuilder for HashAggExecutorBuilder {
             .iter()
             .map(build_agg_call_from_prost)
             .try_collect()?;
-        let keyspace = Keyspace::shared_executor_root(store, params.executor_id);
+        let keyspace = state_keyspace(store, params.executor_id, node.table_id);
+        if let Some(ttl) = params.state_ttl {
+            keyspace.set_ttl(ttl);
+        }
         let input = params.input.remove(0);
         let keys = key_indices
             .iter()
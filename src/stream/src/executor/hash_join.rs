@@ -56,6 +56,25 @@ mod SideType {
 const JOIN_LEFT_PATH: u8 = b'l';
 const JOIN_RIGHT_PATH: u8 = b'r';
 
+/// Returns the length of the join keys serialized by [`Row::serialize`], if all the key columns
+/// have fixed-size encodings. A null key is shorter, and is never filtered with the prefix.
+fn fixed_join_key_len(data_types: &[DataType], key_indices: &[usize]) -> Option<usize> {
+    key_indices
+        .iter()
+        .map(|idx| {
+            let size = match data_types[*idx] {
+                DataType::Boolean => 1,
+                DataType::Int16 => 2,
+                DataType::Int32 | DataType::Float32 => 4,
+                DataType::Int64 | DataType::Float64 => 8,
+                _ => return None,
+            };
+            // One more byte for the null tag.
+            Some(size + 1)
+        })
+        .sum()
+}
+
 const fn outer_side_keep(join_type: JoinTypePrimitive, side_type: SideTypePrimitive) -> bool {
     join_type == JoinType::FullOuter
         || (join_type == JoinType::LeftOuter && side_type == SideType::Left)
@@ -158,6 +177,11 @@ impl ExecutorBuilder for HashJoinExecutorBuilder {
             .map(|key| *key as usize)
             .collect::<Vec<_>>();
 
+        let keyspace = Keyspace::shared_executor_root(store, params.operator_id);
+        if let Some(ttl) = params.state_ttl {
+            keyspace.set_ttl(ttl);
+        }
+
         macro_rules! impl_create_hash_join_executor {
             ($( { $join_type_proto:ident, $join_type:ident } ),*) => {
                 |typ| match typ {
@@ -167,7 +191,7 @@ impl ExecutorBuilder for HashJoinExecutorBuilder {
                         params_l,
                         params_r,
                         params.pk_indices,
-                        Keyspace::shared_executor_root(store.clone(), params.operator_id),
+                        keyspace,
                         params.executor_id,
                         condition,
                         params.op_info,
@@ -347,6 +371,15 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
 
         let ks_l = keyspace.append_u8(JOIN_LEFT_PATH);
         let ks_r = keyspace.append_u8(JOIN_RIGHT_PATH);
+        // The join keys lead the keys of the join states. Put them into the prefix bloom filters
+        // if they're of fixed length, so that fetching the rows of a join key skips the tables
+        // without it.
+        if let Some(len) = fixed_join_key_len(&col_l_datatypes, &params_l.key_indices) {
+            ks_l.set_prefix_extractor(len);
+        }
+        if let Some(len) = fixed_join_key_len(&col_r_datatypes, &params_r.key_indices) {
+            ks_r.set_prefix_extractor(len);
+        }
         Self {
             aligner: BarrierAligner::new(input_l, input_r),
             output_data_types,
@@ -80,11 +80,13 @@ async fn test_merger_sum_aggr() {
                         kind: AggKind::RowCount,
                         args: AggArgs::None,
                         return_type: DataType::Int64,
+                        distinct: false,
                     },
                     AggCall {
                         kind: AggKind::Sum,
                         args: AggArgs::Unary(DataType::Int64, 0),
                         return_type: DataType::Int64,
+                        distinct: false,
                     },
                 ],
                 vec![],
@@ -148,11 +150,13 @@ async fn test_merger_sum_aggr() {
                     kind: AggKind::Sum,
                     args: AggArgs::Unary(DataType::Int64, 0),
                     return_type: DataType::Int64,
+                    distinct: false,
                 },
                 AggCall {
                     kind: AggKind::Sum,
                     args: AggArgs::Unary(DataType::Int64, 1),
                     return_type: DataType::Int64,
+                    distinct: false,
                 },
             ],
             create_in_memory_keyspace(),
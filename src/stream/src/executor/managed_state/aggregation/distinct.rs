@@ -0,0 +1,346 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deduplication of the inputs of `agg(DISTINCT ...)`.
+
+use std::collections::HashMap;
+
+use risingwave_common::array::stream_chunk::Ops;
+use risingwave_common::array::{ArrayImpl, Op};
+use risingwave_common::buffer::{Bitmap, BitmapBuilder};
+use risingwave_common::error::Result;
+use risingwave_common::types::{serialize_datum_ref_into, DataType, Datum, ScalarImpl};
+use risingwave_common::util::value_encoding::{deserialize_cell, serialize_cell};
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::write_batch::WriteBatch;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::ManagedStateImpl;
+
+/// `ManagedDistinctState` deduplicates the inputs of `agg(DISTINCT ...)` before they reach the
+/// state of the aggregation itself. It keeps a reference count for each distinct value of the group
+/// in the state store, keyed by the memcomparable encoding of the value. Only the first insertion
+/// and the last deletion of a value are forwarded to the inner state, so retractions are handled
+/// correctly.
+///
+/// The rows inserting and deleting a distinct value may have different primary keys, so an inner
+/// table state is keyed by the distinct value instead of the primary key of the input rows.
+pub struct ManagedDistinctState<S: StateStore> {
+    /// The state of the aggregation over the deduplicated inputs. It is never a distinct state.
+    inner: ManagedStateImpl<S>,
+
+    /// The keyspace of the reference counts.
+    keyspace: Keyspace<S>,
+
+    /// Reference counts changed since the last flush.
+    dirty_counts: HashMap<Vec<u8>, i64>,
+}
+
+impl<S: StateStore> ManagedDistinctState<S> {
+    pub fn new(inner: ManagedStateImpl<S>, keyspace: Keyspace<S>) -> Self {
+        assert!(!matches!(inner, ManagedStateImpl::Distinct(_)));
+        Self {
+            inner,
+            keyspace,
+            dirty_counts: HashMap::new(),
+        }
+    }
+
+    /// Apply a batch of data to the reference counts, and forward the rows that change the set of
+    /// distinct values to the inner state.
+    pub async fn apply_batch(
+        &mut self,
+        ops: Ops<'_>,
+        visibility: Option<&Bitmap>,
+        data: &[&ArrayImpl],
+        epoch: u64,
+    ) -> Result<()> {
+        debug_assert!(super::verify_batch(ops, visibility, data));
+        let mut forwarded = BitmapBuilder::with_capacity(ops.len());
+        for (row_id, op) in ops.iter().enumerate() {
+            if let Some(visibility) = visibility {
+                if !visibility.is_set(row_id)? {
+                    forwarded.append(false);
+                    continue;
+                }
+            }
+            let mut serializer = memcomparable::Serializer::new(vec![]);
+            serialize_datum_ref_into(&data[0].value_at(row_id), &mut serializer)?;
+            let count = self.ref_count(serializer.into_inner(), epoch).await?;
+            let forward = match op {
+                Op::Insert | Op::UpdateInsert => {
+                    *count += 1;
+                    *count == 1
+                }
+                Op::Delete | Op::UpdateDelete => {
+                    *count -= 1;
+                    *count == 0
+                }
+            };
+            forwarded.append(forward);
+        }
+        let forwarded = forwarded.finish();
+
+        match &mut self.inner {
+            ManagedStateImpl::Value(state) => state.apply_batch(ops, Some(&forwarded), data).await,
+            ManagedStateImpl::Table(state) => {
+                state
+                    .apply_batch(ops, Some(&forwarded), &[data[0], data[0]], epoch)
+                    .await
+            }
+            ManagedStateImpl::Distinct(_) => unreachable!(),
+        }
+    }
+
+    /// Get the reference count of an encoded value, loading it from the state store if it has not
+    /// been changed since the last flush.
+    async fn ref_count(&mut self, key: Vec<u8>, epoch: u64) -> Result<&mut i64> {
+        if !self.dirty_counts.contains_key(&key) {
+            let count = match self.keyspace.get(&key, epoch).await? {
+                Some(raw) => {
+                    let mut deserializer = value_encoding::Deserializer::new(raw);
+                    deserialize_cell(&mut deserializer, &DataType::Int64)?
+                        .map(|count| *count.as_int64())
+                        .unwrap_or(0)
+                }
+                None => 0,
+            };
+            self.dirty_counts.insert(key.clone(), count);
+        }
+        Ok(self.dirty_counts.get_mut(&key).unwrap())
+    }
+
+    /// Get the output of the inner state. Must flush before getting output.
+    pub async fn get_output(&mut self, epoch: u64) -> Result<Datum> {
+        match &mut self.inner {
+            ManagedStateImpl::Value(state) => state.get_output().await,
+            ManagedStateImpl::Table(state) => state.get_output(epoch).await,
+            ManagedStateImpl::Distinct(_) => unreachable!(),
+        }
+    }
+
+    /// Check if this state needs a flush.
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty_counts.is_empty() || self.inner.is_dirty()
+    }
+
+    /// Flush the reference counts and the inner state to a write batch. Reference counts dropping
+    /// to zero are deleted.
+    pub fn flush(&mut self, write_batch: &mut WriteBatch<S>) -> Result<()> {
+        let mut local = write_batch.prefixify(&self.keyspace);
+        for (key, count) in self.dirty_counts.drain() {
+            if count == 0 {
+                local.delete(key);
+            } else {
+                let value = serialize_cell(&Some(ScalarImpl::Int64(count)))?;
+                local.put(key, StorageValue::new_default_put(value));
+            }
+        }
+        if self.inner.is_dirty() {
+            self.inner.flush(write_batch)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{I32Array, I64Array, ListValue};
+    use risingwave_expr::expr::AggKind;
+    use risingwave_storage::memory::MemoryStateStore;
+    use smallvec::smallvec;
+
+    use super::*;
+    use crate::executor::test_utils::create_in_memory_keyspace;
+    use crate::executor::{AggArgs, AggCall};
+
+    async fn create_count_distinct_state(
+        keyspace: &Keyspace<MemoryStateStore>,
+        row_count: usize,
+    ) -> ManagedStateImpl<MemoryStateStore> {
+        let agg_call = AggCall {
+            kind: AggKind::Count,
+            args: AggArgs::Unary(DataType::Int64, 0),
+            return_type: DataType::Int64,
+            distinct: true,
+        };
+        ManagedStateImpl::create_managed_state(
+            agg_call,
+            keyspace.clone(),
+            Some(row_count),
+            smallvec![],
+            false,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_managed_distinct_state() {
+        let keyspace = create_in_memory_keyspace();
+        let mut managed_state = create_count_distinct_state(&keyspace, 0).await;
+        assert!(matches!(managed_state, ManagedStateImpl::Distinct(_)));
+
+        let mut epoch = 0;
+        managed_state
+            .apply_batch(
+                &[Op::Insert, Op::Insert, Op::Insert, Op::Insert, Op::Insert],
+                None,
+                &[
+                    &I64Array::from_slice(&[Some(1), Some(2), Some(1), None, Some(2)])
+                        .unwrap()
+                        .into(),
+                ],
+                epoch,
+            )
+            .await
+            .unwrap();
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        managed_state.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+        assert_eq!(
+            managed_state.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int64(2))
+        );
+
+        // Reload the state, so that the reference counts are read from the state store.
+        epoch += 1;
+        let mut managed_state = create_count_distinct_state(&keyspace, 5).await;
+        managed_state
+            .apply_batch(
+                &[Op::Delete, Op::Delete, Op::Delete, Op::Insert],
+                None,
+                &[&I64Array::from_slice(&[Some(1), Some(2), Some(2), Some(3)])
+                    .unwrap()
+                    .into()],
+                epoch,
+            )
+            .await
+            .unwrap();
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        managed_state.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+        assert_eq!(
+            managed_state.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int64(1))
+        );
+    }
+
+    async fn create_array_agg_distinct_state(
+        keyspace: &Keyspace<MemoryStateStore>,
+        row_count: usize,
+    ) -> ManagedStateImpl<MemoryStateStore> {
+        let agg_call = AggCall {
+            kind: AggKind::ArrayAgg,
+            args: AggArgs::Unary(DataType::Int32, 0),
+            return_type: DataType::List {
+                datatype: Box::new(DataType::Int32),
+            },
+            distinct: true,
+        };
+        ManagedStateImpl::create_managed_state(
+            agg_call,
+            keyspace.clone(),
+            Some(row_count),
+            smallvec![DataType::Int64],
+            false,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_managed_distinct_table_state_retraction() {
+        let keyspace = create_in_memory_keyspace();
+        let mut managed_state = create_array_agg_distinct_state(&keyspace, 0).await;
+
+        // Rows `(value, pk)`: (1, 1), (1, 2), (2, 3).
+        let mut epoch = 0;
+        managed_state
+            .apply_batch(
+                &[Op::Insert, Op::Insert, Op::Insert],
+                None,
+                &[
+                    &I32Array::from_slice(&[Some(1), Some(1), Some(2)])
+                        .unwrap()
+                        .into(),
+                    &I64Array::from_slice(&[Some(1), Some(2), Some(3)])
+                        .unwrap()
+                        .into(),
+                ],
+                epoch,
+            )
+            .await
+            .unwrap();
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        managed_state.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+        assert_eq!(
+            managed_state.get_output(epoch).await.unwrap(),
+            Some(ListValue::new(vec![Some(1.into()), Some(2.into())]).into())
+        );
+
+        // Reload the state, update row 1 to value 3 and delete row 2. The value 1 is retracted by
+        // a row other than the one inserting it.
+        epoch += 1;
+        let mut managed_state = create_array_agg_distinct_state(&keyspace, 3).await;
+        managed_state
+            .apply_batch(
+                &[Op::UpdateDelete, Op::UpdateInsert, Op::Delete],
+                None,
+                &[
+                    &I32Array::from_slice(&[Some(1), Some(3), Some(1)])
+                        .unwrap()
+                        .into(),
+                    &I64Array::from_slice(&[Some(1), Some(1), Some(2)])
+                        .unwrap()
+                        .into(),
+                ],
+                epoch,
+            )
+            .await
+            .unwrap();
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        managed_state.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+        assert_eq!(
+            managed_state.get_output(epoch).await.unwrap(),
+            Some(ListValue::new(vec![Some(2.into()), Some(3.into())]).into())
+        );
+
+        // Delete the remaining rows, and nothing is left in the inner state.
+        epoch += 1;
+        managed_state
+            .apply_batch(
+                &[Op::Delete, Op::Delete],
+                None,
+                &[
+                    &I32Array::from_slice(&[Some(3), Some(2)]).unwrap().into(),
+                    &I64Array::from_slice(&[Some(1), Some(3)]).unwrap().into(),
+                ],
+                epoch,
+            )
+            .await
+            .unwrap();
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        managed_state.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+        assert_eq!(managed_state.get_output(epoch).await.unwrap(), None);
+        assert!(keyspace
+            .scan_strip_prefix(None, epoch)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
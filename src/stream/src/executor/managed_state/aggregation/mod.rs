@@ -14,6 +14,8 @@
 
 //! Aggregators with state store support
 
+pub use array_agg::*;
+pub use distinct::*;
 pub use extreme::*;
 use risingwave_common::array::stream_chunk::Ops;
 use risingwave_common::array::ArrayImpl;
@@ -25,8 +27,10 @@ use risingwave_storage::write_batch::WriteBatch;
 use risingwave_storage::{Keyspace, StateStore};
 pub use value::*;
 
-use super::super::{AggCall, PkDataTypes};
+use super::super::{AggArgs, AggCall, PkDataTypes};
 
+mod array_agg;
+mod distinct;
 mod extreme;
 mod extreme_serializer;
 mod string_agg;
@@ -51,11 +55,14 @@ pub fn verify_batch(
 /// the state with the underlying state store. Managed states can only be evicted from outer cache
 /// when they are not dirty.
 pub enum ManagedStateImpl<S: StateStore> {
-    /// States as single scalar value e.g. `COUNT`, `SUM`
+    /// States as single scalar value e.g. `COUNT`, `SUM`, `VAR_POP`
     Value(ManagedValueState<S>),
 
-    /// States as table structure e.g. `MAX`, `STRING_AGG`
+    /// States as table structure e.g. `MAX`, `STRING_AGG`, `ARRAY_AGG`
     Table(Box<dyn ManagedTableState<S>>),
+
+    /// States of `agg(DISTINCT ...)`, which deduplicate the inputs of a value or table state
+    Distinct(Box<ManagedDistinctState<S>>),
 }
 
 impl<S: StateStore> ManagedStateImpl<S> {
@@ -69,6 +76,7 @@ impl<S: StateStore> ManagedStateImpl<S> {
         match self {
             Self::Value(state) => state.apply_batch(ops, visibility, data).await,
             Self::Table(state) => state.apply_batch(ops, visibility, data, epoch).await,
+            Self::Distinct(state) => state.apply_batch(ops, visibility, data, epoch).await,
         }
     }
 
@@ -77,6 +85,7 @@ impl<S: StateStore> ManagedStateImpl<S> {
         match self {
             Self::Value(state) => state.get_output().await,
             Self::Table(state) => state.get_output(epoch).await,
+            Self::Distinct(state) => state.get_output(epoch).await,
         }
     }
 
@@ -85,6 +94,7 @@ impl<S: StateStore> ManagedStateImpl<S> {
         match self {
             Self::Value(state) => state.is_dirty(),
             Self::Table(state) => state.is_dirty(),
+            Self::Distinct(state) => state.is_dirty(),
         }
     }
 
@@ -93,6 +103,7 @@ impl<S: StateStore> ManagedStateImpl<S> {
         match self {
             Self::Value(state) => state.flush(write_batch),
             Self::Table(state) => state.flush(write_batch),
+            Self::Distinct(state) => state.flush(write_batch),
         }
     }
 
@@ -103,6 +114,57 @@ impl<S: StateStore> ManagedStateImpl<S> {
         row_count: Option<usize>,
         pk_data_types: PkDataTypes,
         is_row_count: bool,
+    ) -> Result<Self> {
+        // Duplicates make no difference to some aggregations, which don't need deduplication.
+        let needs_dedup = agg_call.distinct
+            && !matches!(
+                agg_call.kind,
+                AggKind::Min
+                    | AggKind::Max
+                    | AggKind::BoolAnd
+                    | AggKind::BoolOr
+                    | AggKind::BitAnd
+                    | AggKind::BitOr
+                    | AggKind::ApproxCountDistinct
+            );
+        if !needs_dedup {
+            return Self::create_non_distinct_managed_state(
+                agg_call,
+                keyspace,
+                row_count,
+                pk_data_types,
+                is_row_count,
+            )
+            .await;
+        }
+
+        // The inner state and the reference counts live in separate sub-keyspaces. An inner table
+        // state is keyed by the distinct value, see `ManagedDistinctState`. Its row count is the
+        // number of input rows, which is only an upper bound of the number of distinct values.
+        let value_data_types = agg_call.args.arg_types().iter().cloned().collect();
+        let inner = Self::create_non_distinct_managed_state(
+            AggCall {
+                distinct: false,
+                ..agg_call
+            },
+            keyspace.append_u8(0),
+            row_count,
+            value_data_types,
+            is_row_count,
+        )
+        .await?;
+        Ok(Self::Distinct(Box::new(ManagedDistinctState::new(
+            inner,
+            keyspace.append_u8(1),
+        ))))
+    }
+
+    async fn create_non_distinct_managed_state(
+        agg_call: AggCall,
+        keyspace: Keyspace<S>,
+        row_count: Option<usize>,
+        pk_data_types: PkDataTypes,
+        is_row_count: bool,
     ) -> Result<Self> {
         match agg_call.kind {
             AggKind::Max | AggKind::Min => {
@@ -131,9 +193,39 @@ impl<S: StateStore> ManagedStateImpl<S> {
                 )
                 .into())
             }
+            AggKind::ArrayAgg => {
+                assert!(
+                    row_count.is_some(),
+                    "should set row_count for value states other than AggKind::RowCount"
+                );
+                let data_type = match agg_call.args {
+                    AggArgs::Unary(data_type, _) => data_type,
+                    _ => panic!("array_agg should only have one arg: {:?}", agg_call),
+                };
+                Ok(Self::Table(Box::new(
+                    ManagedArrayAggState::new(
+                        keyspace,
+                        data_type,
+                        row_count.unwrap(),
+                        pk_data_types,
+                    )
+                    .await?,
+                )))
+            }
             // TODO: for append-only lists, we can create `ManagedValueState` instead of
             // `ManagedExtremeState`.
-            AggKind::Avg | AggKind::Count | AggKind::Sum => {
+            AggKind::Avg
+            | AggKind::Count
+            | AggKind::Sum
+            | AggKind::StddevSamp
+            | AggKind::StddevPop
+            | AggKind::VarSamp
+            | AggKind::VarPop
+            | AggKind::BoolAnd
+            | AggKind::BoolOr
+            | AggKind::BitAnd
+            | AggKind::BitOr
+            | AggKind::ApproxCountDistinct => {
                 assert!(
                     is_row_count || row_count.is_some(),
                     "should set row_count for value states other than AggKind::RowCount"
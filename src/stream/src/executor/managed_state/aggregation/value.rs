@@ -22,7 +22,9 @@ use risingwave_storage::storage_value::StorageValue;
 use risingwave_storage::write_batch::WriteBatch;
 use risingwave_storage::{Keyspace, StateStore};
 
-use crate::executor::{create_streaming_agg_state, AggCall, StreamingAggStateImpl};
+use crate::executor::{
+    create_streaming_agg_state, streaming_agg_state_type, AggCall, StreamingAggStateImpl,
+};
 
 /// A wrapper around [`StreamingAggStateImpl`], which fetches data from the state store and helps
 /// update the state. We don't use any trait to wrap around all `ManagedXxxState`, so as to reduce
@@ -55,7 +57,8 @@ impl<S: StateStore> ManagedValueState<S> {
             // Decode the Datum from the value.
             if let Some(raw_data) = raw_data {
                 let mut deserializer = value_encoding::Deserializer::new(raw_data);
-                Some(deserialize_cell(&mut deserializer, &agg_call.return_type)?)
+                let state_type = streaming_agg_state_type(&agg_call.kind, &agg_call.return_type);
+                Some(deserialize_cell(&mut deserializer, &state_type)?)
             } else {
                 None
             }
@@ -108,7 +111,7 @@ impl<S: StateStore> ManagedValueState<S> {
         debug_assert!(self.is_dirty());
 
         let mut local = write_batch.prefixify(&self.keyspace);
-        let v = self.state.get_output()?;
+        let v = self.state.get_state()?;
         // TODO(Yuanxin): Implement value meta
         local.put_single(StorageValue::new_default_put(serialize_cell(&v)?));
         self.is_dirty = false;
@@ -130,6 +133,7 @@ mod tests {
             kind: risingwave_expr::expr::AggKind::Count,
             args: AggArgs::Unary(DataType::Int64, 0),
             return_type: DataType::Int64,
+            distinct: false,
         }
     }
 
@@ -176,4 +180,50 @@ mod tests {
             Some(ScalarImpl::Int64(3))
         );
     }
+
+    #[tokio::test]
+    async fn test_managed_value_state_persists_state() {
+        let keyspace = create_in_memory_keyspace();
+        let agg_call = AggCall {
+            kind: risingwave_expr::expr::AggKind::VarPop,
+            args: AggArgs::Unary(DataType::Int64, 0),
+            return_type: DataType::Float64,
+            distinct: false,
+        };
+        let mut managed_state = ManagedValueState::new(agg_call.clone(), keyspace.clone(), Some(0))
+            .await
+            .unwrap();
+        managed_state
+            .apply_batch(
+                &[Op::Insert, Op::Insert, Op::Insert],
+                None,
+                &[&I64Array::from_slice(&[Some(1), Some(3), Some(5)])
+                    .unwrap()
+                    .into()],
+            )
+            .await
+            .unwrap();
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        managed_state.flush(&mut write_batch).unwrap();
+        write_batch.ingest(0).await.unwrap();
+
+        // The restored state can still retract values, which the output alone can't do.
+        let mut managed_state = ManagedValueState::new(agg_call, keyspace, Some(3))
+            .await
+            .unwrap();
+        managed_state
+            .apply_batch(
+                &[Op::Delete],
+                None,
+                &[&I64Array::from_slice(&[Some(5)]).unwrap().into()],
+            )
+            .await
+            .unwrap();
+        let mut write_batch = managed_state.keyspace.state_store().start_write_batch();
+        managed_state.flush(&mut write_batch).unwrap();
+        assert_eq!(
+            managed_state.get_output().await.unwrap(),
+            Some(ScalarImpl::Float64(1.0.into()))
+        );
+    }
 }
@@ -38,19 +38,21 @@ pub use project::*;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{ArrayImpl, ArrayRef, DataChunk, StreamChunk};
 use risingwave_common::buffer::Bitmap;
-use risingwave_common::catalog::Schema;
-use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::catalog::{Schema, TableId};
+use risingwave_common::error::{ErrorCode, Result, RwError, ToRwResult};
 use risingwave_common::types::DataType;
+use risingwave_connector::{decode_splits, encode_splits, SplitImpl};
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::barrier::Mutation as ProstMutation;
 use risingwave_pb::data::stream_message::StreamMessage;
 use risingwave_pb::data::{
-    Actors as MutationActors, AddMutation, Barrier as ProstBarrier, Epoch as ProstEpoch,
-    NothingMutation, StopMutation, StreamMessage as ProstStreamMessage, UpdateMutation,
+    Actors as MutationActors, AddMutation, Barrier as ProstBarrier, ConnectorSplits,
+    Epoch as ProstEpoch, NothingMutation, RescheduleMutation, SourceChangeSplitMutation,
+    StopMutation, StreamMessage as ProstStreamMessage, UpdateMutation,
 };
 use risingwave_pb::stream_plan;
 use risingwave_pb::stream_plan::stream_node::Node;
-use risingwave_storage::StateStore;
+use risingwave_storage::{Keyspace, StateStore};
 use smallvec::SmallVec;
 pub use source::*;
 pub use top_n::*;
@@ -99,6 +101,13 @@ pub enum Mutation {
     Stop(HashSet<ActorId>),
     UpdateOutputs(HashMap<ActorId, Vec<ActorInfo>>),
     AddOutput(HashMap<ActorId, Vec<ActorInfo>>),
+    SourceChangeSplit(HashMap<ActorId, Vec<SplitImpl>>),
+    /// Replace the actors in `stops` with the downstream actors in `adds`. The new outputs are
+    /// added before the barrier is dispatched, and the stopped ones are removed after that.
+    Reschedule {
+        adds: HashMap<ActorId, Vec<ActorInfo>>,
+        stops: HashSet<ActorId>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -140,6 +149,8 @@ pub struct Barrier {
     pub epoch: Epoch,
     pub mutation: Option<Arc<Mutation>>,
     pub span: tracing::Span,
+    /// Whether the states should be synced to the storage on this barrier.
+    pub checkpoint: bool,
 }
 
 impl Default for Barrier {
@@ -148,6 +159,7 @@ impl Default for Barrier {
             span: tracing::Span::none(),
             epoch: Epoch::default(),
             mutation: None,
+            checkpoint: false,
         }
     }
 }
@@ -177,7 +189,11 @@ impl Barrier {
     }
 
     pub fn is_to_stop_actor(&self, actor_id: ActorId) -> bool {
-        matches!(self.mutation.as_deref(), Some(Mutation::Stop(actors)) if actors.contains(&actor_id))
+        match self.mutation.as_deref() {
+            Some(Mutation::Stop(actors)) => actors.contains(&actor_id),
+            Some(Mutation::Reschedule { stops, .. }) => stops.contains(&actor_id),
+            _ => false,
+        }
     }
 
     pub fn is_to_add_output(&self, actor_id: ActorId) -> bool {
@@ -189,6 +205,18 @@ impl Barrier {
                 .any(|info| info.actor_id == actor_id)
         )
     }
+
+    /// Whether the actor is newly built to replace a rescheduled one, and this is the first
+    /// barrier it receives.
+    pub fn is_to_reschedule_output(&self, actor_id: ActorId) -> bool {
+        matches!(
+            self.mutation.as_deref(),
+            Some(Mutation::Reschedule { adds, .. }) if adds
+                .values()
+                .flatten()
+                .any(|info| info.actor_id == actor_id)
+        )
+    }
 }
 
 impl PartialEq for Barrier {
@@ -210,7 +238,10 @@ impl Mutation {
 impl Barrier {
     pub fn to_protobuf(&self) -> ProstBarrier {
         let Barrier {
-            epoch, mutation, ..
+            epoch,
+            mutation,
+            checkpoint,
+            ..
         }: Barrier = self.clone();
         ProstBarrier {
             epoch: Some(ProstEpoch {
@@ -250,8 +281,36 @@ impl Barrier {
                         })
                         .collect(),
                 })),
+                Some(Mutation::SourceChangeSplit(changes)) => {
+                    Some(ProstMutation::Splits(SourceChangeSplitMutation {
+                        actor_splits: changes
+                            .iter()
+                            .map(|(&actor_id, splits)| {
+                                let (split_type, splits) = encode_splits(splits).unwrap();
+                                (actor_id, ConnectorSplits { split_type, splits })
+                            })
+                            .collect(),
+                    }))
+                }
+                Some(Mutation::Reschedule { adds, stops }) => {
+                    Some(ProstMutation::Reschedule(RescheduleMutation {
+                        adds: adds
+                            .iter()
+                            .map(|(&id, actors)| {
+                                (
+                                    id,
+                                    MutationActors {
+                                        info: actors.clone(),
+                                    },
+                                )
+                            })
+                            .collect(),
+                        stops: stops.iter().cloned().collect(),
+                    }))
+                }
             },
             span: vec![],
+            checkpoint,
         }
     }
 
@@ -280,6 +339,26 @@ impl Barrier {
                 )
                 .into(),
             ),
+            ProstMutation::Splits(changes) => {
+                let mut actor_splits = HashMap::with_capacity(changes.actor_splits.len());
+                for (&actor_id, splits) in &changes.actor_splits {
+                    let splits =
+                        decode_splits(&splits.split_type, &splits.splits).to_rw_result()?;
+                    actor_splits.insert(actor_id, splits);
+                }
+                Some(Mutation::SourceChangeSplit(actor_splits).into())
+            }
+            ProstMutation::Reschedule(reschedule) => Some(
+                Mutation::Reschedule {
+                    adds: reschedule
+                        .adds
+                        .iter()
+                        .map(|(&id, actors)| (id, actors.get_info().clone()))
+                        .collect(),
+                    stops: HashSet::from_iter(reschedule.get_stops().clone()),
+                }
+                .into(),
+            ),
         };
         let epoch = prost.get_epoch().unwrap();
         Ok(Barrier {
@@ -290,6 +369,7 @@ impl Barrier {
             },
             epoch: Epoch::new(epoch.curr, epoch.prev),
             mutation,
+            checkpoint: prost.checkpoint,
         })
     }
 }
@@ -459,6 +539,17 @@ pub fn pk_input_array_refs<'a>(
         .collect()
 }
 
+/// Returns the keyspace of the states of an executor. The states are kept in the state table
+/// allocated by meta if `table_id` is not 0, so that they can be handed off to other actors on
+/// rescheduling. Otherwise, they are owned by the executor.
+pub fn state_keyspace<S: StateStore>(store: S, executor_id: u64, table_id: u32) -> Keyspace<S> {
+    if table_id == 0 {
+        Keyspace::executor_root(store, executor_id)
+    } else {
+        Keyspace::table_root(store, &TableId::new(table_id))
+    }
+}
+
 pub trait ExecutorBuilder {
     fn new_boxed_executor(
         executor_params: ExecutorParams,
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use prometheus::core::{AtomicU64, GenericCounterVec};
+use std::collections::HashMap;
+
+use prometheus::core::{AtomicU64, Collector, GenericCounterVec};
 use prometheus::{register_int_counter_vec_with_registry, Registry};
 
 pub struct StreamingMetrics {
@@ -47,8 +49,42 @@ impl StreamingMetrics {
         }
     }
 
+    /// Returns the number of rows processed by each actor, i.e. `actor_id` => row count.
+    pub fn actor_row_counts(&self) -> HashMap<u32, u64> {
+        self.actor_row_count
+            .collect()
+            .iter()
+            .flat_map(|family| family.get_metric())
+            .filter_map(|metric| {
+                let actor_id = metric
+                    .get_label()
+                    .iter()
+                    .find(|label| label.get_name() == "actor_id")?
+                    .get_value()
+                    .parse()
+                    .ok()?;
+                Some((actor_id, metric.get_counter().get_value() as u64))
+            })
+            .collect()
+    }
+
     /// Create a new `StreamingMetrics` instance used in tests or other places.
     pub fn unused() -> Self {
         Self::new(prometheus::Registry::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actor_row_counts() {
+        let metrics = StreamingMetrics::unused();
+        metrics.actor_row_count.with_label_values(&["1"]).inc_by(3);
+        metrics.actor_row_count.with_label_values(&["2"]).inc_by(5);
+        metrics.actor_row_count.with_label_values(&["1"]).inc_by(4);
+
+        assert_eq!(metrics.actor_row_counts(), HashMap::from([(1, 7), (2, 5)]));
+    }
+}
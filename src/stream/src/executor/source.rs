@@ -28,17 +28,19 @@ use risingwave_common::catalog::{ColumnId, Field, Schema, TableId};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError, ToRwResult};
 use risingwave_common::try_match_expand;
-use risingwave_connector::{state, SplitImpl};
+use risingwave_connector::{decode_splits, state, SplitImpl};
 use risingwave_pb::stream_plan;
 use risingwave_pb::stream_plan::stream_node::Node;
-use risingwave_source::connector_source::ConnectorStreamSource;
+use risingwave_source::connector_source::SplitOffsets;
 use risingwave_source::*;
 use risingwave_storage::{Keyspace, StateStore};
-use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
 use crate::executor::monitor::StreamingMetrics;
-use crate::executor::{Executor, ExecutorBuilder, Message, PkIndices, PkIndicesRef};
-use crate::task::{ExecutorParams, LocalStreamManagerCore};
+use crate::executor::{
+    Barrier, Executor, ExecutorBuilder, Message, Mutation, PkIndices, PkIndicesRef,
+};
+use crate::task::{ActorId, ExecutorParams, LocalStreamManagerCore};
 
 struct SourceReader {
     /// the future that builds stream_reader. It is required because source should not establish
@@ -48,17 +50,21 @@ struct SourceReader {
     pub stream_reader: Option<Box<dyn StreamSourceReader>>,
     /// The reader for barrier
     pub barrier_receiver: UnboundedReceiver<Message>,
+    /// The receiver of the readers rebuilt on split changes, which replace `stream_reader`.
+    pub reader_receiver: UnboundedReceiver<Box<dyn StreamSourceReader>>,
 }
 
 /// `SourceReader` will be turned into this stream type.
 type ReaderStream =
     Pin<Box<dyn Stream<Item = Either<Result<Message>, Result<StreamChunk>>> + Send>>;
-type StreamReaderFuture =
-    Pin<Box<dyn Future<Output = Result<Box<dyn StreamSourceReader>>> + Send + Sync>>;
+type StreamReaderFuture = Pin<Box<dyn Future<Output = Result<Box<dyn StreamSourceReader>>> + Send>>;
+/// Builds the future of a stream reader reading the given splits.
+type StreamReaderBuilder = Box<dyn Fn(Vec<SplitImpl>) -> StreamReaderFuture + Send + Sync>;
 
 /// [`SourceExecutor`] is a streaming source, from risingwave's batch table, or external systems
 /// such as Kafka.
 pub struct SourceExecutor {
+    actor_id: ActorId,
     source_id: TableId,
     source_desc: SourceDesc,
     column_ids: Vec<ColumnId>,
@@ -85,10 +91,18 @@ pub struct SourceExecutor {
     // monitor
     metrics: Arc<StreamingMetrics>,
 
-    /// Split info for stream source
-    #[allow(dead_code)]
+    /// Split info for stream source, which is reassigned by `SourceChangeSplit` mutations.
     stream_source_splits: Vec<SplitImpl>,
 
+    /// The offsets read from the splits, with which the reader is rebuilt on split changes.
+    split_offsets: SplitOffsets,
+
+    /// Builds the reader of the splits.
+    reader_builder: StreamReaderBuilder,
+
+    /// Sends the reader rebuilt on split changes to `reader_stream`.
+    reader_sender: UnboundedSender<Box<dyn StreamSourceReader>>,
+
     source_identify: String,
 }
 
@@ -112,12 +126,9 @@ impl ExecutorBuilder for SourceExecutorBuilder {
         let source_desc = params.env.source_manager().get_source(&source_id)?;
 
         let stream_source_splits = match &node.stream_source_state {
-            Some(splits) => splits
-                .stream_source_splits
-                .iter()
-                .map(|split| SplitImpl::restore_from_bytes(splits.get_split_type().clone(), split))
-                .collect::<anyhow::Result<Vec<SplitImpl>>>()
-                .to_rw_result(),
+            Some(splits) => {
+                decode_splits(splits.get_split_type(), &splits.stream_source_splits).to_rw_result()
+            }
             _ => Ok(vec![]),
         }?;
 
@@ -149,6 +160,7 @@ impl ExecutorBuilder for SourceExecutorBuilder {
             schema,
             params.pk_indices,
             barrier_receiver,
+            params.actor_id,
             params.executor_id,
             params.operator_id,
             params.op_info,
@@ -163,6 +175,8 @@ async fn build_stream_reader<S: StateStore>(
     operator_id: u64,
     column_ids: Vec<ColumnId>,
     keyspace: Keyspace<S>,
+    splits: Vec<SplitImpl>,
+    split_offsets: SplitOffsets,
 ) -> Result<Box<dyn StreamSourceReader>> {
     let stream_reader: Box<dyn StreamSourceReader> = match source.as_ref() {
         SourceImpl::HighLevelKafka(s) => Box::new(s.stream_reader(
@@ -173,10 +187,14 @@ async fn build_stream_reader<S: StateStore>(
             column_ids,
         )?),
         SourceImpl::TableV2(s) => Box::new(s.stream_reader(TableV2ReaderContext, column_ids)?),
-        SourceImpl::Connector(s) => Box::new(ConnectorStreamSource {
-            source_reader: s.clone(),
-            state_store: state::SourceStateHandler::new(keyspace),
-        }),
+        SourceImpl::Connector(s) => Box::new(
+            s.stream_reader(
+                splits,
+                split_offsets,
+                state::SourceStateHandler::new(keyspace),
+            )
+            .await?,
+        ),
     };
 
     Ok(stream_reader)
@@ -192,21 +210,34 @@ impl SourceExecutor {
         schema: Schema,
         pk_indices: PkIndices,
         barrier_receiver: UnboundedReceiver<Message>,
+        actor_id: ActorId,
         executor_id: u64,
         operator_id: u64,
         op_info: String,
         streaming_metrics: Arc<StreamingMetrics>,
         stream_source_splits: Vec<SplitImpl>,
     ) -> Result<Self> {
-        let source = source_desc.clone().source;
-        let stream_reader_future: StreamReaderFuture = Box::pin(build_stream_reader(
-            source,
-            operator_id,
-            column_ids.clone(),
-            keyspace,
-        ));
+        let split_offsets = SplitOffsets::default();
+        let reader_builder: StreamReaderBuilder = {
+            let source = source_desc.clone().source;
+            let column_ids = column_ids.clone();
+            let split_offsets = split_offsets.clone();
+            Box::new(move |splits| {
+                Box::pin(build_stream_reader(
+                    source.clone(),
+                    operator_id,
+                    column_ids.clone(),
+                    keyspace.clone(),
+                    splits,
+                    split_offsets.clone(),
+                ))
+            })
+        };
+        let stream_reader_future = reader_builder(stream_source_splits.clone());
+        let (reader_sender, reader_receiver) = unbounded_channel();
 
         Ok(Self {
+            actor_id,
             source_id,
             source_desc,
             column_ids,
@@ -216,6 +247,7 @@ impl SourceExecutor {
                 stream_reader_future: Some(stream_reader_future),
                 stream_reader: None,
                 barrier_receiver,
+                reader_receiver,
             }),
             next_row_id: AtomicU64::from(0u64),
             identity: format!("SourceExecutor {:X}", executor_id),
@@ -223,6 +255,9 @@ impl SourceExecutor {
             reader_stream: None,
             metrics: streaming_metrics,
             stream_source_splits,
+            split_offsets,
+            reader_builder,
+            reader_sender,
             source_identify: "Table_".to_string() + &source_id.table_id().to_string(),
         })
     }
@@ -239,6 +274,44 @@ impl SourceExecutor {
         Column::new(Arc::new(ArrayImpl::from(builder.finish().unwrap())))
     }
 
+    /// Updates the splits of this actor if they're reassigned by the barrier, and rebuilds the
+    /// reader of a connector source to read them. The splits kept by this actor continue from the
+    /// offsets already read.
+    async fn apply_split_change(&mut self, barrier: &Barrier) -> Result<()> {
+        if let Some(Mutation::SourceChangeSplit(actor_splits)) = barrier.mutation.as_deref() {
+            if let Some(splits) = actor_splits.get(&self.actor_id) {
+                tracing::info!(
+                    actor_id = self.actor_id,
+                    "source splits changed to {:?}",
+                    splits
+                );
+                let mut splits = splits.clone();
+
+                if matches!(self.source_desc.source.as_ref(), SourceImpl::Connector(_)) {
+                    {
+                        let mut split_offsets = self.split_offsets.lock();
+                        split_offsets
+                            .retain(|split_id, _| splits.iter().any(|s| &s.id() == split_id));
+                        for split in &mut splits {
+                            if let Some(offset) = split_offsets.get(&split.id()) {
+                                split.update_offset(offset).to_rw_result()?;
+                            }
+                        }
+                    }
+
+                    let mut stream_reader = (self.reader_builder)(splits.clone()).await?;
+                    stream_reader.open().await?;
+                    self.reader_sender.send(stream_reader).map_err(|_| {
+                        RwError::from(InternalError("source reader stream closed".to_string()))
+                    })?;
+                }
+
+                self.stream_source_splits = splits;
+            }
+        }
+        Ok(())
+    }
+
     fn refill_row_id_column(&mut self, chunk: StreamChunk) -> StreamChunk {
         if let Some(row_id_index) = self.source_desc.row_id_index {
             let row_id_column_id = self.source_desc.columns[row_id_index as usize].column_id;
@@ -259,19 +332,33 @@ impl SourceExecutor {
 
 impl SourceReader {
     #[try_stream(ok = StreamChunk, error = RwError)]
-    async fn stream_reader(mut stream_reader: Box<dyn StreamSourceReader>) {
+    async fn stream_reader(
+        mut stream_reader: Box<dyn StreamSourceReader>,
+        mut reader_receiver: UnboundedReceiver<Box<dyn StreamSourceReader>>,
+    ) {
         loop {
-            match stream_reader.next().await {
-                Err(e) => {
+            // Switch to the new reader once the splits are changed.
+            let chunk = tokio::select! {
+                biased;
+                Some(new_reader) = reader_receiver.recv() => Either::Left(new_reader),
+                chunk = stream_reader.next() => Either::Right(chunk),
+            };
+
+            match chunk {
+                Either::Left(new_reader) => stream_reader = new_reader,
+                Either::Right(Err(e)) => {
                     // TODO: report this error to meta service to mark the actors failed.
                     error!("hang up stream reader due to polling error: {}", e);
 
                     // Drop the reader, then the error might be caught by the writer side.
                     drop(stream_reader);
-                    // Then hang up this stream by breaking the loop.
-                    break;
+                    // Then hang up this stream until the splits are changed.
+                    match reader_receiver.recv().await {
+                        Some(new_reader) => stream_reader = new_reader,
+                        None => break,
+                    }
                 }
-                Ok(chunk) => yield chunk,
+                Either::Right(Ok(chunk)) => yield chunk,
             }
         }
 
@@ -293,7 +380,7 @@ impl SourceReader {
     }
 
     pub fn into_stream(self) -> impl Stream<Item = Either<Result<Message>, Result<StreamChunk>>> {
-        let stream_reader = Self::stream_reader(self.stream_reader.unwrap());
+        let stream_reader = Self::stream_reader(self.stream_reader.unwrap(), self.reader_receiver);
         let barrier_receiver = Self::barrier_receiver(self.barrier_receiver);
         select_with_strategy(
             barrier_receiver.map(Either::Left),
@@ -316,7 +403,13 @@ impl Executor for SourceExecutor {
 
         match self.reader_stream.as_mut().unwrap().next().await {
             // This branch will be preferred.
-            Some(Either::Left(message)) => message,
+            Some(Either::Left(message)) => {
+                let message = message?;
+                if let Message::Barrier(barrier) = &message {
+                    self.apply_split_change(barrier).await?;
+                }
+                Ok(message)
+            }
 
             // If there's barrier, this branch will be deferred.
             Some(Either::Right(chunk)) => {
@@ -371,15 +464,22 @@ impl Debug for SourceExecutor {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
     use std::sync::Arc;
 
+    use bytes::Bytes;
     use itertools::Itertools;
     use risingwave_common::array::column::Column;
-    use risingwave_common::array::{ArrayImpl, I32Array, I64Array, Op, StreamChunk, Utf8Array};
+    use risingwave_common::array::{
+        Array, ArrayImpl, I32Array, I64Array, Op, StreamChunk, Utf8Array,
+    };
     use risingwave_common::array_nonnull;
     use risingwave_common::catalog::{ColumnDesc, Field, Schema};
     use risingwave_common::types::DataType;
+    use risingwave_connector::base::{InnerMessage, SourceReader};
+    use risingwave_connector::kinesis::split::{KinesisOffset, KinesisSplit};
+    use risingwave_connector::ConnectorState;
+    use risingwave_source::connector_source::{ConnectorSource, SplitReaderBuilder};
     use risingwave_source::*;
     use risingwave_storage::memory::MemoryStateStore;
     use tokio::sync::mpsc::unbounded_channel;
@@ -473,6 +573,7 @@ mod tests {
             barrier_receiver,
             1,
             1,
+            1,
             "SourceExecutor".to_string(),
             Arc::new(StreamingMetrics::new(prometheus::Registry::new())),
             vec![],
@@ -611,6 +712,7 @@ mod tests {
             barrier_receiver,
             1,
             1,
+            1,
             "SourceExecutor".to_string(),
             Arc::new(StreamingMetrics::unused()),
             vec![],
@@ -639,4 +741,202 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_source_change_split() {
+        let table_id = TableId::default();
+        let table_columns = vec![ColumnDesc {
+            column_id: ColumnId::from(0),
+            data_type: DataType::Int64,
+            name: String::new(),
+            field_descs: vec![],
+            type_name: "".to_string(),
+        }];
+        let source_manager = MemSourceManager::new();
+        source_manager
+            .create_table_source_v2(&table_id, table_columns)
+            .unwrap();
+        let source_desc = source_manager.get_source(&table_id).unwrap();
+
+        let (barrier_sender, barrier_receiver) = unbounded_channel();
+        let keyspace = Keyspace::executor_root(MemoryStateStore::new(), 0x2333);
+        let mut source_executor = SourceExecutor::new(
+            table_id,
+            source_desc,
+            keyspace,
+            vec![ColumnId::from(0)],
+            Schema::new(vec![Field::unnamed(DataType::Int64)]),
+            vec![0],
+            barrier_receiver,
+            1,
+            1,
+            1,
+            "SourceExecutor".to_string(),
+            Arc::new(StreamingMetrics::unused()),
+            vec![],
+        )
+        .unwrap();
+
+        let new_split = |shard_id: &str| {
+            SplitImpl::Kinesis(KinesisSplit::new(
+                shard_id.to_string(),
+                KinesisOffset::Earliest,
+                KinesisOffset::None,
+            ))
+        };
+        let barrier = Barrier::new_test_barrier(1).with_mutation(Mutation::SourceChangeSplit(
+            [
+                (1, vec![new_split("shard-0"), new_split("shard-1")]),
+                (2, vec![new_split("shard-2")]),
+            ]
+            .into_iter()
+            .collect(),
+        ));
+        // The mutation survives the protobuf round trip to compute nodes.
+        assert_eq!(
+            Barrier::from_protobuf(&barrier.to_protobuf()).unwrap(),
+            barrier
+        );
+
+        barrier_sender
+            .send(Message::Barrier(barrier.clone()))
+            .unwrap();
+        let message = source_executor.next().await.unwrap();
+        assert_eq!(message.as_barrier().unwrap(), &barrier);
+        assert_eq!(
+            source_executor.stream_source_splits,
+            vec![new_split("shard-0"), new_split("shard-1")]
+        );
+    }
+
+    /// Reads one message `{"v": <n>}` from the split `shard-<n>`, unless it resumes from an offset.
+    struct MockSplitReader {
+        message: Option<InnerMessage>,
+    }
+
+    #[async_trait]
+    impl SourceReader for MockSplitReader {
+        async fn next(&mut self) -> anyhow::Result<Option<Vec<InnerMessage>>> {
+            match self.message.take() {
+                Some(message) => Ok(Some(vec![message])),
+                None => futures::future::pending().await,
+            }
+        }
+
+        async fn new(
+            _config: HashMap<String, String>,
+            state: Option<ConnectorState>,
+        ) -> anyhow::Result<Self> {
+            let message = state
+                .filter(|state| state.start_offset.is_empty())
+                .map(|state| {
+                    let split_id = String::from_utf8(state.identifier.to_vec()).unwrap();
+                    let v = split_id.strip_prefix("shard-").unwrap();
+                    InnerMessage {
+                        payload: Some(Bytes::from(format!("{{\"v\": {}}}", v))),
+                        offset: "0".to_string(),
+                        split_id,
+                    }
+                });
+            Ok(Self { message })
+        }
+    }
+
+    struct MockSplitReaderBuilder;
+
+    #[async_trait]
+    impl SplitReaderBuilder for MockSplitReaderBuilder {
+        async fn build(
+            &self,
+            state: Option<ConnectorState>,
+        ) -> anyhow::Result<Box<dyn SourceReader + Send + Sync>> {
+            Ok(Box::new(MockSplitReader::new(HashMap::new(), state).await?))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connector_source_change_split() {
+        let table_id = TableId::default();
+        let columns = vec![SourceColumnDesc {
+            name: "v".to_string(),
+            data_type: DataType::Int64,
+            column_id: ColumnId::from(0),
+            skip_parse: false,
+            fields: vec![],
+        }];
+        let source = ConnectorSource::new(
+            Arc::new(JSONParser {}),
+            Arc::new(MockSplitReaderBuilder),
+            columns.clone(),
+        );
+        let source_desc = SourceDesc {
+            source: Arc::new(SourceImpl::Connector(source)),
+            format: SourceFormat::Json,
+            columns,
+            row_id_index: None,
+        };
+
+        let new_split = |shard_id: &str| {
+            SplitImpl::Kinesis(KinesisSplit::new(
+                shard_id.to_string(),
+                KinesisOffset::Earliest,
+                KinesisOffset::None,
+            ))
+        };
+        let (barrier_sender, barrier_receiver) = unbounded_channel();
+        let keyspace = Keyspace::executor_root(MemoryStateStore::new(), 0x2333);
+        let mut source_executor = SourceExecutor::new(
+            table_id,
+            source_desc,
+            keyspace,
+            vec![ColumnId::from(0)],
+            Schema::new(vec![Field::unnamed(DataType::Int64)]),
+            vec![0],
+            barrier_receiver,
+            1,
+            1,
+            1,
+            "SourceExecutor".to_string(),
+            Arc::new(StreamingMetrics::unused()),
+            vec![new_split("shard-0")],
+        )
+        .unwrap();
+
+        let read_value = |message: Message| {
+            let chunk = message.into_chunk().unwrap();
+            chunk.column_at(0).array_ref().as_int64().value_at(0)
+        };
+
+        barrier_sender
+            .send(Message::Barrier(Barrier::new_test_barrier(1)))
+            .unwrap();
+        source_executor.next().await.unwrap().as_barrier().unwrap();
+        assert_eq!(read_value(source_executor.next().await.unwrap()), Some(0));
+
+        // Add `shard-1` to the actor at runtime.
+        let barrier = Barrier::new_test_barrier(2).with_mutation(Mutation::SourceChangeSplit(
+            [(1, vec![new_split("shard-0"), new_split("shard-1")])]
+                .into_iter()
+                .collect(),
+        ));
+        barrier_sender
+            .send(Message::Barrier(barrier.clone()))
+            .unwrap();
+        let message = source_executor.next().await.unwrap();
+        assert_eq!(message.as_barrier().unwrap(), &barrier);
+
+        // `shard-0` continues from the offset read, so only the message of `shard-1` is read.
+        assert_eq!(read_value(source_executor.next().await.unwrap()), Some(1));
+        assert_eq!(
+            source_executor.stream_source_splits,
+            vec![
+                SplitImpl::Kinesis(KinesisSplit::new(
+                    "shard-0".to_string(),
+                    KinesisOffset::SequenceNumber("0".to_string()),
+                    KinesisOffset::None,
+                )),
+                new_split("shard-1"),
+            ]
+        );
+    }
 }
@@ -18,9 +18,9 @@ use risingwave_common::util::sort_util::OrderType;
 use risingwave_pb::plan::OrderType as ProstOrderType;
 use risingwave_pb::stream_plan;
 use risingwave_pb::stream_plan::stream_node::Node;
-use risingwave_storage::{Keyspace, StateStore};
+use risingwave_storage::StateStore;
 
-use crate::executor::{Executor, ExecutorBuilder};
+use crate::executor::{state_keyspace, Executor, ExecutorBuilder};
 use crate::executor_v2::{Executor as ExecutorV2, TopNExecutor as TopNExecutorV2};
 use crate::task::{ExecutorParams, LocalStreamManagerCore};
 
@@ -48,7 +48,10 @@ impl ExecutorBuilder for TopNExecutorBuilder {
         };
         let cache_size = Some(1024);
         let total_count = (0, 0, 0);
-        let keyspace = Keyspace::executor_root(store, params.executor_id);
+        let keyspace = state_keyspace(store, params.executor_id, node.table_id);
+        if let Some(ttl) = params.state_ttl {
+            keyspace.set_ttl(ttl);
+        }
         let key_indices = node
             .get_distribution_keys()
             .iter()
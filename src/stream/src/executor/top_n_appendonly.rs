@@ -51,6 +51,9 @@ impl ExecutorBuilder for AppendOnlyTopNExecutorBuilder {
         let cache_size = Some(1024);
         let total_count = (0, 0);
         let keyspace = Keyspace::executor_root(store, params.executor_id);
+        if let Some(ttl) = params.state_ttl {
+            keyspace.set_ttl(ttl);
+        }
         let key_indices = node
             .get_distribution_keys()
             .iter()
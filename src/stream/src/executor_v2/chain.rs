@@ -16,6 +16,7 @@ use futures::StreamExt;
 use futures_async_stream::try_stream;
 use risingwave_common::array::StreamChunk;
 use risingwave_common::catalog::Schema;
+use risingwave_storage::StateStore;
 
 use super::error::TracedStreamExecutorError;
 use super::{BoxedExecutor, Executor, ExecutorInfo, Message};
@@ -25,7 +26,7 @@ use crate::task::{ActorId, FinishCreateMviewNotifier};
 /// newly appended executors. Currently, [`ChainExecutor`] is mainly used to implement MV on MV
 /// feature. It pipes new data of existing MVs to newly created MV only all of the old data in the
 /// existing MVs are dispatched.
-pub struct ChainExecutor {
+pub struct ChainExecutor<S: StateStore> {
     snapshot: BoxedExecutor,
 
     upstream: BoxedExecutor,
@@ -36,6 +37,9 @@ pub struct ChainExecutor {
 
     actor_id: ActorId,
 
+    /// Used to wait for the states of the rescheduled actors to be committed.
+    state_store: S,
+
     info: ExecutorInfo,
 }
 
@@ -53,13 +57,14 @@ fn mapping(upstream_indices: &[usize], msg: Message) -> Message {
     }
 }
 
-impl ChainExecutor {
+impl<S: StateStore> ChainExecutor<S> {
     pub fn new(
         snapshot: BoxedExecutor,
         upstream: BoxedExecutor,
         upstream_indices: Vec<usize>,
         notifier: FinishCreateMviewNotifier,
         actor_id: ActorId,
+        state_store: S,
         info: ExecutorInfo,
     ) -> Self {
         Self {
@@ -68,6 +73,7 @@ impl ChainExecutor {
             upstream_indices,
             notifier,
             actor_id,
+            state_store,
             info,
         }
     }
@@ -88,9 +94,17 @@ impl ChainExecutor {
         // Otherwise, it means we've recovered and the snapshot is already consumed.
         let to_consume_snapshot = barrier.is_to_add_output(self.actor_id);
 
+        // If this actor replaces a rescheduled one, the states flushed by the old actors on this
+        // barrier may be on other nodes. Wait for them to be committed before processing any data.
+        let to_wait_committed = barrier.is_to_reschedule_output(self.actor_id);
+
         // The first barrier message should be propagated.
         yield first_msg;
 
+        if to_wait_committed {
+            self.state_store.wait_epoch(epoch.prev).await?;
+        }
+
         // 2. Consume the snapshot if needed. Note that the snapshot is already projected, so
         // there's no mapping required.
         if to_consume_snapshot {
@@ -114,7 +128,7 @@ impl ChainExecutor {
     }
 }
 
-impl Executor for ChainExecutor {
+impl<S: StateStore> Executor for ChainExecutor<S> {
     fn execute(self: Box<Self>) -> super::BoxedMessageStream {
         self.execute_inner().boxed()
     }
@@ -141,6 +155,7 @@ mod test {
     use risingwave_common::catalog::{Field, Schema};
     use risingwave_common::column_nonnull;
     use risingwave_common::types::DataType;
+    use risingwave_storage::memory::MemoryStateStore;
 
     use super::ChainExecutor;
     use crate::executor::{Barrier, Message, PkIndices};
@@ -201,6 +216,7 @@ mod test {
             vec![0],
             notifier,
             0,
+            MemoryStateStore::new(),
             ExecutorInfo {
                 schema,
                 pk_indices: Vec::new(),
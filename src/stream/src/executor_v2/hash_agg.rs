@@ -458,6 +458,11 @@ mod tests {
         test_local_hash_aggregation_max(create_in_memory_keyspace()).await
     }
 
+    #[tokio::test]
+    async fn test_local_hash_aggregation_approx_count_distinct_in_memory() {
+        test_local_hash_aggregation_approx_count_distinct(create_in_memory_keyspace()).await
+    }
+
     async fn test_local_hash_aggregation_count(keyspace: Keyspace<impl StateStore>) {
         let chunk1 = StreamChunk::new(
             vec![Op::Insert, Op::Insert, Op::Insert],
@@ -486,16 +491,19 @@ mod tests {
                 kind: AggKind::RowCount,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Count,
                 args: AggArgs::Unary(DataType::Int64, 0),
                 return_type: DataType::Int64,
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Count,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                distinct: false,
             },
         ];
 
@@ -595,17 +603,20 @@ mod tests {
                 kind: AggKind::RowCount,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 1),
                 return_type: DataType::Int64,
+                distinct: false,
             },
             // This is local hash aggregation, so we add another sum state
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 2),
                 return_type: DataType::Int64,
+                distinct: false,
             },
         ];
 
@@ -719,11 +730,13 @@ mod tests {
                 kind: AggKind::RowCount,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Min,
                 args: AggArgs::Unary(DataType::Int64, 1),
                 return_type: DataType::Int64,
+                distinct: false,
             },
         ];
 
@@ -785,4 +798,123 @@ mod tests {
             unreachable!("unexpected message {:?}", msg);
         }
     }
+
+    async fn test_local_hash_aggregation_approx_count_distinct(
+        keyspace: Keyspace<impl StateStore>,
+    ) {
+        let chunk1 = StreamChunk::new(
+            vec![Op::Insert; 5],
+            vec![
+                // group key column
+                column_nonnull! { I64Array, [1, 1, 1, 1, 2] },
+                // data column to count distinct
+                column_nonnull! { I64Array, [1, 2, 2, 3, 4] },
+                // primary key column
+                column_nonnull! { I64Array, [1001, 1002, 1003, 1004, 1005] },
+            ],
+            None,
+        );
+        let chunk2 = StreamChunk::new(
+            vec![Op::Delete; 3],
+            vec![
+                // group key column
+                column_nonnull! { I64Array, [1, 1, 2] },
+                // data column to count distinct
+                column_nonnull! { I64Array, [2, 3, 4] },
+                // primary key column
+                column_nonnull! { I64Array, [1002, 1004, 1005] },
+            ],
+            None,
+        );
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+                // primary key column
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let mut source = MockSource::new(schema, vec![2]); // pk
+        source.push_barrier(1, false);
+        source.push_chunks([chunk1].into_iter());
+        source.push_barrier(2, false);
+        source.push_chunks([chunk2].into_iter());
+        source.push_barrier(3, false);
+
+        // This is local hash aggregation, so we add another row count state
+        let keys = vec![0];
+        let agg_calls = vec![
+            AggCall {
+                kind: AggKind::RowCount,
+                args: AggArgs::None,
+                return_type: DataType::Int64,
+                distinct: false,
+            },
+            AggCall {
+                kind: AggKind::ApproxCountDistinct,
+                args: AggArgs::Unary(DataType::Int64, 1),
+                return_type: DataType::Int64,
+                distinct: false,
+            },
+        ];
+
+        let hash_agg =
+            new_boxed_hash_agg_executor(Box::new(source), agg_calls, keys, keyspace, vec![], 1);
+        let mut hash_agg = hash_agg.execute();
+
+        // Consume the init barrier
+        hash_agg.next().await.unwrap().unwrap();
+        // Consume stream chunk
+        let msg = hash_agg.next().await.unwrap().unwrap();
+        if let Message::Chunk(chunk) = msg {
+            let (data_chunk, ops) = chunk.into_parts();
+            let rows = ops
+                .into_iter()
+                .zip_eq(data_chunk.rows().map(Row::from))
+                .sorted()
+                .collect_vec();
+
+            let expected_rows = [
+                // group key, row count, approx count distinct data
+                (Op::Insert, row_nonnull![1i64, 4i64, 3i64]),
+                (Op::Insert, row_nonnull![2i64, 1i64, 1i64]),
+            ]
+            .into_iter()
+            .sorted()
+            .collect_vec();
+
+            assert_eq!(rows, expected_rows);
+        } else {
+            unreachable!("unexpected message {:?}", msg);
+        }
+
+        assert_matches!(
+            hash_agg.next().await.unwrap().unwrap(),
+            Message::Barrier { .. }
+        );
+
+        // Retracting one of the two 2s in group 1 keeps it counted.
+        let msg = hash_agg.next().await.unwrap().unwrap();
+        if let Message::Chunk(chunk) = msg {
+            let (data_chunk, ops) = chunk.into_parts();
+            let rows = ops
+                .into_iter()
+                .zip_eq(data_chunk.rows().map(Row::from))
+                .sorted()
+                .collect_vec();
+            let expected_rows = [
+                // group key, row count, approx count distinct data
+                (Op::Delete, row_nonnull![2i64, 1i64, 1i64]),
+                (Op::UpdateDelete, row_nonnull![1i64, 4i64, 3i64]),
+                (Op::UpdateInsert, row_nonnull![1i64, 2i64, 2i64]),
+            ]
+            .into_iter()
+            .sorted()
+            .collect_vec();
+
+            assert_eq!(rows, expected_rows);
+        } else {
+            unreachable!("unexpected message {:?}", msg);
+        }
+    }
 }
@@ -19,7 +19,7 @@ use itertools::Itertools;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{Op, StreamChunk};
 use risingwave_common::catalog::Schema;
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
 
 use super::{Executor, ExecutorInfo, StreamExecutorResult};
 use crate::executor::{create_streaming_agg_state, AggCall, PkIndicesRef, StreamingAggStateImpl};
@@ -79,6 +79,14 @@ impl AggLocalSimpleAggExecutor {
         schema: Schema,
         executor_id: u64,
     ) -> Result<Self> {
+        // Partial aggregation can't deduplicate inputs across parallel units.
+        if agg_calls.iter().any(|agg_call| agg_call.distinct) {
+            return Err(ErrorCode::NotImplemented(
+                "distinct aggregation in local simple agg".to_string(),
+                None.into(),
+            )
+            .into());
+        }
         // simple agg does not have group key
         let states: Vec<_> = agg_calls
             .iter()
@@ -198,6 +206,7 @@ mod tests {
             kind: AggKind::RowCount,
             args: AggArgs::None,
             return_type: DataType::Int64,
+            distinct: false,
         }];
 
         let simple_agg = Box::new(LocalSimpleAggExecutor::new(
@@ -261,16 +270,19 @@ mod tests {
                 kind: AggKind::RowCount,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 0),
                 return_type: DataType::Int64,
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 1),
                 return_type: DataType::Int64,
+                distinct: false,
             },
         ];
 
@@ -27,7 +27,7 @@ use tracing_futures::Instrument;
 
 use super::{Executor, Message, PkIndicesRef};
 use crate::executor::PkIndices;
-use crate::executor_v2::error::TracedStreamExecutorError;
+use crate::executor_v2::error::{StreamExecutorError, TracedStreamExecutorError};
 use crate::executor_v2::{BoxedMessageStream, ExecutorInfo};
 use crate::task::UpDownActorIds;
 
@@ -165,10 +165,13 @@ impl MergeExecutor {
                     .instrument(tracing::trace_span!("idle"))
                     .await;
 
-                // Panic on channel close.
-                let message = message.expect(
-                    "upstream channel closed unexpectedly, please check error in upstream executors"
-                );
+                // Fail on channel close, so that the actor is reported as failed.
+                let message = message.ok_or_else(|| {
+                    StreamExecutorError::channel_closed(format!(
+                        "upstream of merge executor in actor {}",
+                        self.actor_id
+                    ))
+                })?;
                 // Put back the remainings.
                 active = remainings;
 
@@ -176,11 +176,12 @@ impl ProjectExecutor {
     }
 }
 
-impl ChainExecutor {
+impl<S: StateStore> ChainExecutor<S> {
     pub fn new_from_v1(
         snapshot: Box<dyn ExecutorV1>,
         mview: Box<dyn ExecutorV1>,
         notifier: FinishCreateMviewNotifier,
+        state_store: S,
         schema: Schema,
         column_idxs: Vec<usize>,
         _op_info: String,
@@ -199,6 +200,7 @@ impl ChainExecutor {
             column_idxs,
             notifier,
             actor_id,
+            state_store,
             info,
         )
     }
@@ -15,7 +15,7 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use risingwave_common::error::Result;
+use risingwave_common::error::{Result, RwError};
 use risingwave_pb::stream_service::inject_barrier_response::FinishedCreateMview as ProstFinishedCreateMview;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
@@ -79,6 +79,27 @@ impl std::fmt::Debug for FinishCreateMviewNotifier {
 pub struct CollectResult {
     /// Finished Create MV DDLs in current epoch.
     pub finished_create_mviews: Vec<FinishedCreateMview>,
+
+    /// Actors that exited with an error on this node and haven't been dropped yet.
+    pub failed_actors: Vec<ActorId>,
+}
+
+/// The collection status of barriers on current compute node, for diagnosing barriers that are not
+/// collected for a long time.
+#[derive(Debug, Default)]
+pub struct BarrierCollectionStatus {
+    /// The epoch of the barrier being collected, if any.
+    pub epoch: Option<u64>,
+
+    /// Actors that have collected the barrier.
+    pub collected_actors: Vec<ActorId>,
+
+    /// Actors that haven't collected the barrier. Only known after the barrier is injected by the
+    /// meta service.
+    pub remaining_actors: Vec<ActorId>,
+
+    /// The epoch of the last barrier collected from each actor.
+    pub last_collected_epochs: HashMap<ActorId, u64>,
 }
 
 enum BarrierState {
@@ -181,15 +202,21 @@ impl LocalBarrierManager {
                 .senders
                 .get(&actor_id)
                 .unwrap_or_else(|| panic!("sender for actor {} does not exist", actor_id));
-            sender.send(Message::Barrier(barrier.clone())).unwrap();
+            // The receiver is gone if the actor has failed, which will be reported on collection.
+            if sender.send(Message::Barrier(barrier.clone())).is_err() {
+                warn!("failed to send barrier to actor {}", actor_id);
+            }
         }
 
         // Actors to stop should still accept this barrier, but won't get sent to in next times.
-        if let Some(Mutation::Stop(actors)) = barrier.mutation.as_deref() {
-            trace!("remove actors {:?} from senders", actors);
-            for actor in actors {
-                self.senders.remove(actor);
+        match barrier.mutation.as_deref() {
+            Some(Mutation::Stop(actors)) | Some(Mutation::Reschedule { stops: actors, .. }) => {
+                trace!("remove actors {:?} from senders", actors);
+                for actor in actors {
+                    self.senders.remove(actor);
+                }
             }
+            _ => {}
         }
 
         Ok(rx)
@@ -210,6 +237,42 @@ impl LocalBarrierManager {
         Ok(())
     }
 
+    /// When an actor exits with an error, it should report the failure using this function, so
+    /// that barriers won't wait for it and the meta service can recover the streaming job it
+    /// belongs to.
+    pub fn notify_failure(&mut self, actor_id: ActorId, err: &RwError) {
+        error!("actor {} failed: {}", actor_id, err);
+
+        match &mut self.state {
+            #[cfg(test)]
+            BarrierState::Local => {}
+
+            BarrierState::Managed(managed_state) => managed_state.notify_failure(actor_id),
+        }
+    }
+
+    /// Forget the actor with `actor_id` once it's dropped.
+    pub fn remove_actor(&mut self, actor_id: ActorId) {
+        self.senders.remove(&actor_id);
+
+        match &mut self.state {
+            #[cfg(test)]
+            BarrierState::Local => {}
+
+            BarrierState::Managed(managed_state) => managed_state.remove_actor(actor_id),
+        }
+    }
+
+    /// Returns which actors have collected the barrier in flight and which haven't.
+    pub fn collection_status(&self) -> BarrierCollectionStatus {
+        match &self.state {
+            #[cfg(test)]
+            BarrierState::Local => BarrierCollectionStatus::default(),
+
+            BarrierState::Managed(managed_state) => managed_state.collection_status(),
+        }
+    }
+
     /// Report that a Create MV DDL with given `ddl_epoch` is finished on the actor with `actor_id`.
     /// This will be piggybacked by the collection of current/next barrier and then be reported
     /// to the meta service.
@@ -12,12 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter::once;
 
 use tokio::sync::oneshot;
 
-use super::{CollectResult, FinishedCreateMview};
+use super::{BarrierCollectionStatus, CollectResult, FinishedCreateMview};
 use crate::executor::Barrier;
 use crate::task::ActorId;
 
@@ -44,6 +44,9 @@ enum ManagedBarrierStateInner {
     Issued {
         epoch: u64,
 
+        /// Actor ids we've collected.
+        collected_actors: HashSet<ActorId>,
+
         /// Actor ids remaining to be collected.
         remaining_actors: HashSet<ActorId>,
 
@@ -57,6 +60,13 @@ pub(super) struct ManagedBarrierState {
     inner: ManagedBarrierStateInner,
 
     pub finished_create_mviews: Vec<FinishedCreateMview>,
+
+    /// Actors that exited with an error. They won't be collected from, and will be reported with
+    /// every collection until they are dropped.
+    failed_actors: HashSet<ActorId>,
+
+    /// The epoch of the last barrier collected from each actor.
+    last_collected_epochs: HashMap<ActorId, u64>,
 }
 
 impl ManagedBarrierState {
@@ -68,6 +78,8 @@ impl ManagedBarrierState {
                 last_epoch: None,
             },
             finished_create_mviews: Default::default(),
+            failed_actors: Default::default(),
+            last_collected_epochs: Default::default(),
         }
     }
 
@@ -103,6 +115,7 @@ impl ManagedBarrierState {
                     // Notify about barrier finishing.
                     let result = CollectResult {
                         finished_create_mviews,
+                        failed_actors: self.failed_actors.iter().copied().collect(),
                     };
                     if collect_notifier.send(result).is_err() {
                         warn!("failed to notify barrier collection with epoch {}", epoch)
@@ -123,6 +136,8 @@ impl ManagedBarrierState {
             actor_id,
             self
         );
+        self.last_collected_epochs
+            .insert(actor_id, barrier.epoch.curr);
 
         match self.inner_mut() {
             ManagedBarrierStateInner::Pending { last_epoch } => {
@@ -148,6 +163,7 @@ impl ManagedBarrierState {
 
             ManagedBarrierStateInner::Issued {
                 epoch,
+                collected_actors,
                 remaining_actors,
                 ..
             } => {
@@ -155,11 +171,67 @@ impl ManagedBarrierState {
 
                 let exist = remaining_actors.remove(&actor_id);
                 assert!(exist);
+                collected_actors.insert(actor_id);
                 self.may_notify();
             }
         }
     }
 
+    /// Mark the actor with `actor_id` as failed. The barrier being collected won't wait for it
+    /// anymore.
+    pub(super) fn notify_failure(&mut self, actor_id: ActorId) {
+        if !self.failed_actors.insert(actor_id) {
+            return;
+        }
+
+        if let ManagedBarrierStateInner::Issued {
+            remaining_actors, ..
+        } = self.inner_mut()
+        {
+            remaining_actors.remove(&actor_id);
+            self.may_notify();
+        }
+    }
+
+    /// Forget the actor with `actor_id` once it's dropped.
+    pub(super) fn remove_actor(&mut self, actor_id: ActorId) {
+        self.failed_actors.remove(&actor_id);
+        self.last_collected_epochs.remove(&actor_id);
+    }
+
+    /// Returns the actors collected and remaining for the barrier in flight, and the last epochs
+    /// collected from the actors.
+    pub(super) fn collection_status(&self) -> BarrierCollectionStatus {
+        let (epoch, collected_actors, remaining_actors) = match &self.inner {
+            ManagedBarrierStateInner::Pending { .. } => (None, vec![], vec![]),
+            ManagedBarrierStateInner::Stashed {
+                epoch,
+                collected_actors,
+            } => (
+                Some(*epoch),
+                collected_actors.iter().copied().collect(),
+                vec![],
+            ),
+            ManagedBarrierStateInner::Issued {
+                epoch,
+                collected_actors,
+                remaining_actors,
+                ..
+            } => (
+                Some(*epoch),
+                collected_actors.iter().copied().collect(),
+                remaining_actors.iter().copied().collect(),
+            ),
+        };
+
+        BarrierCollectionStatus {
+            epoch,
+            collected_actors,
+            remaining_actors,
+            last_collected_epochs: self.last_collected_epochs.clone(),
+        }
+    }
+
     /// When the meta service issues a `send_barrier` request, call this function to transform to
     /// `Issued` and start to collect or to notify.
     pub(super) fn transform_to_issued(
@@ -168,12 +240,16 @@ impl ManagedBarrierState {
         actor_ids_to_collect: impl IntoIterator<Item = ActorId>,
         collect_notifier: oneshot::Sender<CollectResult>,
     ) {
-        match self.inner_mut() {
+        match &mut self.inner {
             ManagedBarrierStateInner::Pending { .. } => {
-                let remaining_actors = actor_ids_to_collect.into_iter().collect();
+                let remaining_actors = actor_ids_to_collect
+                    .into_iter()
+                    .filter(|a| !self.failed_actors.contains(a))
+                    .collect();
 
                 *self.inner_mut() = ManagedBarrierStateInner::Issued {
                     epoch: barrier.epoch.curr,
+                    collected_actors: HashSet::new(),
                     remaining_actors,
                     collect_notifier,
                 };
@@ -188,11 +264,13 @@ impl ManagedBarrierState {
 
                 let remaining_actors = actor_ids_to_collect
                     .into_iter()
-                    .filter(|a| !collected_actors.contains(a))
+                    .filter(|a| !collected_actors.contains(a) && !self.failed_actors.contains(a))
                     .collect();
+                let collected_actors = std::mem::take(collected_actors);
 
                 *self.inner_mut() = ManagedBarrierStateInner::Issued {
                     epoch: barrier.epoch.curr,
+                    collected_actors,
                     remaining_actors,
                     collect_notifier,
                 };
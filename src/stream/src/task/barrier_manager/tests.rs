@@ -15,6 +15,7 @@
 use std::iter::once;
 
 use itertools::Itertools;
+use risingwave_common::error::ErrorCode;
 use tokio::sync::mpsc::unbounded_channel;
 
 use super::*;
@@ -138,3 +139,102 @@ async fn test_managed_barrier_collection_before_send_request() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_managed_barrier_collection_with_failure() -> Result<()> {
+    let mut manager = LocalBarrierManager::new();
+
+    // Register actors
+    let actor_ids = vec![233, 234, 235];
+    let mut rxs = actor_ids
+        .iter()
+        .map(|&actor_id| {
+            let (barrier_tx, barrier_rx) = unbounded_channel();
+            manager.register_sender(actor_id, barrier_tx);
+            (actor_id, barrier_rx)
+        })
+        .collect_vec();
+
+    // Send a barrier to all actors
+    let epoch = 114514;
+    let barrier = Barrier::new_test_barrier(epoch);
+    let mut collect_rx = manager
+        .send_barrier(&barrier, actor_ids.clone(), actor_ids)
+        .unwrap()
+        .unwrap();
+
+    // Two actors collect the barrier, and the last one fails.
+    for (actor_id, rx) in rxs.iter_mut().take(2) {
+        assert!(matches!(rx.try_recv().unwrap(), Message::Barrier(_)));
+        manager.collect(*actor_id, &barrier).unwrap();
+    }
+    assert!(collect_rx.try_recv().is_err());
+
+    let err = RwError::from(ErrorCode::InternalError("mock failure".to_string()));
+    manager.notify_failure(235, &err);
+    let result = collect_rx.try_recv().unwrap();
+    assert_eq!(result.failed_actors, vec![235]);
+
+    // The failed actor is reported until it's dropped.
+    let barrier = Barrier::new_test_barrier(epoch + 1);
+    let mut collect_rx = manager
+        .send_barrier(&barrier, vec![233, 234], vec![233, 234, 235])
+        .unwrap()
+        .unwrap();
+    manager.collect(233, &barrier).unwrap();
+    manager.collect(234, &barrier).unwrap();
+    assert_eq!(collect_rx.try_recv().unwrap().failed_actors, vec![235]);
+
+    manager.remove_actor(235);
+    let barrier = Barrier::new_test_barrier(epoch + 2);
+    let mut collect_rx = manager
+        .send_barrier(&barrier, vec![233, 234], vec![233, 234])
+        .unwrap()
+        .unwrap();
+    manager.collect(233, &barrier).unwrap();
+    manager.collect(234, &barrier).unwrap();
+    assert!(collect_rx.try_recv().unwrap().failed_actors.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_barrier_collection_status() -> Result<()> {
+    let mut manager = LocalBarrierManager::new();
+
+    let actor_ids = vec![233, 234];
+    let _rxs = actor_ids
+        .iter()
+        .map(|&actor_id| {
+            let (barrier_tx, barrier_rx) = unbounded_channel();
+            manager.register_sender(actor_id, barrier_tx);
+            barrier_rx
+        })
+        .collect_vec();
+    assert_eq!(manager.collection_status().epoch, None);
+
+    let epoch = 114514;
+    let barrier = Barrier::new_test_barrier(epoch);
+    let _collect_rx = manager
+        .send_barrier(&barrier, actor_ids.clone(), actor_ids)
+        .unwrap()
+        .unwrap();
+    manager.collect(233, &barrier)?;
+
+    let status = manager.collection_status();
+    assert_eq!(status.epoch, Some(epoch));
+    assert_eq!(status.collected_actors, vec![233]);
+    assert_eq!(status.remaining_actors, vec![234]);
+    assert_eq!(status.last_collected_epochs, HashMap::from([(233, epoch)]));
+
+    // The barrier is not in flight after being collected from all actors.
+    manager.collect(234, &barrier)?;
+    let status = manager.collection_status();
+    assert_eq!(status.epoch, None);
+    assert_eq!(
+        status.last_collected_epochs,
+        HashMap::from([(233, epoch), (234, epoch)])
+    );
+
+    Ok(())
+}
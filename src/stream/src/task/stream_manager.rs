@@ -16,11 +16,12 @@ use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::channel::mpsc::{channel, Receiver};
 use itertools::Itertools;
 use parking_lot::Mutex;
-use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::catalog::{Field, Schema, TableId};
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::try_match_expand;
 use risingwave_common::types::DataType;
@@ -30,11 +31,11 @@ use risingwave_expr::expr::AggKind;
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_pb::{expr, stream_plan, stream_service};
-use risingwave_storage::{dispatch_state_store, StateStore, StateStoreImpl};
+use risingwave_storage::{dispatch_state_store, Keyspace, StateStore, StateStoreImpl};
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
-use super::{CollectResult, ComputeClientPool};
+use super::{BarrierCollectionStatus, CollectResult, ComputeClientPool};
 use crate::executor::*;
 use crate::executor_v2::merge::RemoteInput;
 use crate::executor_v2::receiver::ReceiverExecutor;
@@ -80,6 +81,19 @@ pub struct LocalStreamManagerCore {
     /// TODO: currently the client pool won't be cleared. Should remove compute clients when
     /// disconnected.
     compute_client_pool: ComputeClientPool,
+
+    /// Epochs collected by non-checkpoint barriers, whose states are not synced yet.
+    uncheckpointed_epochs: Vec<u64>,
+
+    /// The await tree of each actor, used to diagnose actors that don't collect barriers.
+    await_trees: HashMap<ActorId, Arc<AwaitNode>>,
+
+    /// The fragment of each actor.
+    actor_fragments: HashMap<ActorId, u32>,
+
+    /// The tables written by each actor, i.e. the materialized tables and the state tables
+    /// allocated by meta, whose states are deleted when the materialized view is dropped.
+    actor_tables: HashMap<ActorId, Vec<TableId>>,
 }
 
 /// `LocalStreamManager` manages all stream executors in this project.
@@ -108,6 +122,9 @@ pub struct ExecutorParams {
     /// Id of the actor.
     pub actor_id: ActorId,
     pub executor_stats: Arc<StreamingMetrics>,
+
+    /// Time-to-live of the executor states, if they expire.
+    pub state_ttl: Option<Duration>,
 }
 
 impl Debug for ExecutorParams {
@@ -120,6 +137,7 @@ impl Debug for ExecutorParams {
             .field("op_info", &self.op_info)
             .field("input", &self.input)
             .field("actor_id", &self.actor_id)
+            .field("state_ttl", &self.state_ttl)
             .finish()
     }
 }
@@ -176,7 +194,18 @@ impl LocalStreamManager {
         // Wait for all actors finishing this barrier.
         let collect_result = rx.await.unwrap();
 
-        // Sync states from shared buffer to S3 before telling meta service we've done.
+        // Only checkpoint barriers sync states, including the ones of epochs collected before.
+        {
+            let mut core = self.core.lock();
+            if !barrier.checkpoint {
+                core.uncheckpointed_epochs.push(barrier.epoch.prev);
+                return Ok(collect_result);
+            }
+            core.uncheckpointed_epochs.clear();
+        }
+
+        // Sync states from shared buffer to S3 before telling meta service we've done. All epochs
+        // up to this one are uploaded together.
         dispatch_state_store!(self.state_store(), store, {
             match store.sync(Some(barrier.epoch.prev)).await {
                 Ok(_) => {}
@@ -214,6 +243,62 @@ impl LocalStreamManager {
         Ok(())
     }
 
+    /// Deletes the states of the given actors with range tombstones in `epoch`, as their
+    /// materialized view is dropped. Must be called before the actors are dropped.
+    ///
+    /// The states under the executor roots of the actors and the tables written by them are
+    /// deleted. Shared executor roots are keyed by operator ids, and are left to be cleaned up.
+    pub async fn delete_actor_states(&self, actors: &[ActorId], epoch: u64) -> Result<()> {
+        let table_ids = {
+            let core = self.core.lock();
+            actors
+                .iter()
+                .filter_map(|actor_id| core.actor_tables.get(actor_id))
+                .flatten()
+                .copied()
+                .collect::<HashSet<_>>()
+        };
+
+        dispatch_state_store!(self.state_store(), store, {
+            for actor_id in actors {
+                Keyspace::actor_root(store.clone(), *actor_id)
+                    .delete_all(epoch)
+                    .await?;
+            }
+            for table_id in table_ids {
+                Keyspace::table_root(store.clone(), &table_id)
+                    .delete_all(epoch)
+                    .await?;
+            }
+        });
+        tracing::debug!(actors = ?actors, epoch, "delete actor states");
+        Ok(())
+    }
+
+    /// Returns the number of rows processed by each actor, from the streaming metrics.
+    pub fn actor_row_counts(&self) -> HashMap<ActorId, u64> {
+        self.core.lock().streaming_metrics.actor_row_counts()
+    }
+
+    /// Returns the barrier collection status of this worker.
+    pub fn barrier_collection_status(&self) -> BarrierCollectionStatus {
+        self.core
+            .lock()
+            .context
+            .lock_barrier_manager()
+            .collection_status()
+    }
+
+    /// Renders the await trees of the given actors, or of all actors if `actor_ids` is empty.
+    pub fn dump_await_trees(&self, actor_ids: &[ActorId]) -> HashMap<ActorId, String> {
+        let core = self.core.lock();
+        core.await_trees
+            .iter()
+            .filter(|(actor_id, _)| actor_ids.is_empty() || actor_ids.contains(actor_id))
+            .map(|(actor_id, tree)| (*actor_id, tree.render()))
+            .collect()
+    }
+
     /// Force stop all actors on this worker.
     pub async fn stop_all_actors(&self, epoch: Epoch) -> Result<()> {
         let (actor_ids_to_send, actor_ids_to_collect) = {
@@ -223,21 +308,34 @@ impl LocalStreamManager {
             (actor_ids_to_send, actor_ids_to_collect)
         };
         if actor_ids_to_send.is_empty() || actor_ids_to_collect.is_empty() {
+            self.reset_uncheckpointed_epochs();
             return Ok(());
         }
         let barrier = Barrier {
             epoch,
             mutation: Some(Arc::new(Mutation::Stop(actor_ids_to_collect.clone()))),
             span: tracing::Span::none(),
+            checkpoint: false,
         };
 
         self.send_and_collect_barrier(&barrier, actor_ids_to_send, actor_ids_to_collect)
             .await?;
         self.core.lock().drop_all_actors();
+        // States of the uncommitted epochs will be aborted by the meta service, so they must not
+        // be synced by later checkpoints.
+        self.reset_uncheckpointed_epochs();
 
         Ok(())
     }
 
+    /// Drops the buffered states of the epochs collected by non-checkpoint barriers.
+    fn reset_uncheckpointed_epochs(&self) {
+        let epochs = std::mem::take(&mut self.core.lock().uncheckpointed_epochs);
+        dispatch_state_store!(self.state_store(), store, {
+            store.reset_epochs(&epochs);
+        });
+    }
+
     pub fn take_receiver(&self, ids: UpDownActorIds) -> Result<Receiver<Message>> {
         let core = self.core.lock();
         core.context.take_receiver(&ids)
@@ -325,6 +423,7 @@ pub fn build_agg_call_from_prost(agg_call_proto: &expr::AggCall) -> Result<AggCa
         kind: AggKind::try_from(agg_call_proto.get_type()?)?,
         args,
         return_type: DataType::from(agg_call_proto.get_return_type()?),
+        distinct: agg_call_proto.distinct,
     })
 }
 
@@ -363,6 +462,10 @@ impl LocalStreamManagerCore {
             state_store,
             streaming_metrics,
             compute_client_pool: ComputeClientPool::new(1024),
+            uncheckpointed_epochs: vec![],
+            await_trees: HashMap::new(),
+            actor_fragments: HashMap::new(),
+            actor_tables: HashMap::new(),
         }
     }
 
@@ -456,6 +559,7 @@ impl LocalStreamManagerCore {
     }
 
     /// Create a chain(tree) of nodes, with given `store`.
+    #[allow(clippy::too_many_arguments)]
     fn create_nodes_inner(
         &mut self,
         fragment_id: u32,
@@ -464,11 +568,12 @@ impl LocalStreamManagerCore {
         input_pos: usize,
         env: StreamEnvironment,
         store: impl StateStore,
-    ) -> Result<Box<dyn Executor>> {
+        state_ttl: Option<Duration>,
+    ) -> Result<(Box<dyn Executor>, Arc<AwaitNode>)> {
         let op_info = node.get_identity().clone();
         // Create the input executor before creating itself
         // The node with no input must be a `MergeNode`
-        let input: Vec<Box<dyn Executor>> = node
+        let (input, await_inputs): (Vec<Box<dyn Executor>>, Vec<Arc<AwaitNode>>) = node
             .input
             .iter()
             .enumerate()
@@ -480,9 +585,12 @@ impl LocalStreamManagerCore {
                     input_pos,
                     env.clone(),
                     store.clone(),
+                    state_ttl,
                 )
             })
-            .try_collect()?;
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
 
         let pk_indices = node
             .get_pk_indices()
@@ -506,6 +614,7 @@ impl LocalStreamManagerCore {
             input,
             actor_id,
             executor_stats: self.streaming_metrics.clone(),
+            state_ttl,
         };
         let executor = create_executor(executor_params, self, node, store);
         let executor = Self::wrap_executor_for_debug(
@@ -514,19 +623,22 @@ impl LocalStreamManagerCore {
             input_pos,
             self.streaming_metrics.clone(),
         )?;
-        Ok(executor)
+        let await_node = Arc::new(AwaitNode::new(executor.identity(), await_inputs));
+        let executor = Box::new(AwaitTreeExecutor::new(executor, await_node.clone()));
+        Ok((executor, await_node))
     }
 
-    /// Create a chain(tree) of nodes and return the head executor.
+    /// Create a chain(tree) of nodes and return the head executor, along with its await tree.
     fn create_nodes(
         &mut self,
         fragment_id: u32,
         actor_id: ActorId,
         node: &stream_plan::StreamNode,
         env: StreamEnvironment,
-    ) -> Result<Box<dyn Executor>> {
+        state_ttl: Option<Duration>,
+    ) -> Result<(Box<dyn Executor>, Arc<AwaitNode>)> {
         dispatch_state_store!(self.state_store.clone(), store, {
-            self.create_nodes_inner(fragment_id, actor_id, node, 0, env, store)
+            self.create_nodes_inner(fragment_id, actor_id, node, 0, env, store, state_ttl)
         })
     }
 
@@ -658,8 +770,21 @@ impl LocalStreamManagerCore {
         for actor_id in actors {
             let actor_id = *actor_id;
             let actor = self.actors.remove(&actor_id).unwrap();
-            let executor =
-                self.create_nodes(actor.fragment_id, actor_id, actor.get_nodes()?, env.clone())?;
+            let state_ttl =
+                (actor.state_ttl_ms > 0).then(|| Duration::from_millis(actor.state_ttl_ms));
+            let (executor, await_tree) = self.create_nodes(
+                actor.fragment_id,
+                actor_id,
+                actor.get_nodes()?,
+                env.clone(),
+                state_ttl,
+            )?;
+            self.await_trees.insert(actor_id, await_tree);
+
+            self.actor_fragments.insert(actor_id, actor.fragment_id);
+            let mut table_ids = vec![];
+            Self::collect_tables(actor.get_nodes()?, &mut table_ids);
+            self.actor_tables.insert(actor_id, table_ids);
 
             let dispatchers = actor.get_dispatcher();
             assert_eq!(
@@ -672,11 +797,15 @@ impl LocalStreamManagerCore {
             trace!("build actor: {:#?}", &dispatcher);
 
             let actor = Actor::new(dispatcher, actor_id, self.context.clone());
+            let barrier_manager = self.context.barrier_manager.clone();
             self.handles.insert(
                 actor_id,
                 tokio::spawn(async move {
-                    // unwrap the actor result to panic on error
-                    actor.run().await.expect("actor failed");
+                    // Report the error instead of panicking, so that only the streaming job of this
+                    // actor will be recovered by the meta service.
+                    if let Err(err) = actor.run().await {
+                        barrier_manager.lock().notify_failure(actor_id, &err);
+                    }
                 }),
             );
         }
@@ -684,6 +813,23 @@ impl LocalStreamManagerCore {
         Ok(())
     }
 
+    fn collect_tables(node: &stream_plan::StreamNode, table_ids: &mut Vec<TableId>) {
+        let table_id = match &node.node {
+            Some(Node::MaterializeNode(materialize)) => TableId::from(&materialize.table_ref_id),
+            Some(Node::HashAggNode(node)) => TableId::new(node.table_id),
+            Some(Node::GlobalSimpleAggNode(node)) => TableId::new(node.table_id),
+            Some(Node::TopNNode(node)) => TableId::new(node.table_id),
+            _ => TableId::new(0),
+        };
+        // State tables are not allocated for the operators whose states are owned by actors.
+        if table_id.table_id != 0 {
+            table_ids.push(table_id);
+        }
+        for input in &node.input {
+            Self::collect_tables(input, table_ids);
+        }
+    }
+
     pub fn take_all_handles(&mut self) -> Result<HashMap<ActorId, ActorHandle>> {
         Ok(std::mem::take(&mut self.handles))
     }
@@ -724,9 +870,12 @@ impl LocalStreamManagerCore {
     fn drop_actor(&mut self, actor_id: ActorId) {
         let handle = self.handles.remove(&actor_id).unwrap();
         self.context.retain(|&(up_id, _)| up_id != actor_id);
+        self.context.lock_barrier_manager().remove_actor(actor_id);
 
         self.actor_infos.remove(&actor_id);
         self.actors.remove(&actor_id);
+        self.await_trees.remove(&actor_id);
+        self.clear_state_ttls(actor_id);
         // Task should have already stopped when this method is invoked.
         handle.abort();
     }
@@ -734,13 +883,52 @@ impl LocalStreamManagerCore {
     /// `drop_all_actors` is invoked by meta node via RPC once the stop barrier arrives at all the
     /// sink. All the actors in the actors should stop themselves before this method is invoked.
     fn drop_all_actors(&mut self) {
+        for actor_id in self.handles.keys().copied().collect_vec() {
+            self.clear_state_ttls(actor_id);
+        }
         for (actor_id, handle) in self.handles.drain() {
             self.context.retain(|&(up_id, _)| up_id != actor_id);
+            self.context.lock_barrier_manager().remove_actor(actor_id);
             self.actors.remove(&actor_id);
             // Task should have already stopped when this method is invoked.
             handle.abort();
         }
         self.actor_infos.clear();
+        self.await_trees.clear();
+        self.actor_fragments.clear();
+        self.actor_tables.clear();
+    }
+
+    /// Removes the TTLs registered by the executors of an actor. The shared roots of its fragment
+    /// and its state tables are cleared as well, unless they are still used by other actors on
+    /// this node, e.g. the new actors of a rescheduled fragment.
+    fn clear_state_ttls(&mut self, actor_id: ActorId) {
+        let fragment_id = self
+            .actor_fragments
+            .remove(&actor_id)
+            .filter(|fragment_id| !self.actor_fragments.values().any(|id| id == fragment_id));
+        let table_ids = self
+            .actor_tables
+            .remove(&actor_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|table_id| {
+                !self
+                    .actor_tables
+                    .values()
+                    .flatten()
+                    .any(|id| id == table_id)
+            })
+            .collect_vec();
+        dispatch_state_store!(self.state_store.clone(), store, {
+            Keyspace::actor_root(store.clone(), actor_id).clear_ttl();
+            if let Some(fragment_id) = fragment_id {
+                Keyspace::fragment_root(store.clone(), fragment_id).clear_ttl();
+            }
+            for table_id in table_ids {
+                Keyspace::table_root(store.clone(), &table_id).clear_ttl();
+            }
+        });
     }
 
     fn build_channel_for_chain_node(
@@ -36,6 +36,7 @@ pub enum StatementType {
     DROP_TABLE,
     DROP_MATERIALIZED_VIEW,
     DROP_STREAM,
+    ALTER_MATERIALIZED_VIEW,
     // Introduce ORDER_BY statement type cuz Calcite unvalidated AST has SqlKind.ORDER_BY. Note
     // that Statement Type is not designed to be one to one mapping with SqlKind.
     ORDER_BY,